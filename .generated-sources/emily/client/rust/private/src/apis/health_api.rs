@@ -21,6 +21,7 @@ pub enum CheckHealthError {
     Status404(models::ErrorResponse),
     Status405(models::ErrorResponse),
     Status500(models::ErrorResponse),
+    Status503(models::HealthData),
     UnknownValue(serde_json::Value),
 }
 
@@ -92,11 +92,14 @@ pub fn parse_deep_object(prefix: &str, value: &serde_json::Value) -> Vec<(String
     unimplemented!("Only objects are supported with style=deepObject")
 }
 
+pub mod api_key_api;
 pub mod chainstate_api;
 pub mod cors_api;
 pub mod deposit_api;
+pub mod export_api;
 pub mod health_api;
 pub mod limits_api;
+pub mod subscription_api;
 pub mod withdrawal_api;
 
 pub mod configuration;
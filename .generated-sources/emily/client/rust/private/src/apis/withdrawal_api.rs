@@ -146,7 +146,13 @@ pub async fn get_withdrawal(
 
 pub async fn get_withdrawals(
     configuration: &configuration::Configuration,
-    status: models::Status,
+    status: Option<Vec<models::Status>>,
+    recipient: Option<&str>,
+    sender: Option<&str>,
+    min_height: Option<u64>,
+    max_height: Option<u64>,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
     next_token: Option<&str>,
     page_size: Option<i32>,
 ) -> Result<models::GetWithdrawalsResponse, Error<GetWithdrawalsError>> {
@@ -158,7 +164,49 @@ pub async fn get_withdrawals(
     let mut local_var_req_builder =
         local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
 
-    local_var_req_builder = local_var_req_builder.query(&[("status", &status.to_string())]);
+    if let Some(ref local_var_str) = status {
+        local_var_req_builder = match "csv" {
+            "multi" => local_var_req_builder.query(
+                &local_var_str
+                    .into_iter()
+                    .map(|p| ("status".to_owned(), p.to_string()))
+                    .collect::<Vec<(std::string::String, std::string::String)>>(),
+            ),
+            _ => local_var_req_builder.query(&[(
+                "status",
+                &local_var_str
+                    .into_iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+                    .to_string(),
+            )]),
+        };
+    }
+    if let Some(ref local_var_str) = recipient {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("recipient", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = sender {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("sender", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = min_height {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("minHeight", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = max_height {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("maxHeight", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = min_timestamp {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("minTimestamp", &local_var_str.to_string())]);
+    }
+    if let Some(ref local_var_str) = max_timestamp {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("maxTimestamp", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_str) = next_token {
         local_var_req_builder =
             local_var_req_builder.query(&[("nextToken", &local_var_str.to_string())]);
@@ -0,0 +1,51 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// ApiKey : An api key, without the key itself.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// The unix timestamp in seconds of when the api key was created.
+    #[serde(rename = "createdAt")]
+    pub created_at: u64,
+    /// Description of who the api key is for.
+    #[serde(rename = "description")]
+    pub description: String,
+    /// The id of the api key. This is also the prefix of the key.
+    #[serde(rename = "id")]
+    pub id: String,
+    /// Whether the api key has been revoked.
+    #[serde(rename = "revoked")]
+    pub revoked: bool,
+    /// What the api key is allowed to do.
+    #[serde(rename = "scopes")]
+    pub scopes: Vec<models::ApiKeyScope>,
+}
+
+impl ApiKey {
+    /// An api key, without the key itself.
+    pub fn new(
+        created_at: u64,
+        description: String,
+        id: String,
+        revoked: bool,
+        scopes: Vec<models::ApiKeyScope>,
+    ) -> ApiKey {
+        ApiKey {
+            created_at,
+            description,
+            id,
+            revoked,
+            scopes,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// ApiKeyScope : What an api key is allowed to do.
+/// What an api key is allowed to do.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum ApiKeyScope {
+    #[serde(rename = "signer_update")]
+    SignerUpdate,
+    #[serde(rename = "chainstate_write")]
+    ChainstateWrite,
+    #[serde(rename = "limits_admin")]
+    LimitsAdmin,
+    #[serde(rename = "read_only")]
+    ReadOnly,
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::SignerUpdate => write!(f, "signer_update"),
+            Self::ChainstateWrite => write!(f, "chainstate_write"),
+            Self::LimitsAdmin => write!(f, "limits_admin"),
+            Self::ReadOnly => write!(f, "read_only"),
+        }
+    }
+}
+
+impl Default for ApiKeyScope {
+    fn default() -> ApiKeyScope {
+        Self::SignerUpdate
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateApiKeyResponse : Response to create api key request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(rename = "apiKey")]
+    pub api_key: Box<models::ApiKey>,
+    /// The key to send in the `x-api-key` header. Only its hash is stored, so it can't be retrieved again.
+    #[serde(rename = "key")]
+    pub key: String,
+}
+
+impl CreateApiKeyResponse {
+    /// Response to create api key request.
+    pub fn new(api_key: models::ApiKey, key: String) -> CreateApiKeyResponse {
+        CreateApiKeyResponse {
+            api_key: Box::new(api_key),
+            key,
+        }
+    }
+}
@@ -25,6 +25,14 @@ pub struct CreateWithdrawalRequestBody {
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
     #[serde(rename = "requestId")]
     pub request_id: u64,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(
+        rename = "sender",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sender: Option<Option<String>>,
     /// The stacks block hash in which this request id was initiated.
     #[serde(rename = "stacksBlockHash")]
     pub stacks_block_hash: String,
@@ -48,6 +56,7 @@ impl CreateWithdrawalRequestBody {
             parameters: Box::new(parameters),
             recipient,
             request_id,
+            sender: None,
             stacks_block_hash,
             stacks_block_height,
         }
@@ -38,6 +38,14 @@ pub struct DepositInfo {
     /// Raw reclaim script binary in hex.
     #[serde(rename = "reclaimScript")]
     pub reclaim_script: String,
+    /// Hex encoded script pubkey of the output spent by the first input of the deposit transaction. Only known for deposits that were verified against their bitcoin transaction.
+    #[serde(
+        rename = "sender",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sender: Option<Option<String>>,
     #[serde(rename = "status")]
     pub status: models::Status,
 }
@@ -64,6 +72,7 @@ impl DepositInfo {
             last_update_height,
             recipient,
             reclaim_script,
+            sender: None,
             status,
         }
     }
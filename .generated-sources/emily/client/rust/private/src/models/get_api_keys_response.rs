@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetApiKeysResponse : Response to get api keys request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetApiKeysResponse {
+    /// Api keys, including the revoked ones.
+    #[serde(rename = "apiKeys")]
+    pub api_keys: Vec<models::ApiKey>,
+}
+
+impl GetApiKeysResponse {
+    /// Response to get api keys request.
+    pub fn new(api_keys: Vec<models::ApiKey>) -> GetApiKeysResponse {
+        GetApiKeysResponse { api_keys }
+    }
+}
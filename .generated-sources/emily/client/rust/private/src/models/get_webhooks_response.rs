@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetWebhooksResponse : Response to get webhooks request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetWebhooksResponse {
+    /// Registered webhooks.
+    #[serde(rename = "webhooks")]
+    pub webhooks: Vec<models::Webhook>,
+}
+
+impl GetWebhooksResponse {
+    /// Response to get webhooks request.
+    pub fn new(webhooks: Vec<models::Webhook>) -> GetWebhooksResponse {
+        GetWebhooksResponse { webhooks }
+    }
+}
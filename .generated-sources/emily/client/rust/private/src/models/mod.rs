@@ -1,9 +1,19 @@
 pub mod account_limits;
 pub use self::account_limits::AccountLimits;
+pub mod api_key;
+pub use self::api_key::ApiKey;
+pub mod api_key_scope;
+pub use self::api_key_scope::ApiKeyScope;
 pub mod chainstate;
 pub use self::chainstate::Chainstate;
+pub mod create_api_key_request_body;
+pub use self::create_api_key_request_body::CreateApiKeyRequestBody;
+pub mod create_api_key_response;
+pub use self::create_api_key_response::CreateApiKeyResponse;
 pub mod create_deposit_request_body;
 pub use self::create_deposit_request_body::CreateDepositRequestBody;
+pub mod create_webhook_request_body;
+pub use self::create_webhook_request_body::CreateWebhookRequestBody;
 pub mod create_withdrawal_request_body;
 pub use self::create_withdrawal_request_body::CreateWithdrawalRequestBody;
 pub mod deposit;
@@ -14,14 +24,22 @@ pub mod deposit_parameters;
 pub use self::deposit_parameters::DepositParameters;
 pub mod deposit_update;
 pub use self::deposit_update::DepositUpdate;
+pub mod deposit_verification;
+pub use self::deposit_verification::DepositVerification;
 pub mod error_response;
 pub use self::error_response::ErrorResponse;
+pub mod export_format;
+pub use self::export_format::ExportFormat;
 pub mod fulfillment;
 pub use self::fulfillment::Fulfillment;
+pub mod get_api_keys_response;
+pub use self::get_api_keys_response::GetApiKeysResponse;
 pub mod get_deposits_for_transaction_response;
 pub use self::get_deposits_for_transaction_response::GetDepositsForTransactionResponse;
 pub mod get_deposits_response;
 pub use self::get_deposits_response::GetDepositsResponse;
+pub mod get_webhooks_response;
+pub use self::get_webhooks_response::GetWebhooksResponse;
 pub mod get_withdrawals_response;
 pub use self::get_withdrawals_response::GetWithdrawalsResponse;
 pub mod health_data;
@@ -30,6 +48,10 @@ pub mod limits;
 pub use self::limits::Limits;
 pub mod status;
 pub use self::status::Status;
+pub mod status_event;
+pub use self::status_event::StatusEvent;
+pub mod status_event_filter;
+pub use self::status_event_filter::StatusEventFilter;
 pub mod update_deposits_request_body;
 pub use self::update_deposits_request_body::UpdateDepositsRequestBody;
 pub mod update_deposits_response;
@@ -38,6 +60,8 @@ pub mod update_withdrawals_request_body;
 pub use self::update_withdrawals_request_body::UpdateWithdrawalsRequestBody;
 pub mod update_withdrawals_response;
 pub use self::update_withdrawals_response::UpdateWithdrawalsResponse;
+pub mod webhook;
+pub use self::webhook::Webhook;
 pub mod withdrawal;
 pub use self::withdrawal::Withdrawal;
 pub mod withdrawal_info;
@@ -0,0 +1,77 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// StatusEvent : A change in the status of a deposit or withdrawal, sent to webhooks and server-sent event streams whenever an event is appended to the history of the operation.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusEvent {
+    /// Output index on the bitcoin transaction of the deposit. Only set for deposits.
+    #[serde(
+        rename = "bitcoinTxOutputIndex",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitcoin_tx_output_index: Option<Option<u32>>,
+    /// Bitcoin transaction id of the deposit. Only set for deposits.
+    #[serde(
+        rename = "bitcoinTxid",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitcoin_txid: Option<Option<String>>,
+    /// The recipient of the operation; a Stacks address for deposits and a bitcoin address for withdrawals.
+    #[serde(rename = "recipient")]
+    pub recipient: String,
+    /// The id of the Stacks withdrawal request. Only set for withdrawals.
+    #[serde(
+        rename = "requestId",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub request_id: Option<Option<u64>>,
+    /// Stacks block hash at the time of the status change.
+    #[serde(rename = "stacksBlockHash")]
+    pub stacks_block_hash: String,
+    /// Stacks block height at the time of the status change.
+    #[serde(rename = "stacksBlockHeight")]
+    pub stacks_block_height: u64,
+    #[serde(rename = "status")]
+    pub status: models::Status,
+    /// The status message of the operation.
+    #[serde(rename = "statusMessage")]
+    pub status_message: String,
+}
+
+impl StatusEvent {
+    /// A change in the status of a deposit or withdrawal, sent to webhooks and server-sent event streams whenever an event is appended to the history of the operation.
+    pub fn new(
+        recipient: String,
+        stacks_block_hash: String,
+        stacks_block_height: u64,
+        status: models::Status,
+        status_message: String,
+    ) -> StatusEvent {
+        StatusEvent {
+            bitcoin_tx_output_index: None,
+            bitcoin_txid: None,
+            recipient,
+            request_id: None,
+            stacks_block_hash,
+            stacks_block_height,
+            status,
+            status_message,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// StatusEventFilter : Which status events a subscriber receives. Every field that is set has to match the event for it to be sent.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusEventFilter {
+    /// Only send events for deposits in this bitcoin transaction.
+    #[serde(
+        rename = "bitcoinTxid",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitcoin_txid: Option<Option<String>>,
+    /// Only send events for operations with this recipient.
+    #[serde(
+        rename = "recipient",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub recipient: Option<Option<String>>,
+    #[serde(
+        rename = "status",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<Option<models::Status>>,
+}
+
+impl StatusEventFilter {
+    /// Which status events a subscriber receives. Every field that is set has to match the event for it to be sent.
+    pub fn new() -> StatusEventFilter {
+        StatusEventFilter {
+            bitcoin_txid: None,
+            recipient: None,
+            status: None,
+        }
+    }
+}
@@ -38,6 +38,14 @@ pub struct Withdrawal {
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
     #[serde(rename = "requestId")]
     pub request_id: u64,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(
+        rename = "sender",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sender: Option<Option<String>>,
     /// The stacks block hash in which this request id was initiated.
     #[serde(rename = "stacksBlockHash")]
     pub stacks_block_hash: String,
@@ -73,6 +81,7 @@ impl Withdrawal {
             parameters: Box::new(parameters),
             recipient,
             request_id,
+            sender: None,
             stacks_block_hash,
             stacks_block_height,
             status,
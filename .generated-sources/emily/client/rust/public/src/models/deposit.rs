@@ -47,11 +47,21 @@ pub struct Deposit {
     /// Raw reclaim script binary in hex.
     #[serde(rename = "reclaimScript")]
     pub reclaim_script: String,
+    /// Hex encoded script pubkey of the output spent by the first input of the deposit transaction. Only known for deposits that were verified against their bitcoin transaction.
+    #[serde(
+        rename = "sender",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sender: Option<Option<String>>,
     #[serde(rename = "status")]
     pub status: models::Status,
     /// The status message of the deposit.
     #[serde(rename = "statusMessage")]
     pub status_message: String,
+    #[serde(rename = "verification")]
+    pub verification: models::DepositVerification,
 }
 
 impl Deposit {
@@ -68,6 +78,7 @@ impl Deposit {
         reclaim_script: String,
         status: models::Status,
         status_message: String,
+        verification: models::DepositVerification,
     ) -> Deposit {
         Deposit {
             amount,
@@ -80,8 +91,10 @@ impl Deposit {
             parameters: Box::new(parameters),
             recipient,
             reclaim_script,
+            sender: None,
             status,
             status_message,
+            verification,
         }
     }
 }
@@ -0,0 +1,40 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// DepositVerification : The outcome of checking a deposit against its bitcoin transaction when it was created.
+/// The outcome of checking a deposit against its bitcoin transaction when it was created.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum DepositVerification {
+    #[serde(rename = "unverified")]
+    Unverified,
+    #[serde(rename = "verified")]
+    Verified,
+    #[serde(rename = "unconfirmed")]
+    Unconfirmed,
+}
+
+impl std::fmt::Display for DepositVerification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unverified => write!(f, "unverified"),
+            Self::Verified => write!(f, "verified"),
+            Self::Unconfirmed => write!(f, "unconfirmed"),
+        }
+    }
+}
+
+impl Default for DepositVerification {
+    fn default() -> DepositVerification {
+        Self::Unverified
+    }
+}
@@ -14,14 +14,42 @@ use serde::{Deserialize, Serialize};
 /// HealthData : Struct that represents the current status of the API.
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HealthData {
-    /// Whether the API is okay.
+    /// Seconds since the chain tip last changed. Missing when the database can't be reached or the chain tip hasn't changed since the API began recording when it does.
+    #[serde(
+        rename = "chain_tip_age_seconds",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub chain_tip_age_seconds: Option<Option<u64>>,
+    /// Height of the stacks chain tip that the API is following. Missing when the database can't be reached.
+    #[serde(
+        rename = "chain_tip_height",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub chain_tip_height: Option<Option<u64>>,
+    /// Whether the database could be reached.
+    #[serde(rename = "database_reachable")]
+    pub database_reachable: bool,
+    /// Whether the API is okay, which is whenever its database can be reached.
     #[serde(rename = "is_okay")]
     pub is_okay: bool,
+    /// Whether the API is reorganizing around a new chain tip.
+    #[serde(rename = "is_reorganizing")]
+    pub is_reorganizing: bool,
 }
 
 impl HealthData {
     /// Struct that represents the current status of the API.
-    pub fn new(is_okay: bool) -> HealthData {
-        HealthData { is_okay }
+    pub fn new(is_okay: bool, database_reachable: bool, is_reorganizing: bool) -> HealthData {
+        HealthData {
+            chain_tip_age_seconds: None,
+            chain_tip_height: None,
+            database_reachable,
+            is_okay,
+            is_reorganizing,
+        }
     }
 }
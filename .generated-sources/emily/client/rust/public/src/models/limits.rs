@@ -49,6 +49,46 @@ pub struct Limits {
         skip_serializing_if = "Option::is_none"
     )]
     pub per_withdrawal_cap: Option<Option<u64>>,
+    /// Cap on the BTC pegged in within the rolling window. If none then there is no cap.
+    #[serde(
+        rename = "rollingDepositCap",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_cap: Option<Option<u64>>,
+    /// BTC pegged in within the current rolling window. Only set when there is a rolling deposit cap, and ignored when setting limits.
+    #[serde(
+        rename = "rollingDepositVolume",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_deposit_volume: Option<Option<u64>>,
+    /// Length in seconds of the rolling window that the rolling caps apply to. If none then the window is a day long.
+    #[serde(
+        rename = "rollingWindowSeconds",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_window_seconds: Option<Option<u64>>,
+    /// Cap on the sBTC pegged out within the rolling window. If none then there is no cap.
+    #[serde(
+        rename = "rollingWithdrawalCap",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_withdrawal_cap: Option<Option<u64>>,
+    /// sBTC pegged out within the current rolling window. Only set when there is a rolling withdrawal cap, and ignored when setting limits.
+    #[serde(
+        rename = "rollingWithdrawalVolume",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rolling_withdrawal_volume: Option<Option<u64>>,
 }
 
 impl Limits {
@@ -60,6 +100,11 @@ impl Limits {
             per_deposit_cap: None,
             per_deposit_minimum: None,
             per_withdrawal_cap: None,
+            rolling_deposit_cap: None,
+            rolling_deposit_volume: None,
+            rolling_window_seconds: None,
+            rolling_withdrawal_cap: None,
+            rolling_withdrawal_volume: None,
         }
     }
 }
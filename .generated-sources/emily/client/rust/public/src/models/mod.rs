@@ -14,6 +14,8 @@ pub mod deposit_parameters;
 pub use self::deposit_parameters::DepositParameters;
 pub mod deposit_update;
 pub use self::deposit_update::DepositUpdate;
+pub mod deposit_verification;
+pub use self::deposit_verification::DepositVerification;
 pub mod error_response;
 pub use self::error_response::ErrorResponse;
 pub mod fulfillment;
@@ -0,0 +1,30 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateApiKeyRequestBody : Request structure for the create api key request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateApiKeyRequestBody {
+    /// Description of who the api key is for.
+    #[serde(rename = "description")]
+    pub description: String,
+    /// What the api key is allowed to do.
+    #[serde(rename = "scopes")]
+    pub scopes: Vec<models::ApiKeyScope>,
+}
+
+impl CreateApiKeyRequestBody {
+    /// Request structure for the create api key request.
+    pub fn new(description: String, scopes: Vec<models::ApiKeyScope>) -> CreateApiKeyRequestBody {
+        CreateApiKeyRequestBody { description, scopes }
+    }
+}
@@ -0,0 +1,32 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateWebhookRequestBody : Request structure for the create webhook request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateWebhookRequestBody {
+    #[serde(rename = "filter", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Box<models::StatusEventFilter>>,
+    /// The secret used to sign the payloads. Each payload is signed with HMAC-SHA256 and the hex encoded signature is sent in the `x-emily-signature` header.
+    #[serde(rename = "secret")]
+    pub secret: String,
+    /// The URL that status events are posted to.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+impl CreateWebhookRequestBody {
+    /// Request structure for the create webhook request.
+    pub fn new(secret: String, url: String) -> CreateWebhookRequestBody {
+        CreateWebhookRequestBody { filter: None, secret, url }
+    }
+}
@@ -0,0 +1,36 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// Webhook : A registered webhook. The secret used to sign the payloads is never returned.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    #[serde(rename = "filter")]
+    pub filter: Box<models::StatusEventFilter>,
+    /// The id of the webhook.
+    #[serde(rename = "id")]
+    pub id: String,
+    /// The URL that status events are posted to.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+impl Webhook {
+    /// A registered webhook. The secret used to sign the payloads is never returned.
+    pub fn new(filter: models::StatusEventFilter, id: String, url: String) -> Webhook {
+        Webhook {
+            filter: Box::new(filter),
+            id,
+            url,
+        }
+    }
+}
@@ -29,6 +29,14 @@ pub struct WithdrawalInfo {
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
     #[serde(rename = "requestId")]
     pub request_id: u64,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(
+        rename = "sender",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sender: Option<Option<String>>,
     /// The stacks block hash in which this request id was initiated.
     #[serde(rename = "stacksBlockHash")]
     pub stacks_block_hash: String,
@@ -57,6 +65,7 @@ impl WithdrawalInfo {
             last_update_height,
             recipient,
             request_id,
+            sender: None,
             stacks_block_hash,
             stacks_block_height,
             status,
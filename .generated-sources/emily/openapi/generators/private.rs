@@ -25,6 +25,8 @@ use super::CorsSupport;
         api::handlers::withdrawal::get_withdrawals,
         api::handlers::withdrawal::create_withdrawal,
         api::handlers::withdrawal::update_withdrawals,
+        // Export endpoints.
+        api::handlers::export::export_operations,
         // Chainstate endpoints.
         api::handlers::chainstate::get_chain_tip,
         api::handlers::chainstate::get_chainstate_at_height,
@@ -37,6 +39,14 @@ use super::CorsSupport;
         api::handlers::limits::set_limits,
         api::handlers::limits::get_limits_for_account,
         api::handlers::limits::set_limits_for_account,
+        // Api key endpoints.
+        api::handlers::api_key::create_api_key,
+        api::handlers::api_key::get_api_keys,
+        api::handlers::api_key::revoke_api_key,
+        // Subscription endpoints.
+        api::handlers::subscription::create_webhook,
+        api::handlers::subscription::get_webhooks,
+        api::handlers::subscription::delete_webhook,
     ),
     // Components to be included in the OpenAPI specification.
     components(schemas(
@@ -46,6 +56,7 @@ use super::CorsSupport;
         api::models::deposit::Deposit,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
+        api::models::deposit::DepositVerification,
         api::models::deposit::requests::CreateDepositRequestBody,
         api::models::deposit::requests::DepositUpdate,
         api::models::deposit::requests::UpdateDepositsRequestBody,
@@ -61,6 +72,8 @@ use super::CorsSupport;
         api::models::withdrawal::requests::UpdateWithdrawalsRequestBody,
         api::models::withdrawal::responses::GetWithdrawalsResponse,
         api::models::withdrawal::responses::UpdateWithdrawalsResponse,
+        // Export models.
+        api::models::export::ExportFormat,
         // Health check datatypes.
         api::models::health::responses::HealthData,
         // Common models.
@@ -69,6 +82,18 @@ use super::CorsSupport;
         // Limits models
         api::models::limits::Limits,
         api::models::limits::AccountLimits,
+        // Api key models.
+        api::models::api_key::ApiKey,
+        api::models::api_key::ApiKeyScope,
+        api::models::api_key::requests::CreateApiKeyRequestBody,
+        api::models::api_key::responses::CreateApiKeyResponse,
+        api::models::api_key::responses::GetApiKeysResponse,
+        // Subscription models.
+        api::models::subscription::StatusEvent,
+        api::models::subscription::StatusEventFilter,
+        api::models::subscription::Webhook,
+        api::models::subscription::requests::CreateWebhookRequestBody,
+        api::models::subscription::responses::GetWebhooksResponse,
         // Errors.
         common::error::ErrorResponse,
     ))
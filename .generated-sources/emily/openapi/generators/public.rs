@@ -25,6 +25,8 @@ use super::CorsSupport;
         api::handlers::withdrawal::get_withdrawals,
         api::handlers::withdrawal::create_withdrawal,
         api::handlers::withdrawal::update_withdrawals,
+        // Export endpoints.
+        // api::handlers::export::export_operations, // exports are for operators.
         // Chainstate endpoints.
         api::handlers::chainstate::get_chain_tip,
         api::handlers::chainstate::get_chainstate_at_height,
@@ -37,6 +39,14 @@ use super::CorsSupport;
         // api::handlers::limits::set_limits, // signers / users may not set limits.
         api::handlers::limits::get_limits_for_account,
         // api::handlers::limits::set_limits_for_account, // signers / users may not set limits.
+        // Api key endpoints.
+        // api::handlers::api_key::create_api_key, // api keys are managed by the admin.
+        // api::handlers::api_key::get_api_keys, // api keys are managed by the admin.
+        // api::handlers::api_key::revoke_api_key, // api keys are managed by the admin.
+        // Subscription endpoints.
+        // api::handlers::subscription::create_webhook, // webhooks are registered by operators.
+        // api::handlers::subscription::get_webhooks, // webhooks are registered by operators.
+        // api::handlers::subscription::delete_webhook, // webhooks are registered by operators.
     ),
     // Components to be included in the OpenAPI specification.
     components(schemas(
@@ -46,6 +56,7 @@ use super::CorsSupport;
         api::models::deposit::Deposit,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
+        api::models::deposit::DepositVerification,
         api::models::deposit::requests::CreateDepositRequestBody,
         api::models::deposit::requests::DepositUpdate, // users may not update the state of existing deposits.
         api::models::deposit::requests::UpdateDepositsRequestBody, // users may not update the state of existing deposits.
@@ -16,4 +16,9 @@ export interface EmilyStackProps extends cdk.StackProps {
    * The trusted reorg API key.
    */
   readonly trustedReorgApiKey: string;
+
+  /**
+   * The API key that can create and revoke scoped API keys.
+   */
+  readonly adminApiKey?: string;
 }
@@ -56,6 +56,11 @@ export class EmilyStackUtils {
      */
     private static trustedReorgApiKey?: string;
 
+    /*
+     * The admin API key.
+     */
+    private static adminApiKey?: string;
+
     /*
      * Returns the current stage name.
      */
@@ -141,6 +146,14 @@ export class EmilyStackUtils {
         return this.trustedReorgApiKey;
     }
 
+    /*
+     * Returns the api key that can manage scoped api keys or undefined if none is set.
+     */
+    public static getAdminApiKey(): string | undefined {
+        this.adminApiKey ??= process.env.ADMIN_API_KEY;
+        return this.adminApiKey;
+    }
+
     /*
      * Returns true iff the current stack is a development stack / not a production stack.
      */
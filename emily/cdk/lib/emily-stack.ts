@@ -77,12 +77,32 @@ export class EmilyStack extends cdk.Stack {
             pointInTimeRecovery,
         );
 
+        const webhookTableId: string = 'WebhookTable';
+        const webhookTableName: string = EmilyStackUtils.getResourceName(webhookTableId, props);
+        const webhookTable: dynamodb.Table = this.createOrUpdateWebhookTable(
+            webhookTableId,
+            webhookTableName,
+            persistentResourceRemovalPolicy,
+            pointInTimeRecovery,
+        );
+
+        const apiKeyTableId: string = 'ApiKeyTable';
+        const apiKeyTableName: string = EmilyStackUtils.getResourceName(apiKeyTableId, props);
+        const apiKeyTable: dynamodb.Table = this.createOrUpdateApiKeyTable(
+            apiKeyTableId,
+            apiKeyTableName,
+            persistentResourceRemovalPolicy,
+            pointInTimeRecovery,
+        );
+
         if (!EmilyStackUtils.isTablesOnly()) {
             const operationLambda: lambda.Function = this.createOrUpdateOperationLambda(
                 depositTableName,
                 withdrawalTableName,
                 chainstateTableName,
                 limitTableName,
+                webhookTableName,
+                apiKeyTableName,
                 persistentResourceRemovalPolicy,
                 props
             );
@@ -98,6 +118,8 @@ export class EmilyStack extends cdk.Stack {
             withdrawalTable.grantReadWriteData(operationLambda);
             chainstateTable.grantReadWriteData(operationLambda);
             limitTable.grantReadWriteData(operationLambda);
+            webhookTable.grantReadWriteData(operationLambda);
+            apiKeyTable.grantReadWriteData(operationLambda);
 
             const emilyApis: apig.SpecRestApi[] = this.createOrUpdateApi(
                 alias,
@@ -155,6 +177,8 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "Sender",
+                "CreatedAt",
             ]
         });
 
@@ -178,10 +202,58 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "Sender",
+                "CreatedAt",
+            ]
+        });
+
+        // Sparse index over the deposits whose sender is known; only those
+        // have a `Sender` attribute.
+        const bySenderIndexName: string = "DepositSender";
+        table.addGlobalSecondaryIndex({
+            indexName: bySenderIndexName,
+            partitionKey: {
+                name: 'Sender',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'LastUpdateHeight',
+                type:  dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "BitcoinTxid",
+                "BitcoinTxOutputIndex",
+                "OpStatus",
+                "Recipient",
+                "Amount",
+                "LastUpdateBlockHash",
+                "ReclaimScript",
+                "DepositScript",
+                "CreatedAt",
+            ]
+        });
+
+        // Sparse index over the deposits that count towards the rolling
+        // window limits; only those have an `AcceptedAt` attribute.
+        const acceptedIndexName: string = "DepositAccepted";
+        table.addGlobalSecondaryIndex({
+            indexName: acceptedIndexName,
+            partitionKey: {
+                name: 'OpStatus',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'AcceptedAt',
+                type:  dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "Recipient",
+                "Amount",
             ]
         });
 
-        // TODO(388): Add an additional GSI for querying by user; not required for MVP.
         return table;
     }
 
@@ -234,10 +306,81 @@ export class EmilyStack extends cdk.Stack {
                 "Recipient",
                 "Amount",
                 "LastUpdateBlockHash",
+                "Sender",
+                "CreatedAt",
+            ]
+        });
+
+        const byRecipientIndexName: string = "WithdrawalRecipient";
+        table.addGlobalSecondaryIndex({
+            indexName: byRecipientIndexName,
+            partitionKey: {
+                name: 'Recipient',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'LastUpdateHeight',
+                type:  dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "RequestId",
+                "StacksBlockHash",
+                "StacksBlockHeight",
+                "OpStatus",
+                "Amount",
+                "LastUpdateBlockHash",
+                "Sender",
+                "CreatedAt",
+            ]
+        });
+
+        // Sparse index over the withdrawals whose sender is known; only
+        // those have a `Sender` attribute.
+        const bySenderIndexName: string = "WithdrawalSender";
+        table.addGlobalSecondaryIndex({
+            indexName: bySenderIndexName,
+            partitionKey: {
+                name: 'Sender',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'LastUpdateHeight',
+                type:  dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "RequestId",
+                "StacksBlockHash",
+                "StacksBlockHeight",
+                "OpStatus",
+                "Recipient",
+                "Amount",
+                "LastUpdateBlockHash",
+                "CreatedAt",
+            ]
+        });
+
+        // Sparse index over the withdrawals that count towards the rolling
+        // window limits; only those have an `AcceptedAt` attribute.
+        const acceptedIndexName: string = "WithdrawalAccepted";
+        table.addGlobalSecondaryIndex({
+            indexName: acceptedIndexName,
+            partitionKey: {
+                name: 'OpStatus',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'AcceptedAt',
+                type:  dynamodb.AttributeType.NUMBER
+            },
+            projectionType: dynamodb.ProjectionType.INCLUDE,
+            nonKeyAttributes: [
+                "Recipient",
+                "Amount",
             ]
         });
 
-        // TODO(388): Add an additional GSI for querying by user; not required for MVP.
         return table;
     }
 
@@ -301,11 +444,91 @@ export class EmilyStack extends cdk.Stack {
         });
     }
 
+    /**
+     * Creates or updates a DynamoDB table for status webhooks.
+     * @param {string} tableId The id of the table AWS resource.
+     * @param {string} tableName The name of the DynamoDB table.
+     * @returns {dynamodb.Table} The created or updated DynamoDB table.
+     * @post A DynamoDB table is returned with a global secondary index.
+     */
+    createOrUpdateWebhookTable(
+        tableId: string,
+        tableName: string,
+        removalPolicy: cdk.RemovalPolicy,
+        pointInTimeRecovery: undefined | boolean,
+    ): dynamodb.Table {
+        // Create DynamoDB table to store the webhooks. Encrypted by default.
+        const table: dynamodb.Table = new dynamodb.Table(this, tableId, {
+            tableName: tableName,
+            partitionKey: {
+                name: 'WebhookId',
+                type: dynamodb.AttributeType.STRING,
+            },
+            sortKey: {
+                name: 'CreatedAt',
+                type: dynamodb.AttributeType.NUMBER,
+            },
+            removalPolicy: removalPolicy,
+            billingMode: dynamodb.BillingMode.PAY_PER_REQUEST, // On-demand provisioning
+            pointInTimeRecovery: pointInTimeRecovery,
+        });
+
+        // Status events look up the webhooks that may want them by the key
+        // derived from the webhook filter, and deliver straight from the index.
+        const byFilterIndexName: string = "WebhookFilter";
+        table.addGlobalSecondaryIndex({
+            indexName: byFilterIndexName,
+            partitionKey: {
+                name: 'FilterKey',
+                type:  dynamodb.AttributeType.STRING
+            },
+            sortKey: {
+                name: 'WebhookId',
+                type:  dynamodb.AttributeType.STRING
+            },
+            projectionType: dynamodb.ProjectionType.ALL,
+        });
+
+        return table;
+    }
+
+    /**
+     * Creates or updates a DynamoDB table for scoped api keys.
+     * @param {string} tableId The id of the table AWS resource.
+     * @param {string} tableName The name of the DynamoDB table.
+     * @returns {dynamodb.Table} The created or updated DynamoDB table.
+     * @post A DynamoDB table is returned without additional configuration.
+     */
+    createOrUpdateApiKeyTable(
+        tableId: string,
+        tableName: string,
+        removalPolicy: cdk.RemovalPolicy,
+        pointInTimeRecovery: undefined | boolean,
+    ): dynamodb.Table {
+        // Create DynamoDB table to store the hashed api keys. Encrypted by default.
+        return new dynamodb.Table(this, tableId, {
+            tableName: tableName,
+            partitionKey: {
+                name: 'KeyId',
+                type: dynamodb.AttributeType.STRING,
+            },
+            sortKey: {
+                name: 'CreatedAt',
+                type: dynamodb.AttributeType.NUMBER,
+            },
+            removalPolicy: removalPolicy,
+            billingMode: dynamodb.BillingMode.PAY_PER_REQUEST, // On-demand provisioning
+            pointInTimeRecovery: pointInTimeRecovery,
+        });
+    }
+
     /**
      * Creates or updates the operation Lambda function.
      * @param {string} depositTableName The name of the deposit DynamoDB table.
      * @param {string} withdrawalTableName The name of the withdrawal DynamoDB table.
      * @param {string} chainstateTableName The name of the chainstate DynamoDB table.
+     * @param {string} webhookTableName The name of the webhook DynamoDB table.
+     * @param {string} apiKeyTableName The name of the api key DynamoDB table.
      * @param {EmilyStackProps} props The stack properties.
      * @returns {lambda.Function} The created or updated Lambda function.
      * @post Lambda function with environment variables set and permissions for DynamoDB access is returned.
@@ -315,6 +538,8 @@ export class EmilyStack extends cdk.Stack {
         withdrawalTableName: string,
         chainstateTableName: string,
         limitTableName: string,
+        webhookTableName: string,
+        apiKeyTableName: string,
         removalPolicy: cdk.RemovalPolicy,
         props: EmilyStackProps
     ): lambda.Function {
@@ -338,11 +563,18 @@ export class EmilyStack extends cdk.Stack {
                 WITHDRAWAL_TABLE_NAME: withdrawalTableName,
                 CHAINSTATE_TABLE_NAME: chainstateTableName,
                 LIMIT_TABLE_NAME: limitTableName,
+                WEBHOOK_TABLE_NAME: webhookTableName,
+                API_KEY_TABLE_NAME: apiKeyTableName,
                 // Declare an environment variable that will be overwritten in local SAM
                 // deployments the AWS stack. SAM can only set environment variables that are
                 // already expected to be present in the lambda.
                 IS_LOCAL: "false",
                 TRUSTED_REORG_API_KEY: props.trustedReorgApiKey,
+                // The API gateway checks its own api keys before invoking the lambda, so
+                // keys that the lambda doesn't know about have already been authorized.
+                TRUST_API_GATEWAY_KEYS: "true",
+                // Only the admin api key can create and revoke scoped api keys.
+                ...(props.adminApiKey !== undefined && { ADMIN_API_KEY: props.adminApiKey }),
             },
             description: `Emily Api Handler. ${EmilyStackUtils.getLambdaGitIdentifier()}`,
             currentVersionOptions: {
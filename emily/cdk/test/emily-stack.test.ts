@@ -34,6 +34,8 @@ describe('EmilyStack Test', () => {
         expect(tableNames).toContain(`DepositTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
         expect(tableNames).toContain(`WithdrawalTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
         expect(tableNames).toContain(`ChainstateTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
+        expect(tableNames).toContain(`WebhookTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
+        expect(tableNames).toContain(`ApiKeyTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
     });
 
     it('should create a Lambda function', async () => {
@@ -62,7 +64,10 @@ describe('EmilyStack Test', () => {
                 expect(environment.WITHDRAWAL_TABLE_NAME).toMatch(`WithdrawalTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
                 expect(environment.CHAINSTATE_TABLE_NAME).toMatch(`ChainstateTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
                 expect(environment.LIMIT_TABLE_NAME).toMatch(`LimitTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
+                expect(environment.WEBHOOK_TABLE_NAME).toMatch(`WebhookTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
+                expect(environment.API_KEY_TABLE_NAME).toMatch(`ApiKeyTable-account-region-${Constants.UNIT_TEST_STAGE_NAME}`);
                 expect(environment.TRUSTED_REORG_API_KEY).toEqual("testApiKey");
+                expect(environment.TRUST_API_GATEWAY_KEYS).toEqual("true");
                 expect(environment.IS_LOCAL).toEqual("false");
             });
     });
@@ -0,0 +1,120 @@
+-- Deposit requests. The history, fulfillment and verification fields are
+-- nested documents that are only ever read back whole, so they are kept as
+-- JSONB.
+CREATE TABLE emily_deposits (
+    bitcoin_txid TEXT NOT NULL,
+    bitcoin_tx_output_index BIGINT NOT NULL,
+    version BIGINT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT,
+    amount BIGINT NOT NULL,
+    max_fee BIGINT NOT NULL,
+    lock_time BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    reclaim_script TEXT NOT NULL,
+    deposit_script TEXT NOT NULL,
+    last_update_height BIGINT NOT NULL,
+    last_update_block_hash TEXT NOT NULL,
+    fulfillment JSONB,
+    history JSONB NOT NULL,
+    created_at BIGINT,
+    accepted_at BIGINT,
+    verification JSONB NOT NULL,
+    PRIMARY KEY (bitcoin_txid, bitcoin_tx_output_index)
+);
+
+-- These mirror the secondary indexes on the DynamoDB deposit table.
+CREATE INDEX ix_emily_deposits_status
+    ON emily_deposits (status, last_update_height);
+CREATE INDEX ix_emily_deposits_recipient
+    ON emily_deposits (recipient, last_update_height);
+CREATE INDEX ix_emily_deposits_sender
+    ON emily_deposits (sender, last_update_height)
+    WHERE sender IS NOT NULL;
+CREATE INDEX ix_emily_deposits_accepted_at
+    ON emily_deposits (status, accepted_at)
+    WHERE accepted_at IS NOT NULL;
+
+-- Withdrawal requests.
+CREATE TABLE emily_withdrawals (
+    request_id BIGINT NOT NULL,
+    stacks_block_hash TEXT NOT NULL,
+    stacks_block_height BIGINT NOT NULL,
+    version BIGINT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT,
+    amount BIGINT NOT NULL,
+    max_fee BIGINT NOT NULL,
+    status TEXT NOT NULL,
+    last_update_height BIGINT NOT NULL,
+    last_update_block_hash TEXT NOT NULL,
+    history JSONB NOT NULL,
+    created_at BIGINT,
+    accepted_at BIGINT,
+    PRIMARY KEY (request_id, stacks_block_hash)
+);
+
+-- These mirror the secondary indexes on the DynamoDB withdrawal table.
+CREATE INDEX ix_emily_withdrawals_status
+    ON emily_withdrawals (status, last_update_height);
+CREATE INDEX ix_emily_withdrawals_recipient
+    ON emily_withdrawals (recipient, last_update_height);
+CREATE INDEX ix_emily_withdrawals_sender
+    ON emily_withdrawals (sender, last_update_height)
+    WHERE sender IS NOT NULL;
+CREATE INDEX ix_emily_withdrawals_accepted_at
+    ON emily_withdrawals (status, accepted_at)
+    WHERE accepted_at IS NOT NULL;
+
+-- Stacks blocks that the API has seen.
+CREATE TABLE emily_chainstates (
+    height BIGINT NOT NULL,
+    hash TEXT NOT NULL,
+    PRIMARY KEY (height, hash)
+);
+
+-- The state of the whole API. There is at most one row in this table.
+CREATE TABLE emily_api_state (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    version BIGINT NOT NULL,
+    reorganizing BOOLEAN NOT NULL,
+    chaintip_height BIGINT NOT NULL,
+    chaintip_hash TEXT NOT NULL,
+    updated_at BIGINT
+);
+
+-- Every update to the limits of an account is kept, keyed by the time of
+-- the update.
+CREATE TABLE emily_limits (
+    account TEXT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    peg_cap BIGINT,
+    per_deposit_minimum BIGINT,
+    per_deposit_cap BIGINT,
+    per_withdrawal_cap BIGINT,
+    rolling_window_seconds BIGINT,
+    rolling_deposit_cap BIGINT,
+    rolling_withdrawal_cap BIGINT,
+    PRIMARY KEY (account, timestamp)
+);
+
+-- Webhooks that status events are posted to.
+CREATE TABLE emily_webhooks (
+    webhook_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    filter JSONB NOT NULL,
+    PRIMARY KEY (webhook_id, created_at)
+);
+
+-- Api keys. Only the hash of each key is stored.
+CREATE TABLE emily_api_keys (
+    key_id TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    key_hash TEXT NOT NULL,
+    description TEXT NOT NULL,
+    scopes JSONB NOT NULL,
+    revoked BOOLEAN NOT NULL,
+    PRIMARY KEY (key_id, created_at)
+);
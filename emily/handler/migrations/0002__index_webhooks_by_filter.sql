@@ -0,0 +1,16 @@
+-- Webhooks are looked up by the most selective field of their filter
+-- instead of scanning every webhook for each status event. This mirrors the
+-- filter index on the DynamoDB webhook table.
+ALTER TABLE emily_webhooks ADD COLUMN filter_key TEXT;
+
+UPDATE emily_webhooks SET filter_key = CASE
+    WHEN filter->>'bitcoinTxid' IS NOT NULL THEN 'txid#' || (filter->>'bitcoinTxid')
+    WHEN filter->>'recipient' IS NOT NULL THEN 'recipient#' || (filter->>'recipient')
+    WHEN filter->>'status' IS NOT NULL THEN 'status#' || (filter->>'status')
+    ELSE 'any'
+END;
+
+ALTER TABLE emily_webhooks ALTER COLUMN filter_key SET NOT NULL;
+
+CREATE INDEX ix_emily_webhooks_filter_key
+    ON emily_webhooks (filter_key, webhook_id);
@@ -0,0 +1,6 @@
+-- How far the reconciler has read the events of the sbtc-registry contract.
+-- There is at most one row in this table.
+CREATE TABLE emily_reconciliation_cursor (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    stacks_block_height BIGINT NOT NULL
+);
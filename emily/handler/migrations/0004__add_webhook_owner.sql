@@ -0,0 +1,4 @@
+-- Webhooks belong to the api key that registered them, so that a key can
+-- only list and delete its own webhooks. Webhooks registered before this
+-- belong to no key.
+ALTER TABLE emily_webhooks ADD COLUMN owner TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,78 @@
+//! Api key authorization.
+//!
+//! Api keys have the form `<id>.<secret>`. Only the SHA-256 hash of the full
+//! key is stored, next to the scopes of the key, so the id is used to find
+//! the stored entry and the hash to check the key against it.
+
+use sha2::{Digest, Sha256};
+
+use crate::api::models::api_key::ApiKeyScope;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+
+/// Generate a new api key, returning its id and the full key.
+pub fn generate_api_key() -> (String, String) {
+    let id = hex::encode(rand::random::<[u8; 8]>());
+    let secret = hex::encode(rand::random::<[u8; 32]>());
+    let key = format!("{id}.{secret}");
+    (id, key)
+}
+
+/// Hash an api key for storage.
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Check that the key is the expected key or secret. The hashes of the two
+/// are compared so that the time the comparison takes doesn't reveal how
+/// much of the expected key was guessed.
+fn key_matches(key: &str, expected: &str) -> bool {
+    hash_api_key(key) == hash_api_key(expected)
+}
+
+/// Check that the api key is allowed to act within the scope, then take a
+/// token from the rate limit bucket of the key. Returns the id of the
+/// caller, which is the key id of stored keys and the hash of other keys.
+///
+/// The trusted reorg api key is allowed everything. Keys that aren't stored
+/// in the database are refused, unless the API is configured to trust the
+/// API gateway to have checked them.
+pub async fn authorize(
+    context: &EmilyContext,
+    api_key: &str,
+    scope: ApiKeyScope,
+) -> Result<String, Error> {
+    let caller = if key_matches(api_key, &context.settings.trusted_reorg_api_key) {
+        hash_api_key(api_key)
+    } else {
+        match accessors::get_api_key_entry_for_key(context, api_key).await? {
+            Some(entry) if entry.allows(scope) => entry.key.key_id,
+            Some(_) => return Err(Error::Forbidden),
+            None if context.settings.trust_api_gateway_keys => hash_api_key(api_key),
+            None => return Err(Error::Unauthorized),
+        }
+    };
+    if let Some(quota) = &context.settings.rate_limits.per_key {
+        context.rate_limiter.check_key(&caller, quota)?;
+    }
+    Ok(caller)
+}
+
+/// Check that the api key is the admin api key. Admin requests are refused
+/// when no admin api key is configured.
+pub fn authorize_admin(context: &EmilyContext, api_key: &str) -> Result<(), Error> {
+    match &context.settings.admin_api_key {
+        Some(admin_api_key) if key_matches(api_key, admin_api_key) => Ok(()),
+        _ => Err(Error::Forbidden),
+    }
+}
+
+/// Check that the secret in the path of an event observer request is the
+/// event observer secret. Requests are refused when no secret is configured.
+pub fn authorize_event_observer(context: &EmilyContext, secret: &str) -> Result<(), Error> {
+    match &context.settings.event_observer_secret {
+        Some(expected) if key_matches(secret, expected) => Ok(()),
+        _ => Err(Error::Forbidden),
+    }
+}
@@ -0,0 +1,147 @@
+//! Handlers for api key endpoints.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::instrument;
+use warp::http::StatusCode;
+use warp::reply::{json, with_status, Reply};
+
+use crate::api::auth;
+use crate::api::models::api_key::requests::CreateApiKeyRequestBody;
+use crate::api::models::api_key::responses::{CreateApiKeyResponse, GetApiKeysResponse};
+use crate::api::models::api_key::ApiKey;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::database::entries::api_key::{ApiKeyEntry, ApiKeyEntryKey};
+
+/// Create api key handler.
+#[utoipa::path(
+    post,
+    operation_id = "createApiKey",
+    path = "/api-key",
+    tag = "api-key",
+    request_body = CreateApiKeyRequestBody,
+    responses(
+        (status = 201, description = "Api key created successfully", body = CreateApiKeyResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 403, description = "Not the admin api key", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn create_api_key(
+    context: EmilyContext,
+    api_key: String,
+    body: CreateApiKeyRequestBody,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+        body: CreateApiKeyRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize_admin(&context, &api_key)?;
+        if body.scopes.is_empty() {
+            return Err(Error::BadRequest("Api key must have a scope".into()));
+        }
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
+        let (key_id, key) = auth::generate_api_key();
+        let entry = ApiKeyEntry {
+            key: ApiKeyEntryKey { key_id, created_at },
+            key_hash: auth::hash_api_key(&key),
+            description: body.description,
+            scopes: body.scopes,
+            revoked: false,
+        };
+        accessors::add_api_key_entry(&context, &entry).await?;
+        let response = CreateApiKeyResponse { api_key: entry.into(), key };
+        Ok(with_status(json(&response), StatusCode::CREATED))
+    }
+    // Handle and respond.
+    handler(context, api_key, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Get api keys handler.
+#[utoipa::path(
+    get,
+    operation_id = "getApiKeys",
+    path = "/api-key",
+    tag = "api-key",
+    responses(
+        (status = 200, description = "Api keys retrieved successfully", body = GetApiKeysResponse),
+        (status = 403, description = "Not the admin api key", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn get_api_keys(context: EmilyContext, api_key: String) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize_admin(&context, &api_key)?;
+        let mut api_keys: Vec<ApiKey> = accessors::get_api_key_entries(&context)
+            .await?
+            .into_iter()
+            .map(ApiKey::from)
+            .collect();
+        api_keys.sort_by_key(|api_key| api_key.created_at);
+        let response = GetApiKeysResponse { api_keys };
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, api_key)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Revoke api key handler.
+#[utoipa::path(
+    delete,
+    operation_id = "revokeApiKey",
+    path = "/api-key/{id}",
+    params(
+        ("id" = String, Path, description = "The id of the api key to revoke."),
+    ),
+    tag = "api-key",
+    responses(
+        (status = 200, description = "Api key revoked successfully", body = ApiKey),
+        (status = 403, description = "Not the admin api key", body = ErrorResponse),
+        (status = 404, description = "Api key not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn revoke_api_key(
+    context: EmilyContext,
+    id: String,
+    api_key: String,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        id: String,
+        api_key: String,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize_admin(&context, &api_key)?;
+        let revoked: ApiKey = accessors::revoke_api_key(&context, &id).await?.into();
+        Ok(with_status(json(&revoked), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, id, api_key)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
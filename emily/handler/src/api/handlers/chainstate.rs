@@ -1,8 +1,9 @@
 //! Handlers for chainstate endpoints.
 use crate::{
     api::{
+        auth,
         handlers::internal::{execute_reorg_handler, ExecuteReorgRequest},
-        models::chainstate::Chainstate,
+        models::{api_key::ApiKeyScope, chainstate::Chainstate},
     },
     common::error::{Error, Inconsistency},
     context::EmilyContext,
@@ -100,7 +101,7 @@ pub async fn get_chainstate_at_height(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn set_chainstate(
     context: EmilyContext,
     api_key: String,
@@ -115,6 +116,7 @@ pub async fn set_chainstate(
     ) -> Result<impl warp::reply::Reply, Error> {
         // Convert body to the correct type.
         let chainstate: Chainstate = body;
+        auth::authorize(&context, &api_key, ApiKeyScope::ChainstateWrite).await?;
         let can_reorg = context.settings.trusted_reorg_api_key == api_key;
         add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
         // Respond.
@@ -147,7 +149,7 @@ pub async fn set_chainstate(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn update_chainstate(
     context: EmilyContext,
     api_key: String,
@@ -162,6 +164,7 @@ pub async fn update_chainstate(
     ) -> Result<impl warp::reply::Reply, Error> {
         // Convert body to the correct type.
         let chainstate: Chainstate = body;
+        auth::authorize(&context, &api_key, ApiKeyScope::ChainstateWrite).await?;
         let can_reorg = context.settings.trusted_reorg_api_key == api_key;
         add_chainstate_entry_or_reorg(&context, can_reorg, &chainstate).await?;
         // Respond.
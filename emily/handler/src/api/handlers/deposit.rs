@@ -1,6 +1,10 @@
 //! Handlers for Deposit endpoints.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::api::auth;
+use crate::api::models::api_key::ApiKeyScope;
+use crate::api::models::common::requests::{BasicPaginationQuery, OperationSearchQuery};
 use crate::api::models::common::Status;
-use crate::api::models::deposit::requests::BasicPaginationQuery;
 use crate::api::models::deposit::responses::{
     GetDepositsForTransactionResponse, UpdateDepositsResponse,
 };
@@ -9,21 +13,21 @@ use stacks_common::codec::StacksMessageCodec as _;
 use tracing::{debug, instrument};
 use warp::reply::{json, with_status, Reply};
 
-use crate::api::models::deposit::{Deposit, DepositInfo};
+use crate::api::models::deposit::{Deposit, DepositInfo, DepositVerification};
 use crate::api::models::{
     deposit::requests::{
-        CreateDepositRequestBody, GetDepositsForTransactionQuery, GetDepositsQuery,
-        UpdateDepositsRequestBody,
+        CreateDepositRequestBody, GetDepositsForTransactionQuery, UpdateDepositsRequestBody,
     },
     deposit::responses::GetDepositsResponse,
 };
-use crate::common::error::Error;
+use crate::common::error::{DepositVerificationError, Error};
 use crate::context::EmilyContext;
 use crate::database::accessors;
 use crate::database::entries::deposit::{
     DepositEntry, DepositEntryKey, DepositEvent, DepositParametersEntry,
     ValidatedUpdateDepositsRequest,
 };
+use crate::verification::VerificationOutcome;
 use bitcoin::ScriptBuf;
 use warp::http::StatusCode;
 
@@ -142,7 +146,13 @@ pub async fn get_deposits_for_transaction(
     operation_id = "getDeposits",
     path = "/deposit",
     params(
-        ("status" = Status, Query, description = "the status to search by when getting all deposits."),
+        ("status" = Option<Vec<Status>>, Query, style = Form, explode = false, description = "comma separated statuses to search by. Required unless a recipient or sender is given."),
+        ("recipient" = Option<String>, Query, description = "the recipient to search by."),
+        ("sender" = Option<String>, Query, description = "the hex encoded script pubkey of the output spent by the first input of the deposit transaction to search by."),
+        ("minHeight" = Option<u64>, Query, description = "the minimum last update height, inclusive."),
+        ("maxHeight" = Option<u64>, Query, description = "the maximum last update height, inclusive."),
+        ("minTimestamp" = Option<u64>, Query, description = "the minimum unix time in seconds at which the deposit was received, inclusive."),
+        ("maxTimestamp" = Option<u64>, Query, description = "the maximum unix time in seconds at which the deposit was received, inclusive."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
         ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list.")
     ),
@@ -158,20 +168,21 @@ pub async fn get_deposits_for_transaction(
 #[instrument(skip(context))]
 pub async fn get_deposits(
     context: EmilyContext,
-    query: GetDepositsQuery,
+    query: OperationSearchQuery,
+    pagination: BasicPaginationQuery,
 ) -> impl warp::reply::Reply {
     debug!("In get deposits");
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
-        query: GetDepositsQuery,
+        query: OperationSearchQuery,
+        pagination: BasicPaginationQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
-        // Deserialize next token into the exclusive start key if present/
-        let (entries, next_token) = accessors::get_deposit_entries(
+        let (entries, next_token) = accessors::search_deposit_entries(
             &context,
-            &query.status,
-            query.next_token,
-            query.page_size,
+            &query,
+            pagination.next_token,
+            pagination.page_size,
         )
         .await?;
         // Convert data into resource types.
@@ -182,7 +193,7 @@ pub async fn get_deposits(
         Ok(with_status(json(&response), StatusCode::OK))
     }
     // Handle and respond.
-    handler(context, query)
+    handler(context, query, pagination)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -248,7 +259,7 @@ pub async fn get_deposits_for_recipient(
     request_body = CreateDepositRequestBody,
     responses(
         (status = 201, description = "Deposit created successfully", body = Deposit),
-        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 400, description = "Invalid request body or the deposit doesn't match its bitcoin transaction", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
         (status = 409, description = "Duplicate request", body = ErrorResponse),
@@ -273,6 +284,14 @@ pub async fn create_deposit(
         let chaintip = api_state.chaintip();
         let mut stacks_block_hash: String = chaintip.key.hash;
         let mut stacks_block_height: u64 = chaintip.key.height;
+        // The amount read from the bitcoin transaction when the deposit was
+        // last verified. Deposits that were stored without being verified
+        // record an amount of zero, so theirs can't be compared against.
+        let mut recorded_amount: Option<u64> = None;
+        let mut created_at: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
 
         // Check if deposit with such txid and outindex already exists.
         let entry = accessors::get_deposit_entry(
@@ -292,6 +311,10 @@ pub async fn create_deposit(
                     // If the deposit is pending or reprocessing, we should keep height and hash same as in the old deposit
                     stacks_block_hash = deposit.last_update_block_hash;
                     stacks_block_height = deposit.last_update_height;
+                    if deposit.verification == DepositVerification::Verified {
+                        recorded_amount = Some(deposit.amount);
+                    }
+                    created_at = deposit.created_at.unwrap_or(created_at);
                 }
             }
             Err(Error::NotFound) => {}
@@ -304,6 +327,36 @@ pub async fn create_deposit(
         let script_parameters =
             scripts_to_resource_parameters(&body.deposit_script, &body.reclaim_script)?;
 
+        // Check the deposit against its bitcoin transaction if there's an
+        // endpoint to fetch it from. A transaction that can't be found yet
+        // isn't an error, it just leaves the deposit unconfirmed.
+        let (amount, verification, sender) = match context.deposit_verifier() {
+            None => (
+                script_parameters.amount,
+                DepositVerification::Unverified,
+                None,
+            ),
+            Some(verifier) => match verifier.verify(&body).await? {
+                VerificationOutcome::Verified { info, sender } => {
+                    if let Some(expected) = recorded_amount.filter(|amount| *amount != info.amount)
+                    {
+                        return Err(DepositVerificationError::AmountMismatch {
+                            expected,
+                            actual: info.amount,
+                        }
+                        .into());
+                    }
+                    let sender = sender.map(|script| script.to_hex_string());
+                    (info.amount, DepositVerification::Verified, sender)
+                }
+                VerificationOutcome::Unconfirmed => (
+                    script_parameters.amount,
+                    DepositVerification::Unconfirmed,
+                    None,
+                ),
+            },
+        };
+
         // Make table entry.
         let deposit_entry: DepositEntry = DepositEntry {
             key: DepositEntryKey {
@@ -311,6 +364,7 @@ pub async fn create_deposit(
                 bitcoin_tx_output_index: body.bitcoin_tx_output_index,
             },
             recipient: script_parameters.recipient,
+            sender,
             parameters: DepositParametersEntry {
                 max_fee: script_parameters.max_fee,
                 lock_time: script_parameters.lock_time,
@@ -324,9 +378,11 @@ pub async fn create_deposit(
             status,
             last_update_block_hash: stacks_block_hash,
             last_update_height: stacks_block_height,
-            amount: script_parameters.amount,
+            amount,
             reclaim_script: body.reclaim_script,
             deposit_script: body.deposit_script,
+            created_at: Some(created_at),
+            verification,
             ..Default::default()
         };
         // Validate deposit entry.
@@ -393,7 +449,7 @@ fn scripts_to_resource_parameters(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn update_deposits(
     context: EmilyContext,
     api_key: String,
@@ -406,6 +462,7 @@ pub async fn update_deposits(
         api_key: String,
         body: UpdateDepositsRequestBody,
     ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::SignerUpdate).await?;
         // Get the api state and error if the api state is claimed by a reorg.
         //
         // Note: This may not be necessary due to the implied order of events
@@ -0,0 +1,148 @@
+//! Handlers for the stacks node event observer endpoints.
+use sbtc::events::{RegistryEvent, StacksTxid, TxInfo};
+use sbtc::webhooks::NewBlockEvent;
+use tracing::{debug, error, instrument, warn};
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::reply::Reply;
+
+use crate::api::auth;
+use crate::api::handlers::chainstate::add_chainstate_entry_or_reorg;
+use crate::api::models::chainstate::Chainstate;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::entries::chainstate::ChainstateEntry;
+use crate::registry;
+
+/// Maximum request body size for the new block endpoint. A new block event
+/// carries the events of the block along with the block, so it can be
+/// larger than the 2 MB that a block is limited to.
+pub const EVENT_OBSERVER_BODY_LIMIT: u64 = 8 * 1024 * 1024;
+
+/// New block handler.
+///
+/// Adds the block to the chainstate, reorganizing the API around the block
+/// when it conflicts with the chainstate, and then applies the print events
+/// of the sbtc-registry contract in the block to the deposits and
+/// withdrawals that they are about. The reorg reverts the events of the
+/// blocks that are no longer canonical, and removes the withdrawals that
+/// were requested in them.
+///
+/// The stacks node is the source of the chainstate here, so its blocks are
+/// trusted with reorgs. It can't send headers, so it is authorized by the
+/// event observer secret in the path instead of an api key.
+///
+/// The stacks node resends a block until it gets a success status back,
+/// so a body that can't be read is acknowledged rather than rejected.
+/// Anything that could succeed on a retry is returned as an error.
+///
+/// The stacks node has to reach this instance of the API directly, so the
+/// endpoint isn't part of the API gateway spec.
+#[instrument(skip(context, secret, body))]
+pub async fn new_block(
+    context: EmilyContext,
+    secret: String,
+    body: Bytes,
+) -> impl warp::reply::Reply {
+    debug!("Received a new block event");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        secret: String,
+        body: Bytes,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize_event_observer(&context, &secret)?;
+
+        let event: NewBlockEvent = match serde_json::from_slice(&body) {
+            Ok(event) => event,
+            Err(error) => {
+                error!(%error, "could not deserialize the new block event");
+                return Ok(StatusCode::OK);
+            }
+        };
+        let block_id = event.index_block_hash;
+        let chainstate = Chainstate {
+            stacks_block_height: event.block_height,
+            stacks_block_hash: block_id.to_hex(),
+        };
+        add_chainstate_entry_or_reorg(&context, true, &chainstate).await?;
+
+        let Some(registry_contract_id) = &context.settings.registry_contract_id else {
+            return Ok(StatusCode::OK);
+        };
+        let chainstate: ChainstateEntry = chainstate.into();
+        for registry_event in registry_events(event, registry_contract_id) {
+            registry::apply_registry_event(&context, registry_event, &chainstate).await?;
+        }
+        Ok(StatusCode::OK)
+    }
+    // Handle and respond.
+    handler(context, secret, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Decode the print events of the registry contract in the block. Events
+/// that can't be decoded are logged and left out, since resending the block
+/// won't make them any easier to read.
+fn registry_events(event: NewBlockEvent, registry_contract_id: &str) -> Vec<RegistryEvent> {
+    let block_id = event.index_block_hash;
+    // Only successful transactions emit print events, so there's no need
+    // to check the transaction receipts.
+    event
+        .events
+        .into_iter()
+        .filter(|tx_event| tx_event.committed)
+        .filter_map(|tx_event| Some((tx_event.contract_event?, tx_event.txid)))
+        .filter(|(contract_event, _)| {
+            contract_event.topic == "print"
+                && contract_event.contract_identifier.to_string() == registry_contract_id
+        })
+        .filter_map(|(contract_event, txid)| {
+            let tx_info = TxInfo {
+                txid: StacksTxid(txid.0),
+                block_id,
+            };
+            RegistryEvent::try_new(contract_event.value, tx_info)
+                .inspect_err(|error| warn!(%error, %tx_info, "could not decode the registry event"))
+                .ok()
+        })
+        .collect()
+}
+
+/// New attachments handler.
+///
+/// Attachments aren't used, but the stacks node keeps resending them until
+/// they are acknowledged.
+#[instrument]
+pub async fn new_attachments() -> impl warp::reply::Reply {
+    StatusCode::OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WITHDRAWAL_CREATE_EVENT: &str =
+        include_str!("../../../../../signer/tests/fixtures/withdrawal-create-event.json");
+
+    #[test]
+    fn registry_events_are_read_from_the_registry_contract_only() {
+        let event: NewBlockEvent = serde_json::from_str(WITHDRAWAL_CREATE_EVENT).unwrap();
+        let events = registry_events(
+            event,
+            "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry",
+        );
+        assert!(matches!(
+            events.as_slice(),
+            [RegistryEvent::WithdrawalCreate(_)]
+        ));
+
+        let event: NewBlockEvent = serde_json::from_str(WITHDRAWAL_CREATE_EVENT).unwrap();
+        let events = registry_events(
+            event,
+            "SN2V7WTJ7BHR03MPHZ1C9A9ZR6NZGR4WM8HT4V67Y.sbtc-registry",
+        );
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+//! Handlers for export endpoints.
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt as _;
+use tracing::instrument;
+use warp::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::hyper::Body;
+use warp::reply::Reply;
+
+use crate::api::auth;
+use crate::api::models::api_key::ApiKeyScope;
+use crate::api::models::export::requests::ExportQuery;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::export;
+
+/// Export deposits and withdrawals handler.
+///
+/// Streams every deposit and then every withdrawal that matches the query,
+/// with their full history, followed by a summary of the confirmed
+/// operations. An export that fails part way is cut off before the summary.
+#[utoipa::path(
+    get,
+    operation_id = "exportOperations",
+    path = "/export",
+    params(
+        ("format" = Option<ExportFormat>, Query, description = "the format of the export, either `jsonl` or `csv`. Defaults to `jsonl`."),
+        ("status" = Option<Vec<Status>>, Query, style = Form, explode = false, description = "comma separated statuses to export. Every status is exported if not given."),
+        ("minHeight" = Option<u64>, Query, description = "the minimum last update height, inclusive."),
+        ("maxHeight" = Option<u64>, Query, description = "the maximum last update height, inclusive.")
+    ),
+    tag = "export",
+    responses(
+        (status = 200, description = "Export streamed successfully", body = String, content_type = ["application/x-ndjson", "text/csv"]),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn export_operations(
+    context: EmilyContext,
+    api_key: String,
+    query: ExportQuery,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+        query: ExportQuery,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::ReadOnly).await?;
+        query.validate()?;
+        let format = query.format;
+        // The body can't carry our error type, and the status code has been
+        // sent by the time a chunk fails, so the error only ends the stream.
+        let chunks = ReceiverStream::new(export::spawn_export(context, query))
+            .map(|chunk| chunk.map_err(|err| std::io::Error::other(err.to_string())));
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, format.content_type())
+            .header(
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"emily-export.{}\"",
+                    format.extension()
+                ),
+            )
+            .body(Body::wrap_stream(chunks))
+            .map_err(|err| Error::Debug(format!("Failed to build the export response: {err}")))
+    }
+    // Handle and respond.
+    handler(context, api_key, query)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
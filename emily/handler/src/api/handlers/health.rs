@@ -1,6 +1,14 @@
 //! Handlers for Health endpoint endpoints.
 
-use crate::common::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::warn;
+use warp::http::StatusCode;
+
+use crate::api::models::health::responses::HealthData;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::metrics;
 
 /// Get health handler.
 #[utoipa::path(
@@ -9,15 +17,49 @@ use crate::common::error::Error;
     path = "/health",
     tag = "health",
     responses(
-        // TODO(271): Add success body.
         (status = 200, description = "Successfully retrieved health data.", body = HealthData),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ErrorResponse),
+        (status = 503, description = "The database can't be reached.", body = HealthData)
     ),
     security(("ApiGatewayKey" = []))
 )]
-pub async fn get_health() -> impl warp::reply::Reply {
-    Error::NotImplemented
+pub async fn get_health(context: EmilyContext) -> impl warp::reply::Reply {
+    let health = match accessors::get_api_state(&context).await {
+        Ok(api_state) => {
+            let chain_tip_height = api_state.chaintip().key.height;
+            metrics::set_chain_tip_height(chain_tip_height);
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            HealthData {
+                is_okay: true,
+                database_reachable: true,
+                is_reorganizing: api_state.error_if_reorganizing().is_err(),
+                chain_tip_height: Some(chain_tip_height),
+                chain_tip_age_seconds: api_state
+                    .updated_at
+                    .map(|updated_at| now.saturating_sub(updated_at)),
+            }
+        }
+        Err(error) => {
+            warn!(%error, "could not read the api state for a health check");
+            HealthData {
+                is_okay: false,
+                database_reachable: false,
+                is_reorganizing: false,
+                chain_tip_height: None,
+                chain_tip_age_seconds: None,
+            }
+        }
+    };
+    let status = if health.is_okay {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    warp::reply::with_status(warp::reply::json(&health), status)
 }
@@ -11,6 +11,7 @@ use crate::database::accessors;
 use crate::database::entries::chainstate::{ApiStateEntry, ApiStatus};
 use crate::database::entries::deposit::DepositEntry;
 use crate::database::entries::withdrawal::WithdrawalEntry;
+use crate::metrics::Metrics;
 
 const MAX_SET_API_STATE_ATTEMPTS_DURING_REORG: u32 = 20;
 const ENTRY_UPDATE_RETRIES: u32 = 4;
@@ -102,8 +103,8 @@ pub async fn execute_reorg_handler(
 
     let new_status = ApiStatus::Reorg(request.canonical_tip.clone().into());
     match set_api_state_status(context, &new_status).await? {
-        // Do nothing if we claimed the api correctly.
-        Some(_) => {}
+        // Count the reorg if we claimed the api correctly.
+        Some(_) => metrics::counter!(Metrics::ReorgsTotal).increment(1),
         None => {
             return Ok(empty_reply);
         }
@@ -131,7 +132,10 @@ pub async fn execute_reorg_handler(
                 accessors::get_deposit_entry(context, &deposit.primary_index_key).await?;
             entry.reorganize_around(&request.canonical_tip)?;
             match accessors::set_deposit_entry(context, &mut entry).await {
-                Ok(_) => break,
+                Ok(_) => {
+                    accessors::notify_deposit_subscribers(context, &entry).await;
+                    break;
+                }
                 Err(Error::VersionConflict) => {
                     debug!(
                         "Encountered race condition in updating entry {:?}. Attempt {}/{}",
@@ -165,12 +169,26 @@ pub async fn execute_reorg_handler(
 
     // Kill the history from all the withdrawals.
     for withdrawal in all_withdrawals {
+        // A withdrawal that was requested in a block that is no longer
+        // canonical was never requested at all, so it is removed rather than
+        // reset. The request id can be used again in the canonical chain.
+        if withdrawal.is_orphaned_by(&request.canonical_tip) {
+            info!(
+                "Removing withdrawal requested in an orphaned block: {:?}",
+                withdrawal.primary_index_key
+            );
+            accessors::delete_withdrawal_entry(context, &withdrawal.primary_index_key).await?;
+            continue;
+        }
         for attempt in 0..ENTRY_UPDATE_RETRIES {
             let request_id = withdrawal.primary_index_key.request_id;
             let mut entry = accessors::get_withdrawal_entry(context, &request_id).await?;
             entry.reorganize_around(&request.canonical_tip)?;
             match accessors::set_withdrawal_entry(context, &mut entry).await {
-                Ok(_) => break,
+                Ok(_) => {
+                    accessors::notify_withdrawal_subscribers(context, &entry).await;
+                    break;
+                }
                 Err(Error::VersionConflict) => {
                     debug!(
                         "Encountered race condition in updating entry {:?}. Attempt {}/{}",
@@ -2,13 +2,16 @@
 use std::time::SystemTime;
 
 use crate::{
-    api::models::limits::{AccountLimits, Limits},
+    api::{
+        auth,
+        models::{
+            api_key::ApiKeyScope,
+            limits::{AccountLimits, Limits},
+        },
+    },
     common::error::Error,
     context::EmilyContext,
-    database::{
-        accessors,
-        entries::limits::{LimitEntry, GLOBAL_CAP_ACCOUNT},
-    },
+    database::{accessors, entries::limits::LimitEntry},
 };
 use tracing::instrument;
 use warp::http::StatusCode;
@@ -56,26 +59,23 @@ pub async fn get_limits(context: EmilyContext) -> impl warp::reply::Reply {
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
-pub async fn set_limits(context: EmilyContext, limits: Limits) -> impl warp::reply::Reply {
+#[instrument(skip(context, api_key))]
+pub async fn set_limits(
+    context: EmilyContext,
+    api_key: String,
+    limits: Limits,
+) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
+        api_key: String,
         limits: Limits,
     ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::LimitsAdmin).await?;
         // Set the global limits.
         accessors::set_limit_for_account(
             &context,
-            &LimitEntry::from_account_limit(
-                GLOBAL_CAP_ACCOUNT.to_string(),
-                SystemTime::now(),
-                &AccountLimits {
-                    peg_cap: limits.peg_cap,
-                    per_deposit_minimum: limits.per_deposit_minimum,
-                    per_deposit_cap: limits.per_deposit_cap,
-                    per_withdrawal_cap: limits.per_withdrawal_cap,
-                },
-            ),
+            &LimitEntry::from_global_limits(SystemTime::now(), &limits),
         )
         .await?;
         // Get account cap entries.
@@ -96,7 +96,7 @@ pub async fn set_limits(context: EmilyContext, limits: Limits) -> impl warp::rep
         Ok(with_status(json(&global_limits), StatusCode::CREATED))
     }
     // Handle and respond.
-    handler(context, limits)
+    handler(context, api_key, limits)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -162,18 +162,21 @@ pub async fn get_limits_for_account(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn set_limits_for_account(
     context: EmilyContext,
     account: String,
+    api_key: String,
     body: crate::api::models::limits::AccountLimits,
 ) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
         account: String,
+        api_key: String,
         account_limit: crate::api::models::limits::AccountLimits,
     ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::LimitsAdmin).await?;
         // Create the limit entry.
         let limit_entry =
             LimitEntry::from_account_limit(account, SystemTime::now(), &account_limit);
@@ -183,7 +186,7 @@ pub async fn set_limits_for_account(
         Ok(with_status(json(&account_limit), StatusCode::OK))
     }
     // Handle and respond.
-    handler(context, account, body)
+    handler(context, account, api_key, body)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -1,21 +1,29 @@
 //! Handlers for the emily API
 
-use crate::common::error::ErrorResponse;
+use crate::common::error::{Error, ErrorResponse};
 
 use std::convert::Infallible;
 use tracing::error;
 use warp::{http::StatusCode, Rejection, Reply};
 
+/// Api key handlers.
+pub mod api_key;
 /// Chainstate handlers.
 pub mod chainstate;
 /// Deposit handlers.
 pub mod deposit;
+/// Event observer handlers.
+pub mod event_observer;
+/// Export handlers.
+pub mod export;
 /// Health handlers.
 pub mod health;
 /// Internal handlers.
 pub mod internal;
 /// Limit handlers.
 pub mod limits;
+/// Subscription handlers.
+pub mod subscription;
 /// Testing handlers.
 #[cfg(feature = "testing")]
 pub mod testing;
@@ -32,6 +40,11 @@ pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible>
         return Ok(warp::reply::with_status(json, StatusCode::NOT_FOUND));
     }
 
+    if let Some(e) = err.find::<Error>() {
+        let json = warp::reply::json(&ErrorResponse { message: e.to_string() });
+        return Ok(warp::reply::with_status(json, e.status_code()));
+    }
+
     if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
         let json = warp::reply::json(&ErrorResponse {
             message: format!("Invalid Body: {}", e),
@@ -0,0 +1,169 @@
+//! Handlers for subscription endpoints.
+use std::convert::Infallible;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
+use tracing::instrument;
+use warp::http::StatusCode;
+use warp::reply::{json, with_status, Reply};
+use warp::sse::Event;
+
+use crate::api::auth;
+use crate::api::models::api_key::ApiKeyScope;
+use crate::api::models::subscription::requests::CreateWebhookRequestBody;
+use crate::api::models::subscription::responses::GetWebhooksResponse;
+use crate::api::models::subscription::{StatusEventFilter, Webhook};
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::database::entries::webhook::{WebhookEntry, WebhookEntryKey};
+
+/// Create webhook handler.
+#[utoipa::path(
+    post,
+    operation_id = "createWebhook",
+    path = "/subscription/webhook",
+    tag = "subscription",
+    request_body = CreateWebhookRequestBody,
+    responses(
+        (status = 201, description = "Webhook registered successfully", body = Webhook),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key, body))]
+pub async fn create_webhook(
+    context: EmilyContext,
+    api_key: String,
+    body: CreateWebhookRequestBody,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+        body: CreateWebhookRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let owner = auth::authorize(&context, &api_key, ApiKeyScope::ReadOnly).await?;
+        let url = reqwest::Url::parse(&body.url)
+            .map_err(|err| Error::BadRequest(format!("Invalid webhook url: {err}")))?;
+        context.notifier.check_webhook_url(&url).await?;
+        if body.secret.is_empty() {
+            return Err(Error::BadRequest("Webhook secret must not be empty".into()));
+        }
+
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
+        let key = WebhookEntryKey {
+            webhook_id: hex::encode(rand::random::<[u8; 16]>()),
+            created_at,
+        };
+        let entry = WebhookEntry::new(key, owner, url.to_string(), body.secret, body.filter);
+        accessors::add_webhook_entry(&context, &entry).await?;
+        let webhook: Webhook = entry.into();
+        Ok(with_status(json(&webhook), StatusCode::CREATED))
+    }
+    // Handle and respond.
+    handler(context, api_key, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Get webhooks handler. Only the webhooks registered with the api key of
+/// the request are returned.
+#[utoipa::path(
+    get,
+    operation_id = "getWebhooks",
+    path = "/subscription/webhook",
+    tag = "subscription",
+    responses(
+        (status = 200, description = "Webhooks retrieved successfully", body = GetWebhooksResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn get_webhooks(context: EmilyContext, api_key: String) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let owner = auth::authorize(&context, &api_key, ApiKeyScope::ReadOnly).await?;
+        let webhooks = accessors::get_webhook_entries(&context, &owner)
+            .await?
+            .into_iter()
+            .map(Webhook::from)
+            .collect();
+        let response = GetWebhooksResponse { webhooks };
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, api_key)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Delete webhook handler.
+#[utoipa::path(
+    delete,
+    operation_id = "deleteWebhook",
+    path = "/subscription/webhook/{id}",
+    params(
+        ("id" = String, Path, description = "The id of the webhook to delete."),
+    ),
+    tag = "subscription",
+    responses(
+        (status = 204, description = "Webhook deleted successfully"),
+        (status = 404, description = "Webhook not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context, api_key))]
+pub async fn delete_webhook(
+    context: EmilyContext,
+    id: String,
+    api_key: String,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        id: String,
+        api_key: String,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        let owner = auth::authorize(&context, &api_key, ApiKeyScope::ReadOnly).await?;
+        accessors::delete_webhook_entry(&context, &owner, &id).await?;
+        Ok(with_status(warp::reply(), StatusCode::NO_CONTENT))
+    }
+    // Handle and respond.
+    handler(context, id, api_key)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Stream status events handler.
+///
+/// Opens a server-sent event stream of the status events that match the
+/// filter in the query. Only events published by this instance of the API
+/// reach the stream, so it isn't part of the API gateway spec.
+#[instrument(skip(context))]
+pub async fn stream_status_events(
+    context: EmilyContext,
+    filter: StatusEventFilter,
+) -> Result<impl warp::reply::Reply, Infallible> {
+    let events = BroadcastStream::new(context.notifier.subscribe()).filter_map(move |result| {
+        // A stream that lags behind skips the events it missed.
+        let event = result.ok()?;
+        filter
+            .matches(&event)
+            .then(|| Event::default().event("status").json_data(&event))
+    });
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+}
@@ -1,10 +1,15 @@
 //! Handlers for withdrawal endpoints.
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use tracing::instrument;
 use warp::reply::{json, with_status, Reply};
 
+use crate::api::auth;
+use crate::api::models::api_key::ApiKeyScope;
+use crate::api::models::common::requests::{BasicPaginationQuery, OperationSearchQuery};
 use crate::api::models::common::Status;
 use crate::api::models::withdrawal::{
-    requests::{CreateWithdrawalRequestBody, GetWithdrawalsQuery, UpdateWithdrawalsRequestBody},
+    requests::{CreateWithdrawalRequestBody, UpdateWithdrawalsRequestBody},
     responses::{GetWithdrawalsResponse, UpdateWithdrawalsResponse},
 };
 use crate::api::models::withdrawal::{Withdrawal, WithdrawalInfo};
@@ -63,7 +68,13 @@ pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp
     operation_id = "getWithdrawals",
     path = "/withdrawal",
     params(
-        ("status" = Status, Query, description = "the status to search by when getting all deposits."),
+        ("status" = Option<Vec<Status>>, Query, style = Form, explode = false, description = "comma separated statuses to search by. Required unless a recipient or sender is given."),
+        ("recipient" = Option<String>, Query, description = "the recipient to search by."),
+        ("sender" = Option<String>, Query, description = "the Stacks principal that initiated the withdrawal to search by."),
+        ("minHeight" = Option<u64>, Query, description = "the minimum last update height, inclusive."),
+        ("maxHeight" = Option<u64>, Query, description = "the maximum last update height, inclusive."),
+        ("minTimestamp" = Option<u64>, Query, description = "the minimum unix time in seconds at which the withdrawal was received, inclusive."),
+        ("maxTimestamp" = Option<u64>, Query, description = "the maximum unix time in seconds at which the withdrawal was received, inclusive."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
         ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list.")
     ),
@@ -80,19 +91,20 @@ pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp
 #[instrument(skip(context))]
 pub async fn get_withdrawals(
     context: EmilyContext,
-    query: GetWithdrawalsQuery,
+    query: OperationSearchQuery,
+    pagination: BasicPaginationQuery,
 ) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
-        query: GetWithdrawalsQuery,
+        query: OperationSearchQuery,
+        pagination: BasicPaginationQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
-        // Deserialize next token into the exclusive start key if present.
-        let (entries, next_token) = accessors::get_withdrawal_entries(
+        let (entries, next_token) = accessors::search_withdrawal_entries(
             &context,
-            &query.status,
-            query.next_token,
-            query.page_size,
+            &query,
+            pagination.next_token,
+            pagination.page_size,
         )
         .await?;
         // Convert data into resource types.
@@ -104,7 +116,7 @@ pub async fn get_withdrawals(
         Ok(with_status(json(&response), StatusCode::OK))
     }
     // Handle and respond.
-    handler(context, query)
+    handler(context, query, pagination)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -126,16 +138,19 @@ pub async fn get_withdrawals(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn create_withdrawal(
     context: EmilyContext,
+    api_key: String,
     body: CreateWithdrawalRequestBody,
 ) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
         context: EmilyContext,
+        api_key: String,
         body: CreateWithdrawalRequestBody,
     ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::SignerUpdate).await?;
         // Get the api state and error if the api state is claimed by a reorg.
         //
         // Note: This may not be necessary due to the implied order of events
@@ -149,11 +164,16 @@ pub async fn create_withdrawal(
             stacks_block_hash,
             stacks_block_height,
             recipient,
+            sender,
             amount,
             parameters,
         } = body;
 
         let status = Status::Pending;
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
 
         // Make table entry.
         let withdrawal_entry: WithdrawalEntry = WithdrawalEntry {
@@ -163,6 +183,7 @@ pub async fn create_withdrawal(
                 stacks_block_hash: stacks_block_hash.clone(),
             },
             recipient,
+            sender,
             amount,
             parameters: WithdrawalParametersEntry { max_fee: parameters.max_fee },
             history: vec![WithdrawalEvent {
@@ -174,6 +195,7 @@ pub async fn create_withdrawal(
             status,
             last_update_block_hash: stacks_block_hash,
             last_update_height: stacks_block_height,
+            created_at: Some(created_at),
             ..Default::default()
         };
         // Validate withdrawal entry.
@@ -185,7 +207,7 @@ pub async fn create_withdrawal(
         Ok(with_status(json(&response), StatusCode::CREATED))
     }
     // Handle and respond.
-    handler(context, body)
+    handler(context, api_key, body)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -206,7 +228,7 @@ pub async fn create_withdrawal(
     ),
     security(("ApiGatewayKey" = []))
 )]
-#[instrument(skip(context))]
+#[instrument(skip(context, api_key))]
 pub async fn update_withdrawals(
     context: EmilyContext,
     api_key: String,
@@ -218,6 +240,7 @@ pub async fn update_withdrawals(
         api_key: String,
         body: UpdateWithdrawalsRequestBody,
     ) -> Result<impl warp::reply::Reply, Error> {
+        auth::authorize(&context, &api_key, ApiKeyScope::SignerUpdate).await?;
         // Get the api state and error if the api state is claimed by a reorg.
         //
         // Note: This may not be necessary due to the implied order of events
@@ -1,8 +1,12 @@
 //! This module contains the route configurations and request handlers for the Emily API.
 
+/// Api key authorization.
+pub mod auth;
 /// Request handlers.
 pub mod handlers;
 /// Request and response data structures.
 pub mod models;
+/// Rate limiting.
+pub mod rate_limit;
 /// Route definitions.
 pub mod routes;
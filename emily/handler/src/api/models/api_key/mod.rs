@@ -0,0 +1,55 @@
+//! Structures for api key api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+/// Requests.
+pub mod requests;
+/// Responses.
+pub mod responses;
+
+/// What an api key is allowed to do.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Update the status of deposits and withdrawals and create withdrawals.
+    SignerUpdate,
+    /// Add chainstates to the API.
+    ChainstateWrite,
+    /// Set the global and per account limits.
+    LimitsAdmin,
+    /// Read from the API, including managing status webhooks. Every key has
+    /// this scope.
+    ReadOnly,
+}
+
+/// An api key, without the key itself.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    /// The id of the api key. This is also the prefix of the key.
+    pub id: String,
+    /// Description of who the api key is for.
+    pub description: String,
+    /// What the api key is allowed to do.
+    pub scopes: Vec<ApiKeyScope>,
+    /// The unix timestamp in seconds of when the api key was created.
+    pub created_at: u64,
+    /// Whether the api key has been revoked.
+    pub revoked: bool,
+}
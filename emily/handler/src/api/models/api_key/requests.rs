@@ -0,0 +1,16 @@
+//! Request structures for api key api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::models::api_key::ApiKeyScope;
+
+/// Request structure for the create api key request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequestBody {
+    /// Description of who the api key is for.
+    pub description: String,
+    /// What the api key is allowed to do.
+    pub scopes: Vec<ApiKeyScope>,
+}
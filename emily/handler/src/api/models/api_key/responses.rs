@@ -0,0 +1,25 @@
+//! Response structures for api key api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+use crate::api::models::api_key::ApiKey;
+
+/// Response to create api key request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyResponse {
+    /// The created api key.
+    pub api_key: ApiKey,
+    /// The key to send in the `x-api-key` header. Only its hash is stored,
+    /// so it can't be retrieved again.
+    pub key: String,
+}
+
+/// Response to get api keys request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetApiKeysResponse {
+    /// Api keys, including the revoked ones.
+    pub api_keys: Vec<ApiKey>,
+}
@@ -1,8 +1,10 @@
 //! Requests module that contains all request types for different API calls.
 
+use serde::de::IntoDeserializer as _;
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
 
-use serde::{Deserialize, Serialize};
+use super::Status;
 
 /// Generic paginated query representation.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
@@ -15,3 +17,108 @@ pub struct PaginatedQuery<T> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<u16>,
 }
+
+/// Query structure common for all paginated queries.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicPaginationQuery {
+    /// Next token for the search.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+    /// Maximum number of results to show.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u16>,
+}
+
+/// Filters for searching deposits or withdrawals. Every filter that is set
+/// must match for an operation to be returned.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSearchQuery {
+    /// Operation statuses, given as a comma separated list.
+    #[serde(default, deserialize_with = "deserialize_status_list")]
+    pub status: Option<Vec<Status>>,
+    /// The recipient of the operation.
+    pub recipient: Option<String>,
+    /// The sender of the operation.
+    pub sender: Option<String>,
+    /// Minimum last update height, inclusive.
+    pub min_height: Option<u64>,
+    /// Maximum last update height, inclusive.
+    pub max_height: Option<u64>,
+    /// Minimum unix time in seconds at which the operation was received,
+    /// inclusive.
+    pub min_timestamp: Option<u64>,
+    /// Maximum unix time in seconds at which the operation was received,
+    /// inclusive.
+    pub max_timestamp: Option<u64>,
+}
+
+impl OperationSearchQuery {
+    /// The statuses to search, in the order they were given and without
+    /// duplicates.
+    pub fn statuses(&self) -> Vec<Status> {
+        let mut statuses: Vec<Status> = Vec::new();
+        for status in self.status.iter().flatten() {
+            if !statuses.contains(status) {
+                statuses.push(status.clone());
+            }
+        }
+        statuses
+    }
+
+    /// Whether an operation with the given attributes passes every filter.
+    /// Operations received before their creation time was recorded never
+    /// match a timestamp filter.
+    pub fn matches(
+        &self,
+        status: &Status,
+        recipient: &str,
+        sender: Option<&str>,
+        last_update_height: u64,
+        created_at: Option<u64>,
+    ) -> bool {
+        self.status
+            .as_ref()
+            .map_or(true, |statuses| statuses.contains(status))
+            && self
+                .recipient
+                .as_deref()
+                .map_or(true, |wanted| wanted == recipient)
+            && self
+                .sender
+                .as_deref()
+                .map_or(true, |wanted| Some(wanted) == sender)
+            && self
+                .min_height
+                .map_or(true, |min| last_update_height >= min)
+            && self
+                .max_height
+                .map_or(true, |max| last_update_height <= max)
+            && self
+                .min_timestamp
+                .map_or(true, |min| created_at.is_some_and(|at| at >= min))
+            && self
+                .max_timestamp
+                .map_or(true, |max| created_at.is_some_and(|at| at <= max))
+    }
+}
+
+/// Deserializes a comma separated list of statuses, which is how arrays are
+/// sent in query strings. An empty list is the same as no list.
+pub(crate) fn deserialize_status_list<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<Status>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    raw.split(',')
+        .map(str::trim)
+        .filter(|status| !status.is_empty())
+        .map(|status| Status::deserialize(status.into_deserializer()))
+        .collect::<Result<Vec<_>, D::Error>>()
+        .map(|statuses| (!statuses.is_empty()).then_some(statuses))
+}
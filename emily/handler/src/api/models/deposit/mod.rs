@@ -33,6 +33,11 @@ pub struct Deposit {
     pub bitcoin_tx_output_index: u32,
     /// Stacks address to received the deposited sBTC.
     pub recipient: String,
+    /// Hex encoded script pubkey of the output spent by the first input of the
+    /// deposit transaction. Only known for deposits that were verified against
+    /// their bitcoin transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being deposited in satoshis.
     pub amount: u64,
     /// The most recent Stacks block height the API was aware of when the deposit was last
@@ -56,6 +61,8 @@ pub struct Deposit {
     /// Details about the on chain artifacts that fulfilled the deposit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// Whether the deposit was checked against its bitcoin transaction.
+    pub verification: DepositVerification,
 }
 
 /// Deposit parameters.
@@ -82,6 +89,36 @@ pub struct DepositParameters {
     pub lock_time: u32,
 }
 
+/// The outcome of checking a deposit against its bitcoin transaction when
+/// it was created.
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    ToSchema,
+    ToResponse,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DepositVerification {
+    /// Emily isn't configured to verify deposits.
+    #[default]
+    Unverified,
+    /// The bitcoin transaction was found and the deposit matches one of its
+    /// outputs.
+    Verified,
+    /// The bitcoin transaction couldn't be found yet, so the deposit hasn't
+    /// been checked against it.
+    Unconfirmed,
+}
+
 /// Reduced version of the Deposit data.
 #[derive(
     Clone,
@@ -105,6 +142,11 @@ pub struct DepositInfo {
     pub bitcoin_tx_output_index: u32,
     /// Stacks address to received the deposited sBTC.
     pub recipient: String,
+    /// Hex encoded script pubkey of the output spent by the first input of the
+    /// deposit transaction. Only known for deposits that were verified against
+    /// their bitcoin transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being deposited in satoshis.
     pub amount: u64,
     /// The most recent Stacks block height the API was aware of when the deposit was last
@@ -130,6 +172,7 @@ impl From<Deposit> for DepositInfo {
             bitcoin_txid: deposit.bitcoin_txid,
             bitcoin_tx_output_index: deposit.bitcoin_tx_output_index,
             recipient: deposit.recipient,
+            sender: deposit.sender,
             amount: deposit.amount,
             last_update_height: deposit.last_update_height,
             last_update_block_hash: deposit.last_update_block_hash,
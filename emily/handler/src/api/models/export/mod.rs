@@ -0,0 +1,152 @@
+//! Structures for export api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::models::common::{Fulfillment, Status};
+use crate::api::models::deposit::Deposit;
+use crate::api::models::withdrawal::Withdrawal;
+
+/// Requests.
+pub mod requests;
+
+/// The format that an export is written in.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON record per line.
+    #[default]
+    Jsonl,
+    /// Comma separated values with a header row, followed by the summary
+    /// as `metric,value` rows after a blank line.
+    Csv,
+}
+
+impl ExportFormat {
+    /// The content type of an export in this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv",
+        }
+    }
+
+    /// The file extension of an export in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// An event in the history of an exported deposit or withdrawal.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEvent {
+    /// The status the operation moved to.
+    pub status: Status,
+    /// The status message of the event.
+    pub message: String,
+    /// Stacks block height at the time of the event.
+    pub stacks_block_height: u64,
+    /// Stacks block hash at the time of the event.
+    pub stacks_block_hash: String,
+    /// The fulfillment of the operation, for events that confirmed it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fulfillment: Option<Fulfillment>,
+}
+
+/// A deposit along with its full history.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedDeposit {
+    /// The deposit.
+    #[serde(flatten)]
+    pub deposit: Deposit,
+    /// Every event in the history of the deposit, oldest first.
+    pub history: Vec<ExportEvent>,
+}
+
+/// A withdrawal along with its full history.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedWithdrawal {
+    /// The withdrawal.
+    #[serde(flatten)]
+    pub withdrawal: Withdrawal,
+    /// Every event in the history of the withdrawal, oldest first.
+    pub history: Vec<ExportEvent>,
+}
+
+/// Totals over the operations in an export, used to reconcile the sBTC
+/// supply against the BTC held by the signers.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    /// The number of deposits in the export.
+    pub deposit_count: u64,
+    /// The number of withdrawals in the export.
+    pub withdrawal_count: u64,
+    /// The number of confirmed deposits in the export.
+    pub confirmed_deposit_count: u64,
+    /// The total amount in satoshis of the confirmed deposits.
+    pub confirmed_deposit_amount: u64,
+    /// The total bitcoin fee in satoshis taken from the confirmed deposits.
+    pub confirmed_deposit_fees: u64,
+    /// The number of confirmed withdrawals in the export.
+    pub confirmed_withdrawal_count: u64,
+    /// The total amount in satoshis of the confirmed withdrawals.
+    pub confirmed_withdrawal_amount: u64,
+    /// The total bitcoin fee in satoshis paid for the confirmed withdrawals.
+    pub confirmed_withdrawal_fees: u64,
+    /// The net change in satoshis of the BTC held by the signers, and so of
+    /// the sBTC supply, over the confirmed operations. This is the confirmed
+    /// deposit amount minus the fees taken from the deposits, less the
+    /// confirmed withdrawal amount and the fees paid for the withdrawals.
+    pub net_confirmed_amount: i64,
+}
+
+impl ExportSummary {
+    /// Count a deposit towards the summary.
+    pub fn add_deposit(&mut self, deposit: &Deposit) {
+        self.deposit_count += 1;
+        if deposit.status == Status::Confirmed {
+            self.confirmed_deposit_count += 1;
+            self.confirmed_deposit_amount += deposit.amount;
+            self.confirmed_deposit_fees += deposit.fulfillment.as_ref().map_or(0, |f| f.btc_fee);
+        }
+        self.update_net_amount();
+    }
+
+    /// Count a withdrawal towards the summary.
+    pub fn add_withdrawal(&mut self, withdrawal: &Withdrawal) {
+        self.withdrawal_count += 1;
+        if withdrawal.status == Status::Confirmed {
+            self.confirmed_withdrawal_count += 1;
+            self.confirmed_withdrawal_amount += withdrawal.amount;
+            self.confirmed_withdrawal_fees +=
+                withdrawal.fulfillment.as_ref().map_or(0, |f| f.btc_fee);
+        }
+        self.update_net_amount();
+    }
+
+    fn update_net_amount(&mut self) {
+        let deposited = self.confirmed_deposit_amount as i64 - self.confirmed_deposit_fees as i64;
+        let withdrawn =
+            self.confirmed_withdrawal_amount as i64 + self.confirmed_withdrawal_fees as i64;
+        self.net_confirmed_amount = deposited - withdrawn;
+    }
+}
+
+/// A single record of a JSON lines export.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ExportRecord {
+    /// A deposit.
+    Deposit(ExportedDeposit),
+    /// A withdrawal.
+    Withdrawal(ExportedWithdrawal),
+    /// The summary, which is always the last record of a complete export.
+    Summary(ExportSummary),
+}
@@ -0,0 +1,38 @@
+//! Request structures for export api calls.
+
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::api::models::common::requests::deserialize_status_list;
+use crate::api::models::common::Status;
+use crate::api::models::export::ExportFormat;
+use crate::common::error::Error;
+
+/// Query structure for exporting deposits and withdrawals.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQuery {
+    /// The format of the export.
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Operation statuses to export, given as a comma separated list. Every
+    /// status is exported when this isn't set.
+    #[serde(default, deserialize_with = "deserialize_status_list")]
+    pub status: Option<Vec<Status>>,
+    /// Minimum last update height, inclusive.
+    pub min_height: Option<u64>,
+    /// Maximum last update height, inclusive.
+    pub max_height: Option<u64>,
+}
+
+impl ExportQuery {
+    /// Check that the block range isn't empty.
+    pub fn validate(&self) -> Result<(), Error> {
+        match (self.min_height, self.max_height) {
+            (Some(min), Some(max)) if min > max => Err(Error::BadRequest(format!(
+                "minimum height {min} is above the maximum height {max}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
@@ -4,6 +4,18 @@ use utoipa::ToSchema;
 /// Struct that represents the current status of the API.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub struct HealthData {
-    /// Whether the API is okay.
+    /// Whether the API is okay, which is whenever its database can be
+    /// reached.
     pub is_okay: bool,
+    /// Whether the database could be reached.
+    pub database_reachable: bool,
+    /// Whether the API is reorganizing around a new chain tip.
+    pub is_reorganizing: bool,
+    /// Height of the stacks chain tip that the API is following. Missing
+    /// when the database can't be reached.
+    pub chain_tip_height: Option<u64>,
+    /// Seconds since the chain tip last changed. Missing when the database
+    /// can't be reached or the chain tip hasn't changed since the API began
+    /// recording when it does.
+    pub chain_tip_age_seconds: Option<u64>,
 }
@@ -17,6 +17,21 @@ pub struct Limits {
     pub per_deposit_cap: Option<u64>,
     /// Per withdrawal cap. If none then there is no cap.
     pub per_withdrawal_cap: Option<u64>,
+    /// Length in seconds of the rolling window that the rolling caps apply
+    /// to. If none then the window is a day long.
+    pub rolling_window_seconds: Option<u64>,
+    /// Cap on the BTC pegged in within the rolling window. If none then there
+    /// is no cap.
+    pub rolling_deposit_cap: Option<u64>,
+    /// Cap on the sBTC pegged out within the rolling window. If none then
+    /// there is no cap.
+    pub rolling_withdrawal_cap: Option<u64>,
+    /// BTC pegged in within the current rolling window. Only set when there is
+    /// a rolling deposit cap, and ignored when setting limits.
+    pub rolling_deposit_volume: Option<u64>,
+    /// sBTC pegged out within the current rolling window. Only set when there
+    /// is a rolling withdrawal cap, and ignored when setting limits.
+    pub rolling_withdrawal_volume: Option<u64>,
     /// Represents the individual limits for requests coming from different accounts.
     pub account_caps: HashMap<String, AccountLimits>,
 }
@@ -1,14 +1,20 @@
 //! This module contains the models used within Emily API endpoint interactions.
 
+/// Api structures for api keys.
+pub mod api_key;
 /// Api structures for chainstate.
 pub mod chainstate;
 /// Common API structures.
 pub mod common;
 /// Api structures for deposits.
 pub mod deposit;
+/// Api structures for exports.
+pub mod export;
 /// Api structures for health endpoint.
 pub mod health;
 /// Api structures for limits.
 pub mod limits;
+/// Api structures for status subscriptions.
+pub mod subscription;
 /// Api structures for withdrawals.
 pub mod withdrawal;
@@ -0,0 +1,92 @@
+//! Structures for subscription api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+use crate::api::models::common::Status;
+
+/// Requests.
+pub mod requests;
+/// Responses.
+pub mod responses;
+
+/// A change in the status of a deposit or withdrawal, sent to webhooks and
+/// server-sent event streams whenever an event is appended to the history
+/// of the operation.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusEvent {
+    /// Bitcoin transaction id of the deposit. Only set for deposits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_txid: Option<String>,
+    /// Output index on the bitcoin transaction of the deposit. Only set for
+    /// deposits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_tx_output_index: Option<u32>,
+    /// The id of the Stacks withdrawal request. Only set for withdrawals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+    /// The recipient of the operation; a Stacks address for deposits and a
+    /// bitcoin address for withdrawals.
+    pub recipient: String,
+    /// The new status of the operation.
+    pub status: Status,
+    /// The status message of the operation.
+    pub status_message: String,
+    /// Stacks block height at the time of the status change.
+    pub stacks_block_height: u64,
+    /// Stacks block hash at the time of the status change.
+    pub stacks_block_hash: String,
+}
+
+/// Which status events a subscriber receives. Every field that is set has
+/// to match the event for it to be sent.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusEventFilter {
+    /// Only send events for deposits in this bitcoin transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_txid: Option<String>,
+    /// Only send events for operations with this recipient.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    /// Only send events that change the status to this status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+}
+
+impl StatusEventFilter {
+    /// Returns true if the event passes the filter.
+    pub fn matches(&self, event: &StatusEvent) -> bool {
+        let txid_matches = self
+            .bitcoin_txid
+            .as_ref()
+            .map_or(true, |txid| event.bitcoin_txid.as_ref() == Some(txid));
+        let recipient_matches = self
+            .recipient
+            .as_ref()
+            .map_or(true, |recipient| &event.recipient == recipient);
+        let status_matches = self
+            .status
+            .as_ref()
+            .map_or(true, |status| &event.status == status);
+        txid_matches && recipient_matches && status_matches
+    }
+}
+
+/// A registered webhook. The secret used to sign the payloads is never
+/// returned.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// The id of the webhook.
+    pub id: String,
+    /// The URL that status events are posted to.
+    pub url: String,
+    /// Which status events are posted to the webhook.
+    pub filter: StatusEventFilter,
+}
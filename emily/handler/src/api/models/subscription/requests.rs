@@ -0,0 +1,21 @@
+//! Request structures for subscription api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::models::subscription::StatusEventFilter;
+
+/// Request structure for the create webhook request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequestBody {
+    /// The URL that status events are posted to.
+    pub url: String,
+    /// The secret used to sign the payloads. Each payload is signed with
+    /// HMAC-SHA256 and the hex encoded signature is sent in the
+    /// `x-emily-signature` header.
+    pub secret: String,
+    /// Which status events are posted to the webhook.
+    #[serde(default)]
+    pub filter: StatusEventFilter,
+}
@@ -0,0 +1,14 @@
+//! Response structures for subscription api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+use crate::api::models::subscription::Webhook;
+
+/// Response to get webhooks request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetWebhooksResponse {
+    /// Registered webhooks.
+    pub webhooks: Vec<Webhook>,
+}
@@ -35,6 +35,9 @@ pub struct Withdrawal {
     pub stacks_block_height: u64,
     /// The recipient Bitcoin address.
     pub recipient: String,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being withdrawn in satoshis.
     pub amount: u64,
     /// The most recent Stacks block height the API was aware of when the withdrawal was last
@@ -103,6 +106,9 @@ pub struct WithdrawalInfo {
     pub stacks_block_height: u64,
     /// The recipient Bitcoin address.
     pub recipient: String,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being withdrawn in satoshis.
     pub amount: u64,
     /// The most recent Stacks block height the API was aware of when the withdrawal was last
@@ -125,6 +131,7 @@ impl From<Withdrawal> for WithdrawalInfo {
             stacks_block_hash: withdrawal.stacks_block_hash,
             stacks_block_height: withdrawal.stacks_block_height,
             recipient: withdrawal.recipient,
+            sender: withdrawal.sender,
             amount: withdrawal.amount,
             last_update_height: withdrawal.last_update_height,
             last_update_block_hash: withdrawal.last_update_block_hash,
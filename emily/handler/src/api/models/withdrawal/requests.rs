@@ -6,20 +6,6 @@ use utoipa::ToSchema;
 use crate::api::models::common::{Fulfillment, Status};
 use crate::api::models::withdrawal::WithdrawalParameters;
 
-/// Query structure for the get withdrawals request.
-#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetWithdrawalsQuery {
-    /// Operation status.
-    pub status: Status,
-    /// Next token for the search.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub next_token: Option<String>,
-    /// Maximum number of results to show.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub page_size: Option<u16>,
-}
-
 /// Request structure for the create withdrawal request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -32,6 +18,9 @@ pub struct CreateWithdrawalRequestBody {
     pub stacks_block_height: u64,
     /// The recipient Bitcoin address.
     pub recipient: String,
+    /// The Stacks principal that initiated the withdrawal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being withdrawn in satoshis.
     pub amount: u64,
     /// Withdrawal request parameters.
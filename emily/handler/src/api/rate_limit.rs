@@ -0,0 +1,255 @@
+//! Token bucket rate limiting.
+//!
+//! Each caller gets a bucket that holds up to `burst` tokens and refills at
+//! `per_second` tokens a second. A request takes one token and is refused
+//! with a 429 when the bucket is empty. Every request is limited by the IP
+//! it comes from, and requests with an api key are also limited by the key
+//! once it has been authorized, so made-up keys can't be used to get fresh
+//! buckets.
+//!
+//! The buckets live in the memory of the process, so every lambda instance
+//! keeps its own. IPs and keys are tracked separately, each up to a fixed
+//! number of callers after which the least recently seen caller is dropped.
+
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use warp::Filter;
+
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+
+/// The number of callers of each kind that are tracked before the least
+/// recently seen one is dropped.
+const MAX_TRACKED_BUCKETS: NonZeroUsize = match NonZeroUsize::new(100_000) {
+    Some(max) => max,
+    None => panic!("the number of tracked buckets must be non-zero"),
+};
+
+/// A rate limit quota.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitQuota {
+    /// The number of requests that can be made at once.
+    pub burst: u32,
+    /// The number of requests a second that can be sustained.
+    pub per_second: f64,
+}
+
+/// Rate limit settings. Callers are not limited when a quota isn't set.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitSettings {
+    /// Quota for each IP address.
+    pub per_ip: Option<RateLimitQuota>,
+    /// Quota for each api key.
+    pub per_key: Option<RateLimitQuota>,
+}
+
+/// A token bucket.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens left in the bucket.
+    tokens: f64,
+    /// When the tokens were last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Refill the bucket for the time passed since the last refill.
+    fn refill(&mut self, quota: &RateLimitQuota, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * quota.per_second).min(quota.burst as f64);
+        self.last_refill = now;
+    }
+}
+
+/// The token buckets of one kind of caller.
+type Buckets<K> = Arc<Mutex<LruCache<K, TokenBucket>>>;
+
+/// Tracks the token buckets of every caller.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    /// Buckets by IP address.
+    ips: Buckets<IpAddr>,
+    /// Buckets by api key id.
+    keys: Buckets<String>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            ips: Arc::new(Mutex::new(LruCache::new(MAX_TRACKED_BUCKETS))),
+            keys: Arc::new(Mutex::new(LruCache::new(MAX_TRACKED_BUCKETS))),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Take a token from the bucket of the IP, failing with
+    /// [`Error::TooManyRequests`] if the bucket is empty.
+    pub fn check_ip(&self, ip: IpAddr, quota: &RateLimitQuota) -> Result<(), Error> {
+        take_token(&self.ips, ip, quota, Instant::now())
+    }
+
+    /// Take a token from the bucket of an authorized api key, failing with
+    /// [`Error::TooManyRequests`] if the bucket is empty.
+    pub fn check_key(&self, key_id: &str, quota: &RateLimitQuota) -> Result<(), Error> {
+        take_token(&self.keys, key_id.to_string(), quota, Instant::now())
+    }
+}
+
+/// Take a token from the bucket of the caller as of the given time.
+fn take_token<K>(
+    buckets: &Buckets<K>,
+    caller: K,
+    quota: &RateLimitQuota,
+    now: Instant,
+) -> Result<(), Error>
+where
+    K: std::hash::Hash + Eq,
+{
+    let mut buckets = buckets
+        .lock()
+        .map_err(|_| Error::Debug("Rate limiter lock poisoned".to_string()))?;
+
+    let bucket = buckets.get_or_insert_mut(caller, || TokenBucket {
+        tokens: quota.burst as f64,
+        last_refill: now,
+    });
+    bucket.refill(quota, now);
+    if bucket.tokens < 1.0 {
+        return Err(Error::TooManyRequests);
+    }
+    bucket.tokens -= 1.0;
+    Ok(())
+}
+
+/// Filter that rejects requests from IPs that are over their quota.
+pub fn rate_limit(
+    context: EmilyContext,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::addr::remote())
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and_then(check_ip_rate_limit)
+        .untuple_one()
+}
+
+/// Take a token from the bucket of the IP of the request.
+async fn check_ip_rate_limit(
+    context: EmilyContext,
+    remote: Option<SocketAddr>,
+    forwarded_for: Option<String>,
+) -> Result<(), warp::Rejection> {
+    let Some(quota) = &context.settings.rate_limits.per_ip else {
+        return Ok(());
+    };
+    if let Some(ip) = client_ip(remote, forwarded_for.as_deref()) {
+        context
+            .rate_limiter
+            .check_ip(ip, quota)
+            .map_err(warp::reject::custom)?;
+    }
+    Ok(())
+}
+
+/// The IP of the client making the request.
+///
+/// The remote address is only missing when running behind the API gateway.
+/// The gateway appends the address it received the request from to the
+/// forwarded header, so that is the last entry; everything before it was
+/// sent by the client and can't be trusted.
+fn client_ip(remote: Option<SocketAddr>, forwarded_for: Option<&str>) -> Option<IpAddr> {
+    remote.map(|addr| addr.ip()).or_else(|| {
+        forwarded_for?
+            .rsplit(',')
+            .next()
+            .and_then(|ip| ip.trim().parse().ok())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    const QUOTA: RateLimitQuota = RateLimitQuota { burst: 2, per_second: 1.0 };
+
+    fn buckets<K: std::hash::Hash + Eq>(capacity: usize) -> Buckets<K> {
+        let capacity = NonZeroUsize::new(capacity).unwrap();
+        Arc::new(Mutex::new(LruCache::new(capacity)))
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let buckets = buckets(10);
+        let start = Instant::now();
+        let caller = || "key-id".to_string();
+
+        assert!(take_token(&buckets, caller(), &QUOTA, start).is_ok());
+        assert!(take_token(&buckets, caller(), &QUOTA, start).is_ok());
+        assert!(matches!(
+            take_token(&buckets, caller(), &QUOTA, start),
+            Err(Error::TooManyRequests)
+        ));
+
+        // One token comes back after a second.
+        let later = start + Duration::from_secs(1);
+        assert!(take_token(&buckets, caller(), &QUOTA, later).is_ok());
+        assert!(take_token(&buckets, caller(), &QUOTA, later).is_err());
+    }
+
+    #[test]
+    fn callers_have_separate_buckets() {
+        let buckets = buckets(10);
+        let now = Instant::now();
+        for _ in 0..QUOTA.burst {
+            take_token(&buckets, "a", &QUOTA, now).unwrap();
+        }
+        assert!(take_token(&buckets, "a", &QUOTA, now).is_err());
+        assert!(take_token(&buckets, "b", &QUOTA, now).is_ok());
+    }
+
+    #[test]
+    fn least_recently_seen_callers_are_dropped() {
+        let buckets = buckets(2);
+        let now = Instant::now();
+        for _ in 0..QUOTA.burst {
+            take_token(&buckets, "a", &QUOTA, now).unwrap();
+        }
+        take_token(&buckets, "b", &QUOTA, now).unwrap();
+        assert!(take_token(&buckets, "a", &QUOTA, now).is_err());
+
+        // "b" is now the least recently seen caller, so it is the one that
+        // makes room for "c" and "a" keeps its empty bucket.
+        take_token(&buckets, "c", &QUOTA, now).unwrap();
+        assert!(take_token(&buckets, "a", &QUOTA, now).is_err());
+        assert_eq!(buckets.lock().unwrap().len(), 2);
+        assert!(!buckets.lock().unwrap().contains(&"b"));
+    }
+
+    #[test]
+    fn client_ip_is_the_remote_address_or_the_last_forwarded_hop() {
+        let remote: SocketAddr = "192.0.2.1:4000".parse().unwrap();
+        let forwarded = Some("203.0.113.7, 198.51.100.2");
+
+        assert_eq!(client_ip(Some(remote), forwarded), Some(remote.ip()));
+        assert_eq!(
+            client_ip(None, forwarded),
+            Some("198.51.100.2".parse().unwrap())
+        );
+        assert_eq!(
+            client_ip(None, Some("198.51.100.2")),
+            Some("198.51.100.2".parse().unwrap())
+        );
+        assert_eq!(client_ip(None, Some("not an ip")), None);
+        assert_eq!(client_ip(None, None), None);
+    }
+}
@@ -0,0 +1,53 @@
+//! Route definitions for the api key endpoints.
+
+use warp::Filter;
+
+use crate::context::EmilyContext;
+
+use super::handlers;
+
+/// Api key routes.
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    create_api_key(context.clone())
+        .or(get_api_keys(context.clone()))
+        .or(revoke_api_key(context))
+}
+
+/// Create api key endpoint.
+fn create_api_key(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("api-key"))
+        .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
+        .and(warp::body::json())
+        .then(handlers::api_key::create_api_key)
+}
+
+/// Get api keys endpoint.
+fn get_api_keys(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("api-key"))
+        .and(warp::get())
+        .and(warp::header::<String>("x-api-key"))
+        .then(handlers::api_key::get_api_keys)
+}
+
+/// Revoke api key endpoint.
+fn revoke_api_key(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("api-key" / String))
+        .and(warp::delete())
+        .and(warp::header::<String>("x-api-key"))
+        .then(handlers::api_key::revoke_api_key)
+}
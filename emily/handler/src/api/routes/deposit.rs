@@ -49,6 +49,7 @@ fn get_deposits(
         .and(warp::path!("deposit"))
         .and(warp::get())
         .and(warp::query())
+        .and(warp::query())
         .then(handlers::deposit::get_deposits)
 }
 
@@ -0,0 +1,37 @@
+//! Route definitions for the stacks node event observer endpoints.
+
+use warp::Filter;
+
+use crate::context::EmilyContext;
+
+use super::handlers;
+use super::handlers::event_observer::EVENT_OBSERVER_BODY_LIMIT;
+
+/// Event observer routes. They are served under the event observer secret,
+/// since a stacks node can only be given an endpoint for its observers.
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    new_block(context).or(new_attachments())
+}
+
+/// New block endpoint.
+fn new_block(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("event-observer" / String / "new_block"))
+        .and(warp::post())
+        .and(warp::body::content_length_limit(EVENT_OBSERVER_BODY_LIMIT))
+        .and(warp::body::bytes())
+        .then(handlers::event_observer::new_block)
+}
+
+/// New attachments endpoint. Attachments are only acknowledged, so the
+/// secret isn't checked.
+fn new_attachments() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path!("event-observer" / String / "attachments" / "new")
+        .and(warp::post())
+        .then(|_secret: String| handlers::event_observer::new_attachments())
+}
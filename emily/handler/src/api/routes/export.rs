@@ -0,0 +1,27 @@
+//! Route definitions for the export endpoint.
+
+use warp::Filter;
+
+use crate::context::EmilyContext;
+
+use super::handlers;
+
+/// Export routes.
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    export_operations(context)
+}
+
+/// Export deposits and withdrawals endpoint.
+fn export_operations(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("export"))
+        .and(warp::get())
+        .and(warp::header::<String>("x-api-key"))
+        .and(warp::query())
+        .then(handlers::export::export_operations)
+}
@@ -1,16 +1,23 @@
 //! Route definitions for the health endpoint.
 
 use super::handlers;
+use crate::context::EmilyContext;
 use warp::Filter;
 
 /// Health routes.
-pub fn routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    get_health()
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    get_health(context)
 }
 
 /// Get health endpoint.
-fn get_health() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::path("health")
+fn get_health(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path("health"))
         .and(warp::get())
         .then(handlers::health::get_health)
 }
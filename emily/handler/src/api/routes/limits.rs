@@ -35,6 +35,7 @@ fn set_limits(
         .map(move || context.clone())
         .and(warp::path!("limits"))
         .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::limits::set_limits)
 }
@@ -47,6 +48,7 @@ fn set_limits_for_account(
         .map(move || context.clone())
         .and(warp::path!("limits" / String))
         .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::limits::set_limits_for_account)
 }
@@ -3,17 +3,26 @@
 use crate::context::EmilyContext;
 
 use super::handlers;
+use super::rate_limit::rate_limit;
 use tracing::debug;
 use warp::Filter;
 
+/// Api key routes.
+mod api_key;
 /// Chainstate routes.
 mod chainstate;
 /// Deposit routes.
 mod deposit;
+/// Event observer routes.
+mod event_observer;
+/// Export routes.
+mod export;
 /// Health routes.
 mod health;
 /// Limit routes.
 mod limits;
+/// Subscription routes.
+mod subscription;
 /// Testing routes.
 #[cfg(feature = "testing")]
 mod testing;
@@ -40,13 +49,20 @@ where
 pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    health::routes()
-        .or(chainstate::routes(context.clone()))
-        .or(deposit::routes(context.clone()))
-        .or(withdrawal::routes(context.clone()))
-        .or(limits::routes(context.clone()))
-        .or(testing::routes(context))
-        .or(verbose_not_found_route())
+    rate_limit(context.clone())
+        .and(
+            health::routes(context.clone())
+                .or(chainstate::routes(context.clone()))
+                .or(deposit::routes(context.clone()))
+                .or(withdrawal::routes(context.clone()))
+                .or(export::routes(context.clone()))
+                .or(limits::routes(context.clone()))
+                .or(subscription::routes(context.clone()))
+                .or(api_key::routes(context.clone()))
+                .or(event_observer::routes(context.clone()))
+                .or(testing::routes(context))
+                .or(verbose_not_found_route()),
+        )
         // Convert reply to tuple to that more routes can be added to the returned filter.
         .map(|reply| (reply,))
         .map(log_response)
@@ -57,11 +73,18 @@ pub fn routes(
 pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    health::routes()
-        .or(chainstate::routes(context.clone()))
-        .or(deposit::routes(context.clone()))
-        .or(withdrawal::routes(context))
-        .or(limits::routes(context.clone()))
+    rate_limit(context.clone())
+        .and(
+            health::routes(context.clone())
+                .or(chainstate::routes(context.clone()))
+                .or(deposit::routes(context.clone()))
+                .or(withdrawal::routes(context.clone()))
+                .or(export::routes(context.clone()))
+                .or(limits::routes(context.clone()))
+                .or(subscription::routes(context.clone()))
+                .or(api_key::routes(context.clone()))
+                .or(event_observer::routes(context)),
+        )
         // Convert reply to tuple to that more routes can be added to the returned filter.
         .map(|reply| (reply,))
         .map(log_response)
@@ -0,0 +1,66 @@
+//! Route definitions for the subscription endpoints.
+
+use warp::Filter;
+
+use crate::context::EmilyContext;
+
+use super::handlers;
+
+/// Subscription routes.
+pub fn routes(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    create_webhook(context.clone())
+        .or(get_webhooks(context.clone()))
+        .or(delete_webhook(context.clone()))
+        .or(stream_status_events(context))
+}
+
+/// Create webhook endpoint.
+fn create_webhook(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscription" / "webhook"))
+        .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
+        .and(warp::body::json())
+        .then(handlers::subscription::create_webhook)
+}
+
+/// Get webhooks endpoint.
+fn get_webhooks(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscription" / "webhook"))
+        .and(warp::get())
+        .and(warp::header::<String>("x-api-key"))
+        .then(handlers::subscription::get_webhooks)
+}
+
+/// Delete webhook endpoint.
+fn delete_webhook(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscription" / "webhook" / String))
+        .and(warp::delete())
+        .and(warp::header::<String>("x-api-key"))
+        .then(handlers::subscription::delete_webhook)
+}
+
+/// Server-sent event stream of status events.
+fn stream_status_events(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("subscription" / "events"))
+        .and(warp::get())
+        .and(warp::query())
+        .and_then(handlers::subscription::stream_status_events)
+}
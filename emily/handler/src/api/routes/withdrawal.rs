@@ -35,6 +35,7 @@ fn get_withdrawals(
         .and(warp::path("withdrawal"))
         .and(warp::get())
         .and(warp::query())
+        .and(warp::query())
         .then(handlers::withdrawal::get_withdrawals)
 }
 
@@ -46,6 +47,7 @@ fn create_withdrawal(
         .map(move || context.clone())
         .and(warp::path("withdrawal"))
         .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
         .and(warp::body::json())
         .then(handlers::withdrawal::create_withdrawal)
 }
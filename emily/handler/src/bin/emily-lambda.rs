@@ -5,6 +5,7 @@ use tracing::info;
 
 use emily_handler::api;
 use emily_handler::logging;
+use emily_handler::metrics;
 use warp::Filter;
 
 #[tokio::main]
@@ -12,6 +13,8 @@ async fn main() {
     // Setup logging.
     // TODO(TBD): Make the logging configurable.
     logging::setup_logging("info,emily_handler=debug", false);
+    // Metrics are printed to the logs, where CloudWatch picks them up.
+    metrics::setup_embedded_metrics();
 
     // Setup context.
     // TODO(389 + 358): Handle config pickup in a way that will only fail for the relevant call.
@@ -23,7 +26,7 @@ async fn main() {
     // Create CORS configuration
     let cors = warp::cors()
         .allow_any_origin()
-        .allow_methods(vec!["GET", "POST", "OPTIONS"])
+        .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS"])
         .allow_headers(vec!["content-type", "x-api-key"])
         .build();
 
@@ -31,6 +34,7 @@ async fn main() {
     let service_filter = api::routes::routes_with_stage_prefix(context)
         .recover(api::handlers::handle_rejection)
         .with(warp::log("api"))
+        .with(metrics::log_requests())
         .with(cors);
 
     // Create warp service.
@@ -56,6 +56,14 @@ pub enum Error {
     #[error("Unauthorized access - check your API key")]
     Unauthorized,
 
+    /// The API key is valid but isn't allowed to make the request.
+    #[error("Forbidden - the API key doesn't have the required scope")]
+    Forbidden,
+
+    /// The caller has used up its rate limit quota.
+    #[error("Too many requests")]
+    TooManyRequests,
+
     /// This may be because you either requested a nonexistent endpoint
     /// or referenced a user that does not exist
     #[error("Resource not found")]
@@ -110,6 +118,32 @@ pub enum Error {
     /// Bad request
     #[error("Bad request {0}")]
     BadRequest(String),
+
+    /// A deposit failed verification against its bitcoin transaction.
+    #[error("Deposit verification failed: {0}")]
+    DepositVerification(#[from] DepositVerificationError),
+}
+
+/// Reasons a deposit can fail verification against the bitcoin blockchain.
+#[derive(thiserror::Error, Debug)]
+pub enum DepositVerificationError {
+    /// The bitcoin txid in the request isn't a valid txid.
+    #[error("invalid bitcoin txid {0}")]
+    InvalidTxid(String),
+
+    /// The transaction was found but the deposit doesn't match it.
+    #[error("the deposit doesn't match its bitcoin transaction: {0}")]
+    InvalidTransaction(#[source] sbtc::error::Error),
+
+    /// The amount in the transaction differs from the amount already
+    /// recorded for the deposit.
+    #[error("the deposit amount {actual} doesn't match the recorded amount {expected}")]
+    AmountMismatch {
+        /// The amount already recorded for the deposit.
+        expected: u64,
+        /// The amount in the bitcoin transaction.
+        actual: u64,
+    },
 }
 
 /// Error implementation.
@@ -122,6 +156,8 @@ impl Error {
             Error::Serialization(_) => StatusCode::BAD_REQUEST,
             Error::InvalidApiResponse => StatusCode::BAD_REQUEST,
             Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::Forbidden => StatusCode::FORBIDDEN,
+            Error::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
             Error::NotFound => StatusCode::NOT_FOUND,
             Error::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Error::NotImplemented => StatusCode::NOT_IMPLEMENTED,
@@ -135,6 +171,7 @@ impl Error {
             Error::Reorganzing(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::BadRequest(_) => StatusCode::BAD_REQUEST,
             Error::VersionConflict => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::DepositVerification(_) => StatusCode::BAD_REQUEST,
         }
     }
     /// Converts the error into a warp response.
@@ -258,6 +295,16 @@ impl From<serde_dynamo::Error> for Error {
         Error::Debug(format!("serde_dynamo::Error - {err:?}"))
     }
 }
+impl From<sqlx::Error> for Error {
+    fn from(err: sqlx::Error) -> Self {
+        Error::Debug(format!("sqlx::Error - {err:?}"))
+    }
+}
+impl From<sqlx::migrate::MigrateError> for Error {
+    fn from(err: sqlx::migrate::MigrateError) -> Self {
+        Error::Debug(format!("sqlx::migrate::MigrateError - {err:?}"))
+    }
+}
 impl From<HexToBytesError> for Error {
     fn from(err: HexToBytesError) -> Self {
         Error::Debug(format!("HexToBytesError - {err:?}"))
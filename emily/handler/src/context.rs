@@ -5,6 +5,7 @@
 //! and the Signer that grabs the configuration values from a local default
 //! toml and potentially overwrites the fields with environment values.
 
+use std::collections::HashMap;
 use std::env;
 use std::fmt;
 
@@ -13,8 +14,12 @@ use aws_sdk_dynamodb::Client;
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::api::models::limits::AccountLimits;
+use crate::api::models::limits::Limits;
+use crate::api::rate_limit::{RateLimitQuota, RateLimitSettings, RateLimiter};
 use crate::common::error::Error;
+use crate::database::storage::{DynamoDbStore, PgStore, Store};
+use crate::subscriptions::Notifier;
+use crate::verification::{BitcoinEndpoint, DepositVerifier};
 
 /// Emily lambda settings.
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
@@ -29,10 +34,42 @@ pub struct Settings {
     pub chainstate_table_name: String,
     /// Limit table name.
     pub limit_table_name: String,
+    /// Webhook table name.
+    pub webhook_table_name: String,
+    /// Api key table name.
+    pub api_key_table_name: String,
     /// The default global limits for the system.
-    pub default_limits: AccountLimits,
+    pub default_limits: Limits,
     /// The API key for the Bitcoin Layer 2 API.
     pub trusted_reorg_api_key: String,
+    /// The API key that can create and revoke scoped api keys. The api key
+    /// routes are disabled when this isn't set.
+    #[serde(skip_serializing)]
+    pub admin_api_key: Option<String>,
+    /// Whether api keys that aren't stored in the database are let through
+    /// because the API gateway has already checked them. Only the lambda
+    /// behind the API gateway sets this.
+    pub trust_api_gateway_keys: bool,
+    /// Rate limits for incoming requests.
+    pub rate_limits: RateLimitSettings,
+    /// Where to fetch bitcoin transactions from when verifying new
+    /// deposits. Deposits are stored unverified when this isn't set.
+    pub deposit_verification_endpoint: Option<BitcoinEndpoint>,
+    /// Whether the API is tracking deposits on bitcoin mainnet.
+    pub is_mainnet: bool,
+    /// The contract identifier of the sbtc-registry contract, such as
+    /// `SM3VDXK3WZZSA84XXFKAFAF15NNZX32CTSG82JFQ4.sbtc-registry`. Print
+    /// events from any other contract are ignored, and all of them are when
+    /// this isn't set.
+    pub registry_contract_id: Option<String>,
+    /// The secret in the path of the event observer endpoints, which stacks
+    /// nodes are given as part of their event observer endpoint since they
+    /// can't send headers. The endpoints are disabled when this isn't set.
+    #[serde(skip_serializing)]
+    pub event_observer_secret: Option<String>,
+    /// Whether webhooks can be registered for loopback, private and
+    /// link-local addresses. Only meant for local development.
+    pub allow_private_webhook_urls: bool,
 }
 
 /// Emily Context
@@ -40,9 +77,19 @@ pub struct Settings {
 pub struct EmilyContext {
     /// Lambda settings.
     pub settings: Settings,
-    /// DynamoDB Client.
+    /// Storage backend.
+    #[serde(skip_serializing)]
+    pub storage: Store,
+    /// Publishes status changes to subscribers.
+    #[serde(skip_serializing)]
+    pub notifier: Notifier,
+    /// Rate limit buckets of the callers.
+    #[serde(skip_serializing)]
+    pub rate_limiter: RateLimiter,
+    /// HTTP client shared by every outgoing request, so that connections
+    /// are pooled across requests.
     #[serde(skip_serializing)]
-    pub dynamodb_client: Client,
+    pub http_client: reqwest::Client,
 }
 
 /// Implement debug print for the context struct.
@@ -69,7 +116,9 @@ impl Settings {
             withdrawal_table_name: env::var("WITHDRAWAL_TABLE_NAME")?,
             chainstate_table_name: env::var("CHAINSTATE_TABLE_NAME")?,
             limit_table_name: env::var("LIMIT_TABLE_NAME")?,
-            default_limits: AccountLimits {
+            webhook_table_name: env::var("WEBHOOK_TABLE_NAME")?,
+            api_key_table_name: env::var("API_KEY_TABLE_NAME")?,
+            default_limits: Limits {
                 peg_cap: env::var("DEFAULT_PEG_CAP")
                     .ok()
                     .map(|v| v.parse())
@@ -86,14 +135,95 @@ impl Settings {
                     .ok()
                     .map(|v| v.parse())
                     .transpose()?,
+                rolling_window_seconds: env::var("DEFAULT_ROLLING_WINDOW_SECONDS")
+                    .ok()
+                    .map(|v| v.parse())
+                    .transpose()?,
+                rolling_deposit_cap: env::var("DEFAULT_ROLLING_DEPOSIT_CAP")
+                    .ok()
+                    .map(|v| v.parse())
+                    .transpose()?,
+                rolling_withdrawal_cap: env::var("DEFAULT_ROLLING_WITHDRAWAL_CAP")
+                    .ok()
+                    .map(|v| v.parse())
+                    .transpose()?,
+                rolling_deposit_volume: None,
+                rolling_withdrawal_volume: None,
+                account_caps: HashMap::new(),
             },
             trusted_reorg_api_key: env::var("TRUSTED_REORG_API_KEY")?,
+            admin_api_key: env::var("ADMIN_API_KEY").ok(),
+            trust_api_gateway_keys: env::var("TRUST_API_GATEWAY_KEYS")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            rate_limits: RateLimitSettings {
+                per_ip: quota_from_env("RATE_LIMIT_PER_IP")?,
+                per_key: quota_from_env("RATE_LIMIT_PER_KEY")?,
+            },
+            deposit_verification_endpoint: deposit_verification_endpoint_from_env()?,
+            is_mainnet: env::var("IS_MAINNET")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
+            registry_contract_id: env::var("SBTC_REGISTRY_CONTRACT_ID").ok(),
+            event_observer_secret: env::var("EVENT_OBSERVER_SECRET").ok(),
+            allow_private_webhook_urls: env::var("ALLOW_PRIVATE_WEBHOOK_URLS")
+                .map(|v| v.to_lowercase() == "true")
+                .unwrap_or(false),
         })
     }
 }
 
+/// Read the deposit verification endpoint from the
+/// `DEPOSIT_VERIFICATION_ESPLORA_URL` and
+/// `DEPOSIT_VERIFICATION_BITCOIN_RPC_URL` environment variables, at most one
+/// of which can be set.
+fn deposit_verification_endpoint_from_env() -> Result<Option<BitcoinEndpoint>, Error> {
+    let esplora_url = env::var("DEPOSIT_VERIFICATION_ESPLORA_URL").ok();
+    let bitcoin_rpc_url = env::var("DEPOSIT_VERIFICATION_BITCOIN_RPC_URL").ok();
+    match (esplora_url, bitcoin_rpc_url) {
+        (Some(_), Some(_)) => Err(Error::Debug(
+            "Only one of DEPOSIT_VERIFICATION_ESPLORA_URL and \
+            DEPOSIT_VERIFICATION_BITCOIN_RPC_URL can be set"
+                .to_string(),
+        )),
+        (Some(url), None) => Ok(Some(BitcoinEndpoint::Esplora(url))),
+        (None, Some(url)) => Ok(Some(BitcoinEndpoint::BitcoinRpc(url))),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Read a rate limit quota from the `<prefix>_BURST` and
+/// `<prefix>_PER_SECOND` environment variables. There is no quota when
+/// neither is set.
+fn quota_from_env(prefix: &str) -> Result<Option<RateLimitQuota>, Error> {
+    let burst = env::var(format!("{prefix}_BURST")).ok();
+    let per_second = env::var(format!("{prefix}_PER_SECOND")).ok();
+    match (burst, per_second) {
+        (None, None) => Ok(None),
+        (Some(burst), Some(per_second)) => Ok(Some(RateLimitQuota {
+            burst: burst.parse()?,
+            per_second: per_second
+                .parse()
+                .map_err(|err| Error::Debug(format!("Invalid {prefix}_PER_SECOND: {err}")))?,
+        })),
+        _ => Err(Error::Debug(format!(
+            "Both {prefix}_BURST and {prefix}_PER_SECOND must be set"
+        ))),
+    }
+}
+
 /// Implementation of Context.
 impl EmilyContext {
+    /// Returns a verifier for new deposits if a verification endpoint is
+    /// configured.
+    pub fn deposit_verifier(&self) -> Option<DepositVerifier> {
+        self.settings
+            .deposit_verification_endpoint
+            .clone()
+            .map(|endpoint| {
+                DepositVerifier::new(self.http_client.clone(), endpoint, self.settings.is_mainnet)
+            })
+    }
     /// Create struct instance from env.
     /// TODO(389): Make the implementation of this context more standard.
     pub async fn from_env() -> Result<Self, Error> {
@@ -109,17 +239,22 @@ impl EmilyContext {
                 .endpoint_url("http://dynamodb:8000")
                 .build();
         }
-        // Return.
-        Ok(EmilyContext {
+        let context = EmilyContext {
+            storage: Store::DynamoDb(DynamoDbStore::new(Client::new(&config), settings.clone())),
+            notifier: Notifier::new(settings.allow_private_webhook_urls),
             settings,
-            dynamodb_client: Client::new(&config),
-        })
+            rate_limiter: RateLimiter::default(),
+            http_client: reqwest::Client::new(),
+        };
+        if let Some(verifier) = context.deposit_verifier() {
+            verifier.ensure_ready().await?;
+        }
+        // Return.
+        Ok(context)
     }
     /// Create a local testing instance.
     #[cfg(feature = "testing")]
     pub async fn local_instance(dynamodb_endpoint: &str) -> Result<Self, Error> {
-        use std::collections::HashMap;
-
         // Get config that always points to the dynamodb table directly
         // from outside of a docker compose setup.
         let sdk_config = aws_config::load_defaults(BehaviorVersion::latest())
@@ -143,7 +278,14 @@ impl EmilyContext {
         // Attempt to get all the tables by searching the output of the
         // list tables operation.
         let mut table_name_map: HashMap<&str, String> = HashMap::new();
-        let tables_to_find: Vec<&str> = vec!["Deposit", "Chainstate", "Withdrawal", "Limit"];
+        let tables_to_find: Vec<&str> = vec![
+            "Deposit",
+            "Chainstate",
+            "Withdrawal",
+            "Limit",
+            "Webhook",
+            "ApiKey",
+        ];
         for name in table_names {
             for table_to_find in &tables_to_find {
                 if name.contains(table_to_find) {
@@ -153,29 +295,84 @@ impl EmilyContext {
         }
 
         // Make the context using the assumed table names.
+        let settings = Settings {
+            is_local: true,
+            deposit_table_name: table_name_map
+                .get("Deposit")
+                .expect("Couldn't find valid deposit table in existing table list.")
+                .to_string(),
+            withdrawal_table_name: table_name_map
+                .get("Withdrawal")
+                .expect("Couldn't find valid withdrawal table in existing table list.")
+                .to_string(),
+            chainstate_table_name: table_name_map
+                .get("Chainstate")
+                .expect("Couldn't find valid chainstate table in existing table list.")
+                .to_string(),
+            limit_table_name: table_name_map
+                .get("Limit")
+                .expect("Couldn't find valid limit table table in existing table list.")
+                .to_string(),
+            webhook_table_name: table_name_map
+                .get("Webhook")
+                .expect("Couldn't find valid webhook table in existing table list.")
+                .to_string(),
+            api_key_table_name: table_name_map
+                .get("ApiKey")
+                .expect("Couldn't find valid api key table in existing table list.")
+                .to_string(),
+            default_limits: Limits::default(),
+            trusted_reorg_api_key: "testApiKey".to_string(),
+            admin_api_key: None,
+            trust_api_gateway_keys: false,
+            rate_limits: RateLimitSettings::default(),
+            deposit_verification_endpoint: None,
+            is_mainnet: false,
+            registry_contract_id: None,
+            event_observer_secret: None,
+            allow_private_webhook_urls: false,
+        };
         Ok(EmilyContext {
-            settings: Settings {
-                is_local: true,
-                deposit_table_name: table_name_map
-                    .get("Deposit")
-                    .expect("Couldn't find valid deposit table in existing table list.")
-                    .to_string(),
-                withdrawal_table_name: table_name_map
-                    .get("Withdrawal")
-                    .expect("Couldn't find valid withdrawal table in existing table list.")
-                    .to_string(),
-                chainstate_table_name: table_name_map
-                    .get("Chainstate")
-                    .expect("Couldn't find valid chainstate table in existing table list.")
-                    .to_string(),
-                limit_table_name: table_name_map
-                    .get("Limit")
-                    .expect("Couldn't find valid limit table table in existing table list.")
-                    .to_string(),
-                default_limits: AccountLimits::default(),
-                trusted_reorg_api_key: "testApiKey".to_string(),
-            },
-            dynamodb_client,
+            storage: Store::DynamoDb(DynamoDbStore::new(dynamodb_client, settings.clone())),
+            settings,
+            notifier: Notifier::new(false),
+            rate_limiter: RateLimiter::default(),
+            http_client: reqwest::Client::new(),
+        })
+    }
+    /// Create a local testing instance backed by the PostgreSQL database at
+    /// the given URL, applying any outstanding migrations.
+    #[cfg(feature = "testing")]
+    pub async fn local_postgres_instance(database_url: &str) -> Result<Self, Error> {
+        // The PostgreSQL store has its own fixed tables, so the table names
+        // aren't used.
+        let settings = Settings {
+            is_local: true,
+            deposit_table_name: "Deposit".to_string(),
+            withdrawal_table_name: "Withdrawal".to_string(),
+            chainstate_table_name: "Chainstate".to_string(),
+            limit_table_name: "Limit".to_string(),
+            webhook_table_name: "Webhook".to_string(),
+            api_key_table_name: "ApiKey".to_string(),
+            default_limits: Limits::default(),
+            trusted_reorg_api_key: "testApiKey".to_string(),
+            admin_api_key: None,
+            trust_api_gateway_keys: false,
+            rate_limits: RateLimitSettings::default(),
+            deposit_verification_endpoint: None,
+            is_mainnet: false,
+            registry_contract_id: None,
+            event_observer_secret: None,
+            allow_private_webhook_urls: false,
+        };
+        let store = PgStore::connect(database_url).await?;
+        store.apply_migrations().await?;
+        Ok(EmilyContext {
+            storage: Store::Postgres(store),
+            settings,
+            notifier: Notifier::new(false),
+            rate_limiter: RateLimiter::default(),
+            http_client: reqwest::Client::new(),
         })
     }
 }
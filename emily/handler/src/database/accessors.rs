@@ -1,48 +1,40 @@
 //! Accessors.
 
 use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use aws_sdk_dynamodb::types::AttributeValue;
-use serde_dynamo::Item;
-
+use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 
+use crate::api::auth::hash_api_key;
+use crate::api::models::common::requests::OperationSearchQuery;
 use crate::api::models::limits::{AccountLimits, Limits};
+use crate::api::models::subscription::StatusEvent;
 use crate::common::error::{Error, Inconsistency};
+use crate::metrics;
 
 use crate::{api::models::common::Status, context::EmilyContext};
 
-use super::entries::deposit::{
-    DepositInfoByRecipientEntry, DepositTableByRecipientSecondaryIndex, ValidatedDepositUpdate,
-};
-use super::entries::limits::{
-    LimitEntry, LimitEntryKey, LimitTablePrimaryIndex, GLOBAL_CAP_ACCOUNT,
-};
+use super::entries::api_key::ApiKeyEntry;
+use super::entries::deposit::{DepositInfoByRecipientEntry, ValidatedDepositUpdate};
+use super::entries::limits::{LimitEntry, LimitEntryKey, GLOBAL_CAP_ACCOUNT};
+use super::entries::webhook::{event_filter_keys, WebhookEntry};
 use super::entries::withdrawal::ValidatedWithdrawalUpdate;
 use super::entries::{
-    chainstate::{
-        ApiStateEntry, ApiStatus, ChainstateEntry, ChainstateTablePrimaryIndex,
-        SpecialApiStateIndex,
-    },
-    deposit::{
-        DepositEntry, DepositEntryKey, DepositInfoEntry, DepositTablePrimaryIndex,
-        DepositTableSecondaryIndex, DepositUpdatePackage,
-    },
+    chainstate::{ApiStateEntry, ApiStatus, ChainstateEntry, ReconciliationCursorEntry},
+    deposit::{DepositEntry, DepositEntryKey, DepositInfoEntry, DepositUpdatePackage},
     withdrawal::{
-        WithdrawalEntry, WithdrawalInfoEntry, WithdrawalTablePrimaryIndex,
-        WithdrawalTableSecondaryIndex, WithdrawalUpdatePackage,
+        WithdrawalEntry, WithdrawalEntryKey, WithdrawalInfoEntry, WithdrawalUpdatePackage,
     },
-    EntryTrait, KeyTrait, TableIndexTrait, VersionedEntryTrait, VersionedTableIndexTrait,
 };
-
-// TODO: have different Table structs for each of the table types instead of
-// these individual wrappers.
+use super::entries::{detokenize, tokenize};
+use super::storage::{EmilyStorage, Page};
 
 // Deposit ---------------------------------------------------------------------
 
 /// Add deposit entry.
 pub async fn add_deposit_entry(context: &EmilyContext, entry: &DepositEntry) -> Result<(), Error> {
-    put_entry::<DepositTablePrimaryIndex>(context, entry).await
+    context.storage.put_deposit_entry(entry).await
 }
 
 /// Sets / updates an existing deposit entry.
@@ -50,7 +42,7 @@ pub async fn set_deposit_entry(
     context: &EmilyContext,
     entry: &mut DepositEntry,
 ) -> Result<(), Error> {
-    put_entry_with_version::<DepositTablePrimaryIndex>(context, entry).await
+    context.storage.put_deposit_entry_with_version(entry).await
 }
 
 /// Get deposit entry.
@@ -58,23 +50,107 @@ pub async fn get_deposit_entry(
     context: &EmilyContext,
     key: &DepositEntryKey,
 ) -> Result<DepositEntry, Error> {
-    let entry = get_entry::<DepositTablePrimaryIndex>(context, key).await?;
+    let entry = context.storage.get_deposit_entry(key).await?;
     #[cfg(feature = "testing")]
     Ok(entry)
 }
 
-/// Get deposit entries.
-pub async fn get_deposit_entries(
+/// Search deposit entries, returning a page of the entries that pass every
+/// filter in the query.
+///
+/// The search reads from the sender index if a sender is given, then the
+/// recipient index if a recipient is given, and otherwise from the status
+/// index once per status in the query. Filters that the index can't apply are
+/// applied to the entries as they're read.
+pub async fn search_deposit_entries(
     context: &EmilyContext,
-    status: &Status,
+    query: &OperationSearchQuery,
     maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
 ) -> Result<(Vec<DepositInfoEntry>, Option<String>), Error> {
-    query_with_partition_key::<DepositTableSecondaryIndex>(
-        context,
-        status,
+    let matches = |entry: &DepositInfoEntry| {
+        query.matches(
+            &entry.key.status,
+            &entry.recipient,
+            entry.sender.as_deref(),
+            entry.key.last_update_height,
+            entry.created_at,
+        )
+    };
+
+    if let Some(sender) = query.sender.as_deref() {
+        return search_partitions(
+            1,
+            maybe_next_token,
+            maybe_page_size,
+            matches,
+            move |_, next, size| async move {
+                context
+                    .storage
+                    .query_deposit_entries_by_sender(sender, next, size)
+                    .await
+                    .map(|(entries, next_token)| {
+                        (
+                            entries.into_iter().map(DepositInfoEntry::from).collect(),
+                            next_token,
+                        )
+                    })
+            },
+        )
+        .await;
+    }
+
+    if let Some(recipient) = query.recipient.as_deref() {
+        return search_partitions(
+            1,
+            maybe_next_token,
+            maybe_page_size,
+            matches,
+            move |_, next, size| async move {
+                context
+                    .storage
+                    .query_deposit_entries_by_recipient(recipient, next, size)
+                    .await
+                    .map(|(entries, next_token)| {
+                        (
+                            entries.into_iter().map(DepositInfoEntry::from).collect(),
+                            next_token,
+                        )
+                    })
+            },
+        )
+        .await;
+    }
+
+    let statuses = search_statuses(query)?;
+    let statuses = &statuses;
+    search_partitions(
+        statuses.len(),
         maybe_next_token,
         maybe_page_size,
+        matches,
+        move |partition, next, size| async move {
+            let status = &statuses[partition];
+            match query.min_height {
+                Some(minimum_height) => {
+                    context
+                        .storage
+                        .query_deposit_entries_modified_from_height(
+                            status,
+                            minimum_height,
+                            next,
+                            size,
+                        )
+                        .await
+                }
+                None => {
+                    context
+                        .storage
+                        .query_deposit_entries_by_status(status, next, size)
+                        .await
+                }
+            }
+        },
     )
     .await
 }
@@ -87,18 +163,15 @@ pub async fn get_deposit_entries_by_recipient(
     maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
 ) -> Result<(Vec<DepositInfoByRecipientEntry>, Option<String>), Error> {
-    query_with_partition_key::<DepositTableByRecipientSecondaryIndex>(
-        context,
-        recipient,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
+    context
+        .storage
+        .query_deposit_entries_by_recipient(recipient, maybe_next_token, maybe_page_size)
+        .await
 }
 
 /// Hacky exhasutive list of all statuses that we will iterate over in order to
 /// get every deposit present.
-const ALL_STATUSES: &[Status] = &[
+pub(crate) const ALL_STATUSES: &[Status] = &[
     Status::Accepted,
     Status::Confirmed,
     Status::Failed,
@@ -135,16 +208,28 @@ pub async fn get_all_deposit_entries_modified_from_height_with_status(
     maybe_page_size: Option<u16>,
 ) -> Result<Vec<DepositInfoEntry>, Error> {
     // Make the query.
-    query_all_with_partition_and_sort_key::<DepositTableSecondaryIndex>(
-        context,
-        status,
-        &minimum_height,
-        ">=",
-        maybe_page_size,
-    )
+    query_all_pages(|next_token| {
+        context.storage.query_deposit_entries_modified_from_height(
+            status,
+            minimum_height,
+            next_token,
+            maybe_page_size,
+        )
+    })
     .await
 }
 
+/// Counts the deposit entries with the given status.
+pub async fn count_deposit_entries_with_status(
+    context: &EmilyContext,
+    status: &Status,
+) -> Result<u64, Error> {
+    context
+        .storage
+        .count_deposit_entries_by_status(status)
+        .await
+}
+
 /// Get deposit entries for a given transaction.
 #[allow(clippy::ptr_arg)]
 pub async fn get_deposit_entries_for_transaction(
@@ -153,13 +238,10 @@ pub async fn get_deposit_entries_for_transaction(
     maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
 ) -> Result<(Vec<DepositEntry>, Option<String>), Error> {
-    query_with_partition_key::<DepositTablePrimaryIndex>(
-        context,
-        bitcoin_txid,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
+    context
+        .storage
+        .query_deposit_entries_by_txid(bitcoin_txid, maybe_next_token, maybe_page_size)
+        .await
 }
 
 /// Pulls in a deposit entry and then updates it, retrying the specified number
@@ -201,54 +283,9 @@ pub async fn update_deposit(
     context: &EmilyContext,
     update: &DepositUpdatePackage,
 ) -> Result<DepositEntry, Error> {
-    // Setup the update procedure.
-    let update_expression: &str = " SET
-        History = list_append(History, :new_event),
-        Version = Version + :one,
-        OpStatus = :new_op_status,
-        LastUpdateHeight = :new_height,
-        LastUpdateBlockHash = :new_hash
-    ";
-    // Ensure the version field is what we expect it to be.
-    let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
-    // Make the key item.
-    let key_item: Item = serde_dynamo::to_item(&update.key)?;
-    // Get simplified status enum.
-    let status: Status = (&update.event.status).into();
-    // Build the update.
-    context
-        .dynamodb_client
-        .update_item()
-        .table_name(&context.settings.deposit_table_name)
-        .set_key(Some(key_item.into()))
-        .expression_attribute_values(":new_op_status", serde_dynamo::to_attribute_value(&status)?)
-        .expression_attribute_values(
-            ":new_height",
-            serde_dynamo::to_attribute_value(update.event.stacks_block_height)?,
-        )
-        .expression_attribute_values(
-            ":new_hash",
-            serde_dynamo::to_attribute_value(&update.event.stacks_block_hash)?,
-        )
-        .expression_attribute_values(
-            ":new_event",
-            serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
-        )
-        .expression_attribute_values(
-            ":expected_version",
-            serde_dynamo::to_attribute_value(update.version)?,
-        )
-        .expression_attribute_values(":one", AttributeValue::N(1.to_string()))
-        .condition_expression(condition_expression)
-        .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
-        .update_expression(update_expression)
-        .send()
-        .await?
-        .attributes
-        .ok_or(Error::Debug("Failed updating withdrawal".into()))
-        .and_then(|attributes| {
-            serde_dynamo::from_item::<Item, DepositEntry>(attributes.into()).map_err(Error::from)
-        })
+    let entry = context.storage.update_deposit(update).await?;
+    notify_deposit_subscribers(context, &entry).await;
+    Ok(entry)
 }
 
 // Withdrawal ------------------------------------------------------------------
@@ -258,7 +295,7 @@ pub async fn add_withdrawal_entry(
     context: &EmilyContext,
     entry: &WithdrawalEntry,
 ) -> Result<(), Error> {
-    put_entry::<WithdrawalTablePrimaryIndex>(context, entry).await
+    context.storage.put_withdrawal_entry(entry).await
 }
 
 /// Sets / updates an existing withdrawal entry.
@@ -266,7 +303,18 @@ pub async fn set_withdrawal_entry(
     context: &EmilyContext,
     entry: &mut WithdrawalEntry,
 ) -> Result<(), Error> {
-    put_entry_with_version::<WithdrawalTablePrimaryIndex>(context, entry).await
+    context
+        .storage
+        .put_withdrawal_entry_with_version(entry)
+        .await
+}
+
+/// Delete a withdrawal entry.
+pub async fn delete_withdrawal_entry(
+    context: &EmilyContext,
+    key: &WithdrawalEntryKey,
+) -> Result<(), Error> {
+    context.storage.delete_withdrawal_entry(key).await
 }
 
 /// Get withdrawal entry.
@@ -276,13 +324,10 @@ pub async fn get_withdrawal_entry(
 ) -> Result<WithdrawalEntry, Error> {
     // Get the entries.
     let num_to_retrieve_if_multiple = 3;
-    let (entries, _) = query_with_partition_key::<WithdrawalTablePrimaryIndex>(
-        context,
-        key,
-        None,
-        Some(num_to_retrieve_if_multiple),
-    )
-    .await?;
+    let (entries, _) = context
+        .storage
+        .query_withdrawal_entries_by_request_id(*key, None, Some(num_to_retrieve_if_multiple))
+        .await?;
     // Return.
     match entries.as_slice() {
         [] => Err(Error::NotFound),
@@ -303,18 +348,98 @@ pub async fn get_withdrawal_entry(
     }
 }
 
-/// Get withdrawal entries.
-pub async fn get_withdrawal_entries(
+/// Search withdrawal entries, returning a page of the entries that pass
+/// every filter in the query. Indexes are picked the same way as they are
+/// for [`search_deposit_entries`].
+pub async fn search_withdrawal_entries(
     context: &EmilyContext,
-    status: &Status,
+    query: &OperationSearchQuery,
     maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
 ) -> Result<(Vec<WithdrawalInfoEntry>, Option<String>), Error> {
-    query_with_partition_key::<WithdrawalTableSecondaryIndex>(
-        context,
-        status,
+    let matches = |entry: &WithdrawalInfoEntry| {
+        query.matches(
+            &entry.key.status,
+            &entry.recipient,
+            entry.sender.as_deref(),
+            entry.key.last_update_height,
+            entry.created_at,
+        )
+    };
+
+    if let Some(sender) = query.sender.as_deref() {
+        return search_partitions(
+            1,
+            maybe_next_token,
+            maybe_page_size,
+            matches,
+            move |_, next, size| async move {
+                context
+                    .storage
+                    .query_withdrawal_entries_by_sender(sender, next, size)
+                    .await
+                    .map(|(entries, next_token)| {
+                        (
+                            entries.into_iter().map(WithdrawalInfoEntry::from).collect(),
+                            next_token,
+                        )
+                    })
+            },
+        )
+        .await;
+    }
+
+    if let Some(recipient) = query.recipient.as_deref() {
+        return search_partitions(
+            1,
+            maybe_next_token,
+            maybe_page_size,
+            matches,
+            move |_, next, size| async move {
+                context
+                    .storage
+                    .query_withdrawal_entries_by_recipient(recipient, next, size)
+                    .await
+                    .map(|(entries, next_token)| {
+                        (
+                            entries.into_iter().map(WithdrawalInfoEntry::from).collect(),
+                            next_token,
+                        )
+                    })
+            },
+        )
+        .await;
+    }
+
+    let statuses = search_statuses(query)?;
+    let statuses = &statuses;
+    search_partitions(
+        statuses.len(),
         maybe_next_token,
         maybe_page_size,
+        matches,
+        move |partition, next, size| async move {
+            let status = &statuses[partition];
+            match query.min_height {
+                Some(minimum_height) => {
+                    context
+                        .storage
+                        .query_withdrawal_entries_modified_from_height(
+                            status,
+                            minimum_height,
+                            next,
+                            size,
+                        )
+                        .await
+                }
+                None => {
+                    context
+                        .storage
+                        .query_withdrawal_entries_by_status(status, next, size)
+                        .await
+                }
+            }
+        },
     )
     .await
 }
@@ -348,16 +473,30 @@ pub async fn get_all_withdrawal_entries_modified_from_height_with_status(
     maybe_page_size: Option<u16>,
 ) -> Result<Vec<WithdrawalInfoEntry>, Error> {
     // Make the query.
-    query_all_with_partition_and_sort_key::<WithdrawalTableSecondaryIndex>(
-        context,
-        status,
-        &minimum_height,
-        ">=",
-        maybe_page_size,
-    )
+    query_all_pages(|next_token| {
+        context
+            .storage
+            .query_withdrawal_entries_modified_from_height(
+                status,
+                minimum_height,
+                next_token,
+                maybe_page_size,
+            )
+    })
     .await
 }
 
+/// Counts the withdrawal entries with the given status.
+pub async fn count_withdrawal_entries_with_status(
+    context: &EmilyContext,
+    status: &Status,
+) -> Result<u64, Error> {
+    context
+        .storage
+        .count_withdrawal_entries_by_status(status)
+        .await
+}
+
 /// Pulls in a withdrawal entry and then updates it, retrying the specified number
 /// of times when there's a version conflict.
 ///
@@ -396,54 +535,101 @@ pub async fn update_withdrawal(
     context: &EmilyContext,
     update: &WithdrawalUpdatePackage,
 ) -> Result<WithdrawalEntry, Error> {
-    // Setup the update procedure.
-    let update_expression: &str = " SET
-        History = list_append(History, :new_event),
-        Version = Version + :one,
-        OpStatus = :new_op_status,
-        LastUpdateHeight = :new_height,
-        LastUpdateBlockHash = :new_hash
-    ";
-    // Ensure the version field is what we expect it to be.
-    let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
-    // Make the key item.
-    let key_item: Item = serde_dynamo::to_item(&update.key)?;
-    // Get simplified status enum.
-    let status: Status = (&update.event.status).into();
-    // Execute the update.
-    context
-        .dynamodb_client
-        .update_item()
-        .table_name(&context.settings.withdrawal_table_name)
-        .set_key(Some(key_item.into()))
-        .expression_attribute_values(":new_op_status", serde_dynamo::to_attribute_value(&status)?)
-        .expression_attribute_values(
-            ":new_height",
-            serde_dynamo::to_attribute_value(update.event.stacks_block_height)?,
-        )
-        .expression_attribute_values(
-            ":new_hash",
-            serde_dynamo::to_attribute_value(&update.event.stacks_block_hash)?,
-        )
-        .expression_attribute_values(
-            ":new_event",
-            serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
-        )
-        .expression_attribute_values(
-            ":expected_version",
-            serde_dynamo::to_attribute_value(update.version)?,
-        )
-        .expression_attribute_values(":one", AttributeValue::N(1.to_string()))
-        .condition_expression(condition_expression)
-        .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
-        .update_expression(update_expression)
-        .send()
-        .await?
-        .attributes
-        .ok_or(Error::Debug("Failed updating withdrawal".into()))
-        .and_then(|attributes| {
-            serde_dynamo::from_item::<Item, WithdrawalEntry>(attributes.into()).map_err(Error::from)
+    let entry = context.storage.update_withdrawal(update).await?;
+    notify_withdrawal_subscribers(context, &entry).await;
+    Ok(entry)
+}
+
+// Subscriptions ---------------------------------------------------------------
+
+/// Add webhook entry.
+pub async fn add_webhook_entry(context: &EmilyContext, entry: &WebhookEntry) -> Result<(), Error> {
+    context.storage.put_webhook_entry(entry).await
+}
+
+/// Get the webhook entries registered by the given api key.
+pub async fn get_webhook_entries(
+    context: &EmilyContext,
+    owner: &str,
+) -> Result<Vec<WebhookEntry>, Error> {
+    let entries = context.storage.get_all_webhook_entries().await?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| entry.owner == owner)
+        .collect())
+}
+
+/// Get the webhooks whose filter matches the event, looking them up by
+/// the filter keys of the event rather than reading every webhook.
+pub async fn get_webhook_entries_for_event(
+    context: &EmilyContext,
+    event: &StatusEvent,
+) -> Result<Vec<WebhookEntry>, Error> {
+    let mut webhooks = Vec::new();
+    for filter_key in event_filter_keys(event) {
+        let entries = query_all_pages(|next_token| {
+            context
+                .storage
+                .query_webhook_entries_by_filter_key(&filter_key, next_token, None)
         })
+        .await?;
+        webhooks.extend(
+            entries
+                .into_iter()
+                .map(WebhookEntry::from)
+                .filter(|webhook| webhook.filter.matches(event)),
+        );
+    }
+    Ok(webhooks)
+}
+
+/// Delete the webhook with the given id. Webhooks registered by other api
+/// keys are not found.
+pub async fn delete_webhook_entry(
+    context: &EmilyContext,
+    owner: &str,
+    webhook_id: &str,
+) -> Result<(), Error> {
+    let entries: Vec<WebhookEntry> = get_webhook_entries(context, owner)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.key.webhook_id == webhook_id)
+        .collect();
+    if entries.is_empty() {
+        return Err(Error::NotFound);
+    }
+    for entry in entries {
+        context.storage.delete_webhook_entry(&entry.key).await?;
+    }
+    Ok(())
+}
+
+/// Tell the subscribers about the latest event in the history of a deposit.
+pub async fn notify_deposit_subscribers(context: &EmilyContext, entry: &DepositEntry) {
+    notify_subscribers(context, StatusEvent::try_from(entry)).await
+}
+
+/// Tell the subscribers about the latest event in the history of a
+/// withdrawal.
+pub async fn notify_withdrawal_subscribers(context: &EmilyContext, entry: &WithdrawalEntry) {
+    notify_subscribers(context, StatusEvent::try_from(entry)).await
+}
+
+/// Publish a status event to the subscribers. The webhooks are delivered to
+/// in the background, so the update that produced the event doesn't wait
+/// for them. The change that produced the event has already been stored at
+/// this point, so a failure to notify is logged rather than returned.
+async fn notify_subscribers(context: &EmilyContext, maybe_event: Result<StatusEvent, Error>) {
+    let result = async {
+        let event = maybe_event?;
+        let webhooks = get_webhook_entries_for_event(context, &event).await?;
+        // The deliveries are detached by dropping their handle.
+        context.notifier.publish(event, webhooks).map(drop)
+    }
+    .await;
+    if let Err(error) = result {
+        warn!("Failed to notify subscribers of a status change: {error}");
+    }
 }
 
 // Chainstate ------------------------------------------------------------------
@@ -524,7 +710,10 @@ pub async fn add_chainstate_entry(
             for chainstate in chainstates {
                 // Remove the entry from the table.
                 let existing_entry: ChainstateEntry = chainstate.into();
-                delete_entry::<ChainstateTablePrimaryIndex>(context, &existing_entry.key).await?;
+                context
+                    .storage
+                    .delete_chainstate_entry(&existing_entry.key)
+                    .await?;
             }
         }
         // ..otherwise exit here.
@@ -541,7 +730,7 @@ pub async fn add_chainstate_entry(
         // and have different views of the block hash at this height it would result in two hashes
         // for the same height. This will be explicitly handled when the api attempts to retrieve the
         // chainstate for this height and finds multiple, indicating a conflicting internal state.
-        put_entry::<ChainstateTablePrimaryIndex>(context, entry).await?;
+        context.storage.put_chainstate_entry(entry).await?;
         // Version locked api state prevents inconsistencies here.
         set_api_state(context, &api_state).await
     } else if blocks_higher_than_current_tip > 1 {
@@ -554,7 +743,7 @@ pub async fn add_chainstate_entry(
         // TODO(TBD): Determine the ramifications of allowing a chaintip to be added much
         // higher than expected.
         api_state.api_status = ApiStatus::Stable(entry.clone());
-        put_entry::<ChainstateTablePrimaryIndex>(context, entry).await?;
+        context.storage.put_chainstate_entry(entry).await?;
         set_api_state(context, &api_state).await
     } else {
         // Current tip is higher than the entry we attempted to emplace
@@ -577,9 +766,10 @@ pub async fn get_chainstate_entry_at_height(
     context: &EmilyContext,
     height: &u64,
 ) -> Result<ChainstateEntry, Error> {
-    let (entries, _) =
-        query_with_partition_key::<ChainstateTablePrimaryIndex>(context, height, None, None)
-            .await?;
+    let (entries, _) = context
+        .storage
+        .query_chainstate_entries_by_height(*height, None, None)
+        .await?;
     // If there are multiple entries at this height report an inconsistent state
     // error.
     match entries.as_slice() {
@@ -597,36 +787,79 @@ pub async fn get_chainstate_entries_for_height(
     maybe_next_token: Option<String>,
     maybe_page_size: Option<u16>,
 ) -> Result<(Vec<ChainstateEntry>, Option<String>), Error> {
-    query_with_partition_key::<ChainstateTablePrimaryIndex>(
-        context,
-        height,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
+    context
+        .storage
+        .query_chainstate_entries_by_height(*height, maybe_next_token, maybe_page_size)
+        .await
 }
 
 /// Gets the state of the API.
 pub async fn get_api_state(context: &EmilyContext) -> Result<ApiStateEntry, Error> {
-    let get_api_state_result =
-        get_entry::<SpecialApiStateIndex>(context, &ApiStateEntry::key()).await;
+    let get_api_state_result = context.storage.get_api_state_entry().await;
     match get_api_state_result {
         // If the API state wasn't found then initialize it into the table.
         // TODO(390): Handle any race conditions with the version field in case
         // the entry was initialized and then updated after creation.
         Err(Error::NotFound) => {
             let initial_api_state_entry = ApiStateEntry::default();
-            put_entry::<SpecialApiStateIndex>(context, &initial_api_state_entry).await?;
+            context
+                .storage
+                .put_api_state_entry(&initial_api_state_entry)
+                .await?;
             Ok(initial_api_state_entry)
         }
         result => result,
     }
 }
 
-/// Sets the API state.
+/// Sets the API state, recording when its chain tip changed. Writes that
+/// keep the chain tip, such as the start and end of a reorg around the
+/// current tip, keep the time that the tip was set.
 /// TODO(TBD): Include the relevant logic for updating the entry version.
 pub async fn set_api_state(context: &EmilyContext, api_state: &ApiStateEntry) -> Result<(), Error> {
-    put_entry_with_version::<SpecialApiStateIndex>(context, &mut api_state.clone()).await
+    let mut api_state = api_state.clone();
+    let stored_chaintip = match context.storage.get_api_state_entry().await {
+        Ok(stored) => Some(stored.chaintip()),
+        Err(Error::NotFound) => None,
+        Err(error) => return Err(error),
+    };
+    if stored_chaintip.as_ref() != Some(&api_state.chaintip()) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
+        api_state.updated_at = Some(now);
+    }
+    context
+        .storage
+        .put_api_state_entry_with_version(&mut api_state)
+        .await?;
+    metrics::set_chain_tip_height(api_state.chaintip().key.height);
+    Ok(())
+}
+
+// Reconciliation --------------------------------------------------------------
+
+/// Gets the stacks block height at or below which the reconciler has
+/// settled every registry event, which is zero before its first run.
+pub async fn get_reconciliation_cursor(context: &EmilyContext) -> Result<u64, Error> {
+    match context.storage.get_reconciliation_cursor_entry().await {
+        Ok(entry) => Ok(entry.stacks_block_height),
+        Err(Error::NotFound) => Ok(0),
+        Err(error) => Err(error),
+    }
+}
+
+/// Sets the stacks block height at or below which the reconciler has
+/// settled every registry event.
+pub async fn set_reconciliation_cursor(
+    context: &EmilyContext,
+    stacks_block_height: u64,
+) -> Result<(), Error> {
+    context
+        .storage
+        .put_reconciliation_cursor_entry(&ReconciliationCursorEntry::new(stacks_block_height))
+        .await
 }
 
 // Limits ----------------------------------------------------------------------
@@ -639,9 +872,7 @@ pub async fn set_api_state(context: &EmilyContext, api_state: &ApiStateEntry) ->
 /// return type that is within the table as an entry.
 pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
     // Get all the entries of the limit table. This table shouldn't be too large.
-    let all_entries =
-        LimitTablePrimaryIndex::get_all_entries(&context.dynamodb_client, &context.settings)
-            .await?;
+    let all_entries = context.storage.get_all_limit_entries().await?;
     // Create the default global cap.
     let default_global_cap = context.settings.default_limits.clone();
     let mut global_cap = LimitEntry {
@@ -655,6 +886,9 @@ pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
         per_deposit_minimum: default_global_cap.per_deposit_minimum,
         per_deposit_cap: default_global_cap.per_deposit_cap,
         per_withdrawal_cap: default_global_cap.per_withdrawal_cap,
+        rolling_window_seconds: default_global_cap.rolling_window_seconds,
+        rolling_deposit_cap: default_global_cap.rolling_deposit_cap,
+        rolling_withdrawal_cap: default_global_cap.rolling_withdrawal_cap,
     };
     // Aggregate all the latest entries by account.
     let mut limit_by_account: HashMap<String, LimitEntry> = HashMap::new();
@@ -686,16 +920,72 @@ pub async fn get_limits(context: &EmilyContext) -> Result<Limits, Error> {
         .filter(|(_, limit_entry)| !limit_entry.is_empty())
         .map(|(account, limit_entry)| (account, AccountLimits::from(limit_entry)))
         .collect();
+    // Sum what was accepted within the rolling window. Nothing needs to be
+    // queried when there aren't any rolling caps.
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+        .as_secs();
+    let since = now.saturating_sub(global_cap.rolling_window_seconds());
+    let rolling_deposit_volume = match global_cap.rolling_deposit_cap {
+        Some(_) => Some(get_deposit_volume_since(context, since).await?),
+        None => None,
+    };
+    let rolling_withdrawal_volume = match global_cap.rolling_withdrawal_cap {
+        Some(_) => Some(get_withdrawal_volume_since(context, since).await?),
+        None => None,
+    };
     // Get the global limit for the whole thing.
     Ok(Limits {
         peg_cap: global_cap.peg_cap,
         per_deposit_minimum: global_cap.per_deposit_minimum,
         per_deposit_cap: global_cap.per_deposit_cap,
         per_withdrawal_cap: global_cap.per_withdrawal_cap,
+        rolling_window_seconds: global_cap.rolling_window_seconds,
+        rolling_deposit_cap: global_cap.rolling_deposit_cap,
+        rolling_withdrawal_cap: global_cap.rolling_withdrawal_cap,
+        rolling_deposit_volume,
+        rolling_withdrawal_volume,
         account_caps,
     })
 }
 
+/// Sum the amounts of the deposits that were accepted at or after the given
+/// unix time.
+async fn get_deposit_volume_since(context: &EmilyContext, since: u64) -> Result<u64, Error> {
+    let mut volume = 0u64;
+    for status in [Status::Accepted, Status::Confirmed] {
+        let entries = query_all_pages(|next| {
+            context
+                .storage
+                .query_deposit_entries_accepted_since(&status, since, next, None)
+        })
+        .await?;
+        volume = entries
+            .iter()
+            .fold(volume, |acc, entry| acc.saturating_add(entry.amount));
+    }
+    Ok(volume)
+}
+
+/// Sum the amounts of the withdrawals that were accepted at or after the
+/// given unix time.
+async fn get_withdrawal_volume_since(context: &EmilyContext, since: u64) -> Result<u64, Error> {
+    let mut volume = 0u64;
+    for status in [Status::Accepted, Status::Confirmed] {
+        let entries = query_all_pages(|next| {
+            context
+                .storage
+                .query_withdrawal_entries_accepted_since(&status, since, next, None)
+        })
+        .await?;
+        volume = entries
+            .iter()
+            .fold(volume, |acc, entry| acc.saturating_add(entry.amount));
+    }
+    Ok(volume)
+}
+
 /// Get the limit for a specific account.
 #[allow(clippy::ptr_arg)]
 pub async fn get_limit_for_account(
@@ -703,15 +993,12 @@ pub async fn get_limit_for_account(
     account: &String,
 ) -> Result<LimitEntry, Error> {
     // Make the query.
-    let (mut entries, _) = query_with_partition_key::<LimitTablePrimaryIndex>(
-        context,
-        account,
-        None,
-        // Only get the most recent entry. The internals of this query uses
-        // scan_index_forward = false.
-        Some(1),
-    )
-    .await?;
+    let (mut entries, _) = context
+        .storage
+        // Only get the most recent entry. Queries return entries with the
+        // largest sort key first.
+        .query_limit_entries_by_account(account, None, Some(1))
+        .await?;
     // The limit is set to 1 so there should always only be one entry returned,
     // but for the sake of redundancy also get the most recent entry.
     entries.sort_by_key(|entry| entry.key.timestamp);
@@ -723,125 +1010,77 @@ pub async fn set_limit_for_account(
     context: &EmilyContext,
     limit: &LimitEntry,
 ) -> Result<(), Error> {
-    put_entry::<LimitTablePrimaryIndex>(context, limit).await
+    context.storage.put_limit_entry(limit).await
 }
 
-// Testing ---------------------------------------------------------------------
+// Api keys --------------------------------------------------------------------
 
-/// Wipes all the tables.
-/// TODO(395): Include check for whether the table is running locally.
-#[cfg(feature = "testing")]
-pub async fn wipe_all_tables(context: &EmilyContext) -> Result<(), Error> {
-    wipe_deposit_table(context).await?;
-    wipe_withdrawal_table(context).await?;
-    wipe_chainstate_table(context).await?;
-    wipe_limit_table(context).await?;
-    Ok(())
+/// Add api key entry.
+pub async fn add_api_key_entry(context: &EmilyContext, entry: &ApiKeyEntry) -> Result<(), Error> {
+    context.storage.put_api_key_entry(entry).await
 }
 
-/// Wipes the deposit table.
-#[cfg(feature = "testing")]
-async fn wipe_deposit_table(context: &EmilyContext) -> Result<(), Error> {
-    wipe::<DepositTablePrimaryIndex>(context).await
+/// Get all api key entries, including the revoked ones.
+pub async fn get_api_key_entries(context: &EmilyContext) -> Result<Vec<ApiKeyEntry>, Error> {
+    context.storage.get_all_api_key_entries().await
 }
 
-/// Wipes the withdrawal table.
-#[cfg(feature = "testing")]
-async fn wipe_withdrawal_table(context: &EmilyContext) -> Result<(), Error> {
-    wipe::<WithdrawalTablePrimaryIndex>(context).await
+/// Get the stored entry of an api key, if there is one.
+pub async fn get_api_key_entry_for_key(
+    context: &EmilyContext,
+    api_key: &str,
+) -> Result<Option<ApiKeyEntry>, Error> {
+    // The id is the part of the key before the separator.
+    let Some((key_id, _)) = api_key.split_once('.') else {
+        return Ok(None);
+    };
+    let key_hash = hash_api_key(api_key);
+    let entries = query_all_pages(|maybe_next_token| {
+        context
+            .storage
+            .query_api_key_entries_by_id(key_id, maybe_next_token, None)
+    })
+    .await?;
+    Ok(entries.into_iter().find(|entry| entry.key_hash == key_hash))
 }
 
-/// Wipes the chainstate table.
-#[cfg(feature = "testing")]
-async fn wipe_chainstate_table(context: &EmilyContext) -> Result<(), Error> {
-    delete_entry::<SpecialApiStateIndex>(context, &ApiStateEntry::key()).await?;
-    wipe::<ChainstateTablePrimaryIndex>(context).await
+/// Revoke the api key with the given id, returning the revoked entry.
+pub async fn revoke_api_key(context: &EmilyContext, key_id: &str) -> Result<ApiKeyEntry, Error> {
+    let (mut entries, _) = context
+        .storage
+        .query_api_key_entries_by_id(key_id, None, Some(1))
+        .await?;
+    let mut entry = entries.pop().ok_or(Error::NotFound)?;
+    entry.revoked = true;
+    context.storage.put_api_key_entry(&entry).await?;
+    Ok(entry)
 }
 
-/// Wipes the limit table.
+// Testing ---------------------------------------------------------------------
+
+/// Wipes all the tables.
 #[cfg(feature = "testing")]
-async fn wipe_limit_table(context: &EmilyContext) -> Result<(), Error> {
-    wipe::<LimitTablePrimaryIndex>(context).await
+pub async fn wipe_all_tables(context: &EmilyContext) -> Result<(), Error> {
+    context.storage.wipe_all().await
 }
 
 // Generics --------------------------------------------------------------------
 
-async fn get_entry<T: TableIndexTrait>(
-    context: &EmilyContext,
-    key: &<<T as TableIndexTrait>::Entry as EntryTrait>::Key,
-) -> Result<<T as TableIndexTrait>::Entry, Error> {
-    <T as TableIndexTrait>::get_entry(&context.dynamodb_client, &context.settings, key).await
-}
-
-async fn put_entry<T: TableIndexTrait>(
-    context: &EmilyContext,
-    entry: &<T as TableIndexTrait>::Entry,
-) -> Result<(), Error> {
-    <T as TableIndexTrait>::put_entry(&context.dynamodb_client, &context.settings, entry).await
-}
-
-async fn put_entry_with_version<T: VersionedTableIndexTrait>(
-    context: &EmilyContext,
-    entry: &mut <T as TableIndexTrait>::Entry,
-) -> Result<(), Error>
+/// Calls `query_page` with each next token in turn until there are no pages
+/// left, collecting every entry.
+async fn query_all_pages<T, F, Fut>(mut query_page: F) -> Result<Vec<T>, Error>
 where
-    <T as TableIndexTrait>::Entry: VersionedEntryTrait,
+    F: FnMut(Option<String>) -> Fut,
+    Fut: std::future::Future<Output = Result<Page<T>, Error>>,
 {
-    <T as VersionedTableIndexTrait>::put_entry_with_version(
-        &context.dynamodb_client,
-        &context.settings,
-        entry,
-    )
-    .await
-}
-
-async fn delete_entry<T: TableIndexTrait>(
-    context: &EmilyContext,
-    key: &<<T as TableIndexTrait>::Entry as EntryTrait>::Key,
-) -> Result<(), Error> {
-    <T as TableIndexTrait>::delete_entry(&context.dynamodb_client, &context.settings, key).await
-}
-
-async fn query_with_partition_key<T: TableIndexTrait>(
-    context: &EmilyContext,
-    parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
-    maybe_next_token: Option<String>,
-    maybe_page_size: Option<u16>,
-) -> Result<(Vec<<T as TableIndexTrait>::Entry>, Option<String>), Error> {
-    <T as TableIndexTrait>::query_with_partition_key(
-        &context.dynamodb_client,
-        &context.settings,
-        parition_key,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
-}
-
-async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
-    context: &EmilyContext,
-    parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
-    sort_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::SortKey,
-    sort_key_operator: &str,
-    maybe_page_size: Option<u16>,
-) -> Result<Vec<<T as TableIndexTrait>::Entry>, Error> {
     // item aggregator.
-    let mut items: Vec<<T as TableIndexTrait>::Entry> = Vec::new();
+    let mut items: Vec<T> = Vec::new();
     // Next token.
     let mut next_token: Option<String> = None;
     // Loop over all items.
     loop {
-        let mut new_items: Vec<<T as TableIndexTrait>::Entry>;
-        (new_items, next_token) = <T as TableIndexTrait>::query_with_partition_and_sort_key(
-            &context.dynamodb_client,
-            &context.settings,
-            parition_key,
-            sort_key,
-            sort_key_operator,
-            next_token,
-            maybe_page_size,
-        )
-        .await?;
+        let mut new_items: Vec<T>;
+        (new_items, next_token) = query_page(next_token).await?;
         // add new items.
         items.append(&mut new_items);
         if next_token.is_none() {
@@ -853,9 +1092,79 @@ async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
     Ok(items)
 }
 
-#[cfg(feature = "testing")]
-async fn wipe<T: TableIndexTrait>(context: &EmilyContext) -> Result<(), Error> {
-    <T as TableIndexTrait>::wipe(&context.dynamodb_client, &context.settings).await
+/// The statuses a search without a sender or recipient reads, erroring if
+/// there aren't any since there would be no index to read from.
+fn search_statuses(query: &OperationSearchQuery) -> Result<Vec<Status>, Error> {
+    let statuses = query.statuses();
+    if statuses.is_empty() {
+        return Err(Error::BadRequest(
+            "a status, recipient or sender is required to search".to_string(),
+        ));
+    }
+    Ok(statuses)
+}
+
+/// Next token of a search that reads from several index partitions.
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchToken {
+    /// The partition that the search is up to.
+    partition: usize,
+    /// The next token within that partition.
+    next_token: Option<String>,
+}
+
+/// The most index reads a single search request makes before returning what
+/// it has found so far along with a next token.
+const MAX_SEARCH_READS: usize = 8;
+
+/// Reads pages from each partition in turn, keeping the entries that match,
+/// until the page is full or every partition has been read.
+///
+/// Since entries are filtered after they're read, a single partition can
+/// take several reads to fill a page. Without a page size the search stops
+/// after the first read that keeps any entries. Either way the search stops
+/// after [`MAX_SEARCH_READS`] reads so that a filter matching few entries
+/// can't make one request scan a whole index; the caller picks the search
+/// back up from the returned next token, even if the page is empty.
+async fn search_partitions<T, M, F, Fut>(
+    partition_count: usize,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<u16>,
+    matches: M,
+    mut query_page: F,
+) -> Result<Page<T>, Error>
+where
+    M: Fn(&T) -> bool,
+    F: FnMut(usize, Option<String>, Option<u16>) -> Fut,
+    Fut: std::future::Future<Output = Result<Page<T>, Error>>,
+{
+    let SearchToken { mut partition, mut next_token } = maybe_next_token
+        .map(detokenize)
+        .transpose()?
+        .unwrap_or(SearchToken { partition: 0, next_token: None });
+
+    let mut items: Vec<T> = Vec::new();
+    let mut reads: usize = 0;
+    while partition < partition_count && reads < MAX_SEARCH_READS {
+        reads += 1;
+        let remaining = maybe_page_size.map(|size| size.saturating_sub(items.len() as u16));
+        let new_items: Vec<T>;
+        (new_items, next_token) = query_page(partition, next_token, remaining).await?;
+        items.extend(new_items.into_iter().filter(|item| matches(item)));
+        if next_token.is_none() {
+            partition += 1;
+        }
+        let page_full =
+            maybe_page_size.map_or(!items.is_empty(), |size| items.len() >= size as usize);
+        if page_full {
+            break;
+        }
+    }
+
+    let next_token = (partition < partition_count)
+        .then(|| tokenize(SearchToken { partition, next_token }))
+        .transpose()?;
+    Ok((items, next_token))
 }
 
 // TODO(397): Add accessor function unit tests.
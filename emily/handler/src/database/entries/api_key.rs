@@ -0,0 +1,117 @@
+//! Entries into the api key table.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::api_key::{ApiKey, ApiKeyScope};
+
+use super::{EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait};
+
+// Api key entry ---------------------------------------------------------------
+
+/// Api key table entry key. This is the primary index key.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApiKeyEntryKey {
+    /// The id of the api key.
+    pub key_id: String,
+    /// The unix timestamp in seconds of when the api key was created.
+    pub created_at: u64,
+}
+
+/// Api key table entry.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApiKeyEntry {
+    /// Api key entry key.
+    #[serde(flatten)]
+    pub key: ApiKeyEntryKey,
+    /// Hex encoded SHA-256 hash of the full api key.
+    pub key_hash: String,
+    /// Description of who the api key is for.
+    pub description: String,
+    /// What the api key is allowed to do.
+    pub scopes: Vec<ApiKeyScope>,
+    /// Whether the api key has been revoked.
+    pub revoked: bool,
+}
+
+impl ApiKeyEntry {
+    /// Returns true if the api key hasn't been revoked and is allowed to act
+    /// within the scope. Every key that hasn't been revoked can read.
+    pub fn allows(&self, scope: ApiKeyScope) -> bool {
+        !self.revoked && (scope == ApiKeyScope::ReadOnly || self.scopes.contains(&scope))
+    }
+}
+
+/// Convert from entry to its corresponding api key, leaving out the hash.
+impl From<ApiKeyEntry> for ApiKey {
+    fn from(api_key_entry: ApiKeyEntry) -> Self {
+        ApiKey {
+            id: api_key_entry.key.key_id,
+            description: api_key_entry.description,
+            scopes: api_key_entry.scopes,
+            created_at: api_key_entry.key.created_at,
+            revoked: api_key_entry.revoked,
+        }
+    }
+}
+
+/// Implements the key trait for the api key entry key.
+impl KeyTrait for ApiKeyEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "KeyId";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "CreatedAt";
+}
+
+/// Implements the entry trait for the api key entry.
+impl EntryTrait for ApiKeyEntry {
+    /// The type of the key for this entry type.
+    type Key = ApiKeyEntryKey;
+    /// Extract the key from the api key entry.
+    fn key(&self) -> Self::Key {
+        self.key.clone()
+    }
+}
+
+/// Primary index struct.
+pub struct ApiKeyTablePrimaryIndexInner;
+/// Api key table primary index type.
+pub type ApiKeyTablePrimaryIndex = PrimaryIndex<ApiKeyTablePrimaryIndexInner>;
+/// Definition of Primary index trait.
+impl PrimaryIndexTrait for ApiKeyTablePrimaryIndexInner {
+    type Entry = ApiKeyEntry;
+    fn table_name(settings: &crate::context::Settings) -> &str {
+        &settings.api_key_table_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(&[], ApiKeyScope::ReadOnly, false, true; "every key can read")]
+    #[test_case(&[ApiKeyScope::SignerUpdate], ApiKeyScope::SignerUpdate, false, true; "scoped")]
+    #[test_case(&[ApiKeyScope::SignerUpdate], ApiKeyScope::LimitsAdmin, false, false; "out of scope")]
+    #[test_case(&[ApiKeyScope::SignerUpdate], ApiKeyScope::SignerUpdate, true, false; "revoked")]
+    #[test_case(&[], ApiKeyScope::ReadOnly, true, false; "revoked keys can't read")]
+    fn api_key_entry_allows_scope(
+        scopes: &[ApiKeyScope],
+        scope: ApiKeyScope,
+        revoked: bool,
+        expected: bool,
+    ) {
+        let entry = ApiKeyEntry {
+            scopes: scopes.to_vec(),
+            revoked,
+            ..Default::default()
+        };
+        assert_eq!(entry.allows(scope), expected);
+    }
+}
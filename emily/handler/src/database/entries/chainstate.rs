@@ -158,6 +158,11 @@ pub struct ApiStateEntry {
     pub version: u64,
     /// Api Status.
     pub api_status: ApiStatus,
+    /// Unix timestamp in seconds of when the chain tip of the api status last
+    /// changed. Missing from entries that were written before it was
+    /// recorded.
+    #[serde(default)]
+    pub updated_at: Option<u64>,
 }
 
 /// Api state entry implementation.
@@ -233,3 +238,99 @@ impl PrimaryIndexTrait for SpecialApiStateIndexInner {
         &settings.chainstate_table_name
     }
 }
+
+// Reconciliation Cursor Entry -------------------------------------------------
+
+/// Special hash value for the chainstate entry that stores how far the
+/// reconciler has read the sbtc-registry events.
+const RECONCILIATION_CURSOR_HASH_TOKEN: &str = "RECONCILIATION_CURSOR";
+
+/// Special height value for the chainstate entry that stores how far the
+/// reconciler has read the sbtc-registry events.
+const RECONCILIATION_CURSOR_HEIGHT_TOKEN: i32 = -2;
+
+/// A special key for the reconciliation cursor that, like the
+/// [`SpecialApiStateKey`], uses a constant hash and a negative height so that
+/// no regular access path can reach it.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SpecialReconciliationCursorKey {
+    /// Special token that takes the place of the hash field.
+    #[serde(rename = "Hash")]
+    cursor_token: String,
+    /// Special token that takes the place of the height field.
+    #[serde(rename = "Height")]
+    negative_two: i32,
+}
+
+/// Implementation of default for SpecialReconciliationCursorKey.
+impl Default for SpecialReconciliationCursorKey {
+    fn default() -> Self {
+        SpecialReconciliationCursorKey {
+            cursor_token: RECONCILIATION_CURSOR_HASH_TOKEN.to_string(),
+            negative_two: RECONCILIATION_CURSOR_HEIGHT_TOKEN,
+        }
+    }
+}
+
+/// How far the reconciler has read the events of the sbtc-registry
+/// contract.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReconciliationCursorEntry {
+    /// Special immutable table entry key.
+    #[serde(flatten)]
+    special_cursor_key: SpecialReconciliationCursorKey,
+    /// The stacks block height at or below which every registry event has
+    /// been read and the operation that it belongs to settled, so later
+    /// runs don't read those events again.
+    pub stacks_block_height: u64,
+}
+
+impl ReconciliationCursorEntry {
+    /// Create a cursor at the given stacks block height.
+    pub fn new(stacks_block_height: u64) -> Self {
+        ReconciliationCursorEntry {
+            special_cursor_key: SpecialReconciliationCursorKey::default(),
+            stacks_block_height,
+        }
+    }
+    /// Get the special key.
+    pub fn key() -> SpecialReconciliationCursorKey {
+        SpecialReconciliationCursorKey::default()
+    }
+}
+
+/// Implements the key trait for the reconciliation cursor key.
+impl KeyTrait for SpecialReconciliationCursorKey {
+    /// The type of the partition key.
+    type PartitionKey = i32;
+    /// the type of the sort key.
+    type SortKey = String;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "Height";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "Hash";
+}
+
+/// Implements the entry trait for the reconciliation cursor entry.
+impl EntryTrait for ReconciliationCursorEntry {
+    /// The type of the key for this entry type.
+    type Key = SpecialReconciliationCursorKey;
+    /// Extract the key from the reconciliation cursor entry.
+    fn key(&self) -> Self::Key {
+        ReconciliationCursorEntry::key()
+    }
+}
+
+/// Primary index struct.
+pub struct SpecialReconciliationCursorIndexInner;
+/// Reconciliation cursor primary index type.
+pub type SpecialReconciliationCursorIndex = PrimaryIndex<SpecialReconciliationCursorIndexInner>;
+/// Definition of Primary index trait.
+impl PrimaryIndexTrait for SpecialReconciliationCursorIndexInner {
+    type Entry = ReconciliationCursorEntry;
+    fn table_name(settings: &crate::context::Settings) -> &str {
+        &settings.chainstate_table_name
+    }
+}
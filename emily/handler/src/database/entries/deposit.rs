@@ -1,6 +1,7 @@
 //! Entries into the deposit table.
 
 use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
@@ -10,8 +11,9 @@ use crate::{
         common::{Fulfillment, Status},
         deposit::{
             requests::{DepositUpdate, UpdateDepositsRequestBody},
-            Deposit, DepositInfo, DepositParameters,
+            Deposit, DepositInfo, DepositParameters, DepositVerification,
         },
+        subscription::StatusEvent,
     },
     common::error::{Error, Inconsistency},
 };
@@ -44,6 +46,11 @@ pub struct DepositEntry {
     pub version: u64,
     /// Stacks address to received the deposited sBTC.
     pub recipient: String,
+    /// Hex encoded script pubkey of the output spent by the first input of
+    /// the deposit transaction, if it's known. Only present when known,
+    /// which keeps the sender index sparse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being deposited in satoshis.
     pub amount: u64,
     /// Deposit parameters.
@@ -69,6 +76,19 @@ pub struct DepositEntry {
     pub fulfillment: Option<Fulfillment>,
     /// History of this deposit transaction.
     pub history: Vec<DepositEvent>,
+    /// Unix time in seconds at which the deposit was first received.
+    /// Entries written before this was recorded don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Unix time in seconds at which the deposit was accepted. Only present
+    /// while the deposit is accepted or confirmed, which keeps the accepted
+    /// index sparse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_at: Option<u64>,
+    /// Whether the deposit was checked against its bitcoin transaction.
+    /// Entries written before verification existed are unverified.
+    #[serde(default)]
+    pub verification: DepositVerification,
 }
 
 /// Implements versioned entry trait for the deposit entry.
@@ -216,6 +236,9 @@ impl DepositEntry {
         } else {
             self.fulfillment = None;
         }
+        if !latest_event.status.counts_towards_rolling_volume() {
+            self.accepted_at = None;
+        }
         self.status = new_status;
         self.last_update_height = new_last_update_height;
         self.last_update_block_hash = latest_event.stacks_block_hash;
@@ -245,6 +268,7 @@ impl TryFrom<DepositEntry> for Deposit {
             bitcoin_txid: deposit_entry.key.bitcoin_txid,
             bitcoin_tx_output_index: deposit_entry.key.bitcoin_tx_output_index,
             recipient: deposit_entry.recipient,
+            sender: deposit_entry.sender,
             amount: deposit_entry.amount,
             last_update_height: deposit_entry.last_update_height,
             last_update_block_hash: deposit_entry.last_update_block_hash,
@@ -257,6 +281,26 @@ impl TryFrom<DepositEntry> for Deposit {
             reclaim_script: deposit_entry.reclaim_script,
             deposit_script: deposit_entry.deposit_script,
             fulfillment,
+            verification: deposit_entry.verification,
+        })
+    }
+}
+
+/// Convert the latest event in the history of a deposit entry into a status
+/// event for subscribers.
+impl TryFrom<&DepositEntry> for StatusEvent {
+    type Error = Error;
+    fn try_from(deposit_entry: &DepositEntry) -> Result<Self, Self::Error> {
+        let latest_event = deposit_entry.latest_event()?;
+        Ok(StatusEvent {
+            bitcoin_txid: Some(deposit_entry.key.bitcoin_txid.clone()),
+            bitcoin_tx_output_index: Some(deposit_entry.key.bitcoin_tx_output_index),
+            request_id: None,
+            recipient: deposit_entry.recipient.clone(),
+            status: (&latest_event.status).into(),
+            status_message: latest_event.message.clone(),
+            stacks_block_height: latest_event.stacks_block_height,
+            stacks_block_hash: latest_event.stacks_block_hash.clone(),
         })
     }
 }
@@ -363,6 +407,13 @@ pub struct DepositInfoEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// Hex encoded script pubkey of the output spent by the first input of
+    /// the deposit transaction, if it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Unix time in seconds at which the deposit was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -408,6 +459,7 @@ impl From<DepositInfoEntry> for DepositInfo {
             bitcoin_txid: deposit_info_entry.primary_index_key.bitcoin_txid,
             bitcoin_tx_output_index: deposit_info_entry.primary_index_key.bitcoin_tx_output_index,
             recipient: deposit_info_entry.recipient,
+            sender: deposit_info_entry.sender,
             amount: deposit_info_entry.amount,
             last_update_height: deposit_info_entry.key.last_update_height,
             last_update_block_hash: deposit_info_entry.last_update_block_hash,
@@ -467,6 +519,13 @@ pub struct DepositInfoByRecipientEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// Hex encoded script pubkey of the output spent by the first input of
+    /// the deposit transaction, if it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Unix time in seconds at which the deposit was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -513,6 +572,7 @@ impl From<DepositInfoByRecipientEntry> for DepositInfo {
             bitcoin_txid: deposit_info_entry.primary_index_key.bitcoin_txid,
             bitcoin_tx_output_index: deposit_info_entry.primary_index_key.bitcoin_tx_output_index,
             recipient: deposit_info_entry.key.recipient,
+            sender: deposit_info_entry.sender,
             amount: deposit_info_entry.amount,
             last_update_height: deposit_info_entry.key.last_update_height,
             last_update_block_hash: deposit_info_entry.last_update_block_hash,
@@ -523,6 +583,272 @@ impl From<DepositInfoByRecipientEntry> for DepositInfo {
     }
 }
 
+impl From<DepositInfoByRecipientEntry> for DepositInfoEntry {
+    fn from(entry: DepositInfoByRecipientEntry) -> Self {
+        DepositInfoEntry {
+            key: DepositInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.key.last_update_height,
+            },
+            primary_index_key: entry.primary_index_key,
+            recipient: entry.key.recipient,
+            amount: entry.amount,
+            reclaim_script: entry.reclaim_script,
+            deposit_script: entry.deposit_script,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+// Deposit info by sender entry ------------------------------------------------
+
+/// Key for deposit info entry that's indexed by sender.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositInfoBySenderEntryKey {
+    /// Hex encoded script pubkey of the output spent by the first input of
+    /// the deposit transaction.
+    pub sender: String,
+    /// The most recent Stacks block height the API was aware of when the deposit was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this height is the Stacks block height that contains that artifact.
+    pub last_update_height: u64,
+}
+
+/// Reduced version of the deposit data that is indexed by sender.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositInfoBySenderEntry {
+    /// Gsi key data.
+    #[serde(flatten)]
+    pub key: DepositInfoBySenderEntryKey,
+    /// Primary index key data.
+    #[serde(flatten)]
+    pub primary_index_key: DepositEntryKey,
+    /// The status of the entry.
+    #[serde(rename = "OpStatus")]
+    pub status: Status,
+    /// Stacks address to received the deposited sBTC.
+    pub recipient: String,
+    /// Amount of BTC being deposited in satoshis.
+    pub amount: u64,
+    /// The raw reclaim script.
+    pub reclaim_script: String,
+    /// The raw deposit script.
+    pub deposit_script: String,
+    /// The most recent Stacks block hash the API was aware of when the deposit was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this hash is the Stacks block hash that contains that artifact.
+    pub last_update_block_hash: String,
+    /// Unix time in seconds at which the deposit was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+/// Implements the key trait for the deposit by sender entry key.
+impl KeyTrait for DepositInfoBySenderEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "Sender";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "LastUpdateHeight";
+}
+
+/// Implements the entry trait for the deposit by sender entry.
+impl EntryTrait for DepositInfoBySenderEntry {
+    /// The type of the key for this entry type.
+    type Key = DepositInfoBySenderEntryKey;
+    /// Extract the key from the deposit info entry.
+    fn key(&self) -> Self::Key {
+        DepositInfoBySenderEntryKey {
+            sender: self.key.sender.clone(),
+            last_update_height: self.key.last_update_height,
+        }
+    }
+}
+
+/// Sender index struct.
+pub struct DepositTableBySenderSecondaryIndexInner;
+/// Deposit table sender index type.
+pub type DepositTableBySenderSecondaryIndex =
+    SecondaryIndex<DepositTableBySenderSecondaryIndexInner>;
+/// Definition of the sender index trait.
+impl SecondaryIndexTrait for DepositTableBySenderSecondaryIndexInner {
+    type PrimaryIndex = DepositTablePrimaryIndex;
+    type Entry = DepositInfoBySenderEntry;
+    const INDEX_NAME: &'static str = "DepositSender";
+}
+
+impl From<DepositInfoBySenderEntry> for DepositInfoEntry {
+    fn from(entry: DepositInfoBySenderEntry) -> Self {
+        DepositInfoEntry {
+            key: DepositInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.key.last_update_height,
+            },
+            primary_index_key: entry.primary_index_key,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            reclaim_script: entry.reclaim_script,
+            deposit_script: entry.deposit_script,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: Some(entry.key.sender),
+            created_at: entry.created_at,
+        }
+    }
+}
+
+// Deposit accepted entry ------------------------------------------------------
+
+/// Key for the deposit entries that count towards the rolling window volume.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositAcceptedEntryKey {
+    /// The status of the deposit.
+    #[serde(rename = "OpStatus")]
+    pub status: Status,
+    /// Unix time in seconds at which the deposit was accepted.
+    pub accepted_at: u64,
+}
+
+/// Reduced version of the deposit data holding what the rolling window
+/// volume is computed from.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DepositAcceptedEntry {
+    /// Gsi key data.
+    #[serde(flatten)]
+    pub key: DepositAcceptedEntryKey,
+    /// Primary index key data.
+    #[serde(flatten)]
+    pub primary_index_key: DepositEntryKey,
+    /// Stacks address to received the deposited sBTC.
+    pub recipient: String,
+    /// Amount of BTC being deposited in satoshis.
+    pub amount: u64,
+}
+
+/// Implements the key trait for the deposit accepted entry key.
+impl KeyTrait for DepositAcceptedEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = Status;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "OpStatus";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "AcceptedAt";
+}
+
+/// Implements the entry trait for the deposit accepted entry.
+impl EntryTrait for DepositAcceptedEntry {
+    /// The type of the key for this entry type.
+    type Key = DepositAcceptedEntryKey;
+    /// Extract the key from the deposit accepted entry.
+    fn key(&self) -> Self::Key {
+        DepositAcceptedEntryKey {
+            status: self.key.status.clone(),
+            accepted_at: self.key.accepted_at,
+        }
+    }
+}
+
+/// Accepted index struct.
+pub struct DepositTableAcceptedSecondaryIndexInner;
+/// Deposit table accepted index type.
+pub type DepositTableAcceptedSecondaryIndex =
+    SecondaryIndex<DepositTableAcceptedSecondaryIndexInner>;
+/// Definition of the accepted index trait.
+impl SecondaryIndexTrait for DepositTableAcceptedSecondaryIndexInner {
+    type PrimaryIndex = DepositTablePrimaryIndex;
+    type Entry = DepositAcceptedEntry;
+    const INDEX_NAME: &'static str = "DepositAccepted";
+}
+
+// Projections -----------------------------------------------------------------
+
+/// Project a deposit entry onto the status index.
+impl From<DepositEntry> for DepositInfoEntry {
+    fn from(entry: DepositEntry) -> Self {
+        DepositInfoEntry {
+            key: DepositInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            reclaim_script: entry.reclaim_script,
+            deposit_script: entry.deposit_script,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a deposit entry onto the recipient index.
+impl From<DepositEntry> for DepositInfoByRecipientEntry {
+    fn from(entry: DepositEntry) -> Self {
+        DepositInfoByRecipientEntry {
+            key: DepositInfoByRecipientEntryKey {
+                recipient: entry.recipient,
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            status: entry.status,
+            amount: entry.amount,
+            reclaim_script: entry.reclaim_script,
+            deposit_script: entry.deposit_script,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a deposit entry onto the sender index. Entries without a sender
+/// aren't in the index, so they get an empty sender.
+impl From<DepositEntry> for DepositInfoBySenderEntry {
+    fn from(entry: DepositEntry) -> Self {
+        DepositInfoBySenderEntry {
+            key: DepositInfoBySenderEntryKey {
+                sender: entry.sender.unwrap_or_default(),
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            status: entry.status,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            reclaim_script: entry.reclaim_script,
+            deposit_script: entry.deposit_script,
+            last_update_block_hash: entry.last_update_block_hash,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a deposit entry onto the accepted index. Entries that were never
+/// accepted aren't in the index, so they get an acceptance time of zero.
+impl From<DepositEntry> for DepositAcceptedEntry {
+    fn from(entry: DepositEntry) -> Self {
+        DepositAcceptedEntry {
+            key: DepositAcceptedEntryKey {
+                status: entry.status,
+                accepted_at: entry.accepted_at.unwrap_or_default(),
+            },
+            primary_index_key: entry.key,
+            recipient: entry.recipient,
+            amount: entry.amount,
+        }
+    }
+}
+
 // -----------------------------------------------------------------------------
 
 /// Validated version of the update deposit request.
@@ -647,6 +973,8 @@ pub struct DepositUpdatePackage {
     pub version: u64,
     /// Deposit event.
     pub event: DepositEvent,
+    /// Unix time in seconds at which the update is made.
+    pub timestamp: u64,
 }
 
 /// Implementation of deposit update package.
@@ -663,11 +991,16 @@ impl DepositUpdatePackage {
         entry
             .latest_event()?
             .ensure_following_event_is_valid(&update.event)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
         // Create the deposit update package.
         Ok(DepositUpdatePackage {
             key: entry.key.clone(),
             version: entry.version,
             event: update.event,
+            timestamp,
         })
     }
 }
@@ -697,6 +1030,7 @@ mod tests {
             key: Default::default(),
             version: 0,
             recipient: "".to_string(),
+            sender: None,
             amount: 0,
             parameters: Default::default(),
             status: Status::Pending,
@@ -706,6 +1040,9 @@ mod tests {
             last_update_block_hash: "".to_string(),
             fulfillment: None,
             history: vec![pending, accepted.clone()],
+            created_at: None,
+            accepted_at: None,
+            verification: Default::default(),
         };
 
         let update = ValidatedDepositUpdate {
@@ -736,6 +1073,7 @@ mod tests {
             key: Default::default(),
             version: 0,
             recipient: "".to_string(),
+            sender: None,
             amount: 0,
             parameters: Default::default(),
             status: Status::Pending,
@@ -745,6 +1083,9 @@ mod tests {
             last_update_block_hash: "".to_string(),
             fulfillment: None,
             history: vec![pending.clone()],
+            created_at: None,
+            accepted_at: None,
+            verification: Default::default(),
         };
 
         let update = ValidatedDepositUpdate {
@@ -793,6 +1134,7 @@ mod tests {
             key: Default::default(),
             version: 3,
             recipient: "test-recipient".to_string(),
+            sender: None,
             amount: 100,
             parameters: Default::default(),
             status: (&confirmed.status).into(),
@@ -802,6 +1144,9 @@ mod tests {
             last_update_block_hash: "hash6".to_string(),
             fulfillment: Some(fulfillment.clone()),
             history: vec![pending.clone(), accepted.clone(), confirmed.clone()],
+            created_at: None,
+            accepted_at: None,
+            verification: Default::default(),
         };
 
         // Ensure the deposit is valid.
@@ -4,7 +4,7 @@ use std::{hash::Hash, time::SystemTime};
 
 use serde::{Deserialize, Serialize};
 
-use crate::api::models::limits::AccountLimits;
+use crate::api::models::limits::{AccountLimits, Limits};
 
 use super::{EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait};
 
@@ -13,6 +13,9 @@ use super::{EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait};
 /// The special account name for the global cap.
 pub(crate) const GLOBAL_CAP_ACCOUNT: &str = "GLOBAL";
 
+/// The length of the rolling window when none is set; one day.
+pub(crate) const DEFAULT_ROLLING_WINDOW_SECONDS: u64 = 24 * 60 * 60;
+
 /// Limit table entry key. This is the primary index key.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -38,6 +41,15 @@ pub struct LimitEntry {
     pub per_deposit_cap: Option<u64>,
     /// Per withdrawal cap. If none then the cap is the same as the global per withdrawal cap.
     pub per_withdrawal_cap: Option<u64>,
+    /// Length in seconds of the rolling window that the rolling caps apply
+    /// to. Only set for the global cap.
+    pub rolling_window_seconds: Option<u64>,
+    /// Cap on the BTC pegged in within the rolling window. Only set for the
+    /// global cap.
+    pub rolling_deposit_cap: Option<u64>,
+    /// Cap on the sBTC pegged out within the rolling window. Only set for the
+    /// global cap.
+    pub rolling_withdrawal_cap: Option<u64>,
 }
 
 /// Convert from entry to its corresponding limit.
@@ -72,6 +84,29 @@ impl LimitEntry {
             per_deposit_minimum: account_limit.per_deposit_minimum,
             per_deposit_cap: account_limit.per_deposit_cap,
             per_withdrawal_cap: account_limit.per_withdrawal_cap,
+            rolling_window_seconds: None,
+            rolling_deposit_cap: None,
+            rolling_withdrawal_cap: None,
+        }
+    }
+    /// Create a new global limit entry from the limits and the chosen time.
+    /// The rolling caps are only kept for the global cap, since the signers
+    /// only enforce them globally.
+    pub fn from_global_limits(now: SystemTime, limits: &Limits) -> Self {
+        LimitEntry {
+            rolling_window_seconds: limits.rolling_window_seconds,
+            rolling_deposit_cap: limits.rolling_deposit_cap,
+            rolling_withdrawal_cap: limits.rolling_withdrawal_cap,
+            ..Self::from_account_limit(
+                GLOBAL_CAP_ACCOUNT.to_string(),
+                now,
+                &AccountLimits {
+                    peg_cap: limits.peg_cap,
+                    per_deposit_minimum: limits.per_deposit_minimum,
+                    per_deposit_cap: limits.per_deposit_cap,
+                    per_withdrawal_cap: limits.per_withdrawal_cap,
+                },
+            )
         }
     }
     /// Returns true if the limit entry has no limits set.
@@ -79,6 +114,14 @@ impl LimitEntry {
         self.peg_cap.is_none()
             && self.per_deposit_cap.is_none()
             && self.per_withdrawal_cap.is_none()
+            && self.rolling_deposit_cap.is_none()
+            && self.rolling_withdrawal_cap.is_none()
+    }
+
+    /// The length of the rolling window in seconds.
+    pub fn rolling_window_seconds(&self) -> u64 {
+        self.rolling_window_seconds
+            .unwrap_or(DEFAULT_ROLLING_WINDOW_SECONDS)
     }
 }
 
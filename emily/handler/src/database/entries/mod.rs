@@ -8,8 +8,10 @@
 //! The module is divided into several submodules and components:
 //!
 //! - **Submodules**:
+//!   - `api_key`: Manages entries for the api key table.
 //!   - `chainstate`: Handles entries related to the chain state table.
 //!   - `deposit`: Manages entries for the deposit table.
+//!   - `webhook`: Manages entries for the webhook table.
 //!   - `withdrawal`: Manages entries for the withdrawal table.
 //!
 //! - **Structures**:
@@ -46,7 +48,7 @@
 
 use std::{collections::HashMap, fmt::Debug};
 
-use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::types::{AttributeValue, Select};
 #[cfg(feature = "testing")]
 use aws_sdk_dynamodb::types::{DeleteRequest, WriteRequest};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -59,12 +61,16 @@ use crate::{
     context::Settings,
 };
 
+/// Api key table entries.
+pub mod api_key;
 /// Chainstate table entries.
 pub mod chainstate;
 /// Deposit table entries.
 pub mod deposit;
 /// Limits table entries.
 pub mod limits;
+/// Webhook table entries.
+pub mod webhook;
 /// Withdrawal table entries.
 pub mod withdrawal;
 
@@ -100,6 +106,16 @@ pub enum StatusEntry {
     Failed,
 }
 
+impl StatusEntry {
+    /// Whether an operation in this status counts towards the rolling window
+    /// volume. Accepted operations are counted as well as confirmed ones so
+    /// that the signers cannot accept more than the window allows before
+    /// anything confirms.
+    pub fn counts_towards_rolling_volume(&self) -> bool {
+        matches!(self, StatusEntry::Accepted | StatusEntry::Confirmed(_))
+    }
+}
+
 impl From<&StatusEntry> for Status {
     fn from(value: &StatusEntry) -> Self {
         match value {
@@ -272,6 +288,38 @@ pub(crate) trait TableIndexTrait {
         Ok((entries, next_token))
     }
 
+    /// Generic table count of the items with a given partition key. Only the
+    /// count is returned for each page, so no items are sent back.
+    async fn count_with_partition_key(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        partition_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    ) -> Result<u64, Error> {
+        let mut count: u64 = 0;
+        let mut exclusive_start_key = None;
+        loop {
+            let query_output = dynamodb_client
+                .query()
+                .table_name(Self::table_name(settings))
+                .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
+                .set_exclusive_start_key(exclusive_start_key)
+                .select(Select::Count)
+                .key_condition_expression("#pk = :v")
+                .expression_attribute_names(
+                    "#pk",
+                    <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
+                )
+                .expression_attribute_values(":v", serde_dynamo::to_attribute_value(partition_key)?)
+                .send()
+                .await?;
+            count += query_output.count as u64;
+            exclusive_start_key = query_output.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                return Ok(count);
+            }
+        }
+    }
+
     /// Generic table query for all attributes with a given primary key.
     async fn query_with_partition_and_sort_key(
         dynamodb_client: &aws_sdk_dynamodb::Client,
@@ -556,7 +604,7 @@ where
 }
 
 /// Turns an optional key into a token.
-fn tokenize<T>(key: T) -> Result<String, Error>
+pub(crate) fn tokenize<T>(key: T) -> Result<String, Error>
 where
     T: Serialize,
 {
@@ -584,7 +632,7 @@ where
 }
 
 /// Turns an optional token into a key.
-fn detokenize<T>(token: String) -> Result<T, Error>
+pub(crate) fn detokenize<T>(token: String) -> Result<T, Error>
 where
     T: for<'de> Deserialize<'de>,
 {
@@ -0,0 +1,294 @@
+//! Entries into the webhook table.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::common::Status;
+use crate::api::models::subscription::{StatusEvent, StatusEventFilter, Webhook};
+
+use super::{
+    EntryTrait, KeyTrait, PrimaryIndex, PrimaryIndexTrait, SecondaryIndex, SecondaryIndexTrait,
+};
+
+/// The filter key of webhooks whose filter has no fields set.
+const ANY_FILTER_KEY: &str = "any";
+
+// Webhook entry ---------------------------------------------------------------
+
+/// Webhook table entry key. This is the primary index key.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WebhookEntryKey {
+    /// The id of the webhook.
+    pub webhook_id: String,
+    /// The unix timestamp in seconds of when the webhook was registered.
+    pub created_at: u64,
+}
+
+/// Webhook table entry.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WebhookEntry {
+    /// Webhook entry key.
+    #[serde(flatten)]
+    pub key: WebhookEntryKey,
+    /// The id of the api key that registered the webhook. Only that key
+    /// can list or delete the webhook.
+    #[serde(default)]
+    pub owner: String,
+    /// The URL that status events are posted to.
+    pub url: String,
+    /// The secret used to sign the payloads posted to the URL.
+    pub secret: String,
+    /// Which status events are posted to the webhook.
+    pub filter: StatusEventFilter,
+    /// The key that the webhook is indexed by, derived from the filter with
+    /// [`filter_key`].
+    pub filter_key: String,
+}
+
+impl WebhookEntry {
+    /// Create a webhook entry, deriving its filter key from the filter.
+    pub fn new(
+        key: WebhookEntryKey,
+        owner: String,
+        url: String,
+        secret: String,
+        filter: StatusEventFilter,
+    ) -> Self {
+        let filter_key = filter_key(&filter);
+        WebhookEntry {
+            key,
+            owner,
+            url,
+            secret,
+            filter,
+            filter_key,
+        }
+    }
+}
+
+/// The key that webhooks with the given filter are indexed by. The key is
+/// taken from the most selective field that is set, so every webhook
+/// whose filter matches an event has one of the keys returned by
+/// [`event_filter_keys`] for that event.
+pub fn filter_key(filter: &StatusEventFilter) -> String {
+    if let Some(txid) = &filter.bitcoin_txid {
+        format!("txid#{txid}")
+    } else if let Some(recipient) = &filter.recipient {
+        format!("recipient#{recipient}")
+    } else if let Some(status) = &filter.status {
+        status_filter_key(status)
+    } else {
+        ANY_FILTER_KEY.to_string()
+    }
+}
+
+/// The filter keys of the webhooks that may want the event.
+pub fn event_filter_keys(event: &StatusEvent) -> Vec<String> {
+    let mut keys = vec![
+        ANY_FILTER_KEY.to_string(),
+        format!("recipient#{}", event.recipient),
+        status_filter_key(&event.status),
+    ];
+    if let Some(txid) = &event.bitcoin_txid {
+        keys.push(format!("txid#{txid}"));
+    }
+    keys
+}
+
+/// The filter key of webhooks that only filter by status. The status names
+/// match their serialized form.
+fn status_filter_key(status: &Status) -> String {
+    format!("status#{status:?}").to_lowercase()
+}
+
+/// Convert from entry to its corresponding webhook, leaving out the secret.
+impl From<WebhookEntry> for Webhook {
+    fn from(webhook_entry: WebhookEntry) -> Self {
+        Webhook {
+            id: webhook_entry.key.webhook_id,
+            url: webhook_entry.url,
+            filter: webhook_entry.filter,
+        }
+    }
+}
+
+/// Implements the key trait for the webhook entry key.
+impl KeyTrait for WebhookEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "WebhookId";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "CreatedAt";
+}
+
+/// Implements the entry trait for the webhook entry.
+impl EntryTrait for WebhookEntry {
+    /// The type of the key for this entry type.
+    type Key = WebhookEntryKey;
+    /// Extract the key from the webhook entry.
+    fn key(&self) -> Self::Key {
+        self.key.clone()
+    }
+}
+
+/// Primary index struct.
+pub struct WebhookTablePrimaryIndexInner;
+/// Webhook table primary index type.
+pub type WebhookTablePrimaryIndex = PrimaryIndex<WebhookTablePrimaryIndexInner>;
+/// Definition of Primary index trait.
+impl PrimaryIndexTrait for WebhookTablePrimaryIndexInner {
+    type Entry = WebhookEntry;
+    fn table_name(settings: &crate::context::Settings) -> &str {
+        &settings.webhook_table_name
+    }
+}
+
+// Webhook by filter entry -----------------------------------------------------
+
+/// Webhook by filter table entry key. This is the secondary index key.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WebhookByFilterEntryKey {
+    /// The key that the webhook is indexed by.
+    pub filter_key: String,
+    /// The id of the webhook.
+    pub webhook_id: String,
+}
+
+/// Webhook by filter table entry. The index projects every attribute so
+/// that webhooks can be delivered to straight from it.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WebhookByFilterEntry {
+    /// Gsi key data.
+    #[serde(flatten)]
+    pub key: WebhookByFilterEntryKey,
+    /// The unix timestamp in seconds of when the webhook was registered.
+    pub created_at: u64,
+    /// The id of the api key that registered the webhook.
+    #[serde(default)]
+    pub owner: String,
+    /// The URL that status events are posted to.
+    pub url: String,
+    /// The secret used to sign the payloads posted to the URL.
+    pub secret: String,
+    /// Which status events are posted to the webhook.
+    pub filter: StatusEventFilter,
+}
+
+/// Implements the key trait for the webhook by filter entry key.
+impl KeyTrait for WebhookByFilterEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = String;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "FilterKey";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "WebhookId";
+}
+
+/// Implements the entry trait for the webhook by filter entry.
+impl EntryTrait for WebhookByFilterEntry {
+    /// The type of the key for this entry type.
+    type Key = WebhookByFilterEntryKey;
+    /// Extract the key from the webhook by filter entry.
+    fn key(&self) -> Self::Key {
+        self.key.clone()
+    }
+}
+
+/// Filter index struct.
+pub struct WebhookTableByFilterSecondaryIndexInner;
+/// Webhook table filter index type.
+pub type WebhookTableByFilterSecondaryIndex =
+    SecondaryIndex<WebhookTableByFilterSecondaryIndexInner>;
+/// Definition of the filter index trait.
+impl SecondaryIndexTrait for WebhookTableByFilterSecondaryIndexInner {
+    type PrimaryIndex = WebhookTablePrimaryIndex;
+    type Entry = WebhookByFilterEntry;
+    const INDEX_NAME: &'static str = "WebhookFilter";
+}
+
+impl From<WebhookByFilterEntry> for WebhookEntry {
+    fn from(entry: WebhookByFilterEntry) -> Self {
+        WebhookEntry {
+            key: WebhookEntryKey {
+                webhook_id: entry.key.webhook_id,
+                created_at: entry.created_at,
+            },
+            owner: entry.owner,
+            url: entry.url,
+            secret: entry.secret,
+            filter: entry.filter,
+            filter_key: entry.key.filter_key,
+        }
+    }
+}
+
+// Projections -----------------------------------------------------------------
+
+/// Project a webhook entry onto the filter index.
+impl From<WebhookEntry> for WebhookByFilterEntry {
+    fn from(entry: WebhookEntry) -> Self {
+        WebhookByFilterEntry {
+            key: WebhookByFilterEntryKey {
+                filter_key: entry.filter_key,
+                webhook_id: entry.key.webhook_id,
+            },
+            created_at: entry.key.created_at,
+            owner: entry.owner,
+            url: entry.url,
+            secret: entry.secret,
+            filter: entry.filter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(Some("txid"), Some("recipient"), Some(Status::Accepted), "txid#txid"; "txid")]
+    #[test_case(None, Some("recipient"), Some(Status::Accepted), "recipient#recipient"; "recipient")]
+    #[test_case(None, None, Some(Status::Accepted), "status#accepted"; "status")]
+    #[test_case(None, None, None, "any"; "empty filter")]
+    fn matching_webhooks_are_found_by_an_event_filter_key(
+        bitcoin_txid: Option<&str>,
+        recipient: Option<&str>,
+        status: Option<Status>,
+        expected: &str,
+    ) {
+        let filter = StatusEventFilter {
+            bitcoin_txid: bitcoin_txid.map(str::to_string),
+            recipient: recipient.map(str::to_string),
+            status,
+        };
+        let event = StatusEvent {
+            bitcoin_txid: Some("txid".to_string()),
+            bitcoin_tx_output_index: Some(0),
+            recipient: "recipient".to_string(),
+            status: Status::Accepted,
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&event));
+        assert_eq!(filter_key(&filter), expected);
+        assert!(event_filter_keys(&event).contains(&filter_key(&filter)));
+    }
+
+    #[test]
+    fn status_filter_keys_use_the_serialized_status() {
+        let status = serde_json::to_value(Status::Reprocessing).unwrap();
+        assert_eq!(
+            status_filter_key(&Status::Reprocessing),
+            format!("status#{}", status.as_str().unwrap())
+        );
+    }
+}
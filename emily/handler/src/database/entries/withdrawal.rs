@@ -1,6 +1,7 @@
 //! Entries into the withdrawal table.
 
 use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,7 @@ use crate::{
     api::models::{
         chainstate::Chainstate,
         common::Status,
+        subscription::StatusEvent,
         withdrawal::{
             requests::{UpdateWithdrawalsRequestBody, WithdrawalUpdate},
             Withdrawal, WithdrawalInfo, WithdrawalParameters,
@@ -46,6 +48,10 @@ pub struct WithdrawalEntry {
     pub version: u64,
     /// Stacks address to received the withdrawn sBTC.
     pub recipient: String,
+    /// The Stacks principal that initiated the withdrawal, if it's known.
+    /// Only present when known, which keeps the sender index sparse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
     /// Amount of BTC being withdrawn in satoshis.
     pub amount: u64,
     /// Withdrawal parameters.
@@ -64,6 +70,14 @@ pub struct WithdrawalEntry {
     pub last_update_block_hash: String,
     /// History of this withdrawal transaction.
     pub history: Vec<WithdrawalEvent>,
+    /// Unix time in seconds at which the withdrawal was first received.
+    /// Entries written before this was recorded don't have it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Unix time in seconds at which the withdrawal was accepted. Only
+    /// present while the withdrawal is accepted or confirmed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_at: Option<u64>,
 }
 
 /// Implements versioned entry trait for the withdrawal entry.
@@ -165,6 +179,9 @@ impl WithdrawalEntry {
         let new_status: Status = (&latest_event.status).into();
         let new_last_update_height: u64 = latest_event.stacks_block_height;
         // Set variables.
+        if !latest_event.status.counts_towards_rolling_volume() {
+            self.accepted_at = None;
+        }
         self.status = new_status;
         self.last_update_height = new_last_update_height;
         self.last_update_block_hash = latest_event.stacks_block_hash.clone();
@@ -194,6 +211,7 @@ impl TryFrom<WithdrawalEntry> for Withdrawal {
             stacks_block_hash: withdrawal_entry.key.stacks_block_hash,
             stacks_block_height: withdrawal_entry.stacks_block_height,
             recipient: withdrawal_entry.recipient,
+            sender: withdrawal_entry.sender,
             amount: withdrawal_entry.amount,
             last_update_height: withdrawal_entry.last_update_height,
             last_update_block_hash: withdrawal_entry.last_update_block_hash,
@@ -207,6 +225,25 @@ impl TryFrom<WithdrawalEntry> for Withdrawal {
     }
 }
 
+/// Convert the latest event in the history of a withdrawal entry into a status
+/// event for subscribers.
+impl TryFrom<&WithdrawalEntry> for StatusEvent {
+    type Error = Error;
+    fn try_from(withdrawal_entry: &WithdrawalEntry) -> Result<Self, Self::Error> {
+        let latest_event = withdrawal_entry.latest_event()?;
+        Ok(StatusEvent {
+            bitcoin_txid: None,
+            bitcoin_tx_output_index: None,
+            request_id: Some(withdrawal_entry.key.request_id),
+            recipient: withdrawal_entry.recipient.clone(),
+            status: (&latest_event.status).into(),
+            status_message: latest_event.message.clone(),
+            stacks_block_height: latest_event.stacks_block_height,
+            stacks_block_hash: latest_event.stacks_block_hash.clone(),
+        })
+    }
+}
+
 /// Withdrawal parameters entry.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -341,6 +378,12 @@ pub struct WithdrawalInfoEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// The Stacks principal that initiated the withdrawal, if it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Unix time in seconds at which the withdrawal was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
 }
 
 /// Implements the key trait for the withdrawal info entry key.
@@ -368,6 +411,17 @@ impl EntryTrait for WithdrawalInfoEntry {
     }
 }
 
+impl WithdrawalInfoEntry {
+    /// Whether the withdrawal was requested in a block that isn't in the chain
+    /// that ends at the given chainstate, which is the case when the block is
+    /// above the chainstate or is a different block at the same height.
+    pub fn is_orphaned_by(&self, chainstate: &Chainstate) -> bool {
+        self.stacks_block_height > chainstate.stacks_block_height
+            || (self.stacks_block_height == chainstate.stacks_block_height
+                && self.primary_index_key.stacks_block_hash != chainstate.stacks_block_hash)
+    }
+}
+
 /// Primary index struct.
 pub struct WithdrawalTableSecondaryIndexInner;
 /// Withdrawal table primary index type.
@@ -387,6 +441,7 @@ impl From<WithdrawalInfoEntry> for WithdrawalInfo {
             stacks_block_hash: withdrawal_info_entry.primary_index_key.stacks_block_hash,
             stacks_block_height: withdrawal_info_entry.stacks_block_height,
             recipient: withdrawal_info_entry.recipient,
+            sender: withdrawal_info_entry.sender,
             amount: withdrawal_info_entry.amount,
             last_update_height: withdrawal_info_entry.key.last_update_height,
             last_update_block_hash: withdrawal_info_entry.last_update_block_hash,
@@ -395,6 +450,345 @@ impl From<WithdrawalInfoEntry> for WithdrawalInfo {
     }
 }
 
+// Withdrawal info by recipient entry ------------------------------------------
+
+/// Key for withdrawal info entry that's indexed by recipient.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalInfoByRecipientEntryKey {
+    /// The recipient of the withdrawal.
+    pub recipient: String,
+    /// The most recent Stacks block height the API was aware of when the withdrawal was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this height is the Stacks block height that contains that artifact.
+    pub last_update_height: u64,
+}
+
+/// Reduced version of the withdrawal data that is indexed by recipient.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalInfoByRecipientEntry {
+    /// Secondary index key.
+    #[serde(flatten)]
+    pub key: WithdrawalInfoByRecipientEntryKey,
+    /// Primary index key.
+    #[serde(flatten)]
+    pub primary_index_key: WithdrawalEntryKey,
+    /// The status of the withdrawal.
+    #[serde(rename = "OpStatus")]
+    pub status: Status,
+    /// The height of the Stacks block in which this request id was initiated.
+    pub stacks_block_height: u64,
+    /// Amount of BTC being withdrawn in satoshis.
+    pub amount: u64,
+    /// The most recent Stacks block hash the API was aware of when the withdrawal was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this hash is the Stacks block hash that contains that artifact.
+    pub last_update_block_hash: String,
+    /// The Stacks principal that initiated the withdrawal, if it's known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// Unix time in seconds at which the withdrawal was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+/// Implements the key trait for the withdrawal by recipient entry key.
+impl KeyTrait for WithdrawalInfoByRecipientEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "Recipient";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "LastUpdateHeight";
+}
+
+/// Implements the entry trait for the withdrawal by recipient entry.
+impl EntryTrait for WithdrawalInfoByRecipientEntry {
+    /// The type of the key for this entry type.
+    type Key = WithdrawalInfoByRecipientEntryKey;
+    /// Extract the key from the withdrawal info entry.
+    fn key(&self) -> Self::Key {
+        WithdrawalInfoByRecipientEntryKey {
+            recipient: self.key.recipient.clone(),
+            last_update_height: self.key.last_update_height,
+        }
+    }
+}
+
+/// Recipient index struct.
+pub struct WithdrawalTableByRecipientSecondaryIndexInner;
+/// Withdrawal table recipient index type.
+pub type WithdrawalTableByRecipientSecondaryIndex =
+    SecondaryIndex<WithdrawalTableByRecipientSecondaryIndexInner>;
+/// Definition of the recipient index trait.
+impl SecondaryIndexTrait for WithdrawalTableByRecipientSecondaryIndexInner {
+    type PrimaryIndex = WithdrawalTablePrimaryIndex;
+    type Entry = WithdrawalInfoByRecipientEntry;
+    const INDEX_NAME: &'static str = "WithdrawalRecipient";
+}
+
+impl From<WithdrawalInfoByRecipientEntry> for WithdrawalInfoEntry {
+    fn from(entry: WithdrawalInfoByRecipientEntry) -> Self {
+        WithdrawalInfoEntry {
+            key: WithdrawalInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.key.last_update_height,
+            },
+            primary_index_key: entry.primary_index_key,
+            stacks_block_height: entry.stacks_block_height,
+            recipient: entry.key.recipient,
+            amount: entry.amount,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+// Withdrawal info by sender entry ---------------------------------------------
+
+/// Key for withdrawal info entry that's indexed by sender.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalInfoBySenderEntryKey {
+    /// The Stacks principal that initiated the withdrawal.
+    pub sender: String,
+    /// The most recent Stacks block height the API was aware of when the withdrawal was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this height is the Stacks block height that contains that artifact.
+    pub last_update_height: u64,
+}
+
+/// Reduced version of the withdrawal data that is indexed by sender.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalInfoBySenderEntry {
+    /// Secondary index key.
+    #[serde(flatten)]
+    pub key: WithdrawalInfoBySenderEntryKey,
+    /// Primary index key.
+    #[serde(flatten)]
+    pub primary_index_key: WithdrawalEntryKey,
+    /// The status of the withdrawal.
+    #[serde(rename = "OpStatus")]
+    pub status: Status,
+    /// The height of the Stacks block in which this request id was initiated.
+    pub stacks_block_height: u64,
+    /// Stacks address to received the withdrawn sBTC.
+    pub recipient: String,
+    /// Amount of BTC being withdrawn in satoshis.
+    pub amount: u64,
+    /// The most recent Stacks block hash the API was aware of when the withdrawal was last
+    /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
+    /// then this hash is the Stacks block hash that contains that artifact.
+    pub last_update_block_hash: String,
+    /// Unix time in seconds at which the withdrawal was first received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+}
+
+/// Implements the key trait for the withdrawal by sender entry key.
+impl KeyTrait for WithdrawalInfoBySenderEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = String;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "Sender";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "LastUpdateHeight";
+}
+
+/// Implements the entry trait for the withdrawal by sender entry.
+impl EntryTrait for WithdrawalInfoBySenderEntry {
+    /// The type of the key for this entry type.
+    type Key = WithdrawalInfoBySenderEntryKey;
+    /// Extract the key from the withdrawal info entry.
+    fn key(&self) -> Self::Key {
+        WithdrawalInfoBySenderEntryKey {
+            sender: self.key.sender.clone(),
+            last_update_height: self.key.last_update_height,
+        }
+    }
+}
+
+/// Sender index struct.
+pub struct WithdrawalTableBySenderSecondaryIndexInner;
+/// Withdrawal table sender index type.
+pub type WithdrawalTableBySenderSecondaryIndex =
+    SecondaryIndex<WithdrawalTableBySenderSecondaryIndexInner>;
+/// Definition of the sender index trait.
+impl SecondaryIndexTrait for WithdrawalTableBySenderSecondaryIndexInner {
+    type PrimaryIndex = WithdrawalTablePrimaryIndex;
+    type Entry = WithdrawalInfoBySenderEntry;
+    const INDEX_NAME: &'static str = "WithdrawalSender";
+}
+
+impl From<WithdrawalInfoBySenderEntry> for WithdrawalInfoEntry {
+    fn from(entry: WithdrawalInfoBySenderEntry) -> Self {
+        WithdrawalInfoEntry {
+            key: WithdrawalInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.key.last_update_height,
+            },
+            primary_index_key: entry.primary_index_key,
+            stacks_block_height: entry.stacks_block_height,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: Some(entry.key.sender),
+            created_at: entry.created_at,
+        }
+    }
+}
+
+// Withdrawal accepted entry ---------------------------------------------------
+
+/// Key for the withdrawal entries that count towards the rolling window
+/// volume.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalAcceptedEntryKey {
+    /// The status of the withdrawal.
+    #[serde(rename = "OpStatus")]
+    pub status: Status,
+    /// Unix time in seconds at which the withdrawal was accepted.
+    pub accepted_at: u64,
+}
+
+/// Reduced version of the withdrawal data holding what the rolling window
+/// volume is computed from.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WithdrawalAcceptedEntry {
+    /// Secondary index key.
+    #[serde(flatten)]
+    pub key: WithdrawalAcceptedEntryKey,
+    /// Primary index key.
+    #[serde(flatten)]
+    pub primary_index_key: WithdrawalEntryKey,
+    /// Stacks address to received the withdrawn sBTC.
+    pub recipient: String,
+    /// Amount of BTC being withdrawn in satoshis.
+    pub amount: u64,
+}
+
+/// Implements the key trait for the withdrawal accepted entry key.
+impl KeyTrait for WithdrawalAcceptedEntryKey {
+    /// The type of the partition key.
+    type PartitionKey = Status;
+    /// the type of the sort key.
+    type SortKey = u64;
+    /// The table field name of the partition key.
+    const PARTITION_KEY_NAME: &'static str = "OpStatus";
+    /// The table field name of the sort key.
+    const SORT_KEY_NAME: &'static str = "AcceptedAt";
+}
+
+/// Implements the entry trait for the withdrawal accepted entry.
+impl EntryTrait for WithdrawalAcceptedEntry {
+    /// The type of the key for this entry type.
+    type Key = WithdrawalAcceptedEntryKey;
+    /// Extract the key from the withdrawal accepted entry.
+    fn key(&self) -> Self::Key {
+        WithdrawalAcceptedEntryKey {
+            status: self.key.status.clone(),
+            accepted_at: self.key.accepted_at,
+        }
+    }
+}
+
+/// Accepted index struct.
+pub struct WithdrawalTableAcceptedSecondaryIndexInner;
+/// Withdrawal table accepted index type.
+pub type WithdrawalTableAcceptedSecondaryIndex =
+    SecondaryIndex<WithdrawalTableAcceptedSecondaryIndexInner>;
+/// Definition of the accepted index trait.
+impl SecondaryIndexTrait for WithdrawalTableAcceptedSecondaryIndexInner {
+    type PrimaryIndex = WithdrawalTablePrimaryIndex;
+    type Entry = WithdrawalAcceptedEntry;
+    const INDEX_NAME: &'static str = "WithdrawalAccepted";
+}
+
+// Projections -----------------------------------------------------------------
+
+/// Project a withdrawal entry onto the status index.
+impl From<WithdrawalEntry> for WithdrawalInfoEntry {
+    fn from(entry: WithdrawalEntry) -> Self {
+        WithdrawalInfoEntry {
+            key: WithdrawalInfoEntryKey {
+                status: entry.status,
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            stacks_block_height: entry.stacks_block_height,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a withdrawal entry onto the recipient index.
+impl From<WithdrawalEntry> for WithdrawalInfoByRecipientEntry {
+    fn from(entry: WithdrawalEntry) -> Self {
+        WithdrawalInfoByRecipientEntry {
+            key: WithdrawalInfoByRecipientEntryKey {
+                recipient: entry.recipient,
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            status: entry.status,
+            stacks_block_height: entry.stacks_block_height,
+            amount: entry.amount,
+            last_update_block_hash: entry.last_update_block_hash,
+            sender: entry.sender,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a withdrawal entry onto the sender index. Entries without a
+/// sender aren't in the index, so they get an empty sender.
+impl From<WithdrawalEntry> for WithdrawalInfoBySenderEntry {
+    fn from(entry: WithdrawalEntry) -> Self {
+        WithdrawalInfoBySenderEntry {
+            key: WithdrawalInfoBySenderEntryKey {
+                sender: entry.sender.unwrap_or_default(),
+                last_update_height: entry.last_update_height,
+            },
+            primary_index_key: entry.key,
+            status: entry.status,
+            stacks_block_height: entry.stacks_block_height,
+            recipient: entry.recipient,
+            amount: entry.amount,
+            last_update_block_hash: entry.last_update_block_hash,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Project a withdrawal entry onto the accepted index. Entries that were
+/// never accepted aren't in the index, so they get an acceptance time of zero.
+impl From<WithdrawalEntry> for WithdrawalAcceptedEntry {
+    fn from(entry: WithdrawalEntry) -> Self {
+        WithdrawalAcceptedEntry {
+            key: WithdrawalAcceptedEntryKey {
+                status: entry.status,
+                accepted_at: entry.accepted_at.unwrap_or_default(),
+            },
+            primary_index_key: entry.key,
+            recipient: entry.recipient,
+            amount: entry.amount,
+        }
+    }
+}
+
 /// Validated version of the update withdrawal request.
 #[derive(Clone, Default, Debug, Eq, PartialEq, Hash)]
 pub struct ValidatedUpdateWithdrawalRequest {
@@ -515,6 +909,8 @@ pub struct WithdrawalUpdatePackage {
     pub version: u64,
     /// Withdrawal event.
     pub event: WithdrawalEvent,
+    /// Unix time in seconds at which the update is made.
+    pub timestamp: u64,
 }
 
 /// Implementation of withdrawal update package.
@@ -534,11 +930,16 @@ impl WithdrawalUpdatePackage {
         entry
             .latest_event()?
             .ensure_following_event_is_valid(&update.event)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+            .as_secs();
         // Create the withdrawal update package.
         Ok(WithdrawalUpdatePackage {
             key: entry.key.clone(),
             version: entry.version,
             event: update.event,
+            timestamp,
         })
     }
 }
@@ -552,7 +953,7 @@ mod tests {
         api::models::common::Status,
         database::entries::withdrawal::{
             ValidatedWithdrawalUpdate, WithdrawalEntry, WithdrawalEntryKey, WithdrawalEvent,
-            WithdrawalParametersEntry,
+            WithdrawalInfoEntry, WithdrawalParametersEntry,
         },
     };
     use test_case::test_case;
@@ -582,12 +983,15 @@ mod tests {
             stacks_block_height: 1,
             version: 1,
             recipient: "recipient".to_string(),
+            sender: None,
             amount: 1,
             parameters: WithdrawalParametersEntry { max_fee: 1 },
             status: Status::Pending,
             last_update_height: 1,
             last_update_block_hash: "hash".to_string(),
             history: vec![pending, failed.clone()],
+            created_at: None,
+            accepted_at: None,
         };
 
         let withdrawal_update = ValidatedWithdrawalUpdate { request_id: 1, event: failed };
@@ -624,12 +1028,15 @@ mod tests {
             stacks_block_height: 1,
             version: 1,
             recipient: "recipient".to_string(),
+            sender: None,
             amount: 1,
             parameters: WithdrawalParametersEntry { max_fee: 1 },
             status: Status::Pending,
             last_update_height: 1,
             last_update_block_hash: "hash".to_string(),
             history: vec![pending.clone()],
+            created_at: None,
+            accepted_at: None,
         };
 
         let withdrawal_update = ValidatedWithdrawalUpdate { request_id: 1, event: failed };
@@ -683,12 +1090,15 @@ mod tests {
             stacks_block_height: 1,
             version: 1,
             recipient: "test-recipient".to_string(),
+            sender: None,
             amount: 1,
             parameters: WithdrawalParametersEntry { max_fee: 1 },
             status: Status::Confirmed,
             last_update_height: 6,
             last_update_block_hash: "hash6".to_string(),
             history: vec![pending.clone(), accepted.clone(), confirmed.clone()],
+            created_at: None,
+            accepted_at: None,
         };
 
         // Ensure the withdrawal is valid.
@@ -723,4 +1133,28 @@ mod tests {
         assert_eq!(latest_event.stacks_block_hash, expected_hash);
         assert_eq!(latest_event.status, expected_status);
     }
+
+    #[test_case(4, "hash4", false; "below the chainstate")]
+    #[test_case(5, "hash5", false; "in the chainstate")]
+    #[test_case(5, "other-hash5", true; "beside the chainstate")]
+    #[test_case(6, "hash6", true; "above the chainstate")]
+    fn withdrawals_requested_outside_the_chain_are_orphaned(
+        height: u64,
+        hash: &str,
+        orphaned: bool,
+    ) {
+        let withdrawal = WithdrawalInfoEntry {
+            primary_index_key: WithdrawalEntryKey {
+                request_id: 1,
+                stacks_block_hash: hash.to_string(),
+            },
+            stacks_block_height: height,
+            ..Default::default()
+        };
+        let chainstate = Chainstate {
+            stacks_block_height: 5,
+            stacks_block_hash: "hash5".to_string(),
+        };
+        assert_eq!(withdrawal.is_orphaned_by(&chainstate), orphaned);
+    }
 }
@@ -4,3 +4,5 @@
 pub mod accessors;
 /// Entries into the database.
 pub mod entries;
+/// Storage backends.
+pub mod storage;
@@ -0,0 +1,586 @@
+//! DynamoDB implementation of the Emily storage trait.
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::Client;
+use serde::de::DeserializeOwned;
+use serde_dynamo::Item;
+
+use crate::api::models::common::Status;
+use crate::common::error::Error;
+use crate::context::Settings;
+use crate::database::entries::api_key::{ApiKeyEntry, ApiKeyTablePrimaryIndex};
+use crate::database::entries::chainstate::{
+    ApiStateEntry, ChainstateEntry, ChainstateEntryKey, ChainstateTablePrimaryIndex,
+    ReconciliationCursorEntry, SpecialApiStateIndex, SpecialReconciliationCursorIndex,
+};
+use crate::database::entries::deposit::{
+    DepositAcceptedEntry, DepositEntry, DepositEntryKey, DepositInfoByRecipientEntry,
+    DepositInfoBySenderEntry, DepositInfoEntry, DepositTableAcceptedSecondaryIndex,
+    DepositTableByRecipientSecondaryIndex, DepositTableBySenderSecondaryIndex,
+    DepositTablePrimaryIndex, DepositTableSecondaryIndex, DepositUpdatePackage,
+};
+use crate::database::entries::limits::{LimitEntry, LimitTablePrimaryIndex};
+use crate::database::entries::webhook::{
+    WebhookByFilterEntry, WebhookEntry, WebhookEntryKey, WebhookTableByFilterSecondaryIndex,
+    WebhookTablePrimaryIndex,
+};
+use crate::database::entries::withdrawal::{
+    WithdrawalAcceptedEntry, WithdrawalEntry, WithdrawalEntryKey, WithdrawalInfoByRecipientEntry,
+    WithdrawalInfoBySenderEntry, WithdrawalInfoEntry, WithdrawalTableAcceptedSecondaryIndex,
+    WithdrawalTableByRecipientSecondaryIndex, WithdrawalTableBySenderSecondaryIndex,
+    WithdrawalTablePrimaryIndex, WithdrawalTableSecondaryIndex, WithdrawalUpdatePackage,
+};
+use crate::database::entries::{
+    EntryTrait, KeyTrait, TableIndexTrait, VersionedEntryTrait, VersionedTableIndexTrait,
+};
+
+use super::{EmilyStorage, Page};
+
+/// Storage backed by the DynamoDB tables named in the settings.
+#[derive(Clone, Debug)]
+pub struct DynamoDbStore {
+    /// DynamoDB Client.
+    pub client: Client,
+    /// Settings holding the table names.
+    pub settings: Settings,
+}
+
+impl DynamoDbStore {
+    /// Create a new store from a client and the settings holding the table
+    /// names.
+    pub fn new(client: Client, settings: Settings) -> Self {
+        Self { client, settings }
+    }
+
+    async fn get_entry<T: TableIndexTrait>(
+        &self,
+        key: &<T::Entry as EntryTrait>::Key,
+    ) -> Result<T::Entry, Error> {
+        T::get_entry(&self.client, &self.settings, key).await
+    }
+
+    async fn put_entry<T: TableIndexTrait>(&self, entry: &T::Entry) -> Result<(), Error> {
+        T::put_entry(&self.client, &self.settings, entry).await
+    }
+
+    async fn put_entry_with_version<T: VersionedTableIndexTrait>(
+        &self,
+        entry: &mut T::Entry,
+    ) -> Result<(), Error>
+    where
+        T::Entry: VersionedEntryTrait,
+    {
+        T::put_entry_with_version(&self.client, &self.settings, entry).await
+    }
+
+    async fn query_with_partition_key<T: TableIndexTrait>(
+        &self,
+        partition_key: &<<T::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<T::Entry>, Error> {
+        T::query_with_partition_key(
+            &self.client,
+            &self.settings,
+            partition_key,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    /// Applies the update package to the item with the given key in the
+    /// given table. Deposits and withdrawals share the same update.
+    ///
+    /// The acceptance time is set to the update timestamp the first time the
+    /// item moves into a status that counts towards the rolling window
+    /// volume, and is removed when it moves out of one.
+    #[allow(clippy::too_many_arguments)]
+    async fn update_item<K, E>(
+        &self,
+        table_name: &str,
+        key: &K,
+        version: u64,
+        status: &Status,
+        stacks_block_height: u64,
+        stacks_block_hash: &String,
+        event: AttributeValue,
+        accepted_at: Option<u64>,
+    ) -> Result<E, Error>
+    where
+        K: serde::Serialize,
+        E: DeserializeOwned,
+    {
+        // Setup the update procedure.
+        let update_expression = " SET
+            History = list_append(History, :new_event),
+            Version = Version + :one,
+            OpStatus = :new_op_status,
+            LastUpdateHeight = :new_height,
+            LastUpdateBlockHash = :new_hash
+        ";
+        let update_expression = match accepted_at {
+            Some(_) => format!("{update_expression}, AcceptedAt = if_not_exists(AcceptedAt, :now)"),
+            None => format!("{update_expression} REMOVE AcceptedAt"),
+        };
+        // Ensure the version field is what we expect it to be.
+        let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
+        // Make the key item.
+        let key_item: Item = serde_dynamo::to_item(key)?;
+        // Execute the update.
+        let mut request = self.client.update_item();
+        if let Some(accepted_at) = accepted_at {
+            request = request.expression_attribute_values(
+                ":now",
+                serde_dynamo::to_attribute_value(accepted_at)?,
+            );
+        }
+        request
+            .table_name(table_name)
+            .set_key(Some(key_item.into()))
+            .expression_attribute_values(
+                ":new_op_status",
+                serde_dynamo::to_attribute_value(status)?,
+            )
+            .expression_attribute_values(
+                ":new_height",
+                serde_dynamo::to_attribute_value(stacks_block_height)?,
+            )
+            .expression_attribute_values(
+                ":new_hash",
+                serde_dynamo::to_attribute_value(stacks_block_hash)?,
+            )
+            .expression_attribute_values(":new_event", event)
+            .expression_attribute_values(
+                ":expected_version",
+                serde_dynamo::to_attribute_value(version)?,
+            )
+            .expression_attribute_values(":one", AttributeValue::N(1.to_string()))
+            .condition_expression(condition_expression)
+            .return_values(aws_sdk_dynamodb::types::ReturnValue::AllNew)
+            .update_expression(update_expression)
+            .send()
+            .await?
+            .attributes
+            .ok_or(Error::Debug(format!(
+                "Failed updating item in {table_name}"
+            )))
+            .and_then(|attributes| {
+                serde_dynamo::from_item::<Item, E>(attributes.into()).map_err(Error::from)
+            })
+    }
+}
+
+impl EmilyStorage for DynamoDbStore {
+    async fn put_deposit_entry(&self, entry: &DepositEntry) -> Result<(), Error> {
+        self.put_entry::<DepositTablePrimaryIndex>(entry).await
+    }
+
+    async fn put_deposit_entry_with_version(&self, entry: &mut DepositEntry) -> Result<(), Error> {
+        self.put_entry_with_version::<DepositTablePrimaryIndex>(entry)
+            .await
+    }
+
+    async fn get_deposit_entry(&self, key: &DepositEntryKey) -> Result<DepositEntry, Error> {
+        self.get_entry::<DepositTablePrimaryIndex>(key).await
+    }
+
+    async fn query_deposit_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        self.query_with_partition_key::<DepositTableSecondaryIndex>(
+            status,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn count_deposit_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        DepositTableSecondaryIndex::count_with_partition_key(&self.client, &self.settings, status)
+            .await
+    }
+
+    async fn query_deposit_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoByRecipientEntry>, Error> {
+        self.query_with_partition_key::<DepositTableByRecipientSecondaryIndex>(
+            &recipient.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoBySenderEntry>, Error> {
+        self.query_with_partition_key::<DepositTableBySenderSecondaryIndex>(
+            &sender.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        DepositTableSecondaryIndex::query_with_partition_and_sort_key(
+            &self.client,
+            &self.settings,
+            status,
+            &minimum_height,
+            ">=",
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositAcceptedEntry>, Error> {
+        DepositTableAcceptedSecondaryIndex::query_with_partition_and_sort_key(
+            &self.client,
+            &self.settings,
+            status,
+            &minimum_accepted_at,
+            ">=",
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_by_txid(
+        &self,
+        bitcoin_txid: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositEntry>, Error> {
+        self.query_with_partition_key::<DepositTablePrimaryIndex>(
+            &bitcoin_txid.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn update_deposit(&self, update: &DepositUpdatePackage) -> Result<DepositEntry, Error> {
+        self.update_item(
+            &self.settings.deposit_table_name,
+            &update.key,
+            update.version,
+            &(&update.event.status).into(),
+            update.event.stacks_block_height,
+            &update.event.stacks_block_hash,
+            serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
+            update
+                .event
+                .status
+                .counts_towards_rolling_volume()
+                .then_some(update.timestamp),
+        )
+        .await
+    }
+
+    async fn put_withdrawal_entry(&self, entry: &WithdrawalEntry) -> Result<(), Error> {
+        self.put_entry::<WithdrawalTablePrimaryIndex>(entry).await
+    }
+
+    async fn put_withdrawal_entry_with_version(
+        &self,
+        entry: &mut WithdrawalEntry,
+    ) -> Result<(), Error> {
+        self.put_entry_with_version::<WithdrawalTablePrimaryIndex>(entry)
+            .await
+    }
+
+    async fn query_withdrawal_entries_by_request_id(
+        &self,
+        request_id: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalEntry>, Error> {
+        self.query_with_partition_key::<WithdrawalTablePrimaryIndex>(
+            &request_id,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        self.query_with_partition_key::<WithdrawalTableSecondaryIndex>(
+            status,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn count_withdrawal_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        WithdrawalTableSecondaryIndex::count_with_partition_key(
+            &self.client,
+            &self.settings,
+            status,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoByRecipientEntry>, Error> {
+        self.query_with_partition_key::<WithdrawalTableByRecipientSecondaryIndex>(
+            &recipient.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoBySenderEntry>, Error> {
+        self.query_with_partition_key::<WithdrawalTableBySenderSecondaryIndex>(
+            &sender.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        WithdrawalTableSecondaryIndex::query_with_partition_and_sort_key(
+            &self.client,
+            &self.settings,
+            status,
+            &minimum_height,
+            ">=",
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalAcceptedEntry>, Error> {
+        WithdrawalTableAcceptedSecondaryIndex::query_with_partition_and_sort_key(
+            &self.client,
+            &self.settings,
+            status,
+            &minimum_accepted_at,
+            ">=",
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn update_withdrawal(
+        &self,
+        update: &WithdrawalUpdatePackage,
+    ) -> Result<WithdrawalEntry, Error> {
+        self.update_item(
+            &self.settings.withdrawal_table_name,
+            &update.key,
+            update.version,
+            &(&update.event.status).into(),
+            update.event.stacks_block_height,
+            &update.event.stacks_block_hash,
+            serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
+            update
+                .event
+                .status
+                .counts_towards_rolling_volume()
+                .then_some(update.timestamp),
+        )
+        .await
+    }
+
+    async fn delete_withdrawal_entry(&self, key: &WithdrawalEntryKey) -> Result<(), Error> {
+        WithdrawalTablePrimaryIndex::delete_entry(&self.client, &self.settings, key).await
+    }
+
+    async fn put_chainstate_entry(&self, entry: &ChainstateEntry) -> Result<(), Error> {
+        self.put_entry::<ChainstateTablePrimaryIndex>(entry).await
+    }
+
+    async fn delete_chainstate_entry(&self, key: &ChainstateEntryKey) -> Result<(), Error> {
+        ChainstateTablePrimaryIndex::delete_entry(&self.client, &self.settings, key).await
+    }
+
+    async fn query_chainstate_entries_by_height(
+        &self,
+        height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ChainstateEntry>, Error> {
+        self.query_with_partition_key::<ChainstateTablePrimaryIndex>(
+            &height,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn get_api_state_entry(&self) -> Result<ApiStateEntry, Error> {
+        self.get_entry::<SpecialApiStateIndex>(&ApiStateEntry::key())
+            .await
+    }
+
+    async fn put_api_state_entry(&self, entry: &ApiStateEntry) -> Result<(), Error> {
+        self.put_entry::<SpecialApiStateIndex>(entry).await
+    }
+
+    async fn put_api_state_entry_with_version(
+        &self,
+        entry: &mut ApiStateEntry,
+    ) -> Result<(), Error> {
+        self.put_entry_with_version::<SpecialApiStateIndex>(entry)
+            .await
+    }
+
+    async fn get_reconciliation_cursor_entry(&self) -> Result<ReconciliationCursorEntry, Error> {
+        self.get_entry::<SpecialReconciliationCursorIndex>(&ReconciliationCursorEntry::key())
+            .await
+    }
+
+    async fn put_reconciliation_cursor_entry(
+        &self,
+        entry: &ReconciliationCursorEntry,
+    ) -> Result<(), Error> {
+        self.put_entry::<SpecialReconciliationCursorIndex>(entry)
+            .await
+    }
+
+    async fn get_all_limit_entries(&self) -> Result<Vec<LimitEntry>, Error> {
+        LimitTablePrimaryIndex::get_all_entries(&self.client, &self.settings).await
+    }
+
+    async fn query_limit_entries_by_account(
+        &self,
+        account: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<LimitEntry>, Error> {
+        self.query_with_partition_key::<LimitTablePrimaryIndex>(
+            &account.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn put_limit_entry(&self, entry: &LimitEntry) -> Result<(), Error> {
+        self.put_entry::<LimitTablePrimaryIndex>(entry).await
+    }
+
+    async fn get_all_webhook_entries(&self) -> Result<Vec<WebhookEntry>, Error> {
+        WebhookTablePrimaryIndex::get_all_entries(&self.client, &self.settings).await
+    }
+
+    async fn query_webhook_entries_by_filter_key(
+        &self,
+        filter_key: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WebhookByFilterEntry>, Error> {
+        self.query_with_partition_key::<WebhookTableByFilterSecondaryIndex>(
+            &filter_key.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn put_webhook_entry(&self, entry: &WebhookEntry) -> Result<(), Error> {
+        self.put_entry::<WebhookTablePrimaryIndex>(entry).await
+    }
+
+    async fn delete_webhook_entry(&self, key: &WebhookEntryKey) -> Result<(), Error> {
+        WebhookTablePrimaryIndex::delete_entry(&self.client, &self.settings, key).await
+    }
+
+    async fn get_all_api_key_entries(&self) -> Result<Vec<ApiKeyEntry>, Error> {
+        ApiKeyTablePrimaryIndex::get_all_entries(&self.client, &self.settings).await
+    }
+
+    async fn query_api_key_entries_by_id(
+        &self,
+        key_id: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ApiKeyEntry>, Error> {
+        self.query_with_partition_key::<ApiKeyTablePrimaryIndex>(
+            &key_id.to_string(),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn put_api_key_entry(&self, entry: &ApiKeyEntry) -> Result<(), Error> {
+        self.put_entry::<ApiKeyTablePrimaryIndex>(entry).await
+    }
+
+    /// TODO(395): Include check for whether the table is running locally.
+    #[cfg(feature = "testing")]
+    async fn wipe_all(&self) -> Result<(), Error> {
+        DepositTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        WithdrawalTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        SpecialApiStateIndex::delete_entry(&self.client, &self.settings, &ApiStateEntry::key())
+            .await?;
+        SpecialReconciliationCursorIndex::delete_entry(
+            &self.client,
+            &self.settings,
+            &ReconciliationCursorEntry::key(),
+        )
+        .await?;
+        ChainstateTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        LimitTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        WebhookTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        ApiKeyTablePrimaryIndex::wipe(&self.client, &self.settings).await?;
+        Ok(())
+    }
+}
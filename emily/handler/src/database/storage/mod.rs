@@ -0,0 +1,683 @@
+//! Storage backends for the Emily API.
+//!
+//! The accessors in [`crate::database::accessors`] hold the API specific
+//! logic (retries, chainstate reorg handling, limit aggregation) and use the
+//! [`EmilyStorage`] trait for the primitive reads and writes. The trait is
+//! implemented once per backend, and [`Store`] picks between the backends at
+//! runtime.
+
+use std::future::Future;
+
+use crate::api::models::common::Status;
+use crate::common::error::Error;
+use crate::metrics;
+
+use super::entries::api_key::ApiKeyEntry;
+use super::entries::chainstate::{
+    ApiStateEntry, ChainstateEntry, ChainstateEntryKey, ReconciliationCursorEntry,
+};
+use super::entries::deposit::{
+    DepositAcceptedEntry, DepositEntry, DepositEntryKey, DepositInfoByRecipientEntry,
+    DepositInfoBySenderEntry, DepositInfoEntry, DepositUpdatePackage,
+};
+use super::entries::limits::LimitEntry;
+use super::entries::webhook::{WebhookByFilterEntry, WebhookEntry, WebhookEntryKey};
+use super::entries::withdrawal::{
+    WithdrawalAcceptedEntry, WithdrawalEntry, WithdrawalEntryKey, WithdrawalInfoByRecipientEntry,
+    WithdrawalInfoBySenderEntry, WithdrawalInfoEntry, WithdrawalUpdatePackage,
+};
+
+/// DynamoDB storage backend.
+pub mod dynamodb;
+/// PostgreSQL storage backend.
+pub mod postgres;
+
+pub use dynamodb::DynamoDbStore;
+pub use postgres::PgStore;
+
+/// A single page of entries along with the token for the next page, if
+/// there is one.
+pub type Page<T> = (Vec<T>, Option<String>);
+
+/// The primitive storage operations that the Emily API is built on.
+///
+/// Paginated queries return entries ordered by the sort key of the index
+/// being queried, largest first, along with an opaque token that can be
+/// passed back in to get the next page.
+pub trait EmilyStorage {
+    // Deposit -----------------------------------------------------------------
+
+    /// Insert or overwrite a deposit entry.
+    fn put_deposit_entry(
+        &self,
+        entry: &DepositEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Overwrite an existing deposit entry if its version in storage matches
+    /// the version of the given entry, incrementing the version on success.
+    fn put_deposit_entry_with_version(
+        &self,
+        entry: &mut DepositEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Get the deposit entry with the given key.
+    fn get_deposit_entry(
+        &self,
+        key: &DepositEntryKey,
+    ) -> impl Future<Output = Result<DepositEntry, Error>> + Send;
+
+    /// Get a page of deposit entries with the given status.
+    fn query_deposit_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositInfoEntry>, Error>> + Send;
+
+    /// Count the deposit entries with the given status.
+    fn count_deposit_entries_by_status(
+        &self,
+        status: &Status,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Get a page of deposit entries for the given recipient.
+    fn query_deposit_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositInfoByRecipientEntry>, Error>> + Send;
+
+    /// Get a page of deposit entries for the given sender script.
+    fn query_deposit_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositInfoBySenderEntry>, Error>> + Send;
+
+    /// Get a page of deposit entries with the given status that were last
+    /// updated at or above the given height.
+    fn query_deposit_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositInfoEntry>, Error>> + Send;
+
+    /// Get a page of deposit entries with the given status that were
+    /// accepted at or after the given unix time in seconds.
+    fn query_deposit_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositAcceptedEntry>, Error>> + Send;
+
+    /// Get a page of deposit entries for the given bitcoin transaction.
+    fn query_deposit_entries_by_txid(
+        &self,
+        bitcoin_txid: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<DepositEntry>, Error>> + Send;
+
+    /// Apply the update package to the deposit, failing with
+    /// [`Error::VersionConflict`] if the stored version differs from the
+    /// version in the package.
+    fn update_deposit(
+        &self,
+        update: &DepositUpdatePackage,
+    ) -> impl Future<Output = Result<DepositEntry, Error>> + Send;
+
+    // Withdrawal --------------------------------------------------------------
+
+    /// Insert or overwrite a withdrawal entry.
+    fn put_withdrawal_entry(
+        &self,
+        entry: &WithdrawalEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Overwrite an existing withdrawal entry if its version in storage
+    /// matches the version of the given entry, incrementing the version on
+    /// success.
+    fn put_withdrawal_entry_with_version(
+        &self,
+        entry: &mut WithdrawalEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Get a page of withdrawal entries with the given request id.
+    fn query_withdrawal_entries_by_request_id(
+        &self,
+        request_id: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalEntry>, Error>> + Send;
+
+    /// Get a page of withdrawal entries with the given status.
+    fn query_withdrawal_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalInfoEntry>, Error>> + Send;
+
+    /// Count the withdrawal entries with the given status.
+    fn count_withdrawal_entries_by_status(
+        &self,
+        status: &Status,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
+    /// Get a page of withdrawal entries for the given recipient.
+    fn query_withdrawal_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalInfoByRecipientEntry>, Error>> + Send;
+
+    /// Get a page of withdrawal entries for the given sender principal.
+    fn query_withdrawal_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalInfoBySenderEntry>, Error>> + Send;
+
+    /// Get a page of withdrawal entries with the given status that were last
+    /// updated at or above the given height.
+    fn query_withdrawal_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalInfoEntry>, Error>> + Send;
+
+    /// Get a page of withdrawal entries with the given status that were
+    /// accepted at or after the given unix time in seconds.
+    fn query_withdrawal_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WithdrawalAcceptedEntry>, Error>> + Send;
+
+    /// Apply the update package to the withdrawal, failing with
+    /// [`Error::VersionConflict`] if the stored version differs from the
+    /// version in the package.
+    fn update_withdrawal(
+        &self,
+        update: &WithdrawalUpdatePackage,
+    ) -> impl Future<Output = Result<WithdrawalEntry, Error>> + Send;
+
+    /// Delete the withdrawal entry with the given key.
+    fn delete_withdrawal_entry(
+        &self,
+        key: &WithdrawalEntryKey,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Chainstate --------------------------------------------------------------
+
+    /// Insert or overwrite a chainstate entry.
+    fn put_chainstate_entry(
+        &self,
+        entry: &ChainstateEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the chainstate entry with the given key.
+    fn delete_chainstate_entry(
+        &self,
+        key: &ChainstateEntryKey,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Get a page of chainstate entries at the given height.
+    fn query_chainstate_entries_by_height(
+        &self,
+        height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<ChainstateEntry>, Error>> + Send;
+
+    // API state ---------------------------------------------------------------
+
+    /// Get the API state entry.
+    fn get_api_state_entry(&self) -> impl Future<Output = Result<ApiStateEntry, Error>> + Send;
+
+    /// Insert or overwrite the API state entry.
+    fn put_api_state_entry(
+        &self,
+        entry: &ApiStateEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Overwrite the API state entry if its version in storage matches the
+    /// version of the given entry, incrementing the version on success.
+    fn put_api_state_entry_with_version(
+        &self,
+        entry: &mut ApiStateEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Reconciliation ----------------------------------------------------------
+
+    /// Get the reconciliation cursor entry.
+    fn get_reconciliation_cursor_entry(
+        &self,
+    ) -> impl Future<Output = Result<ReconciliationCursorEntry, Error>> + Send;
+
+    /// Insert or overwrite the reconciliation cursor entry.
+    fn put_reconciliation_cursor_entry(
+        &self,
+        entry: &ReconciliationCursorEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Limits ------------------------------------------------------------------
+
+    /// Get every limit entry.
+    fn get_all_limit_entries(&self) -> impl Future<Output = Result<Vec<LimitEntry>, Error>> + Send;
+
+    /// Get a page of limit entries for the given account.
+    fn query_limit_entries_by_account(
+        &self,
+        account: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<LimitEntry>, Error>> + Send;
+
+    /// Insert or overwrite a limit entry.
+    fn put_limit_entry(&self, entry: &LimitEntry)
+        -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Webhooks ----------------------------------------------------------------
+
+    /// Get every webhook entry.
+    fn get_all_webhook_entries(
+        &self,
+    ) -> impl Future<Output = Result<Vec<WebhookEntry>, Error>> + Send;
+
+    /// Query the webhooks indexed by the given filter key.
+    fn query_webhook_entries_by_filter_key(
+        &self,
+        filter_key: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<WebhookByFilterEntry>, Error>> + Send;
+
+    /// Insert or overwrite a webhook entry.
+    fn put_webhook_entry(
+        &self,
+        entry: &WebhookEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Delete the webhook entry with the given key.
+    fn delete_webhook_entry(
+        &self,
+        key: &WebhookEntryKey,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Api keys ----------------------------------------------------------------
+
+    /// Get every api key entry, including the revoked ones.
+    fn get_all_api_key_entries(
+        &self,
+    ) -> impl Future<Output = Result<Vec<ApiKeyEntry>, Error>> + Send;
+
+    /// Get a page of api key entries with the given id.
+    fn query_api_key_entries_by_id(
+        &self,
+        key_id: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> impl Future<Output = Result<Page<ApiKeyEntry>, Error>> + Send;
+
+    /// Insert or overwrite an api key entry.
+    fn put_api_key_entry(
+        &self,
+        entry: &ApiKeyEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    // Testing -----------------------------------------------------------------
+
+    /// Delete every entry in storage.
+    #[cfg(feature = "testing")]
+    fn wipe_all(&self) -> impl Future<Output = Result<(), Error>> + Send;
+}
+
+/// The storage backend that the API was started with.
+#[derive(Clone, Debug)]
+pub enum Store {
+    /// Entries are stored in DynamoDB tables.
+    DynamoDb(DynamoDbStore),
+    /// Entries are stored in a PostgreSQL database.
+    Postgres(PgStore),
+}
+
+/// Forwards a method call to whichever backend the store wraps, recording
+/// the call in the database metrics.
+macro_rules! dispatch {
+    ($self:ident.$method:ident($($arg:expr),*)) => {{
+        let (backend, result) = match $self {
+            Store::DynamoDb(store) => ("dynamodb", store.$method($($arg),*).await),
+            Store::Postgres(store) => ("postgres", store.$method($($arg),*).await),
+        };
+        metrics::record_database_call(backend, stringify!($method), &result);
+        result
+    }};
+}
+
+impl EmilyStorage for Store {
+    async fn put_deposit_entry(&self, entry: &DepositEntry) -> Result<(), Error> {
+        dispatch!(self.put_deposit_entry(entry))
+    }
+
+    async fn put_deposit_entry_with_version(&self, entry: &mut DepositEntry) -> Result<(), Error> {
+        dispatch!(self.put_deposit_entry_with_version(entry))
+    }
+
+    async fn get_deposit_entry(&self, key: &DepositEntryKey) -> Result<DepositEntry, Error> {
+        dispatch!(self.get_deposit_entry(key))
+    }
+
+    async fn query_deposit_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        dispatch!(self.query_deposit_entries_by_status(status, maybe_next_token, maybe_page_size))
+    }
+
+    async fn count_deposit_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        dispatch!(self.count_deposit_entries_by_status(status))
+    }
+
+    async fn query_deposit_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoByRecipientEntry>, Error> {
+        dispatch!(self.query_deposit_entries_by_recipient(
+            recipient,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_deposit_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoBySenderEntry>, Error> {
+        dispatch!(self.query_deposit_entries_by_sender(sender, maybe_next_token, maybe_page_size))
+    }
+
+    async fn query_deposit_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        dispatch!(self.query_deposit_entries_modified_from_height(
+            status,
+            minimum_height,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_deposit_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositAcceptedEntry>, Error> {
+        dispatch!(self.query_deposit_entries_accepted_since(
+            status,
+            minimum_accepted_at,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_deposit_entries_by_txid(
+        &self,
+        bitcoin_txid: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositEntry>, Error> {
+        dispatch!(self.query_deposit_entries_by_txid(
+            bitcoin_txid,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn update_deposit(&self, update: &DepositUpdatePackage) -> Result<DepositEntry, Error> {
+        dispatch!(self.update_deposit(update))
+    }
+
+    async fn put_withdrawal_entry(&self, entry: &WithdrawalEntry) -> Result<(), Error> {
+        dispatch!(self.put_withdrawal_entry(entry))
+    }
+
+    async fn put_withdrawal_entry_with_version(
+        &self,
+        entry: &mut WithdrawalEntry,
+    ) -> Result<(), Error> {
+        dispatch!(self.put_withdrawal_entry_with_version(entry))
+    }
+
+    async fn query_withdrawal_entries_by_request_id(
+        &self,
+        request_id: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_by_request_id(
+            request_id,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_withdrawal_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_by_status(
+            status,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn count_withdrawal_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        dispatch!(self.count_withdrawal_entries_by_status(status))
+    }
+
+    async fn query_withdrawal_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoByRecipientEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_by_recipient(
+            recipient,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_withdrawal_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoBySenderEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_by_sender(
+            sender,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_withdrawal_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_modified_from_height(
+            status,
+            minimum_height,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn query_withdrawal_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalAcceptedEntry>, Error> {
+        dispatch!(self.query_withdrawal_entries_accepted_since(
+            status,
+            minimum_accepted_at,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn update_withdrawal(
+        &self,
+        update: &WithdrawalUpdatePackage,
+    ) -> Result<WithdrawalEntry, Error> {
+        dispatch!(self.update_withdrawal(update))
+    }
+
+    async fn delete_withdrawal_entry(&self, key: &WithdrawalEntryKey) -> Result<(), Error> {
+        dispatch!(self.delete_withdrawal_entry(key))
+    }
+
+    async fn put_chainstate_entry(&self, entry: &ChainstateEntry) -> Result<(), Error> {
+        dispatch!(self.put_chainstate_entry(entry))
+    }
+
+    async fn delete_chainstate_entry(&self, key: &ChainstateEntryKey) -> Result<(), Error> {
+        dispatch!(self.delete_chainstate_entry(key))
+    }
+
+    async fn query_chainstate_entries_by_height(
+        &self,
+        height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ChainstateEntry>, Error> {
+        dispatch!(self.query_chainstate_entries_by_height(
+            height,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn get_api_state_entry(&self) -> Result<ApiStateEntry, Error> {
+        dispatch!(self.get_api_state_entry())
+    }
+
+    async fn put_api_state_entry(&self, entry: &ApiStateEntry) -> Result<(), Error> {
+        dispatch!(self.put_api_state_entry(entry))
+    }
+
+    async fn put_api_state_entry_with_version(
+        &self,
+        entry: &mut ApiStateEntry,
+    ) -> Result<(), Error> {
+        dispatch!(self.put_api_state_entry_with_version(entry))
+    }
+
+    async fn get_reconciliation_cursor_entry(&self) -> Result<ReconciliationCursorEntry, Error> {
+        dispatch!(self.get_reconciliation_cursor_entry())
+    }
+
+    async fn put_reconciliation_cursor_entry(
+        &self,
+        entry: &ReconciliationCursorEntry,
+    ) -> Result<(), Error> {
+        dispatch!(self.put_reconciliation_cursor_entry(entry))
+    }
+
+    async fn get_all_limit_entries(&self) -> Result<Vec<LimitEntry>, Error> {
+        dispatch!(self.get_all_limit_entries())
+    }
+
+    async fn query_limit_entries_by_account(
+        &self,
+        account: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<LimitEntry>, Error> {
+        dispatch!(self.query_limit_entries_by_account(account, maybe_next_token, maybe_page_size))
+    }
+
+    async fn put_limit_entry(&self, entry: &LimitEntry) -> Result<(), Error> {
+        dispatch!(self.put_limit_entry(entry))
+    }
+
+    async fn get_all_webhook_entries(&self) -> Result<Vec<WebhookEntry>, Error> {
+        dispatch!(self.get_all_webhook_entries())
+    }
+
+    async fn query_webhook_entries_by_filter_key(
+        &self,
+        filter_key: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WebhookByFilterEntry>, Error> {
+        dispatch!(self.query_webhook_entries_by_filter_key(
+            filter_key,
+            maybe_next_token,
+            maybe_page_size
+        ))
+    }
+
+    async fn put_webhook_entry(&self, entry: &WebhookEntry) -> Result<(), Error> {
+        dispatch!(self.put_webhook_entry(entry))
+    }
+
+    async fn delete_webhook_entry(&self, key: &WebhookEntryKey) -> Result<(), Error> {
+        dispatch!(self.delete_webhook_entry(key))
+    }
+
+    async fn get_all_api_key_entries(&self) -> Result<Vec<ApiKeyEntry>, Error> {
+        dispatch!(self.get_all_api_key_entries())
+    }
+
+    async fn query_api_key_entries_by_id(
+        &self,
+        key_id: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ApiKeyEntry>, Error> {
+        dispatch!(self.query_api_key_entries_by_id(key_id, maybe_next_token, maybe_page_size))
+    }
+
+    async fn put_api_key_entry(&self, entry: &ApiKeyEntry) -> Result<(), Error> {
+        dispatch!(self.put_api_key_entry(entry))
+    }
+
+    #[cfg(feature = "testing")]
+    async fn wipe_all(&self) -> Result<(), Error> {
+        dispatch!(self.wipe_all())
+    }
+}
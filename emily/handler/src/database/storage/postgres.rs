@@ -0,0 +1,1395 @@
+//! PostgreSQL implementation of the Emily storage trait.
+//!
+//! Every DynamoDB table has a table here with a column for each field of
+//! its entries, and the DynamoDB secondary indexes are regular indexes on
+//! those tables. Fields holding nested documents, like the history of an
+//! operation, are stored as JSONB since they are only ever read back whole.
+//!
+//! Index queries are ordered by the sort key of the index in descending
+//! order, like the DynamoDB backend, and are paginated by a token holding the
+//! sort key and primary key of the last entry of the page. The schema is
+//! created by the sqlx migrations in the `migrations` directory of this crate.
+
+use std::fmt::Display;
+
+use sqlx::postgres::{PgArguments, PgPoolOptions, PgRow};
+use sqlx::{FromRow, PgPool, Postgres};
+
+use crate::api::models::common::Status;
+use crate::common::error::Error;
+use crate::database::entries::api_key::{ApiKeyEntry, ApiKeyEntryKey};
+use crate::database::entries::chainstate::{
+    ApiStateEntry, ApiStatus, ChainstateEntry, ChainstateEntryKey, ReconciliationCursorEntry,
+};
+use crate::database::entries::deposit::{
+    DepositAcceptedEntry, DepositEntry, DepositEntryKey, DepositInfoByRecipientEntry,
+    DepositInfoBySenderEntry, DepositInfoEntry, DepositParametersEntry, DepositUpdatePackage,
+};
+use crate::database::entries::limits::{LimitEntry, LimitEntryKey};
+use crate::database::entries::webhook::{WebhookByFilterEntry, WebhookEntry, WebhookEntryKey};
+use crate::database::entries::withdrawal::{
+    WithdrawalAcceptedEntry, WithdrawalEntry, WithdrawalEntryKey, WithdrawalInfoByRecipientEntry,
+    WithdrawalInfoBySenderEntry, WithdrawalInfoEntry, WithdrawalParametersEntry,
+    WithdrawalUpdatePackage,
+};
+use crate::database::entries::{detokenize, tokenize, EntryTrait, VersionedEntryTrait};
+
+use super::{EmilyStorage, Page};
+
+/// A query against the database with its bound arguments.
+type PgQuery<'q> = sqlx::query::Query<'q, Postgres, PgArguments>;
+/// Key of the entries in a table.
+type Key<T> = <<T as VersionedTable>::Entry as EntryTrait>::Key;
+
+/// Converts an unsigned integer into the signed integer that is stored.
+fn to_db<T>(value: T) -> Result<i64, Error>
+where
+    T: TryInto<i64> + Copy + Display,
+{
+    value
+        .try_into()
+        .map_err(|_| Error::Debug(format!("{value} is too large to be stored")))
+}
+
+/// Converts a stored signed integer back into an unsigned integer.
+fn from_db<T: TryFrom<i64>>(value: i64) -> Result<T, Error> {
+    T::try_from(value).map_err(|_| Error::Debug(format!("stored value {value} is out of range")))
+}
+
+/// Converts a status into the name it is stored as, which is the same as
+/// the name it is serialized as.
+fn status_to_db(status: &Status) -> Result<String, Error> {
+    match serde_json::to_value(status)? {
+        serde_json::Value::String(status) => Ok(status),
+        value => Err(Error::Debug(format!("status {value} isn't a string"))),
+    }
+}
+
+/// Converts a stored status name back into a status.
+fn status_from_db(status: String) -> Result<Status, Error> {
+    Ok(serde_json::from_value(serde_json::Value::String(status))?)
+}
+
+/// A table of versioned entries, which are the deposits and withdrawals.
+trait VersionedTable {
+    /// The type of the entries in the table.
+    type Entry: VersionedEntryTrait + Send;
+    /// The row that is read from the table.
+    type Row: for<'r> FromRow<'r, PgRow> + TryInto<Self::Entry, Error = Error>;
+    /// The name of the table.
+    const TABLE: &'static str;
+    /// The columns that the row is read from.
+    const COLUMNS: &'static str;
+    /// The two primary key columns.
+    const KEY_COLUMNS: &'static str;
+    /// Filter on the primary key, bound by `bind_key` as the first two
+    /// arguments.
+    const KEY_FILTER: &'static str;
+    /// Statement that writes the entry bound by `bind_entry`, replacing any
+    /// entry with the same key.
+    const UPSERT: &'static str;
+    /// Statement that replaces the entry bound by `bind_entry` if the stored
+    /// entry has the version bound after it.
+    const UPDATE_WITH_VERSION: &'static str;
+    /// Binds the primary key.
+    fn bind_key<'q>(query: PgQuery<'q>, key: &Key<Self>) -> Result<PgQuery<'q>, Error>;
+    /// Binds the entry, starting with its primary key.
+    fn bind_entry<'q>(query: PgQuery<'q>, entry: &Self::Entry) -> Result<PgQuery<'q>, Error>;
+}
+
+/// The deposit table.
+struct Deposits;
+
+/// Row of the deposit table.
+#[derive(FromRow)]
+struct DepositRow {
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: i64,
+    version: i64,
+    recipient: String,
+    sender: Option<String>,
+    amount: i64,
+    max_fee: i64,
+    lock_time: i64,
+    status: String,
+    reclaim_script: String,
+    deposit_script: String,
+    last_update_height: i64,
+    last_update_block_hash: String,
+    fulfillment: Option<String>,
+    history: String,
+    created_at: Option<i64>,
+    accepted_at: Option<i64>,
+    verification: String,
+}
+
+impl TryFrom<DepositRow> for DepositEntry {
+    type Error = Error;
+    fn try_from(row: DepositRow) -> Result<Self, Error> {
+        Ok(DepositEntry {
+            key: DepositEntryKey {
+                bitcoin_txid: row.bitcoin_txid,
+                bitcoin_tx_output_index: from_db(row.bitcoin_tx_output_index)?,
+            },
+            version: from_db(row.version)?,
+            recipient: row.recipient,
+            sender: row.sender,
+            amount: from_db(row.amount)?,
+            parameters: DepositParametersEntry {
+                max_fee: from_db(row.max_fee)?,
+                lock_time: from_db(row.lock_time)?,
+            },
+            status: status_from_db(row.status)?,
+            reclaim_script: row.reclaim_script,
+            deposit_script: row.deposit_script,
+            last_update_height: from_db(row.last_update_height)?,
+            last_update_block_hash: row.last_update_block_hash,
+            fulfillment: row
+                .fulfillment
+                .map(|fulfillment| serde_json::from_str(&fulfillment))
+                .transpose()?,
+            history: serde_json::from_str(&row.history)?,
+            created_at: row.created_at.map(from_db).transpose()?,
+            accepted_at: row.accepted_at.map(from_db).transpose()?,
+            verification: serde_json::from_str(&row.verification)?,
+        })
+    }
+}
+
+impl VersionedTable for Deposits {
+    type Entry = DepositEntry;
+    type Row = DepositRow;
+    const TABLE: &'static str = "emily_deposits";
+    const COLUMNS: &'static str = "bitcoin_txid, bitcoin_tx_output_index, version, recipient, \
+        sender, amount, max_fee, lock_time, status, reclaim_script, deposit_script, \
+        last_update_height, last_update_block_hash, fulfillment::text AS fulfillment, \
+        history::text AS history, created_at, accepted_at, verification::text AS verification";
+    const KEY_COLUMNS: &'static str = "bitcoin_txid, bitcoin_tx_output_index";
+    const KEY_FILTER: &'static str = "bitcoin_txid = $1 AND bitcoin_tx_output_index = $2";
+    const UPSERT: &'static str = "
+        INSERT INTO emily_deposits (
+            bitcoin_txid, bitcoin_tx_output_index, version, recipient, sender, amount,
+            max_fee, lock_time, status, reclaim_script, deposit_script, last_update_height,
+            last_update_block_hash, fulfillment, history, created_at, accepted_at, verification
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12,
+            $13, $14::jsonb, $15::jsonb, $16, $17, $18::jsonb
+        )
+        ON CONFLICT (bitcoin_txid, bitcoin_tx_output_index) DO UPDATE SET
+            version = EXCLUDED.version,
+            recipient = EXCLUDED.recipient,
+            sender = EXCLUDED.sender,
+            amount = EXCLUDED.amount,
+            max_fee = EXCLUDED.max_fee,
+            lock_time = EXCLUDED.lock_time,
+            status = EXCLUDED.status,
+            reclaim_script = EXCLUDED.reclaim_script,
+            deposit_script = EXCLUDED.deposit_script,
+            last_update_height = EXCLUDED.last_update_height,
+            last_update_block_hash = EXCLUDED.last_update_block_hash,
+            fulfillment = EXCLUDED.fulfillment,
+            history = EXCLUDED.history,
+            created_at = EXCLUDED.created_at,
+            accepted_at = EXCLUDED.accepted_at,
+            verification = EXCLUDED.verification";
+    const UPDATE_WITH_VERSION: &'static str = "
+        UPDATE emily_deposits SET
+            version = $3,
+            recipient = $4,
+            sender = $5,
+            amount = $6,
+            max_fee = $7,
+            lock_time = $8,
+            status = $9,
+            reclaim_script = $10,
+            deposit_script = $11,
+            last_update_height = $12,
+            last_update_block_hash = $13,
+            fulfillment = $14::jsonb,
+            history = $15::jsonb,
+            created_at = $16,
+            accepted_at = $17,
+            verification = $18::jsonb
+        WHERE bitcoin_txid = $1
+          AND bitcoin_tx_output_index = $2
+          AND version = $19";
+
+    fn bind_key<'q>(query: PgQuery<'q>, key: &DepositEntryKey) -> Result<PgQuery<'q>, Error> {
+        Ok(query
+            .bind(key.bitcoin_txid.clone())
+            .bind(to_db(key.bitcoin_tx_output_index)?))
+    }
+
+    fn bind_entry<'q>(query: PgQuery<'q>, entry: &DepositEntry) -> Result<PgQuery<'q>, Error> {
+        Ok(Self::bind_key(query, &entry.key)?
+            .bind(to_db(entry.version)?)
+            .bind(entry.recipient.clone())
+            .bind(entry.sender.clone())
+            .bind(to_db(entry.amount)?)
+            .bind(to_db(entry.parameters.max_fee)?)
+            .bind(to_db(entry.parameters.lock_time)?)
+            .bind(status_to_db(&entry.status)?)
+            .bind(entry.reclaim_script.clone())
+            .bind(entry.deposit_script.clone())
+            .bind(to_db(entry.last_update_height)?)
+            .bind(entry.last_update_block_hash.clone())
+            .bind(
+                entry
+                    .fulfillment
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+            )
+            .bind(serde_json::to_string(&entry.history)?)
+            .bind(entry.created_at.map(to_db).transpose()?)
+            .bind(entry.accepted_at.map(to_db).transpose()?)
+            .bind(serde_json::to_string(&entry.verification)?))
+    }
+}
+
+/// The withdrawal table.
+struct Withdrawals;
+
+/// Row of the withdrawal table.
+#[derive(FromRow)]
+struct WithdrawalRow {
+    request_id: i64,
+    stacks_block_hash: String,
+    stacks_block_height: i64,
+    version: i64,
+    recipient: String,
+    sender: Option<String>,
+    amount: i64,
+    max_fee: i64,
+    status: String,
+    last_update_height: i64,
+    last_update_block_hash: String,
+    history: String,
+    created_at: Option<i64>,
+    accepted_at: Option<i64>,
+}
+
+impl TryFrom<WithdrawalRow> for WithdrawalEntry {
+    type Error = Error;
+    fn try_from(row: WithdrawalRow) -> Result<Self, Error> {
+        Ok(WithdrawalEntry {
+            key: WithdrawalEntryKey {
+                request_id: from_db(row.request_id)?,
+                stacks_block_hash: row.stacks_block_hash,
+            },
+            stacks_block_height: from_db(row.stacks_block_height)?,
+            version: from_db(row.version)?,
+            recipient: row.recipient,
+            sender: row.sender,
+            amount: from_db(row.amount)?,
+            parameters: WithdrawalParametersEntry { max_fee: from_db(row.max_fee)? },
+            status: status_from_db(row.status)?,
+            last_update_height: from_db(row.last_update_height)?,
+            last_update_block_hash: row.last_update_block_hash,
+            history: serde_json::from_str(&row.history)?,
+            created_at: row.created_at.map(from_db).transpose()?,
+            accepted_at: row.accepted_at.map(from_db).transpose()?,
+        })
+    }
+}
+
+impl VersionedTable for Withdrawals {
+    type Entry = WithdrawalEntry;
+    type Row = WithdrawalRow;
+    const TABLE: &'static str = "emily_withdrawals";
+    const COLUMNS: &'static str = "request_id, stacks_block_hash, stacks_block_height, version, \
+        recipient, sender, amount, max_fee, status, last_update_height, last_update_block_hash, \
+        history::text AS history, created_at, accepted_at";
+    const KEY_COLUMNS: &'static str = "request_id, stacks_block_hash";
+    const KEY_FILTER: &'static str = "request_id = $1 AND stacks_block_hash = $2";
+    const UPSERT: &'static str = "
+        INSERT INTO emily_withdrawals (
+            request_id, stacks_block_hash, stacks_block_height, version, recipient, sender,
+            amount, max_fee, status, last_update_height, last_update_block_hash, history,
+            created_at, accepted_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12::jsonb, $13, $14)
+        ON CONFLICT (request_id, stacks_block_hash) DO UPDATE SET
+            stacks_block_height = EXCLUDED.stacks_block_height,
+            version = EXCLUDED.version,
+            recipient = EXCLUDED.recipient,
+            sender = EXCLUDED.sender,
+            amount = EXCLUDED.amount,
+            max_fee = EXCLUDED.max_fee,
+            status = EXCLUDED.status,
+            last_update_height = EXCLUDED.last_update_height,
+            last_update_block_hash = EXCLUDED.last_update_block_hash,
+            history = EXCLUDED.history,
+            created_at = EXCLUDED.created_at,
+            accepted_at = EXCLUDED.accepted_at";
+    const UPDATE_WITH_VERSION: &'static str = "
+        UPDATE emily_withdrawals SET
+            stacks_block_height = $3,
+            version = $4,
+            recipient = $5,
+            sender = $6,
+            amount = $7,
+            max_fee = $8,
+            status = $9,
+            last_update_height = $10,
+            last_update_block_hash = $11,
+            history = $12::jsonb,
+            created_at = $13,
+            accepted_at = $14
+        WHERE request_id = $1
+          AND stacks_block_hash = $2
+          AND version = $15";
+
+    fn bind_key<'q>(query: PgQuery<'q>, key: &WithdrawalEntryKey) -> Result<PgQuery<'q>, Error> {
+        Ok(query
+            .bind(to_db(key.request_id)?)
+            .bind(key.stacks_block_hash.clone()))
+    }
+
+    fn bind_entry<'q>(query: PgQuery<'q>, entry: &WithdrawalEntry) -> Result<PgQuery<'q>, Error> {
+        Ok(Self::bind_key(query, &entry.key)?
+            .bind(to_db(entry.stacks_block_height)?)
+            .bind(to_db(entry.version)?)
+            .bind(entry.recipient.clone())
+            .bind(entry.sender.clone())
+            .bind(to_db(entry.amount)?)
+            .bind(to_db(entry.parameters.max_fee)?)
+            .bind(status_to_db(&entry.status)?)
+            .bind(to_db(entry.last_update_height)?)
+            .bind(entry.last_update_block_hash.clone())
+            .bind(serde_json::to_string(&entry.history)?)
+            .bind(entry.created_at.map(to_db).transpose()?)
+            .bind(entry.accepted_at.map(to_db).transpose()?))
+    }
+}
+
+/// Row of the chainstate table.
+#[derive(FromRow)]
+struct ChainstateRow {
+    height: i64,
+    hash: String,
+}
+
+impl TryFrom<ChainstateRow> for ChainstateEntry {
+    type Error = Error;
+    fn try_from(row: ChainstateRow) -> Result<Self, Error> {
+        Ok(ChainstateEntry {
+            key: ChainstateEntryKey {
+                hash: row.hash,
+                height: from_db(row.height)?,
+            },
+        })
+    }
+}
+
+/// Row of the api state table.
+#[derive(FromRow)]
+struct ApiStateRow {
+    version: i64,
+    reorganizing: bool,
+    chaintip_height: i64,
+    chaintip_hash: String,
+    updated_at: Option<i64>,
+}
+
+impl TryFrom<ApiStateRow> for ApiStateEntry {
+    type Error = Error;
+    fn try_from(row: ApiStateRow) -> Result<Self, Error> {
+        let chaintip = ChainstateEntry {
+            key: ChainstateEntryKey {
+                hash: row.chaintip_hash,
+                height: from_db(row.chaintip_height)?,
+            },
+        };
+        let mut entry = ApiStateEntry::default();
+        entry.version = from_db(row.version)?;
+        entry.api_status = if row.reorganizing {
+            ApiStatus::Reorg(chaintip)
+        } else {
+            ApiStatus::Stable(chaintip)
+        };
+        entry.updated_at = row.updated_at.map(from_db).transpose()?;
+        Ok(entry)
+    }
+}
+
+/// Binds the fields of the api state entry.
+fn bind_api_state<'q>(query: PgQuery<'q>, entry: &ApiStateEntry) -> Result<PgQuery<'q>, Error> {
+    let chaintip = entry.chaintip();
+    Ok(query
+        .bind(to_db(entry.version)?)
+        .bind(matches!(entry.api_status, ApiStatus::Reorg(_)))
+        .bind(to_db(chaintip.key.height)?)
+        .bind(chaintip.key.hash)
+        .bind(entry.updated_at.map(to_db).transpose()?))
+}
+
+/// Row of the limit table.
+#[derive(FromRow)]
+struct LimitRow {
+    account: String,
+    timestamp: i64,
+    peg_cap: Option<i64>,
+    per_deposit_minimum: Option<i64>,
+    per_deposit_cap: Option<i64>,
+    per_withdrawal_cap: Option<i64>,
+    rolling_window_seconds: Option<i64>,
+    rolling_deposit_cap: Option<i64>,
+    rolling_withdrawal_cap: Option<i64>,
+}
+
+impl TryFrom<LimitRow> for LimitEntry {
+    type Error = Error;
+    fn try_from(row: LimitRow) -> Result<Self, Error> {
+        Ok(LimitEntry {
+            key: LimitEntryKey {
+                account: row.account,
+                timestamp: from_db(row.timestamp)?,
+            },
+            peg_cap: row.peg_cap.map(from_db).transpose()?,
+            per_deposit_minimum: row.per_deposit_minimum.map(from_db).transpose()?,
+            per_deposit_cap: row.per_deposit_cap.map(from_db).transpose()?,
+            per_withdrawal_cap: row.per_withdrawal_cap.map(from_db).transpose()?,
+            rolling_window_seconds: row.rolling_window_seconds.map(from_db).transpose()?,
+            rolling_deposit_cap: row.rolling_deposit_cap.map(from_db).transpose()?,
+            rolling_withdrawal_cap: row.rolling_withdrawal_cap.map(from_db).transpose()?,
+        })
+    }
+}
+
+/// Row of the webhook table.
+#[derive(FromRow)]
+struct WebhookRow {
+    webhook_id: String,
+    created_at: i64,
+    owner: String,
+    url: String,
+    secret: String,
+    filter: String,
+    filter_key: String,
+}
+
+impl TryFrom<WebhookRow> for WebhookEntry {
+    type Error = Error;
+    fn try_from(row: WebhookRow) -> Result<Self, Error> {
+        Ok(WebhookEntry {
+            key: WebhookEntryKey {
+                webhook_id: row.webhook_id,
+                created_at: from_db(row.created_at)?,
+            },
+            owner: row.owner,
+            url: row.url,
+            secret: row.secret,
+            filter: serde_json::from_str(&row.filter)?,
+            filter_key: row.filter_key,
+        })
+    }
+}
+
+/// Row of the api key table.
+#[derive(FromRow)]
+struct ApiKeyRow {
+    key_id: String,
+    created_at: i64,
+    key_hash: String,
+    description: String,
+    scopes: String,
+    revoked: bool,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKeyEntry {
+    type Error = Error;
+    fn try_from(row: ApiKeyRow) -> Result<Self, Error> {
+        Ok(ApiKeyEntry {
+            key: ApiKeyEntryKey {
+                key_id: row.key_id,
+                created_at: from_db(row.created_at)?,
+            },
+            key_hash: row.key_hash,
+            description: row.description,
+            scopes: serde_json::from_str(&row.scopes)?,
+            revoked: row.revoked,
+        })
+    }
+}
+
+/// Parses fetched rows into entries.
+fn parse_rows<R, E>(rows: &[PgRow]) -> Result<Vec<E>, Error>
+where
+    R: for<'r> FromRow<'r, PgRow> + TryInto<E, Error = Error>,
+{
+    rows.iter()
+        .map(|row| R::from_row(row).map_err(Error::from)?.try_into())
+        .collect()
+}
+
+/// Storage backed by a PostgreSQL database.
+#[derive(Clone, Debug)]
+pub struct PgStore {
+    /// Connection pool.
+    pool: PgPool,
+}
+
+impl PgStore {
+    /// Connect to the database at the given URL.
+    pub async fn connect(database_url: &str) -> Result<Self, Error> {
+        let pool = PgPoolOptions::new().connect(database_url).await?;
+        Ok(Self { pool })
+    }
+
+    /// Get the connection pool.
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Apply any migrations that haven't been applied to the database yet.
+    pub async fn apply_migrations(&self) -> Result<(), Error> {
+        tracing::info!("applying database migrations");
+        sqlx::migrate!("./migrations")
+            .run(&self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    async fn get_entry<T: VersionedTable>(&self, key: &Key<T>) -> Result<T::Entry, Error> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {}",
+            T::COLUMNS,
+            T::TABLE,
+            T::KEY_FILTER
+        );
+        let row = T::bind_key(sqlx::query(&sql), key)?
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        T::Row::from_row(&row)?.try_into()
+    }
+
+    async fn put_entry<T: VersionedTable>(&self, entry: &T::Entry) -> Result<(), Error> {
+        T::bind_entry(sqlx::query(T::UPSERT), entry)?
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn put_entry_with_version<T: VersionedTable>(
+        &self,
+        entry: &mut T::Entry,
+    ) -> Result<(), Error> {
+        let expected_version = entry.get_version();
+        entry.increment_version();
+
+        let result = T::bind_entry(sqlx::query(T::UPDATE_WITH_VERSION), entry)?
+            .bind(to_db(expected_version)?)
+            .execute(&self.pool)
+            .await?;
+
+        // Like the DynamoDB condition check, this fails both when the entry
+        // doesn't exist and when it has a different version.
+        if result.rows_affected() == 0 {
+            return Err(Error::VersionConflict);
+        }
+        Ok(())
+    }
+
+    /// Applies `apply` to the entry with the given key and increments its
+    /// version, all within a transaction holding a lock on the entry.
+    async fn update_entry_with_version<T, F>(
+        &self,
+        key: &Key<T>,
+        expected_version: u64,
+        apply: F,
+    ) -> Result<T::Entry, Error>
+    where
+        T: VersionedTable,
+        F: FnOnce(&mut T::Entry) + Send,
+    {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE {} FOR UPDATE",
+            T::COLUMNS,
+            T::TABLE,
+            T::KEY_FILTER
+        );
+
+        let mut tx = self.pool.begin().await?;
+        let row = T::bind_key(sqlx::query(&sql), key)?
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(Error::VersionConflict)?;
+
+        let mut entry: T::Entry = T::Row::from_row(&row)?.try_into()?;
+        if entry.get_version() != expected_version {
+            return Err(Error::VersionConflict);
+        }
+        apply(&mut entry);
+        entry.increment_version();
+
+        T::bind_entry(sqlx::query(T::UPSERT), &entry)?
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(entry)
+    }
+
+    /// Gets a page of the entries whose `column` equals `value`, ordered by
+    /// `sort_column` and then the primary key, all descending. When given,
+    /// only entries with a sort key of at least `minimum_sort_key` are
+    /// returned.
+    ///
+    /// The next token holds the sort key and primary key of the last entry
+    /// of the page, so the next page starts right after it even if entries
+    /// were written in between.
+    #[allow(clippy::too_many_arguments)]
+    async fn query<T, V, E>(
+        &self,
+        column: &str,
+        value: V,
+        sort_column: &str,
+        sort_key: fn(&T::Entry) -> u64,
+        minimum_sort_key: Option<u64>,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<E>, Error>
+    where
+        T: VersionedTable,
+        V: for<'q> sqlx::Encode<'q, Postgres> + sqlx::Type<Postgres> + Send + 'static,
+        E: From<T::Entry>,
+    {
+        let token: Option<(u64, Key<T>)> = maybe_next_token.map(detokenize).transpose()?;
+
+        let mut sql = format!(
+            "SELECT {} FROM {} WHERE {column} = $1 AND ($2::BIGINT IS NULL OR {sort_column} >= $2)",
+            T::COLUMNS,
+            T::TABLE,
+        );
+        let limit = match token {
+            Some(_) => {
+                sql += &format!(" AND ({sort_column}, {}) < ($3, $4, $5)", T::KEY_COLUMNS);
+                "$6"
+            }
+            None => "$3",
+        };
+        sql += &format!(
+            " ORDER BY {sort_column} DESC, {} DESC LIMIT {limit}",
+            T::KEY_COLUMNS.replace(',', " DESC,"),
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(value)
+            .bind(minimum_sort_key.map(to_db).transpose()?);
+        if let Some((last_sort_key, last_key)) = &token {
+            query = T::bind_key(query.bind(to_db(*last_sort_key)?), last_key)?;
+        }
+        // Fetch one more row than was asked for so that we know whether
+        // there is another page.
+        let rows = query
+            .bind(maybe_page_size.map(|size| size as i64 + 1))
+            .fetch_all(&self.pool)
+            .await?;
+        let mut entries: Vec<T::Entry> = parse_rows::<T::Row, _>(&rows)?;
+
+        let next_token = match (maybe_page_size, entries.len()) {
+            (Some(size), len) if len > size as usize => {
+                entries.truncate(size as usize);
+                entries
+                    .last()
+                    .map(|last| tokenize((sort_key(last), last.key())))
+                    .transpose()?
+            }
+            _ => None,
+        };
+
+        Ok((entries.into_iter().map(E::from).collect(), next_token))
+    }
+
+    /// Gets a page of the entries returned by `sql`, which selects the
+    /// entries of one partition of a table. The statement takes the
+    /// partition key, the exclusive maximum sort key or null, and the page
+    /// size, and returns the entries ordered by the sort key descending.
+    ///
+    /// The sort key is unique within a partition, so the next token is the
+    /// sort key of the last entry of the page.
+    async fn query_partition<R, E, P, S>(
+        &self,
+        sql: &str,
+        partition: P,
+        sort_key: fn(&E) -> Result<S, Error>,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<E>, Error>
+    where
+        R: for<'r> FromRow<'r, PgRow> + TryInto<E, Error = Error>,
+        P: for<'q> sqlx::Encode<'q, Postgres> + sqlx::Type<Postgres> + Send + 'static,
+        S: for<'q> sqlx::Encode<'q, Postgres>
+            + sqlx::Type<Postgres>
+            + serde::Serialize
+            + serde::de::DeserializeOwned
+            + Send
+            + 'static,
+    {
+        let token: Option<S> = maybe_next_token.map(detokenize).transpose()?;
+        let rows = sqlx::query(sql)
+            .bind(partition)
+            .bind(token)
+            .bind(maybe_page_size.map(|size| size as i64 + 1))
+            .fetch_all(&self.pool)
+            .await?;
+        let mut entries: Vec<E> = parse_rows::<R, _>(&rows)?;
+
+        let next_token = match (maybe_page_size, entries.len()) {
+            (Some(size), len) if len > size as usize => {
+                entries.truncate(size as usize);
+                entries
+                    .last()
+                    .map(|last| sort_key(last).and_then(tokenize))
+                    .transpose()?
+            }
+            _ => None,
+        };
+
+        Ok((entries, next_token))
+    }
+}
+
+/// The sort key of the status, recipient and sender indexes of deposits.
+fn deposit_last_update_height(entry: &DepositEntry) -> u64 {
+    entry.last_update_height
+}
+
+/// The sort key of the accepted index of deposits.
+fn deposit_accepted_at(entry: &DepositEntry) -> u64 {
+    entry.accepted_at.unwrap_or_default()
+}
+
+/// The sort key of the withdrawal table queries.
+fn withdrawal_last_update_height(entry: &WithdrawalEntry) -> u64 {
+    entry.last_update_height
+}
+
+/// The sort key of the accepted index of withdrawals.
+fn withdrawal_accepted_at(entry: &WithdrawalEntry) -> u64 {
+    entry.accepted_at.unwrap_or_default()
+}
+
+/// Columns of the api state table.
+const API_STATE_COLUMNS: &str = "version, reorganizing, chaintip_height, chaintip_hash, updated_at";
+/// Columns of the limit table.
+const LIMIT_COLUMNS: &str = "account, timestamp, peg_cap, per_deposit_minimum, \
+    per_deposit_cap, per_withdrawal_cap, rolling_window_seconds, rolling_deposit_cap, \
+    rolling_withdrawal_cap";
+/// Columns of the webhook table.
+const WEBHOOK_COLUMNS: &str =
+    "webhook_id, created_at, owner, url, secret, filter::text AS filter, filter_key";
+/// Columns of the api key table.
+const API_KEY_COLUMNS: &str =
+    "key_id, created_at, key_hash, description, scopes::text AS scopes, revoked";
+
+impl EmilyStorage for PgStore {
+    async fn put_deposit_entry(&self, entry: &DepositEntry) -> Result<(), Error> {
+        self.put_entry::<Deposits>(entry).await
+    }
+
+    async fn put_deposit_entry_with_version(&self, entry: &mut DepositEntry) -> Result<(), Error> {
+        self.put_entry_with_version::<Deposits>(entry).await
+    }
+
+    async fn get_deposit_entry(&self, key: &DepositEntryKey) -> Result<DepositEntry, Error> {
+        self.get_entry::<Deposits>(key).await
+    }
+
+    async fn query_deposit_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "status",
+            status_to_db(status)?,
+            "last_update_height",
+            deposit_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn count_deposit_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM emily_deposits WHERE status = $1")
+                .bind(status_to_db(status)?)
+                .fetch_one(&self.pool)
+                .await?;
+        from_db(count)
+    }
+
+    async fn query_deposit_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoByRecipientEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "recipient",
+            recipient.to_string(),
+            "last_update_height",
+            deposit_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoBySenderEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "sender",
+            sender.to_string(),
+            "last_update_height",
+            deposit_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositInfoEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "status",
+            status_to_db(status)?,
+            "last_update_height",
+            deposit_last_update_height,
+            Some(minimum_height),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositAcceptedEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "status",
+            status_to_db(status)?,
+            "accepted_at",
+            deposit_accepted_at,
+            Some(minimum_accepted_at),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_deposit_entries_by_txid(
+        &self,
+        bitcoin_txid: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<DepositEntry>, Error> {
+        self.query::<Deposits, _, _>(
+            "bitcoin_txid",
+            bitcoin_txid.to_string(),
+            "bitcoin_tx_output_index",
+            |entry| u64::from(entry.key.bitcoin_tx_output_index),
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn update_deposit(&self, update: &DepositUpdatePackage) -> Result<DepositEntry, Error> {
+        self.update_entry_with_version::<Deposits, _>(&update.key, update.version, |entry| {
+            entry.status = (&update.event.status).into();
+            entry.last_update_height = update.event.stacks_block_height;
+            entry.last_update_block_hash = update.event.stacks_block_hash.clone();
+            entry.accepted_at = update
+                .event
+                .status
+                .counts_towards_rolling_volume()
+                .then(|| entry.accepted_at.unwrap_or(update.timestamp));
+            entry.history.push(update.event.clone());
+        })
+        .await
+    }
+
+    async fn put_withdrawal_entry(&self, entry: &WithdrawalEntry) -> Result<(), Error> {
+        self.put_entry::<Withdrawals>(entry).await
+    }
+
+    async fn put_withdrawal_entry_with_version(
+        &self,
+        entry: &mut WithdrawalEntry,
+    ) -> Result<(), Error> {
+        self.put_entry_with_version::<Withdrawals>(entry).await
+    }
+
+    async fn query_withdrawal_entries_by_request_id(
+        &self,
+        request_id: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalEntry>, Error> {
+        // There is one entry for each stacks block that the request was
+        // seen in, so these are ordered by the height of that block.
+        self.query::<Withdrawals, _, _>(
+            "request_id",
+            to_db(request_id)?,
+            "stacks_block_height",
+            |entry| entry.stacks_block_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_by_status(
+        &self,
+        status: &Status,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        self.query::<Withdrawals, _, _>(
+            "status",
+            status_to_db(status)?,
+            "last_update_height",
+            withdrawal_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn count_withdrawal_entries_by_status(&self, status: &Status) -> Result<u64, Error> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM emily_withdrawals WHERE status = $1")
+                .bind(status_to_db(status)?)
+                .fetch_one(&self.pool)
+                .await?;
+        from_db(count)
+    }
+
+    async fn query_withdrawal_entries_by_recipient(
+        &self,
+        recipient: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoByRecipientEntry>, Error> {
+        self.query::<Withdrawals, _, _>(
+            "recipient",
+            recipient.to_string(),
+            "last_update_height",
+            withdrawal_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_by_sender(
+        &self,
+        sender: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoBySenderEntry>, Error> {
+        self.query::<Withdrawals, _, _>(
+            "sender",
+            sender.to_string(),
+            "last_update_height",
+            withdrawal_last_update_height,
+            None,
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_modified_from_height(
+        &self,
+        status: &Status,
+        minimum_height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalInfoEntry>, Error> {
+        self.query::<Withdrawals, _, _>(
+            "status",
+            status_to_db(status)?,
+            "last_update_height",
+            withdrawal_last_update_height,
+            Some(minimum_height),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn query_withdrawal_entries_accepted_since(
+        &self,
+        status: &Status,
+        minimum_accepted_at: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WithdrawalAcceptedEntry>, Error> {
+        self.query::<Withdrawals, _, _>(
+            "status",
+            status_to_db(status)?,
+            "accepted_at",
+            withdrawal_accepted_at,
+            Some(minimum_accepted_at),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn update_withdrawal(
+        &self,
+        update: &WithdrawalUpdatePackage,
+    ) -> Result<WithdrawalEntry, Error> {
+        self.update_entry_with_version::<Withdrawals, _>(&update.key, update.version, |entry| {
+            entry.status = (&update.event.status).into();
+            entry.last_update_height = update.event.stacks_block_height;
+            entry.last_update_block_hash = update.event.stacks_block_hash.clone();
+            entry.accepted_at = update
+                .event
+                .status
+                .counts_towards_rolling_volume()
+                .then(|| entry.accepted_at.unwrap_or(update.timestamp));
+            entry.history.push(update.event.clone());
+        })
+        .await
+    }
+
+    async fn delete_withdrawal_entry(&self, key: &WithdrawalEntryKey) -> Result<(), Error> {
+        sqlx::query(
+            "DELETE FROM emily_withdrawals WHERE request_id = $1 AND stacks_block_hash = $2",
+        )
+        .bind(to_db(key.request_id)?)
+        .bind(&key.stacks_block_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn put_chainstate_entry(&self, entry: &ChainstateEntry) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO emily_chainstates (height, hash) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(to_db(entry.key.height)?)
+        .bind(&entry.key.hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_chainstate_entry(&self, key: &ChainstateEntryKey) -> Result<(), Error> {
+        sqlx::query("DELETE FROM emily_chainstates WHERE height = $1 AND hash = $2")
+            .bind(to_db(key.height)?)
+            .bind(&key.hash)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn query_chainstate_entries_by_height(
+        &self,
+        height: u64,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ChainstateEntry>, Error> {
+        self.query_partition::<ChainstateRow, _, _, _>(
+            "SELECT height, hash FROM emily_chainstates
+             WHERE height = $1 AND ($2::TEXT IS NULL OR hash < $2)
+             ORDER BY hash DESC
+             LIMIT $3",
+            to_db(height)?,
+            |entry: &ChainstateEntry| Ok(entry.key.hash.clone()),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn get_api_state_entry(&self) -> Result<ApiStateEntry, Error> {
+        let sql = format!("SELECT {API_STATE_COLUMNS} FROM emily_api_state");
+        let row = sqlx::query(&sql)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(Error::NotFound)?;
+
+        ApiStateRow::from_row(&row)?.try_into()
+    }
+
+    async fn put_api_state_entry(&self, entry: &ApiStateEntry) -> Result<(), Error> {
+        let sql = format!(
+            "INSERT INTO emily_api_state ({API_STATE_COLUMNS}) VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET
+                version = EXCLUDED.version,
+                reorganizing = EXCLUDED.reorganizing,
+                chaintip_height = EXCLUDED.chaintip_height,
+                chaintip_hash = EXCLUDED.chaintip_hash,
+                updated_at = EXCLUDED.updated_at"
+        );
+        bind_api_state(sqlx::query(&sql), entry)?
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn put_api_state_entry_with_version(
+        &self,
+        entry: &mut ApiStateEntry,
+    ) -> Result<(), Error> {
+        let expected_version = entry.get_version();
+        entry.increment_version();
+
+        let result = bind_api_state(
+            sqlx::query(
+                "UPDATE emily_api_state SET
+                    version = $1,
+                    reorganizing = $2,
+                    chaintip_height = $3,
+                    chaintip_hash = $4,
+                    updated_at = $5
+                 WHERE version = $6",
+            ),
+            entry,
+        )?
+        .bind(to_db(expected_version)?)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::VersionConflict);
+        }
+        Ok(())
+    }
+
+    async fn get_reconciliation_cursor_entry(&self) -> Result<ReconciliationCursorEntry, Error> {
+        let height: i64 =
+            sqlx::query_scalar("SELECT stacks_block_height FROM emily_reconciliation_cursor")
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or(Error::NotFound)?;
+        Ok(ReconciliationCursorEntry::new(from_db(height)?))
+    }
+
+    async fn put_reconciliation_cursor_entry(
+        &self,
+        entry: &ReconciliationCursorEntry,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO emily_reconciliation_cursor (stacks_block_height) VALUES ($1)
+             ON CONFLICT (id) DO UPDATE SET
+                stacks_block_height = EXCLUDED.stacks_block_height",
+        )
+        .bind(to_db(entry.stacks_block_height)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_all_limit_entries(&self) -> Result<Vec<LimitEntry>, Error> {
+        let sql = format!("SELECT {LIMIT_COLUMNS} FROM emily_limits");
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        parse_rows::<LimitRow, _>(&rows)
+    }
+
+    async fn query_limit_entries_by_account(
+        &self,
+        account: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<LimitEntry>, Error> {
+        let sql = format!(
+            "SELECT {LIMIT_COLUMNS} FROM emily_limits
+             WHERE account = $1 AND ($2::BIGINT IS NULL OR timestamp < $2)
+             ORDER BY timestamp DESC
+             LIMIT $3"
+        );
+        self.query_partition::<LimitRow, _, _, _>(
+            &sql,
+            account.to_string(),
+            |entry: &LimitEntry| to_db(entry.key.timestamp),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn put_limit_entry(&self, entry: &LimitEntry) -> Result<(), Error> {
+        let sql = format!(
+            "INSERT INTO emily_limits ({LIMIT_COLUMNS})
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+             ON CONFLICT (account, timestamp) DO UPDATE SET
+                peg_cap = EXCLUDED.peg_cap,
+                per_deposit_minimum = EXCLUDED.per_deposit_minimum,
+                per_deposit_cap = EXCLUDED.per_deposit_cap,
+                per_withdrawal_cap = EXCLUDED.per_withdrawal_cap,
+                rolling_window_seconds = EXCLUDED.rolling_window_seconds,
+                rolling_deposit_cap = EXCLUDED.rolling_deposit_cap,
+                rolling_withdrawal_cap = EXCLUDED.rolling_withdrawal_cap"
+        );
+        sqlx::query(&sql)
+            .bind(&entry.key.account)
+            .bind(to_db(entry.key.timestamp)?)
+            .bind(entry.peg_cap.map(to_db).transpose()?)
+            .bind(entry.per_deposit_minimum.map(to_db).transpose()?)
+            .bind(entry.per_deposit_cap.map(to_db).transpose()?)
+            .bind(entry.per_withdrawal_cap.map(to_db).transpose()?)
+            .bind(entry.rolling_window_seconds.map(to_db).transpose()?)
+            .bind(entry.rolling_deposit_cap.map(to_db).transpose()?)
+            .bind(entry.rolling_withdrawal_cap.map(to_db).transpose()?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_all_webhook_entries(&self) -> Result<Vec<WebhookEntry>, Error> {
+        let sql = format!("SELECT {WEBHOOK_COLUMNS} FROM emily_webhooks");
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        parse_rows::<WebhookRow, _>(&rows)
+    }
+
+    async fn query_webhook_entries_by_filter_key(
+        &self,
+        filter_key: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<WebhookByFilterEntry>, Error> {
+        let sql = format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM emily_webhooks
+             WHERE filter_key = $1 AND ($2::TEXT IS NULL OR webhook_id < $2)
+             ORDER BY webhook_id DESC
+             LIMIT $3"
+        );
+        let (entries, next_token) = self
+            .query_partition::<WebhookRow, WebhookEntry, _, _>(
+                &sql,
+                filter_key.to_string(),
+                |entry: &WebhookEntry| Ok(entry.key.webhook_id.clone()),
+                maybe_next_token,
+                maybe_page_size,
+            )
+            .await?;
+        Ok((entries.into_iter().map(Into::into).collect(), next_token))
+    }
+
+    async fn put_webhook_entry(&self, entry: &WebhookEntry) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO emily_webhooks
+                (webhook_id, created_at, owner, url, secret, filter, filter_key)
+             VALUES ($1, $2, $3, $4, $5, $6::jsonb, $7)
+             ON CONFLICT (webhook_id, created_at) DO UPDATE SET
+                owner = EXCLUDED.owner,
+                url = EXCLUDED.url,
+                secret = EXCLUDED.secret,
+                filter = EXCLUDED.filter,
+                filter_key = EXCLUDED.filter_key",
+        )
+        .bind(&entry.key.webhook_id)
+        .bind(to_db(entry.key.created_at)?)
+        .bind(&entry.owner)
+        .bind(&entry.url)
+        .bind(&entry.secret)
+        .bind(serde_json::to_string(&entry.filter)?)
+        .bind(&entry.filter_key)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_webhook_entry(&self, key: &WebhookEntryKey) -> Result<(), Error> {
+        sqlx::query("DELETE FROM emily_webhooks WHERE webhook_id = $1 AND created_at = $2")
+            .bind(&key.webhook_id)
+            .bind(to_db(key.created_at)?)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_all_api_key_entries(&self) -> Result<Vec<ApiKeyEntry>, Error> {
+        let sql = format!("SELECT {API_KEY_COLUMNS} FROM emily_api_keys");
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        parse_rows::<ApiKeyRow, _>(&rows)
+    }
+
+    async fn query_api_key_entries_by_id(
+        &self,
+        key_id: &str,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<u16>,
+    ) -> Result<Page<ApiKeyEntry>, Error> {
+        let sql = format!(
+            "SELECT {API_KEY_COLUMNS} FROM emily_api_keys
+             WHERE key_id = $1 AND ($2::BIGINT IS NULL OR created_at < $2)
+             ORDER BY created_at DESC
+             LIMIT $3"
+        );
+        self.query_partition::<ApiKeyRow, _, _, _>(
+            &sql,
+            key_id.to_string(),
+            |entry: &ApiKeyEntry| to_db(entry.key.created_at),
+            maybe_next_token,
+            maybe_page_size,
+        )
+        .await
+    }
+
+    async fn put_api_key_entry(&self, entry: &ApiKeyEntry) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO emily_api_keys (key_id, created_at, key_hash, description, scopes, revoked)
+             VALUES ($1, $2, $3, $4, $5::jsonb, $6)
+             ON CONFLICT (key_id, created_at) DO UPDATE SET
+                key_hash = EXCLUDED.key_hash,
+                description = EXCLUDED.description,
+                scopes = EXCLUDED.scopes,
+                revoked = EXCLUDED.revoked",
+        )
+        .bind(&entry.key.key_id)
+        .bind(to_db(entry.key.created_at)?)
+        .bind(&entry.key_hash)
+        .bind(&entry.description)
+        .bind(serde_json::to_string(&entry.scopes)?)
+        .bind(entry.revoked)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    async fn wipe_all(&self) -> Result<(), Error> {
+        sqlx::query(
+            "TRUNCATE emily_deposits, emily_withdrawals, emily_chainstates, emily_api_state,
+                emily_reconciliation_cursor, emily_limits, emily_webhooks, emily_api_keys",
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
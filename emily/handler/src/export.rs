@@ -0,0 +1,471 @@
+//! Bulk export of deposits and withdrawals.
+//!
+//! An export reads every deposit and then every withdrawal that matches the
+//! query, each with its full history, and ends with a summary of the
+//! confirmed operations. Records are formatted and handed over as soon as
+//! they're read so an export is never held in memory as a whole.
+//!
+//! Operations that change status while an export is running can be missed
+//! or exported twice, since they move between the status partitions that
+//! the export reads from.
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::api::models::common::requests::OperationSearchQuery;
+use crate::api::models::common::{Fulfillment, Status};
+use crate::api::models::deposit::Deposit;
+use crate::api::models::export::requests::ExportQuery;
+use crate::api::models::export::{
+    ExportEvent, ExportFormat, ExportRecord, ExportSummary, ExportedDeposit, ExportedWithdrawal,
+};
+use crate::api::models::withdrawal::Withdrawal;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors::{self, ALL_STATUSES};
+use crate::database::entries::deposit::{DepositEntry, DepositEvent};
+use crate::database::entries::withdrawal::{WithdrawalEntry, WithdrawalEvent};
+use crate::database::entries::StatusEntry;
+
+/// The number of index entries read at a time.
+const EXPORT_PAGE_SIZE: u16 = 100;
+
+/// The number of formatted chunks buffered between the export and its
+/// consumer before the export waits for the consumer to catch up.
+const EXPORT_CHANNEL_CAPACITY: usize = 64;
+
+/// The columns of a CSV export.
+const CSV_COLUMNS: &[&str] = &[
+    "type",
+    "bitcoin_txid",
+    "bitcoin_tx_output_index",
+    "request_id",
+    "stacks_block_hash",
+    "stacks_block_height",
+    "recipient",
+    "sender",
+    "amount",
+    "max_fee",
+    "status",
+    "status_message",
+    "last_update_height",
+    "last_update_block_hash",
+    "btc_fee",
+    "fulfillment",
+    "history",
+];
+
+/// Start exporting the operations that match the query in the background,
+/// returning the receiving end of the formatted export.
+///
+/// The export ends with an error if reading or formatting an operation
+/// fails, in which case the export is incomplete and has no summary.
+pub fn spawn_export(
+    context: EmilyContext,
+    query: ExportQuery,
+) -> mpsc::Receiver<Result<String, Error>> {
+    let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        if let Err(error) = export_operations(&context, &query, &sender).await {
+            warn!(%error, "export failed");
+            // The consumer is gone if this fails, so there's nobody to tell.
+            let _ = sender.send(Err(error)).await;
+        }
+    });
+    receiver
+}
+
+/// Export the operations that match the query, sending each formatted chunk
+/// of the export as soon as it's ready. Stops early if the receiver is
+/// dropped.
+pub async fn export_operations(
+    context: &EmilyContext,
+    query: &ExportQuery,
+    chunks: &mpsc::Sender<Result<String, Error>>,
+) -> Result<(), Error> {
+    let search = OperationSearchQuery {
+        status: Some(
+            query
+                .status
+                .clone()
+                .unwrap_or_else(|| ALL_STATUSES.to_vec()),
+        ),
+        min_height: query.min_height,
+        max_height: query.max_height,
+        ..Default::default()
+    };
+    let mut writer = ExportWriter::new(query.format);
+    send(chunks, writer.start()).await?;
+
+    let mut next_token: Option<String> = None;
+    loop {
+        let (entries, token) =
+            accessors::search_deposit_entries(context, &search, next_token, Some(EXPORT_PAGE_SIZE))
+                .await?;
+        for entry in entries {
+            let deposit = accessors::get_deposit_entry(context, &entry.primary_index_key).await?;
+            send(chunks, writer.write_deposit(deposit.try_into()?)?).await?;
+        }
+        next_token = token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    loop {
+        let (entries, token) = accessors::search_withdrawal_entries(
+            context,
+            &search,
+            next_token,
+            Some(EXPORT_PAGE_SIZE),
+        )
+        .await?;
+        for entry in entries {
+            let withdrawal =
+                accessors::get_withdrawal_entry(context, &entry.primary_index_key.request_id)
+                    .await?;
+            send(chunks, writer.write_withdrawal(withdrawal.try_into()?)?).await?;
+        }
+        next_token = token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+
+    send(chunks, writer.finish()?).await
+}
+
+/// Send a chunk of the export, skipping empty chunks.
+async fn send(chunks: &mpsc::Sender<Result<String, Error>>, chunk: String) -> Result<(), Error> {
+    if chunk.is_empty() {
+        return Ok(());
+    }
+    chunks
+        .send(Ok(chunk))
+        .await
+        .map_err(|_| Error::Debug("export receiver was dropped".to_string()))
+}
+
+/// Formats the records of an export while keeping track of the summary.
+#[derive(Debug)]
+pub struct ExportWriter {
+    /// The format of the export.
+    format: ExportFormat,
+    /// The summary of the operations written so far.
+    summary: ExportSummary,
+}
+
+impl ExportWriter {
+    /// Create a writer for an export in the given format.
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            summary: ExportSummary::default(),
+        }
+    }
+
+    /// The text that starts the export.
+    pub fn start(&self) -> String {
+        match self.format {
+            ExportFormat::Jsonl => String::new(),
+            ExportFormat::Csv => csv_line(CSV_COLUMNS.iter().map(|column| column.to_string())),
+        }
+    }
+
+    /// Format a deposit.
+    pub fn write_deposit(&mut self, deposit: ExportedDeposit) -> Result<String, Error> {
+        self.summary.add_deposit(&deposit.deposit);
+        match self.format {
+            ExportFormat::Jsonl => json_line(&ExportRecord::Deposit(deposit)),
+            ExportFormat::Csv => deposit_csv_line(&deposit),
+        }
+    }
+
+    /// Format a withdrawal.
+    pub fn write_withdrawal(&mut self, withdrawal: ExportedWithdrawal) -> Result<String, Error> {
+        self.summary.add_withdrawal(&withdrawal.withdrawal);
+        match self.format {
+            ExportFormat::Jsonl => json_line(&ExportRecord::Withdrawal(withdrawal)),
+            ExportFormat::Csv => withdrawal_csv_line(&withdrawal),
+        }
+    }
+
+    /// Format the summary, which ends the export.
+    pub fn finish(self) -> Result<String, Error> {
+        let summary = self.summary;
+        match self.format {
+            ExportFormat::Jsonl => json_line(&ExportRecord::Summary(summary)),
+            ExportFormat::Csv => {
+                let rows = [
+                    ("deposit_count", summary.deposit_count.to_string()),
+                    ("withdrawal_count", summary.withdrawal_count.to_string()),
+                    (
+                        "confirmed_deposit_count",
+                        summary.confirmed_deposit_count.to_string(),
+                    ),
+                    (
+                        "confirmed_deposit_amount",
+                        summary.confirmed_deposit_amount.to_string(),
+                    ),
+                    (
+                        "confirmed_deposit_fees",
+                        summary.confirmed_deposit_fees.to_string(),
+                    ),
+                    (
+                        "confirmed_withdrawal_count",
+                        summary.confirmed_withdrawal_count.to_string(),
+                    ),
+                    (
+                        "confirmed_withdrawal_amount",
+                        summary.confirmed_withdrawal_amount.to_string(),
+                    ),
+                    (
+                        "confirmed_withdrawal_fees",
+                        summary.confirmed_withdrawal_fees.to_string(),
+                    ),
+                    (
+                        "net_confirmed_amount",
+                        summary.net_confirmed_amount.to_string(),
+                    ),
+                ];
+                let mut text = String::from("\nmetric,value\n");
+                for (metric, value) in rows {
+                    text.push_str(&csv_line([metric.to_string(), value]));
+                }
+                Ok(text)
+            }
+        }
+    }
+}
+
+/// Serialize a record as a single line of JSON.
+fn json_line(record: &ExportRecord) -> Result<String, Error> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Format a deposit as a CSV line.
+fn deposit_csv_line(exported: &ExportedDeposit) -> Result<String, Error> {
+    let deposit: &Deposit = &exported.deposit;
+    Ok(csv_line([
+        "deposit".to_string(),
+        deposit.bitcoin_txid.clone(),
+        deposit.bitcoin_tx_output_index.to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        deposit.recipient.clone(),
+        deposit.sender.clone().unwrap_or_default(),
+        deposit.amount.to_string(),
+        deposit.parameters.max_fee.to_string(),
+        status_name(&deposit.status)?,
+        deposit.status_message.clone(),
+        deposit.last_update_height.to_string(),
+        deposit.last_update_block_hash.clone(),
+        fee_field(deposit.fulfillment.as_ref().map(|f| f.btc_fee)),
+        optional_json_field(deposit.fulfillment.as_ref())?,
+        serde_json::to_string(&exported.history)?,
+    ]))
+}
+
+/// Format a withdrawal as a CSV line.
+fn withdrawal_csv_line(exported: &ExportedWithdrawal) -> Result<String, Error> {
+    let withdrawal: &Withdrawal = &exported.withdrawal;
+    Ok(csv_line([
+        "withdrawal".to_string(),
+        String::new(),
+        String::new(),
+        withdrawal.request_id.to_string(),
+        withdrawal.stacks_block_hash.clone(),
+        withdrawal.stacks_block_height.to_string(),
+        withdrawal.recipient.clone(),
+        withdrawal.sender.clone().unwrap_or_default(),
+        withdrawal.amount.to_string(),
+        withdrawal.parameters.max_fee.to_string(),
+        status_name(&withdrawal.status)?,
+        withdrawal.status_message.clone(),
+        withdrawal.last_update_height.to_string(),
+        withdrawal.last_update_block_hash.clone(),
+        fee_field(withdrawal.fulfillment.as_ref().map(|f| f.btc_fee)),
+        optional_json_field(withdrawal.fulfillment.as_ref())?,
+        serde_json::to_string(&exported.history)?,
+    ]))
+}
+
+/// The name of a status as it appears in the API.
+fn status_name(status: &Status) -> Result<String, Error> {
+    match serde_json::to_value(status)? {
+        serde_json::Value::String(name) => Ok(name),
+        other => Err(Error::Debug(format!("unexpected status encoding: {other}"))),
+    }
+}
+
+/// A fee, or an empty field if there's no fee.
+fn fee_field(fee: Option<u64>) -> String {
+    fee.map(|fee| fee.to_string()).unwrap_or_default()
+}
+
+/// A value as JSON, or an empty field if there's no value.
+fn optional_json_field<T: serde::Serialize>(value: Option<&T>) -> Result<String, Error> {
+    value
+        .map(serde_json::to_string)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(Error::from)
+}
+
+/// Join the fields into a CSV line, quoting the fields that need it.
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// The fulfillment carried by a status, if it has one.
+fn status_fulfillment(status: &StatusEntry) -> Option<Fulfillment> {
+    match status {
+        StatusEntry::Confirmed(fulfillment) => Some(fulfillment.clone()),
+        _ => None,
+    }
+}
+
+impl From<&DepositEvent> for ExportEvent {
+    fn from(event: &DepositEvent) -> Self {
+        ExportEvent {
+            status: (&event.status).into(),
+            message: event.message.clone(),
+            stacks_block_height: event.stacks_block_height,
+            stacks_block_hash: event.stacks_block_hash.clone(),
+            fulfillment: status_fulfillment(&event.status),
+        }
+    }
+}
+
+impl From<&WithdrawalEvent> for ExportEvent {
+    fn from(event: &WithdrawalEvent) -> Self {
+        ExportEvent {
+            status: (&event.status).into(),
+            message: event.message.clone(),
+            stacks_block_height: event.stacks_block_height,
+            stacks_block_hash: event.stacks_block_hash.clone(),
+            fulfillment: status_fulfillment(&event.status),
+        }
+    }
+}
+
+impl TryFrom<DepositEntry> for ExportedDeposit {
+    type Error = Error;
+    fn try_from(entry: DepositEntry) -> Result<Self, Self::Error> {
+        let history = entry.history.iter().map(ExportEvent::from).collect();
+        Ok(ExportedDeposit {
+            deposit: entry.try_into()?,
+            history,
+        })
+    }
+}
+
+impl TryFrom<WithdrawalEntry> for ExportedWithdrawal {
+    type Error = Error;
+    fn try_from(entry: WithdrawalEntry) -> Result<Self, Self::Error> {
+        let history = entry.history.iter().map(ExportEvent::from).collect();
+        Ok(ExportedWithdrawal {
+            withdrawal: entry.try_into()?,
+            history,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confirmed_deposit(amount: u64, btc_fee: u64) -> ExportedDeposit {
+        ExportedDeposit {
+            deposit: Deposit {
+                bitcoin_txid: "txid".to_string(),
+                amount,
+                status: Status::Confirmed,
+                status_message: "minted, finally".to_string(),
+                fulfillment: Some(Fulfillment { btc_fee, ..Default::default() }),
+                ..Default::default()
+            },
+            history: vec![ExportEvent::default()],
+        }
+    }
+
+    fn withdrawal(amount: u64, status: Status) -> ExportedWithdrawal {
+        ExportedWithdrawal {
+            withdrawal: Withdrawal {
+                request_id: 7,
+                amount,
+                status,
+                ..Default::default()
+            },
+            history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn summary_reconciles_confirmed_operations() {
+        let mut writer = ExportWriter::new(ExportFormat::Jsonl);
+        writer.write_deposit(confirmed_deposit(1000, 10)).unwrap();
+        writer.write_deposit(confirmed_deposit(500, 5)).unwrap();
+        writer
+            .write_withdrawal(withdrawal(300, Status::Confirmed))
+            .unwrap();
+        writer
+            .write_withdrawal(withdrawal(5000, Status::Pending))
+            .unwrap();
+        let summary = writer.summary.clone();
+
+        assert_eq!(summary.deposit_count, 2);
+        assert_eq!(summary.withdrawal_count, 2);
+        assert_eq!(summary.confirmed_deposit_amount, 1500);
+        assert_eq!(summary.confirmed_deposit_fees, 15);
+        assert_eq!(summary.confirmed_withdrawal_count, 1);
+        assert_eq!(summary.confirmed_withdrawal_amount, 300);
+        assert_eq!(summary.net_confirmed_amount, 1185);
+
+        let last_line = writer.finish().unwrap();
+        let record: ExportRecord = serde_json::from_str(&last_line).unwrap();
+        assert_eq!(record, ExportRecord::Summary(summary));
+    }
+
+    #[test]
+    fn csv_rows_match_the_header() {
+        let mut writer = ExportWriter::new(ExportFormat::Csv);
+        let header = writer.start();
+        let row = writer.write_deposit(confirmed_deposit(1000, 10)).unwrap();
+
+        assert_eq!(header.trim_end().split(',').count(), CSV_COLUMNS.len());
+        assert!(row.starts_with("deposit,txid,"));
+        // The history is JSON, so it's quoted and its quotes are doubled.
+        assert!(row.contains(r#","[{""status"":""pending"","#));
+        assert!(writer
+            .finish()
+            .unwrap()
+            .contains("net_confirmed_amount,990\n"));
+    }
+}
@@ -5,4 +5,10 @@ pub mod api;
 pub mod common;
 pub mod context;
 pub mod database;
+pub mod export;
 pub mod logging;
+pub mod metrics;
+pub mod reconciliation;
+pub mod registry;
+pub mod subscriptions;
+pub mod verification;
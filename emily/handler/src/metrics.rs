@@ -0,0 +1,377 @@
+//! Metrics for the Emily API.
+//!
+//! `emily-server` serves the metrics in the prometheus format from a port
+//! of its own. The lambda has nowhere to serve them from, so it prints each
+//! measurement as a CloudWatch embedded metric format log line instead,
+//! which CloudWatch turns back into metrics.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use metrics::{
+    Counter, CounterFn, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+    SharedString, Unit,
+};
+use metrics_exporter_prometheus::PrometheusBuilder;
+use serde_json::{json, Map, Value};
+use tracing::warn;
+
+use crate::api::models::common::Status;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors::{self, ALL_STATUSES};
+
+/// The buckets used for metric histograms, in seconds.
+const METRIC_BUCKETS: [f64; 10] = [
+    1e-3,
+    5e-3,
+    1e-2,
+    5e-2,
+    0.1,
+    0.5,
+    1.0,
+    5.0,
+    20.0,
+    f64::INFINITY,
+];
+
+/// The quantiles to use when rendering histograms.
+const METRIC_QUANTILES: [f64; 8] = [0.0, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99, 1.0];
+
+/// The CloudWatch namespace that the lambda's metrics are put in.
+const EMBEDDED_METRICS_NAMESPACE: &str = "Emily";
+
+/// Path segments longer than this are taken to be path parameters.
+const MAX_STATIC_SEGMENT_LENGTH: usize = 32;
+
+/// All metrics captured by the Emily API.
+#[derive(strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Metrics {
+    /// The total number of requests handled, labeled with the route, the
+    /// method and the status code of the response.
+    HttpRequestsTotal,
+    /// The time it took to handle a request in seconds, with the same
+    /// labels as the request count.
+    HttpRequestDurationSeconds,
+    /// The total number of calls made to the storage backend, labeled with
+    /// the backend and the operation.
+    DatabaseCallsTotal,
+    /// The total number of storage backend calls that failed, with the
+    /// same labels as the call count along with the kind of error.
+    DatabaseErrorsTotal,
+    /// The number of deposits or withdrawals in each status. We use a label
+    /// to distinguish between the two.
+    OperationsByStatus,
+    /// The total number of reorgs that the API has executed.
+    ReorgsTotal,
+    /// The height of the stacks chain tip that the API is following.
+    ChainTipHeight,
+}
+
+impl From<Metrics> for KeyName {
+    fn from(value: Metrics) -> Self {
+        KeyName::from_const_str(value.into())
+    }
+}
+
+/// Label for deposit based metrics.
+pub const DEPOSIT: &str = "deposit";
+
+/// Label for withdrawal based metrics.
+pub const WITHDRAWAL: &str = "withdrawal";
+
+/// Serve the metrics in the prometheus format at the given address.
+pub fn setup_prometheus(addr: SocketAddr) {
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .set_buckets(&METRIC_BUCKETS)
+        .expect("received an empty slice of metric buckets")
+        .set_quantiles(&METRIC_QUANTILES)
+        .expect("received an empty slice of metric quantiles")
+        .install()
+        .expect("could not install the prometheus server");
+}
+
+/// Print the metrics as embedded metric format log lines.
+pub fn setup_embedded_metrics() {
+    metrics::set_global_recorder(EmbeddedMetricRecorder)
+        .expect("could not install the embedded metric recorder");
+}
+
+/// A warp filter that records the count and duration of each request.
+pub fn log_requests() -> warp::log::Log<impl Fn(warp::log::Info<'_>) + Copy + Send + Sync> {
+    warp::log::custom(|info| {
+        let labels = [
+            ("route", route_label(info.path())),
+            ("method", info.method().to_string()),
+            ("status", info.status().as_u16().to_string()),
+        ];
+        metrics::counter!(Metrics::HttpRequestsTotal, &labels).increment(1);
+        metrics::histogram!(Metrics::HttpRequestDurationSeconds, &labels)
+            .record(info.elapsed().as_secs_f64());
+    })
+}
+
+/// The route of a request path, with the path parameters replaced so that
+/// every request to a route shares the same label.
+///
+/// Routes are made of lowercase words, while path parameters are txids,
+/// addresses and numbers, so any segment that isn't a short lowercase word
+/// is taken to be a parameter.
+fn route_label(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let is_word = segment.len() <= MAX_STATIC_SEGMENT_LENGTH
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c == '_' || c == '-');
+            if is_word {
+                segment
+            } else {
+                "{param}"
+            }
+        })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// Record a call to the storage backend.
+pub(crate) fn record_database_call<T>(
+    backend: &'static str,
+    operation: &'static str,
+    result: &Result<T, Error>,
+) {
+    metrics::counter!(
+        Metrics::DatabaseCallsTotal,
+        "backend" => backend,
+        "operation" => operation,
+    )
+    .increment(1);
+
+    // Missing entries and version conflicts are a normal part of reading
+    // and updating entries, so they're labeled to tell them apart from
+    // failures.
+    let kind = match result {
+        Ok(_) => return,
+        Err(Error::NotFound) => "not_found",
+        Err(Error::VersionConflict) => "version_conflict",
+        Err(_) => "other",
+    };
+    metrics::counter!(
+        Metrics::DatabaseErrorsTotal,
+        "backend" => backend,
+        "operation" => operation,
+        "kind" => kind,
+    )
+    .increment(1);
+}
+
+/// Record the height of the chain tip.
+pub(crate) fn set_chain_tip_height(height: u64) {
+    metrics::gauge!(Metrics::ChainTipHeight).set(height as f64);
+}
+
+/// Count the deposits and withdrawals in each status. The counts are made on
+/// the status indexes, so the entries themselves aren't read.
+pub async fn refresh_status_gauges(context: &EmilyContext) -> Result<(), Error> {
+    for status in ALL_STATUSES {
+        let deposits = accessors::count_deposit_entries_with_status(context, status).await?;
+        let withdrawals = accessors::count_withdrawal_entries_with_status(context, status).await?;
+        let status = status_label(status);
+        metrics::gauge!(Metrics::OperationsByStatus, "operation" => DEPOSIT, "status" => status)
+            .set(deposits as f64);
+        metrics::gauge!(Metrics::OperationsByStatus, "operation" => WITHDRAWAL, "status" => status)
+            .set(withdrawals as f64);
+    }
+    Ok(())
+}
+
+/// Count the deposits and withdrawals in each status forever, at the given
+/// interval.
+pub async fn refresh_status_gauges_every(context: EmilyContext, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        if let Err(error) = refresh_status_gauges(&context).await {
+            warn!(%error, "could not count the operations in each status");
+        }
+    }
+}
+
+/// The label of a status, which is how it's written in the API.
+fn status_label(status: &Status) -> &'static str {
+    match status {
+        Status::Pending => "pending",
+        Status::Reprocessing => "reprocessing",
+        Status::Accepted => "accepted",
+        Status::Confirmed => "confirmed",
+        Status::Failed => "failed",
+    }
+}
+
+/// Prints every measurement as a CloudWatch embedded metric format log
+/// line as soon as it's made.
+///
+/// Counters print the increment, which CloudWatch sums, gauges print their
+/// new value and histograms print each value that they record. Metric
+/// labels become the dimensions of the metric.
+struct EmbeddedMetricRecorder;
+
+impl Recorder for EmbeddedMetricRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::from_arc(Arc::new(EmbeddedMetric::new(key)))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(Arc::new(EmbeddedMetric::new(key)))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(Arc::new(EmbeddedMetric::new(key)))
+    }
+}
+
+/// A single metric of the embedded metric recorder.
+struct EmbeddedMetric {
+    /// The name and labels of the metric.
+    key: Key,
+    /// The bits of the current value, for gauges that are incremented or
+    /// decremented rather than set.
+    value: AtomicU64,
+}
+
+impl EmbeddedMetric {
+    fn new(key: &Key) -> Self {
+        Self {
+            key: key.clone(),
+            value: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    fn print(&self, value: f64) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        println!(
+            "{}",
+            embedded_metric_line(EMBEDDED_METRICS_NAMESPACE, &self.key, value, timestamp)
+        );
+    }
+
+    fn add_to_gauge(&self, delta: f64) {
+        let update = |bits| Some((f64::from_bits(bits) + delta).to_bits());
+        // The closure always returns `Some`, so the update can't fail.
+        let previous = self
+            .value
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, update)
+            .unwrap_or_else(|bits| bits);
+        self.print(f64::from_bits(previous) + delta);
+    }
+}
+
+impl CounterFn for EmbeddedMetric {
+    fn increment(&self, value: u64) {
+        self.print(value as f64);
+    }
+
+    fn absolute(&self, value: u64) {
+        self.print(value as f64);
+    }
+}
+
+impl GaugeFn for EmbeddedMetric {
+    fn increment(&self, value: f64) {
+        self.add_to_gauge(value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.add_to_gauge(-value);
+    }
+
+    fn set(&self, value: f64) {
+        self.value.store(value.to_bits(), Ordering::Release);
+        self.print(value);
+    }
+}
+
+impl HistogramFn for EmbeddedMetric {
+    fn record(&self, value: f64) {
+        self.print(value);
+    }
+}
+
+/// The embedded metric format log line of a single measurement.
+fn embedded_metric_line(namespace: &str, key: &Key, value: f64, timestamp: u64) -> Value {
+    let dimensions: Vec<&str> = key.labels().map(|label| label.key()).collect();
+    let mut line = Map::new();
+    line.insert(
+        "_aws".to_string(),
+        json!({
+            "Timestamp": timestamp,
+            "CloudWatchMetrics": [{
+                "Namespace": namespace,
+                "Dimensions": [dimensions],
+                "Metrics": [{ "Name": key.name() }],
+            }],
+        }),
+    );
+    for label in key.labels() {
+        line.insert(label.key().to_string(), json!(label.value()));
+    }
+    line.insert(key.name().to_string(), json!(value));
+    Value::Object(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use metrics::Label;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("/health", "/health"; "static route")]
+    #[test_case("/deposit/0b1f6c3a/1", "/deposit/{param}/{param}"; "deposit txid and index")]
+    #[test_case("/withdrawal/42", "/withdrawal/{param}"; "withdrawal request id")]
+    #[test_case("/dev/limits/SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS", "/dev/limits/{param}"; "stage prefix and address")]
+    #[test_case("/", "/"; "root")]
+    fn route_labels_replace_path_parameters(path: &str, expected: &str) {
+        assert_eq!(route_label(path), expected);
+    }
+
+    #[test]
+    fn embedded_metric_lines_put_labels_in_dimensions() {
+        let key = Key::from_parts(
+            "http_requests_total",
+            vec![Label::new("route", "/health"), Label::new("status", "200")],
+        );
+
+        let line = embedded_metric_line("Emily", &key, 1.0, 1_700_000_000_000);
+
+        let expected = json!({
+            "_aws": {
+                "Timestamp": 1_700_000_000_000u64,
+                "CloudWatchMetrics": [{
+                    "Namespace": "Emily",
+                    "Dimensions": [["route", "status"]],
+                    "Metrics": [{ "Name": "http_requests_total" }],
+                }],
+            },
+            "route": "/health",
+            "status": "200",
+            "http_requests_total": 1.0,
+        });
+        assert_eq!(line, expected);
+    }
+}
@@ -0,0 +1,1073 @@
+//! Reconciliation of the deposits and withdrawals that are still waiting on
+//! the signers against the bitcoin and stacks chains.
+//!
+//! Emily only learns that an operation was fulfilled when a signer sends it
+//! an update, so a signer that is down or an update that gets lost leaves
+//! the operation waiting forever. The reconciler reads the events of the
+//! sbtc-registry contract from a stacks API and confirms or fails every
+//! operation that isn't final but that the events show was dealt with.
+//!
+//! Each run only reads the events back to a cursor, a stacks block height
+//! that is stored in Emily. After a run the cursor moves up to a little
+//! below the chain tip, but never past the block of an event whose
+//! operation the run couldn't settle, so that the event is read again.
+//!
+//! When a bitcoin endpoint is configured the sweeps are checked too. A sweep
+//! that can't be found on bitcoin isn't confirmed, and a deposit whose output
+//! was spent without a completed-deposit event is flagged. A flag is a
+//! history event that keeps the status of the operation and explains what
+//! is wrong with it, so that an operator can look into it.
+//!
+//! Like the updates that the signers send, every event is added at the
+//! chain tip that Emily knows of.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr as _;
+
+use bitcoin::{BlockHash, OutPoint, Txid};
+use clarity::vm::Value as ClarityValue;
+use sbtc::events::{
+    CompletedDepositEvent, RegistryEvent, StacksTxid, TxInfo, WithdrawalAcceptEvent,
+    WithdrawalRejectEvent,
+};
+use serde::Deserialize;
+use stacks_common::types::chainstate::StacksBlockId;
+use tracing::{debug, info, warn};
+
+use crate::api::models::common::requests::OperationSearchQuery;
+use crate::api::models::common::Status;
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::database::entries::chainstate::ChainstateEntry;
+use crate::database::entries::deposit::{DepositEntry, DepositEvent, ValidatedDepositUpdate};
+use crate::database::entries::withdrawal::{
+    ValidatedWithdrawalUpdate, WithdrawalEntry, WithdrawalEvent,
+};
+use crate::registry::{self, UPDATE_RETRIES};
+use crate::verification::{DepositVerifier, OutputStatus};
+
+/// The statuses of the operations that are reconciled.
+const NON_FINAL_STATUSES: &[Status] = &[Status::Pending, Status::Reprocessing, Status::Accepted];
+
+/// The number of index entries read at a time.
+const RECONCILIATION_PAGE_SIZE: u16 = 100;
+
+/// The number of contract events read from the stacks API at a time, which
+/// is the most the API allows.
+const STACKS_EVENTS_PAGE_SIZE: usize = 50;
+
+/// The number of stacks blocks below the chain tip that the cursor is kept
+/// at, so that the events of blocks that a stacks fork can still replace
+/// are read again.
+const CURSOR_REORG_DEPTH: u64 = 100;
+
+/// The number of times a scan of the registry events goes back a page
+/// because the list of events shrank while it was read, before giving up.
+const MAX_SCAN_REWINDS: usize = 10;
+
+/// Reconciler settings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReconciliationSettings {
+    /// The base URL of a stacks blockchain API.
+    pub stacks_api_url: String,
+    /// Whether to only report what would change without changing it.
+    pub dry_run: bool,
+}
+
+/// What a reconciliation run found.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    /// The number of non-final deposits that were checked.
+    pub deposits_checked: u64,
+    /// The number of non-final withdrawals that were checked.
+    pub withdrawals_checked: u64,
+    /// The number of deposits that were confirmed.
+    pub deposits_confirmed: u64,
+    /// The number of withdrawals that were confirmed.
+    pub withdrawals_confirmed: u64,
+    /// The number of withdrawals that were failed.
+    pub withdrawals_failed: u64,
+    /// The number of operations that were flagged.
+    pub flagged: u64,
+    /// The number of operations that couldn't be checked.
+    pub errors: u64,
+    /// The stacks block height that the next run reads the registry events
+    /// back to.
+    pub cursor_height: u64,
+}
+
+/// The sbtc-registry events that fulfill operations, keyed by the operation
+/// that they fulfill.
+///
+/// An operation can have more than one event of a kind, for example when
+/// its event was mined in a block that a stacks fork replaced and then
+/// mined again, so every event is kept and the one in a canonical block is
+/// picked when the operation is reconciled.
+#[derive(Debug, Default)]
+pub struct RegistryEvents {
+    /// Completed deposits by the outpoint of the deposit.
+    pub completed_deposits: HashMap<OutPoint, Vec<CompletedDepositEvent>>,
+    /// Accepted withdrawals by request id.
+    pub accepted_withdrawals: HashMap<u64, Vec<WithdrawalAcceptEvent>>,
+    /// Rejected withdrawals by request id.
+    pub rejected_withdrawals: HashMap<u64, Vec<WithdrawalRejectEvent>>,
+}
+
+impl RegistryEvents {
+    /// Add an event, unless the operation already has an event of the same
+    /// kind from the same transaction. The events of an operation keep the
+    /// order they were added in, which is newest first since the stacks API
+    /// lists the newest events first.
+    pub fn insert(&mut self, event: RegistryEvent) {
+        match event {
+            RegistryEvent::CompletedDeposit(event) => push_new(
+                self.completed_deposits.entry(event.outpoint).or_default(),
+                event,
+                |event| event.txid,
+            ),
+            RegistryEvent::WithdrawalAccept(event) => push_new(
+                self.accepted_withdrawals
+                    .entry(event.request_id)
+                    .or_default(),
+                event,
+                |event| event.txid,
+            ),
+            RegistryEvent::WithdrawalReject(event) => push_new(
+                self.rejected_withdrawals
+                    .entry(event.request_id)
+                    .or_default(),
+                event,
+                |event| event.txid,
+            ),
+            RegistryEvent::WithdrawalCreate(_)
+            | RegistryEvent::KeyRotation(_)
+            | RegistryEvent::UpdateProtocolContract(_) => {}
+        }
+    }
+}
+
+/// Push the event unless there already is an event from its transaction.
+fn push_new<E>(events: &mut Vec<E>, event: E, txid: fn(&E) -> StacksTxid) {
+    if !events.iter().any(|known| txid(known) == txid(&event)) {
+        events.push(event);
+    }
+}
+
+/// A page of contract events from the stacks API.
+#[derive(Debug, Deserialize)]
+struct ContractEventsPage {
+    /// The events.
+    results: Vec<ContractEvent>,
+}
+
+/// A contract event from the stacks API.
+#[derive(Debug, Deserialize)]
+struct ContractEvent {
+    /// The index of the event within its transaction.
+    event_index: u64,
+    /// The hex encoded id of the transaction that emitted the event.
+    tx_id: String,
+    /// The printed value, for print events.
+    contract_log: Option<ContractLog>,
+}
+
+impl ContractEvent {
+    /// The transaction and index that identify the event.
+    fn id(&self) -> (String, u64) {
+        (self.tx_id.clone(), self.event_index)
+    }
+}
+
+/// A print event from the stacks API.
+#[derive(Debug, Deserialize)]
+struct ContractLog {
+    /// The printed value.
+    value: ContractLogValue,
+}
+
+/// A printed clarity value.
+#[derive(Debug, Deserialize)]
+struct ContractLogValue {
+    /// The consensus serialization of the value, hex encoded.
+    hex: String,
+}
+
+/// A transaction from the stacks API.
+#[derive(Debug, Deserialize)]
+struct StacksTransaction {
+    /// The status of the transaction, `success` when it went through.
+    tx_status: String,
+    /// Whether the block of the transaction is on the canonical fork.
+    #[serde(default)]
+    canonical: bool,
+    /// The hex encoded id of the block of the transaction.
+    index_block_hash: Option<String>,
+    /// The height of the block of the transaction.
+    block_height: Option<u64>,
+}
+
+/// The block that a stacks transaction that went through is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionBlock {
+    /// The id of the block.
+    pub block_id: StacksBlockId,
+    /// The height of the block.
+    pub block_height: u64,
+    /// Whether the block is on the canonical fork.
+    pub canonical: bool,
+}
+
+/// Reads the sbtc-registry events from a stacks API.
+#[derive(Clone, Debug)]
+pub struct StacksEventClient {
+    /// HTTP client.
+    client: reqwest::Client,
+    /// The base URL of the stacks API.
+    api_url: String,
+    /// The contract identifier of the sbtc-registry contract.
+    registry_contract_id: String,
+}
+
+impl StacksEventClient {
+    /// Create a new client for the registry contract on the stacks API.
+    pub fn new(api_url: String, registry_contract_id: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url,
+            registry_contract_id,
+        }
+    }
+
+    /// Read the events of the registry contract, newest first, up to and
+    /// including the first page whose oldest event is in a block at or
+    /// below the cursor height. Events that can't be decoded are logged and
+    /// left out.
+    ///
+    /// Consecutive pages overlap by one event. Events that are added while
+    /// the list is read push events that were already read onto the next
+    /// page, which only repeats them. Events that are dropped from the
+    /// list, when the stacks API abandons a fork, pull events that weren't
+    /// read yet onto pages that were, so the scan goes back a page whenever
+    /// a page shares no event with the pages before it.
+    ///
+    /// The stacks API doesn't say which block an event is in, so the block
+    /// ids of the returned events are all zeros until they're filled in
+    /// with [`StacksEventClient::get_transaction_block`].
+    pub async fn get_registry_events(&self, cursor_height: u64) -> Result<RegistryEvents, Error> {
+        let mut events = RegistryEvents::default();
+        let mut seen: HashSet<(String, u64)> = HashSet::new();
+        let mut offset = 0;
+        let mut rewinds = 0;
+        loop {
+            let page = self.get_events_page(offset).await?;
+            let count = page.len();
+            if offset > 0 && count > 0 && !page.iter().any(|event| seen.contains(&event.id())) {
+                rewinds += 1;
+                if rewinds > MAX_SCAN_REWINDS {
+                    return Err(Error::Debug(
+                        "The registry events kept changing while they were read".to_string(),
+                    ));
+                }
+                offset = offset.saturating_sub(STACKS_EVENTS_PAGE_SIZE - 1);
+                continue;
+            }
+
+            for event in &page {
+                seen.insert(event.id());
+                let Some(log) = &event.contract_log else {
+                    continue;
+                };
+                match parse_registry_event(&event.tx_id, &log.value.hex) {
+                    Ok(registry_event) => events.insert(registry_event),
+                    Err(error) => warn!(%error, txid = %event.tx_id, "skipping registry event"),
+                }
+            }
+
+            let Some(oldest) = page.last() else {
+                return Ok(events);
+            };
+            if count < STACKS_EVENTS_PAGE_SIZE {
+                return Ok(events);
+            }
+            let oldest_block = self
+                .get_transaction_block(&parse_stacks_txid(&oldest.tx_id)?)
+                .await?;
+            if oldest_block.is_some_and(|block| block.block_height <= cursor_height) {
+                return Ok(events);
+            }
+            offset += count - 1;
+        }
+    }
+
+    /// Read a page of the registry contract events, newest first.
+    async fn get_events_page(&self, offset: usize) -> Result<Vec<ContractEvent>, Error> {
+        let url = format!(
+            "{}/extended/v1/contract/{}/events?limit={STACKS_EVENTS_PAGE_SIZE}&offset={offset}",
+            self.api_url.trim_end_matches('/'),
+            self.registry_contract_id,
+        );
+        let page: ContractEventsPage = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(page.results)
+    }
+
+    /// Get the block of the transaction, if the transaction went through
+    /// and is in a block.
+    pub async fn get_transaction_block(
+        &self,
+        txid: &StacksTxid,
+    ) -> Result<Option<TransactionBlock>, Error> {
+        let url = format!(
+            "{}/extended/v1/tx/0x{txid}",
+            self.api_url.trim_end_matches('/')
+        );
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let tx: StacksTransaction = response.error_for_status()?.json().await?;
+        if tx.tx_status != "success" {
+            return Ok(None);
+        }
+        let (Some(block_id), Some(block_height)) = (tx.index_block_hash, tx.block_height) else {
+            return Ok(None);
+        };
+
+        let block_id = StacksBlockId::from_hex(block_id.trim_start_matches("0x"))
+            .map_err(|err| Error::Debug(format!("Invalid stacks block id {block_id}: {err:?}")))?;
+        Ok(Some(TransactionBlock {
+            block_id,
+            block_height,
+            canonical: tx.canonical,
+        }))
+    }
+}
+
+/// Decode a hex encoded stacks transaction id.
+fn parse_stacks_txid(tx_id: &str) -> Result<StacksTxid, Error> {
+    hex::decode(tx_id.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(StacksTxid)
+        .ok_or_else(|| Error::Debug(format!("Invalid stacks txid {tx_id}")))
+}
+
+/// Decode a print event of the registry contract.
+fn parse_registry_event(tx_id: &str, value_hex: &str) -> Result<RegistryEvent, Error> {
+    let txid = parse_stacks_txid(tx_id)?;
+    let value = ClarityValue::try_deserialize_hex_untyped(value_hex.trim_start_matches("0x"))
+        .map_err(|err| Error::Debug(format!("Failed to decode clarity value: {err}")))?;
+    let tx_info = TxInfo {
+        txid,
+        block_id: StacksBlockId([0; 32]),
+    };
+    RegistryEvent::try_new(value, tx_info)
+        .map_err(|err| Error::Debug(format!("Failed to decode registry event: {err}")))
+}
+
+/// Where the reconciliation cursor moves to once a run is over.
+#[derive(Debug)]
+struct NextCursor {
+    /// The height the cursor was at when the run started.
+    start: u64,
+    /// The height the cursor moves to.
+    height: u64,
+}
+
+impl NextCursor {
+    /// Keep the cursor below the block of an event whose operation wasn't
+    /// settled, so that the next run reads the event again. The cursor
+    /// stays where it was if the block of the event isn't known.
+    fn hold(&mut self, block: Option<&TransactionBlock>) {
+        let limit = block.map_or(self.start, |block| block.block_height.saturating_sub(1));
+        self.height = self.height.min(limit);
+    }
+}
+
+/// Reconciles the non-final operations in Emily against the chains.
+#[derive(Clone, Debug)]
+pub struct Reconciler {
+    /// Emily context.
+    context: EmilyContext,
+    /// Where the registry events are read from.
+    stacks: StacksEventClient,
+    /// Where the sweeps are checked, if anywhere.
+    bitcoin: Option<DepositVerifier>,
+    /// Whether to only report what would change.
+    dry_run: bool,
+}
+
+impl Reconciler {
+    /// Create a new reconciler for the registry contract of the context.
+    /// The bitcoin side is checked against the deposit verification
+    /// endpoint of the context, if one is set.
+    pub fn new(context: EmilyContext, settings: ReconciliationSettings) -> Result<Self, Error> {
+        let registry_contract_id =
+            context
+                .settings
+                .registry_contract_id
+                .clone()
+                .ok_or_else(|| {
+                    Error::Debug("The sbtc-registry contract id isn't configured".to_string())
+                })?;
+        Ok(Self {
+            bitcoin: context.deposit_verifier(),
+            stacks: StacksEventClient::new(settings.stacks_api_url, registry_contract_id),
+            dry_run: settings.dry_run,
+            context,
+        })
+    }
+
+    /// Reconcile every non-final deposit and withdrawal once.
+    ///
+    /// An operation that can't be checked is logged and counted as an
+    /// error in the report rather than stopping the run.
+    pub async fn run(&self) -> Result<ReconciliationReport, Error> {
+        let api_state = accessors::get_api_state(&self.context).await?;
+        api_state.error_if_reorganizing()?;
+        let chaintip = api_state.chaintip();
+        let cursor_height = accessors::get_reconciliation_cursor(&self.context).await?;
+        let events = self.stacks.get_registry_events(cursor_height).await?;
+        let mut report = ReconciliationReport::default();
+        let mut next_cursor = NextCursor {
+            start: cursor_height,
+            height: chaintip
+                .key
+                .height
+                .saturating_sub(CURSOR_REORG_DEPTH)
+                .max(cursor_height),
+        };
+
+        // The keys are read up front because reconciling an operation moves
+        // it out of the statuses that are being searched.
+        let search = OperationSearchQuery {
+            status: Some(NON_FINAL_STATUSES.to_vec()),
+            ..Default::default()
+        };
+        let mut deposit_keys = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let (entries, token) = accessors::search_deposit_entries(
+                &self.context,
+                &search,
+                next_token,
+                Some(RECONCILIATION_PAGE_SIZE),
+            )
+            .await?;
+            deposit_keys.extend(entries.into_iter().map(|entry| entry.primary_index_key));
+            next_token = token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+        let mut request_ids = Vec::new();
+        loop {
+            let (entries, token) = accessors::search_withdrawal_entries(
+                &self.context,
+                &search,
+                next_token,
+                Some(RECONCILIATION_PAGE_SIZE),
+            )
+            .await?;
+            request_ids.extend(
+                entries
+                    .into_iter()
+                    .map(|entry| entry.primary_index_key.request_id),
+            );
+            next_token = token;
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        for key in deposit_keys {
+            report.deposits_checked += 1;
+            let result = match accessors::get_deposit_entry(&self.context, &key).await {
+                Ok(entry) => {
+                    self.reconcile_deposit(
+                        &entry,
+                        &chaintip,
+                        &events,
+                        &mut next_cursor,
+                        &mut report,
+                    )
+                    .await
+                }
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                warn!(%error, txid = %key.bitcoin_txid, vout = key.bitcoin_tx_output_index, "failed to reconcile deposit");
+                report.errors += 1;
+            }
+        }
+        for request_id in request_ids {
+            report.withdrawals_checked += 1;
+            let result = match accessors::get_withdrawal_entry(&self.context, &request_id).await {
+                Ok(entry) => {
+                    self.reconcile_withdrawal(
+                        &entry,
+                        &chaintip,
+                        &events,
+                        &mut next_cursor,
+                        &mut report,
+                    )
+                    .await
+                }
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                warn!(%error, request_id, "failed to reconcile withdrawal");
+                report.errors += 1;
+            }
+        }
+
+        // An operation that couldn't be checked may have events that weren't
+        // looked at, so the cursor stays put.
+        report.cursor_height = if report.errors > 0 {
+            cursor_height
+        } else {
+            next_cursor.height
+        };
+        if !self.dry_run && report.cursor_height != cursor_height {
+            accessors::set_reconciliation_cursor(&self.context, report.cursor_height).await?;
+        }
+        Ok(report)
+    }
+
+    /// Find the first of the events, which are newest first, whose
+    /// transaction is in a canonical block. When none is, the cursor is held
+    /// below the blocks of the events.
+    async fn find_canonical<'a, E>(
+        &self,
+        events: &'a [E],
+        txid: fn(&E) -> StacksTxid,
+        next_cursor: &mut NextCursor,
+    ) -> Result<Option<(&'a E, TransactionBlock)>, Error> {
+        let mut blocks = Vec::with_capacity(events.len());
+        for event in events {
+            match self.stacks.get_transaction_block(&txid(event)).await? {
+                Some(block) if block.canonical => return Ok(Some((event, block))),
+                block => blocks.push(block),
+            }
+        }
+        for block in blocks {
+            next_cursor.hold(block.as_ref());
+        }
+        Ok(None)
+    }
+
+    /// Confirm the deposit if the registry shows that it was completed, or
+    /// flag it if the chains disagree about it.
+    async fn reconcile_deposit(
+        &self,
+        entry: &DepositEntry,
+        chaintip: &ChainstateEntry,
+        events: &RegistryEvents,
+        next_cursor: &mut NextCursor,
+        report: &mut ReconciliationReport,
+    ) -> Result<(), Error> {
+        let txid = Txid::from_str(&entry.key.bitcoin_txid).map_err(|err| {
+            Error::Debug(format!(
+                "Invalid deposit txid {}: {err}",
+                entry.key.bitcoin_txid
+            ))
+        })?;
+        let outpoint = OutPoint::new(txid, entry.key.bitcoin_tx_output_index);
+
+        let completed_deposits = events
+            .completed_deposits
+            .get(&outpoint)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if let Some((event, block)) = self
+            .find_canonical(completed_deposits, |event| event.txid, next_cursor)
+            .await?
+        {
+            let mut event = event.clone();
+            let block_id = block.block_id;
+            event.block_id = block_id;
+            if !self
+                .is_on_bitcoin(&event.sweep_txid, &event.sweep_block_hash)
+                .await?
+            {
+                next_cursor.hold(Some(&block));
+                let message = format!(
+                    "Completed in stacks block {} but the sweep {} was not found in bitcoin block {}",
+                    block_id.to_hex(),
+                    event.sweep_txid,
+                    event.sweep_block_hash
+                );
+                return self.flag_deposit(entry, chaintip, message, report).await;
+            }
+            let update = ValidatedDepositUpdate {
+                key: entry.key.clone(),
+                event: registry::completed_deposit_event(
+                    entry.amount,
+                    &event,
+                    format!(
+                        "Included in block {}, found by reconciliation",
+                        block_id.to_hex()
+                    ),
+                    chaintip,
+                ),
+            };
+            self.update_deposit(update).await?;
+            report.deposits_confirmed += 1;
+            return Ok(());
+        }
+        if !completed_deposits.is_empty() {
+            debug!(%outpoint, "no completed-deposit event is canonical");
+        }
+
+        let Some(bitcoin) = &self.bitcoin else {
+            return Ok(());
+        };
+        if bitcoin.get_output_status(&outpoint).await? == OutputStatus::Spent {
+            let message = "Deposit output was spent on bitcoin but no completed-deposit event \
+                has been found on stacks yet"
+                .to_string();
+            return self.flag_deposit(entry, chaintip, message, report).await;
+        }
+        Ok(())
+    }
+
+    /// Confirm or fail the withdrawal if the registry shows that it was
+    /// accepted or rejected, or flag it if the chains disagree about it.
+    async fn reconcile_withdrawal(
+        &self,
+        entry: &WithdrawalEntry,
+        chaintip: &ChainstateEntry,
+        events: &RegistryEvents,
+        next_cursor: &mut NextCursor,
+        report: &mut ReconciliationReport,
+    ) -> Result<(), Error> {
+        let request_id = entry.key.request_id;
+
+        let accepted_withdrawals = events
+            .accepted_withdrawals
+            .get(&request_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if let Some((event, block)) = self
+            .find_canonical(accepted_withdrawals, |event| event.txid, next_cursor)
+            .await?
+        {
+            let mut event = event.clone();
+            let block_id = block.block_id;
+            event.block_id = block_id;
+            if !self
+                .is_on_bitcoin(&event.sweep_txid, &event.sweep_block_hash)
+                .await?
+            {
+                next_cursor.hold(Some(&block));
+                let message = format!(
+                    "Accepted in stacks block {} but the sweep {} was not found in bitcoin block {}",
+                    block_id.to_hex(),
+                    event.sweep_txid,
+                    event.sweep_block_hash
+                );
+                return self.flag_withdrawal(entry, chaintip, message, report).await;
+            }
+            let update = ValidatedWithdrawalUpdate {
+                request_id,
+                event: registry::accepted_withdrawal_event(
+                    &event,
+                    format!(
+                        "Included in block {}, found by reconciliation",
+                        block_id.to_hex()
+                    ),
+                    chaintip,
+                ),
+            };
+            self.update_withdrawal(update).await?;
+            report.withdrawals_confirmed += 1;
+            return Ok(());
+        }
+        if !accepted_withdrawals.is_empty() {
+            debug!(request_id, "no withdrawal-accept event is canonical");
+        }
+
+        let rejected_withdrawals = events
+            .rejected_withdrawals
+            .get(&request_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if let Some((_, block)) = self
+            .find_canonical(rejected_withdrawals, |event| event.txid, next_cursor)
+            .await?
+        {
+            let update = ValidatedWithdrawalUpdate {
+                request_id,
+                event: registry::rejected_withdrawal_event(
+                    format!(
+                        "Rejected in block {}, found by reconciliation",
+                        block.block_id.to_hex()
+                    ),
+                    chaintip,
+                ),
+            };
+            self.update_withdrawal(update).await?;
+            report.withdrawals_failed += 1;
+            return Ok(());
+        }
+        if !rejected_withdrawals.is_empty() {
+            debug!(request_id, "no withdrawal-reject event is canonical");
+        }
+
+        Ok(())
+    }
+
+    /// Check whether the transaction is in the given block on the best
+    /// bitcoin chain. Every transaction is assumed to be where its event
+    /// says when there's no endpoint.
+    async fn is_on_bitcoin(&self, txid: &Txid, block_hash: &BlockHash) -> Result<bool, Error> {
+        match &self.bitcoin {
+            Some(bitcoin) => bitcoin.is_in_block(txid, block_hash).await,
+            None => Ok(true),
+        }
+    }
+
+    /// Add a flag to the history of the deposit, unless the latest event is
+    /// already the same flag.
+    async fn flag_deposit(
+        &self,
+        entry: &DepositEntry,
+        chaintip: &ChainstateEntry,
+        message: String,
+        report: &mut ReconciliationReport,
+    ) -> Result<(), Error> {
+        let Some(latest) = entry.history.last() else {
+            return Ok(());
+        };
+        if latest.message == message {
+            return Ok(());
+        }
+        let update = ValidatedDepositUpdate {
+            key: entry.key.clone(),
+            event: DepositEvent {
+                status: latest.status.clone(),
+                message,
+                stacks_block_height: chaintip.key.height,
+                stacks_block_hash: chaintip.key.hash.clone(),
+            },
+        };
+        self.update_deposit(update).await?;
+        report.flagged += 1;
+        Ok(())
+    }
+
+    /// Add a flag to the history of the withdrawal, unless the latest event
+    /// is already the same flag.
+    async fn flag_withdrawal(
+        &self,
+        entry: &WithdrawalEntry,
+        chaintip: &ChainstateEntry,
+        message: String,
+        report: &mut ReconciliationReport,
+    ) -> Result<(), Error> {
+        let Some(latest) = entry.history.last() else {
+            return Ok(());
+        };
+        if latest.message == message {
+            return Ok(());
+        }
+        let update = ValidatedWithdrawalUpdate {
+            request_id: entry.key.request_id,
+            event: WithdrawalEvent {
+                status: latest.status.clone(),
+                message,
+                stacks_block_height: chaintip.key.height,
+                stacks_block_hash: chaintip.key.hash.clone(),
+            },
+        };
+        self.update_withdrawal(update).await?;
+        report.flagged += 1;
+        Ok(())
+    }
+
+    /// Apply the deposit update, unless this is a dry run.
+    async fn update_deposit(&self, update: ValidatedDepositUpdate) -> Result<(), Error> {
+        info!(
+            txid = %update.key.bitcoin_txid,
+            vout = update.key.bitcoin_tx_output_index,
+            message = %update.event.message,
+            dry_run = self.dry_run,
+            "reconciling deposit"
+        );
+        if !self.dry_run {
+            accessors::pull_and_update_deposit_with_retry(&self.context, update, UPDATE_RETRIES)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Apply the withdrawal update, unless this is a dry run.
+    async fn update_withdrawal(&self, update: ValidatedWithdrawalUpdate) -> Result<(), Error> {
+        info!(
+            request_id = update.request_id,
+            message = %update.event.message,
+            dry_run = self.dry_run,
+            "reconciling withdrawal"
+        );
+        if !self.dry_run {
+            accessors::pull_and_update_withdrawal_with_retry(&self.context, update, UPDATE_RETRIES)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use clarity::vm::types::TupleData;
+    use clarity::vm::ClarityName;
+    use mockito::{mock, Matcher};
+
+    use super::*;
+
+    fn completed_deposit(
+        txid: StacksTxid,
+        outpoint: OutPoint,
+        amount: u64,
+    ) -> CompletedDepositEvent {
+        CompletedDepositEvent {
+            txid,
+            block_id: StacksBlockId([2; 32]),
+            amount,
+            outpoint,
+            sweep_block_hash: bitcoin::BlockHash::all_zeros(),
+            sweep_block_height: 120,
+            sweep_txid: Txid::all_zeros(),
+        }
+    }
+
+    /// The hex encoded consensus serialization of a registry print event.
+    fn event_hex(topic: &str, fields: Vec<(&str, ClarityValue)>) -> String {
+        let mut data: Vec<(ClarityName, ClarityValue)> = fields
+            .into_iter()
+            .map(|(name, value)| (ClarityName::from(name), value))
+            .collect();
+        data.push((
+            ClarityName::from("topic"),
+            ClarityValue::string_ascii_from_bytes(topic.as_bytes().to_vec()).unwrap(),
+        ));
+        ClarityValue::Tuple(TupleData::from_data(data).unwrap())
+            .serialize_to_hex()
+            .unwrap()
+    }
+
+    fn withdrawal_reject_hex(request_id: u64) -> String {
+        event_hex(
+            "withdrawal-reject",
+            vec![
+                ("request-id", ClarityValue::UInt(request_id as u128)),
+                ("signer-bitmap", ClarityValue::UInt(0)),
+            ],
+        )
+    }
+
+    /// A print event from the stacks API, emitted by the transaction with
+    /// the given id.
+    fn api_event(tx_id: &str, hex: &str) -> serde_json::Value {
+        serde_json::json!({
+            "event_index": 0,
+            "event_type": "smart_contract_log",
+            "tx_id": tx_id,
+            "contract_log": {
+                "contract_id": "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry",
+                "topic": "print",
+                "value": { "hex": hex, "repr": "" },
+            },
+        })
+    }
+
+    fn tx_id(n: u64) -> String {
+        format!("0x{n:064x}")
+    }
+
+    /// Mock a page of the contract events of the given contract.
+    fn mock_events_page(
+        contract_id: &str,
+        offset: usize,
+        events: Vec<serde_json::Value>,
+    ) -> mockito::Mock {
+        mock(
+            "GET",
+            format!("/extended/v1/contract/{contract_id}/events").as_str(),
+        )
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("limit".into(), STACKS_EVENTS_PAGE_SIZE.to_string()),
+            Matcher::UrlEncoded("offset".into(), offset.to_string()),
+        ]))
+        .with_body(serde_json::json!({ "results": events }).to_string())
+        .create()
+    }
+
+    /// Mock the lookup of a transaction in a canonical block at the given
+    /// height.
+    fn mock_transaction(tx_id: &str, block_height: u64) -> mockito::Mock {
+        mock("GET", format!("/extended/v1/tx/{tx_id}").as_str())
+            .with_body(
+                serde_json::json!({
+                    "tx_status": "success",
+                    "canonical": true,
+                    "index_block_hash": format!("0x{}", "ab".repeat(32)),
+                    "block_height": block_height,
+                })
+                .to_string(),
+            )
+            .create()
+    }
+
+    #[test]
+    fn registry_events_keep_every_event_of_an_operation() {
+        let outpoint = OutPoint::new(Txid::from_byte_array([3; 32]), 0);
+        let mut events = RegistryEvents::default();
+
+        events.insert(RegistryEvent::CompletedDeposit(completed_deposit(
+            StacksTxid([1; 32]),
+            outpoint,
+            10,
+        )));
+        events.insert(RegistryEvent::CompletedDeposit(completed_deposit(
+            StacksTxid([4; 32]),
+            outpoint,
+            20,
+        )));
+        // Overlapping pages return the same event twice.
+        events.insert(RegistryEvent::CompletedDeposit(completed_deposit(
+            StacksTxid([1; 32]),
+            outpoint,
+            10,
+        )));
+
+        let amounts: Vec<u64> = events.completed_deposits[&outpoint]
+            .iter()
+            .map(|event| event.amount)
+            .collect();
+        assert_eq!(amounts, vec![10, 20]);
+    }
+
+    #[test]
+    fn the_cursor_is_held_below_unsettled_events() {
+        let block = |block_height| TransactionBlock {
+            block_id: StacksBlockId([0; 32]),
+            block_height,
+            canonical: false,
+        };
+        let mut next_cursor = NextCursor { start: 50, height: 900 };
+
+        next_cursor.hold(Some(&block(700)));
+        assert_eq!(next_cursor.height, 699);
+        next_cursor.hold(Some(&block(800)));
+        assert_eq!(next_cursor.height, 699);
+        // Without a block the cursor can't move at all.
+        next_cursor.hold(None);
+        assert_eq!(next_cursor.height, 50);
+    }
+
+    #[tokio::test]
+    async fn registry_events_are_read_back_to_the_cursor() {
+        let contract_id = "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry-paging";
+        let page = |ids: std::ops::RangeInclusive<u64>| {
+            ids.map(|id| api_event(&tx_id(id), &withdrawal_reject_hex(id)))
+                .collect::<Vec<_>>()
+        };
+        // The second page starts with the last event of the first page.
+        let _first = mock_events_page(contract_id, 0, page(1..=50));
+        let _second = mock_events_page(contract_id, 49, page(50..=60));
+        let _oldest = mock_transaction(&tx_id(50), 200);
+
+        let client = StacksEventClient::new(mockito::server_url(), contract_id.to_string());
+
+        let events = client.get_registry_events(100).await.unwrap();
+        assert_eq!(events.rejected_withdrawals.len(), 60);
+        assert!(events
+            .rejected_withdrawals
+            .values()
+            .all(|events| events.len() == 1));
+
+        // The oldest event of the first page is at or below the cursor, so
+        // the older pages aren't read.
+        let events = client.get_registry_events(200).await.unwrap();
+        assert_eq!(events.rejected_withdrawals.len(), 50);
+        assert!(!events.rejected_withdrawals.contains_key(&60));
+    }
+
+    #[tokio::test]
+    async fn registry_events_are_decoded_from_their_clarity_values() {
+        let contract_id = "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry-parsing";
+        let completed_deposit = event_hex(
+            "completed-deposit",
+            vec![
+                ("amount", ClarityValue::UInt(1_000)),
+                (
+                    "bitcoin-txid",
+                    ClarityValue::buff_from(vec![1; 32]).unwrap(),
+                ),
+                ("output-index", ClarityValue::UInt(3)),
+                ("burn-hash", ClarityValue::buff_from(vec![2; 32]).unwrap()),
+                ("burn-height", ClarityValue::UInt(139)),
+                ("sweep-txid", ClarityValue::buff_from(vec![3; 32]).unwrap()),
+            ],
+        );
+        let withdrawal_accept = event_hex(
+            "withdrawal-accept",
+            vec![
+                ("request-id", ClarityValue::UInt(7)),
+                ("signer-bitmap", ClarityValue::UInt(0)),
+                ("fee", ClarityValue::UInt(369)),
+                (
+                    "bitcoin-txid",
+                    ClarityValue::buff_from(vec![4; 32]).unwrap(),
+                ),
+                ("output-index", ClarityValue::UInt(2)),
+                ("burn-hash", ClarityValue::buff_from(vec![5; 32]).unwrap()),
+                ("burn-height", ClarityValue::UInt(140)),
+                ("sweep-txid", ClarityValue::buff_from(vec![6; 32]).unwrap()),
+            ],
+        );
+        let mut not_a_print = api_event(&tx_id(4), "");
+        not_a_print["contract_log"] = serde_json::Value::Null;
+        let _page = mock_events_page(
+            contract_id,
+            0,
+            vec![
+                api_event(&tx_id(1), &completed_deposit),
+                api_event(&tx_id(2), &withdrawal_accept),
+                api_event(&tx_id(3), &withdrawal_reject_hex(8)),
+                not_a_print,
+                // Values that aren't registry events are skipped.
+                api_event(&tx_id(5), "0x0100000000000000000000000000000001"),
+                api_event(&tx_id(6), "0xnot-hex"),
+            ],
+        );
+
+        let client = StacksEventClient::new(mockito::server_url(), contract_id.to_string());
+        let events = client.get_registry_events(0).await.unwrap();
+
+        let outpoint = OutPoint::new(Txid::from_byte_array([1; 32]), 3);
+        let deposit = &events.completed_deposits[&outpoint][0];
+        assert_eq!(deposit.amount, 1_000);
+        assert_eq!(deposit.txid, parse_stacks_txid(&tx_id(1)).unwrap());
+        assert_eq!(deposit.sweep_block_height, 139);
+
+        let accept = &events.accepted_withdrawals[&7][0];
+        assert_eq!(accept.fee, 369);
+        assert_eq!(accept.outpoint.vout, 2);
+        assert_eq!(accept.sweep_block_height, 140);
+
+        assert_eq!(events.rejected_withdrawals[&8].len(), 1);
+        assert_eq!(events.completed_deposits.len(), 1);
+        assert_eq!(events.accepted_withdrawals.len(), 1);
+        assert_eq!(events.rejected_withdrawals.len(), 1);
+    }
+}
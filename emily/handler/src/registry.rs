@@ -0,0 +1,243 @@
+//! Applying the print events of the sbtc-registry contract to the deposits
+//! and withdrawals that they are about.
+//!
+//! The events are turned into the same entries, history events and
+//! fulfillments that the signers report for them, so an operation looks
+//! the same whichever way Emily learnt about it.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sbtc::events::{
+    CompletedDepositEvent, RegistryEvent, WithdrawalAcceptEvent, WithdrawalCreateEvent,
+};
+use tracing::{debug, info};
+
+use crate::api::models::common::{Fulfillment, Status};
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::database::entries::chainstate::ChainstateEntry;
+use crate::database::entries::deposit::{DepositEntryKey, DepositEvent, ValidatedDepositUpdate};
+use crate::database::entries::withdrawal::{
+    ValidatedWithdrawalUpdate, WithdrawalEntry, WithdrawalEntryKey, WithdrawalEvent,
+    WithdrawalParametersEntry,
+};
+use crate::database::entries::StatusEntry;
+
+/// The number of times an update is retried on a version conflict.
+pub(crate) const UPDATE_RETRIES: u16 = 15;
+
+/// Apply an event that was emitted in the block of the chainstate.
+///
+/// Events about operations that Emily doesn't know of are logged and
+/// skipped, as are withdrawal creations for withdrawals that already exist.
+/// Applying the same event twice changes nothing.
+pub async fn apply_registry_event(
+    context: &EmilyContext,
+    event: RegistryEvent,
+    chainstate: &ChainstateEntry,
+) -> Result<(), Error> {
+    match event {
+        RegistryEvent::CompletedDeposit(event) => {
+            let key = DepositEntryKey {
+                bitcoin_txid: event.outpoint.txid.to_string(),
+                bitcoin_tx_output_index: event.outpoint.vout,
+            };
+            let entry = match accessors::get_deposit_entry(context, &key).await {
+                Err(Error::NotFound) => {
+                    info!(outpoint = %event.outpoint, "skipping completed deposit that isn't in emily");
+                    return Ok(());
+                }
+                result => result?,
+            };
+            let message = format!("Included in block {}", event.block_id.to_hex());
+            let update = ValidatedDepositUpdate {
+                key,
+                event: completed_deposit_event(entry.amount, &event, message, chainstate),
+            };
+            accessors::pull_and_update_deposit_with_retry(context, update, UPDATE_RETRIES).await?;
+        }
+        RegistryEvent::WithdrawalAccept(event) => {
+            let message = format!("Included in block {}", event.block_id.to_hex());
+            let update = ValidatedWithdrawalUpdate {
+                request_id: event.request_id,
+                event: accepted_withdrawal_event(&event, message, chainstate),
+            };
+            update_known_withdrawal(context, update).await?;
+        }
+        RegistryEvent::WithdrawalReject(event) => {
+            let update = ValidatedWithdrawalUpdate {
+                request_id: event.request_id,
+                event: rejected_withdrawal_event("Rejected".to_string(), chainstate),
+            };
+            update_known_withdrawal(context, update).await?;
+        }
+        RegistryEvent::WithdrawalCreate(event) => {
+            match accessors::get_withdrawal_entry(context, &event.request_id).await {
+                Err(Error::NotFound) => {}
+                Ok(_) => {
+                    debug!(request_id = event.request_id, "withdrawal already exists");
+                    return Ok(());
+                }
+                Err(error) => return Err(error),
+            }
+            let created_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|err| Error::Debug(format!("System time before unix epoch: {err}")))?
+                .as_secs();
+            let entry = created_withdrawal_entry(&event, chainstate.key.height, created_at);
+            entry.validate()?;
+            accessors::add_withdrawal_entry(context, &entry).await?;
+        }
+        RegistryEvent::KeyRotation(_) | RegistryEvent::UpdateProtocolContract(_) => {}
+    }
+    Ok(())
+}
+
+/// Update a withdrawal, skipping withdrawals that Emily doesn't know of.
+async fn update_known_withdrawal(
+    context: &EmilyContext,
+    update: ValidatedWithdrawalUpdate,
+) -> Result<(), Error> {
+    let request_id = update.request_id;
+    match accessors::pull_and_update_withdrawal_with_retry(context, update, UPDATE_RETRIES).await {
+        Err(Error::NotFound) => {
+            info!(request_id, "skipping withdrawal that isn't in emily");
+            Ok(())
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+/// The event that confirms a deposit, with the same fulfillment that the
+/// signers report for a completed deposit.
+pub fn completed_deposit_event(
+    deposit_amount: u64,
+    event: &CompletedDepositEvent,
+    message: String,
+    chainstate: &ChainstateEntry,
+) -> DepositEvent {
+    DepositEvent {
+        status: StatusEntry::Confirmed(Fulfillment {
+            bitcoin_txid: event.outpoint.txid.to_string(),
+            bitcoin_tx_index: event.outpoint.vout,
+            stacks_txid: event.txid.to_string(),
+            bitcoin_block_hash: event.sweep_block_hash.to_string(),
+            bitcoin_block_height: event.sweep_block_height,
+            // The fee is whatever wasn't minted.
+            btc_fee: deposit_amount.saturating_sub(event.amount),
+        }),
+        message,
+        stacks_block_height: chainstate.key.height,
+        stacks_block_hash: chainstate.key.hash.clone(),
+    }
+}
+
+/// The event that confirms a withdrawal, with the same fulfillment that the
+/// signers report for an accepted withdrawal.
+pub fn accepted_withdrawal_event(
+    event: &WithdrawalAcceptEvent,
+    message: String,
+    chainstate: &ChainstateEntry,
+) -> WithdrawalEvent {
+    WithdrawalEvent {
+        status: StatusEntry::Confirmed(Fulfillment {
+            bitcoin_txid: event.outpoint.txid.to_string(),
+            bitcoin_tx_index: event.outpoint.vout,
+            stacks_txid: event.txid.to_string(),
+            bitcoin_block_hash: event.sweep_block_hash.to_string(),
+            bitcoin_block_height: event.sweep_block_height,
+            btc_fee: event.fee,
+        }),
+        message,
+        stacks_block_height: chainstate.key.height,
+        stacks_block_hash: chainstate.key.hash.clone(),
+    }
+}
+
+/// The event that fails a rejected withdrawal.
+pub fn rejected_withdrawal_event(message: String, chainstate: &ChainstateEntry) -> WithdrawalEvent {
+    WithdrawalEvent {
+        status: StatusEntry::Failed,
+        message,
+        stacks_block_height: chainstate.key.height,
+        stacks_block_hash: chainstate.key.hash.clone(),
+    }
+}
+
+/// The entry of a newly created withdrawal, with the same fields that the
+/// signers report for a created withdrawal.
+pub fn created_withdrawal_entry(
+    event: &WithdrawalCreateEvent,
+    stacks_block_height: u64,
+    created_at: u64,
+) -> WithdrawalEntry {
+    let stacks_block_hash = event.block_id.to_hex();
+    WithdrawalEntry {
+        key: WithdrawalEntryKey {
+            request_id: event.request_id,
+            stacks_block_hash: stacks_block_hash.clone(),
+        },
+        stacks_block_height,
+        recipient: event.recipient.to_string(),
+        sender: Some(event.sender.to_string()),
+        amount: event.amount,
+        parameters: WithdrawalParametersEntry { max_fee: event.max_fee },
+        history: vec![WithdrawalEvent {
+            status: StatusEntry::Pending,
+            message: "Just received withdrawal".to_string(),
+            stacks_block_hash: stacks_block_hash.clone(),
+            stacks_block_height,
+        }],
+        status: Status::Pending,
+        last_update_block_hash: stacks_block_hash,
+        last_update_height: stacks_block_height,
+        created_at: Some(created_at),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::{OutPoint, Txid};
+    use sbtc::events::StacksTxid;
+    use stacks_common::types::chainstate::StacksBlockId;
+
+    use crate::database::entries::chainstate::ChainstateEntryKey;
+
+    use super::*;
+
+    #[test]
+    fn completed_deposit_fee_is_the_amount_not_minted() {
+        let outpoint = OutPoint::new(Txid::from_byte_array([3; 32]), 1);
+        let event = CompletedDepositEvent {
+            txid: StacksTxid([1; 32]),
+            block_id: StacksBlockId([2; 32]),
+            amount: 99_000,
+            outpoint,
+            sweep_block_hash: bitcoin::BlockHash::all_zeros(),
+            sweep_block_height: 120,
+            sweep_txid: Txid::all_zeros(),
+        };
+        let chainstate = ChainstateEntry {
+            key: ChainstateEntryKey {
+                hash: "tip".to_string(),
+                height: 42,
+            },
+        };
+
+        let event = completed_deposit_event(100_000, &event, "minted".to_string(), &chainstate);
+
+        let StatusEntry::Confirmed(fulfillment) = event.status else {
+            panic!("expected a confirmed event, got {:?}", event.status);
+        };
+        assert_eq!(fulfillment.btc_fee, 1_000);
+        assert_eq!(fulfillment.bitcoin_txid, outpoint.txid.to_string());
+        assert_eq!(fulfillment.bitcoin_tx_index, 1);
+        assert_eq!(fulfillment.stacks_txid, StacksTxid([1; 32]).to_string());
+        assert_eq!(fulfillment.bitcoin_block_height, 120);
+        assert_eq!(event.stacks_block_height, 42);
+        assert_eq!(event.stacks_block_hash, "tip");
+    }
+}
@@ -0,0 +1,449 @@
+//! Status notifications for subscribers.
+//!
+//! Whenever an event is appended to the history of a deposit or withdrawal
+//! the new status is published here. Open server-sent event streams receive
+//! it through an in-process broadcast channel, and registered webhooks get
+//! it as a signed `POST` request that is retried with exponential backoff.
+//!
+//! Webhook deliveries run detached from the update that produced the event,
+//! so a slow or failing webhook never holds up the writes of the signers.
+//! The Lambda runtime freezes as soon as the response is sent, so there a
+//! delivery that is still running carries on during the next invocation of
+//! the same instance, and is lost if the instance is shut down first.
+//! Deliveries are best effort either way; subscribers that can't miss an
+//! event should read the status back from the API. Event streams only see
+//! the events published by the same process, so they are only served by
+//! `emily-server`.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use sha2::Sha256;
+use tokio::sync::broadcast;
+use tokio::task::{JoinHandle, JoinSet};
+use tracing::warn;
+
+use crate::api::models::subscription::StatusEvent;
+use crate::common::error::Error;
+use crate::database::entries::webhook::WebhookEntry;
+
+/// The header holding the signature of a webhook payload.
+pub const SIGNATURE_HEADER: &str = "x-emily-signature";
+
+/// The number of events buffered for each event stream. A stream that falls
+/// further behind than this misses events.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// The maximum number of attempts made to deliver an event to a webhook.
+const MAX_WEBHOOK_ATTEMPTS: u32 = 3;
+
+/// The delay before the first retry of a webhook delivery. The delay doubles
+/// after every failed attempt.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The timeout of a single webhook request. Together with the retries this
+/// bounds how long a delivery to a failing webhook keeps running.
+const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Publishes status events to event streams and webhooks.
+#[derive(Clone, Debug)]
+pub struct Notifier {
+    /// Sender for the open event streams.
+    sender: broadcast::Sender<StatusEvent>,
+    /// HTTP client used for webhook deliveries.
+    client: reqwest::Client,
+    /// Whether webhooks can point at loopback, private and link-local
+    /// addresses.
+    allow_private_addresses: bool,
+}
+
+impl Notifier {
+    /// Create a new notifier. Unless `allow_private_addresses` is set,
+    /// webhooks can only be registered for and delivered to public
+    /// addresses, so that they can't be used to reach internal services.
+    pub fn new(allow_private_addresses: bool) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        // Redirects could lead anywhere, so they count as failures.
+        let mut builder = reqwest::Client::builder()
+            .timeout(WEBHOOK_REQUEST_TIMEOUT)
+            .redirect(Policy::none());
+        if !allow_private_addresses {
+            builder = builder.dns_resolver(Arc::new(PublicAddressResolver));
+        }
+        let client = builder
+            .build()
+            .expect("Failed to build the webhook HTTP client");
+        Self {
+            sender,
+            client,
+            allow_private_addresses,
+        }
+    }
+
+    /// Subscribe to the events published by this process.
+    pub fn subscribe(&self) -> broadcast::Receiver<StatusEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Check that status events can be posted to the URL. The host of the
+    /// URL is resolved so that names of internal services are rejected
+    /// when the webhook is registered rather than on every delivery.
+    pub async fn check_webhook_url(&self, url: &Url) -> Result<(), Error> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::BadRequest(format!(
+                "Webhook url must be http or https, got {}",
+                url.scheme()
+            )));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::BadRequest("Webhook url must have a host".to_string()))?;
+        if self.allow_private_addresses {
+            return Ok(());
+        }
+
+        let addresses: Vec<IpAddr> = match ip_literal(url) {
+            Some(address) => vec![address],
+            None => {
+                let port = url.port_or_known_default().unwrap_or_default();
+                tokio::net::lookup_host((host, port))
+                    .await
+                    .map_err(|err| {
+                        Error::BadRequest(format!("Failed to resolve webhook host {host}: {err}"))
+                    })?
+                    .map(|address| address.ip())
+                    .collect()
+            }
+        };
+        if addresses.is_empty() || !addresses.into_iter().all(is_public_address) {
+            return Err(Error::BadRequest(format!(
+                "Webhook url must point at a public address, {host} does not"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Publish the event to the open event streams and start delivering it
+    /// to every webhook whose filter matches it. The deliveries run in a
+    /// detached task, whose handle finishes once every delivery has either
+    /// succeeded or run out of attempts.
+    pub fn publish(
+        &self,
+        event: StatusEvent,
+        webhooks: Vec<WebhookEntry>,
+    ) -> Result<JoinHandle<()>, Error> {
+        let payload = serde_json::to_vec(&event)?;
+        let webhooks: Vec<WebhookEntry> = webhooks
+            .into_iter()
+            .filter(|webhook| webhook.filter.matches(&event))
+            .collect();
+        // Sending only fails when there are no open streams.
+        let _ = self.sender.send(event);
+
+        let notifier = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut deliveries = JoinSet::new();
+            for webhook in webhooks {
+                deliveries.spawn(notifier.clone().deliver(webhook, payload.clone()));
+            }
+            while deliveries.join_next().await.is_some() {}
+        }))
+    }
+
+    /// Deliver a payload to a webhook, retrying with exponential backoff
+    /// until the webhook responds with a success status or the attempts run
+    /// out.
+    async fn deliver(self, webhook: WebhookEntry, payload: Vec<u8>) {
+        let webhook_id = &webhook.key.webhook_id;
+        // Host names are checked by the resolver of the client, but
+        // addresses in the URL itself never reach it.
+        if !self.allow_private_addresses {
+            let literal = Url::parse(&webhook.url)
+                .ok()
+                .and_then(|url| ip_literal(&url));
+            if literal.is_some_and(|address| !is_public_address(address)) {
+                warn!("Not delivering status event to webhook {webhook_id} at a private address");
+                return;
+            }
+        }
+
+        let signature = format!("sha256={}", sign_payload(&webhook.secret, &payload));
+        let mut delay = INITIAL_RETRY_DELAY;
+
+        for attempt in 1..=MAX_WEBHOOK_ATTEMPTS {
+            let result = self
+                .client
+                .post(&webhook.url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(payload.clone())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+
+            match result {
+                Ok(_) => return,
+                Err(error) => warn!(
+                    "Failed to deliver status event to webhook {webhook_id}. Attempt {attempt}/{MAX_WEBHOOK_ATTEMPTS}: {error}"
+                ),
+            }
+
+            if attempt < MAX_WEBHOOK_ATTEMPTS {
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+    }
+}
+
+/// Sign a webhook payload, returning the hex encoded HMAC-SHA256 of the
+/// payload keyed with the secret of the webhook.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take a key of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// The address in the host of the URL, if the host is an address rather
+/// than a name.
+fn ip_literal(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Returns true if the address is reachable on the public internet, as
+/// opposed to loopback, private, link-local and other reserved addresses.
+fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_ipv4(address),
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => is_public_ipv4(address),
+            None => is_public_ipv6(address),
+        },
+    }
+}
+
+/// Returns true if the IPv4 address is reachable on the public internet.
+fn is_public_ipv4(address: Ipv4Addr) -> bool {
+    let [first, second, ..] = address.octets();
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast()
+        // The "this network" block, 0.0.0.0/8.
+        || first == 0
+        // The shared address space of carrier-grade NATs, 100.64.0.0/10.
+        || (first == 100 && second & 0xc0 == 64)
+        // The reserved block, 240.0.0.0/4.
+        || first >= 240)
+}
+
+/// Returns true if the IPv6 address is reachable on the public internet.
+fn is_public_ipv6(address: Ipv6Addr) -> bool {
+    let [first, second, ..] = address.segments();
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_multicast()
+        // Unique local addresses, fc00::/7.
+        || first & 0xfe00 == 0xfc00
+        // Link-local addresses, fe80::/10.
+        || first & 0xffc0 == 0xfe80
+        // Documentation addresses, 2001:db8::/32.
+        || (first == 0x2001 && second == 0x0db8))
+}
+
+/// Resolves webhook hosts to their public addresses only, so that a
+/// webhook can't reach internal services through a name that resolves to
+/// them, including one whose records change after it was registered.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_address(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                let message = format!("{} has no public addresses", name.as_str());
+                return Err(message.into());
+            }
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(addresses)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockito::{mock, Matcher};
+    use test_case::test_case;
+
+    use crate::api::models::common::Status;
+    use crate::api::models::subscription::StatusEventFilter;
+    use crate::database::entries::webhook::WebhookEntryKey;
+
+    fn status_event() -> StatusEvent {
+        StatusEvent {
+            bitcoin_txid: Some("txid".to_string()),
+            bitcoin_tx_output_index: Some(0),
+            recipient: "recipient".to_string(),
+            status: Status::Accepted,
+            ..Default::default()
+        }
+    }
+
+    /// A webhook posting to the given path of the mock server. The mock
+    /// server is shared by every test, so each test uses its own path.
+    fn webhook(path: &str, filter: StatusEventFilter) -> WebhookEntry {
+        let key = WebhookEntryKey {
+            webhook_id: path.to_string(),
+            created_at: 0,
+        };
+        let url = format!("{}{path}", mockito::server_url());
+        WebhookEntry::new(key, "owner".to_string(), url, "secret".to_string(), filter)
+    }
+
+    #[test]
+    fn payload_signature_is_hmac_sha256() {
+        let signature = sign_payload("key", b"The quick brown fox jumps over the lazy dog");
+        assert_eq!(
+            signature,
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test_case(None, None, None, true; "empty filter")]
+    #[test_case(Some("txid"), None, None, true; "matching txid")]
+    #[test_case(Some("other"), None, None, false; "other txid")]
+    #[test_case(None, Some("recipient"), Some(Status::Accepted), true; "matching recipient and status")]
+    #[test_case(None, Some("recipient"), Some(Status::Confirmed), false; "other status")]
+    #[test_case(Some("txid"), Some("other"), None, false; "other recipient")]
+    fn status_event_filter_matches_every_set_field(
+        bitcoin_txid: Option<&str>,
+        recipient: Option<&str>,
+        status: Option<Status>,
+        expected: bool,
+    ) {
+        let filter = StatusEventFilter {
+            bitcoin_txid: bitcoin_txid.map(str::to_string),
+            recipient: recipient.map(str::to_string),
+            status,
+        };
+        assert_eq!(filter.matches(&status_event()), expected);
+    }
+
+    #[tokio::test]
+    async fn matching_webhooks_receive_signed_events() {
+        let event = status_event();
+        let payload = serde_json::to_vec(&event).unwrap();
+        let signature = format!("sha256={}", sign_payload("secret", &payload));
+        let delivered = mock("POST", "/signed")
+            .match_header(SIGNATURE_HEADER, signature.as_str())
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Exact(String::from_utf8(payload).unwrap()))
+            .expect(1)
+            .create();
+        let filtered_out = mock("POST", "/filtered-out").expect(0).create();
+
+        let other_recipient = StatusEventFilter {
+            recipient: Some("other".to_string()),
+            ..Default::default()
+        };
+        let webhooks = vec![
+            webhook("/signed", StatusEventFilter::default()),
+            webhook("/filtered-out", other_recipient),
+        ];
+        let deliveries = Notifier::new(true).publish(event, webhooks).unwrap();
+        deliveries.await.unwrap();
+
+        delivered.assert();
+        filtered_out.assert();
+    }
+
+    #[tokio::test]
+    async fn failing_webhooks_are_retried_until_the_attempts_run_out() {
+        let failing = mock("POST", "/failing")
+            .with_status(500)
+            .expect(MAX_WEBHOOK_ATTEMPTS as usize)
+            .create();
+
+        let webhooks = vec![webhook("/failing", StatusEventFilter::default())];
+        let deliveries = Notifier::new(true)
+            .publish(status_event(), webhooks)
+            .unwrap();
+        deliveries.await.unwrap();
+
+        failing.assert();
+    }
+
+    #[tokio::test]
+    async fn webhooks_at_private_addresses_are_not_delivered_to() {
+        // The mock server listens on a loopback address.
+        let private = mock("POST", "/private").expect(0).create();
+
+        let webhooks = vec![webhook("/private", StatusEventFilter::default())];
+        let deliveries = Notifier::new(false)
+            .publish(status_event(), webhooks)
+            .unwrap();
+        deliveries.await.unwrap();
+
+        private.assert();
+    }
+
+    #[tokio::test]
+    async fn event_streams_receive_published_events() {
+        let notifier = Notifier::new(false);
+        let mut stream = notifier.subscribe();
+
+        notifier.publish(status_event(), Vec::new()).unwrap();
+
+        assert_eq!(stream.recv().await.unwrap(), status_event());
+    }
+
+    #[test_case("http://127.0.0.1/hook"; "loopback")]
+    #[test_case("http://10.1.2.3/hook"; "private")]
+    #[test_case("http://169.254.169.254/latest/meta-data"; "link-local")]
+    #[test_case("http://100.64.0.1/hook"; "shared address space")]
+    #[test_case("http://[::1]/hook"; "ipv6 loopback")]
+    #[test_case("http://[fd00::1]/hook"; "ipv6 unique local")]
+    #[test_case("http://[fe80::1]/hook"; "ipv6 link-local")]
+    #[test_case("http://[::ffff:192.168.0.1]/hook"; "ipv4 mapped private")]
+    #[test_case("http://localhost:8080/hook"; "localhost")]
+    #[test_case("ftp://203.0.113.1/hook"; "other scheme")]
+    #[tokio::test]
+    async fn webhook_urls_must_point_at_public_addresses(url: &str) {
+        let url = Url::parse(url).unwrap();
+        let result = Notifier::new(false).check_webhook_url(&url).await;
+        assert!(matches!(result, Err(Error::BadRequest(_))));
+    }
+
+    #[test_case("https://93.184.215.14/hook"; "public ipv4")]
+    #[test_case("https://[2606:2800:21f:cb07:6820:80da:af6b:8b2c]/hook"; "public ipv6")]
+    #[tokio::test]
+    async fn webhook_urls_at_public_addresses_are_allowed(url: &str) {
+        let url = Url::parse(url).unwrap();
+        Notifier::new(false).check_webhook_url(&url).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn private_webhook_urls_are_allowed_when_configured() {
+        let url = Url::parse("http://127.0.0.1:8080/hook").unwrap();
+        Notifier::new(true).check_webhook_url(&url).await.unwrap();
+    }
+}
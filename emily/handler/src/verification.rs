@@ -0,0 +1,741 @@
+//! On-chain verification of the deposits submitted to the API.
+//!
+//! When an endpoint is configured, new deposits are checked against the
+//! bitcoin transaction they claim to be in before they are stored, so that
+//! the signers only ever see deposits that exist on chain or are about to.
+//! The same endpoint is used by the reconciler to check sweeps.
+
+use std::collections::HashMap;
+use std::str::FromStr as _;
+
+use bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid};
+use sbtc::deposits::{CreateDepositRequest, DepositInfo};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::deposit::requests::CreateDepositRequestBody;
+use crate::common::error::{DepositVerificationError, Error};
+
+/// The error code that bitcoin-core returns from `getrawtransaction` when
+/// it doesn't know about the transaction.
+const RPC_INVALID_ADDRESS_OR_KEY: i64 = -5;
+
+/// An endpoint that bitcoin transactions can be fetched from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitcoinEndpoint {
+    /// The base URL of an Esplora HTTP API.
+    Esplora(String),
+    /// The URL of a bitcoin-core RPC server. Credentials, if any, are taken
+    /// from the user info of the URL.
+    ///
+    /// The node must run with `-txindex`. Without it `getrawtransaction`
+    /// only finds mempool transactions, so every confirmed deposit would be
+    /// stored as unconfirmed. [`DepositVerifier::ensure_ready`] checks this.
+    BitcoinRpc(String),
+}
+
+/// The outcome of verifying a deposit against the bitcoin blockchain.
+#[derive(Debug)]
+pub enum VerificationOutcome {
+    /// The transaction was found and the deposit matches one of its outputs.
+    Verified {
+        /// The deposit as read from the transaction.
+        info: DepositInfo,
+        /// The script pubkey of the output spent by the first input of the
+        /// transaction, if that output could be fetched.
+        sender: Option<ScriptBuf>,
+    },
+    /// The transaction isn't known to the endpoint yet.
+    Unconfirmed,
+}
+
+/// Whether a transaction output has been spent, as far as the endpoint
+/// knows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStatus {
+    /// The endpoint doesn't know about the output.
+    Unknown,
+    /// The output exists and hasn't been spent.
+    Unspent,
+    /// The output has been spent by a transaction in a block.
+    Spent,
+}
+
+/// Checks deposits against the bitcoin transactions that they claim to be
+/// in.
+#[derive(Clone, Debug)]
+pub struct DepositVerifier {
+    /// HTTP client.
+    client: reqwest::Client,
+    /// Where transactions are fetched from.
+    endpoint: BitcoinEndpoint,
+    /// Whether the deposits are for bitcoin mainnet.
+    is_mainnet: bool,
+}
+
+/// JSON-RPC response from bitcoin-core.
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    /// The result of the call, if the call succeeded and had a result.
+    result: Option<T>,
+    /// The error, if the call failed.
+    error: Option<RpcError>,
+}
+
+/// JSON-RPC error from bitcoin-core.
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    /// Error code.
+    code: i64,
+    /// Error message.
+    message: String,
+}
+
+/// The state of one of the optional bitcoin-core indexes, as returned by
+/// `getindexinfo`.
+#[derive(Debug, Deserialize)]
+struct RpcIndexInfo {
+    /// Whether the index has caught up with the chain tip.
+    synced: bool,
+}
+
+/// Esplora response to an outspend request.
+#[derive(Debug, Deserialize)]
+struct EsploraOutspend {
+    /// Whether the output is spent.
+    spent: bool,
+    /// The status of the spending transaction, if the output is spent.
+    status: Option<EsploraTxStatus>,
+}
+
+/// Esplora status of a transaction.
+#[derive(Debug, Deserialize)]
+struct EsploraTxStatus {
+    /// Whether the transaction is in a block.
+    confirmed: bool,
+    /// The block of the transaction, if it is in one.
+    #[serde(default)]
+    block_hash: Option<BlockHash>,
+}
+
+/// The part of a `getblockheader` response that says whether the block is
+/// on the best chain.
+#[derive(Debug, Deserialize)]
+struct RpcBlockHeader {
+    /// The number of confirmations of the block, -1 if it isn't on the best
+    /// chain.
+    confirmations: i64,
+}
+
+impl DepositVerifier {
+    /// Create a new verifier that fetches transactions from the endpoint
+    /// using the given client.
+    pub fn new(client: reqwest::Client, endpoint: BitcoinEndpoint, is_mainnet: bool) -> Self {
+        Self { client, endpoint, is_mainnet }
+    }
+
+    /// Check that the endpoint can find confirmed transactions, which for
+    /// bitcoin-core means that the transaction index is enabled.
+    pub async fn ensure_ready(&self) -> Result<(), Error> {
+        let BitcoinEndpoint::BitcoinRpc(url) = &self.endpoint else {
+            return Ok(());
+        };
+        let params = serde_json::json!(["txindex"]);
+        let response: RpcResponse<HashMap<String, RpcIndexInfo>> =
+            self.rpc_call(url, "getindexinfo", params).await?;
+        match response {
+            RpcResponse { error: Some(error), .. } => Err(Error::Debug(format!(
+                "getindexinfo failed with code {}: {}",
+                error.code, error.message
+            ))),
+            RpcResponse { result: Some(indexes), .. } => match indexes.get("txindex") {
+                Some(RpcIndexInfo { synced: true }) => Ok(()),
+                Some(RpcIndexInfo { synced: false }) => {
+                    tracing::warn!(
+                        "the transaction index of the deposit verification node is still \
+                        syncing, so older deposits will be stored as unconfirmed"
+                    );
+                    Ok(())
+                }
+                None => Err(Error::Debug(
+                    "the bitcoin-core node used to verify deposits must run with -txindex"
+                        .to_string(),
+                )),
+            },
+            RpcResponse { result: None, error: None } => Err(Error::Debug(
+                "getindexinfo returned neither a result nor an error".to_string(),
+            )),
+        }
+    }
+
+    /// Verify the deposit in the request body against its bitcoin
+    /// transaction.
+    ///
+    /// A deposit whose transaction can't be found is reported as
+    /// unconfirmed rather than rejected, since it may simply not have
+    /// reached the endpoint yet. A deposit whose transaction was found but
+    /// doesn't match the request is rejected.
+    pub async fn verify(
+        &self,
+        body: &CreateDepositRequestBody,
+    ) -> Result<VerificationOutcome, Error> {
+        let txid = Txid::from_str(&body.bitcoin_txid)
+            .map_err(|_| DepositVerificationError::InvalidTxid(body.bitcoin_txid.clone()))?;
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(txid, body.bitcoin_tx_output_index),
+            reclaim_script: ScriptBuf::from_hex(&body.reclaim_script)?,
+            deposit_script: ScriptBuf::from_hex(&body.deposit_script)?,
+        };
+
+        let Some(tx) = self.get_transaction(&txid).await? else {
+            return Ok(VerificationOutcome::Unconfirmed);
+        };
+
+        let info = request
+            .validate_tx(&tx, self.is_mainnet)
+            .map_err(DepositVerificationError::InvalidTransaction)?;
+        let sender = self.get_sender_script(&tx).await;
+
+        Ok(VerificationOutcome::Verified { info, sender })
+    }
+
+    /// Check whether the transaction is in the given block and the block is
+    /// on the best chain. Transactions that are only in the mempool, or in
+    /// some other block, are not.
+    ///
+    /// The lookup is scoped to the block, so bitcoin-core finds the
+    /// transaction whether or not it runs with `-txindex`.
+    pub async fn is_in_block(&self, txid: &Txid, block_hash: &BlockHash) -> Result<bool, Error> {
+        match &self.endpoint {
+            BitcoinEndpoint::Esplora(url) => {
+                let url = format!("{}/tx/{txid}/status", url.trim_end_matches('/'));
+                let response = self.client.get(url).send().await?;
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(false);
+                }
+                let status: EsploraTxStatus = response.error_for_status()?.json().await?;
+                Ok(status.confirmed && status.block_hash.as_ref() == Some(block_hash))
+            }
+            BitcoinEndpoint::BitcoinRpc(url) => {
+                let params = serde_json::json!([block_hash.to_string(), true]);
+                let response: RpcResponse<RpcBlockHeader> =
+                    self.rpc_call(url, "getblockheader", params).await?;
+                let is_on_best_chain = match response {
+                    RpcResponse { result: Some(header), .. } => header.confirmations > 0,
+                    RpcResponse {
+                        error:
+                            Some(RpcError {
+                                code: RPC_INVALID_ADDRESS_OR_KEY,
+                                ..
+                            }),
+                        ..
+                    } => false,
+                    RpcResponse { error: Some(error), .. } => {
+                        return Err(Error::Debug(format!(
+                            "getblockheader failed with code {}: {}",
+                            error.code, error.message
+                        )))
+                    }
+                    RpcResponse { result: None, error: None } => {
+                        return Err(Error::Debug(
+                            "getblockheader returned neither a result nor an error".to_string(),
+                        ))
+                    }
+                };
+                if !is_on_best_chain {
+                    return Ok(false);
+                }
+                Ok(self
+                    .get_rpc_tx_hex(url, txid, Some(block_hash))
+                    .await?
+                    .is_some())
+            }
+        }
+    }
+
+    /// Check whether the output has been spent by a transaction in a block.
+    /// Spends that are still in the mempool are not counted.
+    pub async fn get_output_status(&self, outpoint: &OutPoint) -> Result<OutputStatus, Error> {
+        let Some(tx) = self.get_transaction(&outpoint.txid).await? else {
+            return Ok(OutputStatus::Unknown);
+        };
+        if tx.output.len() <= outpoint.vout as usize {
+            return Ok(OutputStatus::Unknown);
+        }
+
+        let is_spent = match &self.endpoint {
+            BitcoinEndpoint::Esplora(url) => self.is_esplora_output_spent(url, outpoint).await?,
+            BitcoinEndpoint::BitcoinRpc(url) => self.is_rpc_output_spent(url, outpoint).await?,
+        };
+        Ok(if is_spent {
+            OutputStatus::Spent
+        } else {
+            OutputStatus::Unspent
+        })
+    }
+
+    /// Fetch the script pubkey of the output that the first input of the
+    /// transaction spends. The sender is only informational, so a failure
+    /// to fetch it is logged rather than returned.
+    async fn get_sender_script(&self, tx: &Transaction) -> Option<ScriptBuf> {
+        let outpoint = tx.input.first()?.previous_output;
+        match self.get_transaction(&outpoint.txid).await {
+            Ok(Some(prev_tx)) => prev_tx
+                .output
+                .get(outpoint.vout as usize)
+                .map(|output| output.script_pubkey.clone()),
+            Ok(None) => None,
+            Err(error) => {
+                tracing::warn!(%error, %outpoint, "could not fetch the deposit sender");
+                None
+            }
+        }
+    }
+
+    /// Fetch a transaction from the endpoint, returning `None` if the
+    /// endpoint doesn't know about it.
+    async fn get_transaction(&self, txid: &Txid) -> Result<Option<Transaction>, Error> {
+        let maybe_tx_hex = match &self.endpoint {
+            BitcoinEndpoint::Esplora(url) => self.get_esplora_tx_hex(url, txid).await?,
+            BitcoinEndpoint::BitcoinRpc(url) => self.get_rpc_tx_hex(url, txid, None).await?,
+        };
+
+        maybe_tx_hex
+            .map(|tx_hex| bitcoin::consensus::encode::deserialize_hex(&tx_hex))
+            .transpose()
+            .map_err(|err| Error::Debug(format!("Failed to decode transaction {txid}: {err}")))
+    }
+
+    /// Fetch the raw transaction hex from an Esplora API.
+    async fn get_esplora_tx_hex(
+        &self,
+        base_url: &str,
+        txid: &Txid,
+    ) -> Result<Option<String>, Error> {
+        let url = format!("{}/tx/{txid}/hex", base_url.trim_end_matches('/'));
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.text().await?))
+    }
+
+    /// Check whether an output is spent by a transaction in a block using
+    /// the Esplora outspend API.
+    async fn is_esplora_output_spent(
+        &self,
+        base_url: &str,
+        outpoint: &OutPoint,
+    ) -> Result<bool, Error> {
+        let url = format!(
+            "{}/tx/{}/outspend/{}",
+            base_url.trim_end_matches('/'),
+            outpoint.txid,
+            outpoint.vout
+        );
+        let outspend: EsploraOutspend = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(outspend.spent && outspend.status.map_or(false, |status| status.confirmed))
+    }
+
+    /// Check whether an output is spent by a transaction in a block using
+    /// `gettxout`, leaving out the mempool. bitcoin-core returns nothing for
+    /// spent outputs.
+    async fn is_rpc_output_spent(&self, url: &str, outpoint: &OutPoint) -> Result<bool, Error> {
+        let params = serde_json::json!([outpoint.txid.to_string(), outpoint.vout, false]);
+        let response: RpcResponse<serde_json::Value> =
+            self.rpc_call(url, "gettxout", params).await?;
+        match response {
+            RpcResponse { error: Some(error), .. } => Err(Error::Debug(format!(
+                "gettxout failed with code {}: {}",
+                error.code, error.message
+            ))),
+            RpcResponse { result, .. } => Ok(result.is_none()),
+        }
+    }
+
+    /// Fetch the raw transaction hex from bitcoin-core using
+    /// `getrawtransaction`, looking only in the given block if there is one.
+    async fn get_rpc_tx_hex(
+        &self,
+        url: &str,
+        txid: &Txid,
+        block_hash: Option<&BlockHash>,
+    ) -> Result<Option<String>, Error> {
+        let params = match block_hash {
+            Some(block_hash) => {
+                serde_json::json!([txid.to_string(), false, block_hash.to_string()])
+            }
+            None => serde_json::json!([txid.to_string(), false]),
+        };
+        let response: RpcResponse<String> = self.rpc_call(url, "getrawtransaction", params).await?;
+        match response {
+            RpcResponse { result: Some(tx_hex), .. } => Ok(Some(tx_hex)),
+            RpcResponse {
+                error:
+                    Some(RpcError {
+                        code: RPC_INVALID_ADDRESS_OR_KEY,
+                        ..
+                    }),
+                ..
+            } => Ok(None),
+            RpcResponse { error: Some(error), .. } => Err(Error::Debug(format!(
+                "getrawtransaction failed with code {}: {}",
+                error.code, error.message
+            ))),
+            RpcResponse { result: None, error: None } => Err(Error::Debug(
+                "getrawtransaction returned neither a result nor an error".to_string(),
+            )),
+        }
+    }
+
+    /// Make a JSON-RPC call to bitcoin-core.
+    async fn rpc_call<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<RpcResponse<T>, Error> {
+        let mut url = reqwest::Url::parse(url)
+            .map_err(|err| Error::Debug(format!("Invalid bitcoin RPC URL: {err}")))?;
+        let username = url.username().to_string();
+        let password = url.password().map(str::to_string);
+        // The credentials are sent in the authorization header instead.
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        let mut request = self.client.post(url).json(&serde_json::json!({
+            "jsonrpc": "1.0",
+            "id": "emily",
+            "method": method,
+            "params": params,
+        }));
+        if !username.is_empty() {
+            request = request.basic_auth(username, password);
+        }
+
+        // bitcoin-core responds with an error status code along with the
+        // JSON-RPC error, so the body is read regardless of the status.
+        Ok(request.send().await?.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bitcoin::consensus::encode::serialize_hex;
+    use bitcoin::hashes::Hash as _;
+    use mockito::{mock, Matcher};
+    use sbtc::testing::deposits::{tx_setup, TxSetup};
+
+    const AMOUNT: u64 = 100_000;
+
+    fn request_body(setup: &TxSetup) -> CreateDepositRequestBody {
+        CreateDepositRequestBody {
+            bitcoin_txid: setup.tx.compute_txid().to_string(),
+            bitcoin_tx_output_index: 0,
+            reclaim_script: setup.reclaim.reclaim_script().to_hex_string(),
+            deposit_script: setup.deposit.deposit_script().to_hex_string(),
+        }
+    }
+
+    fn esplora_verifier() -> DepositVerifier {
+        let endpoint = BitcoinEndpoint::Esplora(mockito::server_url());
+        DepositVerifier::new(reqwest::Client::new(), endpoint, false)
+    }
+
+    /// The mock server is shared by every test, so each test that talks to
+    /// the RPC endpoint uses its own path.
+    fn rpc_verifier(path: &str) -> DepositVerifier {
+        let endpoint = BitcoinEndpoint::BitcoinRpc(format!("{}{path}", mockito::server_url()));
+        DepositVerifier::new(reqwest::Client::new(), endpoint, false)
+    }
+
+    fn rpc_result(result: serde_json::Value) -> String {
+        serde_json::json!({ "result": result, "error": null, "id": "emily" }).to_string()
+    }
+
+    #[tokio::test]
+    async fn esplora_deposits_are_verified_against_their_transaction() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("GET", format!("/tx/{txid}/hex").as_str())
+            .with_body(serialize_hex(&setup.tx))
+            .create();
+
+        let outcome = esplora_verifier()
+            .verify(&request_body(&setup))
+            .await
+            .unwrap();
+
+        match outcome {
+            VerificationOutcome::Verified { info, sender } => {
+                assert_eq!(info.amount, AMOUNT);
+                assert_eq!(info.max_fee, 1_000);
+                // The transaction has no inputs to take the sender from.
+                assert_eq!(sender, None);
+            }
+            VerificationOutcome::Unconfirmed => panic!("expected the deposit to be verified"),
+        }
+    }
+
+    #[tokio::test]
+    async fn esplora_deposits_with_unknown_transactions_are_unconfirmed() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("GET", format!("/tx/{txid}/hex").as_str())
+            .with_status(404)
+            .create();
+
+        let outcome = esplora_verifier()
+            .verify(&request_body(&setup))
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, VerificationOutcome::Unconfirmed));
+    }
+
+    #[tokio::test]
+    async fn esplora_deposits_that_do_not_match_their_transaction_are_rejected() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("GET", format!("/tx/{txid}/hex").as_str())
+            .with_body(serialize_hex(&setup.tx))
+            .create();
+
+        // The scripts of another deposit don't match the output.
+        let other = tx_setup(10, 1_000, AMOUNT);
+        let body = CreateDepositRequestBody {
+            bitcoin_txid: txid.to_string(),
+            ..request_body(&other)
+        };
+        let result = esplora_verifier().verify(&body).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::DepositVerification(
+                DepositVerificationError::InvalidTransaction(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn esplora_outputs_spent_in_a_block_are_spent() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("GET", format!("/tx/{txid}/hex").as_str())
+            .with_body(serialize_hex(&setup.tx))
+            .create();
+        let _outspend = mock("GET", format!("/tx/{txid}/outspend/0").as_str())
+            .with_body(r#"{"spent": true, "status": {"confirmed": true}}"#)
+            .create();
+
+        let status = esplora_verifier()
+            .get_output_status(&OutPoint::new(txid, 0))
+            .await
+            .unwrap();
+        assert_eq!(status, OutputStatus::Spent);
+
+        // The transaction only has one output.
+        let status = esplora_verifier()
+            .get_output_status(&OutPoint::new(txid, 1))
+            .await
+            .unwrap();
+        assert_eq!(status, OutputStatus::Unknown);
+    }
+
+    #[tokio::test]
+    async fn rpc_deposits_are_verified_against_their_transaction() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("POST", "/rpc-verified")
+            .match_header("authorization", "Basic dXNlcjpwYXNz")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "getrawtransaction",
+                "params": [txid.to_string(), false],
+            })))
+            .with_body(rpc_result(serialize_hex(&setup.tx).into()))
+            .create();
+
+        let url = mockito::server_url().replace("http://", "http://user:pass@");
+        let endpoint = BitcoinEndpoint::BitcoinRpc(format!("{url}/rpc-verified"));
+        let verifier = DepositVerifier::new(reqwest::Client::new(), endpoint, false);
+        let outcome = verifier.verify(&request_body(&setup)).await.unwrap();
+
+        match outcome {
+            VerificationOutcome::Verified { info, .. } => assert_eq!(info.amount, AMOUNT),
+            VerificationOutcome::Unconfirmed => panic!("expected the deposit to be verified"),
+        }
+    }
+
+    #[tokio::test]
+    async fn rpc_deposits_with_unknown_transactions_are_unconfirmed() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        // bitcoin-core responds with an error status along with the error.
+        let _tx = mock("POST", "/rpc-unknown")
+            .with_status(500)
+            .with_body(
+                serde_json::json!({
+                    "result": null,
+                    "error": { "code": RPC_INVALID_ADDRESS_OR_KEY, "message": "No such transaction" },
+                    "id": "emily",
+                })
+                .to_string(),
+            )
+            .create();
+
+        let outcome = rpc_verifier("/rpc-unknown")
+            .verify(&request_body(&setup))
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, VerificationOutcome::Unconfirmed));
+    }
+
+    #[tokio::test]
+    async fn rpc_errors_other_than_unknown_transactions_are_errors() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let _tx = mock("POST", "/rpc-error")
+            .with_status(500)
+            .with_body(
+                serde_json::json!({
+                    "result": null,
+                    "error": { "code": -28, "message": "Loading block index" },
+                    "id": "emily",
+                })
+                .to_string(),
+            )
+            .create();
+
+        let result = rpc_verifier("/rpc-error")
+            .verify(&request_body(&setup))
+            .await;
+
+        assert!(matches!(result, Err(Error::Debug(_))));
+    }
+
+    #[tokio::test]
+    async fn rpc_endpoints_must_have_a_transaction_index() {
+        let _synced = mock("POST", "/rpc-txindex")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({ "method": "getindexinfo" }),
+            ))
+            .with_body(rpc_result(serde_json::json!({
+                "txindex": { "synced": true, "best_block_height": 100 }
+            })))
+            .create();
+        let _missing = mock("POST", "/rpc-no-txindex")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({ "method": "getindexinfo" }),
+            ))
+            .with_body(rpc_result(serde_json::json!({})))
+            .create();
+
+        rpc_verifier("/rpc-txindex").ensure_ready().await.unwrap();
+        let result = rpc_verifier("/rpc-no-txindex").ensure_ready().await;
+        assert!(matches!(result, Err(Error::Debug(_))));
+
+        // Esplora always indexes transactions.
+        esplora_verifier().ensure_ready().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rpc_outputs_missing_from_the_utxo_set_are_spent() {
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let txid = setup.tx.compute_txid();
+        let _tx = mock("POST", "/rpc-spent")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({ "method": "getrawtransaction" }),
+            ))
+            .with_body(rpc_result(serialize_hex(&setup.tx).into()))
+            .create();
+        let _txout = mock("POST", "/rpc-spent")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "gettxout",
+                "params": [txid.to_string(), 0, false],
+            })))
+            .with_body(rpc_result(serde_json::Value::Null))
+            .create();
+
+        let status = rpc_verifier("/rpc-spent")
+            .get_output_status(&OutPoint::new(txid, 0))
+            .await
+            .unwrap();
+        assert_eq!(status, OutputStatus::Spent);
+    }
+
+    #[tokio::test]
+    async fn esplora_transactions_are_only_in_the_block_they_were_confirmed_in() {
+        let txid = Txid::from_byte_array([4; 32]);
+        let block_hash = BlockHash::from_byte_array([5; 32]);
+        let _status = mock("GET", format!("/tx/{txid}/status").as_str())
+            .with_body(
+                serde_json::json!({
+                    "confirmed": true,
+                    "block_height": 100,
+                    "block_hash": block_hash.to_string(),
+                })
+                .to_string(),
+            )
+            .create();
+        let mempool_txid = Txid::from_byte_array([6; 32]);
+        let _mempool = mock("GET", format!("/tx/{mempool_txid}/status").as_str())
+            .with_body(r#"{"confirmed": false}"#)
+            .create();
+
+        let verifier = esplora_verifier();
+        assert!(verifier.is_in_block(&txid, &block_hash).await.unwrap());
+        let other_block = BlockHash::from_byte_array([7; 32]);
+        assert!(!verifier.is_in_block(&txid, &other_block).await.unwrap());
+        assert!(!verifier
+            .is_in_block(&mempool_txid, &block_hash)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn rpc_transactions_are_looked_up_in_their_block() {
+        let txid = Txid::from_byte_array([8; 32]);
+        let block_hash = BlockHash::from_byte_array([9; 32]);
+        let stale_block_hash = BlockHash::from_byte_array([10; 32]);
+        let setup = tx_setup(10, 1_000, AMOUNT);
+        let _header = mock("POST", "/rpc-in-block")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "getblockheader",
+                "params": [block_hash.to_string(), true],
+            })))
+            .with_body(rpc_result(serde_json::json!({ "confirmations": 3 })))
+            .create();
+        let _stale_header = mock("POST", "/rpc-in-block")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "getblockheader",
+                "params": [stale_block_hash.to_string(), true],
+            })))
+            .with_body(rpc_result(serde_json::json!({ "confirmations": -1 })))
+            .create();
+        // The lookup names the block, which works without a transaction
+        // index.
+        let _tx = mock("POST", "/rpc-in-block")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "method": "getrawtransaction",
+                "params": [txid.to_string(), false, block_hash.to_string()],
+            })))
+            .with_body(rpc_result(serialize_hex(&setup.tx).into()))
+            .create();
+
+        let verifier = rpc_verifier("/rpc-in-block");
+        assert!(verifier.is_in_block(&txid, &block_hash).await.unwrap());
+        assert!(!verifier
+            .is_in_block(&txid, &stale_block_hash)
+            .await
+            .unwrap());
+    }
+}
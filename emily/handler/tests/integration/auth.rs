@@ -0,0 +1,72 @@
+//! Tests that privileged routes refuse api keys that aren't allowed to use
+//! them.
+
+use test_case::test_case;
+use testing_emily_client::apis;
+use testing_emily_client::models::Chainstate;
+
+use emily_handler::api::models::api_key::ApiKeyScope;
+
+use crate::common::{clean_setup, store_api_key, with_api_key, StandardError};
+
+/// The api key that the test case uses.
+async fn api_key_for_case(case: &str) -> String {
+    match case {
+        "unknown" => "unknown.secret".to_string(),
+        "malformed" => "not-a-scoped-key".to_string(),
+        "out of scope" => store_api_key("readonly", vec![ApiKeyScope::ReadOnly], false).await,
+        "revoked" => store_api_key("revoked", vec![ApiKeyScope::ChainstateWrite], true).await,
+        "in scope" => store_api_key("writer", vec![ApiKeyScope::ChainstateWrite], false).await,
+        _ => unreachable!("unknown test case"),
+    }
+}
+
+#[tokio::test]
+#[test_case("unknown", Some(401); "unknown key")]
+#[test_case("malformed", Some(401); "malformed key")]
+#[test_case("out of scope", Some(403); "out of scope key")]
+#[test_case("revoked", Some(403); "revoked key")]
+#[test_case("in scope", None; "in scope key")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn scoped_routes_check_api_keys(case: &str, expected_status: Option<u16>) {
+    let configuration = clean_setup().await;
+    let key = api_key_for_case(case).await;
+    let configuration = with_api_key(&configuration, key);
+
+    let chainstate = Chainstate {
+        stacks_block_hash: "test-hash-1".to_string(),
+        stacks_block_height: 1,
+    };
+    let result = apis::chainstate_api::set_chainstate(&configuration, chainstate).await;
+
+    match expected_status {
+        Some(status_code) => {
+            let error: StandardError = result
+                .expect_err("Set a chainstate with a key that isn't allowed to.")
+                .into();
+            assert_eq!(error.status_code, status_code);
+        }
+        None => {
+            result.expect("Received an error after making a valid set chainstate api call.");
+        }
+    }
+}
+
+#[tokio::test]
+#[test_case("unknown"; "unknown key")]
+#[test_case("malformed"; "malformed key")]
+#[test_case("out of scope"; "out of scope key")]
+#[test_case("revoked"; "revoked key")]
+#[test_case("in scope"; "in scope key")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn admin_routes_refuse_scoped_api_keys(case: &str) {
+    let configuration = clean_setup().await;
+    let key = api_key_for_case(case).await;
+    let configuration = with_api_key(&configuration, key);
+
+    let error: StandardError = apis::api_key_api::get_api_keys(&configuration)
+        .await
+        .expect_err("Listed the api keys with a key that isn't the admin key.")
+        .into();
+    assert_eq!(error.status_code, 403);
+}
@@ -9,6 +9,12 @@ use testing_emily_client::{
     models::ErrorResponse,
 };
 
+use emily_handler::api::auth::hash_api_key;
+use emily_handler::api::models::api_key::ApiKeyScope;
+use emily_handler::context::EmilyContext;
+use emily_handler::database::entries::api_key::{ApiKeyEntry, ApiKeyEntryKey};
+use emily_handler::database::storage::EmilyStorage;
+
 use crate::config::SETTINGS;
 
 /// Standard error type.
@@ -28,6 +34,68 @@ pub async fn clean_setup() -> Configuration {
     configuration
 }
 
+/// A storage backend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    DynamoDb,
+    Postgres,
+}
+
+/// Connect to the storage backend and return a context using it.
+pub async fn connect(backend: Backend) -> EmilyContext {
+    match backend {
+        Backend::DynamoDb => {
+            EmilyContext::local_instance(&SETTINGS.storage.dynamodb_endpoint).await
+        }
+        Backend::Postgres => {
+            EmilyContext::local_postgres_instance(&SETTINGS.storage.postgres_url).await
+        }
+    }
+    .expect("Failed to connect to the storage backend.")
+}
+
+/// Connect to the storage backend, wipe it and return a context using it.
+pub async fn clean_context(backend: Backend) -> EmilyContext {
+    let context = connect(backend).await;
+    context
+        .storage
+        .wipe_all()
+        .await
+        .expect("Failed to wipe the storage backend.");
+    context
+}
+
+/// Store a scoped api key in the storage backend of the server under test
+/// and return the full key.
+pub async fn store_api_key(key_id: &str, scopes: Vec<ApiKeyScope>, revoked: bool) -> String {
+    let context = connect(SETTINGS.server.backend).await;
+    let key = format!("{key_id}.secret");
+    let entry = ApiKeyEntry {
+        key: ApiKeyEntryKey {
+            key_id: key_id.to_string(),
+            created_at: 1,
+        },
+        key_hash: hash_api_key(&key),
+        description: "test key".to_string(),
+        scopes,
+        revoked,
+    };
+    context
+        .storage
+        .put_api_key_entry(&entry)
+        .await
+        .expect("Failed to store the api key.");
+    key
+}
+
+/// Use a different api key with the configuration.
+pub fn with_api_key(configuration: &Configuration, key: String) -> Configuration {
+    let mut configuration = configuration.clone();
+    configuration.api_key = Some(ApiKey { prefix: None, key });
+    configuration
+}
+
 /// Error type that represents an error
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestError<T> {
@@ -4,11 +4,15 @@ use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
+use crate::common::Backend;
+
 /// Top-level configuration.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Settings {
     /// Server config.
     pub server: ServerConfig,
+    /// Storage config.
+    pub storage: StorageConfig,
 }
 
 /// Server config.
@@ -20,6 +24,19 @@ pub struct ServerConfig {
     pub port: u16,
     /// Api key.
     pub api_key: String,
+    /// The storage backend of the server, which the tests that write to
+    /// storage directly use too.
+    pub backend: Backend,
+}
+
+/// Storage config, used by the tests that exercise the storage backends
+/// directly.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StorageConfig {
+    /// DynamoDB endpoint.
+    pub dynamodb_endpoint: String,
+    /// PostgreSQL connection URL.
+    pub postgres_url: String,
 }
 
 /// Statically configured settings.
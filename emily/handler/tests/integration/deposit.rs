@@ -6,7 +6,9 @@ use sbtc::testing;
 use sbtc::testing::deposits::TxSetup;
 use stacks_common::codec::StacksMessageCodec as _;
 use stacks_common::types::chainstate::StacksAddress;
-use testing_emily_client::models::{Fulfillment, Status, UpdateDepositsRequestBody};
+use testing_emily_client::models::{
+    DepositVerification, Fulfillment, Status, UpdateDepositsRequestBody,
+};
 use testing_emily_client::{
     apis::{self, configuration::Configuration},
     models::{CreateDepositRequestBody, Deposit, DepositInfo, DepositParameters, DepositUpdate},
@@ -130,8 +132,10 @@ async fn create_and_get_deposit_happy_path() {
             max_fee: DEPOSIT_MAX_FEE,
         }),
         recipient: expected_recipient,
+        sender: None,
         status: testing_emily_client::models::Status::Pending,
         status_message: INITIAL_DEPOSIT_STATUS_MESSAGE.into(),
+        verification: DepositVerification::Unverified,
     };
 
     // Act.
@@ -247,8 +251,10 @@ async fn get_deposits_for_transaction() {
                 max_fee: DEPOSIT_MAX_FEE,
             }),
             recipient: expected_recipient.clone(),
+            sender: None,
             status: testing_emily_client::models::Status::Pending,
             status_message: INITIAL_DEPOSIT_STATUS_MESSAGE.into(),
+            verification: DepositVerification::Unverified,
         };
         expected_deposits.push(expected_deposit);
     }
@@ -313,6 +319,7 @@ async fn get_deposits() {
                 last_update_block_hash: BLOCK_HASH.into(),
                 last_update_height: BLOCK_HEIGHT,
                 recipient: expected_recipient.clone(),
+                sender: None,
                 status: testing_emily_client::models::Status::Pending,
                 reclaim_script: reclaim_script.clone(),
                 deposit_script: deposit_script.clone(),
@@ -337,7 +344,13 @@ async fn get_deposits() {
     loop {
         let response = apis::deposit_api::get_deposits(
             &configuration,
-            status,
+            Some(vec![status]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
             next_token.as_ref().and_then(|o| o.as_deref()),
             Some(chunksize as i32),
         )
@@ -433,6 +446,7 @@ async fn get_deposits_for_recipient() {
                 last_update_block_hash: BLOCK_HASH.into(),
                 last_update_height: BLOCK_HEIGHT,
                 recipient: recipient.clone(),
+                sender: None,
                 status: testing_emily_client::models::Status::Pending,
                 reclaim_script: reclaim_script.clone(),
                 deposit_script: deposit_script.clone(),
@@ -546,6 +560,7 @@ async fn update_deposits() {
                 last_update_height: update_block_height,
                 status: update_status.clone(),
                 status_message: update_status_message.into(),
+                verification: DepositVerification::Unverified,
             };
             deposit_updates.push(deposit_update);
 
@@ -563,6 +578,7 @@ async fn update_deposits() {
                     max_fee: DEPOSIT_MAX_FEE,
                 }),
                 recipient: expected_recipient.clone(),
+                sender: None,
                 status: update_status.clone(),
                 status_message: update_status_message.into(),
             };
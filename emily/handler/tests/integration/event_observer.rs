@@ -0,0 +1,168 @@
+//! Tests that stacks node new block events are applied to the deposits and
+//! withdrawals that their registry events are about, and reverted on reorg.
+
+use sbtc::events::{RegistryEvent, StacksTxid, TxInfo};
+use sbtc::webhooks::NewBlockEvent;
+use test_case::test_case;
+
+use emily_handler::api::models::common::Status;
+use emily_handler::api::routes::routes;
+use emily_handler::common::error::Error;
+use emily_handler::context::EmilyContext;
+use emily_handler::database::accessors;
+use emily_handler::database::entries::deposit::{DepositEntry, DepositEntryKey, DepositEvent};
+use emily_handler::database::entries::StatusEntry;
+use emily_handler::database::storage::EmilyStorage;
+
+use crate::common::{clean_context, Backend};
+
+const COMPLETED_DEPOSIT_EVENT: &str =
+    include_str!("../../../../signer/tests/fixtures/completed-deposit-event.json");
+
+const WITHDRAWAL_CREATE_EVENT: &str =
+    include_str!("../../../../signer/tests/fixtures/withdrawal-create-event.json");
+
+const WITHDRAWAL_ACCEPT_EVENT: &str =
+    include_str!("../../../../signer/tests/fixtures/withdrawal-accept-event.json");
+
+/// The contract that emitted the events in the fixtures.
+const REGISTRY_CONTRACT_ID: &str = "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry";
+
+/// The event observer secret of the test context.
+const EVENT_OBSERVER_SECRET: &str = "observer-secret";
+
+/// Connect to the storage backend, wipe it and set up the context to apply
+/// the events of the registry contract in the fixtures.
+async fn observer_context(backend: Backend) -> EmilyContext {
+    let mut context = clean_context(backend).await;
+    context.settings.registry_contract_id = Some(REGISTRY_CONTRACT_ID.to_string());
+    context.settings.event_observer_secret = Some(EVENT_OBSERVER_SECRET.to_string());
+    context
+}
+
+/// Post a new block event to the event observer endpoint and return the
+/// status code of the response.
+async fn post_new_block(context: &EmilyContext, secret: &str, body: &str) -> u16 {
+    warp::test::request()
+        .method("POST")
+        .path(&format!("/event-observer/{secret}/new_block"))
+        .body(body.to_string())
+        .reply(&routes(context.clone()))
+        .await
+        .status()
+        .as_u16()
+}
+
+/// A block at the same height as the block of the fixture but with another
+/// block id and no events, which conflicts with the block of the fixture.
+fn conflicting_block(fixture: &str) -> String {
+    let mut block: serde_json::Value = serde_json::from_str(fixture).unwrap();
+    block["index_block_hash"] = format!("0x{}", "ab".repeat(32)).into();
+    block["events"] = serde_json::Value::Array(Vec::new());
+    block.to_string()
+}
+
+/// The key of the deposit that the completed deposit fixture completes.
+fn completed_deposit_key() -> (DepositEntryKey, u64) {
+    let block: NewBlockEvent = serde_json::from_str(COMPLETED_DEPOSIT_EVENT).unwrap();
+    let tx_event = block
+        .events
+        .into_iter()
+        .find(|tx_event| tx_event.contract_event.is_some())
+        .unwrap();
+    let tx_info = TxInfo {
+        txid: StacksTxid(tx_event.txid.0),
+        block_id: block.index_block_hash,
+    };
+    let value = tx_event.contract_event.unwrap().value;
+    let Ok(RegistryEvent::CompletedDeposit(event)) = RegistryEvent::try_new(value, tx_info) else {
+        panic!("the fixture must hold a completed deposit event");
+    };
+    let key = DepositEntryKey {
+        bitcoin_txid: event.outpoint.txid.to_string(),
+        bitcoin_tx_output_index: event.outpoint.vout,
+    };
+    (key, event.amount)
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn new_blocks_update_deposits_and_withdrawals_until_reorged(backend: Backend) {
+    let context = observer_context(backend).await;
+
+    // Arrange.
+    // --------
+    let (deposit_key, minted_amount) = completed_deposit_key();
+    let deposit = DepositEntry {
+        key: deposit_key.clone(),
+        recipient: "recipient".to_string(),
+        amount: minted_amount + 1_000,
+        status: Status::Pending,
+        last_update_height: 200,
+        last_update_block_hash: "hash-200".to_string(),
+        history: vec![DepositEvent {
+            status: StatusEntry::Pending,
+            message: "Just received deposit".to_string(),
+            stacks_block_height: 200,
+            stacks_block_hash: "hash-200".to_string(),
+        }],
+        ..Default::default()
+    };
+    context.storage.put_deposit_entry(&deposit).await.unwrap();
+
+    // Act & Assert.
+    // -------------
+    // Blocks that don't come with the secret change nothing.
+    let status = post_new_block(&context, "wrong-secret", WITHDRAWAL_CREATE_EVENT).await;
+    assert_eq!(status, 403);
+    let result = accessors::get_withdrawal_entry(&context, &1).await;
+    assert!(matches!(result, Err(Error::NotFound)));
+
+    for block in [
+        COMPLETED_DEPOSIT_EVENT,
+        WITHDRAWAL_CREATE_EVENT,
+        WITHDRAWAL_ACCEPT_EVENT,
+    ] {
+        let status = post_new_block(&context, EVENT_OBSERVER_SECRET, block).await;
+        assert_eq!(status, 200);
+    }
+
+    let deposit = accessors::get_deposit_entry(&context, &deposit_key)
+        .await
+        .unwrap();
+    let StatusEntry::Confirmed(fulfillment) = &deposit.latest_event().unwrap().status else {
+        panic!("the deposit must be confirmed, got {:?}", deposit.status);
+    };
+    assert_eq!(fulfillment.btc_fee, 1_000);
+    assert_eq!(deposit.last_update_height, 227);
+
+    let withdrawal = accessors::get_withdrawal_entry(&context, &1).await.unwrap();
+    assert_eq!(withdrawal.status, Status::Confirmed);
+    assert_eq!(withdrawal.stacks_block_height, 253);
+    assert_eq!(withdrawal.last_update_height, 301);
+
+    // A block that replaces the block of the acceptance reverts it.
+    let block = conflicting_block(WITHDRAWAL_ACCEPT_EVENT);
+    let status = post_new_block(&context, EVENT_OBSERVER_SECRET, &block).await;
+    assert_eq!(status, 200);
+
+    let withdrawal = accessors::get_withdrawal_entry(&context, &1).await.unwrap();
+    assert_eq!(withdrawal.status, Status::Pending);
+    assert_eq!(withdrawal.last_update_height, 253);
+
+    // A block that replaces the block of the request removes the withdrawal,
+    // while the deposit confirmed below it stays confirmed.
+    let block = conflicting_block(WITHDRAWAL_CREATE_EVENT);
+    let status = post_new_block(&context, EVENT_OBSERVER_SECRET, &block).await;
+    assert_eq!(status, 200);
+
+    let result = accessors::get_withdrawal_entry(&context, &1).await;
+    assert!(matches!(result, Err(Error::NotFound)));
+
+    let deposit = accessors::get_deposit_entry(&context, &deposit_key)
+        .await
+        .unwrap();
+    assert_eq!(deposit.status, Status::Confirmed);
+}
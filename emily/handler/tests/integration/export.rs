@@ -0,0 +1,140 @@
+use emily_handler::api::models::export::ExportRecord;
+use testing_emily_client::apis;
+use testing_emily_client::models::{
+    CreateWithdrawalRequestBody, ExportFormat, Fulfillment, Status, UpdateWithdrawalsRequestBody,
+    WithdrawalParameters, WithdrawalUpdate,
+};
+
+use crate::common::{clean_setup, StandardError};
+
+const RECIPIENT: &'static str = "TEST_RECIPIENT";
+const BLOCK_HASH: &'static str = "TEST_BLOCK_HASH";
+const BLOCK_HEIGHT: u64 = 0;
+const CONFIRMATION_HEIGHT: u64 = 5;
+const BTC_FEE: u64 = 10;
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn export_withdrawals_with_summary() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let amounts: Vec<(u64, u64)> = vec![(1, 1000), (2, 400), (3, 250)];
+    for &(request_id, amount) in amounts.iter() {
+        let request = CreateWithdrawalRequestBody {
+            amount,
+            parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+            recipient: RECIPIENT.into(),
+            sender: None,
+            request_id,
+            stacks_block_hash: BLOCK_HASH.into(),
+            stacks_block_height: BLOCK_HEIGHT,
+        };
+        apis::withdrawal_api::create_withdrawal(&configuration, request)
+            .await
+            .expect("Received an error after making a valid create withdrawal request api call.");
+    }
+
+    // Confirm the first two withdrawals.
+    let fulfillment = Fulfillment {
+        bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+        bitcoin_block_height: 23,
+        bitcoin_tx_index: 45,
+        bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+        btc_fee: BTC_FEE,
+        stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+    };
+    let update_request = UpdateWithdrawalsRequestBody {
+        withdrawals: [1, 2]
+            .into_iter()
+            .map(|request_id| WithdrawalUpdate {
+                request_id,
+                fulfillment: Some(Some(Box::new(fulfillment.clone()))),
+                last_update_block_hash: format!("hash_{}", CONFIRMATION_HEIGHT),
+                last_update_height: CONFIRMATION_HEIGHT,
+                status: Status::Confirmed,
+                status_message: "confirmed".into(),
+            })
+            .collect(),
+    };
+    apis::withdrawal_api::update_withdrawals(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+
+    // Act.
+    // ----
+    let jsonl = apis::export_api::export_operations(&configuration, None, None, None, None)
+        .await
+        .expect("Received an error after making a valid export api call.");
+    let csv = apis::export_api::export_operations(
+        &configuration,
+        Some(ExportFormat::Csv),
+        Some(vec![Status::Confirmed]),
+        Some(CONFIRMATION_HEIGHT),
+        Some(CONFIRMATION_HEIGHT),
+    )
+    .await
+    .expect("Received an error after making a valid export api call.");
+    let empty_range = apis::export_api::export_operations(
+        &configuration,
+        None,
+        None,
+        Some(CONFIRMATION_HEIGHT + 1),
+        Some(CONFIRMATION_HEIGHT),
+    )
+    .await
+    .map_err(StandardError::from)
+    .expect_err("Exporting an empty block range should fail.");
+
+    // Assert.
+    // -------
+    let records: Vec<ExportRecord> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("Failed to parse an export record."))
+        .collect();
+    assert_eq!(records.len(), amounts.len() + 1);
+
+    let mut request_ids: Vec<u64> = records
+        .iter()
+        .filter_map(|record| match record {
+            ExportRecord::Withdrawal(exported) => Some(exported.withdrawal.request_id),
+            _ => None,
+        })
+        .collect();
+    request_ids.sort();
+    assert_eq!(request_ids, vec![1, 2, 3]);
+
+    // Confirmed withdrawals carry their whole history.
+    let confirmed = records
+        .iter()
+        .find_map(|record| match record {
+            ExportRecord::Withdrawal(exported) if exported.withdrawal.request_id == 1 => {
+                Some(exported)
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(confirmed.history.len(), 2);
+    assert!(confirmed.history[1].fulfillment.is_some());
+
+    let ExportRecord::Summary(summary) = records.last().unwrap() else {
+        panic!("The last record of an export should be the summary.");
+    };
+    assert_eq!(summary.withdrawal_count, 3);
+    assert_eq!(summary.confirmed_withdrawal_count, 2);
+    assert_eq!(summary.confirmed_withdrawal_amount, 1400);
+    assert_eq!(summary.confirmed_withdrawal_fees, 2 * BTC_FEE);
+    assert_eq!(summary.net_confirmed_amount, -1400 - 2 * BTC_FEE as i64);
+
+    let mut sections = csv.split("\n\n");
+    let rows: Vec<&str> = sections.next().unwrap().lines().collect();
+    assert!(rows[0].starts_with("type,"));
+    assert_eq!(rows.len(), 3);
+    assert!(rows[1..].iter().all(|row| row.starts_with("withdrawal,")));
+    let summary_rows = sections.next().unwrap();
+    assert!(summary_rows.starts_with("metric,value\n"));
+    assert!(summary_rows.contains("confirmed_withdrawal_amount,1400\n"));
+
+    assert_eq!(empty_range.status_code, 400);
+}
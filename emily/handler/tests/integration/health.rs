@@ -0,0 +1,43 @@
+use testing_emily_client::apis;
+use testing_emily_client::models::Chainstate;
+
+use crate::common::clean_setup;
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn health_reports_the_chain_tip() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    for height in 1123..=1125 {
+        let chainstate = Chainstate {
+            stacks_block_hash: format!("test-hash-{height}"),
+            stacks_block_height: height,
+        };
+        apis::chainstate_api::set_chainstate(&configuration, chainstate)
+            .await
+            .expect("Received an error after making a valid set chainstate api call.");
+    }
+
+    // Act.
+    // --------
+    let health = apis::health_api::check_health(&configuration)
+        .await
+        .expect("Received an error after making a valid check health api call.");
+
+    // Assert.
+    // --------
+    assert!(health.is_okay);
+    assert!(health.database_reachable);
+    assert!(!health.is_reorganizing);
+    assert_eq!(health.chain_tip_height, Some(Some(1125)));
+    let age = health
+        .chain_tip_age_seconds
+        .flatten()
+        .expect("the chain tip age should be known after setting a chainstate");
+    assert!(
+        age < 60,
+        "the chain tip was just set but is {age} seconds old"
+    );
+}
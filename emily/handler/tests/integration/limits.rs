@@ -4,6 +4,7 @@ use testing_emily_client::apis;
 use testing_emily_client::models;
 use testing_emily_client::models::AccountLimits;
 use testing_emily_client::models::Limits;
+use testing_emily_client::models::Status;
 
 use crate::common::clean_setup;
 
@@ -17,6 +18,11 @@ async fn empty_default_is_as_expected() {
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_window_seconds: Some(None),
+        rolling_deposit_cap: Some(None),
+        rolling_withdrawal_cap: Some(None),
+        rolling_deposit_volume: Some(None),
+        rolling_withdrawal_volume: Some(None),
         account_caps: HashMap::new(),
     };
 
@@ -114,6 +120,11 @@ async fn adding_and_then_updating_single_accout_limit_works() {
         per_deposit_minimum: Some(None),
         per_deposit_cap: Some(None),
         per_withdrawal_cap: Some(None),
+        rolling_window_seconds: Some(None),
+        rolling_deposit_cap: Some(None),
+        rolling_withdrawal_cap: Some(None),
+        rolling_deposit_volume: Some(None),
+        rolling_withdrawal_volume: Some(None),
         account_caps: expected_account_caps.clone(),
     };
 
@@ -230,6 +241,11 @@ async fn test_updating_account_limits_via_global_limit_works() {
         per_deposit_minimum: Some(Some(654)),
         per_deposit_cap: Some(Some(456)),
         per_withdrawal_cap: Some(Some(789)),
+        rolling_window_seconds: Some(None),
+        rolling_deposit_cap: Some(None),
+        rolling_withdrawal_cap: Some(None),
+        rolling_deposit_volume: Some(None),
+        rolling_withdrawal_volume: Some(None),
         account_caps: account_limits_to_set_globally.clone(),
     };
 
@@ -272,6 +288,11 @@ async fn test_updating_account_limits_via_global_limit_works() {
         per_deposit_minimum: Some(Some(654)),
         per_deposit_cap: Some(Some(456)),
         per_withdrawal_cap: Some(Some(789)),
+        rolling_window_seconds: Some(None),
+        rolling_deposit_cap: Some(None),
+        rolling_withdrawal_cap: Some(None),
+        rolling_deposit_volume: Some(None),
+        rolling_withdrawal_volume: Some(None),
         account_caps: expected_global_account_limits.clone(),
     };
 
@@ -317,3 +338,80 @@ async fn test_updating_account_limits_via_global_limit_works() {
     assert_eq!(global_limits_returned_on_set, expected_global_limits);
     assert_eq!(global_limits, expected_global_limits);
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn rolling_withdrawal_volume_counts_accepted_withdrawals() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let recipient = "test_recipient";
+    let global_limits_to_set = Limits {
+        peg_cap: Some(None),
+        per_deposit_minimum: Some(None),
+        per_deposit_cap: Some(None),
+        per_withdrawal_cap: Some(None),
+        rolling_window_seconds: Some(None),
+        rolling_deposit_cap: Some(None),
+        rolling_withdrawal_cap: Some(Some(10_000)),
+        rolling_deposit_volume: Some(None),
+        rolling_withdrawal_volume: Some(None),
+        account_caps: HashMap::new(),
+    };
+
+    // Only the first two withdrawals get accepted.
+    let withdrawals = vec![
+        (1, recipient, 300, Status::Accepted),
+        (2, "other_recipient", 500, Status::Accepted),
+        (3, recipient, 700, Status::Pending),
+    ];
+
+    // Act.
+    // ----
+    apis::limits_api::set_limits(&configuration, global_limits_to_set)
+        .await
+        .expect("Failed to set global limits during test.");
+    let mut withdrawal_updates = Vec::new();
+    for (request_id, recipient, amount, status) in withdrawals {
+        let request = models::CreateWithdrawalRequestBody {
+            amount,
+            parameters: Box::new(models::WithdrawalParameters { max_fee: 123 }),
+            recipient: recipient.into(),
+            sender: None,
+            request_id,
+            stacks_block_hash: "test_block_hash".into(),
+            stacks_block_height: 0,
+        };
+        apis::withdrawal_api::create_withdrawal(&configuration, request)
+            .await
+            .expect("Failed to create withdrawal during test.");
+        if status != Status::Pending {
+            withdrawal_updates.push(models::WithdrawalUpdate {
+                request_id,
+                fulfillment: None,
+                last_update_block_hash: "update_block_hash".into(),
+                last_update_height: 1,
+                status,
+                status_message: "accepted".into(),
+            });
+        }
+    }
+    apis::withdrawal_api::update_withdrawals(
+        &configuration,
+        models::UpdateWithdrawalsRequestBody {
+            withdrawals: withdrawal_updates,
+        },
+    )
+    .await
+    .expect("Failed to update withdrawals during test.");
+
+    let global_limits = apis::limits_api::get_limits(&configuration)
+        .await
+        .expect("Failed to get limits during test.");
+
+    // Assert.
+    // -------
+    assert_eq!(global_limits.rolling_withdrawal_volume, Some(Some(800)));
+    assert_eq!(global_limits.rolling_deposit_volume, Some(None));
+}
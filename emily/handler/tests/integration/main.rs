@@ -1,3 +1,5 @@
+/// Api key authorization test module.
+pub mod auth;
 /// Chainstate test module.
 pub mod chainstate;
 /// Common test utilities.
@@ -6,7 +8,17 @@ pub mod common;
 pub mod config;
 /// Deposit test module.
 pub mod deposit;
+/// Event observer test module.
+pub mod event_observer;
+/// Export test module.
+pub mod export;
+/// Health test module.
+pub mod health;
 /// Limit test module.
 pub mod limits;
+/// Storage backend test module.
+pub mod storage;
+/// Subscription test module.
+pub mod subscription;
 /// Withdrawal test module.
 pub mod withdrawal;
@@ -0,0 +1,357 @@
+//! Tests that run the same storage operations against both storage backends
+//! so that they behave the same way.
+
+use test_case::test_case;
+
+use emily_handler::api::models::common::Status;
+use emily_handler::api::models::subscription::{StatusEvent, StatusEventFilter};
+use emily_handler::common::error::Error;
+use emily_handler::database::accessors;
+use emily_handler::database::entries::chainstate::{
+    ApiStateEntry, ApiStatus, ChainstateEntry, ChainstateEntryKey,
+};
+use emily_handler::database::entries::deposit::{
+    DepositEntry, DepositEntryKey, DepositEvent, DepositUpdatePackage,
+};
+use emily_handler::database::entries::webhook::{event_filter_keys, WebhookEntry, WebhookEntryKey};
+use emily_handler::database::entries::StatusEntry;
+use emily_handler::database::storage::{EmilyStorage, Store};
+
+use crate::common::{clean_context, Backend};
+
+/// Connect to the storage backend and wipe it.
+async fn clean_store(backend: Backend) -> Store {
+    clean_context(backend).await.storage
+}
+
+/// A pending deposit entry with an initial history event.
+fn pending_deposit(bitcoin_txid: &str, output_index: u32, height: u64) -> DepositEntry {
+    DepositEntry {
+        key: DepositEntryKey {
+            bitcoin_txid: bitcoin_txid.to_string(),
+            bitcoin_tx_output_index: output_index,
+        },
+        recipient: "recipient".to_string(),
+        sender: Some("sender".to_string()),
+        amount: 100_000,
+        status: Status::Pending,
+        last_update_height: height,
+        last_update_block_hash: format!("hash-{height}"),
+        history: vec![DepositEvent {
+            status: StatusEntry::Pending,
+            message: "Just received deposit".to_string(),
+            stacks_block_height: height,
+            stacks_block_hash: format!("hash-{height}"),
+        }],
+        created_at: Some(1_000),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn deposit_entries_round_trip(backend: Backend) {
+    let store = clean_store(backend).await;
+    let entry = pending_deposit("txid", 1, 10);
+
+    store.put_deposit_entry(&entry).await.unwrap();
+
+    let stored = store.get_deposit_entry(&entry.key).await.unwrap();
+    assert_eq!(stored, entry);
+
+    let missing = DepositEntryKey {
+        bitcoin_txid: "txid".to_string(),
+        bitcoin_tx_output_index: 2,
+    };
+    let result = store.get_deposit_entry(&missing).await;
+    assert!(matches!(result, Err(Error::NotFound)));
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn versioned_deposit_puts_conflict_on_stale_versions(backend: Backend) {
+    let store = clean_store(backend).await;
+    let entry = pending_deposit("txid", 1, 10);
+
+    // There is nothing to compare the version with yet.
+    let result = store
+        .put_deposit_entry_with_version(&mut entry.clone())
+        .await;
+    assert!(matches!(result, Err(Error::VersionConflict)));
+
+    store.put_deposit_entry(&entry).await.unwrap();
+
+    let mut updated = entry.clone();
+    updated.amount = 200_000;
+    store
+        .put_deposit_entry_with_version(&mut updated)
+        .await
+        .unwrap();
+    assert_eq!(updated.version, entry.version + 1);
+
+    // The original entry now has a stale version.
+    let result = store
+        .put_deposit_entry_with_version(&mut entry.clone())
+        .await;
+    assert!(matches!(result, Err(Error::VersionConflict)));
+
+    let stored = store.get_deposit_entry(&entry.key).await.unwrap();
+    assert_eq!(stored, updated);
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn deposit_status_queries_page_through_every_entry(backend: Backend) {
+    let store = clean_store(backend).await;
+    for height in 0..5 {
+        let entry = pending_deposit(&format!("txid-{height}"), 0, height);
+        store.put_deposit_entry(&entry).await.unwrap();
+    }
+
+    let mut heights = Vec::new();
+    let mut next_token = None;
+    loop {
+        let (page, token) = store
+            .query_deposit_entries_by_status(&Status::Pending, next_token, Some(2))
+            .await
+            .unwrap();
+        assert!(page.len() <= 2);
+        heights.extend(page.iter().map(|entry| entry.key.last_update_height));
+        next_token = token;
+        if next_token.is_none() {
+            break;
+        }
+    }
+    // Both backends return the entries with the latest updates first.
+    assert_eq!(heights, vec![4, 3, 2, 1, 0]);
+
+    let (page, _) = store
+        .query_deposit_entries_modified_from_height(&Status::Pending, 3, None, None)
+        .await
+        .unwrap();
+    let heights: Vec<u64> = page
+        .iter()
+        .map(|entry| entry.key.last_update_height)
+        .collect();
+    assert_eq!(heights, vec![4, 3]);
+
+    let (page, _) = store
+        .query_deposit_entries_by_status(&Status::Accepted, None, None)
+        .await
+        .unwrap();
+    assert!(page.is_empty());
+
+    let count = store
+        .count_deposit_entries_by_status(&Status::Pending)
+        .await
+        .unwrap();
+    assert_eq!(count, 5);
+    let count = store
+        .count_deposit_entries_by_status(&Status::Accepted)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn deposit_updates_append_to_the_history(backend: Backend) {
+    let store = clean_store(backend).await;
+    let entry = pending_deposit("txid", 1, 10);
+    store.put_deposit_entry(&entry).await.unwrap();
+
+    let event = DepositEvent {
+        status: StatusEntry::Accepted,
+        message: "Accepted".to_string(),
+        stacks_block_height: 11,
+        stacks_block_hash: "hash-11".to_string(),
+    };
+    let update = DepositUpdatePackage {
+        key: entry.key.clone(),
+        version: entry.version,
+        event: event.clone(),
+        timestamp: 2_000,
+    };
+    let updated = store.update_deposit(&update).await.unwrap();
+
+    assert_eq!(updated.status, Status::Accepted);
+    assert_eq!(updated.last_update_height, 11);
+    assert_eq!(updated.accepted_at, Some(2_000));
+    assert_eq!(updated.history.last(), Some(&event));
+    assert_eq!(updated.version, entry.version + 1);
+    assert_eq!(store.get_deposit_entry(&entry.key).await.unwrap(), updated);
+
+    // Applying the same update again uses a stale version.
+    let result = store.update_deposit(&update).await;
+    assert!(matches!(result, Err(Error::VersionConflict)));
+
+    let (page, _) = store
+        .query_deposit_entries_accepted_since(&Status::Accepted, 1_000, None, None)
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].key.accepted_at, 2_000);
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn chainstate_and_api_state_round_trip(backend: Backend) {
+    let store = clean_store(backend).await;
+    let chainstate = |hash: &str| ChainstateEntry {
+        key: ChainstateEntryKey {
+            hash: hash.to_string(),
+            height: 7,
+        },
+    };
+    store.put_chainstate_entry(&chainstate("a")).await.unwrap();
+    store.put_chainstate_entry(&chainstate("b")).await.unwrap();
+
+    let (page, _) = store
+        .query_chainstate_entries_by_height(7, None, None)
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 2);
+
+    store
+        .delete_chainstate_entry(&chainstate("a").key)
+        .await
+        .unwrap();
+    let (page, _) = store
+        .query_chainstate_entries_by_height(7, None, None)
+        .await
+        .unwrap();
+    assert_eq!(page, vec![chainstate("b")]);
+
+    let mut api_state = ApiStateEntry::default();
+    api_state.api_status = ApiStatus::Reorg(chainstate("b"));
+    api_state.updated_at = Some(3_000);
+    store.put_api_state_entry(&api_state).await.unwrap();
+    assert_eq!(store.get_api_state_entry().await.unwrap(), api_state);
+
+    let mut stale = api_state.clone();
+    store
+        .put_api_state_entry_with_version(&mut api_state)
+        .await
+        .unwrap();
+    let result = store.put_api_state_entry_with_version(&mut stale).await;
+    assert!(matches!(result, Err(Error::VersionConflict)));
+    assert_eq!(store.get_api_state_entry().await.unwrap(), api_state);
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn api_state_keeps_the_time_its_chain_tip_changed(backend: Backend) {
+    let context = clean_context(backend).await;
+    let chainstate = |height: u64| ChainstateEntry {
+        key: ChainstateEntryKey {
+            hash: format!("hash-{height}"),
+            height,
+        },
+    };
+
+    // A status flip that keeps the tip keeps the time the tip was set.
+    let mut api_state = accessors::get_api_state(&context).await.unwrap();
+    api_state.api_status = ApiStatus::Stable(chainstate(7));
+    api_state.updated_at = Some(1_000);
+    context
+        .storage
+        .put_api_state_entry(&api_state)
+        .await
+        .unwrap();
+
+    let mut api_state = accessors::get_api_state(&context).await.unwrap();
+    api_state.api_status = ApiStatus::Reorg(chainstate(7));
+    accessors::set_api_state(&context, &api_state)
+        .await
+        .unwrap();
+
+    let stored = accessors::get_api_state(&context).await.unwrap();
+    assert_eq!(stored.api_status, ApiStatus::Reorg(chainstate(7)));
+    assert_eq!(stored.updated_at, Some(1_000));
+
+    // A new tip records when it was set.
+    let mut api_state = stored;
+    api_state.api_status = ApiStatus::Stable(chainstate(8));
+    accessors::set_api_state(&context, &api_state)
+        .await
+        .unwrap();
+
+    let stored = accessors::get_api_state(&context).await.unwrap();
+    assert!(stored.updated_at.unwrap() > 1_000);
+}
+
+#[tokio::test]
+#[test_case(Backend::DynamoDb; "dynamodb")]
+#[test_case(Backend::Postgres; "postgres")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn webhooks_are_found_by_their_filter_key(backend: Backend) {
+    let store = clean_store(backend).await;
+    let webhook = |webhook_id: &str, filter: StatusEventFilter| {
+        let key = WebhookEntryKey {
+            webhook_id: webhook_id.to_string(),
+            created_at: 1_000,
+        };
+        let url = format!("https://example.com/{webhook_id}");
+        WebhookEntry::new(key, "owner".to_string(), url, "secret".to_string(), filter)
+    };
+    let by_txid = webhook(
+        "by-txid",
+        StatusEventFilter {
+            bitcoin_txid: Some("txid".to_string()),
+            ..Default::default()
+        },
+    );
+    let by_recipient = webhook(
+        "by-recipient",
+        StatusEventFilter {
+            recipient: Some("recipient".to_string()),
+            status: Some(Status::Confirmed),
+            ..Default::default()
+        },
+    );
+    let by_other_txid = webhook(
+        "by-other-txid",
+        StatusEventFilter {
+            bitcoin_txid: Some("other".to_string()),
+            ..Default::default()
+        },
+    );
+    let unfiltered = webhook("unfiltered", StatusEventFilter::default());
+    for entry in [&by_txid, &by_recipient, &by_other_txid, &unfiltered] {
+        store.put_webhook_entry(entry).await.unwrap();
+    }
+
+    let event = StatusEvent {
+        bitcoin_txid: Some("txid".to_string()),
+        bitcoin_tx_output_index: Some(0),
+        recipient: "recipient".to_string(),
+        status: Status::Accepted,
+        ..Default::default()
+    };
+    let mut found = Vec::new();
+    for filter_key in event_filter_keys(&event) {
+        let (page, next_token) = store
+            .query_webhook_entries_by_filter_key(&filter_key, None, None)
+            .await
+            .unwrap();
+        assert_eq!(next_token, None);
+        found.extend(page.into_iter().map(WebhookEntry::from));
+    }
+    found.sort_by(|a, b| a.key.webhook_id.cmp(&b.key.webhook_id));
+
+    // The recipient webhook is read from the index but its status doesn't
+    // match, which is left to the caller.
+    assert_eq!(found, vec![by_recipient, by_txid, unfiltered]);
+}
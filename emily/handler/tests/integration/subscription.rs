@@ -0,0 +1,209 @@
+use std::time::Duration;
+
+use test_case::test_case;
+use testing_emily_client::apis;
+use testing_emily_client::apis::configuration::Configuration;
+use testing_emily_client::models::Status;
+use testing_emily_client::models::{
+    Chainstate, CreateDepositRequestBody, CreateWebhookRequestBody, DepositUpdate, StatusEvent,
+    UpdateDepositsRequestBody,
+};
+
+use emily_handler::api::models::api_key::ApiKeyScope;
+
+use crate::common::{clean_setup, store_api_key, with_api_key, StandardError};
+
+/// How long to wait for a status event before failing the test.
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An open server-sent event stream of status events.
+struct EventStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventStream {
+    /// Open an event stream of the events for the given deposit transaction.
+    async fn open(configuration: &Configuration, bitcoin_txid: &str) -> Self {
+        let response = reqwest::Client::new()
+            .get(format!("{}/subscription/events", configuration.base_path))
+            .query(&[("bitcoinTxid", bitcoin_txid)])
+            .send()
+            .await
+            .expect("Failed to open the event stream.")
+            .error_for_status()
+            .expect("Failed to open the event stream.");
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Wait for the next status event, skipping keep-alive comments.
+    async fn next(&mut self) -> StatusEvent {
+        tokio::time::timeout(EVENT_TIMEOUT, async {
+            loop {
+                while let Some(end) = self.buffer.find("\n\n") {
+                    let message: String = self.buffer.drain(..end + 2).collect();
+                    let data: String = message
+                        .lines()
+                        .filter_map(|line| line.strip_prefix("data:"))
+                        .map(str::trim)
+                        .collect();
+                    if !data.is_empty() {
+                        return serde_json::from_str(&data).expect("Invalid status event.");
+                    }
+                }
+                let chunk = self
+                    .response
+                    .chunk()
+                    .await
+                    .expect("Failed to read the event stream.")
+                    .expect("The event stream closed.");
+                self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+            }
+        })
+        .await
+        .expect("Timed out waiting for a status event.")
+    }
+}
+
+/// Make a test chainstate.
+fn new_test_chainstate(height: u64, fork_id: i32) -> Chainstate {
+    Chainstate {
+        stacks_block_hash: format!("test-hash-{height}-fork-{fork_id}"),
+        stacks_block_height: height,
+    }
+}
+
+#[tokio::test]
+#[test_case("http://127.0.0.1:8080/hook"; "loopback")]
+#[test_case("http://169.254.169.254/latest/meta-data"; "link-local")]
+#[test_case("http://10.0.0.1/hook"; "private")]
+#[test_case("http://[::1]/hook"; "ipv6 loopback")]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn webhooks_at_private_addresses_are_rejected(url: &str) {
+    let configuration = clean_setup().await;
+
+    let request = CreateWebhookRequestBody::new("secret".to_string(), url.to_string());
+    let response = apis::subscription_api::create_webhook(&configuration, request)
+        .await
+        .expect_err("Registered a webhook at a private address.");
+    let error: StandardError = response.into();
+    assert_eq!(error.status_code, 400);
+
+    let webhooks = apis::subscription_api::get_webhooks(&configuration)
+        .await
+        .expect("Received an error after making a valid get webhooks api call.");
+    assert!(webhooks.webhooks.is_empty());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn webhooks_are_only_visible_to_the_key_that_registered_them() {
+    let configuration = clean_setup().await;
+    let key_a = store_api_key("subscriber-a", vec![ApiKeyScope::ReadOnly], false).await;
+    let key_b = store_api_key("subscriber-b", vec![ApiKeyScope::ReadOnly], false).await;
+    let configuration_a = with_api_key(&configuration, key_a);
+    let configuration_b = with_api_key(&configuration, key_b);
+
+    let request = CreateWebhookRequestBody::new("secret".to_string(), "http://1.1.1.1/hook".into());
+    let webhook_b = apis::subscription_api::create_webhook(&configuration_b, request)
+        .await
+        .expect("Received an error after making a valid create webhook api call.");
+
+    // Key A neither sees nor deletes the webhook of key B.
+    let webhooks_a = apis::subscription_api::get_webhooks(&configuration_a)
+        .await
+        .expect("Received an error after making a valid get webhooks api call.");
+    assert!(webhooks_a.webhooks.is_empty());
+
+    let error: StandardError =
+        apis::subscription_api::delete_webhook(&configuration_a, &webhook_b.id)
+            .await
+            .expect_err("Deleted the webhook of another api key.")
+            .into();
+    assert_eq!(error.status_code, 404);
+
+    // The webhook is still there for key B, which can delete it.
+    let webhooks_b = apis::subscription_api::get_webhooks(&configuration_b)
+        .await
+        .expect("Received an error after making a valid get webhooks api call.");
+    assert_eq!(webhooks_b.webhooks, vec![webhook_b.clone()]);
+
+    apis::subscription_api::delete_webhook(&configuration_b, &webhook_b.id)
+        .await
+        .expect("Received an error after making a valid delete webhook api call.");
+    let webhooks_b = apis::subscription_api::get_webhooks(&configuration_b)
+        .await
+        .expect("Received an error after making a valid get webhooks api call.");
+    assert!(webhooks_b.webhooks.is_empty());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+async fn deposit_status_changes_and_reorgs_are_streamed() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid = "bitcoin_txid_streamed";
+    let setup = sbtc::testing::deposits::tx_setup(12345, 30, 0);
+    let create_request = CreateDepositRequestBody {
+        bitcoin_txid: bitcoin_txid.into(),
+        bitcoin_tx_output_index: 0,
+        reclaim_script: setup.reclaim.reclaim_script().to_hex_string(),
+        deposit_script: setup.deposit.deposit_script().to_hex_string(),
+    };
+    let accepted_chainstate = new_test_chainstate(5, 0);
+    let update_request = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_txid: bitcoin_txid.into(),
+            bitcoin_tx_output_index: 0,
+            fulfillment: None,
+            last_update_block_hash: accepted_chainstate.stacks_block_hash.clone(),
+            last_update_height: accepted_chainstate.stacks_block_height,
+            status: Status::Accepted,
+            status_message: "accepted".into(),
+        }],
+    };
+
+    // Act.
+    // ----
+    apis::deposit_api::create_deposit(&configuration, create_request)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    for height in 1..=5 {
+        apis::chainstate_api::set_chainstate(&configuration, new_test_chainstate(height, 0))
+            .await
+            .expect("Received an error after making a valid set chainstate api call.");
+    }
+
+    let mut stream = EventStream::open(&configuration, bitcoin_txid).await;
+
+    apis::deposit_api::update_deposits(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+    let accepted = stream.next().await;
+
+    // A conflicting block below the acceptance reorganizes the API around
+    // it, which reverts the deposit to its earlier status.
+    apis::chainstate_api::set_chainstate(&configuration, new_test_chainstate(3, 1))
+        .await
+        .expect("Received an error after making a valid set chainstate api call.");
+    let reverted = stream.next().await;
+
+    // Assert.
+    // -------
+    assert_eq!(accepted.bitcoin_txid, Some(Some(bitcoin_txid.to_string())));
+    assert_eq!(accepted.status, Status::Accepted);
+    assert_eq!(accepted.stacks_block_height, 5);
+    assert_eq!(
+        accepted.stacks_block_hash,
+        accepted_chainstate.stacks_block_hash
+    );
+
+    assert_eq!(reverted.bitcoin_txid, Some(Some(bitcoin_txid.to_string())));
+    assert_eq!(reverted.status, Status::Pending);
+    assert!(reverted.stacks_block_height < 3);
+}
@@ -68,6 +68,7 @@ async fn create_and_get_withdrawal_happy_path() {
         amount,
         parameters: Box::new(parameters.clone()),
         recipient: RECIPIENT.into(),
+        sender: None,
         request_id,
         stacks_block_hash: BLOCK_HASH.into(),
         stacks_block_height: BLOCK_HEIGHT,
@@ -80,6 +81,7 @@ async fn create_and_get_withdrawal_happy_path() {
         last_update_height: BLOCK_HEIGHT,
         parameters: Box::new(parameters.clone()),
         recipient: RECIPIENT.into(),
+        sender: None,
         request_id,
         stacks_block_hash: BLOCK_HASH.into(),
         stacks_block_height: BLOCK_HEIGHT,
@@ -122,6 +124,7 @@ async fn get_withdrawals() {
             amount,
             parameters: Box::new(parameters.clone()),
             recipient: RECIPIENT.into(),
+            sender: None,
             request_id,
             stacks_block_hash: BLOCK_HASH.into(),
             stacks_block_height: BLOCK_HEIGHT,
@@ -133,6 +136,7 @@ async fn get_withdrawals() {
             last_update_block_hash: BLOCK_HASH.into(),
             last_update_height: BLOCK_HEIGHT,
             recipient: RECIPIENT.into(),
+            sender: None,
             request_id,
             stacks_block_hash: BLOCK_HASH.into(),
             stacks_block_height: BLOCK_HEIGHT,
@@ -157,7 +161,13 @@ async fn get_withdrawals() {
     loop {
         let response = apis::withdrawal_api::get_withdrawals(
             &configuration,
-            status,
+            Some(vec![status]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
             next_token.as_ref().and_then(|o| o.as_deref()),
             Some(chunksize as i32),
         )
@@ -191,6 +201,196 @@ async fn get_withdrawals() {
     assert_eq!(expected_withdrawal_infos, gotten_withdrawal_infos);
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn search_withdrawals() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let parameters = WithdrawalParameters { max_fee: 123 };
+    let senders = ["sender_a", "sender_b"];
+    let recipients = ["recipient_a", "recipient_b"];
+
+    let mut create_requests: Vec<CreateWithdrawalRequestBody> = Vec::new();
+    for request_id in 1..=8u64 {
+        create_requests.push(CreateWithdrawalRequestBody {
+            amount: 0,
+            parameters: Box::new(parameters.clone()),
+            recipient: recipients[(request_id % 2) as usize].into(),
+            sender: Some(Some(senders[(request_id / 5) as usize].into())),
+            request_id,
+            stacks_block_hash: BLOCK_HASH.into(),
+            stacks_block_height: request_id,
+        });
+    }
+
+    // Accept a couple of them so that they move to a different status.
+    let update_request = UpdateWithdrawalsRequestBody {
+        withdrawals: [1, 2]
+            .into_iter()
+            .map(|request_id| WithdrawalUpdate {
+                request_id,
+                fulfillment: None,
+                last_update_block_hash: format!("hash_{}", request_id),
+                last_update_height: request_id,
+                status: Status::Accepted,
+                status_message: "accepted".into(),
+            })
+            .collect(),
+    };
+
+    // Act.
+    // ----
+    batch_create_withdrawals(&configuration, create_requests).await;
+    apis::withdrawal_api::update_withdrawals(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+
+    let search = |status: Option<Vec<Status>>,
+                  recipient: Option<&'static str>,
+                  sender: Option<&'static str>,
+                  min_height: Option<u64>| {
+        let configuration = configuration.clone();
+        async move {
+            let response = apis::withdrawal_api::get_withdrawals(
+                &configuration,
+                status,
+                recipient,
+                sender,
+                min_height,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .expect("Received an error after making a valid get withdrawals api call.");
+            let mut request_ids: Vec<u64> = response
+                .withdrawals
+                .into_iter()
+                .map(|withdrawal| withdrawal.request_id)
+                .collect();
+            request_ids.sort();
+            request_ids
+        }
+    };
+
+    // Assert.
+    // -------
+    assert_eq!(
+        search(None, None, Some("sender_a"), None).await,
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(
+        search(None, None, Some("sender_b"), None).await,
+        vec![5, 6, 7, 8]
+    );
+    assert_eq!(
+        search(None, Some("recipient_a"), None, None).await,
+        vec![2, 4, 6, 8]
+    );
+    assert_eq!(
+        search(None, Some("recipient_b"), Some("sender_b"), None).await,
+        vec![5, 7]
+    );
+    assert_eq!(
+        search(Some(vec![Status::Accepted]), None, None, None).await,
+        vec![1, 2]
+    );
+    assert_eq!(
+        search(
+            Some(vec![Status::Pending, Status::Accepted]),
+            None,
+            None,
+            Some(4)
+        )
+        .await,
+        vec![4, 5, 6, 7, 8]
+    );
+    assert_eq!(
+        search(Some(vec![Status::Accepted]), None, Some("sender_b"), None).await,
+        Vec::<u64>::new()
+    );
+
+    // Searching without any status, recipient or sender is rejected.
+    let result = apis::withdrawal_api::get_withdrawals(
+        &configuration,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn sparse_withdrawal_searches_return_partial_pages() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let parameters = WithdrawalParameters { max_fee: 123 };
+    let create_requests: Vec<CreateWithdrawalRequestBody> = (1..=12u64)
+        .map(|request_id| CreateWithdrawalRequestBody {
+            amount: 0,
+            parameters: Box::new(parameters.clone()),
+            recipient: RECIPIENT.into(),
+            sender: Some(Some("sender_a".into())),
+            request_id,
+            stacks_block_hash: BLOCK_HASH.into(),
+            stacks_block_height: request_id,
+        })
+        .collect();
+
+    // Act.
+    // ----
+    batch_create_withdrawals(&configuration, create_requests).await;
+
+    // None of the withdrawals are this high, so every one of them is read
+    // and filtered out.
+    let mut pages: Vec<(usize, bool)> = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let response = apis::withdrawal_api::get_withdrawals(
+            &configuration,
+            None,
+            None,
+            Some("sender_a"),
+            Some(100),
+            None,
+            None,
+            None,
+            next_token.as_deref(),
+            Some(1),
+        )
+        .await
+        .expect("Received an error after making a valid get withdrawals api call.");
+        next_token = response.next_token.flatten();
+        pages.push((response.withdrawals.len(), next_token.is_some()));
+        if next_token.is_none() || pages.len() > 12 {
+            break;
+        }
+    }
+
+    // Assert.
+    // -------
+    // Reading one withdrawal at a time, the first request stops before
+    // it has read them all and hands back a next token with an empty page.
+    assert_eq!(pages.first(), Some(&(0, true)));
+    assert!(pages.len() > 1);
+    assert!(pages.len() <= 12);
+    assert!(pages.iter().all(|(items, _)| *items == 0));
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn update_withdrawals() {
@@ -228,6 +428,7 @@ async fn update_withdrawals() {
             amount,
             parameters: Box::new(parameters.clone()),
             recipient: RECIPIENT.into(),
+            sender: None,
             request_id,
             stacks_block_hash: BLOCK_HASH.into(),
             stacks_block_height: BLOCK_HEIGHT,
@@ -251,6 +452,7 @@ async fn update_withdrawals() {
             last_update_height: update_block_height.clone(),
             parameters: Box::new(parameters.clone()),
             recipient: RECIPIENT.into(),
+            sender: None,
             request_id,
             stacks_block_hash: BLOCK_HASH.into(),
             stacks_block_height: BLOCK_HEIGHT,
@@ -295,6 +497,7 @@ async fn update_withdrawals_updates_chainstate() {
         amount,
         parameters: Box::new(parameters.clone()),
         recipient: RECIPIENT.into(),
+        sender: None,
         request_id,
         stacks_block_hash: BLOCK_HASH.into(),
         stacks_block_height: BLOCK_HEIGHT,
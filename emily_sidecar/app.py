@@ -22,9 +22,14 @@ class NewBlockEventSchema(Schema):
 # Get the API key from environment variables
 api_key = os.getenv("EMILY_API_KEY", "default_api_key")
 url = os.getenv("EMILY_CHAINSTATE_URL",  "http://host.docker.internal:3031/chainstate")
+# When set, whole blocks are forwarded to the Emily event observer endpoint
+# instead of only their chainstate. The URL includes the event observer
+# secret, as in http://emily-server:3031/event-observer/<secret>/new_block.
+new_block_url = os.getenv("EMILY_NEW_BLOCK_URL")
 deployer_address = os.getenv("DEPLOYER_ADDRESS", "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS")
 
 logger.info("Using chainstate URL: %s", url)
+logger.info("Using new block URL: %s", new_block_url)
 logger.info("Using deployer address: %s", deployer_address)
 
 headers = {
@@ -60,6 +65,18 @@ def handle_new_block():
         logger.error("Failed to validate new block event: %s", e)
         return jsonify(str(e)), 400
 
+    if new_block_url:
+        try:
+            resp = requests.post(new_block_url, headers=headers, json=data)
+            resp.raise_for_status()
+        except requests.RequestException as e:
+            logger.error("Failed to send new block %s to %s: %s", validated_data["block_height"], new_block_url, e)
+            # lets return an error so that the node will retry
+            return jsonify({"error": "Failed to send new block"}), 500
+
+        logger.info("Successfully forwarded new block %s", validated_data["block_height"])
+        return jsonify({}), 200
+
     chainstate = {
         "stacksBlockHeight": validated_data["block_height"],
         "stacksBlockHash": validated_data["index_block_hash"].removeprefix("0x"),
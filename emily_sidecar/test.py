@@ -87,6 +87,22 @@ class NewBlockTestCase(unittest.TestCase):
         self.assertEqual(mock_post.call_count, 1)
         mock_post.assert_called_with(chainstate_url, headers=headers, json=chainstate)
 
+    @patch('requests.post')
+    def test_new_block_forwards_whole_block(self, mock_post):
+        mock_response = MagicMock()
+        mock_response.status_code = 200
+        mock_post.return_value = mock_response
+
+        new_block_url = "http://emily-server:3031/event-observer/secret/new_block"
+        fixture = FIXTURES["withdrawal_create"]
+        with patch('app.new_block_url', new_block_url):
+            response = self.app.post('/new_block', json=fixture)
+        self.assertEqual(response.status_code, 200)
+        self.assertEqual({}, response.get_json())
+
+        self.assertEqual(mock_post.call_count, 1)
+        mock_post.assert_called_with(new_block_url, headers=headers, json=fixture)
+
 
 class AttachmentsTestCase(unittest.TestCase):
     def setUp(self):
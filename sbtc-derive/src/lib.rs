@@ -0,0 +1,182 @@
+#![deny(missing_docs)]
+
+//! # sBTC derive macros
+//!
+//! This crate holds the procedural macros used by the `sbtc` crate. It
+//! should not be used directly, the macros are re-exported from the
+//! modules of `sbtc` that define the traits they implement.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse_macro_input;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Field;
+use syn::Fields;
+use syn::LitStr;
+use syn::Path;
+
+/// Derive `TryFrom<sbtc::events::RawTupleData>` and
+/// `TryFrom<(ClarityValue, sbtc::events::TxInfo)>` for a struct with named
+/// fields, with `sbtc::events::EventError` as the error type.
+///
+/// The conversions take the transaction that emitted the tuple along with
+/// it, since fields can be taken from that transaction. A `ClarityValue`
+/// that is not a tuple is refused with
+/// `sbtc::events::EventError::ClarityUnexpectedValue`.
+///
+/// Each field is read from the tuple entry with the field's name, where
+/// underscores are replaced with dashes, and converted with
+/// `sbtc::events::FromClarityValue`. The following field attributes
+/// change that:
+///
+/// * `#[clarity(rename = "name")]`: read the field from the tuple entry
+///   with the given name.
+/// * `#[clarity(txid)]` and `#[clarity(block_id)]`: take the field from
+///   the transaction that emitted the tuple instead of from the tuple.
+/// * `#[clarity(little_endian)]`: read a `(buff 32)` in little-endian
+///   byte order and convert it with `sbtc::events::FromLittleEndianOrder`.
+/// * `#[clarity(recipient)]`: read a `{ version: (buff 1), hashbytes:
+///   (buff 32) }` tuple and convert it into a `bitcoin::ScriptBuf`.
+/// * `#[clarity(with = "path::to::function")]`: decode the field with a
+///   function taking `&mut sbtc::events::RawTupleData`, for fields that
+///   are made from more than one tuple entry.
+#[proc_macro_derive(FromClarityTuple, attributes(clarity))]
+pub fn derive_from_clarity_tuple(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_clarity_tuple(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How the value of a field is obtained.
+enum FieldKind {
+    /// Converted from the tuple entry with `FromClarityValue`.
+    Value,
+    /// The ID of the transaction that emitted the tuple.
+    Txid,
+    /// The ID of the block with the transaction that emitted the tuple.
+    BlockId,
+    /// A 32 byte buffer in little-endian byte order.
+    LittleEndian,
+    /// A bitcoin recipient tuple.
+    Recipient,
+    /// Decoded by the given function.
+    With(Path),
+}
+
+/// The parsed `#[clarity(...)]` attributes of a field.
+struct FieldAttrs {
+    rename: Option<LitStr>,
+    kind: FieldKind,
+}
+
+fn parse_field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut rename = None;
+    let mut kind = FieldKind::Value;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("clarity"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let new_kind = if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<LitStr>()?);
+                return Ok(());
+            } else if meta.path.is_ident("txid") {
+                FieldKind::Txid
+            } else if meta.path.is_ident("block_id") {
+                FieldKind::BlockId
+            } else if meta.path.is_ident("little_endian") {
+                FieldKind::LittleEndian
+            } else if meta.path.is_ident("recipient") {
+                FieldKind::Recipient
+            } else if meta.path.is_ident("with") {
+                let path = meta.value()?.parse::<LitStr>()?;
+                FieldKind::With(path.parse()?)
+            } else {
+                return Err(meta.error("unsupported clarity attribute"));
+            };
+
+            if !matches!(kind, FieldKind::Value) {
+                return Err(meta.error("a field can only have one clarity conversion"));
+            }
+            kind = new_kind;
+            Ok(())
+        })?;
+    }
+
+    Ok(FieldAttrs { rename, kind })
+}
+
+fn expand_from_clarity_tuple(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "FromClarityTuple can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "FromClarityTuple can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut initializers = Vec::with_capacity(fields.named.len());
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let attrs = parse_field_attrs(field)?;
+        let name = attrs
+            .rename
+            .map(|name| name.value())
+            .unwrap_or_else(|| ident.to_string().replace('_', "-"));
+
+        let value = match attrs.kind {
+            FieldKind::Value => quote! { tuple.remove(#name)? },
+            FieldKind::Txid => quote! { tuple.tx_info().txid },
+            FieldKind::BlockId => quote! { tuple.tx_info().block_id },
+            FieldKind::LittleEndian => quote! { tuple.remove_le_bytes(#name)? },
+            FieldKind::Recipient => quote! { tuple.remove_recipient(#name)? },
+            FieldKind::With(path) => quote! { #path(&mut tuple)? },
+        };
+        initializers.push(quote! { #ident: #value });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::TryFrom<::sbtc::events::RawTupleData>
+            for #ident #ty_generics #where_clause
+        {
+            type Error = ::sbtc::events::EventError;
+
+            #[allow(unused_mut)]
+            fn try_from(
+                mut tuple: ::sbtc::events::RawTupleData,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                ::core::result::Result::Ok(Self {
+                    #(#initializers,)*
+                })
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<(
+            ::sbtc::events::ClarityValue,
+            ::sbtc::events::TxInfo,
+        )> for #ident #ty_generics #where_clause
+        {
+            type Error = ::sbtc::events::EventError;
+
+            fn try_from(
+                (value, tx_info): (::sbtc::events::ClarityValue, ::sbtc::events::TxInfo),
+            ) -> ::core::result::Result<Self, Self::Error> {
+                let tuple = ::sbtc::events::RawTupleData::try_new(value, tx_info)?;
+                ::core::convert::TryFrom::try_from(tuple)
+            }
+        }
+    })
+}
@@ -2,7 +2,7 @@
 //! contract.
 //!
 //! Print events from the sBTC registry come in as [`TupleData`] clarity
-//! values. Each tuple has a topic field that takes one of four values. We
+//! values. Each tuple has a topic field that takes one of six values. We
 //! deconstruct the tuple data based on the topic.
 //!
 //! This module attempts to use only types that are found in the
@@ -25,12 +25,14 @@ use clarity::vm::types::PrincipalData;
 use clarity::vm::types::SequenceData;
 use clarity::vm::types::TupleData;
 use clarity::vm::ClarityName;
-use clarity::vm::Value as ClarityValue;
 use secp256k1::PublicKey;
 use stacks_common::types::chainstate::StacksBlockId;
 
 use std::fmt::Display;
 
+pub use clarity::vm::Value as ClarityValue;
+pub use sbtc_derive::FromClarityTuple;
+
 /// Stacks transaction identifier. Wrapper over a 32 byte array.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct StacksTxid(pub [u8; 32]);
@@ -99,6 +101,10 @@ pub enum EventError {
     /// block hash field is not exactly equal to 32. This should never occur.
     #[error("unexpected improper hash byte length, received {0} bytes")]
     ClarityHashByteLength(usize),
+    /// This happens when the protocol contract type in an
+    /// `update-protocol-contract` event is not exactly one byte long.
+    #[error("unexpected protocol contract type byte length, received {0} bytes")]
+    ClarityContractTypeByteLength(usize),
     /// This error is thrown when trying to convert a public key from a
     /// Clarity buffer into a proper public key. It should never be thrown.
     #[error("Could not convert a public key in clarity event into the expected public key {0}")]
@@ -114,8 +120,10 @@ pub enum EventError {
     /// maximum length.
     #[error("tried to create an invalid witness program {0}")]
     InvalidWitnessProgram(#[source] bitcoin::witness_program::Error),
-    /// This a programmer error bug that should never be thrown.
-    #[error("The field {0} was missing from the print event for topic; {1}")]
+    /// This a programmer error bug that should never be thrown. It
+    /// happens when a field is missing from a print event or its value
+    /// has an unexpected type.
+    #[error("The field {0} was missing from the print event or had an unexpected type; {1}")]
     TupleEventField(&'static str, TxInfo),
     /// This should never happen, we check the version in the smart
     /// contract.
@@ -136,6 +144,8 @@ pub enum RegistryEvent {
     WithdrawalCreate(WithdrawalCreateEvent),
     /// For the `key-rotation` topic
     KeyRotation(KeyRotationEvent),
+    /// For the `update-protocol-contract` topic
+    UpdateProtocolContract(UpdateProtocolContractEvent),
 }
 
 /// A type that points to a transaction in a stacks block.
@@ -157,59 +167,108 @@ impl RegistryEvent {
     /// Transform the [`ClarityValue`] from the sbtc-registry event into a
     /// proper type.
     pub fn try_new(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
-        match value {
-            ClarityValue::Tuple(TupleData { data_map, .. }) => {
-                let mut event_map = RawTupleData::new(data_map, tx_info);
-                // Lucky for us, each sBTC print event in the sbtc-registry
-                // smart contract has a topic. We use that to match on what
-                // to expect when decomposing the event from a
-                // [`ClarityValue`] into a proper type.
-                let topic = event_map.remove_string("topic")?;
-
-                match topic.as_str() {
-                    "completed-deposit" => event_map.completed_deposit(),
-                    "withdrawal-accept" => event_map.withdrawal_accept(),
-                    "withdrawal-create" => event_map.withdrawal_create(),
-                    "withdrawal-reject" => event_map.withdrawal_reject(),
-                    "key-rotation" => event_map.key_rotation(),
-                    _ => Err(EventError::ClarityUnexpectedEventTopic(topic)),
-                }
+        let mut event_map = RawTupleData::try_new(value, tx_info)?;
+        // Lucky for us, each sBTC print event in the sbtc-registry
+        // smart contract has a topic. We use that to match on what
+        // to expect when decomposing the event from a
+        // [`ClarityValue`] into a proper type.
+        let topic: String = event_map.remove("topic")?;
+
+        match topic.as_str() {
+            "completed-deposit" => {
+                CompletedDepositEvent::try_from(event_map).map(RegistryEvent::CompletedDeposit)
             }
-            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
+            "withdrawal-accept" => {
+                WithdrawalAcceptEvent::try_from(event_map).map(RegistryEvent::WithdrawalAccept)
+            }
+            "withdrawal-create" => {
+                WithdrawalCreateEvent::try_from(event_map).map(RegistryEvent::WithdrawalCreate)
+            }
+            "withdrawal-reject" => {
+                WithdrawalRejectEvent::try_from(event_map).map(RegistryEvent::WithdrawalReject)
+            }
+            "key-rotation" => KeyRotationEvent::try_from(event_map).map(RegistryEvent::KeyRotation),
+            "update-protocol-contract" => UpdateProtocolContractEvent::try_from(event_map)
+                .map(RegistryEvent::UpdateProtocolContract),
+            _ => Err(EventError::ClarityUnexpectedEventTopic(topic)),
         }
     }
 }
 
-/// This is the event that is emitted from the `create-withdrawal-request`
+/// This is the event that is emitted from the `complete-deposit`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// The print events for complete-deposit calls are structured like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "completed-deposit",
+///   bitcoin-txid: (buff 32),
+///   output-index: uint,
+///   amount: uint
+///   burn-hash: (buff 32),
+///   burn-height: uint,
+///   sweep-txid: (buff 32),
+/// })
+/// ```
+///
+/// The above event is emitted after the indicated amount of sBTC has
+/// been emitted to the recipient.
+#[derive(Debug, Clone, FromClarityTuple)]
 pub struct CompletedDepositEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
+    #[clarity(txid)]
     pub txid: StacksTxid,
     /// The block ID of the block for this event.
+    #[clarity(block_id)]
     pub block_id: StacksBlockId,
     /// This is the amount of sBTC to mint to the intended recipient.
     pub amount: u64,
     /// This is the outpoint of the original bitcoin deposit transaction.
+    #[clarity(with = "bitcoin_outpoint")]
     pub outpoint: OutPoint,
     /// The bitcoin block hash where the sweep transaction was included.
+    #[clarity(rename = "burn-hash", little_endian)]
     pub sweep_block_hash: BitcoinBlockHash,
     /// The bitcoin block height where the sweep transaction was included.
+    #[clarity(rename = "burn-height")]
     pub sweep_block_height: u64,
     /// The transaction id of the bitcoin transaction that fulfilled the
     /// deposit.
+    #[clarity(little_endian)]
     pub sweep_txid: BitcoinTxid,
 }
 
 /// This is the event that is emitted from the `create-withdrawal-request`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// The print events for `create-withdrawal-request` calls are structured
+/// like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "withdrawal-create",
+///   amount: uint,
+///   request-id: uint,
+///   sender: principal,
+///   recipient: { version: (buff 1), hashbytes: (buff 32) },
+///   block-height: uint,
+///   max-fee: uint,
+/// })
+/// ```
+#[derive(Debug, Clone, FromClarityTuple)]
 pub struct WithdrawalCreateEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
+    #[clarity(txid)]
     pub txid: StacksTxid,
     /// The block ID of the block for this event.
+    #[clarity(block_id)]
     pub block_id: StacksBlockId,
     /// This is the unique identifier of the withdrawal request.
     pub request_id: u64,
@@ -220,6 +279,7 @@ pub struct WithdrawalCreateEvent {
     pub sender: PrincipalData,
     /// This is the address to send the BTC to when fulfilling the
     /// withdrawal request.
+    #[clarity(recipient)]
     pub recipient: ScriptBuf,
     /// This is the maximum amount of BTC "spent" to the miners for the
     /// transaction fee.
@@ -231,12 +291,33 @@ pub struct WithdrawalCreateEvent {
 
 /// This is the event that is emitted from the `complete-withdrawal-accept`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// The print events for `complete-withdrawal-accept` calls are
+/// structured like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "withdrawal-accept",
+///   request-id: uint,
+///   bitcoin-txid: (buff 32),
+///   signer-bitmap: uint,
+///   output-index: uint,
+///   fee: uint,
+///   burn-hash: (buff 32),
+///   burn-height: uint,
+///   sweep-txid: (buff 32),
+/// })
+/// ```
+#[derive(Debug, Clone, FromClarityTuple)]
 pub struct WithdrawalAcceptEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
+    #[clarity(txid)]
     pub txid: StacksTxid,
     /// The block ID of the block for this event.
+    #[clarity(block_id)]
     pub block_id: StacksBlockId,
     /// This is the unique identifier of the withdrawal request.
     pub request_id: u64,
@@ -246,27 +327,49 @@ pub struct WithdrawalAcceptEvent {
     pub signer_bitmap: u128,
     /// This is the outpoint for the bitcoin transaction that serviced the
     /// request.
+    #[clarity(with = "bitcoin_outpoint")]
     pub outpoint: OutPoint,
     /// This is the fee that was spent to the bitcoin miners to confirm the
     /// withdrawal request.
     pub fee: u64,
     /// The bitcoin block hash where the sweep transaction was included.
+    #[clarity(rename = "burn-hash", little_endian)]
     pub sweep_block_hash: BitcoinBlockHash,
     /// The bitcoin block height where the sweep transaction was included.
+    #[clarity(rename = "burn-height")]
     pub sweep_block_height: u64,
     /// The transaction id of the bitcoin transaction that fulfilled the
     /// withdrawal request.
+    #[clarity(little_endian)]
     pub sweep_txid: BitcoinTxid,
 }
 
 /// This is the event that is emitted from the `complete-withdrawal-reject`
 /// public function in sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// The print events for `complete-withdrawal-reject` calls are structured
+/// like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "withdrawal-reject",
+///   request-id: uint,
+///   signer-bitmap: uint,
+/// })
+/// ```
+///
+/// The above event is emitted after the locked sBTC has been unlocked back
+/// to the account that initiated the request.
+#[derive(Debug, Clone, FromClarityTuple)]
 pub struct WithdrawalRejectEvent {
     /// The transaction id of the stacks transaction that generated this
     /// event.
+    #[clarity(txid)]
     pub txid: StacksTxid,
     /// The block ID of the block for this event.
+    #[clarity(block_id)]
     pub block_id: StacksBlockId,
     /// This is the unique identifier of user created the withdrawal
     /// request.
@@ -279,7 +382,24 @@ pub struct WithdrawalRejectEvent {
 
 /// This is the event that is emitted from the `rotate-keys`
 /// public function in the sbtc-registry smart contract.
-#[derive(Debug, Clone)]
+///
+/// # Notes
+///
+/// The print events for `rotate-keys` calls are structured like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "key-rotation",
+///   new-keys: (list 128 (buff 33))
+///   new-address: principal
+///   new-aggregate-pubkey: (buff 33)
+///   new-signature-threshold: uint
+/// })
+/// ```
+///
+/// The above event is emitted after the keys for the multi-sig wallet
+/// have been rotated.
+#[derive(Debug, Clone, FromClarityTuple)]
 pub struct KeyRotationEvent {
     /// The new set of public keys for all known signers during this
     /// PoX cycle.
@@ -292,159 +412,222 @@ pub struct KeyRotationEvent {
     pub new_signature_threshold: u16,
 }
 
-#[derive(Debug)]
-struct RawTupleData {
-    data_map: BTreeMap<ClarityName, ClarityValue>,
-    tx_info: TxInfo,
+/// This is the event that is emitted from the `update-protocol-contract`
+/// public function in the sbtc-registry smart contract.
+///
+/// # Notes
+///
+/// The print events for `update-protocol-contract` calls are
+/// structured like so:
+///
+/// ```clarity
+/// (print {
+///   topic: "update-protocol-contract",
+///   contract-type: (buff 1),
+///   new-contract: principal,
+/// })
+/// ```
+#[derive(Debug, Clone, FromClarityTuple)]
+pub struct UpdateProtocolContractEvent {
+    /// The transaction id of the stacks transaction that generated this
+    /// event.
+    #[clarity(txid)]
+    pub txid: StacksTxid,
+    /// The block ID of the block for this event.
+    #[clarity(block_id)]
+    pub block_id: StacksBlockId,
+    /// The role flag of the protocol contract that was updated. The
+    /// sbtc-registry uses 0x00 for governance, 0x01 for deposits and 0x02
+    /// for withdrawals.
+    #[clarity(with = "protocol_contract_type")]
+    pub contract_type: u8,
+    /// The principal of the contract that is now active for the role.
+    pub new_contract: PrincipalData,
 }
 
-impl RawTupleData {
-    fn new(data_map: BTreeMap<ClarityName, ClarityValue>, tx_info: TxInfo) -> Self {
-        Self { data_map, tx_info }
+/// Decode the outpoint of a bitcoin transaction from the `bitcoin-txid`
+/// and `output-index` fields of a print event.
+fn bitcoin_outpoint(tuple: &mut RawTupleData) -> Result<OutPoint, EventError> {
+    Ok(OutPoint {
+        // This shouldn't error, this is set from a proper [`Txid`] in a
+        // contract call.
+        txid: tuple.remove_le_bytes("bitcoin-txid")?,
+        // This shouldn't actually error, we cast u32s to u128s before
+        // making the contract call, and that is the value that gets
+        // emitted here.
+        vout: tuple.remove("output-index")?,
+    })
+}
+
+/// Decode the one byte `contract-type` field of an
+/// `update-protocol-contract` print event.
+fn protocol_contract_type(tuple: &mut RawTupleData) -> Result<u8, EventError> {
+    let contract_type = tuple.remove_buff("contract-type")?;
+    match contract_type[..] {
+        [contract_type] => Ok(contract_type),
+        _ => Err(EventError::ClarityContractTypeByteLength(
+            contract_type.len(),
+        )),
     }
-    /// Extract the u128 value from the given field
-    fn remove_u128(&mut self, field: &'static str) -> Result<u128, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::UInt(val)) => Ok(val),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+}
+
+/// A type that can be decoded from a single Clarity value in a print
+/// event.
+pub trait FromClarityValue: Sized {
+    /// Decode the type from a Clarity value that was emitted by the
+    /// transaction described by `tx_info`.
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError>;
+}
+
+impl FromClarityValue for u128 {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::UInt(val) => Ok(val),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
-    /// Extract the buff value from the given field
-    fn remove_buff(&mut self, field: &'static str) -> Result<Vec<u8>, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::Buffer(buf))) => Ok(buf.data),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+}
+
+/// Implement [`FromClarityValue`] for integer types that the contracts
+/// emit as a `uint`. The values were either set from the smaller integer
+/// type by the signers or are bounded by the contracts, so the conversion
+/// shouldn't error.
+macro_rules! impl_from_clarity_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl FromClarityValue for $ty {
+                fn from_clarity_value(
+                    value: ClarityValue,
+                    tx_info: TxInfo,
+                ) -> Result<Self, EventError> {
+                    let val = u128::from_clarity_value(value, tx_info)?;
+                    <$ty>::try_from(val).map_err(EventError::ClarityIntConversion)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_clarity_uint!(u64, u32, u16);
+
+impl FromClarityValue for String {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Sequence(SequenceData::String(CharType::ASCII(ascii))) => {
+                String::from_utf8(ascii.data).map_err(EventError::ClarityStringConversion)
+            }
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
-    /// Extract the principal value from the given field
-    fn remove_principal(&mut self, field: &'static str) -> Result<PrincipalData, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Principal(principal)) => Ok(principal),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+}
+
+impl FromClarityValue for PrincipalData {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Principal(principal) => Ok(principal),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
-    /// Extract the string value from the given field
-    fn remove_string(&mut self, field: &'static str) -> Result<String, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::String(CharType::ASCII(ascii)))) => {
-                String::from_utf8(ascii.data).map_err(EventError::ClarityStringConversion)
-            }
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+}
+
+impl FromClarityValue for [u8; 32] {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Sequence(SequenceData::Buffer(buf)) => <[u8; 32]>::try_from(buf.data)
+                .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len())),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
-    /// Extract the tuple value from the given field
-    fn remove_tuple(&mut self, field: &'static str) -> Result<Self, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Tuple(TupleData { data_map, .. })) => {
-                Ok(Self::new(data_map, self.tx_info))
+}
+
+impl FromClarityValue for PublicKey {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Sequence(SequenceData::Buffer(buf)) => {
+                PublicKey::from_slice(&buf.data).map_err(EventError::ClarityPublicKeyConversion)
             }
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
+}
 
-    /// Extract the list value from the given field
-    fn remove_list(&mut self, field: &'static str) -> Result<Vec<ClarityValue>, EventError> {
-        match self.data_map.remove(field) {
-            Some(ClarityValue::Sequence(SequenceData::List(list))) => Ok(list.data),
-            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+impl<T: FromClarityValue> FromClarityValue for Vec<T> {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Sequence(SequenceData::List(list)) => list
+                .data
+                .into_iter()
+                .map(|val| T::from_clarity_value(val, tx_info))
+                .collect(),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
         }
     }
+}
 
-    /// This function is for transforming the print events of the
-    /// complete-deposit function in the sbtc-registry.
-    ///
-    /// # Notes
-    ///
-    /// The print events for complete-deposit calls are structured like so:
-    ///
-    /// ```clarity
-    /// (print {
-    ///   topic: "completed-deposit",
-    ///   bitcoin-txid: (buff 32),
-    ///   output-index: uint,
-    ///   amount: uint
-    ///   burn-hash: (buff 32),
-    ///   burn-height: uint,
-    ///   sweep-txid: (buff 32),
-    /// })
-    /// ```
-    ///
-    /// The above event is emitted after the indicated amount of sBTC has
-    /// been emitted to the recipient.
-    fn completed_deposit(mut self) -> Result<RegistryEvent, EventError> {
-        let amount = self.remove_u128("amount")?;
-        let vout = self.remove_u128("output-index")?;
-        let txid_bytes = <[u8; 32]>::try_from(self.remove_buff("bitcoin-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_txid = <[u8; 32]>::try_from(self.remove_buff("sweep-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_hash = <[u8; 32]>::try_from(self.remove_buff("burn-hash")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_height = self.remove_u128("burn-height")?;
-
-        Ok(RegistryEvent::CompletedDeposit(CompletedDepositEvent {
-            txid: self.tx_info.txid,
-            block_id: self.tx_info.block_id,
-            // This shouldn't error, since this amount is set from the u64
-            // amount of sats by us.
-            amount: u64::try_from(amount).map_err(EventError::ClarityIntConversion)?,
-            outpoint: OutPoint {
-                // This shouldn't error, this is set from a proper [`Txid`]
-                // in a contract call.
-                txid: BitcoinTxid::from_le_bytes(txid_bytes),
-                // This shouldn't actually error, we cast u32s to u128s
-                // before making the contract call, and that is the value
-                // that gets emitted here.
-                vout: u32::try_from(vout).map_err(EventError::ClarityIntConversion)?,
-            },
-            sweep_block_hash: BitcoinBlockHash::from_le_bytes(sweep_block_hash),
-            sweep_block_height: u64::try_from(sweep_block_height)
-                .map_err(EventError::ClarityIntConversion)?,
-            sweep_txid: BitcoinTxid::from_le_bytes(sweep_txid),
-        }))
+impl FromClarityValue for RawTupleData {
+    fn from_clarity_value(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        RawTupleData::try_new(value, tx_info)
     }
+}
 
-    /// This function is for transforming the print events of the
-    /// `complete-withdrawal-accept` function in the sbtc-registry.
-    ///
-    /// # Notes
-    ///
-    /// The print events for `create-withdrawal-request` calls are structured
-    /// like so:
-    ///
-    /// ```clarity
-    /// (print {
-    ///   topic: "withdrawal-create",
-    ///   amount: uint,
-    ///   request-id: uint,
-    ///   sender: principal,
-    ///   recipient: { version: (buff 1), hashbytes: (buff 32) },
-    ///   block-height: uint,
-    ///   max-fee: uint,
-    /// })
-    /// ```
-    fn withdrawal_create(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
-        let amount = self.remove_u128("amount")?;
-        let max_fee = self.remove_u128("max-fee")?;
-        let block_height = self.remove_u128("block-height")?;
-        let sender = self.remove_principal("sender")?;
-        let recipient = self.remove_tuple("recipient")?;
-
-        Ok(RegistryEvent::WithdrawalCreate(WithdrawalCreateEvent {
-            txid: self.tx_info.txid,
-            block_id: self.tx_info.block_id,
-            // This shouldn't error, practically speaking. Each withdrawal
-            // request increments the integer by one, so we'd have to do many
-            // orders of magnitude more requests than there are bitcoin
-            // transactions, ever.
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
-            amount: u64::try_from(amount).map_err(EventError::ClarityIntConversion)?,
-            max_fee: u64::try_from(max_fee).map_err(EventError::ClarityIntConversion)?,
-            block_height: u64::try_from(block_height).map_err(EventError::ClarityIntConversion)?,
-            recipient: recipient.try_into_script_pub_key()?,
-            sender,
-        }))
+/// The fields of a Clarity tuple that was emitted in a print event, along
+/// with the transaction that emitted it. Fields are removed from the
+/// tuple as they are decoded.
+#[derive(Debug)]
+pub struct RawTupleData {
+    data_map: BTreeMap<ClarityName, ClarityValue>,
+    tx_info: TxInfo,
+}
+
+impl RawTupleData {
+    fn new(data_map: BTreeMap<ClarityName, ClarityValue>, tx_info: TxInfo) -> Self {
+        Self { data_map, tx_info }
+    }
+    /// Create a new one from a Clarity value, which must be a tuple.
+    pub fn try_new(value: ClarityValue, tx_info: TxInfo) -> Result<Self, EventError> {
+        match value {
+            ClarityValue::Tuple(TupleData { data_map, .. }) => Ok(Self::new(data_map, tx_info)),
+            value => Err(EventError::ClarityUnexpectedValue(value, tx_info)),
+        }
+    }
+    /// The transaction that emitted the tuple.
+    pub fn tx_info(&self) -> TxInfo {
+        self.tx_info
+    }
+    /// Remove the given field and decode its value. A value of the wrong
+    /// type is reported as an error with the field.
+    pub fn remove<T: FromClarityValue>(&mut self, field: &'static str) -> Result<T, EventError> {
+        let Some(value) = self.data_map.remove(field) else {
+            return Err(EventError::TupleEventField(field, self.tx_info));
+        };
+        T::from_clarity_value(value, self.tx_info).map_err(|error| match error {
+            EventError::ClarityUnexpectedValue(..) => {
+                EventError::TupleEventField(field, self.tx_info)
+            }
+            error => error,
+        })
+    }
+    /// Remove the given field and decode its value from a 32 byte buffer
+    /// in little-endian byte order.
+    pub fn remove_le_bytes<T>(&mut self, field: &'static str) -> Result<T, EventError>
+    where
+        T: FromLittleEndianOrder,
+    {
+        self.remove(field).map(T::from_le_bytes)
+    }
+    /// Remove the given field and decode its value, a recipient tuple of
+    /// the form `{ version: (buff 1), hashbytes: (buff 32) }`, into a
+    /// bitcoin `scriptPubKey`.
+    pub fn remove_recipient(&mut self, field: &'static str) -> Result<ScriptBuf, EventError> {
+        self.remove::<RawTupleData>(field)?
+            .try_into_script_pub_key()
+    }
+    /// Extract the buff value from the given field
+    fn remove_buff(&mut self, field: &'static str) -> Result<Vec<u8>, EventError> {
+        match self.data_map.remove(field) {
+            Some(ClarityValue::Sequence(SequenceData::Buffer(buf))) => Ok(buf.data),
+            _ => Err(EventError::TupleEventField(field, self.tx_info)),
+        }
     }
 
     /// This function takes in a recipient as a Clarity Value and returns a
@@ -596,146 +779,6 @@ impl RawTupleData {
             _ => Err(EventError::UnhandledRecipient(version, hash_bytes_buf)),
         }
     }
-
-    /// This function is for transforming the print events of the
-    /// `complete-withdrawal-accept` function in the sbtc-registry.
-    ///
-    /// # Notes
-    ///
-    /// The print events for `complete-withdrawal-accept` calls are
-    /// structured like so:
-    ///
-    /// ```clarity
-    /// (print {
-    ///   topic: "withdrawal-accept",
-    ///   request-id: uint,
-    ///   bitcoin-txid: (buff 32),
-    ///   signer-bitmap: uint,
-    ///   bitcoin-index: uint,
-    ///   fee: uint,
-    ///   burn-hash: (buff 32),
-    ///   burn-height: uint,
-    ///   sweep-txid: (buff 32),
-    /// })
-    /// ```
-    fn withdrawal_accept(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
-        let bitmap = self.remove_u128("signer-bitmap")?;
-        let fee = self.remove_u128("fee")?;
-        let vout = self.remove_u128("output-index")?;
-        let txid_bytes = <[u8; 32]>::try_from(self.remove_buff("bitcoin-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_txid = <[u8; 32]>::try_from(self.remove_buff("sweep-txid")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_hash = <[u8; 32]>::try_from(self.remove_buff("burn-hash")?)
-            .map_err(|bytes| EventError::ClarityHashByteLength(bytes.len()))?;
-        let sweep_block_height = self.remove_u128("burn-height")?;
-
-        Ok(RegistryEvent::WithdrawalAccept(WithdrawalAcceptEvent {
-            txid: self.tx_info.txid,
-            block_id: self.tx_info.block_id,
-            // This shouldn't error for the reasons noted in
-            // [`withdrawal_create`].
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
-            signer_bitmap: bitmap,
-            outpoint: OutPoint {
-                // This shouldn't error, this is set from a proper [`Txid`] in
-                // a contract call.
-                txid: BitcoinTxid::from_le_bytes(txid_bytes),
-                // This shouldn't actually error, we cast u32s to u128s before
-                // making the contract call, and that is the value that gets
-                // emitted here.
-                vout: u32::try_from(vout).map_err(EventError::ClarityIntConversion)?,
-            },
-            // This shouldn't error, since this amount is set from the u64
-            // amount of sats by us.
-            fee: u64::try_from(fee).map_err(EventError::ClarityIntConversion)?,
-
-            sweep_block_hash: BitcoinBlockHash::from_le_bytes(sweep_block_hash),
-
-            sweep_block_height: u64::try_from(sweep_block_height)
-                .map_err(EventError::ClarityIntConversion)?,
-
-            sweep_txid: BitcoinTxid::from_le_bytes(sweep_txid),
-        }))
-    }
-
-    /// This function is for transforming the print events of the
-    /// `complete-withdrawal-reject` function in the sbtc-registry.
-    ///
-    /// # Notes
-    ///
-    /// The print events for `complete-withdrawal-reject` calls are structured
-    /// like so:
-    ///
-    /// ```clarity
-    /// (print {
-    ///   topic: "withdrawal-reject",
-    ///   request-id: uint,
-    ///   signer-bitmap: uint,
-    /// })
-    /// ```
-    ///
-    /// The above event is emitted after the locked sBTC has been unlocked back
-    /// to the account that initiated the request.
-    fn withdrawal_reject(mut self) -> Result<RegistryEvent, EventError> {
-        let request_id = self.remove_u128("request-id")?;
-        let bitmap = self.remove_u128("signer-bitmap")?;
-
-        Ok(RegistryEvent::WithdrawalReject(WithdrawalRejectEvent {
-            txid: self.tx_info.txid,
-            block_id: self.tx_info.block_id,
-            // This shouldn't error for the reasons noted in
-            // [`withdrawal_create`].
-            request_id: u64::try_from(request_id).map_err(EventError::ClarityIntConversion)?,
-            signer_bitmap: bitmap,
-        }))
-    }
-
-    /// This function is for transforming the print events of the
-    /// `rotate-keys` function in the sbtc-registry.
-    ///
-    /// # Notes
-    ///
-    /// The print events for `rotate-keys` calls are structured like so:
-    ///
-    /// ```clarity
-    /// (print {
-    ///   topic: "key-rotation",
-    ///   new-keys: (list 128 (buff 33))
-    ///   new-address: principal
-    ///   new-aggregate-pubkey: (buff 33)
-    ///   new-signature-threshold: uint
-    /// })
-    /// ```
-    ///
-    /// The above event is emitted after the keys for the multi-sig wallet
-    /// have been rotated.
-    fn key_rotation(mut self) -> Result<RegistryEvent, EventError> {
-        let new_keys = self
-            .remove_list("new-keys")?
-            .into_iter()
-            .map(|val| match val {
-                ClarityValue::Sequence(SequenceData::Buffer(buf)) => {
-                    PublicKey::from_slice(&buf.data).map_err(EventError::ClarityPublicKeyConversion)
-                }
-                _ => Err(EventError::ClarityUnexpectedValue(val, self.tx_info)),
-            })
-            .collect::<Result<Vec<PublicKey>, EventError>>()?;
-
-        let new_address = self.remove_principal("new-address")?;
-        let new_aggregate_pubkey = self.remove_buff("new-aggregate-pubkey")?;
-        let new_signature_threshold = self.remove_u128("new-signature-threshold")?;
-
-        Ok(RegistryEvent::KeyRotation(KeyRotationEvent {
-            new_keys,
-            new_address,
-            new_aggregate_pubkey: PublicKey::from_slice(&new_aggregate_pubkey)
-                .map_err(EventError::ClarityPublicKeyConversion)?,
-            new_signature_threshold: u16::try_from(new_signature_threshold)
-                .map_err(EventError::ClarityIntConversion)?,
-        }))
-    }
 }
 
 #[cfg(test)]
@@ -1017,6 +1060,40 @@ mod tests {
         };
     }
 
+    #[test]
+    fn update_protocol_contract_event() {
+        let new_contract =
+            PrincipalData::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.sbtc-deposit-v2")
+                .unwrap();
+        let event = [
+            (
+                ClarityName::from("contract-type"),
+                ClarityValue::buff_from(vec![0x01]).unwrap(),
+            ),
+            (
+                ClarityName::from("new-contract"),
+                ClarityValue::Principal(new_contract.clone()),
+            ),
+            (
+                ClarityName::from("topic"),
+                ClarityValue::string_ascii_from_bytes(
+                    "update-protocol-contract".as_bytes().to_vec(),
+                )
+                .unwrap(),
+            ),
+        ];
+        let tuple_data = TupleData::from_data(event.to_vec()).unwrap();
+        let value = ClarityValue::Tuple(tuple_data);
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::UpdateProtocolContract(event) => {
+                assert_eq!(event.contract_type, 0x01);
+                assert_eq!(event.new_contract, new_contract);
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
     // Just a random public key to make the test case definitions below a
     // little tidier.
     static PUBLIC_KEY: LazyLock<CompressedPublicKey> = LazyLock::new(|| {
@@ -1120,4 +1197,261 @@ mod tests {
         let res = map.try_into_script_pub_key();
         assert!(res.is_err());
     }
+
+    // The following values are print events emitted by the sbtc-registry
+    // contract, hex encoded in their consensus serialization, as the
+    // stacks node sent them to the event observer.
+    const COMPLETED_DEPOSIT_HEX: &str = "0c0000000706616d6f756e7401000000000000000000000000075ed2850c626974636f696e2d7478696402000000200000000000000000000000000000000000000000000000000000000000000000096275726e2d68617368020000002001010101010101010101010101010101010101010101010101010101010101010b6275726e2d686569676874010000000000000000000000000000002a0c6f75747075742d696e64657801000000000000000000000000ffffffff0a73776565702d747869640200000020020202020202020202020202020202020202020202020202020202020202020205746f7069630d00000011636f6d706c657465642d6465706f736974";
+    const WITHDRAWAL_ACCEPT_HEX: &str = "0c000000090c626974636f696e2d7478696402000000200000000000000000000000000000000000000000000000000000000000000000096275726e2d68617368020000002001010101010101010101010101010101010101010101010101010101010101010b6275726e2d686569676874010000000000000000000000000000002a0366656501000000000000000000000000000009c40c6f75747075742d696e64657801000000000000000000000000ffffffff0a726571756573742d696401000000000000000000000000000000010d7369676e65722d6269746d617001000000000000000000000000000000000a73776565702d747869640200000020020202020202020202020202020202020202020202020202020202020202020205746f7069630d000000117769746864726177616c2d616363657074";
+    const WITHDRAWAL_CREATE_HEX: &str = "0c0000000706616d6f756e7401000000000000000000000000000057e40c626c6f636b2d6865696768740100000000000000000000000000000089076d61782d6665650100000000000000000000000000000bb809726563697069656e740c0000000209686173686279746573020000001400000000000000000000000000000000000000000776657273696f6e0200000001000a726571756573742d696401000000000000000000000000000000010673656e6465720515b67e6a475c7001d2d1f8589527f8357f0c13944405746f7069630d000000117769746864726177616c2d637265617465";
+    const WITHDRAWAL_REJECT_HEX: &str = "0c000000030a726571756573742d696401000000000000000000000000000000020d7369676e65722d6269746d6170010000000000000000000000000000000005746f7069630d000000117769746864726177616c2d72656a656374";
+    const KEY_ROTATION_HEX: &str = "0c000000050b6e65772d616464726573730515f08277fe51877c890918bb778e0192309b307c39146e65772d6167677265676174652d7075626b6579020000002102e77bbc2ff7d6a0bb53d0a736a1a95032c6251b9e54a2187d7fc9405ce330e943086e65772d6b6579730b00000003020000002102007311430123d4cad97f4f7e86e023b28143130a18099ecf094d36fef0f6135c0200000021031a4d9f4903da97498945a4e01a5023a1d53bc96ad670bfe03adf8a06c52e63800200000021035249137286c077ccee65ecc43e724b9b9e5a588e3d7f51e3b62f9624c2a49e46176e65772d7369676e61747572652d7468726573686f6c64010000000000000000000000000000000205746f7069630d0000000c6b65792d726f746174696f6e";
+
+    #[test]
+    fn completed_deposit_event_emitted_by_contract() {
+        let value = ClarityValue::try_deserialize_hex_untyped(COMPLETED_DEPOSIT_HEX).unwrap();
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::CompletedDeposit(event) => {
+                assert_eq!(event.amount, 123654789);
+                assert_eq!(event.outpoint, OutPoint::null());
+                let sweep_block_hash = BitcoinBlockHash::from_byte_array([1; 32]);
+                assert_eq!(event.sweep_block_hash, sweep_block_hash);
+                assert_eq!(event.sweep_block_height, 42);
+                assert_eq!(event.sweep_txid, BitcoinTxid::from_byte_array([2; 32]));
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
+    #[test]
+    fn withdrawal_accept_event_emitted_by_contract() {
+        let value = ClarityValue::try_deserialize_hex_untyped(WITHDRAWAL_ACCEPT_HEX).unwrap();
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::WithdrawalAccept(event) => {
+                assert_eq!(event.request_id, 1);
+                assert_eq!(event.signer_bitmap, 0);
+                assert_eq!(event.outpoint, OutPoint::null());
+                assert_eq!(event.fee, 2500);
+                let sweep_block_hash = BitcoinBlockHash::from_byte_array([1; 32]);
+                assert_eq!(event.sweep_block_hash, sweep_block_hash);
+                assert_eq!(event.sweep_block_height, 42);
+                assert_eq!(event.sweep_txid, BitcoinTxid::from_byte_array([2; 32]));
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
+    #[test]
+    fn withdrawal_create_event_emitted_by_contract() {
+        let value = ClarityValue::try_deserialize_hex_untyped(WITHDRAWAL_CREATE_HEX).unwrap();
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::WithdrawalCreate(event) => {
+                assert_eq!(event.amount, 22500);
+                assert_eq!(event.request_id, 1);
+                assert_eq!(event.block_height, 137);
+                assert_eq!(event.max_fee, 3000);
+                assert!(matches!(event.sender, PrincipalData::Standard(_)));
+                let recipient = ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([0; 20]));
+                assert_eq!(event.recipient, recipient);
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
+    #[test]
+    fn withdrawal_reject_event_emitted_by_contract() {
+        let value = ClarityValue::try_deserialize_hex_untyped(WITHDRAWAL_REJECT_HEX).unwrap();
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::WithdrawalReject(event) => {
+                assert_eq!(event.request_id, 2);
+                assert_eq!(event.signer_bitmap, 0);
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
+    #[test]
+    fn key_rotation_event_emitted_by_contract() {
+        let value = ClarityValue::try_deserialize_hex_untyped(KEY_ROTATION_HEX).unwrap();
+
+        match RegistryEvent::try_new(value, TX_INFO).unwrap() {
+            RegistryEvent::KeyRotation(event) => {
+                let key = |hex: &str| hex.parse::<PublicKey>().unwrap();
+                let new_keys = [
+                    key("02007311430123d4cad97f4f7e86e023b28143130a18099ecf094d36fef0f6135c"),
+                    key("031a4d9f4903da97498945a4e01a5023a1d53bc96ad670bfe03adf8a06c52e6380"),
+                    key("035249137286c077ccee65ecc43e724b9b9e5a588e3d7f51e3b62f9624c2a49e46"),
+                ];
+                assert_eq!(event.new_keys, new_keys);
+                assert_eq!(
+                    event.new_aggregate_pubkey,
+                    key("02e77bbc2ff7d6a0bb53d0a736a1a95032c6251b9e54a2187d7fc9405ce330e943"),
+                );
+                assert_eq!(event.new_signature_threshold, 2);
+                assert!(matches!(event.new_address, PrincipalData::Standard(_)));
+            }
+            e => panic!("Got the wrong event variant: {e:?}"),
+        };
+    }
+
+    /// A struct that uses each of the field attributes of the derive.
+    #[derive(Debug, PartialEq, FromClarityTuple)]
+    struct TestEvent {
+        #[clarity(txid)]
+        txid: StacksTxid,
+        #[clarity(block_id)]
+        block_id: StacksBlockId,
+        #[clarity(rename = "burn-hash", little_endian)]
+        block_hash: BitcoinBlockHash,
+        #[clarity(with = "bitcoin_outpoint")]
+        outpoint: OutPoint,
+        #[clarity(recipient)]
+        recipient: ScriptBuf,
+        sender: PrincipalData,
+        signer_keys: Vec<PublicKey>,
+        amount: u64,
+    }
+
+    impl TestEvent {
+        /// The clarity tuple that the struct is decoded from.
+        fn to_clarity_value(&self) -> ClarityValue {
+            let mut block_hash = self.block_hash.to_byte_array();
+            block_hash.reverse();
+            let mut txid = self.outpoint.txid.to_byte_array();
+            txid.reverse();
+            let recipient = [
+                (
+                    ClarityName::from("version"),
+                    ClarityValue::buff_from_byte(0x04),
+                ),
+                (
+                    ClarityName::from("hashbytes"),
+                    ClarityValue::buff_from(self.recipient.as_bytes()[2..].to_vec()).unwrap(),
+                ),
+            ];
+            let signer_keys = self
+                .signer_keys
+                .iter()
+                .map(|key| ClarityValue::buff_from(key.serialize().to_vec()).unwrap())
+                .collect();
+
+            let fields = [
+                (
+                    ClarityName::from("burn-hash"),
+                    ClarityValue::buff_from(block_hash.to_vec()).unwrap(),
+                ),
+                (
+                    ClarityName::from("bitcoin-txid"),
+                    ClarityValue::buff_from(txid.to_vec()).unwrap(),
+                ),
+                (
+                    ClarityName::from("output-index"),
+                    ClarityValue::UInt(self.outpoint.vout as u128),
+                ),
+                (
+                    ClarityName::from("recipient"),
+                    ClarityValue::Tuple(TupleData::from_data(recipient.to_vec()).unwrap()),
+                ),
+                (
+                    ClarityName::from("sender"),
+                    ClarityValue::Principal(self.sender.clone()),
+                ),
+                (
+                    ClarityName::from("signer-keys"),
+                    ClarityValue::Sequence(SequenceData::List(ListData {
+                        data: signer_keys,
+                        type_signature: ListTypeData::new_list(BUFF_33.clone(), 128).unwrap(),
+                    })),
+                ),
+                (
+                    ClarityName::from("amount"),
+                    ClarityValue::UInt(self.amount as u128),
+                ),
+            ];
+            ClarityValue::Tuple(TupleData::from_data(fields.to_vec()).unwrap())
+        }
+    }
+
+    fn test_event() -> TestEvent {
+        let tx_info = TxInfo {
+            txid: StacksTxid([7; 32]),
+            block_id: StacksBlockId([8; 32]),
+        };
+        let keys = (0..3)
+            .map(|_| SECP256K1.generate_keypair(&mut OsRng).1)
+            .collect();
+
+        TestEvent {
+            txid: tx_info.txid,
+            block_id: tx_info.block_id,
+            block_hash: BitcoinBlockHash::from_byte_array([1; 32]),
+            outpoint: OutPoint {
+                txid: BitcoinTxid::from_byte_array([2; 32]),
+                vout: 5,
+            },
+            recipient: ScriptBuf::new_p2wpkh(&PUBLIC_KEY.wpubkey_hash()),
+            sender: PrincipalData::parse("ST1RQHF4VE5CZ6EK3MZPZVQBA0JVSMM9H5PMHMS1Y").unwrap(),
+            signer_keys: keys,
+            amount: 123456,
+        }
+    }
+
+    #[test]
+    fn derived_tuple_decoding_round_trips() {
+        let expected = test_event();
+        let tx_info = TxInfo {
+            txid: expected.txid,
+            block_id: expected.block_id,
+        };
+
+        // Go through the consensus serialization, since that is how the
+        // values reach us from the stacks node.
+        let hex = expected.to_clarity_value().serialize_to_hex().unwrap();
+        let value = ClarityValue::try_deserialize_hex_untyped(&hex).unwrap();
+        let actual = TestEvent::try_from((value, tx_info)).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn derived_decoding_rejects_values_that_are_not_tuples() {
+        match TestEvent::try_from((ClarityValue::UInt(1), TX_INFO)) {
+            Err(EventError::ClarityUnexpectedValue(ClarityValue::UInt(1), _)) => {}
+            res => panic!("expected an unexpected value error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn derived_tuple_decoding_reports_missing_fields() {
+        let event = test_event();
+        let ClarityValue::Tuple(TupleData { mut data_map, .. }) = event.to_clarity_value() else {
+            panic!("expected a tuple");
+        };
+        data_map.remove("signer-keys");
+
+        let tuple = RawTupleData::new(data_map, TX_INFO);
+        match TestEvent::try_from(tuple) {
+            Err(EventError::TupleEventField("signer-keys", _)) => {}
+            res => panic!("expected a missing field error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn derived_tuple_decoding_rejects_values_of_the_wrong_type() {
+        let event = test_event();
+        let ClarityValue::Tuple(TupleData { mut data_map, .. }) = event.to_clarity_value() else {
+            panic!("expected a tuple");
+        };
+        data_map.insert(ClarityName::from("amount"), ClarityValue::Int(1));
+
+        let tuple = RawTupleData::new(data_map, TX_INFO);
+        match TestEvent::try_from(tuple) {
+            Err(EventError::TupleEventField("amount", _)) => {}
+            res => panic!("expected a field error, got {res:?}"),
+        }
+    }
 }
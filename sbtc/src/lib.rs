@@ -7,6 +7,10 @@ use std::sync::LazyLock;
 
 use bitcoin::XOnlyPublicKey;
 
+// The derive macros in `sbtc-derive` refer to this crate as `::sbtc`,
+// which needs to resolve when they are used inside this crate too.
+extern crate self as sbtc;
+
 pub mod deposits;
 pub mod error;
 pub mod events;
@@ -0,0 +1,1180 @@
+//! An in-process bitcoin chain for tests that cannot run bitcoin-core.
+//!
+//! [`MockChain`] mines blocks, keeps the UTXO set of the active chain and
+//! a mempool, and follows the most-work chain, so reorgs of any depth
+//! happen the same way they do on regtest. Transactions that enter the
+//! mempool go through the checks that bitcoin-core's
+//! `testmempoolaccept` RPC does, with the notable exception of script
+//! validation: scripts and signatures are never executed, so inputs can
+//! be spent without valid witness data.
+//!
+//! Every block has the same amount of work, so the chain with the most
+//! blocks is the active chain, and the first block seen wins a tie.
+//!
+//! Like bitcoin-core, the coinbase of blocks at heights 1 through 16
+//! encodes the height with a single `OP_N` opcode, which
+//! [`bitcoin::Block::bip34_block_height`] cannot parse. Code that reads
+//! the height of a block from its coinbase should only be pointed at
+//! blocks above height 16.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use bitcoin::absolute::LockTime;
+use bitcoin::block::Header;
+use bitcoin::block::Version as BlockVersion;
+use bitcoin::blockdata::constants::genesis_block;
+use bitcoin::hashes::Hash as _;
+use bitcoin::transaction::Version;
+use bitcoin::Amount;
+use bitcoin::Block;
+use bitcoin::BlockHash;
+use bitcoin::CompactTarget;
+use bitcoin::Network;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Sequence;
+use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::TxMerkleNode;
+use bitcoin::TxOut;
+use bitcoin::Txid;
+use bitcoin::Witness;
+
+/// The number of blocks that must be mined on top of a coinbase
+/// transaction before its outputs can be spent.
+pub const COINBASE_MATURITY: u64 = 100;
+
+/// The number of blocks between block subsidy halvings on regtest.
+const SUBSIDY_HALVING_INTERVAL: u64 = 150;
+
+/// The number of seconds between the timestamps of consecutive blocks.
+const BLOCK_INTERVAL_SECS: u32 = 600;
+
+/// The `nBits` of every regtest block.
+const REGTEST_BITS: u32 = 0x207f_ffff;
+
+/// The maximum number of transactions that a replacement transaction may
+/// evict from the mempool, as in BIP-125.
+const MAX_REPLACEMENT_CANDIDATES: usize = 100;
+
+/// The fee paid by the transactions created with [`MockChain::send_to`].
+const SEND_TO_FEE: Amount = Amount::from_sat(1_000);
+
+/// The reasons a transaction or block is rejected by the mock chain.
+///
+/// The messages match the reject reasons that bitcoin-core returns, so
+/// that code that inspects them behaves the same way against both.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MockChainError {
+    /// The transaction has no inputs.
+    #[error("bad-txns-vin-empty")]
+    EmptyInputs,
+    /// The transaction has no outputs.
+    #[error("bad-txns-vout-empty")]
+    EmptyOutputs,
+    /// The transaction spends the same output more than once.
+    #[error("bad-txns-inputs-duplicate")]
+    DuplicateInputs,
+    /// Coinbase transactions can only be part of a block.
+    #[error("coinbase")]
+    Coinbase,
+    /// The transaction is already in the mempool.
+    #[error("txn-already-in-mempool")]
+    AlreadyInMempool,
+    /// The transaction is already confirmed on the active chain.
+    #[error("txn-already-known")]
+    AlreadyKnown,
+    /// One of the outputs spent by the transaction does not exist or has
+    /// already been spent.
+    #[error("missing-inputs")]
+    MissingInputs,
+    /// The transaction spends a coinbase output that is not mature yet.
+    #[error("bad-txns-premature-spend-of-coinbase")]
+    PrematureCoinbaseSpend,
+    /// The outputs of the transaction are worth more than its inputs.
+    #[error("bad-txns-in-belowout")]
+    InputsBelowOutputs,
+    /// One of the outputs of the transaction is dust.
+    #[error("dust")]
+    Dust,
+    /// The fee rate of the transaction is below the minimum relay fee
+    /// rate.
+    #[error("min relay fee not met")]
+    MinRelayFeeNotMet,
+    /// The transaction conflicts with a mempool transaction that does not
+    /// signal replaceability, and full RBF is turned off.
+    #[error("txn-mempool-conflict")]
+    MempoolConflict,
+    /// The transaction does not pay enough to replace the transactions
+    /// that it conflicts with.
+    #[error("insufficient fee")]
+    InsufficientFee,
+    /// The transaction would evict too many transactions from the
+    /// mempool.
+    #[error("too many potential replacements")]
+    TooManyReplacements,
+    /// The parent of a block is not known to the chain.
+    #[error("prev-blk-not-found")]
+    UnknownParent,
+}
+
+/// The mempool policy of the mock chain.
+#[derive(Debug, Clone, Copy)]
+pub struct MempoolPolicy {
+    /// The minimum fee rate, in sats per vbyte, for a transaction to be
+    /// accepted, and the fee rate that replacements must pay for their
+    /// own relay.
+    pub min_relay_fee_rate: f64,
+    /// Whether transactions can be replaced even if they do not signal
+    /// replaceability. This is the default as of bitcoin-core v28.
+    pub full_rbf: bool,
+    /// Whether transactions with dust outputs are rejected.
+    pub reject_dust: bool,
+}
+
+impl Default for MempoolPolicy {
+    fn default() -> Self {
+        Self {
+            min_relay_fee_rate: 1.0,
+            full_rbf: true,
+            reject_dust: true,
+        }
+    }
+}
+
+/// An unspent transaction output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utxo {
+    /// The output itself.
+    pub output: TxOut,
+    /// The height of the block that confirmed the output.
+    pub height: u64,
+    /// Whether the output was created by a coinbase transaction.
+    pub is_coinbase: bool,
+}
+
+/// A transaction in the mempool.
+#[derive(Debug, Clone)]
+pub struct MempoolEntry {
+    /// The transaction.
+    pub tx: Transaction,
+    /// The fee paid by the transaction.
+    pub fee: Amount,
+    /// The virtual size of the transaction.
+    pub vsize: u64,
+    /// The timestamp of the chain tip when the transaction was accepted.
+    pub time: u64,
+    /// The height of the chain tip when the transaction was accepted.
+    pub height: u64,
+}
+
+impl MempoolEntry {
+    /// The fee rate of the transaction in sats per vbyte.
+    pub fn fee_rate(&self) -> f64 {
+        self.fee.to_sat() as f64 / self.vsize as f64
+    }
+}
+
+/// The result of checking a transaction against the mempool policy.
+#[derive(Debug, Clone)]
+pub struct MempoolAcceptance {
+    /// The ID of the transaction.
+    pub txid: Txid,
+    /// The fee paid by the transaction.
+    pub fee: Amount,
+    /// The virtual size of the transaction.
+    pub vsize: u64,
+    /// The mempool transactions that would be evicted if the transaction
+    /// were broadcast, because it replaces them or one of their
+    /// ancestors.
+    pub replaced: Vec<Txid>,
+}
+
+/// A block that the chain knows about, whether or not it is on the active
+/// chain.
+#[derive(Debug, Clone)]
+struct BlockEntry {
+    block: Block,
+    height: u64,
+}
+
+/// An in-process bitcoin chain. See the [module docs](self) for how it
+/// relates to bitcoin-core.
+#[derive(Debug, Clone)]
+pub struct MockChain {
+    /// The mempool policy that transactions are checked against.
+    pub policy: MempoolPolicy,
+    /// The `scriptPubKey` that the coinbase outputs pay to.
+    pub coinbase_script: ScriptBuf,
+    /// Every block that has been mined, including stale ones.
+    blocks: HashMap<BlockHash, BlockEntry>,
+    /// The hashes of the blocks on the active chain, indexed by height.
+    active_chain: Vec<BlockHash>,
+    /// The UTXO set of the active chain.
+    utxos: HashMap<OutPoint, Utxo>,
+    /// Every transaction that has been seen, in a block or in the mempool.
+    transactions: HashMap<Txid, Transaction>,
+    /// The blocks that include each transaction, including stale ones.
+    tx_blocks: HashMap<Txid, Vec<BlockHash>>,
+    /// The mempool, in the order that transactions were accepted, so that
+    /// parents always come before their children.
+    mempool: Vec<MempoolEntry>,
+    /// The blocks that were connected to the active chain since the last
+    /// call to [`MockChain::drain_connected_blocks`].
+    connected: Vec<BlockHash>,
+    /// A counter that makes blocks with the same parent and transactions
+    /// distinct.
+    extra_nonce: u64,
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockChain {
+    /// Create a chain with only the regtest genesis block, with the
+    /// default mempool policy and with coinbase outputs that pay to an
+    /// empty script.
+    pub fn new() -> Self {
+        let genesis = genesis_block(Network::Regtest);
+        let genesis_hash = genesis.block_hash();
+        let mut chain = Self {
+            policy: MempoolPolicy::default(),
+            coinbase_script: ScriptBuf::new(),
+            blocks: HashMap::new(),
+            active_chain: vec![genesis_hash],
+            utxos: HashMap::new(),
+            transactions: HashMap::new(),
+            tx_blocks: HashMap::new(),
+            mempool: Vec::new(),
+            connected: Vec::new(),
+            extra_nonce: 0,
+        };
+        // Like in bitcoin-core, the outputs of the genesis coinbase are
+        // not part of the UTXO set.
+        chain.index_block(&genesis);
+        chain
+            .blocks
+            .insert(genesis_hash, BlockEntry { block: genesis, height: 0 });
+        chain
+    }
+
+    /// The hash of the tip of the active chain.
+    pub fn tip(&self) -> BlockHash {
+        *self
+            .active_chain
+            .last()
+            .expect("the chain always has a genesis block")
+    }
+
+    /// The height of the tip of the active chain.
+    pub fn tip_height(&self) -> u64 {
+        self.active_chain.len() as u64 - 1
+    }
+
+    /// The block with the given hash, whether or not it is on the active
+    /// chain.
+    pub fn block(&self, block_hash: &BlockHash) -> Option<&Block> {
+        self.blocks.get(block_hash).map(|entry| &entry.block)
+    }
+
+    /// The height of the block with the given hash.
+    pub fn block_height(&self, block_hash: &BlockHash) -> Option<u64> {
+        self.blocks.get(block_hash).map(|entry| entry.height)
+    }
+
+    /// The hash of the block at the given height on the active chain.
+    pub fn block_hash_at(&self, height: u64) -> Option<BlockHash> {
+        self.active_chain.get(height as usize).copied()
+    }
+
+    /// Whether the block with the given hash is on the active chain.
+    pub fn is_in_active_chain(&self, block_hash: &BlockHash) -> bool {
+        self.block_height(block_hash)
+            .and_then(|height| self.block_hash_at(height))
+            .is_some_and(|hash| &hash == block_hash)
+    }
+
+    /// The number of confirmations of the block with the given hash, which
+    /// is `None` if the block is not on the active chain.
+    pub fn confirmations(&self, block_hash: &BlockHash) -> Option<u32> {
+        if !self.is_in_active_chain(block_hash) {
+            return None;
+        }
+        let height = self.block_height(block_hash)?;
+        Some((self.tip_height() - height + 1) as u32)
+    }
+
+    /// The unspent output at the given outpoint on the active chain.
+    pub fn utxo(&self, outpoint: &OutPoint) -> Option<&Utxo> {
+        self.utxos.get(outpoint)
+    }
+
+    /// The unspent outputs on the active chain that pay to the given
+    /// `scriptPubKey`.
+    pub fn utxos_for(&self, script_pubkey: &ScriptBuf) -> Vec<(OutPoint, Utxo)> {
+        let mut utxos: Vec<_> = self
+            .utxos
+            .iter()
+            .filter(|(_, utxo)| &utxo.output.script_pubkey == script_pubkey)
+            .map(|(outpoint, utxo)| (*outpoint, utxo.clone()))
+            .collect();
+        utxos.sort_by_key(|(outpoint, utxo)| (utxo.height, *outpoint));
+        utxos
+    }
+
+    /// Any transaction that the chain has seen, confirmed or not.
+    pub fn transaction(&self, txid: &Txid) -> Option<&Transaction> {
+        self.transactions.get(txid)
+    }
+
+    /// The output at the given outpoint, whether or not it is spent.
+    pub fn output(&self, outpoint: &OutPoint) -> Option<&TxOut> {
+        self.transactions
+            .get(&outpoint.txid)?
+            .output
+            .get(outpoint.vout as usize)
+    }
+
+    /// The fee paid by the given transaction, which is `None` if the
+    /// transaction or one of its prevouts is unknown. Coinbase
+    /// transactions do not pay a fee.
+    pub fn transaction_fee(&self, txid: &Txid) -> Option<Amount> {
+        let tx = self.transactions.get(txid)?;
+        if tx.is_coinbase() {
+            return Some(Amount::ZERO);
+        }
+        let inputs = tx
+            .input
+            .iter()
+            .map(|tx_in| self.output(&tx_in.previous_output).map(|out| out.value))
+            .sum::<Option<Amount>>()?;
+        let outputs = tx.output.iter().map(|out| out.value).sum::<Amount>();
+        inputs.checked_sub(outputs)
+    }
+
+    /// The blocks that include the given transaction, including stale
+    /// ones.
+    pub fn blocks_with_transaction(&self, txid: &Txid) -> &[BlockHash] {
+        self.tx_blocks
+            .get(txid)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The block on the active chain that includes the given transaction.
+    pub fn confirming_block(&self, txid: &Txid) -> Option<BlockHash> {
+        self.blocks_with_transaction(txid)
+            .iter()
+            .find(|block_hash| self.is_in_active_chain(block_hash))
+            .copied()
+    }
+
+    /// The mempool entry for the given transaction.
+    pub fn mempool_entry(&self, txid: &Txid) -> Option<&MempoolEntry> {
+        self.mempool
+            .iter()
+            .find(|entry| &entry.tx.compute_txid() == txid)
+    }
+
+    /// The transactions in the mempool, in the order they were accepted.
+    pub fn mempool(&self) -> &[MempoolEntry] {
+        &self.mempool
+    }
+
+    /// The mempool transactions that spend the given output.
+    pub fn mempool_spending(&self, outpoint: &OutPoint) -> Vec<Txid> {
+        self.mempool
+            .iter()
+            .filter(|entry| {
+                entry
+                    .tx
+                    .input
+                    .iter()
+                    .any(|tx_in| &tx_in.previous_output == outpoint)
+            })
+            .map(|entry| entry.tx.compute_txid())
+            .collect()
+    }
+
+    /// The mempool transactions that spend an output of the given
+    /// transaction, directly or through other mempool transactions.
+    pub fn mempool_descendants(&self, txid: &Txid) -> Vec<Txid> {
+        let mut descendants = Vec::new();
+        let mut ancestors = HashSet::from([*txid]);
+        // Children are always accepted after their parents, so a single
+        // pass in mempool order finds every descendant.
+        for entry in &self.mempool {
+            let spends_ancestor = entry
+                .tx
+                .input
+                .iter()
+                .any(|tx_in| ancestors.contains(&tx_in.previous_output.txid));
+            if spends_ancestor {
+                let txid = entry.tx.compute_txid();
+                ancestors.insert(txid);
+                descendants.push(txid);
+            }
+        }
+        descendants
+    }
+
+    /// The mempool transactions whose outputs are spent by the given
+    /// mempool transaction, directly or through other mempool
+    /// transactions.
+    pub fn mempool_ancestors(&self, txid: &Txid) -> Vec<Txid> {
+        let mut ancestors = Vec::new();
+        let mut pending = vec![*txid];
+        while let Some(txid) = pending.pop() {
+            let Some(entry) = self.mempool_entry(&txid) else {
+                continue;
+            };
+            for tx_in in &entry.tx.input {
+                let parent = tx_in.previous_output.txid;
+                if self.mempool_entry(&parent).is_some() && !ancestors.contains(&parent) {
+                    ancestors.push(parent);
+                    pending.push(parent);
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Check whether the transaction would be accepted into the mempool,
+    /// like the `testmempoolaccept` RPC does, without changing anything.
+    pub fn test_mempool_accept(
+        &self,
+        tx: &Transaction,
+    ) -> Result<MempoolAcceptance, MockChainError> {
+        let txid = tx.compute_txid();
+        check_transaction(tx)?;
+        if self.mempool_entry(&txid).is_some() {
+            return Err(MockChainError::AlreadyInMempool);
+        }
+        if self.confirming_block(&txid).is_some() {
+            return Err(MockChainError::AlreadyKnown);
+        }
+
+        // The mempool transactions that spend the same outputs, along with
+        // everything that spends their outputs.
+        let conflicts: Vec<Txid> = tx
+            .input
+            .iter()
+            .flat_map(|tx_in| self.mempool_spending(&tx_in.previous_output))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut replaced: Vec<Txid> = conflicts.clone();
+        for conflict in &conflicts {
+            for descendant in self.mempool_descendants(conflict) {
+                if !replaced.contains(&descendant) {
+                    replaced.push(descendant);
+                }
+            }
+        }
+
+        let view = self.mempool_view(&replaced);
+        let fee = check_inputs(&view, tx, self.tip_height() + 1)?;
+        let vsize = tx.vsize() as u64;
+
+        if self.policy.reject_dust {
+            let has_dust = tx.output.iter().any(|out| {
+                !out.script_pubkey.is_op_return()
+                    && out.value < out.script_pubkey.minimal_non_dust()
+            });
+            if has_dust {
+                return Err(MockChainError::Dust);
+            }
+        }
+
+        let fee_rate = fee.to_sat() as f64 / vsize as f64;
+        if fee_rate < self.policy.min_relay_fee_rate {
+            return Err(MockChainError::MinRelayFeeNotMet);
+        }
+
+        if !conflicts.is_empty() {
+            self.check_replacement(&conflicts, &replaced, fee, vsize)?;
+        }
+
+        Ok(MempoolAcceptance { txid, fee, vsize, replaced })
+    }
+
+    /// Check the BIP-125 rules for a transaction that conflicts with
+    /// mempool transactions.
+    fn check_replacement(
+        &self,
+        conflicts: &[Txid],
+        replaced: &[Txid],
+        fee: Amount,
+        vsize: u64,
+    ) -> Result<(), MockChainError> {
+        let conflicts: Vec<&MempoolEntry> = conflicts
+            .iter()
+            .filter_map(|txid| self.mempool_entry(txid))
+            .collect();
+
+        if !self.policy.full_rbf {
+            let signals_rbf = |txid: &Txid| {
+                self.mempool_entry(txid)
+                    .is_some_and(|entry| entry.tx.input.iter().any(|tx_in| tx_in.sequence.is_rbf()))
+            };
+            let replaceable = conflicts.iter().all(|entry| {
+                let txid = entry.tx.compute_txid();
+                signals_rbf(&txid) || self.mempool_ancestors(&txid).iter().any(signals_rbf)
+            });
+            if !replaceable {
+                return Err(MockChainError::MempoolConflict);
+            }
+        }
+
+        if replaced.len() > MAX_REPLACEMENT_CANDIDATES {
+            return Err(MockChainError::TooManyReplacements);
+        }
+
+        // The replacement must have a higher fee rate than each of the
+        // transactions it directly replaces.
+        let fee_rate = fee.to_sat() as f64 / vsize as f64;
+        if conflicts.iter().any(|entry| fee_rate <= entry.fee_rate()) {
+            return Err(MockChainError::InsufficientFee);
+        }
+
+        // It must also pay for everything it evicts, plus its own relay.
+        let replaced_fees = replaced
+            .iter()
+            .filter_map(|txid| self.mempool_entry(txid))
+            .map(|entry| entry.fee)
+            .sum::<Amount>();
+        let relay_fee = (self.policy.min_relay_fee_rate * vsize as f64).ceil() as u64;
+        if fee < replaced_fees + Amount::from_sat(relay_fee) {
+            return Err(MockChainError::InsufficientFee);
+        }
+
+        Ok(())
+    }
+
+    /// Add the transaction to the mempool, evicting the transactions that
+    /// it replaces. The transaction must pass
+    /// [`MockChain::test_mempool_accept`].
+    pub fn broadcast(&mut self, tx: Transaction) -> Result<Txid, MockChainError> {
+        let acceptance = self.test_mempool_accept(&tx)?;
+        self.mempool
+            .retain(|entry| !acceptance.replaced.contains(&entry.tx.compute_txid()));
+
+        self.transactions.insert(acceptance.txid, tx.clone());
+        self.mempool.push(MempoolEntry {
+            tx,
+            fee: acceptance.fee,
+            vsize: acceptance.vsize,
+            time: self.tip_time(),
+            height: self.tip_height(),
+        });
+        Ok(acceptance.txid)
+    }
+
+    /// Create and broadcast a transaction that pays the given amount to
+    /// the given `scriptPubKey` from the mature coinbase outputs of the
+    /// chain, returning the outpoint of the payment.
+    ///
+    /// At least [`COINBASE_MATURITY`] + 1 blocks have to be mined before
+    /// the first coinbase output can be spent.
+    pub fn send_to(
+        &mut self,
+        script_pubkey: ScriptBuf,
+        amount: Amount,
+    ) -> Result<OutPoint, MockChainError> {
+        let view = self.mempool_view(&[]);
+        let spendable_height = (self.tip_height() + 1).saturating_sub(COINBASE_MATURITY);
+        let mut funding: Vec<_> = view
+            .iter()
+            .filter(|(_, utxo)| utxo.output.script_pubkey == self.coinbase_script)
+            .filter(|(_, utxo)| !utxo.is_coinbase || utxo.height <= spendable_height)
+            .map(|(outpoint, utxo)| (*outpoint, utxo.output.value))
+            .collect();
+        funding.sort();
+
+        let mut input = Vec::new();
+        let mut total = Amount::ZERO;
+        for (outpoint, value) in funding {
+            if total >= amount + SEND_TO_FEE {
+                break;
+            }
+            input.push(TxIn {
+                previous_output: outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            });
+            total += value;
+        }
+        let change = total
+            .checked_sub(amount + SEND_TO_FEE)
+            .ok_or(MockChainError::MissingInputs)?;
+
+        let mut output = vec![TxOut { value: amount, script_pubkey }];
+        if change > Amount::ZERO {
+            output.push(TxOut {
+                value: change,
+                script_pubkey: self.coinbase_script.clone(),
+            });
+        }
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input,
+            output,
+        };
+        let txid = self.broadcast(tx)?;
+        Ok(OutPoint { txid, vout: 0 })
+    }
+
+    /// Mine the given number of blocks on the tip of the active chain.
+    /// The first block includes every transaction in the mempool.
+    pub fn generate_blocks(&mut self, num_blocks: u64) -> Vec<BlockHash> {
+        (0..num_blocks)
+            .map(|_| {
+                let txdata = self.mempool.iter().map(|entry| entry.tx.clone()).collect();
+                let tip = self.tip();
+                self.mine_block(&tip, txdata)
+                    .expect("mempool transactions are valid on the chain tip")
+            })
+            .collect()
+    }
+
+    /// Mine a block with the given transactions on top of the given
+    /// parent, which does not have to be the chain tip. The transactions
+    /// must be valid on the parent's chain, in order, but they do not
+    /// have to pass the mempool policy.
+    ///
+    /// If the new block makes its chain longer than the active chain, the
+    /// chain reorganizes onto it.
+    pub fn mine_block(
+        &mut self,
+        parent: &BlockHash,
+        txdata: Vec<Transaction>,
+    ) -> Result<BlockHash, MockChainError> {
+        let parent_entry = self
+            .blocks
+            .get(parent)
+            .ok_or(MockChainError::UnknownParent)?;
+        let height = parent_entry.height + 1;
+        let time = parent_entry.block.header.time + BLOCK_INTERVAL_SECS;
+
+        let mut view = self.utxo_view_at(parent);
+        let mut fees = Amount::ZERO;
+        for tx in &txdata {
+            check_transaction(tx)?;
+            fees += check_inputs(&view, tx, height)?;
+            apply_transaction(&mut view, tx, height);
+        }
+
+        self.extra_nonce += 1;
+        let coinbase = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                // BIP-34 requires the height to be the first push.
+                script_sig: ScriptBuf::builder()
+                    .push_int(height as i64)
+                    .push_int(self.extra_nonce as i64)
+                    .into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: block_subsidy(height) + fees,
+                script_pubkey: self.coinbase_script.clone(),
+            }],
+        };
+
+        let mut block = Block {
+            header: Header {
+                version: BlockVersion::TWO,
+                prev_blockhash: *parent,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time,
+                bits: CompactTarget::from_consensus(REGTEST_BITS),
+                nonce: 0,
+            },
+            txdata: std::iter::once(coinbase).chain(txdata).collect(),
+        };
+        block.header.merkle_root = block
+            .compute_merkle_root()
+            .expect("blocks always have a coinbase transaction");
+
+        let block_hash = block.block_hash();
+        self.index_block(&block);
+        self.blocks.insert(block_hash, BlockEntry { block, height });
+
+        if height > self.tip_height() {
+            self.activate(block_hash);
+        }
+        Ok(block_hash)
+    }
+
+    /// Replace the top `depth` blocks of the active chain with `depth + 1`
+    /// empty blocks, returning the hashes of the new blocks. The
+    /// transactions of the replaced blocks go back into the mempool if
+    /// they are still valid.
+    pub fn reorg(&mut self, depth: u64) -> Vec<BlockHash> {
+        let fork_height = self
+            .tip_height()
+            .checked_sub(depth)
+            .expect("cannot reorg the genesis block");
+        let mut parent = self.active_chain[fork_height as usize];
+
+        (0..=depth)
+            .map(|_| {
+                parent = self
+                    .mine_block(&parent, Vec::new())
+                    .expect("empty blocks are always valid");
+                parent
+            })
+            .collect()
+    }
+
+    /// Return the blocks that were connected to the active chain since
+    /// the last call, in the order they were connected. This is what
+    /// bitcoin-core publishes on its `hashblock` ZMQ topic, so during a
+    /// reorg only the blocks of the new chain are included.
+    pub fn drain_connected_blocks(&mut self) -> Vec<BlockHash> {
+        std::mem::take(&mut self.connected)
+    }
+
+    /// The timestamp of the chain tip.
+    fn tip_time(&self) -> u64 {
+        self.blocks[&self.tip()].block.header.time as u64
+    }
+
+    /// Record the transactions of the block.
+    fn index_block(&mut self, block: &Block) {
+        let block_hash = block.block_hash();
+        for tx in &block.txdata {
+            let txid = tx.compute_txid();
+            self.transactions.insert(txid, tx.clone());
+            self.tx_blocks.entry(txid).or_default().push(block_hash);
+        }
+    }
+
+    /// Make the chain ending with the given block the active chain.
+    fn activate(&mut self, block_hash: BlockHash) {
+        let mut new_chain = Vec::new();
+        let mut current = block_hash;
+        while !self.is_in_active_chain(&current) {
+            new_chain.push(current);
+            current = self.blocks[&current].block.header.prev_blockhash;
+        }
+        new_chain.reverse();
+
+        // The UTXO set has to be computed while the old tip is still the
+        // tip, so that extending the active chain does not replay it.
+        let utxos = self.utxo_view_at(&block_hash);
+        let fork_height = self.blocks[&current].height as usize;
+        let disconnected = self.active_chain.split_off(fork_height + 1);
+        self.active_chain.extend(new_chain.iter().copied());
+        self.utxos = utxos;
+        self.connected.extend(new_chain);
+
+        // Transactions in disconnected blocks go back to the mempool ahead
+        // of the ones that were already there, since they may be their
+        // parents. Anything that is no longer valid is dropped.
+        let time = self.tip_time();
+        let height = self.tip_height();
+        let mempool = std::mem::take(&mut self.mempool);
+        let candidates: Vec<MempoolEntry> = disconnected
+            .iter()
+            .flat_map(|hash| self.blocks[hash].block.txdata.iter().skip(1))
+            .map(|tx| MempoolEntry {
+                tx: tx.clone(),
+                fee: Amount::ZERO,
+                vsize: tx.vsize() as u64,
+                time,
+                height,
+            })
+            .chain(mempool)
+            .collect();
+
+        let mut view = self.utxos.clone();
+        let next_height = self.tip_height() + 1;
+        for mut entry in candidates {
+            let tx = &entry.tx;
+            let txid = tx.compute_txid();
+            if self.confirming_block(&txid).is_some() {
+                continue;
+            }
+            let Ok(fee) = check_inputs(&view, tx, next_height) else {
+                continue;
+            };
+            apply_transaction(&mut view, tx, next_height);
+            entry.fee = fee;
+            self.mempool.push(entry);
+        }
+    }
+
+    /// The UTXO set at the given block. Blocks that are not the tip or
+    /// one of its children are replayed from genesis.
+    fn utxo_view_at(&self, block_hash: &BlockHash) -> HashMap<OutPoint, Utxo> {
+        let tip = self.tip();
+        if block_hash == &tip {
+            return self.utxos.clone();
+        }
+        if let Some(entry) = self.blocks.get(block_hash) {
+            if entry.block.header.prev_blockhash == tip {
+                let mut view = self.utxos.clone();
+                for tx in &entry.block.txdata {
+                    apply_transaction(&mut view, tx, entry.height);
+                }
+                return view;
+            }
+        }
+
+        let mut chain = Vec::new();
+        let mut current = *block_hash;
+        while let Some(entry) = self.blocks.get(&current) {
+            if entry.height == 0 {
+                break;
+            }
+            chain.push(entry);
+            current = entry.block.header.prev_blockhash;
+        }
+
+        let mut view = HashMap::new();
+        for entry in chain.into_iter().rev() {
+            for tx in &entry.block.txdata {
+                apply_transaction(&mut view, tx, entry.height);
+            }
+        }
+        view
+    }
+
+    /// The UTXO set of the chain tip with the mempool applied on top,
+    /// ignoring the given mempool transactions.
+    fn mempool_view(&self, ignored: &[Txid]) -> HashMap<OutPoint, Utxo> {
+        let mut view = self.utxos.clone();
+        let height = self.tip_height() + 1;
+        for entry in &self.mempool {
+            if !ignored.contains(&entry.tx.compute_txid()) {
+                apply_transaction(&mut view, &entry.tx, height);
+            }
+        }
+        view
+    }
+}
+
+/// The block subsidy at the given height on regtest.
+fn block_subsidy(height: u64) -> Amount {
+    let halvings = height / SUBSIDY_HALVING_INTERVAL;
+    let subsidy = Amount::from_int_btc(50).to_sat();
+    Amount::from_sat(subsidy.checked_shr(halvings as u32).unwrap_or(0))
+}
+
+/// Check the parts of the transaction that do not depend on the chain.
+fn check_transaction(tx: &Transaction) -> Result<(), MockChainError> {
+    if tx.input.is_empty() {
+        return Err(MockChainError::EmptyInputs);
+    }
+    if tx.output.is_empty() {
+        return Err(MockChainError::EmptyOutputs);
+    }
+    if tx.is_coinbase() {
+        return Err(MockChainError::Coinbase);
+    }
+    let outpoints: HashSet<_> = tx.input.iter().map(|tx_in| tx_in.previous_output).collect();
+    if outpoints.len() != tx.input.len() {
+        return Err(MockChainError::DuplicateInputs);
+    }
+    Ok(())
+}
+
+/// Check that the transaction can spend its inputs from the given UTXO
+/// set in a block at the given height, returning the fee that it pays.
+fn check_inputs(
+    view: &HashMap<OutPoint, Utxo>,
+    tx: &Transaction,
+    height: u64,
+) -> Result<Amount, MockChainError> {
+    let mut inputs = Amount::ZERO;
+    for tx_in in &tx.input {
+        let utxo = view
+            .get(&tx_in.previous_output)
+            .ok_or(MockChainError::MissingInputs)?;
+        if utxo.is_coinbase && height - utxo.height < COINBASE_MATURITY {
+            return Err(MockChainError::PrematureCoinbaseSpend);
+        }
+        inputs += utxo.output.value;
+    }
+
+    let outputs = tx.output.iter().map(|out| out.value).sum::<Amount>();
+    inputs
+        .checked_sub(outputs)
+        .ok_or(MockChainError::InputsBelowOutputs)
+}
+
+/// Spend the inputs of the transaction and add its outputs to the UTXO
+/// set.
+fn apply_transaction(view: &mut HashMap<OutPoint, Utxo>, tx: &Transaction, height: u64) {
+    let is_coinbase = tx.is_coinbase();
+    if !is_coinbase {
+        for tx_in in &tx.input {
+            view.remove(&tx_in.previous_output);
+        }
+    }
+
+    let txid = tx.compute_txid();
+    for (vout, output) in tx.output.iter().enumerate() {
+        let outpoint = OutPoint { txid, vout: vout as u32 };
+        let utxo = Utxo {
+            output: output.clone(),
+            height,
+            is_coinbase,
+        };
+        view.insert(outpoint, utxo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A chain where the first few coinbase outputs are spendable.
+    fn funded_chain() -> MockChain {
+        let mut chain = MockChain::new();
+        chain.generate_blocks(COINBASE_MATURITY + 5);
+        chain
+    }
+
+    fn p2wpkh(byte: u8) -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([byte; 20]))
+    }
+
+    /// A transaction that spends the given outpoint into one output.
+    fn spend(outpoint: OutPoint, value: Amount, script_pubkey: ScriptBuf) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut { value, script_pubkey }],
+        }
+    }
+
+    #[test]
+    fn mined_blocks_extend_the_chain_and_utxo_set() {
+        let mut chain = MockChain::new();
+        let hashes = chain.generate_blocks(20);
+
+        assert_eq!(chain.tip_height(), 20);
+        assert_eq!(chain.tip(), *hashes.last().unwrap());
+        assert_eq!(chain.drain_connected_blocks(), hashes);
+        assert!(chain.drain_connected_blocks().is_empty());
+
+        let block = chain.block(&hashes[16]).unwrap();
+        assert_eq!(block.bip34_block_height().unwrap(), 17);
+        assert_eq!(block.header.prev_blockhash, hashes[15]);
+        assert_eq!(chain.confirmations(&hashes[16]), Some(4));
+        assert_eq!(chain.utxos_for(&chain.coinbase_script).len(), 20);
+    }
+
+    #[test]
+    fn mempool_transactions_get_mined() {
+        let mut chain = funded_chain();
+        let recipient = p2wpkh(1);
+        let outpoint = chain
+            .send_to(recipient.clone(), Amount::from_sat(50_000))
+            .unwrap();
+
+        assert!(chain.mempool_entry(&outpoint.txid).is_some());
+        assert!(chain.utxo(&outpoint).is_none());
+
+        let block_hash = chain.generate_blocks(1)[0];
+        assert!(chain.mempool().is_empty());
+        assert_eq!(chain.confirming_block(&outpoint.txid), Some(block_hash));
+        assert_eq!(
+            chain.utxo(&outpoint).unwrap().output.script_pubkey,
+            recipient
+        );
+        assert_eq!(chain.transaction_fee(&outpoint.txid), Some(SEND_TO_FEE));
+    }
+
+    #[test]
+    fn policy_checks_reject_bad_transactions() {
+        let mut chain = funded_chain();
+        let outpoint = chain.send_to(p2wpkh(1), Amount::from_sat(50_000)).unwrap();
+        chain.generate_blocks(1);
+
+        let missing = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let tx = spend(missing, Amount::from_sat(1_000), p2wpkh(2));
+        assert_eq!(
+            chain.test_mempool_accept(&tx).unwrap_err(),
+            MockChainError::MissingInputs
+        );
+
+        let tx = spend(outpoint, Amount::from_sat(60_000), p2wpkh(2));
+        assert_eq!(
+            chain.test_mempool_accept(&tx).unwrap_err(),
+            MockChainError::InputsBelowOutputs
+        );
+
+        let tx = spend(outpoint, Amount::from_sat(50_000), p2wpkh(2));
+        assert_eq!(
+            chain.test_mempool_accept(&tx).unwrap_err(),
+            MockChainError::MinRelayFeeNotMet
+        );
+
+        let tx = spend(outpoint, Amount::from_sat(100), p2wpkh(2));
+        assert_eq!(
+            chain.test_mempool_accept(&tx).unwrap_err(),
+            MockChainError::Dust
+        );
+
+        // The coinbase of the tip is not mature.
+        let coinbase = chain.block(&chain.tip()).unwrap().txdata[0].compute_txid();
+        let tx = spend(
+            OutPoint { txid: coinbase, vout: 0 },
+            Amount::from_sat(50_000),
+            p2wpkh(2),
+        );
+        assert_eq!(
+            chain.test_mempool_accept(&tx).unwrap_err(),
+            MockChainError::PrematureCoinbaseSpend
+        );
+
+        // Nothing was added to the mempool by any of the checks.
+        assert!(chain.mempool().is_empty());
+        let tx = spend(outpoint, Amount::from_sat(49_000), p2wpkh(2));
+        assert!(chain.test_mempool_accept(&tx).is_ok());
+        assert!(chain.mempool().is_empty());
+    }
+
+    #[test]
+    fn replacements_must_pay_more_and_evict_descendants() {
+        let mut chain = funded_chain();
+        let outpoint = chain.send_to(p2wpkh(1), Amount::from_sat(50_000)).unwrap();
+        chain.generate_blocks(1);
+
+        let original = spend(outpoint, Amount::from_sat(49_000), p2wpkh(2));
+        let original_txid = chain.broadcast(original).unwrap();
+        let child = spend(
+            OutPoint { txid: original_txid, vout: 0 },
+            Amount::from_sat(48_000),
+            p2wpkh(3),
+        );
+        let child_txid = chain.broadcast(child).unwrap();
+        assert_eq!(chain.mempool_spending(&outpoint), vec![original_txid]);
+        assert_eq!(chain.mempool_descendants(&original_txid), vec![child_txid]);
+
+        // Paying the same fee rate is not enough, and neither is beating
+        // the fee rate of the original without covering the child's fee.
+        let same_fee = spend(outpoint, Amount::from_sat(49_000), p2wpkh(4));
+        assert_eq!(
+            chain.broadcast(same_fee).unwrap_err(),
+            MockChainError::InsufficientFee
+        );
+        let no_child_fee = spend(outpoint, Amount::from_sat(48_500), p2wpkh(4));
+        assert_eq!(
+            chain.broadcast(no_child_fee).unwrap_err(),
+            MockChainError::InsufficientFee
+        );
+
+        let replacement = spend(outpoint, Amount::from_sat(47_000), p2wpkh(4));
+        let acceptance = chain.test_mempool_accept(&replacement).unwrap();
+        assert_eq!(acceptance.replaced.len(), 2);
+        let replacement_txid = chain.broadcast(replacement).unwrap();
+
+        let mempool: Vec<_> = chain
+            .mempool()
+            .iter()
+            .map(|e| e.tx.compute_txid())
+            .collect();
+        assert_eq!(mempool, vec![replacement_txid]);
+    }
+
+    #[test]
+    fn replacement_requires_signaling_without_full_rbf() {
+        let mut chain = funded_chain();
+        chain.policy.full_rbf = false;
+        let outpoint = chain.send_to(p2wpkh(1), Amount::from_sat(50_000)).unwrap();
+        chain.generate_blocks(1);
+
+        let mut original = spend(outpoint, Amount::from_sat(49_000), p2wpkh(2));
+        original.input[0].sequence = Sequence::MAX;
+        chain.broadcast(original).unwrap();
+
+        let replacement = spend(outpoint, Amount::from_sat(40_000), p2wpkh(3));
+        assert_eq!(
+            chain.broadcast(replacement).unwrap_err(),
+            MockChainError::MempoolConflict
+        );
+    }
+
+    #[test]
+    fn deep_reorgs_switch_the_active_chain() {
+        let mut chain = funded_chain();
+        let outpoint = chain.send_to(p2wpkh(1), Amount::from_sat(50_000)).unwrap();
+        let old_blocks = chain.generate_blocks(10);
+        chain.drain_connected_blocks();
+        let old_tip_height = chain.tip_height();
+
+        let new_blocks = chain.reorg(10);
+
+        assert_eq!(new_blocks.len(), 11);
+        assert_eq!(chain.tip(), *new_blocks.last().unwrap());
+        assert_eq!(chain.tip_height(), old_tip_height + 1);
+        assert!(old_blocks
+            .iter()
+            .all(|hash| !chain.is_in_active_chain(hash)));
+        assert!(old_blocks.iter().all(|hash| chain.block(hash).is_some()));
+        assert_eq!(chain.drain_connected_blocks(), new_blocks);
+
+        // The transaction was confirmed on the old chain, so it is back in
+        // the mempool now, with its output unconfirmed.
+        assert!(chain.confirming_block(&outpoint.txid).is_none());
+        assert!(chain.mempool_entry(&outpoint.txid).is_some());
+        assert!(chain.utxo(&outpoint).is_none());
+        assert_eq!(chain.blocks_with_transaction(&outpoint.txid).len(), 1);
+    }
+
+    #[test]
+    fn side_chains_win_only_when_longer() {
+        let mut chain = funded_chain();
+        let fork_point = chain.tip();
+        let main = chain.generate_blocks(2);
+
+        let side1 = chain.mine_block(&fork_point, Vec::new()).unwrap();
+        let side2 = chain.mine_block(&side1, Vec::new()).unwrap();
+        assert_eq!(chain.tip(), main[1]);
+        assert!(!chain.is_in_active_chain(&side2));
+
+        let side3 = chain.mine_block(&side2, Vec::new()).unwrap();
+        assert_eq!(chain.tip(), side3);
+        assert!(!chain.is_in_active_chain(&main[0]));
+        assert_eq!(chain.confirmations(&side1), Some(3));
+    }
+
+    #[test]
+    fn blocks_with_invalid_transactions_are_rejected() {
+        let mut chain = funded_chain();
+        let tip = chain.tip();
+        let missing = OutPoint {
+            txid: Txid::all_zeros(),
+            vout: 0,
+        };
+        let tx = spend(missing, Amount::from_sat(1_000), p2wpkh(2));
+
+        assert_eq!(
+            chain.mine_block(&tip, vec![tx]).unwrap_err(),
+            MockChainError::MissingInputs
+        );
+        assert_eq!(chain.tip(), tip);
+    }
+}
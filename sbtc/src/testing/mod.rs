@@ -1,3 +1,4 @@
 //! All testing utility functions.
 pub mod deposits;
+pub mod mock_chain;
 pub mod regtest;
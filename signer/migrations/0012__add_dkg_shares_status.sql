@@ -0,0 +1,17 @@
+CREATE TYPE sbtc_signer.dkg_shares_status AS ENUM (
+    'unverified',
+    'verified',
+    'failed'
+);
+
+ALTER TABLE sbtc_signer.dkg_shares
+ADD COLUMN dkg_shares_status sbtc_signer.dkg_shares_status;
+
+-- Any shares written before this migration have already been put to use
+-- by the signers, so we treat them as verified.
+UPDATE sbtc_signer.dkg_shares
+SET dkg_shares_status = 'verified';
+
+ALTER TABLE sbtc_signer.dkg_shares
+ALTER COLUMN dkg_shares_status SET NOT NULL,
+ALTER COLUMN dkg_shares_status SET DEFAULT 'unverified';
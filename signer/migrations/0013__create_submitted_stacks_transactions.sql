@@ -0,0 +1,23 @@
+CREATE TYPE sbtc_signer.stacks_tx_status AS ENUM (
+    'pending',
+    'confirmed',
+    'replaced',
+    'dropped'
+);
+
+-- Stacks transactions that have been signed by the signers and submitted
+-- to a stacks node by a coordinator. We keep the sign request around so
+-- that the transaction can be rebuilt with a higher fee.
+CREATE TABLE sbtc_signer.submitted_stacks_transactions (
+    txid BYTEA PRIMARY KEY,
+    nonce BIGINT NOT NULL,
+    tx_fee BIGINT NOT NULL,
+    sign_request BYTEA NOT NULL,
+    status sbtc_signer.stacks_tx_status NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- Index to serve queries for transactions that are still pending.
+CREATE INDEX ix_submitted_stacks_transactions_status
+    ON sbtc_signer.submitted_stacks_transactions(status);
@@ -0,0 +1,14 @@
+-- Stacks blocks whose events have been looked at, either when the stacks
+-- node posted the block to the event observer or when the block observer
+-- backfilled events that the event observer missed. There is no foreign
+-- key to stacks_blocks since the stacks node can post a block before the
+-- block observer has written it.
+CREATE TABLE sbtc_signer.stacks_block_event_scans (
+    block_hash BYTEA PRIMARY KEY,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- The events of blocks that we already know about were handled by the
+-- event observer before this table existed.
+INSERT INTO sbtc_signer.stacks_block_event_scans (block_hash)
+SELECT block_hash FROM sbtc_signer.stacks_blocks;
@@ -0,0 +1,14 @@
+-- Every webhook body that the stacks node posted to the /new_block
+-- endpoint of the event observer, exactly as it was received. Rows are
+-- only ever appended, and removed once they are older than the configured
+-- retention period, so that payloads that could not be handled can be
+-- replayed later.
+CREATE TABLE sbtc_signer.event_observer_journal (
+    id BIGSERIAL PRIMARY KEY,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+-- Index to serve the removal of payloads past the retention period.
+CREATE INDEX ix_event_observer_journal_created_at
+    ON sbtc_signer.event_observer_journal(created_at);
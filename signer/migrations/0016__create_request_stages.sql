@@ -0,0 +1,40 @@
+-- The stages that a deposit or withdrawal request goes through, in order.
+-- A request ends in either 'completed' or 'rejected'.
+CREATE TYPE sbtc_signer.request_stage AS ENUM (
+    'first_seen',
+    'decided',
+    'sweep_signed',
+    'sweep_confirmed',
+    'completed',
+    'rejected'
+);
+
+-- When this signer saw each deposit request reach each stage. A stage is
+-- only recorded the first time it is reached. There is no foreign key to
+-- deposit_requests since a request can be swept or completed in a block
+-- that we process before we hear about the request itself.
+CREATE TABLE sbtc_signer.deposit_request_stages (
+    txid BYTEA NOT NULL,
+    output_index INTEGER NOT NULL,
+    stage sbtc_signer.request_stage NOT NULL,
+    reached_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (txid, output_index, stage)
+);
+
+-- Index to serve the request backlog, which only counts requests that
+-- reached a stage recently.
+CREATE INDEX ix_deposit_request_stages_reached_at
+    ON sbtc_signer.deposit_request_stages(reached_at);
+
+-- When this signer saw each withdrawal request reach each stage. The
+-- request ID alone identifies the request here, so that a withdrawal
+-- request that was reorged into another stacks block keeps its history.
+CREATE TABLE sbtc_signer.withdrawal_request_stages (
+    request_id BIGINT NOT NULL,
+    stage sbtc_signer.request_stage NOT NULL,
+    reached_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (request_id, stage)
+);
+
+CREATE INDEX ix_withdrawal_request_stages_reached_at
+    ON sbtc_signer.withdrawal_request_stages(reached_at);
@@ -0,0 +1,33 @@
+-- An append-only log of every signature share and stacks transaction
+-- signature that this signer produced. Each entry commits to the one
+-- before it through prev_hash, so removing or changing an entry breaks
+-- the chain for every entry after it.
+CREATE TABLE sbtc_signer.signing_audit_log (
+    -- The position of the entry in the log, starting at zero.
+    position BIGINT PRIMARY KEY CHECK (position >= 0),
+    -- The entry_hash of the previous entry, or 32 zero bytes for the
+    -- first entry.
+    prev_hash BYTEA NOT NULL,
+    -- SHA-256 over the position, prev_hash and record of this entry.
+    entry_hash BYTEA NOT NULL UNIQUE,
+    -- The JSON encoded record of what was signed and why.
+    record BYTEA NOT NULL,
+    -- An ECDSA signature over entry_hash by the signer's private key.
+    signature BYTEA NOT NULL,
+    -- a timestamp of when this record was created in the database.
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION sbtc_signer.reject_signing_audit_log_changes() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'the signing audit log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER signing_audit_log_no_updates
+    BEFORE UPDATE OR DELETE ON sbtc_signer.signing_audit_log
+    FOR EACH ROW EXECUTE FUNCTION sbtc_signer.reject_signing_audit_log_changes();
+
+CREATE TRIGGER signing_audit_log_no_truncate
+    BEFORE TRUNCATE ON sbtc_signer.signing_audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION sbtc_signer.reject_signing_audit_log_changes();
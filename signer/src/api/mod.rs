@@ -2,11 +2,21 @@
 //!
 
 mod new_block;
+mod replay;
 mod router;
 mod status;
+mod sweep;
 
 pub use new_block::new_block_handler;
+pub use new_block::sbtc_registry_identifier;
+pub use new_block::write_registry_event;
+pub use replay::replay_events;
+pub use replay::ReplayOutcome;
+pub use replay::ReplayedEvent;
+pub use replay::ReplayedPayload;
+pub use router::get_admin_router;
 pub use router::get_router;
+pub use sweep::sweep_dry_run_handler;
 
 /// A struct with state data necessary for runtime operation.
 #[derive(Debug, Clone)]
@@ -4,6 +4,7 @@
 
 use axum::extract::State;
 use axum::http::StatusCode;
+use clarity::types::chainstate::StacksBlockId;
 use clarity::vm::representations::ContractName;
 use clarity::vm::types::QualifiedContractIdentifier;
 use clarity::vm::types::StandardPrincipalData;
@@ -16,17 +17,25 @@ use emily_client::models::UpdateWithdrawalsResponse;
 use emily_client::models::WithdrawalParameters;
 use emily_client::models::WithdrawalUpdate;
 use futures::FutureExt;
+use sbtc::events::EventError;
 use sbtc::events::RegistryEvent;
 use sbtc::events::TxInfo;
+use sbtc::events::UpdateProtocolContractEvent;
 use std::sync::OnceLock;
+use time::OffsetDateTime;
 
 use crate::context::Context;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::metrics::Metrics;
 use crate::metrics::STACKS_BLOCKCHAIN;
+use crate::request_lifecycle;
+use crate::stacks::contracts::ActiveContracts;
+use crate::stacks::contracts::ProtocolRole;
+use crate::storage::model::BitcoinTxId;
 use crate::storage::model::CompletedDepositEvent;
 use crate::storage::model::KeyRotationEvent;
+use crate::storage::model::RequestStage;
 use crate::storage::model::RotateKeysTransaction;
 use crate::storage::model::StacksBlock;
 use crate::storage::model::StacksTxId;
@@ -36,6 +45,7 @@ use crate::storage::model::WithdrawalRejectEvent;
 use crate::storage::DbRead;
 use crate::storage::DbWrite;
 use sbtc::webhooks::NewBlockEvent;
+use sbtc::webhooks::TransactionEvent;
 
 use super::ApiState;
 use super::SBTC_REGISTRY_CONTRACT_NAME;
@@ -99,13 +109,11 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
 
     let api = state.0;
 
-    let registry_address = SBTC_REGISTRY_IDENTIFIER.get_or_init(|| {
-        // Although the following line can panic, our unit tests hit this
-        // code path so if tests pass then this will work in production.
-        let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
-        let issuer = StandardPrincipalData::from(api.ctx.config().signer.deployer);
-        QualifiedContractIdentifier::new(issuer, contract_name)
-    });
+    let registry_address = sbtc_registry_identifier(&api.ctx);
+
+    // Every body is journaled before it is parsed, so that the ones that
+    // we fail to handle can be replayed once the issue is fixed.
+    journal_payload(&api.ctx, &body).await;
 
     let new_block_event: NewBlockEvent = match serde_json::from_str(&body) {
         Ok(value) => value,
@@ -119,12 +127,7 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
         }
     };
 
-    let stacks_chaintip = StacksBlock {
-        block_hash: new_block_event.index_block_hash.into(),
-        block_height: new_block_event.block_height,
-        parent_hash: new_block_event.parent_index_block_hash.into(),
-        bitcoin_anchor: new_block_event.burn_block_hash.into(),
-    };
+    let stacks_chaintip = new_block_stacks_block(&new_block_event);
     let block_id = new_block_event.index_block_hash;
 
     let span = tracing::span::Span::current();
@@ -135,20 +138,24 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
 
     tracing::debug!("received a new block event from stacks-core");
 
-    // Although transactions can fail, only successful transactions emit
-    // sBTC print events, since those events are emitted at the very end of
-    // the contract call.
-    let events = new_block_event
-        .events
-        .into_iter()
-        .filter(|x| x.committed)
-        .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid)))
-        .filter(|(ev, _)| &ev.contract_identifier == registry_address && ev.topic == "print")
-        .collect::<Vec<_>>();
+    let events = registry_events(new_block_event.events, block_id, registry_address);
+
+    // Any of our own transactions that were included in this block,
+    // whether or not they succeeded, have consumed their nonce, so we
+    // stop tracking them for resubmission.
+    let txids: Vec<StacksTxId> = new_block_event
+        .transactions
+        .iter()
+        .map(|receipt| receipt.txid.into())
+        .collect();
+    if let Err(error) = api.ctx.get_storage_mut().confirm_stacks_txs(&txids).await {
+        tracing::warn!(%error, "could not mark submitted stacks transactions as confirmed");
+    }
 
     if events.is_empty() {
         // If there are no events to process, we return early with a 200 OK
         // status code so that the node does not retry the webhook.
+        record_event_scan(&api.ctx, &stacks_chaintip).await;
         return StatusCode::OK;
     }
 
@@ -159,37 +166,46 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
     let mut updated_withdrawals = Vec::new();
     let mut created_withdrawals = Vec::new();
 
-    for (ev, txid) in events {
-        let tx_info = TxInfo {
-            txid: sbtc::events::StacksTxid(txid.0),
-            block_id,
-        };
-        let res = match RegistryEvent::try_new(ev.value, tx_info) {
+    // The webhook is called as the block is processed, so the events
+    // reached their stage just now.
+    let reached_at = OffsetDateTime::now_utc();
+
+    // The block is only recorded as scanned once every one of its events
+    // has been written, otherwise the backfill tries it again.
+    let mut wrote_every_event = true;
+
+    for (tx_info, event) in events {
+        let res = match event {
             Ok(RegistryEvent::CompletedDeposit(event)) => {
-                handle_completed_deposit(&api.ctx, event.into(), &stacks_chaintip)
+                handle_completed_deposit(&api.ctx, event.into(), &stacks_chaintip, reached_at)
                     .await
                     .map(|x| completed_deposits.push(x))
             }
             Ok(RegistryEvent::WithdrawalAccept(event)) => {
-                handle_withdrawal_accept(&api.ctx, event.into(), &stacks_chaintip)
+                handle_withdrawal_accept(&api.ctx, event.into(), &stacks_chaintip, reached_at)
                     .await
                     .map(|x| updated_withdrawals.push(x))
             }
             Ok(RegistryEvent::WithdrawalReject(event)) => {
-                handle_withdrawal_reject(&api.ctx, event.into(), &stacks_chaintip)
+                handle_withdrawal_reject(&api.ctx, event.into(), &stacks_chaintip, reached_at)
                     .await
                     .map(|x| updated_withdrawals.push(x))
             }
             Ok(RegistryEvent::WithdrawalCreate(event)) => {
-                handle_withdrawal_create(&api.ctx, event.into(), stacks_chaintip.block_height)
+                let block_height = stacks_chaintip.block_height;
+                handle_withdrawal_create(&api.ctx, event.into(), block_height, reached_at)
                     .await
                     .map(|x| created_withdrawals.push(x))
             }
             Ok(RegistryEvent::KeyRotation(event)) => {
                 handle_key_rotation(&api.ctx, event.into(), tx_info.txid.into()).await
             }
+            Ok(RegistryEvent::UpdateProtocolContract(event)) => {
+                handle_update_protocol_contract(&api.ctx, event)
+            }
             Err(error) => {
-                tracing::error!(%error, %txid, "got an error when transforming the event ClarityValue");
+                tracing::error!(%error, txid = %tx_info.txid, "got an error when transforming the event ClarityValue");
+                wrote_every_event = false;
                 continue;
             }
         };
@@ -206,9 +222,14 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
         // ensure that the update is sent to Emily.
         } else if let Err(error) = res {
             tracing::error!(%error, "got an error when processing event");
+            wrote_every_event = false;
         }
     }
 
+    if wrote_every_event {
+        record_event_scan(&api.ctx, &stacks_chaintip).await;
+    }
+
     // Send the updates to Emily.
     let emily_client = api.ctx.get_emily_client();
 
@@ -257,6 +278,133 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
     StatusCode::OK
 }
 
+/// Return the identifier of the sbtc-registry smart contract, the only
+/// contract whose events we act on.
+pub fn sbtc_registry_identifier(ctx: &impl Context) -> &'static QualifiedContractIdentifier {
+    SBTC_REGISTRY_IDENTIFIER.get_or_init(|| {
+        // Although the following line can panic, our unit tests hit this
+        // code path so if tests pass then this will work in production.
+        let contract_name = ContractName::from(SBTC_REGISTRY_CONTRACT_NAME);
+        let issuer = StandardPrincipalData::from(ctx.config().signer.deployer);
+        QualifiedContractIdentifier::new(issuer, contract_name)
+    })
+}
+
+/// The stacks block that a new block webhook is about.
+pub(super) fn new_block_stacks_block(new_block_event: &NewBlockEvent) -> StacksBlock {
+    StacksBlock {
+        block_hash: new_block_event.index_block_hash.into(),
+        block_height: new_block_event.block_height,
+        parent_hash: new_block_event.parent_index_block_hash.into(),
+        bitcoin_anchor: new_block_event.burn_block_hash.into(),
+    }
+}
+
+/// Decode the print events of the sbtc-registry contract among the events
+/// of a new block webhook, along with the transaction that emitted each
+/// of them. Events from any other contract are dropped.
+pub(super) fn registry_events(
+    events: Vec<TransactionEvent>,
+    block_id: StacksBlockId,
+    registry_address: &QualifiedContractIdentifier,
+) -> Vec<(TxInfo, Result<RegistryEvent, EventError>)> {
+    // Although transactions can fail, only successful transactions emit
+    // sBTC print events, since those events are emitted at the very end of
+    // the contract call.
+    events
+        .into_iter()
+        .filter(|x| x.committed)
+        .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid)))
+        .filter(|(ev, _)| &ev.contract_identifier == registry_address && ev.topic == "print")
+        .map(|(ev, txid)| {
+            let tx_info = TxInfo {
+                txid: sbtc::events::StacksTxid(txid.0),
+                block_id,
+            };
+            (tx_info, RegistryEvent::try_new(ev.value, tx_info))
+        })
+        .collect()
+}
+
+/// Append the webhook body to the event observer journal. Failing to do
+/// so only means that the body cannot be replayed, so the error is logged
+/// rather than returned.
+async fn journal_payload(ctx: &impl Context, body: &str) {
+    if let Err(error) = ctx
+        .get_storage_mut()
+        .write_event_observer_payload(body)
+        .await
+    {
+        tracing::error!(%error, "could not write the webhook body to the event observer journal");
+    }
+}
+
+/// Record that the events of the given block have been handled, so that
+/// the registry event backfill does not fetch them again. Failing to do so
+/// only means that the events may be written again, so the error is
+/// logged rather than returned.
+async fn record_event_scan(ctx: &impl Context, block: &StacksBlock) {
+    let result = ctx
+        .get_storage_mut()
+        .write_stacks_block_event_scan(&block.block_hash)
+        .await;
+    if let Err(error) = result {
+        tracing::warn!(%error, "could not record the event scan of the stacks block");
+    }
+}
+
+/// Write a registry event that was emitted by the given transaction in
+/// the given block to the database, in the same way that events from the `POST /new_block`
+/// webhook are written.
+///
+/// This is for events that were not delivered by the webhook, so Emily
+/// is not updated; Emily learns about these events from the other
+/// signers or from its own reconciliation. A completed deposit for a
+/// deposit request that we do not know about is still written.
+///
+/// The events are handled well after they happened, so the caller gives
+/// the time that the events' requests reached their stage, usually the
+/// time of the block.
+pub async fn write_registry_event(
+    ctx: &impl Context,
+    event: RegistryEvent,
+    stacks_txid: StacksTxId,
+    stacks_block: &StacksBlock,
+    reached_at: OffsetDateTime,
+) -> Result<(), Error> {
+    let res = match event {
+        RegistryEvent::CompletedDeposit(event) => {
+            handle_completed_deposit(ctx, event.into(), stacks_block, reached_at)
+                .await
+                .map(|_| ())
+        }
+        RegistryEvent::WithdrawalAccept(event) => {
+            handle_withdrawal_accept(ctx, event.into(), stacks_block, reached_at)
+                .await
+                .map(|_| ())
+        }
+        RegistryEvent::WithdrawalReject(event) => {
+            handle_withdrawal_reject(ctx, event.into(), stacks_block, reached_at)
+                .await
+                .map(|_| ())
+        }
+        RegistryEvent::WithdrawalCreate(event) => {
+            handle_withdrawal_create(ctx, event.into(), stacks_block.block_height, reached_at)
+                .await
+                .map(|_| ())
+        }
+        RegistryEvent::KeyRotation(event) => {
+            handle_key_rotation(ctx, event.into(), stacks_txid).await
+        }
+        RegistryEvent::UpdateProtocolContract(event) => handle_update_protocol_contract(ctx, event),
+    };
+
+    match res {
+        Err(Error::MissingDepositRequest(_)) => Ok(()),
+        res => res,
+    }
+}
+
 /// Processes a completed deposit event by updating relevant deposit records
 /// and preparing data to be sent to Emily.
 ///
@@ -265,6 +413,7 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
 /// - `event`: The deposit event to be processed.
 /// - `stacks_chaintip`: Current chaintip information for the Stacks blockchain,
 ///   including block height and hash.
+/// - `reached_at`: The time that the deposit request was completed.
 ///
 /// # Returns
 /// - `Result<DepositUpdate, Error>`: On success, returns a `DepositUpdate` struct containing
@@ -278,10 +427,15 @@ async fn handle_completed_deposit(
     ctx: &impl Context,
     event: CompletedDepositEvent,
     stacks_chaintip: &StacksBlock,
+    reached_at: OffsetDateTime,
 ) -> Result<DepositUpdate, Error> {
-    ctx.get_storage_mut()
-        .write_completed_deposit_event(&event)
-        .await?;
+    let db = ctx.get_storage_mut();
+    db.write_completed_deposit_event(&event).await?;
+
+    let txid = BitcoinTxId::from(event.outpoint.txid);
+    let stage = RequestStage::Completed;
+    let vout = event.outpoint.vout;
+    request_lifecycle::record_deposit_stage(&db, &txid, vout, stage, reached_at).await;
 
     tracing::debug!(topic = "completed-deposit", "handled stacks event");
 
@@ -337,10 +491,13 @@ async fn handle_withdrawal_accept(
     ctx: &impl Context,
     event: WithdrawalAcceptEvent,
     stacks_chaintip: &StacksBlock,
+    reached_at: OffsetDateTime,
 ) -> Result<WithdrawalUpdate, Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_accept_event(&event)
-        .await?;
+    let db = ctx.get_storage_mut();
+    db.write_withdrawal_accept_event(&event).await?;
+
+    let stage = RequestStage::Completed;
+    request_lifecycle::record_withdrawal_stage(&db, event.request_id, stage, reached_at).await;
 
     tracing::debug!(topic = "withdrawal-accept", "handled stacks event");
 
@@ -368,6 +525,7 @@ async fn handle_withdrawal_accept(
 /// - `ctx`: Shared application context containing configuration and database access.
 /// - `event`: The withdrawal creation event to be processed.
 /// - `stacks_block_height`: The height of the Stacks block containing the withdrawal tx.
+/// - `reached_at`: The time that the withdrawal request was created.
 ///
 /// # Returns
 /// - `Result<CreateWithdrawalRequestBody, Error>`: On success, returns a `CreateWithdrawalRequestBody`
@@ -380,10 +538,13 @@ async fn handle_withdrawal_create(
     ctx: &impl Context,
     event: WithdrawalCreateEvent,
     stacks_block_height: u64,
+    reached_at: OffsetDateTime,
 ) -> Result<CreateWithdrawalRequestBody, Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_create_event(&event)
-        .await?;
+    let db = ctx.get_storage_mut();
+    db.write_withdrawal_create_event(&event).await?;
+
+    let stage = RequestStage::FirstSeen;
+    request_lifecycle::record_withdrawal_stage(&db, event.request_id, stage, reached_at).await;
 
     tracing::debug!(topic = "withdrawal-create", "handled stacks event");
 
@@ -391,6 +552,7 @@ async fn handle_withdrawal_create(
         amount: event.amount,
         parameters: Box::new(WithdrawalParameters { max_fee: event.max_fee }),
         recipient: event.recipient.to_string(),
+        sender: Some(Some(event.sender.to_string())),
         request_id: event.request_id,
         stacks_block_hash: event.block_id.to_hex(),
         stacks_block_height,
@@ -405,6 +567,7 @@ async fn handle_withdrawal_create(
 /// - `event`: The withdrawal rejection event to be processed.
 /// - `stacks_chaintip`: Information about the current chaintip of the Stacks blockchain,
 ///   such as block height and hash.
+/// - `reached_at`: The time that the withdrawal request was rejected.
 ///
 /// # Returns
 /// - `Result<WithdrawalUpdate, Error>`: Returns a `WithdrawalUpdate` with rejection information.
@@ -417,10 +580,13 @@ async fn handle_withdrawal_reject(
     ctx: &impl Context,
     event: WithdrawalRejectEvent,
     stacks_chaintip: &StacksBlock,
+    reached_at: OffsetDateTime,
 ) -> Result<WithdrawalUpdate, Error> {
-    ctx.get_storage_mut()
-        .write_withdrawal_reject_event(&event)
-        .await?;
+    let db = ctx.get_storage_mut();
+    db.write_withdrawal_reject_event(&event).await?;
+
+    let stage = RequestStage::Rejected;
+    request_lifecycle::record_withdrawal_stage(&db, event.request_id, stage, reached_at).await;
 
     tracing::debug!(topic = "withdrawal-reject", "handled stacks event");
 
@@ -461,6 +627,30 @@ async fn handle_key_rotation(
     Ok(())
 }
 
+/// The sbtc-registry only accepts calls from the active protocol contract
+/// for each role, so we switch over to the new contract as soon as we see
+/// it get activated.
+#[tracing::instrument(skip_all, fields(
+    stacks_txid = %event.txid,
+    new_contract = %event.new_contract
+))]
+fn handle_update_protocol_contract(
+    ctx: &impl Context,
+    event: UpdateProtocolContractEvent,
+) -> Result<(), Error> {
+    let Some(role) = ProtocolRole::from_flag(event.contract_type) else {
+        return Err(Error::UnknownProtocolContract(event.new_contract));
+    };
+    let deployer = &ctx.config().signer.deployer;
+    let version = ActiveContracts::parse_contract(deployer, role, &event.new_contract)?;
+
+    ctx.state().set_active_contract_version(role, version);
+
+    tracing::info!(%role, %version, topic = "update-protocol-contract", "handled stacks event");
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -482,6 +672,7 @@ mod tests {
     use tower::ServiceExt;
 
     use crate::api::get_router;
+    use crate::stacks::contracts::ContractVersion;
     use crate::storage::in_memory::Store;
     use crate::storage::model::DepositRequest;
     use crate::storage::model::ScriptPubKey;
@@ -561,6 +752,10 @@ mod tests {
 
         // Now there should be something here
         assert!(!table_is_empty(db.lock().await));
+        // And the block does not need to be backfilled.
+        assert_eq!(db.lock().await.stacks_block_event_scans.len(), 1);
+        // And it is journaled.
+        assert_eq!(db.lock().await.event_observer_journal.len(), 1);
     }
 
     #[test_case(COMPLETED_DEPOSIT_WEBHOOK, |db| db.completed_deposit_events.get(&OutPoint::null()).is_none(); "completed-deposit")]
@@ -710,7 +905,8 @@ mod tests {
             last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
             last_update_height: stacks_chaintip.block_height,
         };
-        let res = handle_completed_deposit(&ctx, event, stacks_chaintip).await;
+        let res =
+            handle_completed_deposit(&ctx, event, stacks_chaintip, OffsetDateTime::now_utc()).await;
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), expectation);
         let db = db.lock().await;
@@ -758,7 +954,8 @@ mod tests {
             sweep_block_height: bitcoin_block.block_height,
             sweep_txid: txid.into(),
         };
-        let res = handle_completed_deposit(&ctx, event, stacks_chaintip).await;
+        let res =
+            handle_completed_deposit(&ctx, event, stacks_chaintip, OffsetDateTime::now_utc()).await;
         assert!(res.is_err());
         assert!(matches!(
             res.unwrap_err(),
@@ -769,6 +966,63 @@ mod tests {
         assert!(db.completed_deposit_events.get(&outpoint).is_some());
     }
 
+    /// Backfilled completed deposits are written even when we do not know
+    /// about the deposit request, since there is no Emily update to make.
+    #[tokio::test]
+    async fn write_registry_event_ignores_missing_deposit_requests() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let test_params = crate::testing::storage::model::Params {
+            num_bitcoin_blocks: 1,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 1,
+            num_withdraw_requests_per_block: 1,
+            num_signers_per_request: 0,
+        };
+        let db = ctx.inner_storage();
+        let test_data = TestData::generate(&mut rng, &[], &test_params);
+
+        let txid = test_data.bitcoin_transactions[0].txid;
+        let bitcoin_block = &test_data.bitcoin_blocks[0];
+        let stacks_block = &test_data.stacks_blocks[0];
+        let stacks_txid = sbtc::events::StacksTxid([1; 32]);
+
+        let outpoint = OutPoint { txid: *txid, vout: 0 };
+        let event = sbtc::events::CompletedDepositEvent {
+            outpoint,
+            txid: stacks_txid,
+            block_id: stacks_block.block_hash.into(),
+            amount: 100,
+            sweep_block_hash: *bitcoin_block.block_hash,
+            sweep_block_height: bitcoin_block.block_height,
+            sweep_txid: *txid,
+        };
+        let event = RegistryEvent::CompletedDeposit(event);
+        let block_time = OffsetDateTime::now_utc() - time::Duration::days(1);
+        write_registry_event(&ctx, event, stacks_txid.into(), stacks_block, block_time)
+            .await
+            .unwrap();
+
+        // The deposit was completed when the block was produced, not when
+        // we got around to writing the event.
+        let stages = ctx
+            .get_storage()
+            .get_deposit_request_stages(&txid, outpoint.vout)
+            .await
+            .unwrap();
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].stage, RequestStage::Completed);
+        assert_eq!(stages[0].reached_at, block_time);
+
+        let db = db.lock().await;
+        assert!(db.completed_deposit_events.get(&outpoint).is_some());
+    }
+
     /// Tests handling a withdrawal acceptance event.
     /// This function validates that when a withdrawal is accepted, the handler
     /// correctly updates the database and returns the expected response.
@@ -829,7 +1083,8 @@ mod tests {
             last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
             last_update_height: stacks_chaintip.block_height,
         };
-        let res = handle_withdrawal_accept(&ctx, event, stacks_chaintip).await;
+        let res =
+            handle_withdrawal_accept(&ctx, event, stacks_chaintip, OffsetDateTime::now_utc()).await;
 
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), expectation);
@@ -883,12 +1138,19 @@ mod tests {
             amount: event.amount,
             parameters: Box::new(WithdrawalParameters { max_fee: event.max_fee }),
             recipient: event.recipient.to_string(),
+            sender: Some(Some(event.sender.to_string())),
             request_id: event.request_id,
             stacks_block_hash: stacks_first_block.block_hash.to_hex(),
             stacks_block_height: stacks_first_block.block_height,
         };
 
-        let res = handle_withdrawal_create(&ctx, event, stacks_first_block.block_height).await;
+        let res = handle_withdrawal_create(
+            &ctx,
+            event,
+            stacks_first_block.block_height,
+            OffsetDateTime::now_utc(),
+        )
+        .await;
 
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), expectation);
@@ -946,10 +1208,22 @@ mod tests {
             status_message: "Rejected".to_string(),
         };
 
-        let res = handle_withdrawal_reject(&ctx, event, stacks_chaintip).await;
+        let res =
+            handle_withdrawal_reject(&ctx, event, stacks_chaintip, OffsetDateTime::now_utc()).await;
 
         assert!(res.is_ok());
         assert_eq!(res.unwrap(), expectation);
+
+        // Rejections get their own stage so that they do not count as
+        // completed requests.
+        let stages = ctx
+            .get_storage()
+            .get_withdrawal_request_stages(expectation.request_id)
+            .await
+            .unwrap();
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].stage, RequestStage::Rejected);
+
         let db = db.lock().await;
         assert_eq!(db.withdrawal_reject_events.len(), 1);
         assert!(db
@@ -988,6 +1262,49 @@ mod tests {
         assert!(db.rotate_keys_transactions.get(&txid).is_some());
     }
 
+    #[test]
+    fn test_handle_update_protocol_contract() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let deployer = ctx.config().signer.deployer;
+        let contract = QualifiedContractIdentifier::new(
+            deployer.into(),
+            ContractName::from("sbtc-deposit-v2"),
+        );
+        let event = UpdateProtocolContractEvent {
+            txid: sbtc::events::StacksTxid([0; 32]),
+            block_id: StacksBlockId([0; 32]),
+            contract_type: ProtocolRole::Deposit.flag(),
+            new_contract: PrincipalData::Contract(contract),
+        };
+
+        handle_update_protocol_contract(&ctx, event).unwrap();
+
+        let active_contracts = ctx.state().active_contracts();
+        assert_eq!(active_contracts.deposit, ContractVersion::V2);
+        assert_eq!(active_contracts.withdrawal, ContractVersion::V1);
+        assert_eq!(active_contracts.governance, ContractVersion::V1);
+
+        // A contract that was not deployed by the deployer is rejected
+        // and leaves the active contracts alone.
+        let contract = QualifiedContractIdentifier::new(
+            StandardPrincipalData::transient(),
+            ContractName::from("sbtc-withdrawal-v2"),
+        );
+        let event = UpdateProtocolContractEvent {
+            txid: sbtc::events::StacksTxid([0; 32]),
+            block_id: StacksBlockId([0; 32]),
+            contract_type: ProtocolRole::Withdrawal.flag(),
+            new_contract: PrincipalData::Contract(contract),
+        };
+
+        assert!(handle_update_protocol_contract(&ctx, event).is_err());
+        assert_eq!(ctx.state().active_contracts(), active_contracts);
+    }
+
     #[test_case(EVENT_OBSERVER_BODY_LIMIT, true; "event within limit")]
     #[test_case(EVENT_OBSERVER_BODY_LIMIT + 1, false; "event over limit")]
     #[tokio::test]
@@ -1037,6 +1354,32 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn every_body_is_journaled() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let db = ctx.inner_storage();
+
+        // A block without sbtc-registry events is journaled.
+        let mut block: serde_json::Value = serde_json::from_str(WITHDRAWAL_CREATE_WEBHOOK).unwrap();
+        block["events"] = serde_json::Value::Array(Vec::new());
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, block.to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        // And so is a body that does not parse.
+        let state = State(ApiState { ctx: ctx.clone() });
+        let res = new_block_handler(state, "not a webhook".to_string()).await;
+        assert_eq!(res, StatusCode::OK);
+
+        let store = db.lock().await;
+        assert_eq!(store.event_observer_journal.len(), 2);
+        assert_eq!(store.event_observer_journal[0].body, block.to_string());
+        assert_eq!(store.event_observer_journal[1].body, "not a webhook");
+    }
+
     #[tokio::test]
     async fn test_invalid_event() {
         let mut ctx = TestContext::builder()
@@ -1084,5 +1427,8 @@ mod tests {
         // But we expect the second (valid) event to be processed anyway
         assert_eq!(res, StatusCode::OK);
         assert!(!db.lock().await.rotate_keys_transactions.is_empty());
+        // The block is left for the backfill, since one of its events was
+        // not written.
+        assert!(db.lock().await.stacks_block_event_scans.is_empty());
     }
 }
@@ -0,0 +1,282 @@
+//! This module replays the webhook bodies in the event observer journal
+//! through the same parsing that the `POST /new_block` endpoint uses.
+//!
+//! Replaying is meant for incident response: it shows how each journaled
+//! payload parses with the current code, and it can write the events of
+//! the payloads to the database after a parsing bug has been fixed.
+
+use std::fmt;
+
+use sbtc::events::RegistryEvent;
+use sbtc::webhooks::NewBlockEvent;
+use time::OffsetDateTime;
+
+use crate::context::Context;
+use crate::error::Error;
+use crate::storage::model::StacksBlock;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+
+use super::new_block::new_block_stacks_block;
+use super::new_block::registry_events;
+use super::new_block::sbtc_registry_identifier;
+use super::new_block::write_registry_event;
+
+/// The number of payloads read from the journal at a time.
+const REPLAY_PAGE_SIZE: u32 = 100;
+
+/// How a journaled payload parsed when it was replayed.
+#[derive(Debug)]
+pub struct ReplayedPayload {
+    /// The ID of the payload in the journal.
+    pub id: i64,
+    /// When the payload was received, in seconds since the unix epoch.
+    pub created_at: u64,
+    /// What the payload turned out to be.
+    pub outcome: ReplayOutcome,
+}
+
+/// The result of parsing a journaled payload.
+#[derive(Debug)]
+pub enum ReplayOutcome {
+    /// The payload could not be deserialized into a new block event.
+    Malformed(String),
+    /// The payload is a new block event.
+    NewBlock {
+        /// The stacks block that the event is about.
+        block: StacksBlock,
+        /// The sbtc-registry events in the block, in the order that they
+        /// were emitted.
+        events: Vec<ReplayedEvent>,
+    },
+}
+
+/// The result of decoding, and maybe writing, one sbtc-registry event.
+#[derive(Debug)]
+pub struct ReplayedEvent {
+    /// The transaction that emitted the event.
+    pub txid: sbtc::events::StacksTxid,
+    /// The topic of the event when it could be decoded, or why it could
+    /// not be decoded or written.
+    pub result: Result<&'static str, String>,
+}
+
+impl fmt::Display for ReplayedPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} (received at {}): ", self.id, self.created_at)?;
+        match &self.outcome {
+            ReplayOutcome::Malformed(error) => writeln!(f, "malformed payload: {error}"),
+            ReplayOutcome::NewBlock { block, events } => {
+                writeln!(
+                    f,
+                    "stacks block {} at height {} with {} sbtc-registry event(s)",
+                    block.block_hash,
+                    block.block_height,
+                    events.len()
+                )?;
+                for event in events {
+                    match &event.result {
+                        Ok(topic) => writeln!(f, "  {} {topic}", event.txid)?,
+                        Err(error) => writeln!(f, "  {} error: {error}", event.txid)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The topic of the print event that the registry event was decoded from.
+fn topic(event: &RegistryEvent) -> &'static str {
+    match event {
+        RegistryEvent::CompletedDeposit(_) => "completed-deposit",
+        RegistryEvent::WithdrawalAccept(_) => "withdrawal-accept",
+        RegistryEvent::WithdrawalReject(_) => "withdrawal-reject",
+        RegistryEvent::WithdrawalCreate(_) => "withdrawal-create",
+        RegistryEvent::KeyRotation(_) => "key-rotation",
+        RegistryEvent::UpdateProtocolContract(_) => "update-protocol-contract",
+    }
+}
+
+/// Replay the journaled payloads with IDs from `from_id` up to and
+/// including `to_id`, calling `report` with the outcome of each one in
+/// the order that they were received.
+///
+/// Nothing is written unless `apply` is set, in which case the decoded
+/// events are written to the database in the same way as backfilled
+/// events, and the blocks whose events were all written are marked as
+/// scanned. Emily is not updated.
+pub async fn replay_events<C, F>(
+    ctx: &C,
+    from_id: i64,
+    to_id: Option<i64>,
+    apply: bool,
+    mut report: F,
+) -> Result<(), Error>
+where
+    C: Context,
+    F: FnMut(ReplayedPayload),
+{
+    let registry_address = sbtc_registry_identifier(ctx);
+    let db = ctx.get_storage_mut();
+
+    let mut next_id = from_id;
+    loop {
+        let payloads = db
+            .get_event_observer_payloads(next_id, REPLAY_PAGE_SIZE)
+            .await?;
+        let Some(last) = payloads.last() else {
+            return Ok(());
+        };
+        next_id = last.id + 1;
+
+        for payload in payloads {
+            if to_id.is_some_and(|to_id| payload.id > to_id) {
+                return Ok(());
+            }
+
+            let new_block_event: NewBlockEvent = match serde_json::from_str(&payload.body) {
+                Ok(event) => event,
+                Err(error) => {
+                    report(ReplayedPayload {
+                        id: payload.id,
+                        created_at: payload.created_at,
+                        outcome: ReplayOutcome::Malformed(error.to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            let block = new_block_stacks_block(&new_block_event);
+            let block_id = new_block_event.index_block_hash;
+            // The payload was received when the block was processed, which
+            // is when the requests in its events reached their stage.
+            let received_at = OffsetDateTime::from_unix_timestamp(payload.created_at as i64)
+                .map_err(Error::InvalidTimestamp)?;
+            let mut events = Vec::new();
+            for (tx_info, event) in
+                registry_events(new_block_event.events, block_id, registry_address)
+            {
+                let result = match event {
+                    Ok(event) if apply => {
+                        let topic = topic(&event);
+                        write_registry_event(ctx, event, tx_info.txid.into(), &block, received_at)
+                            .await
+                            .map(|_| topic)
+                            .map_err(|error| error.to_string())
+                    }
+                    Ok(event) => Ok(topic(&event)),
+                    Err(error) => Err(error.to_string()),
+                };
+                events.push(ReplayedEvent { txid: tx_info.txid, result });
+            }
+
+            // A block is only marked as scanned when all of its events
+            // were written, so that the backfill picks up the rest.
+            if apply && events.iter().all(|event| event.result.is_ok()) {
+                db.write_stacks_block_event_scan(&block.block_hash).await?;
+            }
+
+            report(ReplayedPayload {
+                id: payload.id,
+                created_at: payload.created_at,
+                outcome: ReplayOutcome::NewBlock { block, events },
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::storage::DbWrite as _;
+    use crate::testing::context::*;
+
+    const WITHDRAWAL_CREATE_WEBHOOK: &str =
+        include_str!("../../tests/fixtures/withdrawal-create-event.json");
+
+    const ROTATE_KEYS_AND_INVALID_EVENT_WEBHOOK: &str =
+        include_str!("../../tests/fixtures/rotate-keys-and-invalid-event.json");
+
+    #[tokio::test]
+    async fn replay_reports_each_payload_and_applies_events() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let db = ctx.get_storage_mut();
+
+        db.write_event_observer_payload("not a webhook")
+            .await
+            .unwrap();
+        db.write_event_observer_payload(WITHDRAWAL_CREATE_WEBHOOK)
+            .await
+            .unwrap();
+
+        // Without `apply` nothing gets written.
+        let mut replayed = Vec::new();
+        replay_events(&ctx, 0, None, false, |payload| replayed.push(payload))
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.len(), 2);
+        assert!(matches!(replayed[0].outcome, ReplayOutcome::Malformed(_)));
+        let ReplayOutcome::NewBlock { events, .. } = &replayed[1].outcome else {
+            panic!("expected a new block, got {:?}", replayed[1].outcome);
+        };
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].result, Ok("withdrawal-create"));
+        assert!(ctx
+            .inner_storage()
+            .lock()
+            .await
+            .withdrawal_create_events
+            .is_empty());
+
+        // Replaying only the second payload with `apply` writes its event.
+        let mut replayed = Vec::new();
+        replay_events(&ctx, 2, Some(2), true, |payload| replayed.push(payload))
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].id, 2);
+        let store = ctx.inner_storage();
+        let store = store.lock().await;
+        assert_eq!(store.withdrawal_create_events.len(), 1);
+        assert_eq!(store.stacks_block_event_scans.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn blocks_with_events_that_fail_are_not_marked_as_scanned() {
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let db = ctx.get_storage_mut();
+
+        db.write_event_observer_payload(ROTATE_KEYS_AND_INVALID_EVENT_WEBHOOK)
+            .await
+            .unwrap();
+
+        let mut replayed = Vec::new();
+        replay_events(&ctx, 0, None, true, |payload| replayed.push(payload))
+            .await
+            .unwrap();
+
+        assert_eq!(replayed.len(), 1);
+        let ReplayOutcome::NewBlock { events, .. } = &replayed[0].outcome else {
+            panic!("expected a new block, got {:?}", replayed[0].outcome);
+        };
+        assert!(events[0].result.is_err());
+        assert_eq!(events[1].result, Ok("key-rotation"));
+
+        // The valid event is written, but the block is left for the
+        // backfill since one of its events was not.
+        let store = ctx.inner_storage();
+        let store = store.lock().await;
+        assert_eq!(store.rotate_keys_transactions.len(), 1);
+        assert!(store.stacks_block_event_scans.is_empty());
+    }
+}
@@ -11,7 +11,7 @@ use crate::context::Context;
 
 use axum::http::StatusCode;
 
-use super::{new_block, status, ApiState};
+use super::{new_block, status, sweep, ApiState};
 
 async fn new_attachment_handler() -> StatusCode {
     StatusCode::OK
@@ -31,6 +31,13 @@ pub fn get_router<C: Context + 'static>() -> Router<ApiState<C>> {
         .route("/attachments/new", post(new_attachment_handler))
 }
 
+/// Return the router for the admin API. These routes are for operators,
+/// so they are served on their own listener rather than alongside the
+/// Stacks event observer.
+pub fn get_admin_router<C: Context + 'static>() -> Router<ApiState<C>> {
+    Router::new().route("/sweep/dry-run", get(sweep::sweep_dry_run_handler))
+}
+
 #[cfg(test)]
 mod tests {
     use axum::{
@@ -61,4 +68,21 @@ mod tests {
         let response = app.oneshot(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn sweep_dry_run_is_not_served_by_the_event_observer() {
+        let context = TestContext::default_mocked();
+
+        let state = ApiState { ctx: context.clone() };
+        let app: Router = get_router().with_state(state);
+
+        let request = Request::builder()
+            .uri("/sweep/dry-run")
+            .method(Method::GET)
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -0,0 +1,27 @@
+//! This module is for the `GET /sweep/dry-run` endpoint, which reports on
+//! how the signers would handle pending requests in the next sweep.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+
+use crate::bitcoin::dry_run::SweepDryRunReport;
+use crate::context::Context;
+use crate::transaction_coordinator;
+
+use super::ApiState;
+
+/// Construct and validate the sweep transaction package against the
+/// current chain tip without signing or broadcasting it, and return the
+/// per-request report.
+pub async fn sweep_dry_run_handler(
+    State(state): State<ApiState<impl Context>>,
+) -> Result<Json<SweepDryRunReport>, StatusCode> {
+    match transaction_coordinator::sweep_dry_run(&state.ctx).await {
+        Ok(report) => Ok(Json(report)),
+        Err(error) => {
+            tracing::warn!(%error, "sweep dry run failed");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
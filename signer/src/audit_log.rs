@@ -0,0 +1,734 @@
+//! # Signing audit log
+//!
+//! Every signature share and every stacks transaction signature that a
+//! signer produces gets an entry in an append-only log in the signer's
+//! database. The entry holds a [`SigningRecord`]: the digest that was
+//! signed, the signed [`SignerMessage`] that asked for the signature
+//! along with the coordinator's signature over it, the bitcoin chain tip,
+//! the key of the coordinator and what the signer found when it validated
+//! the request.
+//!
+//! The log is a hash chain. The hash of each entry is
+//!
+//! ```text
+//! SHA-256(AUDIT_LOG_TAG || position || prev_hash || record)
+//! ```
+//!
+//! where `position` is the big-endian position of the entry in the log,
+//! `prev_hash` is the hash of the entry before it, or 32 zero bytes for
+//! the first entry, and `record` is the JSON encoded [`SigningRecord`].
+//! The signer also signs each entry hash with its private key. Changing
+//! or removing an entry therefore breaks the chain for all later entries,
+//! and rewriting the whole chain requires the signer's private key.
+//!
+//! Entries are written before the signature leaves the process, so a
+//! signature that the other signers have seen always has an entry.
+//!
+//! The chain alone cannot tell whether its newest entries were removed,
+//! so the signer also signs [`Checkpoint`]s of the head of the log. The
+//! signer logs a checkpoint every [`CHECKPOINT_INTERVAL`] entries, and
+//! one can be made on demand. A log that is verified against a checkpoint
+//! must still contain the entry that the checkpoint names.
+//!
+//! [`SignerMessage`]: crate::message::SignerMessage
+
+use blockstack_lib::types::chainstate::StacksAddress;
+use sha2::Digest as _;
+
+use crate::codec::Encode as _;
+use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::keys::PublicKeyXOnly;
+use crate::message::StacksTransactionSignRequest;
+use crate::network;
+use crate::stacks::contracts::ReqContext;
+use crate::storage::model;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::SigningAuditEntry;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksTxId;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+
+/// The tag prepended to the contents of an entry when computing its hash.
+const AUDIT_LOG_TAG: &[u8] = b"SBTC_SIGNING_AUDIT_LOG_ENTRY";
+
+/// The tag prepended to the contents of a checkpoint when computing the
+/// digest that the signer signs.
+const CHECKPOINT_TAG: &[u8] = b"SBTC_SIGNING_AUDIT_LOG_CHECKPOINT";
+
+/// The number of entries between the checkpoints that the signer logs.
+pub const CHECKPOINT_INTERVAL: u64 = 100;
+
+/// The previous hash of the first entry in the log.
+pub const GENESIS_HASH: [u8; 32] = [0; 32];
+
+/// The number of entries to read from the database at a time when
+/// reading the log.
+pub const READ_BATCH_SIZE: u32 = 1000;
+
+/// The number of times an entry is appended to the log before giving up
+/// when other writers keep taking its position first.
+const MAX_APPEND_ATTEMPTS: u32 = 10;
+
+/// The kinds of signatures that a signer produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureKind {
+    /// A share of a WSTS Schnorr signature.
+    SignatureShare,
+    /// An ECDSA signature over a stacks transaction.
+    StacksTransaction,
+}
+
+/// What a signer signed and why.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SigningRecord {
+    /// The kind of signature that was produced.
+    pub kind: SignatureKind,
+    /// When the signature was produced, as a unix timestamp in seconds.
+    pub produced_at: i64,
+    /// The public key of the signer that produced the signature.
+    pub signer_public_key: PublicKey,
+    /// The public key of the coordinator that asked for the signature.
+    pub coordinator_public_key: PublicKey,
+    /// The bitcoin chain tip that the request was made against.
+    #[serde(serialize_with = "serialize_display")]
+    pub bitcoin_chain_tip: BitcoinBlockHash,
+    /// The digest that was signed.
+    #[serde(serialize_with = "serialize_hex")]
+    pub digest: Vec<u8>,
+    /// The protobuf encoded signed message that asked for the signature,
+    /// including the coordinator's signature over it.
+    #[serde(serialize_with = "serialize_hex")]
+    pub request: Vec<u8>,
+    /// The coordinator's signature over the request, in compact form.
+    #[serde(serialize_with = "serialize_hex")]
+    pub request_signature: Vec<u8>,
+    /// What the signer found when it validated the request.
+    pub validation: SigningValidation,
+}
+
+impl SigningRecord {
+    /// Create a record of a signature over the given digest, produced now,
+    /// in response to the given signed request from the coordinator.
+    ///
+    /// The request is encoded with the sBTC codec, which is deterministic,
+    /// so the coordinator's signature can be checked against the recorded
+    /// bytes.
+    pub fn new(
+        kind: SignatureKind,
+        signer_public_key: PublicKey,
+        request: &network::Msg,
+        digest: &[u8],
+        validation: SigningValidation,
+    ) -> Self {
+        Self {
+            kind,
+            produced_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+            signer_public_key,
+            coordinator_public_key: request.signer_public_key,
+            bitcoin_chain_tip: request.bitcoin_chain_tip,
+            digest: digest.to_vec(),
+            request: request.clone().encode_to_vec(),
+            request_signature: request.signature.serialize_compact().to_vec(),
+            validation,
+        }
+    }
+}
+
+/// The result of validating a request for a signature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SigningValidation {
+    /// The digest is the sighash of an input of a sweep transaction.
+    ///
+    /// The signer validates sweep transactions when it receives the
+    /// pre-sign request, and stores what it found about each input and
+    /// withdrawal output. These are those rows, for the input being signed
+    /// and for the withdrawal outputs of the same transaction.
+    BitcoinTx {
+        /// The validation of the input being signed.
+        input: InputValidation,
+        /// The validation of the withdrawal outputs of the transaction.
+        withdrawal_outputs: Vec<WithdrawalOutputValidation>,
+    },
+    /// The digest is the message signed to verify the DKG shares for the
+    /// given aggregate key.
+    DkgVerification {
+        /// The aggregate key of the DKG shares being verified.
+        aggregate_key: PublicKeyXOnly,
+    },
+    /// The digest is the presign sighash of a stacks transaction.
+    StacksTx(StacksTxValidation),
+}
+
+/// The outcome of validating an input of a sweep transaction.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct InputValidation {
+    /// The ID of the sweep transaction.
+    #[serde(serialize_with = "serialize_display")]
+    pub txid: BitcoinTxId,
+    /// The chain tip when the transaction was validated.
+    #[serde(serialize_with = "serialize_display")]
+    pub chain_tip: BitcoinBlockHash,
+    /// The transaction that created the output being spent.
+    #[serde(serialize_with = "serialize_display")]
+    pub prevout_txid: BitcoinTxId,
+    /// The index of the output being spent.
+    pub prevout_output_index: u32,
+    /// Whether the output being spent is a deposit or the signers' UTXO.
+    #[serde(serialize_with = "serialize_display")]
+    pub prevout_type: model::TxPrevoutType,
+    /// The aggregate key locking the output being spent.
+    pub aggregate_key: PublicKeyXOnly,
+    /// The outcome of validating the input.
+    pub validation_result: crate::bitcoin::validation::InputValidationResult,
+    /// Whether every input and output of the transaction passed
+    /// validation.
+    pub is_valid_tx: bool,
+    /// Whether the signer agreed to sign the input.
+    pub will_sign: bool,
+}
+
+impl From<model::BitcoinTxSigHash> for InputValidation {
+    fn from(row: model::BitcoinTxSigHash) -> Self {
+        Self {
+            txid: row.txid,
+            chain_tip: row.chain_tip,
+            prevout_txid: row.prevout_txid,
+            prevout_output_index: row.prevout_output_index,
+            prevout_type: row.prevout_type,
+            aggregate_key: row.aggregate_key,
+            validation_result: row.validation_result,
+            is_valid_tx: row.is_valid_tx,
+            will_sign: row.will_sign,
+        }
+    }
+}
+
+/// The outcome of validating a withdrawal output of a sweep transaction.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct WithdrawalOutputValidation {
+    /// The index of the output in the sweep transaction.
+    pub output_index: u32,
+    /// The request ID of the withdrawal request.
+    pub request_id: u64,
+    /// The stacks transaction that created the withdrawal request.
+    #[serde(serialize_with = "serialize_display")]
+    pub stacks_txid: StacksTxId,
+    /// The stacks block that included the transaction.
+    #[serde(serialize_with = "serialize_display")]
+    pub stacks_block_hash: StacksBlockHash,
+    /// The outcome of validating the withdrawal request.
+    pub validation_result: crate::bitcoin::validation::WithdrawalValidationResult,
+}
+
+impl From<model::BitcoinWithdrawalOutput> for WithdrawalOutputValidation {
+    fn from(row: model::BitcoinWithdrawalOutput) -> Self {
+        Self {
+            output_index: row.output_index,
+            request_id: row.request_id,
+            stacks_txid: row.stacks_txid,
+            stacks_block_hash: row.stacks_block_hash,
+            validation_result: row.validation_result,
+        }
+    }
+}
+
+/// The request to sign a stacks transaction, and the context that the
+/// signer validated it in.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct StacksTxValidation {
+    /// The ID of the transaction that was signed.
+    #[serde(serialize_with = "serialize_display")]
+    pub txid: StacksTxId,
+    /// The kind of contract call or deployment in the transaction.
+    pub tx_kind: &'static str,
+    /// The signer's view of the canonical bitcoin chain tip.
+    #[serde(serialize_with = "serialize_display")]
+    pub chain_tip: BitcoinBlockHash,
+    /// The height of the chain tip.
+    pub chain_tip_height: u64,
+    /// How many bitcoin blocks back from the chain tip the signer looks
+    /// for requests.
+    pub context_window: u16,
+    /// The aggregate key of the signing set asked to sign.
+    pub aggregate_key: PublicKey,
+    /// The number of signatures required for the transaction.
+    pub signatures_required: u16,
+    /// The expected deployer of the sBTC smart contracts.
+    #[serde(serialize_with = "serialize_display")]
+    pub deployer: StacksAddress,
+}
+
+impl StacksTxValidation {
+    /// Describe the validation of the given request in the given context.
+    pub fn new(request: &StacksTransactionSignRequest, req_ctx: &ReqContext) -> Self {
+        Self {
+            txid: request.txid.into(),
+            tx_kind: request.tx_kind(),
+            chain_tip: req_ctx.chain_tip.block_hash,
+            chain_tip_height: req_ctx.chain_tip.block_height,
+            context_window: req_ctx.context_window,
+            aggregate_key: req_ctx.aggregate_key,
+            signatures_required: req_ctx.signatures_required,
+            deployer: req_ctx.deployer,
+        }
+    }
+}
+
+/// Compute the hash of an entry with the given contents.
+pub fn entry_hash(position: u64, prev_hash: &[u8], record: &[u8]) -> [u8; 32] {
+    sha2::Sha256::new()
+        .chain_update(AUDIT_LOG_TAG)
+        .chain_update(position.to_be_bytes())
+        .chain_update(prev_hash)
+        .chain_update(record)
+        .finalize()
+        .into()
+}
+
+/// Append an entry for the given record to the end of the signing audit
+/// log, signing it with the given private key.
+///
+/// Reading the last entry and writing the next one are separate steps, so
+/// another append can take the position in between. The entry is then
+/// built again on top of the new last entry, which only fails after
+/// [`MAX_APPEND_ATTEMPTS`] tries.
+pub async fn append<S>(
+    storage: &S,
+    private_key: &PrivateKey,
+    record: &SigningRecord,
+) -> Result<SigningAuditEntry, Error>
+where
+    S: DbRead + DbWrite,
+{
+    let record = serde_json::to_vec(record).map_err(Error::JsonSerialize)?;
+    let mut attempt = 1;
+
+    loop {
+        let (position, prev_hash) = match storage.get_last_signing_audit_entry().await? {
+            Some(last) => (last.position + 1, last.entry_hash),
+            None => (0, GENESIS_HASH.to_vec()),
+        };
+
+        let entry_hash = entry_hash(position, &prev_hash, &record);
+        let signature = private_key.sign_ecdsa(&secp256k1::Message::from_digest(entry_hash));
+
+        let entry = SigningAuditEntry {
+            position,
+            prev_hash,
+            entry_hash: entry_hash.to_vec(),
+            record: record.clone(),
+            signature: signature.serialize_compact().to_vec(),
+        };
+
+        match storage.write_signing_audit_entry(&entry).await {
+            Ok(()) => return Ok(entry),
+            Err(Error::AuditLogPositionTaken(position)) if attempt < MAX_APPEND_ATTEMPTS => {
+                tracing::debug!(%position, %attempt, "signing audit log position taken, retrying");
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// A signed statement of the head of the signing audit log.
+///
+/// Checkpoints are kept outside of the signer's database, so that a log
+/// whose newest entries were removed can be told apart from a log that
+/// never had them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    /// The number of entries in the log.
+    pub length: u64,
+    /// The hash of the last entry in the log, or the [`GENESIS_HASH`]
+    /// if the log is empty.
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub head_hash: Vec<u8>,
+    /// The signer's signature over the checkpoint digest, in compact form.
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub signature: Vec<u8>,
+}
+
+impl Checkpoint {
+    /// Create a checkpoint of a log with the given length and head hash,
+    /// signed with the given private key.
+    pub fn new(length: u64, head_hash: Vec<u8>, private_key: &PrivateKey) -> Self {
+        let digest = checkpoint_digest(length, &head_hash);
+        let signature = private_key.sign_ecdsa(&secp256k1::Message::from_digest(digest));
+        Self {
+            length,
+            head_hash,
+            signature: signature.serialize_compact().to_vec(),
+        }
+    }
+
+    /// Create a checkpoint of a log whose last entry is the given entry.
+    pub fn of_entry(entry: &SigningAuditEntry, private_key: &PrivateKey) -> Self {
+        Self::new(entry.position + 1, entry.entry_hash.clone(), private_key)
+    }
+
+    /// Check that the checkpoint was signed by the given public key.
+    pub fn verify_signature(&self, public_key: &PublicKey) -> Result<(), Error> {
+        let digest = checkpoint_digest(self.length, &self.head_hash);
+        let msg = secp256k1::Message::from_digest(digest);
+        secp256k1::ecdsa::Signature::from_compact(&self.signature)
+            .and_then(|signature| signature.verify(&msg, public_key))
+            .map_err(|_| Error::InvalidAuditLogCheckpoint)
+    }
+}
+
+/// Compute the digest that is signed for a checkpoint with the given
+/// contents.
+fn checkpoint_digest(length: u64, head_hash: &[u8]) -> [u8; 32] {
+    sha2::Sha256::new()
+        .chain_update(CHECKPOINT_TAG)
+        .chain_update(length.to_be_bytes())
+        .chain_update(head_hash)
+        .finalize()
+        .into()
+}
+
+/// Create a checkpoint of the current head of the signing audit log,
+/// signed with the given private key.
+pub async fn checkpoint<S>(storage: &S, private_key: &PrivateKey) -> Result<Checkpoint, Error>
+where
+    S: DbRead,
+{
+    match storage.get_last_signing_audit_entry().await? {
+        Some(last) => Ok(Checkpoint::of_entry(&last, private_key)),
+        None => Ok(Checkpoint::new(0, GENESIS_HASH.to_vec(), private_key)),
+    }
+}
+
+/// Check that the signing audit log forms an unbroken chain from its
+/// first entry, and that every entry was signed by the given public key.
+///
+/// If a checkpoint is given, it must have been signed by the given public
+/// key and the log must contain the entry that it names, so that removing
+/// the newest entries of the log is caught too.
+///
+/// Returns the number of entries in the log, or an
+/// [`Error::AuditLogBroken`] for the first entry that fails.
+pub async fn verify<S>(
+    storage: &S,
+    public_key: &PublicKey,
+    checkpoint: Option<&Checkpoint>,
+) -> Result<u64, Error>
+where
+    S: DbRead,
+{
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.verify_signature(public_key)?;
+        if checkpoint.length == 0 && checkpoint.head_hash != GENESIS_HASH {
+            return Err(Error::InvalidAuditLogCheckpoint);
+        }
+    }
+
+    let mut prev_hash = GENESIS_HASH.to_vec();
+    let mut position = 0;
+
+    loop {
+        let entries = storage
+            .get_signing_audit_entries(position, READ_BATCH_SIZE)
+            .await?;
+        if entries.is_empty() {
+            break;
+        }
+
+        for entry in entries {
+            verify_entry(&entry, position, &prev_hash, public_key)?;
+            prev_hash = entry.entry_hash;
+            position += 1;
+
+            // The entry named by the checkpoint must be the one that was
+            // there when the checkpoint was made.
+            if checkpoint.is_some_and(|c| c.length == position && c.head_hash != prev_hash) {
+                return Err(Error::AuditLogBroken {
+                    position: position - 1,
+                    reason: "the entry does not match the checkpoint",
+                });
+            }
+        }
+    }
+
+    if checkpoint.is_some_and(|c| c.length > position) {
+        return Err(Error::AuditLogBroken {
+            position,
+            reason: "the entries up to the checkpoint are missing",
+        });
+    }
+
+    Ok(position)
+}
+
+/// Check a single entry, given the position that it should be at and the
+/// hash of the entry before it.
+fn verify_entry(
+    entry: &SigningAuditEntry,
+    position: u64,
+    prev_hash: &[u8],
+    public_key: &PublicKey,
+) -> Result<(), Error> {
+    let broken = |reason| Error::AuditLogBroken { position, reason };
+
+    if entry.position != position {
+        return Err(broken("the entry at this position is missing"));
+    }
+    if entry.prev_hash != prev_hash {
+        return Err(broken(
+            "the previous hash does not match the previous entry",
+        ));
+    }
+
+    let hash = entry_hash(entry.position, &entry.prev_hash, &entry.record);
+    if entry.entry_hash != hash {
+        return Err(broken("the entry hash does not match the entry"));
+    }
+
+    let msg = secp256k1::Message::from_digest(hash);
+    secp256k1::ecdsa::Signature::from_compact(&entry.signature)
+        .and_then(|signature| signature.verify(&msg, public_key))
+        .map_err(|_| broken("the entry was not signed by the signer"))
+}
+
+/// An entry of the signing audit log in the form that it is exported in.
+#[derive(Debug, serde::Serialize)]
+pub struct ExportedEntry {
+    /// The position of the entry in the log.
+    pub position: u64,
+    /// The hash of the previous entry.
+    #[serde(serialize_with = "serialize_hex")]
+    pub prev_hash: Vec<u8>,
+    /// The hash of the entry.
+    #[serde(serialize_with = "serialize_hex")]
+    pub entry_hash: Vec<u8>,
+    /// The signer's signature over the entry hash.
+    #[serde(serialize_with = "serialize_hex")]
+    pub signature: Vec<u8>,
+    /// The record, exactly as it was hashed.
+    pub record: String,
+}
+
+impl From<SigningAuditEntry> for ExportedEntry {
+    fn from(entry: SigningAuditEntry) -> Self {
+        Self {
+            position: entry.position,
+            prev_hash: entry.prev_hash,
+            entry_hash: entry.entry_hash,
+            signature: entry.signature,
+            // Records are always written as JSON, which is valid UTF-8.
+            record: String::from_utf8_lossy(&entry.record).into_owned(),
+        }
+    }
+}
+
+fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: std::fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
+}
+
+fn serialize_hex<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&hex::encode(bytes))
+}
+
+fn deserialize_hex<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let hex = <String as serde::Deserialize>::deserialize(deserializer)?;
+    hex::decode(hex).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use rand::SeedableRng as _;
+
+    use crate::ecdsa::SignEcdsa as _;
+    use crate::ecdsa::Signed;
+    use crate::message::SignerMessage;
+    use crate::storage::in_memory::Store;
+
+    use super::*;
+
+    fn dkg_verification_record(rng: &mut rand::rngs::StdRng) -> SigningRecord {
+        let coordinator_key = PrivateKey::new(rng);
+        let request = fake::Faker
+            .fake_with_rng::<SignerMessage, _>(rng)
+            .sign_ecdsa(&coordinator_key);
+        let aggregate_key: PublicKeyXOnly = fake::Faker.fake_with_rng(rng);
+        SigningRecord::new(
+            SignatureKind::SignatureShare,
+            fake::Faker.fake_with_rng(rng),
+            &request,
+            &crate::wsts_state_machine::dkg_verification_message(&aggregate_key),
+            SigningValidation::DkgVerification { aggregate_key },
+        )
+    }
+
+    #[test]
+    fn records_hold_the_coordinators_signed_request() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let record = dkg_verification_record(&mut rng);
+
+        // The recorded request carries a signature by the coordinator over
+        // the recorded bytes.
+        let (request, digest) =
+            Signed::<SignerMessage>::decode_with_digest(&record.request).unwrap();
+        request.verify_digest(digest).unwrap();
+        assert_eq!(request.signer_public_key, record.coordinator_public_key);
+        assert_eq!(request.bitcoin_chain_tip, record.bitcoin_chain_tip);
+        assert_eq!(
+            request.signature.serialize_compact().as_slice(),
+            record.request_signature
+        );
+    }
+
+    #[tokio::test]
+    async fn appended_entries_form_a_verifiable_chain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let store = Store::new_shared();
+        let private_key = PrivateKey::new(&mut rng);
+        let public_key = PublicKey::from_private_key(&private_key);
+
+        assert_eq!(verify(&store, &public_key, None).await.unwrap(), 0);
+
+        for _ in 0..3 {
+            let record = dkg_verification_record(&mut rng);
+            append(&store, &private_key, &record).await.unwrap();
+        }
+
+        let entries = store.lock().await.signing_audit_log.clone();
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].entry_hash);
+        assert_eq!(entries[2].prev_hash, entries[1].entry_hash);
+        assert_eq!(verify(&store, &public_key, None).await.unwrap(), 3);
+
+        // Entries signed with some other key fail verification.
+        let other_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
+        let error = verify(&store, &other_key, None).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 0, .. }));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn concurrent_appends_all_make_it_into_the_chain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let store = Store::new_shared();
+        let private_key = PrivateKey::new(&mut rng);
+        let public_key = PublicKey::from_private_key(&private_key);
+
+        // Each append that loses a race for a position lost it to another
+        // append that succeeded, so there are fewer concurrent appends
+        // than attempts for this to always succeed.
+        let appends: Vec<_> = (0..MAX_APPEND_ATTEMPTS - 1)
+            .map(|_| {
+                let store = store.clone();
+                let record = dkg_verification_record(&mut rng);
+                tokio::spawn(async move { append(&store, &private_key, &record).await })
+            })
+            .collect();
+        for result in futures::future::join_all(appends).await {
+            result.unwrap().unwrap();
+        }
+
+        let count = verify(&store, &public_key, None).await.unwrap();
+        assert_eq!(count, u64::from(MAX_APPEND_ATTEMPTS - 1));
+    }
+
+    #[tokio::test]
+    async fn tampering_breaks_the_chain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let store = Store::new_shared();
+        let private_key = PrivateKey::new(&mut rng);
+        let public_key = PublicKey::from_private_key(&private_key);
+
+        for _ in 0..3 {
+            let record = dkg_verification_record(&mut rng);
+            append(&store, &private_key, &record).await.unwrap();
+        }
+
+        // Changing the record of an entry is caught by its hash.
+        let original = store.lock().await.signing_audit_log[1].clone();
+        store.lock().await.signing_audit_log[1].record[0] ^= 1;
+        let error = verify(&store, &public_key, None).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 1, .. }));
+
+        // Recomputing the hash after changing the record does not help,
+        // since the signature is over the original hash.
+        let mut changed = original.clone();
+        changed.record[0] ^= 1;
+        changed.entry_hash = entry_hash(1, &changed.prev_hash, &changed.record).to_vec();
+        store.lock().await.signing_audit_log[1] = changed;
+        let error = verify(&store, &public_key, None).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 1, .. }));
+
+        // Removing an entry leaves a gap.
+        store.lock().await.signing_audit_log.remove(1);
+        let error = verify(&store, &public_key, None).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn checkpoints_catch_removed_entries() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let store = Store::new_shared();
+        let private_key = PrivateKey::new(&mut rng);
+        let public_key = PublicKey::from_private_key(&private_key);
+
+        // A checkpoint of the empty log holds for any later log.
+        let empty = checkpoint(&store, &private_key).await.unwrap();
+        assert_eq!(empty.length, 0);
+        assert_eq!(empty.head_hash, GENESIS_HASH);
+
+        for _ in 0..3 {
+            let record = dkg_verification_record(&mut rng);
+            append(&store, &private_key, &record).await.unwrap();
+        }
+        let head = checkpoint(&store, &private_key).await.unwrap();
+        assert_eq!(head.length, 3);
+
+        // Checkpoints survive being exported as JSON.
+        let json = serde_json::to_string(&head).unwrap();
+        let head: Checkpoint = serde_json::from_str(&json).unwrap();
+
+        // Entries appended after the checkpoint was made are fine.
+        let record = dkg_verification_record(&mut rng);
+        append(&store, &private_key, &record).await.unwrap();
+        assert_eq!(verify(&store, &public_key, Some(&empty)).await.unwrap(), 4);
+        assert_eq!(verify(&store, &public_key, Some(&head)).await.unwrap(), 4);
+
+        // Removing the newest entries leaves an unbroken chain, which
+        // only the checkpoint catches.
+        store.lock().await.signing_audit_log.truncate(2);
+        assert_eq!(verify(&store, &public_key, None).await.unwrap(), 2);
+        let error = verify(&store, &public_key, Some(&head)).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 2, .. }));
+
+        // Neither does a rewritten head, since the checkpoint names the
+        // hash of the entry that was there.
+        let record = dkg_verification_record(&mut rng);
+        append(&store, &private_key, &record).await.unwrap();
+        let error = verify(&store, &public_key, Some(&head)).await.unwrap_err();
+        assert!(matches!(error, Error::AuditLogBroken { position: 2, .. }));
+
+        // A checkpoint made with another key is rejected.
+        let other_key = PrivateKey::new(&mut rng);
+        let forged = Checkpoint::new(2, head.head_hash.clone(), &other_key);
+        let error = verify(&store, &public_key, Some(&forged))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidAuditLogCheckpoint));
+    }
+}
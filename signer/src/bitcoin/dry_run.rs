@@ -0,0 +1,907 @@
+//! Reporting for dry runs of sweep transaction construction.
+//!
+//! A dry run goes through the same steps that the coordinator takes when
+//! constructing the sweep transaction package, and that the other signers
+//! take when validating it, but nothing gets signed or broadcast. The
+//! [`SweepDryRunReport`] records what happened to each pending request,
+//! so that operators can tell why a request was not swept.
+
+use std::collections::HashSet;
+
+use bitcoin::Amount;
+use bitcoin::OutPoint;
+use bitcoin::Txid;
+use serde::Serialize;
+
+use crate::bitcoin::packaging::Weighted as _;
+use crate::bitcoin::utxo::FeeAssessment as _;
+use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::utxo::FilterReason;
+use crate::bitcoin::utxo::RequestRef;
+use crate::bitcoin::utxo::SbtcRequests;
+use crate::bitcoin::utxo::UnsignedTransaction;
+use crate::bitcoin::validation::BitcoinTxValidationData;
+use crate::bitcoin::validation::InputValidationResult;
+use crate::bitcoin::validation::WithdrawalValidationResult;
+use crate::error::Error;
+use crate::storage::model;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::QualifiedRequestId;
+use crate::storage::model::StacksBlockHash;
+use crate::storage::model::StacksTxId;
+
+/// The reason that a request was left out of the sweep transaction
+/// package, or would not be signed for by the signers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", content = "result", rename_all = "snake_case")]
+pub enum ExclusionReason {
+    /// The deposit request failed validation for the given reason.
+    Deposit(InputValidationResult),
+    /// The withdrawal request failed validation for the given reason.
+    Withdrawal(WithdrawalValidationResult),
+    /// Sweeping in the deposit would take the total amount minted above
+    /// the maximum mintable amount.
+    MaxMintableCap,
+    /// Sweeping in the deposit would take the amount pegged-in within
+    /// Emily's rolling window above the rolling deposit cap.
+    RollingDepositCap,
+    /// Servicing the withdrawal would take the amount pegged-out within
+    /// Emily's rolling window above the rolling withdrawal cap.
+    RollingWithdrawalCap,
+    /// The max fee of the withdrawal request is below the fee of a sweep
+    /// transaction servicing only that withdrawal.
+    WithdrawalFeeTooHigh,
+    /// So many signers voted against the request that it cannot be
+    /// included in any transaction.
+    VotesAgainst {
+        /// The number of signers that voted against the request.
+        votes_against: u32,
+        /// The maximum number of votes against that a transaction can
+        /// have.
+        max_votes_against: u32,
+    },
+    /// The request passed filtering but did not fit into the package.
+    /// This happens when the package reaches the maximum vsize, the
+    /// maximum number of transactions, or when a transaction reaches the
+    /// maximum number of deposits.
+    PackageLimits,
+    /// The request passed validation but another request in the same
+    /// transaction did not, so the signers would not sign the
+    /// transaction.
+    PackageRejected,
+}
+
+impl From<FilterReason> for ExclusionReason {
+    fn from(reason: FilterReason) -> Self {
+        match reason {
+            FilterReason::Deposit(result) => ExclusionReason::Deposit(result),
+            FilterReason::MaxMintableCap => ExclusionReason::MaxMintableCap,
+            FilterReason::RollingDepositCap => ExclusionReason::RollingDepositCap,
+            FilterReason::RollingWithdrawalCap => ExclusionReason::RollingWithdrawalCap,
+        }
+    }
+}
+
+impl std::fmt::Display for ExclusionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExclusionReason::Deposit(result) => write!(f, "deposit validation: {result}"),
+            ExclusionReason::Withdrawal(result) => write!(f, "withdrawal validation: {result}"),
+            ExclusionReason::MaxMintableCap => f.write_str("max mintable cap exceeded"),
+            ExclusionReason::RollingDepositCap => f.write_str("rolling deposit cap exceeded"),
+            ExclusionReason::RollingWithdrawalCap => f.write_str("rolling withdrawal cap exceeded"),
+            ExclusionReason::WithdrawalFeeTooHigh => f.write_str("withdrawal fee too high"),
+            ExclusionReason::VotesAgainst {
+                votes_against,
+                max_votes_against,
+            } => {
+                write!(
+                    f,
+                    "{votes_against} votes against, at most {max_votes_against} allowed"
+                )
+            }
+            ExclusionReason::PackageLimits => f.write_str("package limits reached"),
+            ExclusionReason::PackageRejected => f.write_str("transaction failed validation"),
+        }
+    }
+}
+
+/// A summary of one transaction in the sweep transaction package.
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageReport {
+    /// The txid of the unsigned transaction.
+    pub txid: Txid,
+    /// The total fee of the transaction in sats.
+    pub tx_fee: u64,
+    /// The virtual size of the transaction.
+    pub tx_vsize: u32,
+    /// Whether the transaction passed validation as a whole. This is
+    /// `None` if the package could not be validated.
+    pub is_valid_tx: Option<bool>,
+}
+
+/// What happened to a deposit request during the dry run.
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositReport {
+    /// The outpoint of the deposit UTXO.
+    pub outpoint: OutPoint,
+    /// The deposit amount in sats.
+    pub amount: u64,
+    /// The max fee of the deposit request in sats.
+    pub max_fee: u64,
+    /// The number of signers that voted against the request.
+    pub votes_against: u32,
+    /// The index of the transaction in the package that includes the
+    /// request.
+    pub package: Option<usize>,
+    /// The fee assessed to the request in sats.
+    pub assessed_fee: Option<u64>,
+    /// Why the request would not be swept.
+    pub excluded: Option<ExclusionReason>,
+}
+
+/// What happened to a withdrawal request during the dry run.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithdrawalReport {
+    /// The ID that was generated in the clarity contract call for the
+    /// withdrawal request.
+    pub request_id: u64,
+    /// The txid that generated the request.
+    #[serde(serialize_with = "serialize_display")]
+    pub txid: StacksTxId,
+    /// The Stacks block ID that includes the transaction that generated
+    /// the request.
+    #[serde(serialize_with = "serialize_display")]
+    pub block_hash: StacksBlockHash,
+    /// The withdrawal amount in sats.
+    pub amount: u64,
+    /// The max fee of the withdrawal request in sats.
+    pub max_fee: u64,
+    /// The number of signers that voted against the request.
+    pub votes_against: u32,
+    /// The index of the transaction in the package that includes the
+    /// request.
+    pub package: Option<usize>,
+    /// The fee assessed to the request in sats.
+    pub assessed_fee: Option<u64>,
+    /// Why the request would not be swept.
+    pub excluded: Option<ExclusionReason>,
+}
+
+impl WithdrawalReport {
+    fn qualified_id(&self) -> QualifiedRequestId {
+        QualifiedRequestId {
+            request_id: self.request_id,
+            txid: self.txid,
+            block_hash: self.block_hash,
+        }
+    }
+}
+
+/// The outcome of a sweep dry run for a bitcoin chain tip.
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepDryRunReport {
+    /// The bitcoin chain tip that the dry run was done against.
+    #[serde(serialize_with = "serialize_display")]
+    pub chain_tip: BitcoinBlockHash,
+    /// The height of the bitcoin chain tip.
+    pub chain_tip_height: u64,
+    /// The fee rate, in sats per vbyte, used to construct the package.
+    /// This is `None` if there were no requests to sweep.
+    pub fee_rate: Option<f64>,
+    /// The fees of the sweep transaction that the package would replace.
+    pub last_fees: Option<Fees>,
+    /// The transactions in the sweep transaction package.
+    pub packages: Vec<PackageReport>,
+    /// The outcome of each deposit request.
+    pub deposits: Vec<DepositReport>,
+    /// The outcome of each withdrawal request.
+    pub withdrawals: Vec<WithdrawalReport>,
+    /// The error returned while validating the package the way that the
+    /// other signers would, if validation failed outright.
+    pub validation_error: Option<String>,
+}
+
+impl SweepDryRunReport {
+    /// Create a new report with no requests.
+    pub fn new(chain_tip: BitcoinBlockHash, chain_tip_height: u64) -> Self {
+        Self {
+            chain_tip,
+            chain_tip_height,
+            fee_rate: None,
+            last_fees: None,
+            packages: Vec::new(),
+            deposits: Vec::new(),
+            withdrawals: Vec::new(),
+            validation_error: None,
+        }
+    }
+
+    /// Add the outcome of packaging and validating the given requests.
+    ///
+    /// The `packages` must be the output of
+    /// [`SbtcRequests::construct_transactions`] and `validation` must
+    /// have one entry for each of those transactions.
+    pub fn add_requests(
+        &mut self,
+        requests: &SbtcRequests,
+        packages: &[UnsignedTransaction],
+        validation: Result<Vec<BitcoinTxValidationData>, Error>,
+    ) {
+        self.fee_rate = Some(requests.signer_state.fee_rate);
+        self.last_fees = requests.signer_state.last_fees;
+
+        let validation = validation.unwrap_or_else(|error| {
+            self.validation_error = Some(error.to_string());
+            Vec::new()
+        });
+
+        for (index, tx) in packages.iter().enumerate() {
+            self.add_package(index, tx, validation.get(index));
+        }
+
+        let max_votes_against = requests.reject_capacity();
+        let packaging_reason = |votes_against: u32| {
+            if votes_against > max_votes_against {
+                ExclusionReason::VotesAgainst {
+                    votes_against,
+                    max_votes_against,
+                }
+            } else {
+                ExclusionReason::PackageLimits
+            }
+        };
+
+        let packaged: HashSet<OutPoint> = self.deposits.iter().map(|req| req.outpoint).collect();
+        // The filter keeps a running total of the amount to mint, so we
+        // need to go through the deposits in the same order that
+        // construct_transactions does.
+        let filter = requests.requests_filter();
+        let mut amount_to_mint = Amount::ZERO;
+        for req in requests.deposits.iter() {
+            let reason = match filter.check_deposit(amount_to_mint, req) {
+                Ok(new_amount) => {
+                    amount_to_mint = new_amount;
+                    packaging_reason(req.votes().count_ones())
+                }
+                Err(reason) => reason.into(),
+            };
+            if packaged.contains(&req.outpoint) {
+                continue;
+            }
+            self.deposits.push(DepositReport {
+                outpoint: req.outpoint,
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: req.votes().count_ones(),
+                package: None,
+                assessed_fee: None,
+                excluded: Some(reason),
+            });
+        }
+
+        let packaged: HashSet<QualifiedRequestId> = self
+            .withdrawals
+            .iter()
+            .map(WithdrawalReport::qualified_id)
+            .collect();
+        // Same as with the deposits, the running total of the amount to
+        // withdraw only includes the withdrawals with a sufficient fee.
+        let mut amount_to_withdraw = Amount::ZERO;
+        for req in requests.withdrawals.iter() {
+            let reason = if !requests.is_withdrawal_fee_sufficient(req) {
+                ExclusionReason::WithdrawalFeeTooHigh
+            } else {
+                match filter.check_withdrawal(amount_to_withdraw, req) {
+                    Ok(new_amount) => {
+                        amount_to_withdraw = new_amount;
+                        packaging_reason(req.votes().count_ones())
+                    }
+                    Err(reason) => reason.into(),
+                }
+            };
+            if packaged.contains(&req.qualified_id()) {
+                continue;
+            }
+            self.withdrawals.push(WithdrawalReport {
+                request_id: req.request_id,
+                txid: req.txid,
+                block_hash: req.block_hash,
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: req.votes().count_ones(),
+                package: None,
+                assessed_fee: None,
+                excluded: Some(reason),
+            });
+        }
+    }
+
+    fn add_package(
+        &mut self,
+        index: usize,
+        tx: &UnsignedTransaction,
+        validation: Option<&BitcoinTxValidationData>,
+    ) {
+        let is_valid_tx = validation.map(BitcoinTxValidationData::is_valid_tx);
+        let input_rows = validation
+            .map(|data| data.to_input_rows())
+            .unwrap_or_default();
+        let withdrawal_rows = validation
+            .map(|data| data.to_withdrawal_rows())
+            .unwrap_or_default();
+        let tx_fee = Amount::from_sat(tx.tx_fee);
+
+        self.packages.push(PackageReport {
+            txid: tx.tx.compute_txid(),
+            tx_fee: tx.tx_fee,
+            tx_vsize: tx.tx_vsize,
+            is_valid_tx,
+        });
+
+        // The first two outputs are always the signers' UTXO and the
+        // OP_RETURN output, followed by the withdrawal outputs in the
+        // same order as the requests.
+        let withdrawal_vouts = 2..;
+        let withdrawals = tx.requests.iter().filter_map(RequestRef::as_withdrawal);
+
+        for req in tx.requests.iter().filter_map(RequestRef::as_deposit) {
+            let result = input_rows
+                .iter()
+                .find(|row| {
+                    row.prevout_txid == BitcoinTxId::from(req.outpoint.txid)
+                        && row.prevout_output_index == req.outpoint.vout
+                })
+                .map(|row| row.validation_result);
+
+            let excluded = match result {
+                Some(InputValidationResult::Ok) if is_valid_tx == Some(false) => {
+                    Some(ExclusionReason::PackageRejected)
+                }
+                Some(InputValidationResult::Ok) | None => None,
+                Some(result) => Some(ExclusionReason::Deposit(result)),
+            };
+
+            self.deposits.push(DepositReport {
+                outpoint: req.outpoint,
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: req.votes().count_ones(),
+                package: Some(index),
+                assessed_fee: tx
+                    .assess_input_fee(&req.outpoint, tx_fee)
+                    .map(Amount::to_sat),
+                excluded,
+            });
+        }
+
+        for (vout, req) in withdrawal_vouts.zip(withdrawals) {
+            // There is no passing withdrawal validation result, so any
+            // result here means that the withdrawal would not be swept.
+            let excluded = withdrawal_rows
+                .iter()
+                .find(|row| {
+                    row.request_id == req.request_id
+                        && row.stacks_txid == req.txid
+                        && row.stacks_block_hash == req.block_hash
+                })
+                .map(|row| ExclusionReason::Withdrawal(row.validation_result));
+
+            self.withdrawals.push(WithdrawalReport {
+                request_id: req.request_id,
+                txid: req.txid,
+                block_hash: req.block_hash,
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: req.votes().count_ones(),
+                package: Some(index),
+                assessed_fee: tx.assess_output_fee(vout, tx_fee).map(Amount::to_sat),
+                excluded,
+            });
+        }
+    }
+
+    /// Add deposit requests that this signer has not voted on. These
+    /// never make it into the pending requests used to construct the
+    /// package, so we would not otherwise know about them.
+    pub fn add_unvoted_deposits(&mut self, requests: &[model::DepositRequest]) {
+        let reported: HashSet<OutPoint> = self.deposits.iter().map(|req| req.outpoint).collect();
+        let unvoted = requests
+            .iter()
+            .filter(|req| !reported.contains(&req.outpoint()))
+            .map(|req| DepositReport {
+                outpoint: req.outpoint(),
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: 0,
+                package: None,
+                assessed_fee: None,
+                excluded: Some(ExclusionReason::Deposit(InputValidationResult::NoVote)),
+            })
+            .collect::<Vec<_>>();
+
+        self.deposits.extend(unvoted);
+    }
+
+    /// Add withdrawal requests that have been accepted by the signers but
+    /// that are not swept, because withdrawals are not supported yet.
+    pub fn add_unsupported_withdrawals(&mut self, requests: &[model::WithdrawalRequest]) {
+        let reported: HashSet<QualifiedRequestId> = self
+            .withdrawals
+            .iter()
+            .map(WithdrawalReport::qualified_id)
+            .collect();
+        let result = WithdrawalValidationResult::Unsupported;
+        let unsupported = requests
+            .iter()
+            .filter(|req| !reported.contains(&req.qualified_id()))
+            .map(|req| WithdrawalReport {
+                request_id: req.request_id,
+                txid: req.txid,
+                block_hash: req.block_hash,
+                amount: req.amount,
+                max_fee: req.max_fee,
+                votes_against: 0,
+                package: None,
+                assessed_fee: None,
+                excluded: Some(ExclusionReason::Withdrawal(result)),
+            })
+            .collect::<Vec<_>>();
+
+        self.withdrawals.extend(unsupported);
+    }
+}
+
+impl std::fmt::Display for SweepDryRunReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "sweep dry run at bitcoin block {} (height {})",
+            self.chain_tip, self.chain_tip_height
+        )?;
+        if let Some(fee_rate) = self.fee_rate {
+            writeln!(f, "fee rate: {fee_rate} sats/vbyte")?;
+        }
+        if let Some(Fees { total, rate }) = self.last_fees {
+            writeln!(f, "replacing fees: {total} sats at {rate} sats/vbyte")?;
+        }
+        if let Some(error) = &self.validation_error {
+            writeln!(f, "package validation failed: {error}")?;
+        }
+
+        writeln!(f, "packages:")?;
+        for (index, package) in self.packages.iter().enumerate() {
+            let validity = match package.is_valid_tx {
+                Some(true) => "valid",
+                Some(false) => "invalid",
+                None => "not validated",
+            };
+            writeln!(
+                f,
+                "  [{index}] {} fee={} vsize={} {validity}",
+                package.txid, package.tx_fee, package.tx_vsize
+            )?;
+        }
+
+        writeln!(f, "deposits:")?;
+        for deposit in self.deposits.iter() {
+            let outcome = Outcome {
+                package: deposit.package,
+                assessed_fee: deposit.assessed_fee,
+                excluded: deposit.excluded,
+            };
+            writeln!(
+                f,
+                "  {} amount={} max_fee={} votes_against={} {outcome}",
+                deposit.outpoint, deposit.amount, deposit.max_fee, deposit.votes_against
+            )?;
+        }
+
+        writeln!(f, "withdrawals:")?;
+        for withdrawal in self.withdrawals.iter() {
+            let outcome = Outcome {
+                package: withdrawal.package,
+                assessed_fee: withdrawal.assessed_fee,
+                excluded: withdrawal.excluded,
+            };
+            writeln!(
+                f,
+                "  {}:{} amount={} max_fee={} votes_against={} {outcome}",
+                withdrawal.txid,
+                withdrawal.request_id,
+                withdrawal.amount,
+                withdrawal.max_fee,
+                withdrawal.votes_against
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Helper for printing the outcome of a single request.
+struct Outcome {
+    package: Option<usize>,
+    assessed_fee: Option<u64>,
+    excluded: Option<ExclusionReason>,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.excluded, self.package) {
+            (None, Some(package)) => write!(f, "included in [{package}]")?,
+            (None, None) => f.write_str("included")?,
+            (Some(reason), _) => write!(f, "excluded ({reason})")?,
+        }
+        if let Some(fee) = self.assessed_fee {
+            write!(f, " assessed_fee={fee}")?;
+        }
+        Ok(())
+    }
+}
+
+fn serialize_display<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: std::fmt::Display,
+    S: serde::Serializer,
+{
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::ScriptBuf;
+    use bitvec::array::BitArray;
+    use fake::Fake as _;
+    use rand::Rng as _;
+    use rand::SeedableRng as _;
+
+    use bitcoin::WPubkeyHash;
+
+    use crate::bitcoin::utxo::DepositRequest;
+    use crate::bitcoin::utxo::SignerBtcState;
+    use crate::bitcoin::utxo::SignerUtxo;
+    use crate::bitcoin::utxo::WithdrawalRequest;
+    use crate::bitcoin::validation::DepositConfirmationStatus;
+    use crate::bitcoin::validation::DepositRequestReport;
+    use crate::bitcoin::validation::SbtcReports;
+    use crate::bitcoin::validation::WithdrawalRequestReport;
+    use crate::bitcoin::validation::WithdrawalRequestStatus;
+    use crate::context::RollingCap;
+    use crate::context::SbtcLimits;
+    use crate::keys::PublicKey;
+
+    use super::*;
+
+    fn deposit(
+        rng: &mut rand::rngs::StdRng,
+        amount: u64,
+        max_fee: u64,
+        votes: u128,
+    ) -> DepositRequest {
+        let public_key: PublicKey = fake::Faker.fake_with_rng(rng);
+        DepositRequest {
+            outpoint: OutPoint::new(bitcoin::Txid::from_byte_array(rng.gen()), 0),
+            max_fee,
+            signer_bitmap: BitArray::new(votes.to_le_bytes()),
+            amount,
+            deposit_script: ScriptBuf::new(),
+            reclaim_script: ScriptBuf::new(),
+            signers_public_key: public_key.into(),
+        }
+    }
+
+    fn withdrawal(
+        rng: &mut rand::rngs::StdRng,
+        amount: u64,
+        max_fee: u64,
+        votes: u128,
+    ) -> WithdrawalRequest {
+        WithdrawalRequest {
+            request_id: rng.gen(),
+            txid: StacksTxId::from(rng.gen::<[u8; 32]>()),
+            block_hash: StacksBlockHash::from(rng.gen::<[u8; 32]>()),
+            amount,
+            max_fee,
+            script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array(rng.gen())).into(),
+            signer_bitmap: BitArray::new(votes.to_le_bytes()),
+        }
+    }
+
+    fn sbtc_requests(
+        rng: &mut rand::rngs::StdRng,
+        deposits: Vec<DepositRequest>,
+        withdrawals: Vec<WithdrawalRequest>,
+        sbtc_limits: SbtcLimits,
+    ) -> SbtcRequests {
+        let public_key: PublicKey = fake::Faker.fake_with_rng(rng);
+        SbtcRequests {
+            deposits,
+            withdrawals,
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::new(bitcoin::Txid::from_byte_array(rng.gen()), 0),
+                    amount: 1_000_000,
+                    public_key: public_key.into(),
+                },
+                fee_rate: 1.0,
+                public_key: public_key.into(),
+                last_fees: None,
+                magic_bytes: [0; 2],
+            },
+            accept_threshold: 4,
+            num_signers: 5,
+            sbtc_limits,
+            max_deposits_per_bitcoin_tx: 25,
+        }
+    }
+
+    #[test]
+    fn unpackaged_requests_report_exclusion_reasons() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+        let public_key: PublicKey = fake::Faker.fake_with_rng(&mut rng);
+
+        let low_fee = deposit(&mut rng, 100_000, 1, 0);
+        let below_minimum = deposit(&mut rng, 5_000, 10_000, 0);
+        let above_cap = deposit(&mut rng, 10_000_000, 10_000, 0);
+        let rejected = deposit(&mut rng, 100_000, 10_000, 0b1111);
+        let accepted = deposit(&mut rng, 100_000, 10_000, 0);
+
+        let requests = SbtcRequests {
+            deposits: vec![
+                low_fee.clone(),
+                below_minimum.clone(),
+                above_cap.clone(),
+                rejected.clone(),
+                accepted.clone(),
+            ],
+            withdrawals: Vec::new(),
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: OutPoint::null(),
+                    amount: 1_000_000,
+                    public_key: public_key.into(),
+                },
+                fee_rate: 1.0,
+                public_key: public_key.into(),
+                last_fees: None,
+                magic_bytes: [0; 2],
+            },
+            accept_threshold: 4,
+            num_signers: 5,
+            sbtc_limits: SbtcLimits::new_per_deposit(10_000, 1_000_000),
+            max_deposits_per_bitcoin_tx: 25,
+        };
+
+        let mut report = SweepDryRunReport::new(BitcoinBlockHash::from([1; 32]), 100);
+        report.add_requests(&requests, &[], Ok(Vec::new()));
+
+        let reason = |req: &DepositRequest| {
+            report
+                .deposits
+                .iter()
+                .find(|report| report.outpoint == req.outpoint)
+                .and_then(|report| report.excluded)
+                .unwrap()
+        };
+
+        assert_eq!(
+            reason(&low_fee),
+            ExclusionReason::Deposit(InputValidationResult::FeeTooHigh)
+        );
+        assert_eq!(
+            reason(&below_minimum),
+            ExclusionReason::Deposit(InputValidationResult::AmountTooLow)
+        );
+        assert_eq!(
+            reason(&above_cap),
+            ExclusionReason::Deposit(InputValidationResult::AmountTooHigh)
+        );
+        assert_eq!(
+            reason(&rejected),
+            ExclusionReason::VotesAgainst {
+                votes_against: 4,
+                max_votes_against: 1
+            }
+        );
+        assert_eq!(reason(&accepted), ExclusionReason::PackageLimits);
+        assert_eq!(report.fee_rate, Some(1.0));
+        assert!(report.validation_error.is_none());
+    }
+
+    #[test]
+    fn filtered_requests_report_limit_exclusion_reasons() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+        // The minimum fee for a solo deposit at one sat per vbyte is 267
+        // sats, which leaves this deposit with less than the dust limit.
+        let dust = deposit(&mut rng, 800, 800, 0);
+        let first = deposit(&mut rng, 100_000, 10_000, 0);
+        let above_max_mintable = deposit(&mut rng, 100_000, 10_000, 0);
+        let above_rolling_cap = deposit(&mut rng, 30_000, 10_000, 0);
+
+        let low_fee = withdrawal(&mut rng, 10_000, 1, 0);
+        let first_withdrawal = withdrawal(&mut rng, 40_000, 10_000, 0);
+        let above_rolling_withdrawal_cap = withdrawal(&mut rng, 20_000, 10_000, 0);
+
+        let sbtc_limits = SbtcLimits::new(None, None, None, None, Some(Amount::from_sat(150_000)))
+            .with_rolling_caps(
+                Some(RollingCap {
+                    cap: Amount::from_sat(220_000),
+                    volume: Amount::from_sat(100_000),
+                }),
+                Some(RollingCap {
+                    cap: Amount::from_sat(100_000),
+                    volume: Amount::from_sat(50_000),
+                }),
+            );
+        let requests = sbtc_requests(
+            &mut rng,
+            vec![
+                dust.clone(),
+                first.clone(),
+                above_max_mintable.clone(),
+                above_rolling_cap.clone(),
+            ],
+            vec![
+                low_fee.clone(),
+                first_withdrawal.clone(),
+                above_rolling_withdrawal_cap.clone(),
+            ],
+            sbtc_limits,
+        );
+
+        let mut report = SweepDryRunReport::new(BitcoinBlockHash::from([1; 32]), 100);
+        report.add_requests(&requests, &[], Ok(Vec::new()));
+
+        let unvoted: model::DepositRequest = fake::Faker.fake_with_rng(&mut rng);
+        report.add_unvoted_deposits(&[unvoted.clone()]);
+
+        let deposit_reason = |outpoint: OutPoint| {
+            report
+                .deposits
+                .iter()
+                .find(|report| report.outpoint == outpoint)
+                .and_then(|report| report.excluded)
+                .unwrap()
+        };
+        let withdrawal_reason = |req: &WithdrawalRequest| {
+            report
+                .withdrawals
+                .iter()
+                .find(|report| report.request_id == req.request_id)
+                .and_then(|report| report.excluded)
+                .unwrap()
+        };
+
+        assert_eq!(
+            deposit_reason(dust.outpoint),
+            ExclusionReason::Deposit(InputValidationResult::MintAmountBelowDustLimit)
+        );
+        assert_eq!(
+            deposit_reason(first.outpoint),
+            ExclusionReason::PackageLimits
+        );
+        assert_eq!(
+            deposit_reason(above_max_mintable.outpoint),
+            ExclusionReason::MaxMintableCap
+        );
+        assert_eq!(
+            deposit_reason(above_rolling_cap.outpoint),
+            ExclusionReason::RollingDepositCap
+        );
+        assert_eq!(
+            deposit_reason(unvoted.outpoint()),
+            ExclusionReason::Deposit(InputValidationResult::NoVote)
+        );
+
+        assert_eq!(
+            withdrawal_reason(&low_fee),
+            ExclusionReason::WithdrawalFeeTooHigh
+        );
+        assert_eq!(
+            withdrawal_reason(&first_withdrawal),
+            ExclusionReason::PackageLimits
+        );
+        assert_eq!(
+            withdrawal_reason(&above_rolling_withdrawal_cap),
+            ExclusionReason::RollingWithdrawalCap
+        );
+    }
+
+    #[test]
+    fn packaged_requests_report_validation_results() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+        let deposit = deposit(&mut rng, 100_000, 10_000, 0);
+        let withdrawal = withdrawal(&mut rng, 50_000, 10_000, 0);
+        let requests = sbtc_requests(
+            &mut rng,
+            vec![deposit.clone()],
+            vec![withdrawal.clone()],
+            SbtcLimits::new_per_deposit(10_000, 1_000_000),
+        );
+
+        let packages = requests.construct_transactions().unwrap();
+        assert_eq!(packages.len(), 1);
+        let package = &packages[0];
+        let sighashes = package.construct_digests().unwrap();
+
+        // The deposit passes validation on its own, but withdrawals are
+        // not supported yet, so the transaction as a whole fails
+        // validation.
+        let chain_tip_height = 100;
+        let deposit_report = DepositRequestReport {
+            outpoint: deposit.outpoint,
+            status: DepositConfirmationStatus::Confirmed(
+                chain_tip_height - 1,
+                BitcoinBlockHash::from([2; 32]),
+            ),
+            can_sign: Some(true),
+            can_accept: Some(true),
+            amount: deposit.amount,
+            max_fee: deposit.max_fee,
+            lock_time: bitcoin::relative::LockTime::from_height(u16::MAX),
+            deposit_script: deposit.deposit_script.clone(),
+            reclaim_script: deposit.reclaim_script.clone(),
+            signers_public_key: deposit.signers_public_key,
+        };
+        let withdrawal_report = WithdrawalRequestReport {
+            id: QualifiedRequestId {
+                request_id: withdrawal.request_id,
+                txid: withdrawal.txid,
+                block_hash: withdrawal.block_hash,
+            },
+            status: WithdrawalRequestStatus::Confirmed(
+                chain_tip_height - 1,
+                BitcoinBlockHash::from([2; 32]),
+            ),
+            amount: withdrawal.amount,
+            max_fee: withdrawal.max_fee,
+            script_pubkey: withdrawal.script_pubkey.clone().into(),
+        };
+        let validation = BitcoinTxValidationData {
+            signer_sighash: sighashes.signer_sighash(),
+            deposit_sighashes: sighashes.deposit_sighashes(),
+            reports: SbtcReports {
+                deposits: vec![(deposit.clone(), deposit_report)],
+                withdrawals: vec![(withdrawal.clone(), withdrawal_report)],
+                signer_state: requests.signer_state,
+            },
+            chain_tip: BitcoinBlockHash::from([1; 32]),
+            tx: package.tx.clone(),
+            tx_fee: Amount::from_sat(package.tx_fee),
+            chain_tip_height,
+            sbtc_limits: requests.sbtc_limits.clone(),
+        };
+
+        let mut report = SweepDryRunReport::new(BitcoinBlockHash::from([1; 32]), chain_tip_height);
+        report.add_requests(&requests, &packages, Ok(vec![validation]));
+
+        assert_eq!(report.packages.len(), 1);
+        assert_eq!(report.packages[0].is_valid_tx, Some(false));
+
+        let [deposit_report] = report.deposits.as_slice() else {
+            panic!("expected one deposit report");
+        };
+        assert_eq!(deposit_report.package, Some(0));
+        assert!(deposit_report.assessed_fee.is_some());
+        assert_eq!(
+            deposit_report.excluded,
+            Some(ExclusionReason::PackageRejected)
+        );
+
+        let [withdrawal_report] = report.withdrawals.as_slice() else {
+            panic!("expected one withdrawal report");
+        };
+        assert_eq!(withdrawal_report.package, Some(0));
+        assert_eq!(
+            withdrawal_report.excluded,
+            Some(ExclusionReason::Withdrawal(
+                WithdrawalValidationResult::Unsupported
+            ))
+        );
+    }
+}
@@ -14,6 +14,7 @@ use rpc::GetTxResponse;
 use crate::error::Error;
 
 pub mod client;
+pub mod dry_run;
 pub mod fees;
 pub mod packaging;
 pub mod rpc;
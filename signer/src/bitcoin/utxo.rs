@@ -36,6 +36,7 @@ use serde::Serialize;
 use crate::bitcoin::packaging::compute_optimal_packages;
 use crate::bitcoin::packaging::Weighted;
 use crate::bitcoin::rpc::BitcoinTxInfo;
+use crate::bitcoin::validation::InputValidationResult;
 use crate::context::SbtcLimits;
 use crate::error::Error;
 use crate::keys::SignerScriptPubKey as _;
@@ -114,7 +115,23 @@ pub trait GetFees {
     fn get_fees(&self) -> Result<Option<Fees>, Error>;
 }
 
-/// Filter out the deposit requests that do not meet the amount or fee requirements.
+/// The reason that [`SbtcRequestsFilter`] filtered out a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// The deposit request would fail validation for the given reason.
+    Deposit(InputValidationResult),
+    /// Sweeping in the deposit would take the total amount minted above
+    /// the maximum mintable amount.
+    MaxMintableCap,
+    /// Sweeping in the deposit would take the amount pegged-in within
+    /// Emily's rolling window above the rolling deposit cap.
+    RollingDepositCap,
+    /// Servicing the withdrawal would take the amount pegged-out within
+    /// Emily's rolling window above the rolling withdrawal cap.
+    RollingWithdrawalCap,
+}
+
+/// Filter out the requests that do not meet the amount or fee requirements.
 pub struct SbtcRequestsFilter<'a> {
     sbtc_limits: &'a SbtcLimits,
     minimum_fee: u64,
@@ -126,40 +143,75 @@ impl<'a> SbtcRequestsFilter<'a> {
         Self { sbtc_limits, minimum_fee }
     }
 
-    /// Validate deposit requests based on four constraints:
+    /// Check a deposit request against five constraints:
     /// 1. The user's max fee must be >= our minimum required fee for deposits
     ///     (based on fixed deposit tx size)
     /// 2. The deposit amount must be greater than or equal to the per-deposit minimum
     /// 3. The deposit amount must be less than or equal to the per-deposit cap
     /// 4. The total amount being minted must stay under the maximum allowed mintable amount
+    /// 5. The total amount being minted must stay under what is left of the
+    ///     rolling deposit cap
+    ///
+    /// On success, the total amount to mint with this deposit included is
+    /// returned, otherwise we return the reason for filtering it out.
+    pub fn check_deposit(
+        &self,
+        amount_to_mint: Amount,
+        req: &DepositRequest,
+    ) -> Result<Amount, FilterReason> {
+        let req_amount = Amount::from_sat(req.amount);
+
+        if req.max_fee.min(req.amount) < self.minimum_fee {
+            return Err(FilterReason::Deposit(InputValidationResult::FeeTooHigh));
+        }
+        if req.amount.saturating_sub(self.minimum_fee) < DEPOSIT_DUST_LIMIT {
+            let result = InputValidationResult::MintAmountBelowDustLimit;
+            return Err(FilterReason::Deposit(result));
+        }
+        if req_amount < self.sbtc_limits.per_deposit_minimum() {
+            return Err(FilterReason::Deposit(InputValidationResult::AmountTooLow));
+        }
+        if req_amount > self.sbtc_limits.per_deposit_cap() {
+            return Err(FilterReason::Deposit(InputValidationResult::AmountTooHigh));
+        }
+
+        let new_amount = match amount_to_mint.checked_add(req_amount) {
+            Some(new_amount) if new_amount <= self.sbtc_limits.max_mintable_cap() => new_amount,
+            _ => return Err(FilterReason::MaxMintableCap),
+        };
+        if new_amount > self.sbtc_limits.rolling_deposit_capacity() {
+            return Err(FilterReason::RollingDepositCap);
+        }
+        Ok(new_amount)
+    }
+
+    /// Check that the total amount being withdrawn with this withdrawal
+    /// request included stays under what is left of the rolling withdrawal
+    /// cap.
+    ///
+    /// On success, the total amount to withdraw with this withdrawal
+    /// included is returned, otherwise we return the reason for filtering
+    /// it out.
+    pub fn check_withdrawal(
+        &self,
+        amount_to_withdraw: Amount,
+        req: &WithdrawalRequest,
+    ) -> Result<Amount, FilterReason> {
+        match amount_to_withdraw.checked_add(Amount::from_sat(req.amount)) {
+            Some(new_amount) if new_amount <= self.sbtc_limits.rolling_withdrawal_capacity() => {
+                Ok(new_amount)
+            }
+            _ => Err(FilterReason::RollingWithdrawalCap),
+        }
+    }
+
     fn validate_deposit_amount(
         &self,
         amount_to_mint: &mut Amount,
         req: &'a DepositRequest,
     ) -> Option<RequestRef<'a>> {
-        let is_fee_valid = req.max_fee.min(req.amount) >= self.minimum_fee;
-        let is_above_dust = req.amount.saturating_sub(self.minimum_fee) >= DEPOSIT_DUST_LIMIT;
-        let req_amount = Amount::from_sat(req.amount);
-        let is_above_per_deposit_minimum = req_amount >= self.sbtc_limits.per_deposit_minimum();
-        let is_within_per_deposit_cap = req_amount <= self.sbtc_limits.per_deposit_cap();
-        let is_within_max_mintable_cap =
-            if let Some(new_amount) = amount_to_mint.checked_add(req_amount) {
-                new_amount <= self.sbtc_limits.max_mintable_cap()
-            } else {
-                false
-            };
-
-        if is_fee_valid
-            && is_above_dust
-            && is_above_per_deposit_minimum
-            && is_within_per_deposit_cap
-            && is_within_max_mintable_cap
-        {
-            *amount_to_mint += req_amount;
-            Some(RequestRef::Deposit(req))
-        } else {
-            None
-        }
+        *amount_to_mint = self.check_deposit(*amount_to_mint, req).ok()?;
+        Some(RequestRef::Deposit(req))
     }
 
     /// Filter sbtc deposits that don't meet the validation criteria.
@@ -172,6 +224,24 @@ impl<'a> SbtcRequestsFilter<'a> {
             .flatten()
             .collect()
     }
+
+    /// Filter sbtc withdrawals that don't meet the validation criteria.
+    pub fn filter_withdrawals<I>(&self, withdrawals: I) -> Vec<RequestRef<'a>>
+    where
+        I: IntoIterator<Item = &'a WithdrawalRequest>,
+    {
+        withdrawals
+            .into_iter()
+            .scan(Amount::ZERO, |amount_to_withdraw, req| {
+                let checked = self.check_withdrawal(*amount_to_withdraw, req).ok();
+                if let Some(new_amount) = checked {
+                    *amount_to_withdraw = new_amount;
+                }
+                Some(checked.map(|_| RequestRef::Withdrawal(req)))
+            })
+            .flatten()
+            .collect()
+    }
 }
 
 /// Summary of the Signers' UTXO and information necessary for
@@ -229,25 +299,16 @@ impl SbtcRequests {
             return Ok(Vec::new());
         }
 
+        let filter = self.requests_filter();
         // Now we filter withdrawal requests where the user's max fee
         // could be less than fee we may charge.
         let withdrawals = self
             .withdrawals
             .iter()
-            .filter(|req| {
-                // This is the size for a BTC transaction servicing
-                // a single withdrawal.
-                let withdrawal_output = req.as_tx_output();
-                let tx_vsize = BASE_WITHDRAWAL_TX_VSIZE + withdrawal_output.size() as f64;
-                req.max_fee >= self.compute_minimum_fee(tx_vsize)
-            })
-            .map(RequestRef::Withdrawal);
+            .filter(|req| self.is_withdrawal_fee_sufficient(req));
+        let withdrawals = filter.filter_withdrawals(withdrawals);
 
-        let request_filter = SbtcRequestsFilter::new(
-            &self.sbtc_limits,
-            self.compute_minimum_fee(SOLO_DEPOSIT_TX_VSIZE),
-        );
-        let deposits = request_filter.filter_deposits(&self.deposits);
+        let deposits = filter.filter_deposits(&self.deposits);
 
         // Create a list of requests where each request can be approved on its own.
         let items = deposits.into_iter().chain(withdrawals);
@@ -275,7 +336,26 @@ impl SbtcRequests {
             .collect()
     }
 
-    fn reject_capacity(&self) -> u32 {
+    /// The filter that requests must pass before they are considered for
+    /// inclusion in a transaction package.
+    pub fn requests_filter(&self) -> SbtcRequestsFilter<'_> {
+        SbtcRequestsFilter::new(
+            &self.sbtc_limits,
+            self.compute_minimum_fee(SOLO_DEPOSIT_TX_VSIZE),
+        )
+    }
+
+    /// Whether the user's max fee covers the fee of a BTC transaction
+    /// servicing only the given withdrawal request.
+    pub fn is_withdrawal_fee_sufficient(&self, req: &WithdrawalRequest) -> bool {
+        let withdrawal_output = req.as_tx_output();
+        let tx_vsize = BASE_WITHDRAWAL_TX_VSIZE + withdrawal_output.size() as f64;
+        req.max_fee >= self.compute_minimum_fee(tx_vsize)
+    }
+
+    /// The maximum number of votes against that a request may have and
+    /// still be included in a transaction.
+    pub fn reject_capacity(&self) -> u32 {
         self.num_signers.saturating_sub(self.accept_threshold) as u32
     }
 
@@ -1477,6 +1557,7 @@ mod tests {
     use stacks_common::types::chainstate::StacksAddress;
     use test_case::test_case;
 
+    use crate::context::RollingCap;
     use crate::testing;
     use crate::testing::btc::base_signer_transaction;
     use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
@@ -1536,6 +1617,13 @@ mod tests {
         )
     }
 
+    fn rolling_cap(cap: u64, volume: u64) -> RollingCap {
+        RollingCap {
+            cap: Amount::from_sat(cap),
+            volume: Amount::from_sat(volume),
+        }
+    }
+
     /// Create a new deposit request depositing from a random public key.
     fn create_deposit(amount: u64, max_fee: u64, signer_bitmap: u128) -> DepositRequest {
         let signers_public_key = generate_x_only_public_key();
@@ -3156,6 +3244,16 @@ mod tests {
         &create_limits_for_deposits_and_max_mintable(10_000, 20_000, 40_000),
         1_000,
         2, 30_000; "should_respect_all_limits")]
+    #[test_case(
+        &vec![
+            create_deposit(10_000, 10_000, 0), // accepted
+            create_deposit(10_000, 10_000, 0), // rejected (above rolling capacity)
+            create_deposit(5_000, 10_000, 0),  // accepted
+        ],
+        &create_limits_for_deposits_and_max_mintable(0, 20_000, 40_000)
+            .with_rolling_caps(Some(rolling_cap(30_000, 15_000)), None),
+        1_000,
+        2, 15_000; "should_respect_rolling_deposit_capacity")]
     #[tokio::test]
     async fn test_deposit_filter_filters_deposits_over_limits(
         deposits: &Vec<DepositRequest>,
@@ -3177,4 +3275,43 @@ mod tests {
         assert_eq!(deposits.len(), num_accepted_deposits);
         assert_eq!(total_amount, accepted_amount);
     }
+
+    #[test_case(
+        &vec![
+            create_withdrawal(6_000, 10_000, 0),
+            create_withdrawal(6_000, 10_000, 0),
+            create_withdrawal(4_000, 10_000, 0),
+        ],
+        None,
+        3, 16_000; "should_accept_all_withdrawals_without_rolling_cap")]
+    #[test_case(
+        &vec![
+            create_withdrawal(6_000, 10_000, 0), // accepted
+            create_withdrawal(6_000, 10_000, 0), // rejected (above rolling capacity)
+            create_withdrawal(4_000, 10_000, 0), // accepted
+        ],
+        Some(rolling_cap(20_000, 10_000)),
+        2, 10_000; "should_respect_rolling_withdrawal_capacity")]
+    #[test_case(
+        &vec![create_withdrawal(1, 10_000, 0)],
+        Some(rolling_cap(20_000, 25_000)),
+        0, 0; "should_reject_withdrawals_when_window_is_over_cap")]
+    fn test_requests_filter_filters_withdrawals_over_rolling_cap(
+        withdrawals: &Vec<WithdrawalRequest>,
+        rolling_withdrawal_cap: Option<RollingCap>,
+        num_accepted_withdrawals: usize,
+        accepted_amount: u64,
+    ) {
+        let sbtc_limits = SbtcLimits::default().with_rolling_caps(None, rolling_withdrawal_cap);
+        let filter = SbtcRequestsFilter::new(&sbtc_limits, 0);
+
+        let withdrawals = filter.filter_withdrawals(withdrawals);
+        let total_amount: u64 = withdrawals
+            .iter()
+            .map(|req| req.as_withdrawal().unwrap().amount)
+            .sum();
+
+        assert_eq!(withdrawals.len(), num_accepted_withdrawals);
+        assert_eq!(total_amount, accepted_amount);
+    }
 }
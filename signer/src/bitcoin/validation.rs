@@ -214,6 +214,63 @@ impl BitcoinPreSignRequest {
         Ok(())
     }
 
+    /// Check that the requests stay under what is left of the rolling
+    /// window caps.
+    ///
+    /// The requests that this signer has already agreed to sweep are taken
+    /// out of the volume reported by Emily, since Emily counts them once
+    /// their sweep is broadcast, and they are part of this package again
+    /// when that sweep is replaced or signed again.
+    async fn validate_rolling_caps<C>(
+        &self,
+        ctx: &C,
+        cache: &ValidationCache<'_>,
+    ) -> Result<(), Error>
+    where
+        C: Context + Send + Sync,
+    {
+        let db = ctx.get_storage();
+
+        let mut swept_deposits = 0u64;
+        for ((txid, output_index), (report, _)) in cache.deposit_reports.iter() {
+            let txid = BitcoinTxId::from(**txid);
+            if db.is_deposit_in_signed_sweep(&txid, *output_index).await? {
+                swept_deposits = swept_deposits.saturating_add(report.amount);
+            }
+        }
+        let mut swept_withdrawals = 0u64;
+        for (id, (report, _)) in cache.withdrawal_reports.iter() {
+            if db.is_withdrawal_in_signed_sweep(id).await? {
+                swept_withdrawals = swept_withdrawals.saturating_add(report.amount);
+            }
+        }
+
+        let limits = ctx.state().get_current_limits().excluding_rolling_volume(
+            Amount::from_sat(swept_deposits),
+            Amount::from_sat(swept_withdrawals),
+        );
+
+        let capacity = limits.rolling_deposit_capacity().to_sat();
+        let total_amount = cache
+            .deposit_reports
+            .values()
+            .fold(0u64, |acc, (report, _)| acc.saturating_add(report.amount));
+        if total_amount > capacity {
+            return Err(Error::ExceedsRollingDepositCap { total_amount, capacity });
+        }
+
+        let capacity = limits.rolling_withdrawal_capacity().to_sat();
+        let total_amount = cache
+            .withdrawal_reports
+            .values()
+            .fold(0u64, |acc, (report, _)| acc.saturating_add(report.amount));
+        if total_amount > capacity {
+            return Err(Error::ExceedsRollingWithdrawalCap { total_amount, capacity });
+        }
+
+        Ok(())
+    }
+
     /// Construct the reports for each request that this transaction will
     /// service.
     pub async fn construct_package_sighashes<C>(
@@ -229,6 +286,7 @@ impl BitcoinPreSignRequest {
         let cache = self.fetch_all_reports(&ctx.get_storage(), btc_ctx).await?;
 
         self.validate_max_mintable(ctx, &cache).await?;
+        self.validate_rolling_caps(ctx, &cache).await?;
 
         let signer_utxo = ctx
             .get_storage()
@@ -524,8 +582,22 @@ impl SbtcReports {
 }
 
 /// The responses for validation of a sweep transaction on bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::Display,
+    serde::Serialize,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum InputValidationResult {
     /// The deposit request passed validation
@@ -581,8 +653,22 @@ impl InputValidationResult {
 
 /// The responses for validation of the outputs of a sweep transaction on
 /// bitcoin.
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::Display,
+    serde::Serialize,
+)]
 #[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub enum WithdrawalValidationResult {
     /// The withdrawal request amount exceeds the allowed per-withdrawal cap
@@ -889,9 +975,15 @@ mod tests {
     use bitcoin::Witness;
     use test_case::test_case;
 
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::context::RollingCap;
     use crate::context::SbtcLimits;
     use crate::storage::model::StacksBlockHash;
     use crate::storage::model::StacksTxId;
+    use crate::storage::model::TxPrevoutType;
+    use crate::storage::DbWrite as _;
     use crate::testing::context::TestContext;
 
     use super::*;
@@ -1576,4 +1668,80 @@ mod tests {
             (result, expected) => panic!("Expected {:?} but got {:?}", expected, result),
         };
     }
+
+    #[test_case(vec![1000, 2000], 5000, 1000, false, None; "should_accept_deposits_under_rolling_cap")]
+    #[test_case(vec![2000, 2000], 5000, 1000, false, None; "should_accept_deposits_equal_to_rolling_capacity")]
+    #[test_case(vec![2000, 2001], 5000, 1000, false, Some(4001); "should_reject_deposits_over_rolling_capacity")]
+    #[test_case(vec![1], 5000, 6000, false, Some(1); "should_reject_deposits_when_window_is_over_cap")]
+    #[test_case(vec![1000, 2000], 5000, 5000, false, Some(3000); "should_reject_deposits_when_window_is_full")]
+    #[test_case(vec![1000, 2000], 5000, 5000, true, None; "should_not_count_deposits_of_signed_sweeps_twice")]
+    #[tokio::test]
+    async fn test_validate_rolling_deposit_cap(
+        deposit_amounts: Vec<u64>,
+        cap: u64,
+        volume: u64,
+        in_signed_sweep: bool,
+        rejected_total: Option<u64>,
+    ) {
+        let context = TestContext::default_mocked();
+        let rolling_cap = RollingCap {
+            cap: Amount::from_sat(cap),
+            volume: Amount::from_sat(volume),
+        };
+        context.state().update_current_limits(
+            SbtcLimits::default().with_rolling_caps(Some(rolling_cap), None),
+        );
+
+        let deposit_reports: Vec<(DepositRequestReport, SignerVotes)> = deposit_amounts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, amount)| create_test_report(idx as u8, amount))
+            .collect();
+
+        // The deposits were swept by an earlier sweep that this signer
+        // agreed to sign, so Emily already counts them in the volume.
+        if in_signed_sweep {
+            let sighashes: Vec<BitcoinTxSigHash> = deposit_reports
+                .iter()
+                .map(|(report, _)| BitcoinTxSigHash {
+                    prevout_txid: report.outpoint.txid.into(),
+                    prevout_output_index: report.outpoint.vout,
+                    prevout_type: TxPrevoutType::Deposit,
+                    will_sign: true,
+                    ..Faker.fake()
+                })
+                .collect();
+            context
+                .get_storage_mut()
+                .write_bitcoin_txs_sighashes(&sighashes)
+                .await
+                .unwrap();
+        }
+        let mut cache = ValidationCache::default();
+        cache.deposit_reports = deposit_reports
+            .iter()
+            .map(|(report, votes)| {
+                (
+                    (&report.outpoint.txid, report.outpoint.vout),
+                    (report.clone(), votes.clone()),
+                )
+            })
+            .collect();
+
+        let request = BitcoinPreSignRequest {
+            request_package: vec![],
+            fee_rate: 2.0,
+            last_fees: None,
+        };
+        let result = request.validate_rolling_caps(&context, &cache).await;
+
+        match (result, rejected_total) {
+            (Ok(()), None) => {}
+            (Err(Error::ExceedsRollingDepositCap { total_amount, capacity }), Some(expected)) => {
+                assert_eq!(total_amount, expected);
+                assert_eq!(capacity, cap.saturating_sub(volume));
+            }
+            (result, expected) => panic!("Expected {:?} but got {:?}", expected, result),
+        };
+    }
 }
@@ -26,14 +26,19 @@ use crate::bitcoin::utxo::TxDeconstructor as _;
 use crate::bitcoin::BitcoinInteract;
 use crate::context::Context;
 use crate::context::SbtcLimits;
+use crate::context::SignerCommand;
 use crate::context::SignerEvent;
+use crate::context::SignerSignal;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::metrics::Metrics;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
+use crate::request_lifecycle;
 use crate::stacks::api::GetNakamotoStartHeight as _;
 use crate::stacks::api::StacksInteract;
 use crate::stacks::api::TenureBlocks;
+use crate::stacks::blockchain_api::BlockchainApiClient;
+use crate::stacks::contracts::ActiveContracts;
 use crate::storage;
 use crate::storage::model;
 use crate::storage::DbRead;
@@ -47,7 +52,26 @@ use futures::stream::Stream;
 use futures::stream::StreamExt;
 use sbtc::deposits::CreateDepositRequest;
 use sbtc::deposits::DepositInfo;
+use sbtc::events::RegistryEvent;
+use sbtc::events::TxInfo;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use tracing::Instrument as _;
+
+/// The number of stacks blocks back from the stacks chain tip that are
+/// checked for events that the event observer missed. With stacks blocks
+/// every few seconds this covers well over an hour of downtime.
+const MAX_EVENT_BACKFILL_DEPTH: u16 = 2000;
+
+/// The maximum number of stacks blocks whose events are fetched in one
+/// run of the registry event backfill. The remaining blocks are picked up
+/// by the runs that follow the next bitcoin blocks.
+const MAX_EVENT_BACKFILL_BLOCKS_PER_RUN: usize = 100;
+
+/// The number of runs of the registry event backfill that may fail to
+/// scan a stacks block before the backfill gives up on it, so that blocks
+/// that the API will never serve do not hold up the blocks after them.
+const MAX_EVENT_BACKFILL_ATTEMPTS: u32 = 5;
 
 /// Block observer
 #[derive(Debug)]
@@ -142,33 +166,15 @@ where
 
             match poll.await {
                 Ok(Some(Ok(block_hash))) => {
-                    tracing::info!("observed new bitcoin block from stream");
-                    metrics::counter!(
-                        Metrics::BlocksObservedTotal,
-                        "blockchain" => BITCOIN_BLOCKCHAIN,
-                    )
-                    .increment(1);
-
-                    if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
-                        tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
-                    }
-
-                    if let Err(error) = self.process_stacks_blocks().await {
-                        tracing::warn!(%error, "could not process stacks blocks");
-                    }
-
-                    if let Err(error) = self.update_sbtc_limits().await {
-                        tracing::warn!(%error, "could not update sBTC limits");
-                        continue;
-                    }
-
-                    tracing::info!("loading latest deposit requests from Emily");
-                    if let Err(error) = self.load_latest_deposit_requests().await {
-                        tracing::warn!(%error, "could not load latest deposit requests from Emily");
-                    }
-
-                    self.context
-                        .signal(SignerEvent::BitcoinBlockObserved.into())?;
+                    // Every block starts a new trace, rather than adding
+                    // to the trace of the event loop, which only ends when
+                    // the signer stops.
+                    let span = tracing::info_span!(
+                        parent: None,
+                        "block-observer-iteration",
+                        %block_hash,
+                    );
+                    self.handle_new_block(block_hash).instrument(span).await?;
                 }
                 Ok(Some(Err(error))) => {
                     tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
@@ -182,6 +188,241 @@ where
 
         Ok(())
     }
+
+    /// Process a new bitcoin block from the stream, and signal the other
+    /// event loops once it has been processed. Only errors from signalling
+    /// are returned.
+    async fn handle_new_block(&mut self, block_hash: bitcoin::BlockHash) -> Result<(), Error> {
+        tracing::info!("observed new bitcoin block from stream");
+        metrics::counter!(
+            Metrics::BlocksObservedTotal,
+            "blockchain" => BITCOIN_BLOCKCHAIN,
+        )
+        .increment(1);
+
+        if let Err(error) = self.process_bitcoin_blocks_until(block_hash).await {
+            tracing::warn!(%error, %block_hash, "could not process bitcoin blocks");
+        }
+
+        if let Err(error) = self.process_stacks_blocks().await {
+            tracing::warn!(%error, "could not process stacks blocks");
+        }
+
+        if let Err(error) = self.prune_event_observer_journal().await {
+            tracing::warn!(%error, "could not prune the event observer journal");
+        }
+
+        if let Err(error) = self.update_active_contracts().await {
+            tracing::warn!(%error, "could not update active protocol contracts");
+        }
+
+        if let Err(error) = self.update_sbtc_limits().await {
+            tracing::warn!(%error, "could not update sBTC limits");
+            return Ok(());
+        }
+
+        tracing::info!("loading latest deposit requests from Emily");
+        if let Err(error) = self.load_latest_deposit_requests().await {
+            tracing::warn!(%error, "could not load latest deposit requests from Emily");
+        }
+
+        request_lifecycle::update_backlog_gauges(&self.context.get_storage()).await;
+
+        self.context
+            .signal(SignerEvent::BitcoinBlockObserved.into())
+    }
+}
+
+/// Writes the sbtc-registry events of stacks blocks whose events never
+/// reached our event observer, say because the signer was down when the
+/// stacks node posted them.
+///
+/// The events are fetched from the Stacks blockchain API, one request at
+/// a time, so this runs in its own task after the block observer has
+/// processed a bitcoin block rather than as part of that processing.
+#[derive(Debug)]
+pub struct RegistryEventBackfill<C> {
+    /// Signer context
+    pub context: C,
+}
+
+/// This function defines which messages the registry event backfill is
+/// interested in.
+fn backfill_message_filter(signal: &SignerSignal) -> bool {
+    matches!(
+        signal,
+        SignerSignal::Command(SignerCommand::Shutdown)
+            | SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+    )
+}
+
+impl<C: Context> RegistryEventBackfill<C> {
+    /// Run the registry event backfill
+    #[tracing::instrument(skip_all, name = "registry-event-backfill")]
+    pub async fn run(self) -> Result<(), Error> {
+        let mut signal_stream = self.context.as_signal_stream(backfill_message_filter);
+        // The number of runs that failed to scan each of the unscanned
+        // stacks blocks.
+        let mut failed_attempts = HashMap::new();
+
+        while let Some(message) = signal_stream.next().await {
+            match message {
+                SignerSignal::Command(SignerCommand::Shutdown) => break,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved) => {
+                    let span =
+                        tracing::info_span!(parent: None, "registry-event-backfill-iteration");
+                    if let Err(error) = self
+                        .backfill_registry_events(&mut failed_attempts)
+                        .instrument(span)
+                        .await
+                    {
+                        tracing::warn!(%error, "could not backfill sbtc-registry events");
+                    }
+
+                    let message = SignerEvent::RegistryEventsBackfilled.into();
+                    if self.context.signal(message).is_err() {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        tracing::info!("registry event backfill has stopped");
+        Ok(())
+    }
+
+    /// Backfill the events of the stacks blocks below the chain tip that
+    /// have not been scanned, oldest first and at most
+    /// [`MAX_EVENT_BACKFILL_BLOCKS_PER_RUN`] of them.
+    ///
+    /// Nothing is done unless a Stacks blockchain API is configured. A
+    /// block that the API has not indexed yet, or whose events could not
+    /// be fetched or written, is left unscanned and tried again the next
+    /// time around. Once a block has failed
+    /// [`MAX_EVENT_BACKFILL_ATTEMPTS`] times it is skipped until the
+    /// signer restarts, so that the blocks after it still get backfilled.
+    async fn backfill_registry_events(
+        &self,
+        failed_attempts: &mut HashMap<model::StacksBlockHash, u32>,
+    ) -> Result<(), Error> {
+        let Some(endpoint) = &self.context.config().stacks.blockchain_api_endpoint else {
+            return Ok(());
+        };
+        let db = self.context.get_storage();
+        let Some(chain_tip) = db.get_bitcoin_canonical_chain_tip().await? else {
+            return Ok(());
+        };
+
+        let stacks_blocks = db
+            .get_stacks_blocks_without_event_scan(&chain_tip, MAX_EVENT_BACKFILL_DEPTH)
+            .await?;
+        // Blocks that have been scanned since, or that are now too far
+        // below the chain tip, are no longer tracked.
+        let unscanned: HashSet<_> = stacks_blocks.iter().map(|block| block.block_hash).collect();
+        failed_attempts.retain(|block_hash, _| unscanned.contains(block_hash));
+
+        let stacks_blocks: Vec<model::StacksBlock> = stacks_blocks
+            .into_iter()
+            .filter(|block| {
+                failed_attempts
+                    .get(&block.block_hash)
+                    .map_or(true, |attempts| *attempts < MAX_EVENT_BACKFILL_ATTEMPTS)
+            })
+            .take(MAX_EVENT_BACKFILL_BLOCKS_PER_RUN)
+            .collect();
+        if stacks_blocks.is_empty() {
+            return Ok(());
+        }
+
+        tracing::info!(count = %stacks_blocks.len(), "backfilling sbtc-registry events");
+        let client = BlockchainApiClient::new(endpoint.clone())?;
+
+        for stacks_block in stacks_blocks {
+            let block_hash = stacks_block.block_hash;
+            let scanned = match self.backfill_stacks_block(&client, stacks_block).await {
+                Ok(scanned) => scanned,
+                Err(error) => {
+                    tracing::warn!(%error, %block_hash, "could not backfill the events of a stacks block");
+                    false
+                }
+            };
+            if scanned {
+                continue;
+            }
+
+            let attempts = failed_attempts.entry(block_hash).or_insert(0);
+            *attempts += 1;
+            if *attempts == MAX_EVENT_BACKFILL_ATTEMPTS {
+                tracing::warn!(
+                    %block_hash,
+                    attempts = %MAX_EVENT_BACKFILL_ATTEMPTS,
+                    "giving up on backfilling the events of a stacks block"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the sbtc-registry events of the given stacks block and
+    /// record that the block has been scanned. Returns whether the block
+    /// was scanned, which it is not if the API has not indexed it or if
+    /// one of its events could not be decoded.
+    async fn backfill_stacks_block(
+        &self,
+        client: &BlockchainApiClient,
+        stacks_block: model::StacksBlock,
+    ) -> Result<bool, Error> {
+        let registry = crate::api::sbtc_registry_identifier(&self.context);
+        let block_id = stacks_block.block_hash.into();
+        let events = client
+            .get_contract_print_events(&block_id, registry)
+            .await?;
+        let Some(events) = events else {
+            tracing::debug!(block_hash = %stacks_block.block_hash, "stacks block not indexed yet");
+            return Ok(false);
+        };
+
+        // The requests in these events reached their stage when the
+        // block was produced, which may have been a while ago.
+        let mut decoded_every_event = true;
+        for print_event in events {
+            let txid = print_event.txid;
+            let tx_info = TxInfo {
+                txid: sbtc::events::StacksTxid(txid.0),
+                block_id,
+            };
+            match RegistryEvent::try_new(print_event.value, tx_info) {
+                Ok(event) => {
+                    crate::api::write_registry_event(
+                        &self.context,
+                        event,
+                        txid.into(),
+                        &stacks_block,
+                        print_event.block_time,
+                    )
+                    .await?
+                }
+                Err(error) => {
+                    tracing::error!(%error, %tx_info, "could not decode backfilled registry event");
+                    decoded_every_event = false;
+                }
+            }
+        }
+
+        // A block with an event that we could not decode is left
+        // unscanned, so that the event is tried again.
+        if !decoded_every_event {
+            return Ok(false);
+        }
+
+        self.context
+            .get_storage_mut()
+            .write_stacks_block_event_scan(&stacks_block.block_hash)
+            .await?;
+        Ok(true)
+    }
 }
 
 impl<C: Context, B> BlockObserver<C, B> {
@@ -384,6 +625,25 @@ impl<C: Context, B> BlockObserver<C, B> {
         Ok(())
     }
 
+    /// Remove the webhook bodies in the event observer journal that are
+    /// past the configured retention period.
+    async fn prune_event_observer_journal(&self) -> Result<(), Error> {
+        let retention = self
+            .context
+            .config()
+            .signer
+            .event_observer
+            .journal_retention;
+        let count = self
+            .context
+            .get_storage_mut()
+            .delete_event_observer_payloads_older_than(retention)
+            .await?;
+
+        tracing::debug!(%count, "pruned the event observer journal");
+        Ok(())
+    }
+
     /// For each of the deposit requests, persist the corresponding
     /// transaction and the parsed deposit info into the database.
     ///
@@ -421,10 +681,21 @@ impl<C: Context, B> BlockObserver<C, B> {
             .into_iter()
             .unzip();
 
+        let outpoints = deposit_requests
+            .iter()
+            .map(|req: &model::DepositRequest| (req.txid, req.output_index))
+            .collect::<Vec<_>>();
+
         let db = self.context.get_storage_mut();
         db.write_bitcoin_transactions(deposit_request_txs).await?;
         db.write_deposit_requests(deposit_requests).await?;
 
+        let now = time::OffsetDateTime::now_utc();
+        for (txid, output_index) in outpoints {
+            let stage = model::RequestStage::FirstSeen;
+            request_lifecycle::record_deposit_stage(&db, &txid, output_index, stage, now).await;
+        }
+
         Ok(())
     }
 
@@ -502,12 +773,31 @@ impl<C: Context, B> BlockObserver<C, B> {
                         "blockchain" => BITCOIN_BLOCKCHAIN,
                     )
                     .increment(1);
+                    request_lifecycle::record_deposit_stage(
+                        &db,
+                        &prevout.prevout_txid,
+                        prevout.prevout_output_index,
+                        model::RequestStage::SweepConfirmed,
+                        time::OffsetDateTime::now_utc(),
+                    )
+                    .await;
                 }
             }
 
             for output in tx_info.to_outputs(&signer_script_pubkeys) {
                 db.write_tx_output(&output).await?;
             }
+
+            // The withdrawals that a sweep transaction fulfills are
+            // recorded by the signers when they validate it.
+            if tx_type == model::TransactionType::SbtcTransaction {
+                let sweep_txid: model::BitcoinTxId = txid.into();
+                for request_id in db.get_withdrawal_request_ids_swept_by(&sweep_txid).await? {
+                    let stage = model::RequestStage::SweepConfirmed;
+                    let now = time::OffsetDateTime::now_utc();
+                    request_lifecycle::record_withdrawal_stage(&db, request_id, stage, now).await;
+                }
+            }
         }
 
         // Write these transactions into storage.
@@ -541,32 +831,7 @@ impl<C: Context, B> BlockObserver<C, B> {
 
     /// Update the sBTC peg limits from Emily
     async fn update_sbtc_limits(&self) -> Result<(), Error> {
-        let limits = self.context.get_emily_client().get_limits().await?;
-        let sbtc_deployed = self.context.state().sbtc_contracts_deployed();
-
-        let max_mintable = if limits.total_cap_exists() && sbtc_deployed {
-            let sbtc_supply = self
-                .context
-                .get_stacks_client()
-                .get_sbtc_total_supply(&self.context.config().signer.deployer)
-                .await?;
-            // The maximum amount of sBTC that can be minted is the total cap
-            // minus the current supply.
-            limits
-                .total_cap()
-                .checked_sub(sbtc_supply)
-                .unwrap_or(Amount::ZERO)
-        } else {
-            Amount::MAX_MONEY
-        };
-
-        let limits = SbtcLimits::new(
-            Some(limits.total_cap()),
-            Some(limits.per_deposit_minimum()),
-            Some(limits.per_deposit_cap()),
-            Some(limits.per_withdrawal_cap()),
-            Some(max_mintable),
-        );
+        let limits = get_sbtc_limits(&self.context).await?;
 
         let signer_state = self.context.state();
         if limits == signer_state.get_current_limits() {
@@ -577,15 +842,79 @@ impl<C: Context, B> BlockObserver<C, B> {
         }
         Ok(())
     }
+
+    /// Update the versions of the protocol contracts that are active in
+    /// the sbtc-registry.
+    async fn update_active_contracts(&self) -> Result<(), Error> {
+        // The sbtc-registry does not exist until the contracts have been
+        // deployed, and until then every contract is at the first version.
+        if !self.context.state().sbtc_contracts_deployed() {
+            return Ok(());
+        }
+
+        let stacks = self.context.get_stacks_client();
+        let deployer = &self.context.config().signer.deployer;
+        let active_contracts = ActiveContracts::load(&stacks, deployer).await?;
+
+        let signer_state = self.context.state();
+        if active_contracts != signer_state.active_contracts() {
+            tracing::info!(?active_contracts, "active protocol contracts have changed");
+            signer_state.set_active_contracts(active_contracts);
+        }
+        Ok(())
+    }
+}
+
+/// Fetch the current sBTC peg limits from Emily.
+///
+/// The maximum mintable amount is the total cap less the current sBTC
+/// supply, which is only known once the sBTC contracts have been
+/// deployed.
+pub async fn get_sbtc_limits<C: Context>(ctx: &C) -> Result<SbtcLimits, Error> {
+    let limits = ctx.get_emily_client().get_limits().await?;
+    let sbtc_deployed = ctx.state().sbtc_contracts_deployed();
+
+    let max_mintable = if limits.total_cap_exists() && sbtc_deployed {
+        let sbtc_supply = ctx
+            .get_stacks_client()
+            .get_sbtc_total_supply(&ctx.config().signer.deployer)
+            .await?;
+        // The maximum amount of sBTC that can be minted is the total cap
+        // minus the current supply.
+        limits
+            .total_cap()
+            .checked_sub(sbtc_supply)
+            .unwrap_or(Amount::ZERO)
+    } else {
+        Amount::MAX_MONEY
+    };
+
+    Ok(SbtcLimits::new(
+        Some(limits.total_cap()),
+        Some(limits.per_deposit_minimum()),
+        Some(limits.per_deposit_cap()),
+        Some(limits.per_withdrawal_cap()),
+        Some(max_mintable),
+    )
+    .with_rolling_caps(
+        limits.rolling_deposit_cap(),
+        limits.rolling_withdrawal_cap(),
+    ))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
     use bitcoin::Amount;
     use bitcoin::BlockHash;
     use bitcoin::TxOut;
+    use blockstack_lib::types::chainstate::StacksBlockId;
     use fake::Dummy;
     use fake::Fake;
+
     use model::BitcoinTxId;
     use model::ScriptPubKey;
     use rand::SeedableRng;
@@ -598,6 +927,7 @@ mod tests {
     use crate::storage;
     use crate::testing::block_observer::TestHarness;
     use crate::testing::context::*;
+    use crate::testing::mock_chain::MockChainClient;
 
     use super::*;
 
@@ -648,6 +978,281 @@ mod tests {
         handle.abort();
     }
 
+    /// Wait until the canonical bitcoin chain tip in the database is the
+    /// given block.
+    async fn wait_for_canonical_tip<S: DbRead>(db: &S, block_hash: BlockHash) {
+        tokio::time::timeout(Duration::from_secs(3), async {
+            while db.get_bitcoin_canonical_chain_tip().await.unwrap() != Some(block_hash.into()) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("block observer did not reach the chain tip within timeout");
+    }
+
+    #[test(tokio::test)]
+    async fn block_observer_follows_reorgs_of_the_mock_chain() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let bitcoin_client = MockChainClient::default();
+        // The block observer cannot parse the height of blocks below
+        // height 17, so we start well above that.
+        bitcoin_client.generate_blocks(100);
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(bitcoin_client.clone())
+            .modify_settings(|settings| settings.signer.sbtc_bitcoin_start_height = Some(95))
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: bitcoin_client.block_hash_stream(),
+        };
+        let handle = tokio::spawn(block_observer.run());
+
+        let old_blocks = bitcoin_client.generate_blocks(5);
+        wait_for_canonical_tip(&storage, old_blocks[4]).await;
+
+        // Replace the last three blocks with four new ones.
+        let new_blocks = bitcoin_client.with_chain(|chain| chain.reorg(3));
+        wait_for_canonical_tip(&storage, new_blocks[3]).await;
+
+        // Every block from the start height on was written, including the
+        // ones that are no longer on the active chain.
+        let active_blocks: Vec<BlockHash> = bitcoin_client.with_chain(|chain| {
+            (95..=chain.tip_height())
+                .filter_map(|height| chain.block_hash_at(height))
+                .collect()
+        });
+        assert!(new_blocks.iter().all(|hash| active_blocks.contains(hash)));
+        for block_hash in active_blocks.iter().chain(&old_blocks) {
+            let persisted = storage
+                .get_bitcoin_block(&(*block_hash).into())
+                .await
+                .expect("storage error")
+                .expect("block wasn't persisted");
+            assert_eq!(persisted.block_hash, (*block_hash).into());
+        }
+
+        handle.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn registry_event_backfill_skips_stacks_blocks_that_fail() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 1..5);
+        let min_height = test_harness.min_block_height();
+
+        // The first request for the transactions of a stacks block gets
+        // a body that is not JSON, while every other stacks block has no
+        // transactions.
+        let requests = Arc::new(AtomicUsize::new(0));
+        let request_count = requests.clone();
+        let mut server = mockito::Server::new_async().await;
+        let transactions = serde_json::json!({
+            "limit": 50,
+            "offset": 0,
+            "total": 0,
+            "results": [],
+        })
+        .to_string();
+        let _mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/extended/v2/blocks/0x[0-9a-f]+/transactions".into()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body_from_request(
+                move |_| match request_count.fetch_add(1, Ordering::SeqCst) {
+                    0 => b"not json".to_vec(),
+                    _ => transactions.clone().into_bytes(),
+                },
+            )
+            .create_async()
+            .await;
+        let endpoint: url::Url = server.url().parse().unwrap();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| {
+                settings.signer.sbtc_bitcoin_start_height = min_height;
+                settings.stacks.blockchain_api_endpoint = Some(endpoint);
+            })
+            .build();
+
+        // The block observer is only told about the last bitcoin block, so
+        // the backfill runs once, after all blocks have been written.
+        let chain_tip = test_harness.bitcoin_blocks().last().unwrap().block_hash();
+        let (block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(1);
+        block_hash_tx.send(Ok(chain_tip)).await.unwrap();
+
+        let backfill = RegistryEventBackfill { context: ctx.clone() };
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+        };
+        let backfill_handle = tokio::spawn(backfill.run());
+        let block_observer_handle = tokio::spawn(block_observer.run());
+
+        ctx.wait_for_signal(Duration::from_secs(5), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::RegistryEventsBackfilled)
+            )
+        })
+        .await
+        .expect("registry event backfill failed to complete within timeout");
+
+        // Only the stacks block whose request failed is left unscanned,
+        // every block after it was scanned in the same run.
+        let unscanned = storage
+            .get_stacks_blocks_without_event_scan(&chain_tip.into(), MAX_EVENT_BACKFILL_DEPTH)
+            .await
+            .unwrap();
+        assert_eq!(unscanned.len(), 1);
+
+        let request_count = requests.load(Ordering::SeqCst);
+        assert!(request_count > 1);
+        assert!(request_count <= MAX_EVENT_BACKFILL_BLOCKS_PER_RUN);
+
+        backfill_handle.abort();
+        block_observer_handle.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn registry_event_backfill_gives_up_on_stacks_blocks_that_keep_failing() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 30, 8..12);
+        let min_height = test_harness.min_block_height();
+        let mut server = mockito::Server::new_async().await;
+        let endpoint: url::Url = server.url().parse().unwrap();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .modify_settings(|settings| {
+                settings.signer.sbtc_bitcoin_start_height = min_height;
+                settings.stacks.blockchain_api_endpoint = Some(endpoint);
+            })
+            .build();
+
+        // Let the block observer write the blocks, and only then run the
+        // backfill ourselves.
+        let chain_tip = test_harness.bitcoin_blocks().last().unwrap().block_hash();
+        let (block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(1);
+        block_hash_tx.send(Ok(chain_tip)).await.unwrap();
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+        };
+        let block_observer_handle = tokio::spawn(block_observer.run());
+
+        ctx.wait_for_signal(Duration::from_secs(5), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+            )
+        })
+        .await
+        .expect("block observer failed to observe the chain tip within timeout");
+        block_observer_handle.abort();
+
+        // The oldest stacks blocks, more of them than fit in one run,
+        // always get a body that is not JSON, while every other stacks
+        // block has no transactions.
+        let unscanned = storage
+            .get_stacks_blocks_without_event_scan(&chain_tip.into(), MAX_EVENT_BACKFILL_DEPTH)
+            .await
+            .unwrap();
+        let num_failing = MAX_EVENT_BACKFILL_BLOCKS_PER_RUN + 10;
+        assert!(unscanned.len() > num_failing);
+
+        let failing: HashSet<model::StacksBlockHash> = unscanned
+            .iter()
+            .take(num_failing)
+            .map(|block| block.block_hash)
+            .collect();
+        let failing_paths: HashSet<String> = failing
+            .iter()
+            .map(|block_hash| {
+                let block_id: StacksBlockId = (*block_hash).into();
+                format!("/extended/v2/blocks/0x{}/transactions", block_id.to_hex())
+            })
+            .collect();
+        let transactions = serde_json::json!({
+            "limit": 50,
+            "offset": 0,
+            "total": 0,
+            "results": [],
+        })
+        .to_string();
+        let _mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/extended/v2/blocks/0x[0-9a-f]+/transactions".into()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body_from_request(move |request| {
+                if failing_paths.contains(request.path()) {
+                    b"not json".to_vec()
+                } else {
+                    transactions.clone().into_bytes()
+                }
+            })
+            .create_async()
+            .await;
+
+        // Until the failing blocks are given up on, every run is spent on
+        // them and the blocks after them are not backfilled.
+        let backfill = RegistryEventBackfill { context: ctx.clone() };
+        let mut failed_attempts = HashMap::new();
+        for _ in 0..MAX_EVENT_BACKFILL_ATTEMPTS {
+            backfill
+                .backfill_registry_events(&mut failed_attempts)
+                .await
+                .unwrap();
+        }
+        let still_unscanned = storage
+            .get_stacks_blocks_without_event_scan(&chain_tip.into(), MAX_EVENT_BACKFILL_DEPTH)
+            .await
+            .unwrap();
+        assert_eq!(still_unscanned.len(), unscanned.len());
+
+        // After that the failing blocks are skipped, so the remaining
+        // failing blocks are given up on too and every later block gets
+        // backfilled.
+        for _ in 0..MAX_EVENT_BACKFILL_ATTEMPTS {
+            backfill
+                .backfill_registry_events(&mut failed_attempts)
+                .await
+                .unwrap();
+        }
+        let still_unscanned: HashSet<model::StacksBlockHash> = storage
+            .get_stacks_blocks_without_event_scan(&chain_tip.into(), MAX_EVENT_BACKFILL_DEPTH)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|block| block.block_hash)
+            .collect();
+        assert_eq!(still_unscanned, failing);
+        assert!(failed_attempts
+            .values()
+            .all(|attempts| *attempts == MAX_EVENT_BACKFILL_ATTEMPTS));
+    }
+
     /// Test that `BlockObserver::load_latest_deposit_requests` takes
     /// deposits from emily, validates them and only keeps the ones that
     /// pass validation and have been confirmed.
@@ -898,6 +1503,7 @@ mod tests {
             public_shares: Vec::new(),
             signer_set_public_keys: vec![aggregate_key],
             signature_share_threshold: 1,
+            dkg_shares_status: model::DkgSharesStatus::Verified,
         };
         storage.write_encrypted_dkg_shares(&shares).await.unwrap();
 
@@ -10,7 +10,11 @@ use blocklist_api::apis::configuration::Configuration;
 use blocklist_api::apis::Error as ClientError;
 use blocklist_api::models::BlocklistStatus;
 use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::watch;
+use url::Url;
 
+use crate::config::Settings;
 use crate::context::Context;
 
 /// A trait for checking if an address is blocklisted.
@@ -27,11 +31,19 @@ pub trait BlocklistChecker {
 #[derive(Clone, Debug)]
 pub struct BlocklistClient {
     config: Configuration,
+    /// The latest settings of the signer. The endpoint of the blocklist
+    /// service is taken from here on every call, so that it can be
+    /// changed while the signer is running.
+    settings: watch::Receiver<Arc<Settings>>,
 }
 
 impl BlocklistChecker for BlocklistClient {
     async fn can_accept(&self, address: &str) -> Result<bool, ClientError<CheckAddressError>> {
-        let config = self.config.clone();
+        let mut config = self.config.clone();
+        let settings = self.settings.borrow().clone();
+        if let Some(blocklist_config) = &settings.blocklist_client {
+            config.base_path = base_path(&blocklist_config.endpoint);
+        }
 
         // Call the generated function from blocklist-api
         let resp: BlocklistStatus = check_address(&config, address).await?;
@@ -44,20 +56,15 @@ impl BlocklistClient {
     pub fn new(ctx: &impl Context) -> Option<Self> {
         let config = ctx.config().blocklist_client.as_ref()?;
 
-        let mut config = Configuration {
-            base_path: config.endpoint.to_string(),
+        let config = Configuration {
+            base_path: base_path(&config.endpoint),
             ..Default::default()
         };
 
-        // Url::parse defaults `path` to `/` even if the parsed url was without the trailing `/`
-        // causing the api calls to have two leading slashes in the path (getting a 404)
-        config.base_path = config
-            .base_path
-            .to_string()
-            .trim_end_matches("/")
-            .to_string();
-
-        Some(BlocklistClient { config })
+        Some(BlocklistClient {
+            config,
+            settings: ctx.subscribe_config(),
+        })
     }
 
     #[cfg(test)]
@@ -66,11 +73,22 @@ impl BlocklistClient {
             base_path: base_url.clone(),
             ..Default::default()
         };
+        let mut settings = Settings::new_from_default_config().unwrap();
+        settings.blocklist_client = None;
+        let (_, settings) = watch::channel(Arc::new(settings));
 
-        BlocklistClient { config }
+        BlocklistClient { config, settings }
     }
 }
 
+/// Return the base path of API calls to the blocklist service at the
+/// given endpoint.
+fn base_path(endpoint: &Url) -> String {
+    // Url::parse defaults `path` to `/` even if the parsed url was without the trailing `/`
+    // causing the api calls to have two leading slashes in the path (getting a 404)
+    endpoint.to_string().trim_end_matches("/").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
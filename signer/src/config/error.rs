@@ -65,4 +65,14 @@ pub enum SignerConfigError {
     /// An error returned for duration parameters that must be positive.
     #[error("Duration for {0} must be nonzero")]
     ZeroDurationForbidden(&'static str),
+
+    /// The log directives could not be parsed.
+    #[error("Invalid log directives: {0}")]
+    InvalidLogDirectives(#[source] tracing_subscriber::filter::ParseError),
+
+    /// Settings that were reloaded while the signer was running changed
+    /// settings that are only read when the signer starts. The names of
+    /// the settings are separated by commas.
+    #[error("These settings cannot be changed while the signer is running, restart the signer to change them: {0}")]
+    ImmutableSettingChanged(String),
 }
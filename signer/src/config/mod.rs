@@ -18,10 +18,12 @@ use crate::config::serialization::duration_seconds_deserializer;
 use crate::config::serialization::p2p_multiaddr_deserializer_vec;
 use crate::config::serialization::parse_stacks_address;
 use crate::config::serialization::private_key_deserializer;
+use crate::config::serialization::url_deserializer_option;
 use crate::config::serialization::url_deserializer_single;
 use crate::config::serialization::url_deserializer_vec;
 use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
+use crate::stacks::contracts::ActiveContracts;
 use crate::stacks::wallet::SignerWallet;
 use crate::DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX;
 
@@ -31,6 +33,16 @@ mod serialization;
 /// Maximum configurable delay (in seconds) before processing new Bitcoin blocks.
 pub const MAX_BITCOIN_PROCESSING_DELAY_SECONDS: u64 = 300;
 
+/// The settings that the signer reads while it is running, so that
+/// changes to them take effect when the settings are reloaded. Changes to
+/// any other setting are refused until the signer is restarted.
+pub const LIVE_RELOADABLE_SETTINGS: [&str; 4] = [
+    "blocklist_client.endpoint",
+    "emily.endpoints",
+    "signer.log_directives",
+    "signer.max_deposits_per_bitcoin_tx",
+];
+
 /// Trait for validating configuration values.
 trait Validatable {
     /// Validate the configuration values.
@@ -215,6 +227,18 @@ pub struct SignerConfig {
     pub db_endpoint: Url,
     /// The scrape endpoint for exporting metrics for Prometheus.
     pub prometheus_exporter_endpoint: Option<std::net::SocketAddr>,
+    /// The address that the admin API, which serves the sweep dry run
+    /// report, listens on. The admin API is not served when this is unset.
+    pub admin_api_endpoint: Option<std::net::SocketAddr>,
+    /// The base URL of an OpenTelemetry collector that spans are exported
+    /// to over OTLP/HTTP, like `http://localhost:4318`. Spans are not
+    /// exported when this is not set.
+    #[serde(default, deserialize_with = "url_deserializer_option")]
+    pub otlp_exporter_endpoint: Option<Url>,
+    /// The directives that decide which log lines are written, in the
+    /// format of the `RUST_LOG` environment variable, like
+    /// `info,signer=debug`. `RUST_LOG` takes precedence when it is set.
+    pub log_directives: String,
     /// The public keys of the signer sit during the bootstrapping phase of
     /// the signers.
     pub bootstrap_signing_set: Vec<PublicKey>,
@@ -245,6 +269,20 @@ pub struct SignerConfig {
     /// receiving a DKG begin message before relaying to give the other
     /// signers time to catch up.
     pub dkg_begin_pause: Option<u64>,
+    /// How long a submitted Stacks transaction can remain unconfirmed
+    /// before the coordinator rebuilds it with a higher fee.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub stacks_tx_resubmit_timeout: std::time::Duration,
+    /// Whether to execute contract calls against a local copy of the sBTC
+    /// contracts before signing them.
+    #[serde(default)]
+    pub simulate_contract_calls: bool,
+    /// The versions of the protocol contracts that this signer will run.
+    /// When a version here differs from the one that is active in the
+    /// sbtc-registry and the contract has been deployed, the coordinator
+    /// proposes activating it, and this signer agrees to activate it.
+    #[serde(default)]
+    pub contract_versions: ActiveContracts,
     /// The minimum bitcoin block height for which the sbtc signers will
     /// backfill bitcoin blocks to.
     pub sbtc_bitcoin_start_height: Option<u64>,
@@ -321,6 +359,16 @@ impl Validatable for SignerConfig {
                 SignerConfigError::ZeroDurationForbidden("signer_round_max_duration").to_string(),
             ));
         }
+        if cfg.signer.stacks_tx_resubmit_timeout == zero {
+            return Err(ConfigError::Message(
+                SignerConfigError::ZeroDurationForbidden("stacks_tx_resubmit_timeout").to_string(),
+            ));
+        }
+        if let Err(err) = tracing_subscriber::EnvFilter::try_new(&self.log_directives) {
+            return Err(ConfigError::Message(
+                SignerConfigError::InvalidLogDirectives(err).to_string(),
+            ));
+        }
         // db_endpoint note: we don't validate the host because we will never
         // get here; the URL deserializer will fail if the host is empty.
         Ok(())
@@ -347,6 +395,10 @@ impl SignerConfig {
 pub struct EventObserverConfig {
     /// The address and port to bind the server to.
     pub bind: std::net::SocketAddr,
+    /// How long the webhook bodies in the event observer journal are
+    /// kept before they are removed. Every body is journaled.
+    #[serde(deserialize_with = "duration_seconds_deserializer")]
+    pub journal_retention: std::time::Duration,
 }
 
 impl Settings {
@@ -400,11 +452,15 @@ impl Settings {
         cfg_builder = cfg_builder.set_default("signer.dkg_max_duration", 120)?;
         cfg_builder = cfg_builder.set_default("signer.bitcoin_presign_request_max_duration", 30)?;
         cfg_builder = cfg_builder.set_default("signer.signer_round_max_duration", 30)?;
+        cfg_builder = cfg_builder.set_default("signer.stacks_tx_resubmit_timeout", 300)?;
         cfg_builder = cfg_builder.set_default(
             "signer.max_deposits_per_bitcoin_tx",
             DEFAULT_MAX_DEPOSITS_PER_BITCOIN_TX,
         )?;
         cfg_builder = cfg_builder.set_default("signer.dkg_target_rounds", 1)?;
+        cfg_builder = cfg_builder.set_default("signer.log_directives", "info,signer=debug")?;
+        cfg_builder =
+            cfg_builder.set_default("signer.event_observer.journal_retention", 259_200)?;
 
         if let Some(path) = config_path {
             cfg_builder = cfg_builder.add_source(File::from(path.as_ref()));
@@ -426,6 +482,222 @@ impl Settings {
 
         Ok(())
     }
+
+    /// Check that the given settings, reloaded while the signer is
+    /// running, only differ from these settings in
+    /// [`LIVE_RELOADABLE_SETTINGS`].
+    ///
+    /// Every other setting is only read when the signer starts, so
+    /// changing it takes a restart. The error names all of the settings
+    /// that were changed and need one.
+    pub fn assert_reloadable(&self, new: &Settings) -> Result<(), ConfigError> {
+        let needs_restart: Vec<&str> = self
+            .changed_settings(new)
+            .into_iter()
+            .filter(|name| !LIVE_RELOADABLE_SETTINGS.contains(name))
+            .collect();
+
+        if needs_restart.is_empty() {
+            return Ok(());
+        }
+        Err(ConfigError::Message(
+            SignerConfigError::ImmutableSettingChanged(needs_restart.join(", ")).to_string(),
+        ))
+    }
+
+    /// Return the names of the settings that differ between these
+    /// settings and the given ones.
+    pub fn changed_settings(&self, new: &Settings) -> Vec<&'static str> {
+        // The settings are destructured without `..`, so that a new
+        // setting does not compile until it is compared here.
+        let Settings {
+            blocklist_client,
+            signer,
+            bitcoin,
+            stacks,
+            emily,
+        } = self;
+        let SignerConfig {
+            private_key,
+            p2p,
+            network,
+            event_observer,
+            deployer,
+            db_endpoint,
+            prometheus_exporter_endpoint,
+            admin_api_endpoint,
+            otlp_exporter_endpoint,
+            log_directives,
+            bootstrap_signing_set,
+            bootstrap_signatures_required,
+            bitcoin_processing_delay,
+            context_window,
+            signer_round_max_duration,
+            bitcoin_presign_request_max_duration,
+            dkg_max_duration,
+            dkg_begin_pause,
+            stacks_tx_resubmit_timeout,
+            simulate_contract_calls,
+            contract_versions,
+            sbtc_bitcoin_start_height,
+            max_deposits_per_bitcoin_tx,
+            dkg_min_bitcoin_block_height,
+            dkg_target_rounds,
+        } = signer;
+        let P2PNetworkConfig {
+            seeds,
+            listen_on,
+            public_endpoints,
+            enable_mdns,
+        } = p2p;
+        let EventObserverConfig { bind, journal_retention } = event_observer;
+        let BitcoinConfig {
+            rpc_endpoints,
+            block_hash_stream_endpoints,
+        } = bitcoin;
+        let StacksConfig {
+            endpoints: stacks_endpoints,
+            blockchain_api_endpoint,
+        } = stacks;
+        let EmilyClientConfig { endpoints: emily_endpoints } = emily;
+
+        let new_signer = &new.signer;
+        let blocklist_endpoint_changed = match (blocklist_client, &new.blocklist_client) {
+            (Some(current), Some(reloaded)) => current.endpoint != reloaded.endpoint,
+            _ => false,
+        };
+        let comparisons = [
+            (
+                "blocklist_client",
+                blocklist_client.is_some() != new.blocklist_client.is_some(),
+            ),
+            ("blocklist_client.endpoint", blocklist_endpoint_changed),
+            ("signer.private_key", *private_key != new_signer.private_key),
+            ("signer.p2p.seeds", *seeds != new_signer.p2p.seeds),
+            (
+                "signer.p2p.listen_on",
+                *listen_on != new_signer.p2p.listen_on,
+            ),
+            (
+                "signer.p2p.public_endpoints",
+                *public_endpoints != new_signer.p2p.public_endpoints,
+            ),
+            (
+                "signer.p2p.enable_mdns",
+                *enable_mdns != new_signer.p2p.enable_mdns,
+            ),
+            ("signer.network", *network != new_signer.network),
+            (
+                "signer.event_observer.bind",
+                *bind != new_signer.event_observer.bind,
+            ),
+            (
+                "signer.event_observer.journal_retention",
+                *journal_retention != new_signer.event_observer.journal_retention,
+            ),
+            ("signer.deployer", *deployer != new_signer.deployer),
+            ("signer.db_endpoint", *db_endpoint != new_signer.db_endpoint),
+            (
+                "signer.prometheus_exporter_endpoint",
+                *prometheus_exporter_endpoint != new_signer.prometheus_exporter_endpoint,
+            ),
+            (
+                "signer.admin_api_endpoint",
+                *admin_api_endpoint != new_signer.admin_api_endpoint,
+            ),
+            (
+                "signer.otlp_exporter_endpoint",
+                *otlp_exporter_endpoint != new_signer.otlp_exporter_endpoint,
+            ),
+            (
+                "signer.log_directives",
+                *log_directives != new_signer.log_directives,
+            ),
+            (
+                "signer.bootstrap_signing_set",
+                *bootstrap_signing_set != new_signer.bootstrap_signing_set,
+            ),
+            (
+                "signer.bootstrap_signatures_required",
+                *bootstrap_signatures_required != new_signer.bootstrap_signatures_required,
+            ),
+            (
+                "signer.bitcoin_processing_delay",
+                *bitcoin_processing_delay != new_signer.bitcoin_processing_delay,
+            ),
+            (
+                "signer.context_window",
+                *context_window != new_signer.context_window,
+            ),
+            (
+                "signer.signer_round_max_duration",
+                *signer_round_max_duration != new_signer.signer_round_max_duration,
+            ),
+            (
+                "signer.bitcoin_presign_request_max_duration",
+                *bitcoin_presign_request_max_duration
+                    != new_signer.bitcoin_presign_request_max_duration,
+            ),
+            (
+                "signer.dkg_max_duration",
+                *dkg_max_duration != new_signer.dkg_max_duration,
+            ),
+            (
+                "signer.dkg_begin_pause",
+                *dkg_begin_pause != new_signer.dkg_begin_pause,
+            ),
+            (
+                "signer.stacks_tx_resubmit_timeout",
+                *stacks_tx_resubmit_timeout != new_signer.stacks_tx_resubmit_timeout,
+            ),
+            (
+                "signer.simulate_contract_calls",
+                *simulate_contract_calls != new_signer.simulate_contract_calls,
+            ),
+            (
+                "signer.contract_versions",
+                *contract_versions != new_signer.contract_versions,
+            ),
+            (
+                "signer.sbtc_bitcoin_start_height",
+                *sbtc_bitcoin_start_height != new_signer.sbtc_bitcoin_start_height,
+            ),
+            (
+                "signer.max_deposits_per_bitcoin_tx",
+                *max_deposits_per_bitcoin_tx != new_signer.max_deposits_per_bitcoin_tx,
+            ),
+            (
+                "signer.dkg_min_bitcoin_block_height",
+                *dkg_min_bitcoin_block_height != new_signer.dkg_min_bitcoin_block_height,
+            ),
+            (
+                "signer.dkg_target_rounds",
+                *dkg_target_rounds != new_signer.dkg_target_rounds,
+            ),
+            (
+                "bitcoin.rpc_endpoints",
+                *rpc_endpoints != new.bitcoin.rpc_endpoints,
+            ),
+            (
+                "bitcoin.block_hash_stream_endpoints",
+                *block_hash_stream_endpoints != new.bitcoin.block_hash_stream_endpoints,
+            ),
+            (
+                "stacks.endpoints",
+                *stacks_endpoints != new.stacks.endpoints,
+            ),
+            (
+                "stacks.blockchain_api_endpoint",
+                *blockchain_api_endpoint != new.stacks.blockchain_api_endpoint,
+            ),
+            ("emily.endpoints", *emily_endpoints != new.emily.endpoints),
+        ];
+
+        comparisons
+            .into_iter()
+            .filter_map(|(name, changed)| changed.then_some(name))
+            .collect()
+    }
 }
 
 /// Settings associated with the stacks node that this signer uses for information
@@ -434,6 +706,11 @@ pub struct StacksConfig {
     /// The endpoint to use when making requests to a stacks node.
     #[serde(deserialize_with = "url_deserializer_vec")]
     pub endpoints: Vec<url::Url>,
+    /// The endpoint of a Stacks blockchain API. When set, the signer
+    /// uses it to backfill sbtc-registry events from stacks blocks that
+    /// never made it to our event observer.
+    #[serde(default, deserialize_with = "url_deserializer_option")]
+    pub blockchain_api_endpoint: Option<url::Url>,
 }
 
 impl Validatable for StacksConfig {
@@ -526,6 +803,9 @@ mod tests {
         assert_eq!(settings.signer.bootstrap_signatures_required, 2);
         assert_eq!(settings.signer.context_window, 1000);
         assert!(settings.signer.prometheus_exporter_endpoint.is_none());
+        assert!(settings.signer.admin_api_endpoint.is_none());
+        assert!(settings.signer.otlp_exporter_endpoint.is_none());
+        assert_eq!(settings.signer.log_directives, "info,signer=debug");
         assert_eq!(
             settings.signer.bitcoin_presign_request_max_duration,
             Duration::from_secs(30)
@@ -540,6 +820,45 @@ mod tests {
             NonZeroU32::new(1).unwrap()
         );
         assert_eq!(settings.signer.dkg_min_bitcoin_block_height, None);
+        assert!(settings.stacks.blockchain_api_endpoint.is_none());
+        assert_eq!(
+            settings.signer.event_observer.journal_retention,
+            Duration::from_secs(259_200)
+        );
+    }
+
+    #[test]
+    fn default_config_toml_loads_otlp_exporter_endpoint_with_environment() {
+        clear_env();
+
+        std::env::set_var(
+            "SIGNER_SIGNER__OTLP_EXPORTER_ENDPOINT",
+            "http://localhost:4318",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+
+        assert_eq!(
+            settings.signer.otlp_exporter_endpoint,
+            Some(url("http://localhost:4318"))
+        );
+    }
+
+    #[test]
+    fn default_config_toml_loads_stacks_blockchain_api_endpoint_with_environment() {
+        clear_env();
+
+        std::env::set_var(
+            "SIGNER_STACKS__BLOCKCHAIN_API_ENDPOINT",
+            "http://localhost:3999",
+        );
+
+        let settings = Settings::new_from_default_config().unwrap();
+
+        assert_eq!(
+            settings.stacks.blockchain_api_endpoint,
+            Some(url("http://localhost:3999"))
+        );
     }
 
     #[test]
@@ -834,6 +1153,7 @@ mod tests {
         remove_parameter("signer_round_max_duration");
         remove_parameter("bitcoin_presign_request_max_duration");
         remove_parameter("dkg_max_duration");
+        remove_parameter("stacks_tx_resubmit_timeout");
         remove_parameter("max_deposits_per_bitcoin_tx");
 
         let new_config = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
@@ -852,6 +1172,15 @@ mod tests {
             Duration::from_secs(30)
         );
         assert_eq!(settings.signer.dkg_max_duration, Duration::from_secs(120));
+        assert_eq!(
+            settings.signer.stacks_tx_resubmit_timeout,
+            Duration::from_secs(300)
+        );
+        assert!(!settings.signer.simulate_contract_calls);
+        assert_eq!(
+            settings.signer.contract_versions,
+            ActiveContracts::default()
+        );
     }
 
     #[test]
@@ -865,6 +1194,7 @@ mod tests {
         test_one("dkg_max_duration");
         test_one("bitcoin_presign_request_max_duration");
         test_one("signer_round_max_duration");
+        test_one("stacks_tx_resubmit_timeout");
     }
 
     #[test]
@@ -1209,4 +1539,78 @@ mod tests {
             ConfigError::Message(msg) if msg == SignerConfigError::UnsupportedDatabaseDriver(driver.to_string()).to_string()
         ));
     }
+
+    #[test]
+    fn invalid_log_directives_returns_correct_error() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__LOG_DIRECTIVES", "signer=loud");
+        let settings = Settings::new_from_default_config();
+        assert!(matches!(
+            settings.unwrap_err(),
+            ConfigError::Message(msg) if msg.starts_with("Invalid log directives")
+        ));
+    }
+
+    #[test]
+    fn reloadable_settings_can_change_endpoints_and_limits() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let mut reloaded = settings.clone();
+        reloaded.emily.endpoints = vec![url("http://emily:3031")];
+        reloaded.blocklist_client = None;
+        reloaded.signer.log_directives = "warn".to_string();
+        reloaded.signer.max_deposits_per_bitcoin_tx = NonZeroU16::new(5).unwrap();
+
+        settings.assert_reloadable(&reloaded).unwrap();
+    }
+
+    #[test_case::test_case(|s| s.signer.network = NetworkKind::Testnet, "signer.network"; "network")]
+    #[test_case::test_case(|s| s.bitcoin.rpc_endpoints = vec![url("http://bitcoin:18443")], "bitcoin.rpc_endpoints"; "bitcoin endpoints")]
+    #[test_case::test_case(|s| s.stacks.endpoints = vec![url("http://stacks:20443")], "stacks.endpoints"; "stacks endpoints")]
+    #[test_case::test_case(|s| s.signer.dkg_target_rounds = NonZeroU32::new(5).unwrap(), "signer.dkg_target_rounds"; "dkg target rounds")]
+    #[test_case::test_case(|s| s.signer.bootstrap_signing_set.clear(), "signer.bootstrap_signing_set"; "bootstrap signing set")]
+    #[test_case::test_case(|s| s.signer.private_key = PrivateKey::new(&mut rand::thread_rng()), "signer.private_key"; "private key")]
+    #[test_case::test_case(|s| s.signer.deployer = StacksAddress::burn_address(false), "signer.deployer"; "deployer")]
+    #[test_case::test_case(|s| s.blocklist_client = Some(BlocklistClientConfig { endpoint: url("http://blocklist:3032") }), "blocklist_client"; "blocklist client")]
+    fn reloadable_settings_refuse_immutable_changes(change: fn(&mut Settings), name: &'static str) {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let mut reloaded = settings.clone();
+        change(&mut reloaded);
+
+        let error = settings.assert_reloadable(&reloaded).unwrap_err();
+        assert!(matches!(
+            error,
+            ConfigError::Message(msg) if msg == SignerConfigError::ImmutableSettingChanged(name.to_string()).to_string()
+        ));
+    }
+
+    #[test]
+    fn reloadable_settings_name_every_setting_that_needs_a_restart() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        let mut reloaded = settings.clone();
+        reloaded.signer.context_window += 1;
+        reloaded.signer.log_directives = "warn".to_string();
+        reloaded.stacks.blockchain_api_endpoint = Some(url("http://stacks-api:3999"));
+
+        assert_eq!(
+            settings.changed_settings(&reloaded),
+            [
+                "signer.log_directives",
+                "signer.context_window",
+                "stacks.blockchain_api_endpoint"
+            ]
+        );
+        let error = settings.assert_reloadable(&reloaded).unwrap_err();
+        let names = "signer.context_window, stacks.blockchain_api_endpoint".to_string();
+        assert!(matches!(
+            error,
+            ConfigError::Message(msg) if msg == SignerConfigError::ImmutableSettingChanged(names).to_string()
+        ));
+    }
 }
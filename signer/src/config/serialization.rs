@@ -33,6 +33,15 @@ where
         .map_err(serde::de::Error::custom)
 }
 
+/// A deserializer for an optional url::Url, for use with
+/// `#[serde(default)]` so that a missing value is `None`.
+pub fn url_deserializer_option<'de, D>(deserializer: D) -> Result<Option<url::Url>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    url_deserializer_single(deserializer).map(Some)
+}
+
 /// A deserializer for the std::time::Duration type.
 /// Serde includes a default deserializer, but it expects a struct.
 pub fn duration_seconds_deserializer<'de, D>(
@@ -5,6 +5,8 @@ mod signer_context;
 mod signer_state;
 mod termination;
 
+use std::sync::Arc;
+
 use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -24,8 +26,18 @@ pub use termination::*;
 
 /// Context trait that is implemented by the [`SignerContext`].
 pub trait Context: Clone + Sync + Send {
-    /// Get the current configuration for the signer.
+    /// Get the configuration that the signer was started with.
     fn config(&self) -> &Settings;
+    /// Subscribe to the configuration of the signer. The receiver holds
+    /// the latest settings, which change when the settings are reloaded
+    /// while the signer is running.
+    fn subscribe_config(&self) -> tokio::sync::watch::Receiver<Arc<Settings>>;
+    /// Get the latest configuration of the signer. Components that can
+    /// apply changes to a setting without a restart should read the
+    /// setting from here instead of from [`Context::config`].
+    fn latest_config(&self) -> Arc<Settings> {
+        self.subscribe_config().borrow().clone()
+    }
     /// Get the current state for the signer.
     fn state(&self) -> &SignerState;
     /// Subscribe to the application signalling channel, returning a receiver
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::sync::RwLock;
 
 use tokio::sync::broadcast::Sender;
 use url::Url;
@@ -20,6 +21,9 @@ use super::{Context, SignerSignal, SignerState, TerminationHandle};
 #[derive(Debug, Clone)]
 pub struct SignerContext<S, BC, ST, EM> {
     config: Settings,
+    /// Handle to the channel with the latest settings, which are replaced
+    /// when the settings are reloaded while the signer is running.
+    config_tx: tokio::sync::watch::Sender<Arc<Settings>>,
     // Handle to the app signalling channel. This keeps the channel alive
     // for the duration of the program and is used both to send messages
     // and to hand out new receivers.
@@ -39,8 +43,9 @@ pub struct SignerContext<S, BC, ST, EM> {
     // count down.
     /// Handle to a Stacks-RPC fallback-client.
     stacks_client: ST,
-    /// Handle to a Emily-API fallback-client.
-    emily_client: EM,
+    /// Handle to a Emily-API fallback-client. This is replaced when the
+    /// Emily endpoints are changed by reloading the settings.
+    emily_client: Arc<RwLock<EM>>,
     // /// Handle to a Blocklist-API fallback-client.
     //blocklist_client: ApiFallbackClient<BL>,
 }
@@ -64,6 +69,30 @@ where
 
         Ok(Self::new(config, db, bc, st, em))
     }
+
+    /// Publish settings that were reloaded while the signer is running to
+    /// the components that can apply them without a restart.
+    ///
+    /// The settings are refused if they change any setting other than the
+    /// ones that the signer reads while it is running, see
+    /// [`Settings::assert_reloadable`].
+    pub fn reload_config(&self, config: Settings) -> Result<(), Error> {
+        let current = self.latest_config();
+        current
+            .assert_reloadable(&config)
+            .map_err(Error::SignerConfig)?;
+
+        if config.emily.endpoints != current.emily.endpoints {
+            let emily_client = EM::try_from(&config.emily)?;
+            *self
+                .emily_client
+                .write()
+                .expect("BUG: Failed to acquire write lock") = emily_client;
+        }
+
+        self.config_tx.send_replace(Arc::new(config));
+        Ok(())
+    }
 }
 
 impl<S, BC, ST, EM> SignerContext<S, BC, ST, EM>
@@ -86,6 +115,7 @@ where
         // messages into a local VecDequeue and process them in their own time.
         let (signal_tx, _) = tokio::sync::broadcast::channel(SIGNER_CHANNEL_CAPACITY);
         let (term_tx, _) = tokio::sync::watch::channel(false);
+        let (config_tx, _) = tokio::sync::watch::channel(Arc::new(config.clone()));
         let state = SignerState::default();
         if let Some(height) = config.signer.sbtc_bitcoin_start_height {
             state.set_sbtc_bitcoin_start_height(height);
@@ -93,13 +123,14 @@ where
 
         Self {
             config,
+            config_tx,
             state: Arc::new(state),
             signal_tx,
             term_tx,
             storage: db,
             bitcoin_client,
             stacks_client,
-            emily_client,
+            emily_client: Arc::new(RwLock::new(emily_client)),
         }
     }
 }
@@ -115,6 +146,10 @@ where
         &self.config
     }
 
+    fn subscribe_config(&self) -> tokio::sync::watch::Receiver<Arc<Settings>> {
+        self.config_tx.subscribe()
+    }
+
     fn state(&self) -> &SignerState {
         &self.state
     }
@@ -162,7 +197,11 @@ where
     }
 
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static {
-        self.emily_client.clone()
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        self.emily_client
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .clone()
     }
 }
 
@@ -173,13 +212,28 @@ mod tests {
         Arc,
     };
 
+    use std::num::NonZeroU16;
+
     use tokio::sync::Notify;
 
     use crate::{
-        context::{Context as _, SignerEvent, SignerSignal},
+        bitcoin::rpc::BitcoinCoreClient,
+        config::{NetworkKind, Settings},
+        context::{Context as _, SignerContext, SignerEvent, SignerSignal},
+        emily_client::EmilyClient,
+        stacks::api::StacksClient,
+        storage::in_memory::{SharedStore, Store},
         testing::context::*,
+        util::ApiFallbackClient,
     };
 
+    type RealClientsContext = SignerContext<
+        SharedStore,
+        ApiFallbackClient<BitcoinCoreClient>,
+        ApiFallbackClient<StacksClient>,
+        ApiFallbackClient<EmilyClient>,
+    >;
+
     /// This test shows that cloning a context and signalling on the original
     /// context will also signal on the cloned context. But it also demonstrates
     /// that there can be timing issues (particularly in tests) when signalling
@@ -240,4 +294,48 @@ mod tests {
         // Ensure that the signal was received.
         assert_eq!(recv_count.load(std::sync::atomic::Ordering::Relaxed), 1);
     }
+
+    #[test]
+    fn reloaded_settings_are_published() {
+        let settings = Settings::new_from_default_config().unwrap();
+        let context = RealClientsContext::init(settings.clone(), Store::new_shared()).unwrap();
+        let receiver = context.subscribe_config();
+
+        let mut reloaded = settings.clone();
+        reloaded.signer.max_deposits_per_bitcoin_tx = NonZeroU16::new(3).unwrap();
+        reloaded.emily.endpoints = vec!["http://localhost:3032".parse().unwrap()];
+        context.reload_config(reloaded).unwrap();
+
+        assert!(receiver.has_changed().unwrap());
+        let latest = context.latest_config();
+        assert_eq!(latest.signer.max_deposits_per_bitcoin_tx.get(), 3);
+        assert_eq!(latest.emily.endpoints[0].port(), Some(3032));
+
+        // The settings that the signer was started with stay the same.
+        assert_eq!(
+            context.config().signer.max_deposits_per_bitcoin_tx,
+            settings.signer.max_deposits_per_bitcoin_tx
+        );
+    }
+
+    #[test]
+    fn reloaded_settings_with_immutable_changes_are_refused() {
+        let settings = Settings::new_from_default_config().unwrap();
+        let context = RealClientsContext::init(settings.clone(), Store::new_shared()).unwrap();
+        let receiver = context.subscribe_config();
+
+        let mut reloaded = settings.clone();
+        reloaded.signer.max_deposits_per_bitcoin_tx = NonZeroU16::new(3).unwrap();
+        reloaded.signer.network = NetworkKind::Testnet;
+
+        let error = context.reload_config(reloaded).unwrap_err();
+        assert!(error.to_string().contains("signer.network"));
+
+        // None of the changes were applied.
+        assert!(!receiver.has_changed().unwrap());
+        assert_eq!(
+            context.latest_config().signer.max_deposits_per_bitcoin_tx,
+            settings.signer.max_deposits_per_bitcoin_tx
+        );
+    }
 }
@@ -10,6 +10,9 @@ use hashbrown::HashSet;
 use libp2p::PeerId;
 
 use crate::keys::PublicKey;
+use crate::stacks::contracts::ActiveContracts;
+use crate::stacks::contracts::ContractVersion;
+use crate::stacks::contracts::ProtocolRole;
 
 /// A struct for holding internal signer state. This struct is served by
 /// the [`SignerContext`] and can be used to cache global state instead of
@@ -18,6 +21,7 @@ use crate::keys::PublicKey;
 pub struct SignerState {
     current_signer_set: SignerSet,
     current_limits: RwLock<SbtcLimits>,
+    active_contracts: RwLock<ActiveContracts>,
     sbtc_contracts_deployed: AtomicBool,
     sbtc_bitcoin_start_height: AtomicU64,
     is_sbtc_bitcoin_start_height_set: AtomicBool,
@@ -48,6 +52,33 @@ impl SignerState {
         *limits = new_limits;
     }
 
+    /// Get the versions of the protocol contracts that are active in the
+    /// sbtc-registry.
+    pub fn active_contracts(&self) -> ActiveContracts {
+        *self
+            .active_contracts
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+    }
+
+    /// Update the versions of the protocol contracts that are active in
+    /// the sbtc-registry.
+    pub fn set_active_contracts(&self, active_contracts: ActiveContracts) {
+        *self
+            .active_contracts
+            .write()
+            .expect("BUG: Failed to acquire write lock") = active_contracts;
+    }
+
+    /// Update the active version of the protocol contract for the given
+    /// role.
+    pub fn set_active_contract_version(&self, role: ProtocolRole, version: ContractVersion) {
+        self.active_contracts
+            .write()
+            .expect("BUG: Failed to acquire write lock")
+            .set_version(role, version);
+    }
+
     /// Returns true if sbtc smart contracts are deployed
     pub fn sbtc_contracts_deployed(&self) -> bool {
         self.sbtc_contracts_deployed.load(Ordering::SeqCst)
@@ -90,14 +121,43 @@ pub struct SbtcLimits {
     per_withdrawal_cap: Option<Amount>,
     /// Represents the maximum amount of sBTC that can currently be minted.
     max_mintable_cap: Option<Amount>,
+    /// Represents the cap on the BTC pegged-in within Emily's rolling window.
+    rolling_deposit_cap: Option<RollingCap>,
+    /// Represents the cap on the sBTC pegged-out within Emily's rolling window.
+    rolling_withdrawal_cap: Option<RollingCap>,
+}
+
+/// A cap on the amount pegged within a rolling window, along with the
+/// amount that was already pegged within the current window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingCap {
+    /// The most that can be pegged within the window.
+    pub cap: Amount,
+    /// The amount already pegged within the current window.
+    pub volume: Amount,
+}
+
+impl RollingCap {
+    /// The amount that can still be pegged within the current window.
+    pub fn capacity(&self) -> Amount {
+        self.cap.checked_sub(self.volume).unwrap_or(Amount::ZERO)
+    }
+
+    /// Take the given amount out of the volume.
+    pub fn excluding(self, amount: Amount) -> Self {
+        Self {
+            volume: self.volume.checked_sub(amount).unwrap_or(Amount::ZERO),
+            ..self
+        }
+    }
 }
 
 impl std::fmt::Display for SbtcLimits {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[total cap: {:?}, per-deposit min: {:?}, per-deposit cap: {:?}, per-withdrawal cap: {:?}, max-mintable cap: {:?}]",
-            self.total_cap, self.per_deposit_minimum, self.per_deposit_cap, self.per_withdrawal_cap, self.max_mintable_cap
+            "[total cap: {:?}, per-deposit min: {:?}, per-deposit cap: {:?}, per-withdrawal cap: {:?}, max-mintable cap: {:?}, rolling deposit cap: {:?}, rolling withdrawal cap: {:?}]",
+            self.total_cap, self.per_deposit_minimum, self.per_deposit_cap, self.per_withdrawal_cap, self.max_mintable_cap, self.rolling_deposit_cap, self.rolling_withdrawal_cap
         )
     }
 }
@@ -117,9 +177,23 @@ impl SbtcLimits {
             per_deposit_cap,
             per_withdrawal_cap,
             max_mintable_cap,
+            rolling_deposit_cap: None,
+            rolling_withdrawal_cap: None,
         }
     }
 
+    /// Set the caps on the amounts pegged-in and pegged-out within Emily's
+    /// rolling window.
+    pub fn with_rolling_caps(
+        mut self,
+        rolling_deposit_cap: Option<RollingCap>,
+        rolling_withdrawal_cap: Option<RollingCap>,
+    ) -> Self {
+        self.rolling_deposit_cap = rolling_deposit_cap;
+        self.rolling_withdrawal_cap = rolling_withdrawal_cap;
+        self
+    }
+
     /// Get the total cap for all pegged-in BTC/sBTC.
     pub fn total_cap(&self) -> Amount {
         self.total_cap.unwrap_or(Amount::MAX_MONEY)
@@ -150,6 +224,45 @@ impl SbtcLimits {
         self.max_mintable_cap.unwrap_or(Amount::MAX_MONEY)
     }
 
+    /// Get the cap on the BTC pegged-in within the rolling window, if any.
+    pub fn rolling_deposit_cap(&self) -> Option<RollingCap> {
+        self.rolling_deposit_cap
+    }
+
+    /// Get the cap on the sBTC pegged-out within the rolling window, if any.
+    pub fn rolling_withdrawal_cap(&self) -> Option<RollingCap> {
+        self.rolling_withdrawal_cap
+    }
+
+    /// Get the amount of BTC that can still be pegged-in within the current
+    /// rolling window.
+    pub fn rolling_deposit_capacity(&self) -> Amount {
+        self.rolling_deposit_cap
+            .map_or(Amount::MAX_MONEY, |cap| cap.capacity())
+    }
+
+    /// Get the amount of sBTC that can still be pegged-out within the
+    /// current rolling window.
+    pub fn rolling_withdrawal_capacity(&self) -> Amount {
+        self.rolling_withdrawal_cap
+            .map_or(Amount::MAX_MONEY, |cap| cap.capacity())
+    }
+
+    /// Take the amounts of requests that Emily already counts, but that
+    /// are being swept again, out of the volumes of the rolling caps.
+    ///
+    /// Emily counts requests towards the rolling window once their sweep
+    /// has been broadcast. When that sweep is replaced or signed again,
+    /// its requests are part of the new package as well as of the volume,
+    /// and would otherwise be counted twice.
+    pub fn excluding_rolling_volume(mut self, deposits: Amount, withdrawals: Amount) -> Self {
+        self.rolling_deposit_cap = self.rolling_deposit_cap.map(|cap| cap.excluding(deposits));
+        self.rolling_withdrawal_cap = self
+            .rolling_withdrawal_cap
+            .map(|cap| cap.excluding(withdrawals));
+        self
+    }
+
     #[cfg(test)]
     pub fn new_per_deposit(min: u64, max: u64) -> Self {
         Self {
@@ -158,6 +271,8 @@ impl SbtcLimits {
             per_deposit_cap: Some(Amount::from_sat(max)),
             per_withdrawal_cap: None,
             max_mintable_cap: None,
+            rolling_deposit_cap: None,
+            rolling_withdrawal_cap: None,
         }
     }
 }
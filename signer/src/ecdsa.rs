@@ -297,6 +297,7 @@ mod tests {
         let original_message = SignerMessage {
             bitcoin_chain_tip: BitcoinBlockHash::from([1; 32]),
             payload: Faker.fake_with_rng::<T, _>(&mut OsRng).into(),
+            trace_context: None,
         };
 
         // We sign a payload digest. It should always be what this function
@@ -355,6 +356,7 @@ mod tests {
         let original_message = SignerMessage {
             bitcoin_chain_tip: BitcoinBlockHash::from([1; 32]),
             payload: Faker.fake_with_rng::<T, _>(&mut OsRng).into(),
+            trace_context: None,
         };
 
         // We sign a payload digest. It should always be what this function
@@ -443,6 +445,7 @@ mod tests {
         let original_message = SignerMessage {
             bitcoin_chain_tip: BitcoinBlockHash::from([1; 32]),
             payload: Faker.fake_with_rng::<T, _>(&mut OsRng).into(),
+            trace_context: None,
         };
 
         // The upgraded signer sends messages with an additional field.
@@ -622,6 +625,7 @@ mod tests {
         let signer_message = SignerMessage {
             bitcoin_chain_tip: fake::Faker.fake_with_rng(&mut rng),
             payload: message::Payload::SignerWithdrawalDecision(payload.clone()),
+            trace_context: None,
         };
 
         let msg = signer_message.sign_ecdsa(&private_key);
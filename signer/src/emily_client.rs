@@ -30,6 +30,7 @@ use url::Url;
 use crate::bitcoin::utxo::RequestRef;
 use crate::bitcoin::utxo::UnsignedTransaction;
 use crate::config::EmilyClientConfig;
+use crate::context::RollingCap;
 use crate::context::SbtcLimits;
 use crate::error::Error;
 use crate::storage::model::BitcoinTxId;
@@ -217,10 +218,21 @@ impl EmilyInteract for EmilyClient {
     async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
         // TODO: hanlde pagination -- if the queried data is over 1MB DynamoDB will
         // paginate the results even if we pass `None` as page limit.
-        let resp = deposit_api::get_deposits(&self.config, Status::Pending, None, None)
-            .await
-            .map_err(EmilyClientError::GetDeposits)
-            .map_err(Error::EmilyApi)?;
+        let resp = deposit_api::get_deposits(
+            &self.config,
+            Some(vec![Status::Pending]),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .map_err(EmilyClientError::GetDeposits)
+        .map_err(Error::EmilyApi)?;
 
         resp.deposits
             .iter()
@@ -345,6 +357,12 @@ impl EmilyInteract for EmilyClient {
         let per_withdrawal_cap = limits
             .per_withdrawal_cap
             .and_then(|cap| cap.map(Amount::from_sat));
+        let rolling_deposit_cap =
+            rolling_cap(limits.rolling_deposit_cap, limits.rolling_deposit_volume);
+        let rolling_withdrawal_cap = rolling_cap(
+            limits.rolling_withdrawal_cap,
+            limits.rolling_withdrawal_volume,
+        );
 
         Ok(SbtcLimits::new(
             total_cap,
@@ -352,7 +370,8 @@ impl EmilyInteract for EmilyClient {
             per_deposit_cap,
             per_withdrawal_cap,
             None,
-        ))
+        )
+        .with_rolling_caps(rolling_deposit_cap, rolling_withdrawal_cap))
     }
 }
 
@@ -433,10 +452,42 @@ impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
     }
 }
 
+/// Make the rolling cap out of the cap and volume reported by Emily.
+///
+/// Emily reports the volume next to every rolling cap, so a cap without a
+/// volume means that something went wrong on Emily's side. Such a cap is
+/// treated as exhausted rather than as having nothing pegged within the
+/// window.
+fn rolling_cap(cap: Option<Option<u64>>, volume: Option<Option<u64>>) -> Option<RollingCap> {
+    let cap = Amount::from_sat(cap.flatten()?);
+    let volume = match volume.flatten() {
+        Some(volume) => Amount::from_sat(volume),
+        None => {
+            tracing::warn!(%cap, "Emily reported a rolling cap without its volume");
+            cap
+        }
+    };
+    Some(RollingCap { cap, volume })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn rolling_cap_without_volume_is_exhausted() {
+        assert_eq!(rolling_cap(None, Some(Some(10))), None);
+        assert_eq!(rolling_cap(Some(None), None), None);
+
+        let cap = rolling_cap(Some(Some(1_000)), Some(Some(400))).unwrap();
+        assert_eq!(cap.capacity(), Amount::from_sat(600));
+
+        let cap = rolling_cap(Some(Some(1_000)), None).unwrap();
+        assert_eq!(cap.capacity(), Amount::ZERO);
+        let cap = rolling_cap(Some(Some(1_000)), Some(None)).unwrap();
+        assert_eq!(cap.capacity(), Amount::ZERO);
+    }
+
     #[test]
     fn try_from_url_with_key() {
         // Arrange.
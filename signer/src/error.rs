@@ -1,14 +1,19 @@
 //! Top-level error type for the signer
 use std::borrow::Cow;
 
+use blockstack_lib::clarity::vm::types::PrincipalData;
 use blockstack_lib::types::chainstate::StacksBlockId;
 
 use crate::codec;
 use crate::emily_client::EmilyClientError;
 use crate::keys::PublicKey;
+use crate::stacks::contracts::ContractVersion;
 use crate::stacks::contracts::DepositValidationError;
+use crate::stacks::contracts::ProtocolRole;
 use crate::stacks::contracts::RotateKeysValidationError;
+use crate::stacks::contracts::UpdateProtocolContractValidationError;
 use crate::stacks::contracts::WithdrawalAcceptValidationError;
+use crate::stacks::simulator::SimulationError;
 use crate::storage::model::SigHash;
 
 /// Top-level signer error
@@ -373,6 +378,23 @@ pub enum Error {
     #[error("failed to make a request to the stacks Node: {0}")]
     StacksNodeRequest(#[source] reqwest::Error),
 
+    /// Could not make a request to the Stacks blockchain API.
+    #[error("failed to make a request to the stacks blockchain API: {0}")]
+    StacksBlockchainApiRequest(#[source] reqwest::Error),
+
+    /// The Stacks blockchain API responded with a non success status code.
+    #[error("received a non success status code response from the stacks blockchain API: {0}")]
+    StacksBlockchainApiResponse(#[source] reqwest::Error),
+
+    /// The response from the Stacks blockchain API could not be
+    /// deserialized.
+    #[error("response from the stacks blockchain API did not conform to the expected schema: {0}")]
+    UnexpectedStacksBlockchainApiResponse(#[source] reqwest::Error),
+
+    /// The response from the Stacks blockchain API was inconsistent.
+    #[error("invalid stacks blockchain API response: {0}")]
+    InvalidStacksBlockchainApiResponse(&'static str),
+
     /// We failed to submit the transaction to the mempool.
     #[error("stacks transaction rejected: {0}")]
     StacksTxRejection(#[from] crate::stacks::api::TxRejection),
@@ -385,6 +407,11 @@ pub enum Error {
     #[error("invalid stacks response: {0}")]
     InvalidStacksResponse(&'static str),
 
+    /// Could not serialize a clarity value for a request to the Stacks
+    /// node.
+    #[error("could not serialize clarity value: {0}")]
+    ClaritySerialization(#[source] clarity::vm::types::serialization::SerializationError),
+
     /// Taproot error
     #[error("an error occurred when constructing the taproot signing digest: {0}")]
     Taproot(#[from] bitcoin::sighash::TaprootError),
@@ -409,6 +436,11 @@ pub enum Error {
     #[error("missing dkg shares for the given aggregate key: {0}")]
     MissingDkgShares(crate::keys::PublicKeyXOnly),
 
+    /// The signature produced during DKG verification was not valid for
+    /// the aggregate key of the new DKG shares.
+    #[error("DKG verification failed for the aggregate key: {0}")]
+    DkgVerificationFailed(crate::keys::PublicKeyXOnly),
+
     /// Missing public key
     #[error("missing public key")]
     MissingPublicKey,
@@ -497,6 +529,11 @@ pub enum Error {
     #[error("rotate keys validation error: {0}")]
     RotateKeysValidation(#[source] Box<RotateKeysValidationError>),
 
+    /// The error for when the request to sign an update-protocol-contract
+    /// transaction fails at the validation step.
+    #[error("update protocol contract validation error: {0}")]
+    UpdateProtocolContractValidation(#[source] Box<UpdateProtocolContractValidationError>),
+
     /// Thrown when the recoverable signature has a public key that is
     /// unexpected.
     #[error("unexpected public key from signature. key {0}; digest: {1}")]
@@ -562,6 +599,26 @@ pub enum Error {
     #[error("smart contract already deployed, contract name: {0}")]
     ContractAlreadyDeployed(&'static str),
 
+    /// The smart contract is a version of a protocol contract that this
+    /// signer has not been configured to run.
+    #[error("smart contract is not a configured protocol contract version, contract name: {0}")]
+    ContractVersionNotConfigured(&'static str),
+
+    /// The contract call targets a version of a protocol contract that
+    /// the sbtc-registry does not consider active.
+    #[error("contract call targets the {1} {0} contract, which is not the active version")]
+    InactiveContractVersion(ProtocolRole, ContractVersion),
+
+    /// The sbtc-registry reported an active protocol contract that this
+    /// signer does not know how to call.
+    #[error("the sbtc-registry has an unrecognized active protocol contract: {0}")]
+    UnknownProtocolContract(PrincipalData),
+
+    /// Executing the contract call locally showed that it would fail on
+    /// chain.
+    #[error("contract call simulation error: {0}")]
+    ContractCallSimulation(#[source] SimulationError),
+
     /// Received coordinator message wasn't from coordinator for this chain tip
     #[error("not chain tip coordinator")]
     NotChainTipCoordinator,
@@ -593,6 +650,70 @@ pub enum Error {
         /// Maximum sBTC mintable
         max_mintable: u64,
     },
+
+    /// Error when deposit requests would exceed what is left of the rolling
+    /// deposit cap
+    #[error("Total deposit amount ({total_amount} sats) would exceed the rolling deposit cap (current capacity is {capacity} sats)")]
+    ExceedsRollingDepositCap {
+        /// Total deposit amount in sats
+        total_amount: u64,
+        /// What is left of the rolling deposit cap in sats
+        capacity: u64,
+    },
+
+    /// Error when withdrawal requests would exceed what is left of the
+    /// rolling withdrawal cap
+    #[error("Total withdrawal amount ({total_amount} sats) would exceed the rolling withdrawal cap (current capacity is {capacity} sats)")]
+    ExceedsRollingWithdrawalCap {
+        /// Total withdrawal amount in sats
+        total_amount: u64,
+        /// What is left of the rolling withdrawal cap in sats
+        capacity: u64,
+    },
+
+    /// The log directives could not be parsed.
+    #[error("invalid log directives: {0}")]
+    InvalidLogDirectives(#[source] tracing_subscriber::filter::ParseError),
+
+    /// The log filter of the global subscriber could not be replaced.
+    #[error("could not replace the log filter: {0}")]
+    ReloadLogFilter(#[source] tracing_subscriber::reload::Error),
+
+    /// The exporter of spans to an OpenTelemetry collector could not be
+    /// created.
+    #[error("could not create the OTLP span exporter: {0}")]
+    OtlpExporter(#[source] opentelemetry::trace::TraceError),
+
+    /// This happens when a timestamp read from the database is outside of
+    /// the range that we can represent.
+    #[error("invalid timestamp: {0}")]
+    InvalidTimestamp(#[source] time::error::ComponentRange),
+
+    /// This happens when writing an entry to the signing audit log at a
+    /// position that already has an entry, which means that more than one
+    /// writer is appending to the log.
+    #[error("the signing audit log already has an entry at position {0}")]
+    AuditLogPositionTaken(u64),
+
+    /// The signing audit log failed verification. Either an entry was
+    /// changed or removed, or entries were written with another key.
+    #[error("the signing audit log is broken at position {position}: {reason}")]
+    AuditLogBroken {
+        /// The position of the first entry that failed verification.
+        position: u64,
+        /// What was wrong with the entry.
+        reason: &'static str,
+    },
+
+    /// A signing audit log checkpoint was not signed by the signer, or it
+    /// names an empty log with a head other than the genesis hash.
+    #[error("the signing audit log checkpoint is invalid")]
+    InvalidAuditLogCheckpoint,
+
+    /// The transaction signer produced a signature share without a record
+    /// of what it was asked to sign, so the share was not sent.
+    #[error("refusing to send a signature share that is not in the signing audit log")]
+    MissingSigningAuditRecord,
 }
 
 impl From<std::convert::Infallible> for Error {
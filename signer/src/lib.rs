@@ -7,6 +7,7 @@
 )]
 
 pub mod api;
+pub mod audit_log;
 pub mod bitcoin;
 pub mod block_observer;
 pub mod blocklist_client;
@@ -23,6 +24,7 @@ pub mod metrics;
 pub mod network;
 pub mod proto;
 pub mod request_decider;
+pub mod request_lifecycle;
 pub mod signature;
 pub mod stacks;
 pub mod storage;
@@ -1,10 +1,43 @@
 //! This module sets up logging for the application using `tracing_subscriber`
-//! It provides functions to initialize logging in either JSON format or pretty format
+//! It provides functions to initialize logging in either JSON format or pretty format,
+//! and to optionally export spans to an OpenTelemetry collector over OTLP.
+//! The log filter can be swapped while the signer is running with
+//! [`set_log_directives`].
 
+use std::sync::OnceLock;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig as _;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::Tracer;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::fmt::time::UtcTime;
+use tracing_subscriber::layer::Layered;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Registry;
+use url::Url;
+
+use crate::error::Error;
+
+/// The layer that turns spans into OpenTelemetry spans.
+type OtlpLayer = OpenTelemetryLayer<Registry, Tracer>;
+
+/// The service name that spans are exported under.
+const OTLP_SERVICE_NAME: &str = "sbtc-signer";
+
+/// The part of the global subscriber that sits below the log filter.
+type FilteredSubscriber = Layered<Option<OtlpLayer>, Registry>;
+
+/// The handle for the log filter. This is only set when the filter comes
+/// from the directives that the signer was configured with, rather than
+/// from the `RUST_LOG` environment variable.
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, FilteredSubscriber>> = OnceLock::new();
 
 /// Sets up logging based on the provided format preference
 ///
@@ -14,12 +47,106 @@ use tracing_subscriber::EnvFilter;
 
 pub fn setup_logging(directives: &str, pretty: bool) {
     match pretty {
-        true => setup_logging_pretty(directives),
-        false => setup_logging_json(directives),
+        true => setup_logging_pretty(directives, None),
+        false => setup_logging_json(directives, None),
+    }
+}
+
+/// Sets up logging like [`setup_logging`], and also exports spans to the
+/// OpenTelemetry collector at the given endpoint over OTLP/HTTP.
+///
+/// This also installs the W3C trace context propagator, so that signer
+/// messages carry the trace context of the span that sent them. The
+/// returned guard flushes the remaining spans when it is dropped.
+pub fn setup_logging_with_otlp(
+    directives: &str,
+    pretty: bool,
+    otlp_endpoint: &Url,
+) -> Result<OtlpGuard, Error> {
+    let provider = otlp_tracer_provider(otlp_endpoint)?;
+    let layer = otlp_layer(&provider);
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    match pretty {
+        true => setup_logging_pretty(directives, Some(layer)),
+        false => setup_logging_json(directives, Some(layer)),
     }
+    Ok(OtlpGuard(provider))
 }
 
-fn setup_logging_json(directives: &str) {
+/// Flushes the spans that have not been exported yet when dropped.
+#[derive(Debug)]
+pub struct OtlpGuard(TracerProvider);
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.0.shutdown() {
+            tracing::warn!(%error, "could not flush spans to the OTLP collector");
+        }
+    }
+}
+
+/// Create a tracer provider that exports spans in batches to the
+/// collector at the given endpoint. The endpoint is the base URL of the
+/// collector, like `http://localhost:4318`.
+fn otlp_tracer_provider(otlp_endpoint: &Url) -> Result<TracerProvider, Error> {
+    let endpoint = format!("{}/v1/traces", otlp_endpoint.as_str().trim_end_matches('/'));
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(Error::OtlpExporter)?;
+
+    let resource = Resource::new([KeyValue::new("service.name", OTLP_SERVICE_NAME)]);
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(resource)
+        .build();
+
+    Ok(provider)
+}
+
+/// Replace the log filter with one built from the given directives.
+///
+/// This does nothing when the log filter was taken from the `RUST_LOG`
+/// environment variable, since that takes precedence over the settings.
+pub fn set_log_directives(directives: &str) -> Result<(), Error> {
+    let Some(handle) = LOG_FILTER.get() else {
+        tracing::info!("the log filter is set by RUST_LOG, ignoring the new log directives");
+        return Ok(());
+    };
+
+    let filter = EnvFilter::try_new(directives).map_err(Error::InvalidLogDirectives)?;
+    handle.reload(filter).map_err(Error::ReloadLogFilter)?;
+    tracing::info!(%directives, "updated the log filter");
+    Ok(())
+}
+
+/// Create the log filter layer. The filter is taken from the `RUST_LOG`
+/// environment variable if it is set, otherwise it is built from the
+/// given directives and can later be replaced with
+/// [`set_log_directives`].
+fn filter_layer(directives: &str) -> reload::Layer<EnvFilter, FilteredSubscriber> {
+    let from_env = EnvFilter::try_from_default_env().ok();
+    let is_from_env = from_env.is_some();
+    let filter = from_env.unwrap_or_else(|| EnvFilter::new(directives));
+
+    let (layer, handle) = reload::Layer::new(filter);
+    if !is_from_env {
+        // Logging is only set up once, so this cannot already be set.
+        let _ = LOG_FILTER.set(handle);
+    }
+    layer
+}
+
+fn otlp_layer(provider: &TracerProvider) -> OtlpLayer {
+    let tracer = provider.tracer(crate::PACKAGE_NAME);
+    tracing_opentelemetry::layer().with_tracer(tracer)
+}
+
+fn setup_logging_json(directives: &str, otlp_layer: Option<OtlpLayer>) {
     let main_layer = tracing_subscriber::fmt::layer()
         .json()
         .flatten_event(true)
@@ -31,16 +158,104 @@ fn setup_logging_json(directives: &str) {
         .with_timer(UtcTime::rfc_3339());
 
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives)))
+        .with(otlp_layer)
+        .with(filter_layer(directives))
         .with(main_layer)
         .init()
 }
 
-fn setup_logging_pretty(directives: &str) {
+fn setup_logging_pretty(directives: &str, otlp_layer: Option<OtlpLayer>) {
     let main_layer = tracing_subscriber::fmt::layer().with_timer(UtcTime::rfc_3339());
 
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives)))
+        .with(otlp_layer)
+        .with(filter_layer(directives))
         .with(main_layer)
         .init()
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::routing::post;
+    use opentelemetry::trace::TraceContextExt as _;
+    use tokio::sync::mpsc;
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    use crate::message::TraceContext;
+
+    use super::*;
+
+    /// Start a stand-in for an OTLP collector. The body of every export
+    /// request that it receives is sent over the returned channel.
+    async fn start_collector() -> (Url, mpsc::UnboundedReceiver<Bytes>) {
+        let (tx, rx) = mpsc::unbounded_channel::<Bytes>();
+        let export = |State(tx): State<mpsc::UnboundedSender<Bytes>>, body: Bytes| async move {
+            tx.send(body).unwrap();
+        };
+        let app = axum::Router::new()
+            .route("/v1/traces", post(export))
+            .with_state(tx);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (Url::parse(&format!("http://{addr}")).unwrap(), rx)
+    }
+
+    fn count_occurrences(haystack: &[u8], needle: &[u8]) -> usize {
+        haystack
+            .windows(needle.len())
+            .filter(|window| *window == needle)
+            .count()
+    }
+
+    #[test]
+    fn trace_context_is_not_set_without_an_exporter() {
+        let span = tracing::info_span!("coordinate_signing_round");
+        assert!(span.in_scope(TraceContext::current).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn spans_are_exported_and_linked_across_signers() {
+        let (endpoint, mut bodies) = start_collector().await;
+        let provider = otlp_tracer_provider(&endpoint).unwrap();
+        let subscriber = tracing_subscriber::registry().with(otlp_layer(&provider));
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let (trace_context, trace_id) = tracing::subscriber::with_default(subscriber, || {
+            // The coordinator sends a message from within its span.
+            let span = tracing::info_span!("coordinate_signing_round");
+            let trace_id = span.context().span().span_context().trace_id();
+            let trace_context = span.in_scope(TraceContext::current).unwrap();
+            drop(span);
+
+            // A signer receives the message in a span of its own trace.
+            let span = tracing::info_span!(parent: None, "handle_wsts_message");
+            span.in_scope(|| trace_context.link_current_span());
+            (trace_context, trace_id)
+        });
+
+        assert!(trace_context.traceparent.contains(&trace_id.to_string()));
+
+        // Shutting down the provider exports the spans that are left, and
+        // blocks until the collector has responded.
+        tokio::task::spawn_blocking(move || provider.shutdown())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mut exported = Vec::new();
+        while let Ok(body) = bodies.try_recv() {
+            exported.extend_from_slice(&body);
+        }
+
+        assert_eq!(count_occurrences(&exported, b"coordinate_signing_round"), 1);
+        assert_eq!(count_occurrences(&exported, b"handle_wsts_message"), 1);
+        // The trace ID of the coordinator's span shows up twice, once in
+        // the span itself and once in the link from the signer's span.
+        assert_eq!(count_occurrences(&exported, &trace_id.to_bytes()), 2);
+    }
+}
@@ -1,4 +1,5 @@
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
@@ -9,10 +10,13 @@ use axum::http::Request;
 use axum::http::Response;
 use cfg_if::cfg_if;
 use clap::Parser;
+use clap::Subcommand;
 use clap::ValueEnum;
+use futures::StreamExt as _;
 use lru::LruCache;
 use signer::api;
 use signer::api::ApiState;
+use signer::audit_log;
 use signer::bitcoin::rpc::BitcoinCoreClient;
 use signer::bitcoin::zmq::BitcoinCoreMessageStream;
 use signer::block_observer;
@@ -22,11 +26,14 @@ use signer::context::Context;
 use signer::context::SignerContext;
 use signer::emily_client::EmilyClient;
 use signer::error::Error;
+use signer::keys::PublicKey;
 use signer::network::libp2p::SignerSwarmBuilder;
 use signer::network::P2PNetwork;
 use signer::request_decider::RequestDeciderEventLoop;
 use signer::stacks::api::StacksClient;
+use signer::stacks::contracts::SmartContract;
 use signer::storage::postgres::PgStore;
+use signer::storage::DbRead as _;
 use signer::transaction_coordinator;
 use signer::transaction_signer;
 use signer::util::ApiFallbackClient;
@@ -41,6 +48,17 @@ enum LogOutputFormat {
     Pretty,
 }
 
+/// The context of the running signer.
+type MainContext = SignerContext<
+    PgStore,
+    ApiFallbackClient<BitcoinCoreClient>,
+    ApiFallbackClient<StacksClient>,
+    ApiFallbackClient<EmilyClient>,
+>;
+
+/// How often the configuration file is checked for changes.
+const CONFIG_FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Command line arguments for the signer.
 #[derive(Debug, Parser)]
 #[clap(name = "sBTC Signer")]
@@ -57,6 +75,61 @@ struct SignerArgs {
 
     #[clap(short = 'o', long = "output-format", default_value = "pretty")]
     output_format: Option<LogOutputFormat>,
+
+    /// An optional command to run instead of starting the signer.
+    #[clap(subcommand)]
+    command: Option<SignerCommand>,
+}
+
+/// Commands that inspect the signer's state instead of running the signer.
+#[derive(Debug, Subcommand)]
+enum SignerCommand {
+    /// Construct and validate the next sweep transaction package against
+    /// the signer's database without signing or broadcasting anything,
+    /// and print what happened to each pending request.
+    SweepDryRun {
+        /// Print the report as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+    /// Replay the webhook bodies in the event observer journal through the
+    /// same parsing that the event observer uses, and print how each one
+    /// parsed.
+    ReplayEvents {
+        /// The ID of the first payload to replay.
+        #[clap(long, default_value_t = 0)]
+        from: i64,
+        /// The ID of the last payload to replay. Every payload from
+        /// `--from` onwards is replayed if this is not given.
+        #[clap(long)]
+        to: Option<i64>,
+        /// Write the decoded events to the database. Emily is not updated.
+        #[clap(long)]
+        apply: bool,
+    },
+    /// Check that the signing audit log is an unbroken chain of entries
+    /// signed by this signer.
+    VerifyAuditLog {
+        /// A file with a checkpoint of the log, as printed by the
+        /// checkpoint-audit-log command or logged by the signer. The log
+        /// must still contain the entry that the checkpoint names.
+        #[clap(long)]
+        checkpoint: Option<PathBuf>,
+    },
+    /// Print a checkpoint of the head of the signing audit log, signed by
+    /// this signer, as JSON. Keep it somewhere other than the signer's
+    /// database to verify the log against later.
+    CheckpointAuditLog,
+    /// Print the entries of the signing audit log as JSON lines.
+    ExportAuditLog {
+        /// The position of the first entry to print.
+        #[clap(long, default_value_t = 0)]
+        from: u64,
+        /// The position of the last entry to print. Every entry from
+        /// `--from` onwards is printed if this is not given.
+        #[clap(long)]
+        to: Option<u64>,
+    },
 }
 
 #[tokio::main]
@@ -65,9 +138,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse the command line arguments.
     let args = SignerArgs::parse();
 
-    // Configure the binary's stdout/err output based on the provided output format.
+    // Load the configuration file and/or environment variables. This
+    // happens before logging is set up, since the settings say whether
+    // spans are exported.
+    let settings = Settings::new(args.config.as_ref())?;
+
+    // Configure the binary's stdout/err output based on the provided output
+    // format, and export spans if there is a collector to export them to.
+    // The guard flushes the remaining spans when the signer exits.
     let pretty = matches!(args.output_format, Some(LogOutputFormat::Pretty));
-    signer::logging::setup_logging("info,signer=debug", pretty);
+    let directives = settings.signer.log_directives.as_str();
+    let _otlp_guard = match settings.signer.otlp_exporter_endpoint.as_ref() {
+        Some(endpoint) => Some(signer::logging::setup_logging_with_otlp(
+            directives, pretty, endpoint,
+        )?),
+        None => {
+            signer::logging::setup_logging(directives, pretty);
+            None
+        }
+    };
 
     tracing::info!(
         rust_version = signer::RUSTC_VERSION,
@@ -77,8 +166,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "starting the sBTC signer",
     );
 
-    // Load the configuration file and/or environment variables.
-    let settings = Settings::new(args.config)?;
     signer::metrics::setup_metrics(settings.signer.prometheus_exporter_endpoint);
 
     // Open a connection to the signer db.
@@ -90,12 +177,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Initialize the signer context.
-    let context = SignerContext::<
-        _,
-        ApiFallbackClient<BitcoinCoreClient>,
-        ApiFallbackClient<StacksClient>,
-        ApiFallbackClient<EmilyClient>,
-    >::init(settings, db)?;
+    let context = MainContext::init(settings, db)?;
 
     // TODO: We should first check "another source of truth" for the current
     // signing set, and only assume we are bootstrapping if that source is
@@ -105,6 +187,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         context.state().current_signer_set().add_signer(signer);
     }
 
+    match args.command {
+        Some(SignerCommand::SweepDryRun { json }) => {
+            return run_sweep_dry_run(&context, json).await;
+        }
+        Some(SignerCommand::ReplayEvents { from, to, apply }) => {
+            api::replay_events(&context, from, to, apply, |payload| print!("{payload}")).await?;
+            return Ok(());
+        }
+        Some(SignerCommand::VerifyAuditLog { checkpoint }) => {
+            let checkpoint: Option<audit_log::Checkpoint> = match checkpoint {
+                Some(path) => Some(serde_json::from_str(&std::fs::read_to_string(path)?)?),
+                None => None,
+            };
+            let public_key = PublicKey::from_private_key(&settings.signer.private_key);
+            let storage = context.get_storage();
+            let count = audit_log::verify(&storage, &public_key, checkpoint.as_ref()).await?;
+            println!("verified {count} signing audit log entries");
+            return Ok(());
+        }
+        Some(SignerCommand::CheckpointAuditLog) => {
+            let private_key = &settings.signer.private_key;
+            let checkpoint = audit_log::checkpoint(&context.get_storage(), private_key).await?;
+            println!("{}", serde_json::to_string(&checkpoint)?);
+            return Ok(());
+        }
+        Some(SignerCommand::ExportAuditLog { from, to }) => {
+            return export_audit_log(&context, from, to).await;
+        }
+        None => {}
+    }
+
     // Run the application components concurrently. We're `join!`ing them
     // here so that every component can shut itself down gracefully when
     // the shutdown signal is received.
@@ -122,11 +235,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // The rest of our services which run concurrently, and must all be
         // running for the signer to be operational.
         run_checked(run_api, &context),
+        run_checked(run_admin_api, &context),
         run_checked(run_libp2p_swarm, &context),
         run_checked(run_block_observer, &context),
+        run_checked(run_registry_event_backfill, &context),
         run_checked(run_request_decider, &context),
         run_checked(run_transaction_coordinator, &context),
         run_checked(run_transaction_signer, &context),
+        run_checked(|ctx| run_settings_reloader(ctx, args.config), &context),
     );
 
     Ok(())
@@ -151,8 +267,8 @@ where
     Ok(())
 }
 
-/// Runs the shutdown-signal watcher. On Unix systems, this listens for SIGHUP,
-/// SIGTERM, and SIGINT. On other systems, it listens for Ctrl-C.
+/// Runs the shutdown-signal watcher. On Unix systems, this listens for
+/// SIGTERM and SIGINT. On other systems, it listens for Ctrl-C.
 #[tracing::instrument(skip(ctx), name = "shutdown-watcher")]
 async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
     let mut term = ctx.get_termination_handle();
@@ -161,7 +277,6 @@ async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
         // If we are on a Unix system, we can listen for more signals.
         if #[cfg(unix)] {
             let mut terminate = tokio::signal::unix::signal(signal::unix::SignalKind::terminate())?;
-            let mut hangup = tokio::signal::unix::signal(signal::unix::SignalKind::hangup())?;
             let mut interrupt = tokio::signal::unix::signal(signal::unix::SignalKind::interrupt())?;
 
             tokio::select! {
@@ -176,10 +291,6 @@ async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
                 _ = terminate.recv() => {
                     tracing::info!(signal = "SIGTERM", "received termination signal");
                 },
-                // SIGHUP (kill -1)
-                _ = hangup.recv() => {
-                    tracing::info!(signal = "SIGHUP", "received termination signal");
-                },
                 // Ctrl-C will be received as a SIGINT (kill -2)
                 _ = interrupt.recv() => {
                     tracing::info!(signal = "SIGINT", "received termination signal");
@@ -209,6 +320,165 @@ async fn run_shutdown_signal_watcher(ctx: impl Context) -> Result<(), Error> {
     Ok(())
 }
 
+/// Watches for changes to the settings and publishes them to the
+/// components that can apply them while the signer is running.
+///
+/// The settings are reloaded when the contents of the configuration file
+/// change, and on Unix systems when the signer receives a SIGHUP. Reloaded
+/// settings go through the same validation as on startup, and settings
+/// that fail validation or change a setting that requires a restart are
+/// logged and otherwise ignored.
+#[tracing::instrument(skip_all, name = "settings-reloader")]
+async fn run_settings_reloader(
+    ctx: MainContext,
+    config_path: Option<PathBuf>,
+) -> Result<(), Error> {
+    let mut term = ctx.get_termination_handle();
+    let mut poll_interval = tokio::time::interval(CONFIG_FILE_POLL_INTERVAL);
+    let mut config_file = read_config_file(config_path.as_deref());
+
+    cfg_if! {
+        // SIGHUP (kill -1) asks for the settings to be reloaded right away.
+        if #[cfg(unix)] {
+            let mut hangup = signal::unix::signal(signal::unix::SignalKind::hangup())?;
+            let mut hangup = futures::stream::poll_fn(move |cx| hangup.poll_recv(cx));
+        } else {
+            let mut hangup = futures::stream::pending::<()>();
+        }
+    }
+
+    loop {
+        tokio::select! {
+            _ = term.wait_for_shutdown() => {
+                tracing::info!("termination signal received, settings reloader is shutting down");
+                return Ok(());
+            }
+            Some(()) = hangup.next() => {
+                tracing::info!(signal = "SIGHUP", "received settings reload signal");
+                config_file = read_config_file(config_path.as_deref());
+            }
+            _ = poll_interval.tick() => {
+                let contents = read_config_file(config_path.as_deref());
+                if contents == config_file {
+                    continue;
+                }
+                tracing::info!("the configuration file changed");
+                config_file = contents;
+            }
+        }
+
+        reload_settings(&ctx, config_path.as_deref());
+    }
+}
+
+/// Read the contents of the configuration file, if there is one. A file
+/// that cannot be read is treated as empty, and is picked up once it can
+/// be read again.
+fn read_config_file(config_path: Option<&Path>) -> Vec<u8> {
+    let Some(path) = config_path else {
+        return Vec::new();
+    };
+    std::fs::read(path).unwrap_or_else(|error| {
+        tracing::warn!(%error, path = %path.display(), "could not read the configuration file");
+        Vec::new()
+    })
+}
+
+/// Load and validate the settings, then publish them through the context
+/// and apply any change to the log directives.
+///
+/// Only the settings in [`signer::config::LIVE_RELOADABLE_SETTINGS`] can
+/// change this way. Reloaded settings that change any other setting are
+/// refused, and the error names the settings that need a restart.
+fn reload_settings(ctx: &MainContext, config_path: Option<&Path>) {
+    let settings = match Settings::new(config_path) {
+        Ok(settings) => settings,
+        Err(error) => {
+            tracing::error!(%error, "the reloaded settings are invalid, keeping the current settings");
+            return;
+        }
+    };
+
+    let previous = ctx.latest_config();
+    let changed = previous.changed_settings(&settings);
+    if changed.is_empty() {
+        tracing::debug!("the reloaded settings are unchanged");
+        return;
+    }
+
+    let directives = settings.signer.log_directives.clone();
+    if let Err(error) = ctx.reload_config(settings) {
+        tracing::error!(%error, "refusing to apply the reloaded settings");
+        return;
+    }
+
+    if directives != previous.signer.log_directives {
+        if let Err(error) = signer::logging::set_log_directives(&directives) {
+            tracing::error!(%error, "could not apply the reloaded log directives");
+        }
+    }
+    tracing::info!(changed = %changed.join(", "), "applied the reloaded settings");
+}
+
+/// Runs a sweep dry run and prints the report to stdout.
+///
+/// The running signer keeps the sBTC limits and the contract deployment
+/// status in memory, so we load them here before constructing the
+/// package.
+async fn run_sweep_dry_run(
+    ctx: &impl Context,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let deployer = &ctx.config().signer.deployer;
+    let stacks = ctx.get_stacks_client();
+    if SmartContract::SbtcRegistry
+        .is_deployed(&stacks, deployer)
+        .await?
+    {
+        ctx.state().set_sbtc_contracts_deployed();
+    }
+    let limits = block_observer::get_sbtc_limits(ctx).await?;
+    ctx.state().update_current_limits(limits);
+
+    let report = transaction_coordinator::sweep_dry_run(ctx).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{report}");
+    }
+
+    Ok(())
+}
+
+async fn export_audit_log(
+    ctx: &impl Context,
+    from: u64,
+    to: Option<u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let db = ctx.get_storage();
+    let mut position = from;
+
+    while to.map_or(true, |to| position <= to) {
+        let entries = db
+            .get_signing_audit_entries(position, audit_log::READ_BATCH_SIZE)
+            .await?;
+        if entries.is_empty() {
+            break;
+        }
+
+        for entry in entries {
+            if to.is_some_and(|to| entry.position > to) {
+                return Ok(());
+            }
+            position = entry.position + 1;
+            let entry = audit_log::ExportedEntry::from(entry);
+            println!("{}", serde_json::to_string(&entry)?);
+        }
+    }
+
+    Ok(())
+}
+
 /// Runs the libp2p swarm.
 #[tracing::instrument(skip_all, name = "p2p")]
 async fn run_libp2p_swarm(ctx: impl Context) -> Result<(), Error> {
@@ -240,12 +510,38 @@ async fn run_api(ctx: impl Context + 'static) -> Result<(), Error> {
     let socket_addr = ctx.config().signer.event_observer.bind;
     tracing::info!(%socket_addr, "initializing the signer API server");
 
+    serve_router(ctx, socket_addr, api::get_router()).await
+}
+
+/// Runs the signer's admin API server, if an endpoint has been configured
+/// for it. This is kept off of the event observer port since the reports
+/// that it serves are meant for operators only.
+#[tracing::instrument(skip_all, name = "admin-api")]
+async fn run_admin_api(ctx: impl Context + 'static) -> Result<(), Error> {
+    let Some(socket_addr) = ctx.config().signer.admin_api_endpoint else {
+        return Ok(());
+    };
+    tracing::info!(%socket_addr, "initializing the signer admin API server");
+
+    serve_router(ctx, socket_addr, api::get_admin_router()).await
+}
+
+/// Serve the given router on the given address until the application is
+/// shut down.
+async fn serve_router<C>(
+    ctx: C,
+    socket_addr: std::net::SocketAddr,
+    router: axum::Router<ApiState<C>>,
+) -> Result<(), Error>
+where
+    C: Context + 'static,
+{
     let state = ApiState { ctx: ctx.clone() };
 
     let request_id = Arc::new(AtomicU64::new(0));
 
     // Build the signer API application
-    let app = api::get_router()
+    let app = router
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(|request: &Request<_>| {
@@ -311,6 +607,13 @@ async fn run_block_observer(ctx: impl Context) -> Result<(), Error> {
     block_observer.run().await
 }
 
+/// Run the registry event backfill event-loop.
+async fn run_registry_event_backfill(ctx: impl Context) -> Result<(), Error> {
+    let backfill = block_observer::RegistryEventBackfill { context: ctx };
+
+    backfill.run().await
+}
+
 /// Run the transaction signer event-loop.
 async fn run_transaction_signer(ctx: impl Context) -> Result<(), Error> {
     let config = ctx.config().clone();
@@ -330,6 +633,7 @@ async fn run_transaction_signer(ctx: impl Context) -> Result<(), Error> {
         rng: rand::thread_rng(),
         signer_private_key: config.signer.private_key,
         wsts_state_machines: LruCache::new(max_state_machines),
+        dkg_verification_state_machines: LruCache::new(max_state_machines),
         dkg_begin_pause: config.signer.dkg_begin_pause.map(Duration::from_secs),
     };
 
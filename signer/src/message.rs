@@ -1,6 +1,10 @@
 //! Signer message definition for network communication
 
+use std::collections::HashMap;
+
+use opentelemetry::trace::TraceContextExt as _;
 use secp256k1::ecdsa::RecoverableSignature;
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
 
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::validation::TxRequestIds;
@@ -17,6 +21,67 @@ pub struct SignerMessage {
     pub bitcoin_chain_tip: BitcoinBlockHash,
     /// The message payload
     pub payload: Payload,
+    /// The trace context of the span that sent the message. This is only
+    /// set when the sender exports traces.
+    pub trace_context: Option<TraceContext>,
+}
+
+impl SignerMessage {
+    /// Set the trace context of the message to the one of the current
+    /// span, so that the receivers can link their spans to it.
+    pub fn with_current_trace_context(mut self) -> Self {
+        self.trace_context = TraceContext::current();
+        self
+    }
+}
+
+/// A W3C trace context that identifies the span that sent a message.
+///
+/// Signers put it in their messages so that the spans of a signing round
+/// on different machines can be linked together by a tracing backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// The value of the `traceparent` header.
+    pub traceparent: String,
+    /// The value of the `tracestate` header, which may be empty.
+    pub tracestate: String,
+}
+
+const TRACEPARENT: &str = "traceparent";
+const TRACESTATE: &str = "tracestate";
+
+impl TraceContext {
+    /// Return the trace context of the current span. This is `None` unless
+    /// spans are exported, see [`crate::logging::setup_logging_with_otlp`].
+    pub fn current() -> Option<Self> {
+        let context = tracing::Span::current().context();
+        let mut carrier: HashMap<String, String> = HashMap::new();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&context, &mut carrier)
+        });
+
+        Some(TraceContext {
+            traceparent: carrier.remove(TRACEPARENT)?,
+            tracestate: carrier.remove(TRACESTATE).unwrap_or_default(),
+        })
+    }
+
+    /// Add a link from the current span to the span identified by this
+    /// trace context. Nothing happens if the trace context is invalid.
+    pub fn link_current_span(&self) {
+        let carrier = HashMap::from([
+            (TRACEPARENT.to_string(), self.traceparent.clone()),
+            (TRACESTATE.to_string(), self.tracestate.clone()),
+        ]);
+        let context = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&carrier)
+        });
+        let span_context = context.span().span_context().clone();
+
+        if span_context.is_valid() {
+            tracing::Span::current().add_link(span_context);
+        }
+    }
 }
 
 /// The different variants of signer messages
@@ -77,6 +142,7 @@ impl Payload {
         SignerMessage {
             bitcoin_chain_tip,
             payload: self,
+            trace_context: None,
         }
     }
 }
@@ -175,10 +241,19 @@ impl StacksTransactionSignRequest {
     /// signed.
     pub fn tx_kind(&self) -> &'static str {
         match &self.contract_tx {
-            StacksTx::ContractCall(ContractCall::CompleteDepositV1(_)) => "complete-deposit",
-            StacksTx::ContractCall(ContractCall::AcceptWithdrawalV1(_)) => "accept-withdrawal",
-            StacksTx::ContractCall(ContractCall::RejectWithdrawalV1(_)) => "reject-withdrawal",
+            StacksTx::ContractCall(
+                ContractCall::CompleteDepositV1(_) | ContractCall::CompleteDepositV2(_),
+            ) => "complete-deposit",
+            StacksTx::ContractCall(
+                ContractCall::AcceptWithdrawalV1(_) | ContractCall::AcceptWithdrawalV2(_),
+            ) => "accept-withdrawal",
+            StacksTx::ContractCall(
+                ContractCall::RejectWithdrawalV1(_) | ContractCall::RejectWithdrawalV2(_),
+            ) => "reject-withdrawal",
             StacksTx::ContractCall(ContractCall::RotateKeysV1(_)) => "rotate-keys",
+            StacksTx::ContractCall(ContractCall::UpdateProtocolContractV1(_)) => {
+                "update-protocol-contract"
+            }
             StacksTx::SmartContract(_) => "smart-contract-deployment",
         }
     }
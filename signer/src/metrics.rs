@@ -3,11 +3,28 @@
 
 use std::net::SocketAddr;
 
+use metrics_exporter_prometheus::Matcher;
 use metrics_exporter_prometheus::PrometheusBuilder;
 
 /// The buckets used for metric histograms
 const METRIC_BUCKETS: [f64; 9] = [1e-4, 1e-3, 1e-2, 0.1, 0.5, 1.0, 5.0, 20.0, f64::INFINITY];
 
+/// The buckets used for the request stage duration histogram. Requests
+/// move between stages over the course of bitcoin and stacks blocks, so
+/// these range from seconds to days.
+const REQUEST_STAGE_BUCKETS: [f64; 10] = [
+    10.0,
+    60.0,
+    300.0,
+    900.0,
+    1800.0,
+    3600.0,
+    3.0 * 3600.0,
+    12.0 * 3600.0,
+    48.0 * 3600.0,
+    f64::INFINITY,
+];
+
 /// The quantiles to use when rendering histograms
 const METRIC_QUANTILES: [f64; 8] = [0.0, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99, 1.0];
 
@@ -44,6 +61,14 @@ pub enum Metrics {
     /// The amount of time, in seconds for running bitcoin or stacks
     /// validation.
     ValidationDurationSeconds,
+    /// The amount of time, in seconds, between a deposit or withdrawal
+    /// request reaching one stage and reaching a later one. We use labels
+    /// for the kind of request and for the two stages.
+    RequestStageDurationSeconds,
+    /// The number of deposit or withdrawal requests that have not been
+    /// completed or rejected, by the latest stage that they have reached.
+    /// Requests that have not moved in the last week are left out.
+    RequestBacklog,
 }
 
 impl From<Metrics> for metrics::KeyName {
@@ -66,6 +91,11 @@ pub fn setup_metrics(prometheus_exporter_endpoint: Option<SocketAddr>) {
             .add_global_label("app", crate::PACKAGE_NAME)
             .set_buckets(&METRIC_BUCKETS)
             .expect("received an empty slice of metric buckets")
+            .set_buckets_for_metric(
+                Matcher::Full(<&str>::from(Metrics::RequestStageDurationSeconds).to_string()),
+                &REQUEST_STAGE_BUCKETS,
+            )
+            .expect("received an empty slice of metric buckets")
             .set_quantiles(&METRIC_QUANTILES)
             .expect("received an empty slice of metric quantiles")
             .install()
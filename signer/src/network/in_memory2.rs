@@ -1,11 +1,32 @@
 //! New version of the in-memory network
+//!
+//! Besides passing messages between signers, the [`WanNetwork`] can inject
+//! faults into the links between them: latency, dropped, duplicated and
+//! reordered messages, partitions that can be switched on and off, and
+//! messages that are held back until a test releases them.
+//!
+//! What happens to a message is decided when it is sent, using the seeded
+//! random number generator of the network, and a single task delivers the
+//! messages in order of their delivery time, with ties going to the
+//! message that was sent first. A network with a fixed seed that sees the
+//! same broadcasts in the same order therefore delivers them the same way.
 
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU8;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
 use std::time::Duration;
 
-use futures::StreamExt;
+use rand::rngs::StdRng;
+use rand::Rng as _;
+use rand::SeedableRng as _;
 use tokio::sync::broadcast::Sender;
-use tokio_stream::wrappers::BroadcastStream;
+use tokio::sync::Notify;
+use tokio::time::Instant;
 
 use crate::codec::Encode as _;
 use crate::context::Context;
@@ -17,38 +38,454 @@ use crate::error::Error;
 use super::MessageTransfer;
 use super::Msg;
 
-const DEFAULT_WAN_CAPACITY: usize = 10_000;
+/// The distribution of the time that it takes a message to cross a link.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Latency {
+    /// Messages are delivered as soon as they are sent.
+    #[default]
+    None,
+    /// Every message takes the same amount of time.
+    Fixed(Duration),
+    /// The latency is drawn uniformly from the closed interval between
+    /// `min` and `max`.
+    Uniform {
+        /// The shortest latency.
+        min: Duration,
+        /// The longest latency.
+        max: Duration,
+    },
+    /// The latency is `min` plus an exponentially distributed delay with
+    /// the given mean, which gives the long tail of a congested link.
+    Exponential {
+        /// The shortest latency.
+        min: Duration,
+        /// The mean of the delay on top of `min`.
+        mean: Duration,
+    },
+}
+
+impl Latency {
+    /// Draw a latency from this distribution.
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        match *self {
+            Latency::None => Duration::ZERO,
+            Latency::Fixed(latency) => latency,
+            Latency::Uniform { min, max } if min < max => rng.gen_range(min..=max),
+            Latency::Uniform { min, .. } => min,
+            Latency::Exponential { min, mean } => {
+                // Inverse transform sampling; `1 - u` is in (0, 1], so the
+                // logarithm is finite.
+                let u: f64 = rng.gen();
+                min + mean.mul_f64(-(1.0 - u).ln())
+            }
+        }
+    }
+}
+
+/// The conditions of the link that carries messages from one signer to
+/// another.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LinkConditions {
+    /// How long it takes for a message to cross the link.
+    pub latency: Latency,
+    /// The probability that a message is lost.
+    pub drop_probability: f64,
+    /// The probability that a message is delivered twice. The copy gets
+    /// its own latency.
+    pub duplicate_probability: f64,
+    /// Whether messages may overtake each other on the link. When this is
+    /// `false` messages are delivered in the order that they were sent,
+    /// and a slow message holds back the ones sent after it.
+    pub reordering: bool,
+}
+
+/// A named split of the signers into the signers in `side` and everyone
+/// else.
+#[derive(Debug)]
+struct Partition {
+    side: HashSet<u8>,
+    enabled: bool,
+}
+
+/// Messages that match a filter are kept out of the network until they
+/// are released.
+struct HoldBack {
+    name: String,
+    filter: Box<dyn Fn(&Msg) -> bool + Send + Sync>,
+    /// The held messages together with the ID of their recipient.
+    held: Vec<(u8, Msg)>,
+}
+
+impl std::fmt::Debug for HoldBack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HoldBack")
+            .field("name", &self.name)
+            .field("held", &self.held.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A message on its way to a signer.
+#[derive(Debug)]
+struct InFlight {
+    to: u8,
+    msg: Msg,
+}
+
+/// The signers of the WAN network, the faults that it injects into the
+/// links between them, and the messages that are in flight.
+#[derive(Debug)]
+struct Conditions {
+    rng: StdRng,
+    default_link: LinkConditions,
+    links: HashMap<(u8, u8), LinkConditions>,
+    partitions: HashMap<String, Partition>,
+    hold_backs: Vec<HoldBack>,
+    /// The signal channels of the connected signers, indexed by their ID.
+    nodes: Vec<Option<Sender<SignerSignal>>>,
+    /// The messages in flight, keyed by their delivery time and the order
+    /// in which they were sent.
+    in_flight: BTreeMap<(Instant, u64), InFlight>,
+    /// The delivery time of the last message sent over each link that
+    /// keeps its messages in order.
+    link_tails: HashMap<(u8, u8), Instant>,
+    next_seq: u64,
+}
+
+impl Conditions {
+    fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+            default_link: LinkConditions::default(),
+            links: HashMap::new(),
+            partitions: HashMap::new(),
+            hold_backs: Vec::new(),
+            nodes: Vec::new(),
+            in_flight: BTreeMap::new(),
+            link_tails: HashMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    fn link(&self, from: u8, to: u8) -> LinkConditions {
+        *self.links.get(&(from, to)).unwrap_or(&self.default_link)
+    }
+
+    fn is_partitioned(&self, from: u8, to: u8) -> bool {
+        self.partitions
+            .values()
+            .filter(|partition| partition.enabled)
+            .any(|partition| partition.side.contains(&from) != partition.side.contains(&to))
+    }
+
+    /// Decide what happens to the given message sent over the link from
+    /// `from` to `to`, returning the latency of every copy of the message
+    /// that gets delivered. Held back messages are stored along with their
+    /// recipient, so no copy of them is delivered.
+    fn route(&mut self, from: u8, to: u8, msg: &Msg) -> Vec<Duration> {
+        let link = self.link(from, to);
+        let mut delays = Vec::new();
+
+        if self.is_partitioned(from, to) {
+            return delays;
+        }
+        if let Some(hold_back) = self.hold_backs.iter_mut().find(|hold| (hold.filter)(msg)) {
+            hold_back.held.push((to, msg.clone()));
+            return delays;
+        }
+        if self.rng.gen_bool(link.drop_probability.clamp(0.0, 1.0)) {
+            return delays;
+        }
+
+        delays.push(link.latency.sample(&mut self.rng));
+        if self
+            .rng
+            .gen_bool(link.duplicate_probability.clamp(0.0, 1.0))
+        {
+            delays.push(link.latency.sample(&mut self.rng));
+        }
+        delays
+    }
+
+    /// Route the message of signer `from` to every other connected signer
+    /// and put the copies that get delivered in flight.
+    fn send(&mut self, from: u8, msg: &Msg) {
+        let now = Instant::now();
+        for to in 0..self.nodes.len() {
+            let to = to as u8;
+            if to == from || self.nodes[to as usize].is_none() {
+                continue;
+            }
+            let reordering = self.link(from, to).reordering;
+            for delay in self.route(from, to, msg) {
+                let mut deliver_at = now + delay;
+                if !reordering {
+                    let tail = self.link_tails.entry((from, to)).or_insert(deliver_at);
+                    deliver_at = deliver_at.max(*tail);
+                    *tail = deliver_at;
+                }
+                let seq = self.next_seq;
+                self.next_seq += 1;
+                let in_flight = InFlight { to, msg: msg.clone() };
+                self.in_flight.insert((deliver_at, seq), in_flight);
+            }
+        }
+    }
+
+    /// Pass the message to the signer with the given ID, returning whether
+    /// it is connected.
+    fn deliver(&self, to: u8, msg: Msg) -> bool {
+        let Some(Some(signer_tx)) = self.nodes.get(to as usize) else {
+            return false;
+        };
+        if let Err(error) = signer_tx.send(P2PEvent::MessageReceived(msg).into()) {
+            tracing::error!(%error, "instance channel has been closed");
+        };
+        true
+    }
+}
+
+/// The state of a WAN network that its signers share.
+#[derive(Debug)]
+struct Shared {
+    conditions: Mutex<Conditions>,
+    /// Wakes up the delivery task when a message is put in flight.
+    notify: Arc<Notify>,
+}
+
+impl Shared {
+    fn new() -> Arc<Self> {
+        Arc::new(Self {
+            conditions: Mutex::new(Conditions::new(StdRng::from_entropy())),
+            notify: Arc::new(Notify::new()),
+        })
+    }
+
+    fn conditions(&self) -> std::sync::MutexGuard<'_, Conditions> {
+        self.conditions
+            .lock()
+            .expect("the network conditions lock is poisoned")
+    }
+}
+
+/// Deliver the messages in flight as their delivery time comes. The task
+/// stops once the network and all of its signers have been dropped.
+async fn deliver_messages(shared: Weak<Shared>, notify: Arc<Notify>) {
+    loop {
+        let next_delivery = {
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            let conditions = shared.conditions();
+            conditions
+                .in_flight
+                .first_key_value()
+                .map(|((at, _), _)| *at)
+        };
+
+        match next_delivery {
+            Some(deliver_at) if deliver_at > Instant::now() => {
+                tokio::select! {
+                    biased;
+                    _ = notify.notified() => continue,
+                    _ = tokio::time::sleep_until(deliver_at) => {}
+                }
+            }
+            Some(_) => {}
+            None => {
+                notify.notified().await;
+                continue;
+            }
+        }
+
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        let mut conditions = shared.conditions();
+        let now = Instant::now();
+        while let Some(entry) = conditions.in_flight.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let InFlight { to, msg } = entry.remove();
+            conditions.deliver(to, msg);
+        }
+    }
+}
 
 /// In-memory representation of a WAN network between different signers.
 pub struct WanNetwork {
-    /// A sender that passes the message along with the ID of the signer
-    /// that sent it.
-    tx: Sender<(u8, Vec<u8>)>,
     /// A variable with the last ID of the signers.
     id: AtomicU8,
+    /// The state shared with the signers of the network.
+    shared: Arc<Shared>,
+    /// Whether the task that delivers messages has been spawned.
+    delivering: AtomicBool,
 }
 
 impl WanNetwork {
-    /// Create a new in-memory WAN network with the specified channel capacity.
-    pub fn new(capacity: usize) -> Self {
-        let (tx, _) = tokio::sync::broadcast::channel(capacity);
-        let id = AtomicU8::new(0);
-        Self { tx, id }
+    /// Create a new in-memory WAN network.
+    pub fn new() -> Self {
+        Self {
+            id: AtomicU8::new(0),
+            shared: Shared::new(),
+            delivering: AtomicBool::new(false),
+        }
     }
 
     /// Connect to the in-memory WAN network, returning a new signer-scoped
     /// network instance.
+    ///
+    /// Signers are given IDs in the order that they connect, starting at
+    /// zero. The links and partitions of the network refer to signers by
+    /// these IDs, see [`SignerNetwork::id`].
     pub fn connect<C: Context>(&self, ctx: &C) -> SignerNetwork {
         let id = self.id.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-        let network = SignerNetwork::new(ctx, self.tx.clone(), id);
-        network.start();
-        network
+        self.connect_as(id, ctx)
+    }
+
+    /// Connect to the in-memory WAN network under the given ID, replacing
+    /// the signer that was connected under it, if there was one. This is
+    /// how a signer that restarts gets its links and partitions back.
+    ///
+    /// The IDs handed out by [`WanNetwork::connect`] do not skip the IDs
+    /// used here, so a network should use one or the other.
+    pub fn connect_as<C: Context>(&self, id: u8, ctx: &C) -> SignerNetwork {
+        if !self
+            .delivering
+            .swap(true, std::sync::atomic::Ordering::SeqCst)
+        {
+            let notify = Arc::clone(&self.shared.notify);
+            tokio::spawn(deliver_messages(Arc::downgrade(&self.shared), notify));
+        }
+
+        let signer_tx = ctx.get_signal_sender();
+        let mut conditions = self.conditions();
+        let index = id as usize;
+        if conditions.nodes.len() <= index {
+            conditions.nodes.resize(index + 1, None);
+        }
+        conditions.nodes[index] = Some(signer_tx.clone());
+
+        SignerNetwork {
+            shared: Arc::clone(&self.shared),
+            signer_tx,
+            id,
+        }
+    }
+
+    /// Disconnect the signer with the given ID, as if it crashed. Messages
+    /// for it are lost until a signer connects under its ID again.
+    pub fn disconnect(&self, id: u8) {
+        if let Some(node) = self.conditions().nodes.get_mut(id as usize) {
+            *node = None;
+        }
+    }
+
+    fn conditions(&self) -> std::sync::MutexGuard<'_, Conditions> {
+        self.shared.conditions()
+    }
+
+    /// Seed the random number generator that draws latencies, drops and
+    /// duplicates.
+    pub fn set_seed(&self, seed: u64) {
+        self.conditions().rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Set the conditions of every link that doesn't have conditions of
+    /// its own.
+    pub fn set_default_link(&self, link: LinkConditions) {
+        self.conditions().default_link = link;
+    }
+
+    /// Set the conditions of the link that carries messages from signer
+    /// `from` to signer `to`. Links are one-way, so the reverse link keeps
+    /// its conditions.
+    pub fn set_link(&self, from: u8, to: u8, link: LinkConditions) {
+        self.conditions().links.insert((from, to), link);
+    }
+
+    /// Define a partition that separates the given signers from the rest
+    /// of the network. The partition starts out disabled, and replaces
+    /// any partition with the same name.
+    pub fn add_partition<I>(&self, name: &str, side: I)
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        let partition = Partition {
+            side: side.into_iter().collect(),
+            enabled: false,
+        };
+        self.conditions()
+            .partitions
+            .insert(name.to_string(), partition);
+    }
+
+    /// Enable the named partition. Messages sent across it from now on are
+    /// lost, while messages that are already in flight are delivered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no partition with the given name.
+    pub fn enable_partition(&self, name: &str) {
+        self.toggle_partition(name, true);
+    }
+
+    /// Disable the named partition, so that messages can cross it again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no partition with the given name.
+    pub fn disable_partition(&self, name: &str) {
+        self.toggle_partition(name, false);
+    }
+
+    fn toggle_partition(&self, name: &str, enabled: bool) {
+        self.conditions()
+            .partitions
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("there is no partition named {name}"))
+            .enabled = enabled;
+    }
+
+    /// Hold back every message that matches the filter until
+    /// [`WanNetwork::release`] is called with the same name. This is meant
+    /// for holding back specific messages, such as the `DkgPrivateShares`
+    /// of one signer, to exercise timeouts and retries.
+    pub fn hold_back<F>(&self, name: &str, filter: F)
+    where
+        F: Fn(&Msg) -> bool + Send + Sync + 'static,
+    {
+        let hold_back = HoldBack {
+            name: name.to_string(),
+            filter: Box::new(filter),
+            held: Vec::new(),
+        };
+        self.conditions().hold_backs.push(hold_back);
+    }
+
+    /// Stop holding back the messages of the named filter and deliver the
+    /// messages that it held back, returning the number of messages that
+    /// were delivered. Messages for signers that are not connected are
+    /// lost.
+    pub fn release(&self, name: &str) -> usize {
+        let mut conditions = self.conditions();
+        let (released, kept) = std::mem::take(&mut conditions.hold_backs)
+            .into_iter()
+            .partition(|hold_back| hold_back.name == name);
+        conditions.hold_backs = kept;
+
+        released
+            .into_iter()
+            .flat_map(|hold_back: HoldBack| hold_back.held)
+            .filter(|(to, msg)| conditions.deliver(*to, msg.clone()))
+            .count()
     }
 }
 
 impl Default for WanNetwork {
     fn default() -> Self {
-        Self::new(DEFAULT_WAN_CAPACITY)
+        Self::new()
     }
 }
 
@@ -58,75 +495,53 @@ impl Default for WanNetwork {
 /// same network.
 #[derive(Debug, Clone)]
 pub struct SignerNetwork {
-    wan_tx: Sender<(u8, Vec<u8>)>,
+    shared: Arc<Shared>,
     signer_tx: Sender<SignerSignal>,
     id: u8,
 }
 
 impl SignerNetwork {
-    /// Start the in-memory signer network
-    fn start(&self) {
-        // We listen to the WAN network and forward messages to the signer network.
-        let mut rx = BroadcastStream::new(self.wan_tx.subscribe());
-        // We clone the sender to the signer network to be able to send messages
-        let tx = self.signer_tx.clone();
-
-        // We spawn a task that listens to the WAN network and forwards messages
-        // to the signer network, but only if this signer instance isn't the
-        // sender.
-        let my_id = self.id;
-        tokio::spawn(async move {
-            while let Some(item) = rx.next().await {
-                match item {
-                    // We do not send messages where the ID is the same as
-                    // ours, since those originated with us.
-                    Ok((id, msg)) if id != my_id => {
-                        let msg = match Msg::decode_with_digest(msg.as_slice()) {
-                            Ok((msg, digest)) if msg.verify_digest(digest).is_ok() => msg,
-                            Ok(_) => {
-                                tracing::error!("received improperly signed message");
-                                continue;
-                            }
-                            Err(error) => {
-                                tracing::error!(%error, "failed to decode the message");
-                                continue;
-                            }
-                        };
-                        if let Err(error) = tx.send(P2PEvent::MessageReceived(msg).into()) {
-                            tracing::error!(%error, "instance channel has been closed");
-                        };
-                    }
-                    Ok(_) => {}
-                    Err(error) => tracing::error!(%error, "The channel is lagging"),
-                }
-            }
-        });
-    }
-
     /// Create a new in-memory signer network with a single signer instance.
     /// You can use this if you do not need to simulate multiple signers.
     pub fn single<C: Context>(ctx: &C) -> Self {
-        let (wan_tx, _) = tokio::sync::broadcast::channel(DEFAULT_WAN_CAPACITY);
-        Self::new(ctx, wan_tx, 0)
+        Self {
+            shared: Shared::new(),
+            signer_tx: ctx.get_signal_sender(),
+            id: 0,
+        }
     }
 
-    /// Create a new in-memory signer network.
-    fn new<C: Context>(ctx: &C, wan_tx: Sender<(u8, Vec<u8>)>, id: u8) -> Self {
-        // We create a new broadcast channel for this signer's network.
-        let signer_tx = ctx.get_signal_sender();
-
-        Self { wan_tx, signer_tx, id }
+    /// The ID of this signer on the WAN network.
+    pub fn id(&self) -> u8 {
+        self.id
     }
 
     /// Sends a message to the WAN network.
     fn send(&self, msg: Msg) -> Result<(), Error> {
+        // Messages go through the codec, as they would over the wire.
         let encoded_msg = msg.encode_to_vec();
-        // Send the message out to the WAN.
-        self.wan_tx
-            .send((self.id, encoded_msg))
-            .inspect_err(|error| tracing::error!(%error, "could not send over the network"))
-            .map(|_| ())
-            .map_err(|_| Error::SendMessage)
+        let msg = match Msg::decode_with_digest(encoded_msg.as_slice()) {
+            Ok((msg, digest)) if msg.verify_digest(digest).is_ok() => msg,
+            Ok(_) => {
+                tracing::error!("sent improperly signed message");
+                return Ok(());
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to decode the message");
+                return Ok(());
+            }
+        };
+
+        let mut conditions = self.shared.conditions();
+        if conditions.nodes.iter().all(Option::is_none) {
+            tracing::error!("could not send over the network, no signer is connected");
+            return Err(Error::SendMessage);
+        }
+        conditions.send(self.id, &msg);
+        drop(conditions);
+
+        self.shared.notify.notify_one();
+        Ok(())
     }
 
     /// Spawns a new instance of the in-memory signer network.
@@ -179,17 +594,58 @@ mod tests {
     use std::sync::atomic::{AtomicU16, Ordering};
     use std::sync::Arc;
 
+    use bitcoin::hashes::Hash as _;
     use futures::future::join_all;
     use rand::rngs::OsRng;
+    use rand::SeedableRng as _;
+    use test_case::test_case;
 
+    use crate::ecdsa::SignEcdsa as _;
     use crate::keys::PrivateKey;
+    use crate::keys::PublicKey;
+    use crate::message::Payload;
+    use crate::message::WstsMessage;
+    use crate::storage::model::BitcoinBlockHash;
     use crate::testing::context::TestContext;
 
     use super::*;
 
+    /// A `DkgPrivateShares` message signed by the given key.
+    fn dkg_private_shares(private_key: &PrivateKey) -> Msg {
+        let inner = wsts::net::DkgPrivateShares {
+            dkg_id: 1,
+            signer_id: 0,
+            shares: Vec::new(),
+        };
+        let payload = Payload::WstsMessage(WstsMessage {
+            txid: bitcoin::Txid::from_byte_array([0; 32]),
+            inner: wsts::net::Message::DkgPrivateShares(inner),
+        });
+        payload
+            .to_message(BitcoinBlockHash::from([0; 32]))
+            .sign_ecdsa(private_key)
+    }
+
+    fn is_dkg_private_shares(msg: &Msg) -> bool {
+        matches!(
+            &msg.inner.payload,
+            Payload::WstsMessage(WstsMessage {
+                inner: wsts::net::Message::DkgPrivateShares(_),
+                ..
+            })
+        )
+    }
+
+    async fn try_receive(client: &mut SignerNetworkInstance, wait: Duration) -> Option<Msg> {
+        tokio::time::timeout(wait, client.receive())
+            .await
+            .ok()?
+            .ok()
+    }
+
     #[tokio::test]
     async fn signer_2_can_receive_messages_from_signer_1() {
-        let network = WanNetwork::new(100);
+        let network = WanNetwork::new();
         let ctx1 = TestContext::default_mocked();
         let ctx2 = TestContext::default_mocked();
         let signer_1 = network.connect(&ctx1);
@@ -213,7 +669,7 @@ mod tests {
 
     #[tokio::test]
     async fn signer_2_can_receive_messages_from_signer_1_concurrent_send() {
-        let network = WanNetwork::new(1_000);
+        let network = WanNetwork::new();
 
         let ctx1 = TestContext::default_mocked();
         let ctx2 = TestContext::default_mocked();
@@ -255,7 +711,7 @@ mod tests {
 
     #[tokio::test]
     async fn network_instance_does_not_receive_messages_from_same_signer_network() {
-        let network = WanNetwork::new(100);
+        let network = WanNetwork::new();
 
         let ctx = TestContext::default_mocked();
         let client = network.connect(&ctx);
@@ -278,7 +734,7 @@ mod tests {
 
     #[tokio::test]
     async fn two_clients_can_exchange_messages_simple() {
-        let network = WanNetwork::new(100);
+        let network = WanNetwork::new();
 
         let ctx1 = TestContext::default_mocked();
         let ctx2 = TestContext::default_mocked();
@@ -314,7 +770,7 @@ mod tests {
 
     #[tokio::test]
     async fn two_clients_can_exchange_messages_advanced() {
-        let network = WanNetwork::new(100);
+        let network = WanNetwork::new();
 
         let ctx1 = TestContext::default_mocked();
         let ctx2 = TestContext::default_mocked();
@@ -331,4 +787,249 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn link_latency_delays_delivery() {
+        let network = WanNetwork::new();
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+
+        let latency = Latency::Fixed(Duration::from_millis(100));
+        let link = LinkConditions { latency, ..Default::default() };
+        network.set_link(signer_1.id(), signer_2.id(), link);
+
+        let mut client_1 = signer_1.spawn();
+        let mut client_2 = signer_2.spawn();
+
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+
+        let wait = Duration::from_millis(50);
+        assert!(try_receive(&mut client_2, wait).await.is_none());
+        assert!(try_receive(&mut client_2, wait * 2).await.is_some());
+
+        // The reverse link has no latency.
+        client_2.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        assert!(try_receive(&mut client_1, Duration::from_millis(10))
+            .await
+            .is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn links_drop_and_duplicate_messages() {
+        let network = WanNetwork::new();
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+
+        let mut client_1 = signer_1.spawn();
+        let mut client_2 = signer_2.spawn();
+        let wait = Duration::from_secs(1);
+
+        let link = LinkConditions {
+            drop_probability: 1.0,
+            ..Default::default()
+        };
+        network.set_default_link(link);
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        assert!(try_receive(&mut client_2, wait).await.is_none());
+
+        let link = LinkConditions {
+            duplicate_probability: 1.0,
+            ..Default::default()
+        };
+        network.set_default_link(link);
+        let msg = Msg::random(&mut OsRng);
+        client_1.broadcast(msg.clone()).await.unwrap();
+        assert_eq!(try_receive(&mut client_2, wait).await, Some(msg.clone()));
+        assert_eq!(try_receive(&mut client_2, wait).await, Some(msg));
+        assert!(try_receive(&mut client_2, wait).await.is_none());
+    }
+
+    #[test_case(false; "in order")]
+    #[test_case(true; "reordered")]
+    #[tokio::test(start_paused = true)]
+    async fn links_keep_messages_in_order_unless_reordering(reordering: bool) {
+        let network = WanNetwork::new();
+        network.set_seed(46);
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+
+        let latency = Latency::Uniform {
+            min: Duration::ZERO,
+            max: Duration::from_millis(100),
+        };
+        network.set_default_link(LinkConditions {
+            latency,
+            reordering,
+            ..Default::default()
+        });
+
+        let mut client_1 = signer_1.spawn();
+        let mut client_2 = signer_2.spawn();
+
+        let sent: Vec<Msg> = (0..20).map(|_| Msg::random(&mut OsRng)).collect();
+        for msg in sent.iter() {
+            client_1.broadcast(msg.clone()).await.unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Some(msg) = try_receive(&mut client_2, Duration::from_secs(1)).await {
+            received.push(msg);
+        }
+
+        // With twenty messages, the chance that random latencies keep
+        // them in order is negligible.
+        assert_eq!(received.len(), sent.len());
+        assert_eq!(received == sent, !reordering);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn named_partitions_can_be_toggled() {
+        let network = WanNetwork::new();
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let ctx3 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+        let signer_3 = network.connect(&ctx3);
+
+        network.add_partition("isolate-3", [signer_3.id()]);
+
+        let mut client_1 = signer_1.spawn();
+        let mut client_2 = signer_2.spawn();
+        let mut client_3 = signer_3.spawn();
+        let wait = Duration::from_secs(1);
+
+        network.enable_partition("isolate-3");
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        assert!(try_receive(&mut client_2, wait).await.is_some());
+        assert!(try_receive(&mut client_3, wait).await.is_none());
+
+        network.disable_partition("isolate-3");
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        assert!(try_receive(&mut client_2, wait).await.is_some());
+        assert!(try_receive(&mut client_3, wait).await.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn held_back_messages_are_delivered_when_released() {
+        let network = WanNetwork::new();
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+
+        let mut client_1 = signer_1.spawn();
+        let mut client_2 = signer_2.spawn();
+        let wait = Duration::from_secs(1);
+
+        let held_key = PrivateKey::new(&mut OsRng);
+        let other_key = PrivateKey::new(&mut OsRng);
+        let held_public_key = PublicKey::from_private_key(&held_key);
+
+        network.hold_back("private-shares", move |msg| {
+            msg.signer_public_key == held_public_key && is_dkg_private_shares(msg)
+        });
+
+        let held = dkg_private_shares(&held_key);
+        let other = dkg_private_shares(&other_key);
+        client_1.broadcast(held.clone()).await.unwrap();
+        client_1.broadcast(other.clone()).await.unwrap();
+
+        assert_eq!(try_receive(&mut client_2, wait).await, Some(other));
+        assert!(try_receive(&mut client_2, wait).await.is_none());
+
+        assert_eq!(network.release("private-shares"), 1);
+        assert_eq!(try_receive(&mut client_2, wait).await, Some(held));
+
+        // Once released, messages that match the filter go through.
+        client_1
+            .broadcast(dkg_private_shares(&held_key))
+            .await
+            .unwrap();
+        assert!(try_receive(&mut client_2, wait).await.is_some());
+    }
+
+    /// Broadcast a few messages from two signers to a third over links
+    /// that reorder, drop and duplicate messages, and return what the
+    /// third signer receives.
+    async fn received_with_seed(seed: u64) -> Vec<Msg> {
+        let network = WanNetwork::new();
+        network.set_seed(seed);
+        network.set_default_link(LinkConditions {
+            latency: Latency::Uniform {
+                min: Duration::ZERO,
+                max: Duration::from_millis(100),
+            },
+            drop_probability: 0.2,
+            duplicate_probability: 0.2,
+            reordering: true,
+        });
+
+        let contexts: Vec<_> = (0..3).map(|_| TestContext::default_mocked()).collect();
+        let mut clients: Vec<_> = contexts
+            .iter()
+            .map(|ctx| network.connect(ctx).spawn())
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10 {
+            for client in clients.iter_mut().take(2) {
+                client.broadcast(Msg::random(&mut rng)).await.unwrap();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let mut received = Vec::new();
+        while let Some(msg) = try_receive(&mut clients[2], Duration::from_secs(1)).await {
+            received.push(msg);
+        }
+        received
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_gives_same_deliveries() {
+        let received = received_with_seed(7).await;
+        assert!(!received.is_empty());
+        assert_eq!(received, received_with_seed(7).await);
+        assert_ne!(received, received_with_seed(8).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn disconnected_signers_lose_messages_until_they_reconnect() {
+        let network = WanNetwork::new();
+        let ctx1 = TestContext::default_mocked();
+        let ctx2 = TestContext::default_mocked();
+        let signer_1 = network.connect(&ctx1);
+        let signer_2 = network.connect(&ctx2);
+
+        let link = LinkConditions {
+            latency: Latency::Fixed(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        network.set_link(signer_1.id(), signer_2.id(), link);
+
+        let mut client_1 = signer_1.spawn();
+        let wait = Duration::from_secs(1);
+
+        // A message in flight when its recipient crashes is lost.
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        network.disconnect(signer_2.id());
+        tokio::time::sleep(wait).await;
+
+        // The restarted signer keeps the links of its ID.
+        let ctx2 = TestContext::default_mocked();
+        let signer_2 = network.connect_as(signer_2.id(), &ctx2);
+        let mut client_2 = signer_2.spawn();
+        client_1.broadcast(Msg::random(&mut OsRng)).await.unwrap();
+        let wait_for_latency = Duration::from_millis(50);
+        assert!(try_receive(&mut client_2, wait_for_latency).await.is_none());
+        assert!(try_receive(&mut client_2, wait).await.is_some());
+        assert!(try_receive(&mut client_2, wait).await.is_none());
+    }
 }
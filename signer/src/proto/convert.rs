@@ -53,15 +53,22 @@ use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
 use crate::message::StacksTransactionSignRequest;
 use crate::message::StacksTransactionSignature;
+use crate::message::TraceContext;
 use crate::message::WstsMessage;
 use crate::proto;
 use crate::stacks::contracts::AcceptWithdrawalV1;
+use crate::stacks::contracts::AcceptWithdrawalV2;
 use crate::stacks::contracts::CompleteDepositV1;
+use crate::stacks::contracts::CompleteDepositV2;
 use crate::stacks::contracts::ContractCall;
+use crate::stacks::contracts::ContractVersion;
+use crate::stacks::contracts::ProtocolRole;
 use crate::stacks::contracts::RejectWithdrawalV1;
+use crate::stacks::contracts::RejectWithdrawalV2;
 use crate::stacks::contracts::RotateKeysV1;
 use crate::stacks::contracts::SmartContract;
 use crate::stacks::contracts::StacksTx;
+use crate::stacks::contracts::UpdateProtocolContractV1;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinTxId;
 use crate::storage::model::QualifiedRequestId;
@@ -520,6 +527,8 @@ impl From<SmartContract> for proto::SmartContract {
             SmartContract::SbtcDeposit => proto::SmartContract::SbtcDeposit,
             SmartContract::SbtcWithdrawal => proto::SmartContract::SbtcWithdrawal,
             SmartContract::SbtcBootstrap => proto::SmartContract::SbtcBootstrap,
+            SmartContract::SbtcDepositV2 => proto::SmartContract::SbtcDepositV2,
+            SmartContract::SbtcWithdrawalV2 => proto::SmartContract::SbtcWithdrawalV2,
         }
     }
 }
@@ -533,11 +542,80 @@ impl TryFrom<proto::SmartContract> for SmartContract {
             proto::SmartContract::SbtcDeposit => SmartContract::SbtcDeposit,
             proto::SmartContract::SbtcWithdrawal => SmartContract::SbtcWithdrawal,
             proto::SmartContract::SbtcBootstrap => SmartContract::SbtcBootstrap,
+            proto::SmartContract::SbtcDepositV2 => SmartContract::SbtcDepositV2,
+            proto::SmartContract::SbtcWithdrawalV2 => SmartContract::SbtcWithdrawalV2,
             proto::SmartContract::Unspecified => return Err(Error::TypeConversion),
         })
     }
 }
 
+impl From<ProtocolRole> for proto::ProtocolRole {
+    fn from(value: ProtocolRole) -> Self {
+        match value {
+            ProtocolRole::Governance => proto::ProtocolRole::Governance,
+            ProtocolRole::Deposit => proto::ProtocolRole::Deposit,
+            ProtocolRole::Withdrawal => proto::ProtocolRole::Withdrawal,
+        }
+    }
+}
+
+impl TryFrom<proto::ProtocolRole> for ProtocolRole {
+    type Error = Error;
+    fn try_from(value: proto::ProtocolRole) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::ProtocolRole::Governance => ProtocolRole::Governance,
+            proto::ProtocolRole::Deposit => ProtocolRole::Deposit,
+            proto::ProtocolRole::Withdrawal => ProtocolRole::Withdrawal,
+            proto::ProtocolRole::Unspecified => return Err(Error::TypeConversion),
+        })
+    }
+}
+
+impl From<ContractVersion> for proto::ContractVersion {
+    fn from(value: ContractVersion) -> Self {
+        match value {
+            ContractVersion::V1 => proto::ContractVersion::V1,
+            ContractVersion::V2 => proto::ContractVersion::V2,
+        }
+    }
+}
+
+impl TryFrom<proto::ContractVersion> for ContractVersion {
+    type Error = Error;
+    fn try_from(value: proto::ContractVersion) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::ContractVersion::V1 => ContractVersion::V1,
+            proto::ContractVersion::V2 => ContractVersion::V2,
+            proto::ContractVersion::Unspecified => return Err(Error::TypeConversion),
+        })
+    }
+}
+
+impl From<UpdateProtocolContractV1> for proto::UpdateProtocolContract {
+    fn from(value: UpdateProtocolContractV1) -> Self {
+        proto::UpdateProtocolContract {
+            role: proto::ProtocolRole::from(value.role).into(),
+            version: proto::ContractVersion::from(value.version).into(),
+            deployer: Some(value.deployer.into()),
+        }
+    }
+}
+
+impl TryFrom<proto::UpdateProtocolContract> for UpdateProtocolContractV1 {
+    type Error = Error;
+    fn try_from(value: proto::UpdateProtocolContract) -> Result<Self, Self::Error> {
+        Ok(UpdateProtocolContractV1 {
+            role: proto::ProtocolRole::try_from(value.role)
+                .map_err(|_| Error::TypeConversion)?
+                .try_into()?,
+            version: proto::ContractVersion::try_from(value.version)
+                .map_err(|_| Error::TypeConversion)?
+                .try_into()?,
+            deployer: value.deployer.required()?.try_into()?,
+        })
+    }
+}
+
 impl From<StacksTransactionSignRequest> for proto::StacksTransactionSignRequest {
     fn from(value: StacksTransactionSignRequest) -> Self {
         let contract_tx = match value.contract_tx {
@@ -560,6 +638,26 @@ impl From<StacksTransactionSignRequest> for proto::StacksTransactionSignRequest
                 ContractCall::RotateKeysV1(inner) => {
                     proto::stacks_transaction_sign_request::ContractTx::RotateKeys(inner.into())
                 }
+                ContractCall::CompleteDepositV2(inner) => {
+                    proto::stacks_transaction_sign_request::ContractTx::CompleteDepositV2(
+                        inner.0.into(),
+                    )
+                }
+                ContractCall::AcceptWithdrawalV2(inner) => {
+                    proto::stacks_transaction_sign_request::ContractTx::AcceptWithdrawalV2(
+                        inner.0.into(),
+                    )
+                }
+                ContractCall::RejectWithdrawalV2(inner) => {
+                    proto::stacks_transaction_sign_request::ContractTx::RejectWithdrawalV2(
+                        inner.0.into(),
+                    )
+                }
+                ContractCall::UpdateProtocolContractV1(inner) => {
+                    proto::stacks_transaction_sign_request::ContractTx::UpdateProtocolContract(
+                        inner.into(),
+                    )
+                }
             },
             StacksTx::SmartContract(inner) => {
                 proto::stacks_transaction_sign_request::ContractTx::SmartContract(
@@ -598,6 +696,18 @@ impl TryFrom<proto::StacksTransactionSignRequest> for StacksTransactionSignReque
                     .map_err(|_| Error::TypeConversion)?
                     .try_into()?,
             ),
+            proto::ContractTx::CompleteDepositV2(inner) => StacksTx::ContractCall(
+                ContractCall::CompleteDepositV2(CompleteDepositV2(inner.try_into()?)),
+            ),
+            proto::ContractTx::AcceptWithdrawalV2(inner) => StacksTx::ContractCall(
+                ContractCall::AcceptWithdrawalV2(AcceptWithdrawalV2(inner.try_into()?)),
+            ),
+            proto::ContractTx::RejectWithdrawalV2(inner) => StacksTx::ContractCall(
+                ContractCall::RejectWithdrawalV2(RejectWithdrawalV2(inner.try_into()?)),
+            ),
+            proto::ContractTx::UpdateProtocolContract(inner) => {
+                StacksTx::ContractCall(ContractCall::UpdateProtocolContractV1(inner.try_into()?))
+            }
         };
         Ok(StacksTransactionSignRequest {
             aggregate_key: value.aggregate_key.required()?.try_into()?,
@@ -1271,6 +1381,7 @@ impl From<SignerMessage> for proto::SignerMessage {
         proto::SignerMessage {
             bitcoin_chain_tip: Some(value.bitcoin_chain_tip.into()),
             payload: Some(value.payload.into()),
+            trace_context: value.trace_context.map(Into::into),
         }
     }
 }
@@ -1281,10 +1392,29 @@ impl TryFrom<proto::SignerMessage> for SignerMessage {
         Ok(SignerMessage {
             bitcoin_chain_tip: value.bitcoin_chain_tip.required()?.try_into()?,
             payload: value.payload.required()?.try_into()?,
+            trace_context: value.trace_context.map(Into::into),
         })
     }
 }
 
+impl From<TraceContext> for proto::TraceContext {
+    fn from(value: TraceContext) -> Self {
+        proto::TraceContext {
+            traceparent: value.traceparent,
+            tracestate: value.tracestate,
+        }
+    }
+}
+
+impl From<proto::TraceContext> for TraceContext {
+    fn from(value: proto::TraceContext) -> Self {
+        TraceContext {
+            traceparent: value.traceparent,
+            tracestate: value.tracestate,
+        }
+    }
+}
+
 impl From<Payload> for proto::Payload {
     fn from(value: Payload) -> Self {
         match value {
@@ -1658,6 +1788,14 @@ impl codec::ProtoSerializable for Signed<SignerMessage> {
     }
 }
 
+impl codec::ProtoSerializable for StacksTransactionSignRequest {
+    type Message = proto::StacksTransactionSignRequest;
+
+    fn type_tag(&self) -> &'static str {
+        "SBTC_STACKS_TRANSACTION_SIGN_REQUEST"
+    }
+}
+
 impl codec::ProtoSerializable for SignerState {
     type Message = proto::SignerState;
 
@@ -1717,11 +1855,15 @@ mod tests {
     #[test_case(PhantomData::<(RejectWithdrawalV1, proto::RejectWithdrawal)>; "RejectWithdrawal")]
     #[test_case(PhantomData::<(RotateKeysV1, proto::RotateKeys)>; "RotateKeys")]
     #[test_case(PhantomData::<(SmartContract, proto::SmartContract)>; "SmartContract")]
+    #[test_case(PhantomData::<(ProtocolRole, proto::ProtocolRole)>; "ProtocolRole")]
+    #[test_case(PhantomData::<(ContractVersion, proto::ContractVersion)>; "ContractVersion")]
+    #[test_case(PhantomData::<(UpdateProtocolContractV1, proto::UpdateProtocolContract)>; "UpdateProtocolContract")]
     #[test_case(PhantomData::<(Payload, proto::Payload)>; "Payload")]
     #[test_case(PhantomData::<(StacksTransactionSignRequest, proto::StacksTransactionSignRequest)>; "StacksTransactionSignRequest")]
     #[test_case(PhantomData::<(WstsMessage, proto::WstsMessage)>; "WstsMessage")]
     #[test_case(PhantomData::<(SignerMessage, proto::SignerMessage)>; "SignerMessage")]
     #[test_case(PhantomData::<(Signed<SignerMessage>, proto::Signed)>; "Signed")]
+    #[test_case(PhantomData::<(TraceContext, proto::TraceContext)>; "TraceContext")]
     #[test_case(PhantomData::<(QualifiedRequestId, proto::QualifiedRequestId)>; "QualifiedRequestId")]
     #[test_case(PhantomData::<(TxRequestIds, proto::TxRequestIds)>; "TxRequestIds")]
     #[test_case(PhantomData::<(Fees, proto::Fees)>; "Fees")]
@@ -68,7 +68,7 @@ pub struct StacksTransactionSignRequest {
     /// The contract transaction to sign.
     #[prost(
         oneof = "stacks_transaction_sign_request::ContractTx",
-        tags = "5, 6, 7, 8, 9"
+        tags = "5, 6, 7, 8, 9, 10, 11, 12, 13"
     )]
     pub contract_tx: ::core::option::Option<stacks_transaction_sign_request::ContractTx>,
 }
@@ -93,6 +93,21 @@ pub mod stacks_transaction_sign_request {
         /// Ssmart contract deployment
         #[prost(enumeration = "super::SmartContract", tag = "9")]
         SmartContract(i32),
+        /// The `complete-deposit` contract call in the sbtc-deposit-v2
+        /// contract
+        #[prost(message, tag = "10")]
+        CompleteDepositV2(super::CompleteDeposit),
+        /// The `accept-withdrawal-request` contract call in the
+        /// sbtc-withdrawal-v2 contract
+        #[prost(message, tag = "11")]
+        AcceptWithdrawalV2(super::AcceptWithdrawal),
+        /// The `reject-withdrawal-request` contract call in the
+        /// sbtc-withdrawal-v2 contract
+        #[prost(message, tag = "12")]
+        RejectWithdrawalV2(super::RejectWithdrawal),
+        /// The `update-protocol-contract-wrapper` contract call
+        #[prost(message, tag = "13")]
+        UpdateProtocolContract(super::UpdateProtocolContract),
     }
 }
 /// For making a `complete-deposit` contract call in the sbtc-deposit
@@ -204,6 +219,21 @@ pub struct RotateKeys {
     #[prost(uint32, tag = "4")]
     pub signatures_required: u32,
 }
+/// For making an `update-protocol-contract-wrapper` contract call in the
+/// `sbtc-bootstrap-signers` smart contract.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateProtocolContract {
+    /// The role of the protocol contract being replaced.
+    #[prost(enumeration = "ProtocolRole", tag = "1")]
+    pub role: i32,
+    /// The version of the protocol contract to make active.
+    #[prost(enumeration = "ContractVersion", tag = "2")]
+    pub version: i32,
+    /// The address that deployed the contract.
+    #[prost(message, optional, tag = "3")]
+    pub deployer: ::core::option::Option<super::super::StacksAddress>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum SmartContract {
@@ -223,6 +253,12 @@ pub enum SmartContract {
     /// The sbtc-bootstrap-signers contract. Can be deployed after the
     /// sbtc-token contract.
     SbtcBootstrap = 5,
+    /// The sbtc-deposit-v2 contract. Can be deployed after the first
+    /// version of all contracts.
+    SbtcDepositV2 = 6,
+    /// The sbtc-withdrawal-v2 contract. Can be deployed after the first
+    /// version of all contracts.
+    SbtcWithdrawalV2 = 7,
 }
 impl SmartContract {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -237,6 +273,8 @@ impl SmartContract {
             SmartContract::SbtcDeposit => "SMART_CONTRACT_SBTC_DEPOSIT",
             SmartContract::SbtcWithdrawal => "SMART_CONTRACT_SBTC_WITHDRAWAL",
             SmartContract::SbtcBootstrap => "SMART_CONTRACT_SBTC_BOOTSTRAP",
+            SmartContract::SbtcDepositV2 => "SMART_CONTRACT_SBTC_DEPOSIT_V2",
+            SmartContract::SbtcWithdrawalV2 => "SMART_CONTRACT_SBTC_WITHDRAWAL_V2",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -248,6 +286,76 @@ impl SmartContract {
             "SMART_CONTRACT_SBTC_DEPOSIT" => Some(Self::SbtcDeposit),
             "SMART_CONTRACT_SBTC_WITHDRAWAL" => Some(Self::SbtcWithdrawal),
             "SMART_CONTRACT_SBTC_BOOTSTRAP" => Some(Self::SbtcBootstrap),
+            "SMART_CONTRACT_SBTC_DEPOSIT_V2" => Some(Self::SbtcDepositV2),
+            "SMART_CONTRACT_SBTC_WITHDRAWAL_V2" => Some(Self::SbtcWithdrawalV2),
+            _ => None,
+        }
+    }
+}
+/// The roles that the sbtc-registry assigns to protocol contracts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProtocolRole {
+    Unspecified = 0,
+    /// The governance role, held by the sbtc-bootstrap-signers contract.
+    Governance = 1,
+    /// The deposit role, held by the sbtc-deposit contract.
+    Deposit = 2,
+    /// The withdrawal role, held by the sbtc-withdrawal contract.
+    Withdrawal = 3,
+}
+impl ProtocolRole {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ProtocolRole::Unspecified => "PROTOCOL_ROLE_UNSPECIFIED",
+            ProtocolRole::Governance => "PROTOCOL_ROLE_GOVERNANCE",
+            ProtocolRole::Deposit => "PROTOCOL_ROLE_DEPOSIT",
+            ProtocolRole::Withdrawal => "PROTOCOL_ROLE_WITHDRAWAL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PROTOCOL_ROLE_UNSPECIFIED" => Some(Self::Unspecified),
+            "PROTOCOL_ROLE_GOVERNANCE" => Some(Self::Governance),
+            "PROTOCOL_ROLE_DEPOSIT" => Some(Self::Deposit),
+            "PROTOCOL_ROLE_WITHDRAWAL" => Some(Self::Withdrawal),
+            _ => None,
+        }
+    }
+}
+/// The versions of the protocol contracts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ContractVersion {
+    Unspecified = 0,
+    /// The protocol contracts that were deployed with the sbtc-registry.
+    V1 = 1,
+    /// The second version of the protocol contracts.
+    V2 = 2,
+}
+impl ContractVersion {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ContractVersion::Unspecified => "CONTRACT_VERSION_UNSPECIFIED",
+            ContractVersion::V1 => "CONTRACT_VERSION_V1",
+            ContractVersion::V2 => "CONTRACT_VERSION_V2",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CONTRACT_VERSION_UNSPECIFIED" => Some(Self::Unspecified),
+            "CONTRACT_VERSION_V1" => Some(Self::V1),
+            "CONTRACT_VERSION_V2" => Some(Self::V2),
             _ => None,
         }
     }
@@ -261,6 +369,10 @@ pub struct SignerMessage {
     pub bitcoin_chain_tip: ::core::option::Option<
         super::super::super::bitcoin::BitcoinBlockHash,
     >,
+    /// The trace context of the span that sent the message, if the sender
+    /// exports traces.
+    #[prost(message, optional, tag = "12")]
+    pub trace_context: ::core::option::Option<TraceContext>,
     /// The message payload
     #[prost(oneof = "signer_message::Payload", tags = "2, 3, 4, 5, 8, 10, 11")]
     pub payload: ::core::option::Option<signer_message::Payload>,
@@ -294,6 +406,18 @@ pub mod signer_message {
         BitcoinPreSignAck(super::BitcoinPreSignAck),
     }
 }
+/// A W3C trace context, used to link the spans of different signers that
+/// take part in the same operation.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TraceContext {
+    /// The value of the `traceparent` header.
+    #[prost(string, tag = "1")]
+    pub traceparent: ::prost::alloc::string::String,
+    /// The value of the `tracestate` header, which may be empty.
+    #[prost(string, tag = "2")]
+    pub tracestate: ::prost::alloc::string::String,
+}
 /// A wsts message.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
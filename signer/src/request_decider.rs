@@ -24,6 +24,7 @@ use crate::message::SignerDepositDecision;
 use crate::message::SignerMessage;
 use crate::message::SignerWithdrawalDecision;
 use crate::network::MessageTransfer;
+use crate::request_lifecycle;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::DepositSigner;
@@ -31,8 +32,10 @@ use crate::storage::model::WithdrawalSigner;
 use crate::storage::DbRead as _;
 use crate::storage::DbWrite as _;
 
+use bitcoin::Amount;
 use futures::StreamExt;
 use futures::TryStreamExt;
+use tracing::Instrument as _;
 
 /// This struct is responsible for deciding whether to accept or reject
 /// requests and persisting requests from other signers.
@@ -88,12 +91,15 @@ where
                 SignerSignal::Command(SignerCommand::P2PPublish(_)) => {}
                 SignerSignal::Event(event) => match event {
                     SignerEvent::P2P(P2PEvent::MessageReceived(msg)) => {
-                        if let Err(error) = self.handle_signer_message(&msg).await {
+                        let span = self.iteration_span();
+                        let handled = self.handle_signer_message(&msg).instrument(span).await;
+                        if let Err(error) = handled {
                             tracing::error!(%error, "error handling signer message");
                         }
                     }
                     SignerEvent::BitcoinBlockObserved => {
-                        if let Err(error) = self.handle_new_requests().await {
+                        let span = self.iteration_span();
+                        if let Err(error) = self.handle_new_requests().instrument(span).await {
                             tracing::warn!(%error, "error handling new requests; skipping this round");
                         }
 
@@ -116,6 +122,16 @@ where
         Ok(())
     }
 
+    /// The span of a single iteration of the event loop. It has no parent,
+    /// so every iteration is exported as its own trace.
+    fn iteration_span(&self) -> tracing::Span {
+        tracing::info_span!(
+            parent: None,
+            "request-decider-iteration",
+            public_key = %self.signer_public_key(),
+        )
+    }
+
     #[tracing::instrument(skip_all, fields(chain_tip = tracing::field::Empty))]
     async fn handle_new_requests(&mut self) -> Result<(), Error> {
         let db = self.context.get_storage();
@@ -240,6 +256,11 @@ where
 
         db.write_deposit_signer_decision(&signer_decision).await?;
 
+        let stage = model::RequestStage::Decided;
+        let now = time::OffsetDateTime::now_utc();
+        let (txid, output_index) = (&request.txid, request.output_index);
+        request_lifecycle::record_deposit_stage(&db, txid, output_index, stage, now).await;
+
         self.send_message(msg, chain_tip).await?;
 
         self.context
@@ -275,11 +296,15 @@ where
             txid: withdrawal_request.txid,
         };
 
-        self.context
-            .get_storage_mut()
-            .write_withdrawal_signer_decision(&signer_decision)
+        let db = self.context.get_storage_mut();
+        db.write_withdrawal_signer_decision(&signer_decision)
             .await?;
 
+        let stage = model::RequestStage::Decided;
+        let now = time::OffsetDateTime::now_utc();
+        let request_id = withdrawal_request.request_id;
+        request_lifecycle::record_withdrawal_stage(&db, request_id, stage, now).await;
+
         self.send_message(msg, chain_tip).await?;
 
         self.context
@@ -292,6 +317,20 @@ where
         &self,
         req: &model::WithdrawalRequest,
     ) -> Result<bool, Error> {
+        // A withdrawal that is larger than the whole rolling withdrawal
+        // cap can never be serviced within the window.
+        let limits = self.context.state().get_current_limits();
+        if let Some(rolling_cap) = limits.rolling_withdrawal_cap() {
+            if Amount::from_sat(req.amount) > rolling_cap.cap {
+                tracing::info!(
+                    request_id = %req.request_id,
+                    amount = %req.amount,
+                    "withdrawal request exceeds the rolling withdrawal cap"
+                );
+                return Ok(false);
+            }
+        }
+
         // If we have not configured a blocklist checker, then we can
         // return early.
         let Some(client) = self.blocklist_checker.as_ref() else {
@@ -307,6 +346,20 @@ where
     }
 
     async fn can_accept_deposit_request(&self, req: &model::DepositRequest) -> Result<bool, Error> {
+        // A deposit that is larger than the whole rolling deposit cap can
+        // never be swept within the window.
+        let limits = self.context.state().get_current_limits();
+        if let Some(rolling_cap) = limits.rolling_deposit_cap() {
+            if Amount::from_sat(req.amount) > rolling_cap.cap {
+                tracing::info!(
+                    outpoint = %req.outpoint(),
+                    amount = %req.amount,
+                    "deposit request exceeds the rolling deposit cap"
+                );
+                return Ok(false);
+            }
+        }
+
         // If we have not configured a blocklist checker, then we can
         // return early.
         let Some(client) = self.blocklist_checker.as_ref() else {
@@ -435,6 +488,7 @@ where
         let payload: Payload = msg.into();
         let msg = payload
             .to_message(*chain_tip)
+            .with_current_trace_context()
             .sign_ecdsa(&self.signer_private_key);
 
         self.network.broadcast(msg).await?;
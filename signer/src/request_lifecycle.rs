@@ -0,0 +1,270 @@
+//! Tracking of deposit and withdrawal requests as they move through the
+//! stages of being fulfilled.
+//!
+//! Each time a request reaches a [`RequestStage`], the time is written to
+//! the database, so that the durations survive restarts of the signer.
+//! When a stage is reached for the first time we record, in the
+//! [`Metrics::RequestStageDurationSeconds`] histogram, how long it took to
+//! get there from each of the earlier stages that the request reached.
+//! The [`Metrics::RequestBacklog`] gauge tracks how many requests are
+//! waiting in each stage that is not terminal.
+//!
+//! Failing to record a stage should never stop the signer from doing its
+//! job, so the functions here log errors instead of returning them.
+
+use strum::IntoEnumIterator as _;
+use time::OffsetDateTime;
+
+use crate::error::Error;
+use crate::metrics::Metrics;
+use crate::storage::model::BitcoinTxId;
+use crate::storage::model::RequestKind;
+use crate::storage::model::RequestStage;
+use crate::storage::model::RequestStageTimestamp;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+
+/// How far back the backlog looks for requests. Deposits that are
+/// rejected, expire or get reclaimed never reach a terminal stage, so
+/// without a cutoff they would stay in the backlog forever.
+const BACKLOG_WINDOW: time::Duration = time::Duration::days(7);
+
+/// Record that the deposit request identified by the given outpoint
+/// reached the given stage at the given time.
+pub async fn record_deposit_stage<S>(
+    storage: &S,
+    txid: &BitcoinTxId,
+    output_index: u32,
+    stage: RequestStage,
+    reached_at: OffsetDateTime,
+) where
+    S: DbRead + DbWrite,
+{
+    let result = async {
+        let is_new = storage
+            .write_deposit_request_stage(txid, output_index, stage, reached_at)
+            .await?;
+        if is_new {
+            let stages = storage
+                .get_deposit_request_stages(txid, output_index)
+                .await?;
+            record_stage_durations(RequestKind::Deposit, stage, reached_at, &stages);
+        }
+        Ok::<_, Error>(())
+    };
+
+    if let Err(error) = result.await {
+        tracing::warn!(
+            %error,
+            %txid,
+            %output_index,
+            %stage,
+            "could not record the stage of a deposit request"
+        );
+    }
+}
+
+/// Record that the withdrawal request with the given request ID reached
+/// the given stage at the given time.
+pub async fn record_withdrawal_stage<S>(
+    storage: &S,
+    request_id: u64,
+    stage: RequestStage,
+    reached_at: OffsetDateTime,
+) where
+    S: DbRead + DbWrite,
+{
+    let result = async {
+        let is_new = storage
+            .write_withdrawal_request_stage(request_id, stage, reached_at)
+            .await?;
+        if is_new {
+            let stages = storage.get_withdrawal_request_stages(request_id).await?;
+            record_stage_durations(RequestKind::Withdrawal, stage, reached_at, &stages);
+        }
+        Ok::<_, Error>(())
+    };
+
+    if let Err(error) = result.await {
+        tracing::warn!(
+            %error,
+            %request_id,
+            %stage,
+            "could not record the stage of a withdrawal request"
+        );
+    }
+}
+
+/// Set the backlog gauges to the number of requests whose latest stage is
+/// each of the non-terminal stages, counting only the requests that
+/// reached a stage within the [`BACKLOG_WINDOW`].
+pub async fn update_backlog_gauges<S>(storage: &S)
+where
+    S: DbRead,
+{
+    let since = OffsetDateTime::now_utc() - BACKLOG_WINDOW;
+    let backlog = match storage.get_request_stage_backlog(since).await {
+        Ok(backlog) => backlog,
+        Err(error) => {
+            tracing::warn!(%error, "could not fetch the request backlog");
+            return;
+        }
+    };
+
+    for kind in [RequestKind::Deposit, RequestKind::Withdrawal] {
+        let pending_stages = RequestStage::iter().filter(|s| !s.is_terminal());
+        for stage in pending_stages {
+            // We set the gauge for stages without any requests too,
+            // otherwise they would keep the value from the last update.
+            let count = backlog
+                .iter()
+                .find(|item| item.kind == kind && item.stage == stage)
+                .map(|item| item.count)
+                .unwrap_or(0);
+
+            let kind: &'static str = kind.into();
+            let stage: &'static str = stage.into();
+            metrics::gauge!(Metrics::RequestBacklog, "kind" => kind, "stage" => stage)
+                .set(count as f64);
+        }
+    }
+}
+
+/// Record the time it took to reach the given stage from each of the
+/// earlier stages in `stages`.
+fn record_stage_durations(
+    kind: RequestKind,
+    stage: RequestStage,
+    reached_at: OffsetDateTime,
+    stages: &[RequestStageTimestamp],
+) {
+    for (from, seconds) in stage_durations(stage, reached_at, stages) {
+        let kind: &'static str = kind.into();
+        let from: &'static str = from.into();
+        let to: &'static str = stage.into();
+        metrics::histogram!(
+            Metrics::RequestStageDurationSeconds,
+            "kind" => kind,
+            "from" => from,
+            "to" => to,
+        )
+        .record(seconds);
+    }
+}
+
+/// Return the number of seconds between each of the stages in `stages`
+/// that come before the given stage and the time that the given stage
+/// was reached.
+///
+/// Stages can be observed out of order, say when the signer was offline
+/// for a while, so durations that would be negative are left out.
+fn stage_durations(
+    stage: RequestStage,
+    reached_at: OffsetDateTime,
+    stages: &[RequestStageTimestamp],
+) -> Vec<(RequestStage, f64)> {
+    stages
+        .iter()
+        .filter(|earlier| earlier.stage < stage)
+        .filter(|earlier| earlier.reached_at <= reached_at)
+        .map(|earlier| {
+            let duration = reached_at - earlier.reached_at;
+            (earlier.stage, duration.as_seconds_f64())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use fake::Fake as _;
+    use time::Duration;
+
+    use crate::storage::in_memory::Store;
+    use crate::storage::model::RequestStageBacklog;
+
+    use super::*;
+
+    #[test]
+    fn stage_durations_only_include_earlier_stages() {
+        let now = OffsetDateTime::now_utc();
+        let stages = [
+            RequestStageTimestamp {
+                stage: RequestStage::FirstSeen,
+                reached_at: now - Duration::seconds(600),
+            },
+            RequestStageTimestamp {
+                stage: RequestStage::Decided,
+                reached_at: now - Duration::seconds(590),
+            },
+            // Observed before the stage that normally comes before it, so
+            // there is no sensible duration to record.
+            RequestStageTimestamp {
+                stage: RequestStage::SweepSigned,
+                reached_at: now + Duration::seconds(5),
+            },
+            RequestStageTimestamp {
+                stage: RequestStage::SweepConfirmed,
+                reached_at: now,
+            },
+        ];
+
+        let durations = stage_durations(RequestStage::SweepConfirmed, now, &stages);
+        assert_eq!(
+            durations,
+            vec![
+                (RequestStage::FirstSeen, 600.0),
+                (RequestStage::Decided, 590.0)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stages_are_recorded_once_and_counted_in_the_backlog() {
+        let store = Store::new_shared();
+        let txid: BitcoinTxId = fake::Faker.fake();
+        let old_txid: BitcoinTxId = fake::Faker.fake();
+        let now = OffsetDateTime::now_utc();
+        let long_ago = now - BACKLOG_WINDOW - Duration::days(1);
+
+        record_deposit_stage(&store, &txid, 1, RequestStage::FirstSeen, now).await;
+        record_deposit_stage(&store, &txid, 1, RequestStage::Decided, now).await;
+        record_deposit_stage(&store, &txid, 2, RequestStage::FirstSeen, now).await;
+        record_deposit_stage(&store, &old_txid, 0, RequestStage::Decided, long_ago).await;
+        record_withdrawal_stage(&store, 7, RequestStage::FirstSeen, now).await;
+        record_withdrawal_stage(&store, 7, RequestStage::Completed, now).await;
+        record_withdrawal_stage(&store, 8, RequestStage::FirstSeen, now).await;
+        record_withdrawal_stage(&store, 8, RequestStage::Rejected, now).await;
+
+        let stages = store.get_deposit_request_stages(&txid, 1).await.unwrap();
+        let first_seen_at = stages[0].reached_at;
+        assert_eq!(stages.len(), 2);
+
+        // Recording a stage a second time keeps the original timestamp.
+        let later = now + Duration::seconds(10);
+        record_deposit_stage(&store, &txid, 1, RequestStage::FirstSeen, later).await;
+        let stages = store.get_deposit_request_stages(&txid, 1).await.unwrap();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].stage, RequestStage::FirstSeen);
+        assert_eq!(stages[0].reached_at, first_seen_at);
+        assert_eq!(stages[1].stage, RequestStage::Decided);
+
+        // The completed and rejected withdrawals are not part of the
+        // backlog, and neither is the deposit that was last seen before
+        // the cutoff.
+        let since = now - BACKLOG_WINDOW;
+        let backlog = store.get_request_stage_backlog(since).await.unwrap();
+        let expected = vec![
+            RequestStageBacklog {
+                kind: RequestKind::Deposit,
+                stage: RequestStage::FirstSeen,
+                count: 1,
+            },
+            RequestStageBacklog {
+                kind: RequestKind::Deposit,
+                stage: RequestStage::Decided,
+                count: 1,
+            },
+        ];
+        assert_eq!(backlog, expected);
+    }
+}
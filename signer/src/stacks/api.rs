@@ -27,7 +27,7 @@ use blockstack_lib::net::api::postfeerate::RPCFeeEstimateResponse;
 use blockstack_lib::types::chainstate::StacksAddress;
 use blockstack_lib::types::chainstate::StacksBlockId;
 use clarity::types::StacksEpochId;
-use clarity::vm::types::{BuffData, ListData, SequenceData};
+use clarity::vm::types::{BuffData, ListData, OptionalData, SequenceData};
 use clarity::vm::{ClarityName, ContractName, Value};
 use reqwest::header::CONTENT_LENGTH;
 use reqwest::header::CONTENT_TYPE;
@@ -43,6 +43,7 @@ use crate::storage::DbRead;
 use crate::util::ApiFallbackClient;
 
 use super::contracts::AsTxPayload;
+use super::contracts::ProtocolRole;
 use super::contracts::SmartContract;
 use super::wallet::SignerWallet;
 
@@ -53,7 +54,7 @@ const TX_FEE_TX_SIZE_MULTIPLIER: u64 = 2 * MINIMUM_TX_FEE_RATE_PER_BYTE;
 
 /// The max fee in microSTX for a stacks transaction. Used as a backstop in
 /// case the stacks node returns wonky values. This is 10 STX.
-const MAX_TX_FEE: u64 = 10_000_000;
+pub const MAX_TX_FEE: u64 = 10_000_000;
 
 /// This is a dummy STX transfer payload used only for estimating STX
 /// transfer costs.
@@ -222,6 +223,18 @@ pub trait StacksInteract: Send + Sync {
         &self,
         sender: &StacksAddress,
     ) -> impl Future<Output = Result<Amount, Error>> + Send;
+
+    /// Get the principal of the contract that the `sbtc-registry` smart
+    /// contract considers active for the given protocol role.
+    ///
+    /// This is done by calling the `get-active-protocol` read-only
+    /// function, which returns `none` if no contract has been registered
+    /// for the role.
+    fn get_active_protocol_contract(
+        &self,
+        deployer: &StacksAddress,
+        role: ProtocolRole,
+    ) -> impl Future<Output = Result<Option<PrincipalData>, Error>> + Send;
 }
 
 /// A trait for getting the start height of the first EPOCH 3.0 block on the
@@ -494,6 +507,7 @@ impl StacksClient {
         contract_name: &ContractName,
         fn_name: &ClarityName,
         sender: &StacksAddress,
+        arguments: &[Value],
     ) -> Result<Value, Error> {
         let path = format!(
             "/v2/contracts/call-read/{}/{}/{}?tip=latest",
@@ -505,9 +519,15 @@ impl StacksClient {
             .join(&path)
             .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
 
+        let arguments = arguments
+            .iter()
+            .map(Value::serialize_to_hex)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::ClaritySerialization)?;
+
         let body = CallReadRequest {
             sender: sender.to_string(),
-            arguments: vec![], // TODO: Add when needed
+            arguments,
         };
 
         tracing::debug!(
@@ -1059,7 +1079,7 @@ where
 
 /// A deserializer for Clarity's [`Value`] type that deserializes a hex-encoded
 /// string which was serialized using Clarity's consensus serialization format.
-fn clarity_value_deserializer<'de, D>(deserializer: D) -> Result<Value, D::Error>
+pub(crate) fn clarity_value_deserializer<'de, D>(deserializer: D) -> Result<Value, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -1286,6 +1306,7 @@ impl StacksInteract for StacksClient {
                 &ContractName::from(SmartContract::SbtcToken.contract_name()),
                 &ClarityName::from("get-total-supply"),
                 deployer,
+                &[],
             )
             .await?;
 
@@ -1304,6 +1325,35 @@ impl StacksInteract for StacksClient {
             )),
         }
     }
+
+    async fn get_active_protocol_contract(
+        &self,
+        deployer: &StacksAddress,
+        role: ProtocolRole,
+    ) -> Result<Option<PrincipalData>, Error> {
+        let result = self
+            .call_read(
+                deployer,
+                &ContractName::from(SmartContract::SbtcRegistry.contract_name()),
+                &ClarityName::from("get-active-protocol"),
+                deployer,
+                &[Value::buff_from_byte(role.flag())],
+            )
+            .await?;
+
+        match result {
+            Value::Optional(OptionalData { data: None }) => Ok(None),
+            Value::Optional(OptionalData { data: Some(value) }) => match *value {
+                Value::Principal(principal) => Ok(Some(principal)),
+                _ => Err(Error::InvalidStacksResponse(
+                    "expected a principal but got something else",
+                )),
+            },
+            _ => Err(Error::InvalidStacksResponse(
+                "expected an optional but got something else",
+            )),
+        }
+    }
 }
 
 impl StacksInteract for ApiFallbackClient<StacksClient> {
@@ -1402,6 +1452,19 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         self.exec(|client, _| client.get_sbtc_total_supply(deployer))
             .await
     }
+
+    async fn get_active_protocol_contract(
+        &self,
+        deployer: &StacksAddress,
+        role: ProtocolRole,
+    ) -> Result<Option<PrincipalData>, Error> {
+        self.exec(|client, retry| async move {
+            let result = client.get_active_protocol_contract(deployer, role).await;
+            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
+            result
+        })
+        .await
+    }
 }
 
 impl TryFrom<&Settings> for ApiFallbackClient<StacksClient> {
@@ -1629,6 +1692,41 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn get_active_protocol_contract_works() {
+        let deployer =
+            StacksAddress::from_string("SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS").unwrap();
+        let contract =
+            PrincipalData::parse("SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-deposit-v2")
+                .unwrap();
+        let result = Value::some(Value::Principal(contract.clone())).unwrap();
+        let raw_json_response = format!(
+            r#"{{"okay": true, "result": "0x{}"}}"#,
+            Value::serialize_to_hex(&result).expect("failed to serialize value")
+        );
+
+        let mut stacks_node_server = mockito::Server::new_async().await;
+        let mock = stacks_node_server
+            .mock("POST", "/v2/contracts/call-read/SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS/sbtc-registry/get-active-protocol?tip=latest")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "arguments": [Value::serialize_to_hex(&Value::buff_from_byte(0x01)).unwrap()]
+            })))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(raw_json_response)
+            .expect(1)
+            .create();
+
+        let client = StacksClient::new(stacks_node_server.url().parse().unwrap()).unwrap();
+        let result = client
+            .get_active_protocol_contract(&deployer, ProtocolRole::Deposit)
+            .await
+            .unwrap();
+
+        assert_eq!(result, Some(contract));
+        mock.assert();
+    }
+
     #[test_case(|url| StacksClient::new(url).unwrap(); "stacks-client")]
     #[test_case(|url| ApiFallbackClient::new(vec![StacksClient::new(url).unwrap()]).unwrap(); "fallback-client")]
     #[tokio::test]
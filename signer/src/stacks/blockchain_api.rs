@@ -0,0 +1,393 @@
+//! A client for the parts of the Stacks blockchain API that we use to
+//! recover sbtc-registry events that the stacks node never delivered to
+//! our event observer.
+//!
+//! The Stacks blockchain API is the indexer that sits in front of a
+//! stacks node, which is documented at <https://docs.hiro.so/stacks/api>.
+//! Unlike the stacks node, it keeps the events of every block around, so
+//! it is the one source that can tell us what a block printed after the
+//! fact.
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+use blockstack_lib::burnchains::Txid;
+use blockstack_lib::types::chainstate::StacksBlockId;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::Value;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde::Deserializer;
+use time::OffsetDateTime;
+use url::Url;
+
+use crate::error::Error;
+
+use super::api::clarity_value_deserializer;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of items to ask for in each page of a paginated response.
+/// This is the largest limit that every endpoint used here accepts.
+const PAGE_LIMIT: usize = 50;
+
+/// The status of a transaction that was successfully executed.
+const TX_STATUS_SUCCESS: &str = "success";
+
+/// A transaction in the response of a
+/// GET /extended/v2/blocks/<block-id>/transactions request.
+///
+/// Only the fields that we use are deserialized.
+#[derive(Debug, Deserialize)]
+struct BlockTransaction {
+    #[serde(deserialize_with = "txid_deserializer")]
+    tx_id: Txid,
+    tx_index: u32,
+    tx_status: String,
+    event_count: u32,
+    /// The time of the block that includes the transaction, in seconds
+    /// since the unix epoch.
+    block_time: i64,
+}
+
+/// The response of a GET /extended/v2/blocks/<block-id>/transactions
+/// request.
+#[derive(Debug, Deserialize)]
+struct BlockTransactionsResponse {
+    total: usize,
+    results: Vec<BlockTransaction>,
+}
+
+/// A print event emitted by a smart contract.
+#[derive(Debug, Deserialize)]
+struct ContractLog {
+    contract_id: String,
+    topic: String,
+    value: ContractLogValue,
+}
+
+/// The value of a print event, of which we only need the consensus
+/// serialization.
+#[derive(Debug, Deserialize)]
+struct ContractLogValue {
+    #[serde(deserialize_with = "clarity_value_deserializer")]
+    hex: Value,
+}
+
+/// An event in the response of a GET /extended/v1/tx/events request,
+/// filtered to smart contract logs.
+#[derive(Debug, Deserialize)]
+struct TransactionEvent {
+    event_index: u32,
+    contract_log: ContractLog,
+}
+
+/// The response of a GET /extended/v1/tx/events request.
+#[derive(Debug, Deserialize)]
+struct TransactionEventsResponse {
+    events: Vec<TransactionEvent>,
+}
+
+/// A print event emitted by a smart contract, along with the transaction
+/// that emitted it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractPrintEvent {
+    /// The ID of the transaction that emitted the event.
+    pub txid: Txid,
+    /// The time of the block that includes the transaction.
+    pub block_time: OffsetDateTime,
+    /// The value that was printed.
+    pub value: Value,
+}
+
+/// A client for a Stacks blockchain API.
+#[derive(Debug, Clone)]
+pub struct BlockchainApiClient {
+    /// The base url of the Stacks blockchain API.
+    pub endpoint: Url,
+    /// The client used to make the request.
+    pub client: reqwest::Client,
+}
+
+impl BlockchainApiClient {
+    /// Create a new client for the Stacks blockchain API at the given url.
+    pub fn new(url: Url) -> Result<Self, Error> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+
+        Ok(Self { endpoint: url, client })
+    }
+
+    /// Get the print events that the given contract emitted in the block
+    /// with the given ID, along with the ID of the transaction that
+    /// emitted each event and the time of the block. The events are
+    /// returned in the order that they were emitted.
+    ///
+    /// `None` is returned if the API does not know about the block, which
+    /// is usually because it has not indexed the block yet.
+    #[tracing::instrument(skip_all, fields(block_id = %block_id))]
+    pub async fn get_contract_print_events(
+        &self,
+        block_id: &StacksBlockId,
+        contract_id: &QualifiedContractIdentifier,
+    ) -> Result<Option<Vec<ContractPrintEvent>>, Error> {
+        let Some(mut transactions) = self.get_block_transactions(block_id).await? else {
+            return Ok(None);
+        };
+
+        // Only successful transactions emit print events, and they are
+        // the last thing a contract call does.
+        transactions.retain(|tx| tx.tx_status == TX_STATUS_SUCCESS && tx.event_count > 0);
+        transactions.sort_by_key(|tx| tx.tx_index);
+
+        let contract_id = contract_id.to_string();
+        let mut print_events = Vec::new();
+        for tx in transactions {
+            let block_time = OffsetDateTime::from_unix_timestamp(tx.block_time)
+                .map_err(Error::InvalidTimestamp)?;
+            let mut events = self.get_transaction_logs(&tx.tx_id).await?;
+            events.sort_by_key(|event| event.event_index);
+
+            let values = events
+                .into_iter()
+                .map(|event| event.contract_log)
+                .filter(|log| log.contract_id == contract_id && log.topic == "print")
+                .map(|log| ContractPrintEvent {
+                    txid: tx.tx_id,
+                    block_time,
+                    value: log.value.hex,
+                });
+            print_events.extend(values);
+        }
+
+        Ok(Some(print_events))
+    }
+
+    /// Fetch all transactions in the block with the given ID, page by page.
+    async fn get_block_transactions(
+        &self,
+        block_id: &StacksBlockId,
+    ) -> Result<Option<Vec<BlockTransaction>>, Error> {
+        let mut transactions = Vec::new();
+        loop {
+            let path = format!(
+                "/extended/v2/blocks/0x{}/transactions?limit={PAGE_LIMIT}&offset={}",
+                block_id.to_hex(),
+                transactions.len(),
+            );
+            let Some(response) = self.get::<BlockTransactionsResponse>(path).await? else {
+                return Ok(None);
+            };
+
+            let page_len = response.results.len();
+            transactions.extend(response.results);
+            if page_len == 0 || transactions.len() >= response.total {
+                return Ok(Some(transactions));
+            }
+        }
+    }
+
+    /// Fetch all smart contract logs emitted by the transaction with the
+    /// given ID, page by page.
+    async fn get_transaction_logs(&self, txid: &Txid) -> Result<Vec<TransactionEvent>, Error> {
+        let mut events = Vec::new();
+        loop {
+            let path = format!(
+                "/extended/v1/tx/events?tx_id=0x{}&type=smart_contract_log&limit={PAGE_LIMIT}&offset={}",
+                txid.to_hex(),
+                events.len(),
+            );
+            let response = self.get::<TransactionEventsResponse>(path).await?.ok_or(
+                Error::InvalidStacksBlockchainApiResponse(
+                    "the events of a transaction in a known block were not found",
+                ),
+            )?;
+
+            let page_len = response.events.len();
+            events.extend(response.events);
+            if page_len < PAGE_LIMIT {
+                return Ok(events);
+            }
+        }
+    }
+
+    /// Make a GET request to the given path, returning `None` if the
+    /// resource was not found.
+    async fn get<T>(&self, path: String) -> Result<Option<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = self
+            .endpoint
+            .join(&path)
+            .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
+
+        let response = self
+            .client
+            .get(url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(Error::StacksBlockchainApiRequest)?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        response
+            .error_for_status()
+            .map_err(Error::StacksBlockchainApiResponse)?
+            .json()
+            .await
+            .map(Some)
+            .map_err(Error::UnexpectedStacksBlockchainApiResponse)
+    }
+}
+
+/// Deserialize a 0x-prefixed hex encoded transaction ID.
+fn txid_deserializer<'de, D>(deserializer: D) -> Result<Txid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hex = String::deserialize(deserializer)?;
+    Txid::from_hex(hex.trim_start_matches("0x")).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::vm::types::PrincipalData;
+    use clarity::vm::types::StandardPrincipalData;
+
+    use super::*;
+
+    const REGISTRY: &str = "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry";
+
+    const BLOCK_TIME: i64 = 1_730_000_000;
+
+    #[tokio::test]
+    async fn print_events_of_the_contract_are_returned_in_order() {
+        let mut server = mockito::Server::new_async().await;
+        let block_id = StacksBlockId([1; 32]);
+        let txid1 = format!("0x{}", "aa".repeat(32));
+        let txid2 = format!("0x{}", "bb".repeat(32));
+        let txid3 = format!("0x{}", "cc".repeat(32));
+
+        let transactions = serde_json::json!({
+            "limit": PAGE_LIMIT,
+            "offset": 0,
+            "total": 3,
+            "results": [
+                {"tx_id": txid2, "tx_index": 2, "tx_status": "success", "event_count": 2, "block_time": BLOCK_TIME},
+                {"tx_id": txid1, "tx_index": 1, "tx_status": "success", "event_count": 1, "block_time": BLOCK_TIME},
+                {"tx_id": txid3, "tx_index": 3, "tx_status": "abort_by_response", "event_count": 0, "block_time": BLOCK_TIME},
+            ],
+        });
+        let block_mock = server
+            .mock(
+                "GET",
+                format!("/extended/v2/blocks/0x{}/transactions", block_id.to_hex()).as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(transactions.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let log = |index: u32, contract_id: &str, value: Value| {
+            serde_json::json!({
+                "event_index": index,
+                "event_type": "smart_contract_log",
+                "contract_log": {
+                    "contract_id": contract_id,
+                    "topic": "print",
+                    "value": {"hex": value.serialize_to_hex().unwrap(), "repr": ""},
+                },
+            })
+        };
+        let other_contract = "SN2V7WTJ7BHR03MPHZ1C9A9ZR6NZGR4WM8HT4V67Y.sbtc-registry";
+        let tx1_events = serde_json::json!({
+            "limit": PAGE_LIMIT,
+            "offset": 0,
+            "events": [log(0, REGISTRY, Value::UInt(1))],
+        });
+        let tx2_events = serde_json::json!({
+            "limit": PAGE_LIMIT,
+            "offset": 0,
+            "events": [
+                log(1, REGISTRY, Value::UInt(3)),
+                log(0, REGISTRY, Value::UInt(2)),
+                log(2, other_contract, Value::UInt(4)),
+            ],
+        });
+        let mut event_mocks = Vec::new();
+        for (txid, body) in [(&txid1, tx1_events), (&txid2, tx2_events)] {
+            let mock = server
+                .mock("GET", "/extended/v1/tx/events")
+                .match_query(mockito::Matcher::UrlEncoded(
+                    "tx_id".to_string(),
+                    txid.to_string(),
+                ))
+                .with_status(200)
+                .with_body(body.to_string())
+                .expect(1)
+                .create_async()
+                .await;
+            event_mocks.push(mock);
+        }
+
+        let client = BlockchainApiClient::new(server.url().parse().unwrap()).unwrap();
+        let PrincipalData::Contract(contract_id) = PrincipalData::parse(REGISTRY).unwrap() else {
+            panic!("expected a contract principal");
+        };
+        let events = client
+            .get_contract_print_events(&block_id, &contract_id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let values: Vec<_> = events.iter().map(|event| event.value.clone()).collect();
+        assert_eq!(values, [Value::UInt(1), Value::UInt(2), Value::UInt(3)]);
+        assert_eq!(events[0].txid, Txid([0xaa; 32]));
+        assert_eq!(events[2].txid, Txid([0xbb; 32]));
+
+        let block_time = OffsetDateTime::from_unix_timestamp(BLOCK_TIME).unwrap();
+        assert!(events.iter().all(|event| event.block_time == block_time));
+
+        block_mock.assert_async().await;
+        for mock in event_mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_blocks_are_none() {
+        let mut server = mockito::Server::new_async().await;
+        let block_id = StacksBlockId([2; 32]);
+        let mock = server
+            .mock(
+                "GET",
+                format!("/extended/v2/blocks/0x{}/transactions", block_id.to_hex()).as_str(),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_body(r#"{"error":"Block not found"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainApiClient::new(server.url().parse().unwrap()).unwrap();
+        let contract_id = QualifiedContractIdentifier::new(
+            StandardPrincipalData::transient(),
+            "sbtc-registry".into(),
+        );
+        let events = client
+            .get_contract_print_events(&block_id, &contract_id)
+            .await
+            .unwrap();
+
+        assert!(events.is_none());
+        mock.assert_async().await;
+    }
+}
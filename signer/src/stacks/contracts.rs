@@ -15,6 +15,15 @@
 //! * [`RotateKeysV1`]: Used for calling the rotate-keys-wrapper function
 //!   in the sbtc-bootstrap-signers contract. This changes the valid caller
 //!   of most sBTC related functions to a new multi-sig wallet.
+//! * [`UpdateProtocolContractV1`]: Used for calling the
+//!   update-protocol-contract-wrapper function in the
+//!   sbtc-bootstrap-signers contract. This makes a newly deployed version
+//!   of a protocol contract the active one in the sbtc-registry.
+//!
+//! The deposit and withdrawal calls also have `V2` counterparts that make
+//! the same calls against the `-v2` versions of those contracts. Which
+//! version is live is tracked by the sbtc-registry, see
+//! [`ActiveContracts`].
 
 use std::collections::BTreeSet;
 use std::future::Future;
@@ -35,7 +44,9 @@ use blockstack_lib::clarity::vm::types::BuffData;
 use blockstack_lib::clarity::vm::types::ListData;
 use blockstack_lib::clarity::vm::types::ListTypeData;
 use blockstack_lib::clarity::vm::types::PrincipalData;
+use blockstack_lib::clarity::vm::types::QualifiedContractIdentifier;
 use blockstack_lib::clarity::vm::types::SequenceData;
+use blockstack_lib::clarity::vm::types::StandardPrincipalData;
 use blockstack_lib::clarity::vm::types::BUFF_33;
 use blockstack_lib::clarity::vm::ClarityName;
 use blockstack_lib::clarity::vm::ContractName;
@@ -52,6 +63,7 @@ use crate::stacks::wallet::SignerWallet;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinBlockRef;
 use crate::storage::model::BitcoinTxId;
+use crate::storage::model::DkgSharesStatus;
 use crate::storage::model::ToLittleEndianOrder as _;
 use crate::storage::DbRead;
 use crate::DEPOSIT_DUST_LIMIT;
@@ -245,6 +257,18 @@ pub enum ContractCall {
     /// Call the `rotate-keys-wrapper` function in the
     /// `sbtc-bootstrap-signers` smart contract.
     RotateKeysV1(RotateKeysV1),
+    /// Call the `complete-deposit-wrapper` function in the
+    /// `sbtc-deposit-v2` smart contract
+    CompleteDepositV2(CompleteDepositV2),
+    /// Call the `accept-withdrawal-request` function in the
+    /// `sbtc-withdrawal-v2` smart contract.
+    AcceptWithdrawalV2(AcceptWithdrawalV2),
+    /// Call the `reject-withdrawal-request` function in the
+    /// `sbtc-withdrawal-v2` smart contract.
+    RejectWithdrawalV2(RejectWithdrawalV2),
+    /// Call the `update-protocol-contract-wrapper` function in the
+    /// `sbtc-bootstrap-signers` smart contract.
+    UpdateProtocolContractV1(UpdateProtocolContractV1),
 }
 
 impl AsTxPayload for ContractCall {
@@ -254,6 +278,10 @@ impl AsTxPayload for ContractCall {
             ContractCall::CompleteDepositV1(contract) => contract.tx_payload(),
             ContractCall::RejectWithdrawalV1(contract) => contract.tx_payload(),
             ContractCall::RotateKeysV1(contract) => contract.tx_payload(),
+            ContractCall::CompleteDepositV2(contract) => contract.tx_payload(),
+            ContractCall::AcceptWithdrawalV2(contract) => contract.tx_payload(),
+            ContractCall::RejectWithdrawalV2(contract) => contract.tx_payload(),
+            ContractCall::UpdateProtocolContractV1(contract) => contract.tx_payload(),
         }
     }
     fn post_conditions(&self) -> StacksTxPostConditions {
@@ -262,6 +290,72 @@ impl AsTxPayload for ContractCall {
             ContractCall::CompleteDepositV1(contract) => AsContractCall::post_conditions(contract),
             ContractCall::RejectWithdrawalV1(contract) => AsContractCall::post_conditions(contract),
             ContractCall::RotateKeysV1(contract) => AsContractCall::post_conditions(contract),
+            ContractCall::CompleteDepositV2(contract) => AsContractCall::post_conditions(contract),
+            ContractCall::AcceptWithdrawalV2(contract) => AsContractCall::post_conditions(contract),
+            ContractCall::RejectWithdrawalV2(contract) => AsContractCall::post_conditions(contract),
+            ContractCall::UpdateProtocolContractV1(contract) => {
+                AsContractCall::post_conditions(contract)
+            }
+        }
+    }
+}
+
+impl ContractCall {
+    /// Create a complete-deposit contract call against the given version
+    /// of the deposit contract.
+    pub fn complete_deposit(call: CompleteDepositV1, version: ContractVersion) -> Self {
+        match version {
+            ContractVersion::V1 => ContractCall::CompleteDepositV1(call),
+            ContractVersion::V2 => ContractCall::CompleteDepositV2(CompleteDepositV2(call)),
+        }
+    }
+
+    /// Create an accept-withdrawal contract call against the given version
+    /// of the withdrawal contract.
+    pub fn accept_withdrawal(call: AcceptWithdrawalV1, version: ContractVersion) -> Self {
+        match version {
+            ContractVersion::V1 => ContractCall::AcceptWithdrawalV1(call),
+            ContractVersion::V2 => ContractCall::AcceptWithdrawalV2(AcceptWithdrawalV2(call)),
+        }
+    }
+
+    /// Create a reject-withdrawal contract call against the given version
+    /// of the withdrawal contract.
+    pub fn reject_withdrawal(call: RejectWithdrawalV1, version: ContractVersion) -> Self {
+        match version {
+            ContractVersion::V1 => ContractCall::RejectWithdrawalV1(call),
+            ContractVersion::V2 => ContractCall::RejectWithdrawalV2(RejectWithdrawalV2(call)),
+        }
+    }
+
+    /// The role of the protocol contract that this call is made against.
+    pub const fn protocol_role(&self) -> ProtocolRole {
+        match self {
+            ContractCall::CompleteDepositV1(_) | ContractCall::CompleteDepositV2(_) => {
+                ProtocolRole::Deposit
+            }
+            ContractCall::AcceptWithdrawalV1(_)
+            | ContractCall::RejectWithdrawalV1(_)
+            | ContractCall::AcceptWithdrawalV2(_)
+            | ContractCall::RejectWithdrawalV2(_) => ProtocolRole::Withdrawal,
+            ContractCall::RotateKeysV1(_) | ContractCall::UpdateProtocolContractV1(_) => {
+                ProtocolRole::Governance
+            }
+        }
+    }
+
+    /// The version of the protocol contract that this call is made
+    /// against.
+    pub const fn contract_version(&self) -> ContractVersion {
+        match self {
+            ContractCall::CompleteDepositV1(_)
+            | ContractCall::AcceptWithdrawalV1(_)
+            | ContractCall::RejectWithdrawalV1(_)
+            | ContractCall::RotateKeysV1(_)
+            | ContractCall::UpdateProtocolContractV1(_) => ContractVersion::V1,
+            ContractCall::CompleteDepositV2(_)
+            | ContractCall::AcceptWithdrawalV2(_)
+            | ContractCall::RejectWithdrawalV2(_) => ContractVersion::V2,
         }
     }
 }
@@ -1101,13 +1195,14 @@ impl AsContractCall for RotateKeysV1 {
     /// criteria:
     ///
     /// 1. That the smart contract deployer matches the deployer in our context.
-    /// 2. That the signing set matches the signing set for the most recent
+    /// 2. That the most recent DKG shares have passed verification.
+    /// 3. That the signing set matches the signing set for the most recent
     ///    DKG run.
-    /// 3. That the aggregate key matches the one that was output as part of
+    /// 4. That the aggregate key matches the one that was output as part of
     ///    the most recent DKG.
-    /// 4. That the signature threshold matches the one that was used in the
+    /// 5. That the signature threshold matches the one that was used in the
     ///    most recent DKG.
-    /// 5. That there are no other rotate-keys contract calls with these same
+    /// 6. That there are no other rotate-keys contract calls with these same
     ///    details already confirmed on the canonical Stacks blockchain.
     async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
     where
@@ -1120,11 +1215,16 @@ impl AsContractCall for RotateKeysV1 {
             return Err(RotateKeysErrorMsg::DeployerMismatch.into_error(req_ctx, self));
         }
 
-        // 2. That the signing set matches the signing set for the most recent
-        //    DKG run.
+        // 2. That the most recent DKG shares have passed verification.
         let Some(latest_dkg) = db.get_latest_encrypted_dkg_shares().await? else {
             return Err(Error::NoDkgShares);
         };
+        if latest_dkg.dkg_shares_status != DkgSharesStatus::Verified {
+            return Err(RotateKeysErrorMsg::DkgSharesNotVerified.into_error(req_ctx, self));
+        }
+
+        // 3. That the signing set matches the signing set for the most recent
+        //    DKG run.
         let latest_public_key = latest_dkg
             .signer_set_public_keys
             .into_iter()
@@ -1133,19 +1233,19 @@ impl AsContractCall for RotateKeysV1 {
             return Err(RotateKeysErrorMsg::SignerSetMismatch.into_error(req_ctx, self));
         }
 
-        // 3. That the aggregate key matches the one that was output as part of
+        // 4. That the aggregate key matches the one that was output as part of
         //    the most recent DKG.
         if self.aggregate_key != latest_dkg.aggregate_key {
             return Err(RotateKeysErrorMsg::AggregateKeyMismatch.into_error(req_ctx, self));
         }
 
-        // 4. That the signature threshold matches the one that was used in the
+        // 5. That the signature threshold matches the one that was used in the
         //    most recent DKG.
         if self.signatures_required != latest_dkg.signature_share_threshold {
             return Err(RotateKeysErrorMsg::SignaturesRequiredMismatch.into_error(req_ctx, self));
         }
 
-        // 5. That there are no other rotate-keys contract calls with these same
+        // 6. That there are no other rotate-keys contract calls with these same
         //    details already confirmed on the canonical Stacks blockchain.
         let key_rotation_exists_fut = db.key_rotation_exists(
             &req_ctx.chain_tip.block_hash,
@@ -1195,6 +1295,9 @@ pub enum RotateKeysErrorMsg {
     /// The smart contract deployer is fixed, so this should always match.
     #[error("The deployer in the transaction does not match the expected deployer")]
     DeployerMismatch,
+    /// The latest DKG shares have not been verified by the signers.
+    #[error("the latest DKG shares have not been verified")]
+    DkgSharesNotVerified,
     /// The signer set does not match the latest DKG.
     #[error("the signer set does not match the latest DKG")]
     SignerSetMismatch,
@@ -1219,6 +1322,511 @@ impl RotateKeysErrorMsg {
     }
 }
 
+/// This struct is used to generate a properly formatted Stacks transaction
+/// for calling the complete-deposit-wrapper function in the
+/// sbtc-deposit-v2 smart contract. The function signature is unchanged
+/// from the first version, so this wraps a [`CompleteDepositV1`].
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct CompleteDepositV2(pub CompleteDepositV1);
+
+impl Deref for CompleteDepositV2 {
+    type Target = CompleteDepositV1;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsTxPayload for CompleteDepositV2 {
+    fn tx_payload(&self) -> TransactionPayload {
+        TransactionPayload::ContractCall(self.as_contract_call())
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(self)
+    }
+}
+
+impl AsContractCall for CompleteDepositV2 {
+    const CONTRACT_NAME: &'static str = "sbtc-deposit-v2";
+    const FUNCTION_NAME: &'static str = CompleteDepositV1::FUNCTION_NAME;
+
+    fn deployer_address(&self) -> StacksAddress {
+        self.0.deployer_address()
+    }
+    fn as_contract_args(&self) -> Vec<ClarityValue> {
+        self.0.as_contract_args()
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(&self.0)
+    }
+    /// Validates the contract call using the same criteria as
+    /// [`CompleteDepositV1`].
+    async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
+    where
+        C: Context + Send + Sync,
+    {
+        self.0.validate(ctx, req_ctx).await
+    }
+}
+
+/// This struct is used to generate a properly formatted Stacks transaction
+/// for calling the accept-withdrawal-request function in the
+/// sbtc-withdrawal-v2 smart contract. The function signature is unchanged
+/// from the first version, so this wraps an [`AcceptWithdrawalV1`].
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct AcceptWithdrawalV2(pub AcceptWithdrawalV1);
+
+impl Deref for AcceptWithdrawalV2 {
+    type Target = AcceptWithdrawalV1;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsTxPayload for AcceptWithdrawalV2 {
+    fn tx_payload(&self) -> TransactionPayload {
+        TransactionPayload::ContractCall(self.as_contract_call())
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(self)
+    }
+}
+
+impl AsContractCall for AcceptWithdrawalV2 {
+    const CONTRACT_NAME: &'static str = "sbtc-withdrawal-v2";
+    const FUNCTION_NAME: &'static str = AcceptWithdrawalV1::FUNCTION_NAME;
+
+    fn deployer_address(&self) -> StacksAddress {
+        self.0.deployer_address()
+    }
+    fn as_contract_args(&self) -> Vec<ClarityValue> {
+        self.0.as_contract_args()
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(&self.0)
+    }
+    /// Validates the contract call using the same criteria as
+    /// [`AcceptWithdrawalV1`].
+    async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
+    where
+        C: Context + Send + Sync,
+    {
+        self.0.validate(ctx, req_ctx).await
+    }
+}
+
+/// This struct is used to generate a properly formatted Stacks transaction
+/// for calling the reject-withdrawal-request function in the
+/// sbtc-withdrawal-v2 smart contract. The function signature is unchanged
+/// from the first version, so this wraps a [`RejectWithdrawalV1`].
+#[derive(Copy, Clone, Debug, Hash, PartialEq)]
+pub struct RejectWithdrawalV2(pub RejectWithdrawalV1);
+
+impl Deref for RejectWithdrawalV2 {
+    type Target = RejectWithdrawalV1;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsTxPayload for RejectWithdrawalV2 {
+    fn tx_payload(&self) -> TransactionPayload {
+        TransactionPayload::ContractCall(self.as_contract_call())
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(self)
+    }
+}
+
+impl AsContractCall for RejectWithdrawalV2 {
+    const CONTRACT_NAME: &'static str = "sbtc-withdrawal-v2";
+    const FUNCTION_NAME: &'static str = RejectWithdrawalV1::FUNCTION_NAME;
+
+    fn deployer_address(&self) -> StacksAddress {
+        self.0.deployer_address()
+    }
+    fn as_contract_args(&self) -> Vec<ClarityValue> {
+        self.0.as_contract_args()
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(&self.0)
+    }
+    /// Validates the contract call using the same criteria as
+    /// [`RejectWithdrawalV1`].
+    async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
+    where
+        C: Context + Send + Sync,
+    {
+        self.0.validate(ctx, req_ctx).await
+    }
+}
+
+/// The roles that the sbtc-registry assigns to protocol contracts. Only
+/// the active contract for a role may call the registry functions that
+/// are guarded by that role.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum ProtocolRole {
+    /// The governance role, held by the sbtc-bootstrap-signers contract.
+    Governance,
+    /// The deposit role, held by the sbtc-deposit contract.
+    Deposit,
+    /// The withdrawal role, held by the sbtc-withdrawal contract.
+    Withdrawal,
+}
+
+impl ProtocolRole {
+    /// All roles that the sbtc-registry knows about.
+    pub const ALL: [ProtocolRole; 3] = [
+        ProtocolRole::Governance,
+        ProtocolRole::Deposit,
+        ProtocolRole::Withdrawal,
+    ];
+
+    /// The `(buff 1)` flag that the sbtc-registry uses for this role.
+    pub const fn flag(self) -> u8 {
+        match self {
+            ProtocolRole::Governance => 0x00,
+            ProtocolRole::Deposit => 0x01,
+            ProtocolRole::Withdrawal => 0x02,
+        }
+    }
+
+    /// Return the role for the given sbtc-registry flag, if there is one.
+    pub const fn from_flag(flag: u8) -> Option<Self> {
+        match flag {
+            0x00 => Some(ProtocolRole::Governance),
+            0x01 => Some(ProtocolRole::Deposit),
+            0x02 => Some(ProtocolRole::Withdrawal),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolRole::Governance => write!(f, "governance"),
+            ProtocolRole::Deposit => write!(f, "deposit"),
+            ProtocolRole::Withdrawal => write!(f, "withdrawal"),
+        }
+    }
+}
+
+/// The versions of the protocol contracts that this signer knows how to
+/// call. Each version is deployed by the deployer under its own contract
+/// name, and only becomes live once the governance contract registers it
+/// with the sbtc-registry.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum ContractVersion {
+    /// The protocol contracts that were deployed with the sbtc-registry.
+    #[default]
+    V1,
+    /// The second version of the protocol contracts.
+    V2,
+}
+
+impl ContractVersion {
+    /// The name of the contract that implements the given role at this
+    /// version.
+    pub const fn contract_name(self, role: ProtocolRole) -> &'static str {
+        match (role, self) {
+            (ProtocolRole::Governance, ContractVersion::V1) => "sbtc-bootstrap-signers",
+            (ProtocolRole::Governance, ContractVersion::V2) => "sbtc-bootstrap-signers-v2",
+            (ProtocolRole::Deposit, ContractVersion::V1) => "sbtc-deposit",
+            (ProtocolRole::Deposit, ContractVersion::V2) => "sbtc-deposit-v2",
+            (ProtocolRole::Withdrawal, ContractVersion::V1) => "sbtc-withdrawal",
+            (ProtocolRole::Withdrawal, ContractVersion::V2) => "sbtc-withdrawal-v2",
+        }
+    }
+
+    /// Return the version of the contract with the given name, if it is
+    /// one that this signer knows about for the given role.
+    pub fn from_contract_name(role: ProtocolRole, contract_name: &str) -> Option<Self> {
+        [ContractVersion::V1, ContractVersion::V2]
+            .into_iter()
+            .find(|version| version.contract_name(role) == contract_name)
+    }
+}
+
+impl std::fmt::Display for ContractVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContractVersion::V1 => write!(f, "v1"),
+            ContractVersion::V2 => write!(f, "v2"),
+        }
+    }
+}
+
+/// The versions of the protocol contracts that the sbtc-registry
+/// currently considers active. Contract calls must be made against the
+/// active version for their role, otherwise the registry rejects them.
+///
+/// This is also used in the signer configuration, where it holds the
+/// versions that the signer is willing to activate.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, serde::Deserialize)]
+#[serde(default)]
+pub struct ActiveContracts {
+    /// The active version of the governance contract.
+    pub governance: ContractVersion,
+    /// The active version of the deposit contract.
+    pub deposit: ContractVersion,
+    /// The active version of the withdrawal contract.
+    pub withdrawal: ContractVersion,
+}
+
+impl ActiveContracts {
+    /// The active version of the contract for the given role.
+    pub const fn version(&self, role: ProtocolRole) -> ContractVersion {
+        match role {
+            ProtocolRole::Governance => self.governance,
+            ProtocolRole::Deposit => self.deposit,
+            ProtocolRole::Withdrawal => self.withdrawal,
+        }
+    }
+
+    /// Set the active version of the contract for the given role.
+    pub fn set_version(&mut self, role: ProtocolRole, version: ContractVersion) {
+        match role {
+            ProtocolRole::Governance => self.governance = version,
+            ProtocolRole::Deposit => self.deposit = version,
+            ProtocolRole::Withdrawal => self.withdrawal = version,
+        }
+    }
+
+    /// Identify the version of the given protocol contract principal. The
+    /// contract must have been deployed by the deployer and have a name
+    /// that this signer recognizes for the role.
+    pub fn parse_contract(
+        deployer: &StacksAddress,
+        role: ProtocolRole,
+        contract: &PrincipalData,
+    ) -> Result<ContractVersion, Error> {
+        match contract {
+            PrincipalData::Contract(id) if id.issuer == StandardPrincipalData::from(*deployer) => {
+                ContractVersion::from_contract_name(role, id.name.as_str())
+                    .ok_or_else(|| Error::UnknownProtocolContract(contract.clone()))
+            }
+            _ => Err(Error::UnknownProtocolContract(contract.clone())),
+        }
+    }
+
+    /// Fetch the active protocol contracts from the sbtc-registry. Roles
+    /// without a registered contract are assumed to be at the first
+    /// version.
+    pub async fn load<S>(stacks: &S, deployer: &StacksAddress) -> Result<Self, Error>
+    where
+        S: StacksInteract + Send + Sync,
+    {
+        let mut active = ActiveContracts::default();
+        for role in ProtocolRole::ALL {
+            if let Some(contract) = stacks.get_active_protocol_contract(deployer, role).await? {
+                active.set_version(role, Self::parse_contract(deployer, role, &contract)?);
+            }
+        }
+        Ok(active)
+    }
+}
+
+/// This struct is used to generate a properly formatted Stacks transaction
+/// for calling the update-protocol-contract-wrapper function in the
+/// sbtc-bootstrap-signers smart contract. The transaction makes the given
+/// version of a protocol contract the active one in the sbtc-registry.
+#[derive(Copy, Clone, Debug, Hash, PartialEq)]
+pub struct UpdateProtocolContractV1 {
+    /// The role of the protocol contract being replaced.
+    pub role: ProtocolRole,
+    /// The version of the protocol contract to make active.
+    pub version: ContractVersion,
+    /// The address that deployed the contract.
+    pub deployer: StacksAddress,
+}
+
+impl UpdateProtocolContractV1 {
+    /// The name of the contract that this call makes active.
+    pub const fn contract_name(&self) -> &'static str {
+        self.version.contract_name(self.role)
+    }
+
+    /// Check whether the contract that this call makes active has been
+    /// deployed.
+    pub async fn is_deployed<S>(&self, stacks: &S) -> Result<bool, Error>
+    where
+        S: StacksInteract + Send + Sync,
+    {
+        is_contract_deployed(stacks, &self.deployer, self.contract_name()).await
+    }
+}
+
+impl AsTxPayload for UpdateProtocolContractV1 {
+    fn tx_payload(&self) -> TransactionPayload {
+        TransactionPayload::ContractCall(self.as_contract_call())
+    }
+    fn post_conditions(&self) -> StacksTxPostConditions {
+        AsContractCall::post_conditions(self)
+    }
+}
+
+impl AsContractCall for UpdateProtocolContractV1 {
+    const CONTRACT_NAME: &'static str = "sbtc-bootstrap-signers";
+    const FUNCTION_NAME: &'static str = "update-protocol-contract-wrapper";
+
+    fn deployer_address(&self) -> StacksAddress {
+        self.deployer
+    }
+    /// The arguments to the contract call function
+    ///
+    /// # Notes
+    ///
+    /// The signature to this function is:
+    ///
+    ///   (contract-type (buff 1)) (contract-address principal)
+    fn as_contract_args(&self) -> Vec<ClarityValue> {
+        // The ContractName::from call unwraps under the hood, but the
+        // contract names are all constants that we check in our tests.
+        let contract = QualifiedContractIdentifier::new(
+            self.deployer.into(),
+            ContractName::from(self.contract_name()),
+        );
+        vec![
+            ClarityValue::buff_from_byte(self.role.flag()),
+            ClarityValue::Principal(PrincipalData::Contract(contract)),
+        ]
+    }
+    /// Validates that the update-protocol-contract-wrapper call satisfies
+    /// the following criteria:
+    ///
+    /// 1. That the smart contract deployer matches the deployer in our
+    ///    context.
+    /// 2. That the contract is not the governance contract. This signer
+    ///    only makes governance calls against the first version, so
+    ///    replacing it would lock the signers out.
+    /// 3. That the version is the one that this signer has been configured
+    ///    to run for the role.
+    /// 4. That the version is not already active.
+    /// 5. That the contract has been deployed.
+    async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
+    where
+        C: Context + Send + Sync,
+    {
+        // 1. That the smart contract deployer matches the deployer in our
+        //    context.
+        if self.deployer != req_ctx.deployer {
+            return Err(UpdateProtocolContractErrorMsg::DeployerMismatch.into_error(req_ctx, self));
+        }
+
+        // 2. That the contract is not the governance contract.
+        if self.role == ProtocolRole::Governance {
+            return Err(UpdateProtocolContractErrorMsg::GovernanceRole.into_error(req_ctx, self));
+        }
+
+        // 3. That the version is the one that this signer has been
+        //    configured to run for the role.
+        if ctx.config().signer.contract_versions.version(self.role) != self.version {
+            return Err(
+                UpdateProtocolContractErrorMsg::VersionNotConfigured.into_error(req_ctx, self)
+            );
+        }
+
+        // 4. That the version is not already active.
+        if ctx.state().active_contracts().version(self.role) == self.version {
+            return Err(UpdateProtocolContractErrorMsg::AlreadyActive.into_error(req_ctx, self));
+        }
+
+        // 5. That the contract has been deployed.
+        if !self.is_deployed(&ctx.get_stacks_client()).await? {
+            return Err(UpdateProtocolContractErrorMsg::NotDeployed.into_error(req_ctx, self));
+        }
+
+        Ok(())
+    }
+}
+
+/// A struct for a validation error containing all the necessary context.
+#[derive(Debug)]
+pub struct UpdateProtocolContractValidationError {
+    /// The specific error that happened during validation.
+    pub error: UpdateProtocolContractErrorMsg,
+    /// The additional information that was used when trying to
+    /// validate the update-protocol-contract contract call. This includes
+    /// the public key of the signer that was attempting to generate the
+    /// transaction.
+    pub context: ReqContext,
+    /// The specific transaction that was being validated.
+    pub tx: UpdateProtocolContractV1,
+}
+
+impl std::fmt::Display for UpdateProtocolContractValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (contract: {})", self.error, self.tx.contract_name())
+    }
+}
+
+impl std::error::Error for UpdateProtocolContractValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The responses for validation of an update-protocol-contract smart
+/// contract call transaction.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum UpdateProtocolContractErrorMsg {
+    /// The smart contract deployer is fixed, so this should always match.
+    #[error("The deployer in the transaction does not match the expected deployer")]
+    DeployerMismatch,
+    /// The signers do not replace the governance contract.
+    #[error("replacing the governance contract is not supported")]
+    GovernanceRole,
+    /// This signer has not been configured to run this contract version.
+    #[error("the contract version is not the one this signer is configured to run")]
+    VersionNotConfigured,
+    /// The contract version is already the active one.
+    #[error("the contract version is already active")]
+    AlreadyActive,
+    /// The contract has not been deployed.
+    #[error("the contract has not been deployed")]
+    NotDeployed,
+}
+
+impl UpdateProtocolContractErrorMsg {
+    fn into_error(self, ctx: &ReqContext, tx: &UpdateProtocolContractV1) -> Error {
+        Error::UpdateProtocolContractValidation(Box::new(UpdateProtocolContractValidationError {
+            error: self,
+            context: *ctx,
+            tx: *tx,
+        }))
+    }
+}
+
+/// Check whether the contract with the given name has been deployed by
+/// the deployer.
+async fn is_contract_deployed<S>(
+    stacks: &S,
+    deployer: &StacksAddress,
+    contract_name: &str,
+) -> Result<bool, Error>
+where
+    S: StacksInteract + Send + Sync,
+{
+    let contract_source = stacks.get_contract_source(deployer, contract_name).await;
+
+    // If we get an Ok response then we know the contract has been
+    // deployed already and we can return early. If we get a 404
+    // response then we know the contract has not been deployed yet,
+    // and we can proceed with deploying it.
+    match contract_source {
+        Ok(_) => Ok(true),
+        Err(Error::StacksNodeResponse(error))
+            if error.status() == Some(reqwest::StatusCode::NOT_FOUND) =>
+        {
+            Ok(false)
+        }
+        Err(err) => Err(err),
+    }
+}
+
 /// A wrapper type for smart contract deployment that implements
 /// AsTxPayload.
 #[derive(Clone, Copy, Debug, Hash, PartialEq)]
@@ -1239,6 +1847,12 @@ pub enum SmartContract {
     /// The sbtc-bootstrap-signers contract. Can be deployed after the
     /// sbtc-token contract.
     SbtcBootstrap,
+    /// The sbtc-deposit-v2 contract. Can be deployed after the first
+    /// version of all contracts.
+    SbtcDepositV2,
+    /// The sbtc-withdrawal-v2 contract. Can be deployed after the first
+    /// version of all contracts.
+    SbtcWithdrawalV2,
 }
 
 impl AsTxPayload for SmartContract {
@@ -1276,10 +1890,17 @@ impl SmartContract {
             SmartContract::SbtcDeposit => "sbtc-deposit",
             SmartContract::SbtcWithdrawal => "sbtc-withdrawal",
             SmartContract::SbtcBootstrap => "sbtc-bootstrap-signers",
+            SmartContract::SbtcDepositV2 => "sbtc-deposit-v2",
+            SmartContract::SbtcWithdrawalV2 => "sbtc-withdrawal-v2",
         }
     }
 
     /// The actual body of the clarity contract.
+    ///
+    /// The second versions of the deposit and withdrawal contracts are
+    /// deployed from the same source as the first versions, in the same
+    /// way as the `sbtc-deposit-update-test` contract in the Clarinet
+    /// project, until their sources diverge.
     pub const fn contract_body(self) -> &'static str {
         match self {
             SmartContract::SbtcToken => {
@@ -1288,10 +1909,10 @@ impl SmartContract {
             SmartContract::SbtcRegistry => {
                 include_str!("../../../contracts/contracts/sbtc-registry.clar")
             }
-            SmartContract::SbtcDeposit => {
+            SmartContract::SbtcDeposit | SmartContract::SbtcDepositV2 => {
                 include_str!("../../../contracts/contracts/sbtc-deposit.clar")
             }
-            SmartContract::SbtcWithdrawal => {
+            SmartContract::SbtcWithdrawal | SmartContract::SbtcWithdrawalV2 => {
                 include_str!("../../../contracts/contracts/sbtc-withdrawal.clar")
             }
             SmartContract::SbtcBootstrap => {
@@ -1300,34 +1921,56 @@ impl SmartContract {
         }
     }
 
+    /// The smart contract that implements the given version of the
+    /// protocol contract with the given role, if this signer knows how to
+    /// deploy it.
+    pub const fn protocol_contract(role: ProtocolRole, version: ContractVersion) -> Option<Self> {
+        match (role, version) {
+            (ProtocolRole::Governance, ContractVersion::V1) => Some(SmartContract::SbtcBootstrap),
+            (ProtocolRole::Deposit, ContractVersion::V1) => Some(SmartContract::SbtcDeposit),
+            (ProtocolRole::Deposit, ContractVersion::V2) => Some(SmartContract::SbtcDepositV2),
+            (ProtocolRole::Withdrawal, ContractVersion::V1) => Some(SmartContract::SbtcWithdrawal),
+            (ProtocolRole::Withdrawal, ContractVersion::V2) => {
+                Some(SmartContract::SbtcWithdrawalV2)
+            }
+            (ProtocolRole::Governance, ContractVersion::V2) => None,
+        }
+    }
+
+    /// The role and version of the protocol contract that this smart
+    /// contract implements, if it is a later version of one. These
+    /// contracts are only deployed once the signers opt into them.
+    pub const fn protocol_upgrade(self) -> Option<(ProtocolRole, ContractVersion)> {
+        match self {
+            SmartContract::SbtcDepositV2 => Some((ProtocolRole::Deposit, ContractVersion::V2)),
+            SmartContract::SbtcWithdrawalV2 => {
+                Some((ProtocolRole::Withdrawal, ContractVersion::V2))
+            }
+            _ => None,
+        }
+    }
+
     /// Check whether this smart contract has been deployed.
     pub async fn is_deployed<S>(&self, stacks: &S, deployer: &StacksAddress) -> Result<bool, Error>
     where
         S: StacksInteract + Send + Sync,
     {
-        let contract_name = self.contract_name();
-        let contract_source = stacks.get_contract_source(deployer, contract_name).await;
-
-        // If we get an Ok response then we know the contract has been
-        // deployed already and we can return early. If we get a 404
-        // response then we know the contract has not been deployed yet,
-        // and we can proceed with deploying it.
-        match contract_source {
-            Ok(_) => Ok(true),
-            Err(Error::StacksNodeResponse(error))
-                if error.status() == Some(reqwest::StatusCode::NOT_FOUND) =>
-            {
-                Ok(false)
-            }
-            Err(err) => Err(err),
-        }
+        is_contract_deployed(stacks, deployer, self.contract_name()).await
     }
 
-    /// Validates that The contract is not already deployed on the chain.
+    /// Validates that The contract is not already deployed on the chain,
+    /// and that later versions of protocol contracts are only deployed if
+    /// this signer has been configured to run them.
     pub async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
     where
         C: Context + Send + Sync,
     {
+        if let Some((role, version)) = self.protocol_upgrade() {
+            if ctx.config().signer.contract_versions.version(role) != version {
+                return Err(Error::ContractVersionNotConfigured(self.contract_name()));
+            }
+        }
+
         let stacks = ctx.get_stacks_client();
         if self.is_deployed(&stacks, &req_ctx.deployer).await? {
             return Err(Error::ContractAlreadyDeployed(self.contract_name()));
@@ -1426,9 +2069,134 @@ mod tests {
     #[test_case::test_case(SmartContract::SbtcDeposit; "sbtc-deposit")]
     #[test_case::test_case(SmartContract::SbtcWithdrawal; "sbtc-withdrawal")]
     #[test_case::test_case(SmartContract::SbtcToken; "sbtc-token")]
+    #[test_case::test_case(SmartContract::SbtcDepositV2; "sbtc-deposit-v2")]
+    #[test_case::test_case(SmartContract::SbtcWithdrawalV2; "sbtc-withdrawal-v2")]
     fn smart_contract_deploy_payloads_dont_panic(smart_contract: SmartContract) {
         // This is to check that this function doesn't implicitly panic. If
         // it doesn't panic now, it can never panic at runtime.
         let _ = smart_contract.tx_payload();
     }
+
+    #[test_case::test_case(ProtocolRole::Governance, ContractVersion::V1; "governance-v1")]
+    #[test_case::test_case(ProtocolRole::Governance, ContractVersion::V2; "governance-v2")]
+    #[test_case::test_case(ProtocolRole::Deposit, ContractVersion::V1; "deposit-v1")]
+    #[test_case::test_case(ProtocolRole::Deposit, ContractVersion::V2; "deposit-v2")]
+    #[test_case::test_case(ProtocolRole::Withdrawal, ContractVersion::V1; "withdrawal-v1")]
+    #[test_case::test_case(ProtocolRole::Withdrawal, ContractVersion::V2; "withdrawal-v2")]
+    fn update_protocol_contract_call_creation(role: ProtocolRole, version: ContractVersion) {
+        // This is to check that this function doesn't implicitly panic. If
+        // it doesn't panic now, it can never panic at runtime.
+        let deployer = StacksAddress::burn_address(false);
+        let call = UpdateProtocolContractV1 { role, version, deployer };
+        let _ = call.as_contract_call();
+
+        // The principal that we register must be one that we recognize
+        // when we read it back from the sbtc-registry.
+        let ClarityValue::Principal(contract) = &call.as_contract_args()[1] else {
+            panic!("expected a principal argument");
+        };
+        let parsed = ActiveContracts::parse_contract(&deployer, role, contract).unwrap();
+        assert_eq!(parsed, version);
+        assert_eq!(ProtocolRole::from_flag(role.flag()), Some(role));
+    }
+
+    #[test]
+    fn contract_names_match_contract_versions() {
+        use ContractVersion::*;
+        use ProtocolRole::*;
+
+        assert_eq!(CompleteDepositV1::CONTRACT_NAME, V1.contract_name(Deposit));
+        assert_eq!(CompleteDepositV2::CONTRACT_NAME, V2.contract_name(Deposit));
+        assert_eq!(
+            AcceptWithdrawalV1::CONTRACT_NAME,
+            V1.contract_name(Withdrawal)
+        );
+        assert_eq!(
+            AcceptWithdrawalV2::CONTRACT_NAME,
+            V2.contract_name(Withdrawal)
+        );
+        assert_eq!(
+            RejectWithdrawalV1::CONTRACT_NAME,
+            V1.contract_name(Withdrawal)
+        );
+        assert_eq!(
+            RejectWithdrawalV2::CONTRACT_NAME,
+            V2.contract_name(Withdrawal)
+        );
+        assert_eq!(RotateKeysV1::CONTRACT_NAME, V1.contract_name(Governance));
+        assert_eq!(
+            UpdateProtocolContractV1::CONTRACT_NAME,
+            V1.contract_name(Governance)
+        );
+
+        // The first version of each contract is what we deploy.
+        assert_eq!(
+            SmartContract::SbtcDeposit.contract_name(),
+            V1.contract_name(Deposit)
+        );
+        assert_eq!(
+            SmartContract::SbtcWithdrawal.contract_name(),
+            V1.contract_name(Withdrawal)
+        );
+        assert_eq!(
+            SmartContract::SbtcBootstrap.contract_name(),
+            V1.contract_name(Governance)
+        );
+
+        // The contracts that we deploy for each version have the names
+        // that the update-protocol-contract calls register.
+        for role in ProtocolRole::ALL {
+            for version in [V1, V2] {
+                let Some(contract) = SmartContract::protocol_contract(role, version) else {
+                    continue;
+                };
+                assert_eq!(contract.contract_name(), version.contract_name(role));
+                let upgrade = contract.protocol_upgrade();
+                assert_eq!(upgrade, (version != V1).then_some((role, version)));
+            }
+        }
+    }
+
+    #[test]
+    fn versioned_contract_calls_use_the_same_arguments() {
+        let call = CompleteDepositV1 {
+            outpoint: OutPoint::null(),
+            amount: 15000,
+            recipient: PrincipalData::from(StacksAddress::burn_address(true)),
+            deployer: StacksAddress::burn_address(false),
+            sweep_txid: BitcoinTxId::from([0; 32]),
+            sweep_block_hash: BitcoinBlockHash::from([0; 32]),
+            sweep_block_height: 7,
+        };
+
+        let contract_call = ContractCall::complete_deposit(call.clone(), ContractVersion::V2);
+        assert_eq!(contract_call.protocol_role(), ProtocolRole::Deposit);
+        assert_eq!(contract_call.contract_version(), ContractVersion::V2);
+
+        let ContractCall::CompleteDepositV2(call_v2) = contract_call else {
+            panic!("expected a V2 complete-deposit call");
+        };
+        let v1 = call.as_contract_call();
+        let v2 = call_v2.as_contract_call();
+        assert_eq!(v1.function_args, v2.function_args);
+        assert_eq!(v1.function_name, v2.function_name);
+        assert_eq!(v2.contract_name.as_str(), "sbtc-deposit-v2");
+    }
+
+    #[test]
+    fn unknown_protocol_contracts_are_rejected() {
+        let deployer = StacksAddress::burn_address(false);
+        let contract = QualifiedContractIdentifier::new(
+            deployer.into(),
+            ContractName::from("sbtc-deposit-v9"),
+        );
+        let principal = PrincipalData::Contract(contract);
+        let result = ActiveContracts::parse_contract(&deployer, ProtocolRole::Deposit, &principal);
+        assert!(matches!(result, Err(Error::UnknownProtocolContract(_))));
+
+        // The contract must also be one that the deployer deployed.
+        let principal = PrincipalData::from(StacksAddress::burn_address(false));
+        let result = ActiveContracts::parse_contract(&deployer, ProtocolRole::Deposit, &principal);
+        assert!(matches!(result, Err(Error::UnknownProtocolContract(_))));
+    }
 }
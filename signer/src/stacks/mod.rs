@@ -2,7 +2,10 @@
 
 /// Contains an interface for interacting with a stacks node.
 pub mod api;
+pub mod blockchain_api;
 pub mod contracts;
+/// Contains a local clarity simulator for sBTC contract calls.
+pub mod simulator;
 /// Contains structs for signing stacks transactions using the signers'
 /// multi-sig wallet.
 pub mod wallet;
@@ -0,0 +1,1251 @@
+//! Local execution of sBTC contract calls using the clarity VM.
+//!
+//! [`AsContractCall::validate`] checks a contract call against the
+//! signer's database, but it does not execute the call. This module runs
+//! the call against a fresh, in-memory deployment of the sBTC smart
+//! contracts so that calls that would abort on chain are rejected before
+//! any signatures are exchanged.
+//!
+//! The simulated contracts are deployed unmodified and start out in the
+//! same state as a freshly deployed set of contracts. The signer seeds
+//! them with the following before executing a contract call:
+//! * The versions of the deposit and withdrawal contracts that are active
+//!   in the sbtc-registry. Later versions of these contracts are always
+//!   deployed, and they are activated with update-protocol-contract calls.
+//! * The signer set, aggregate key and signature threshold from the last
+//!   key rotation that the signer knows about. This sets the data vars in
+//!   the sbtc-registry contract that identify the current signers.
+//! * The bitcoin block headers that the call references. The clarity VM
+//!   in this module has no view of the bitcoin blockchain, so
+//!   `get-burn-block-info?` is answered from headers seeded by the signer
+//!   instead.
+//! * The deposits that have already been completed and the withdrawal
+//!   requests, along with their outcomes, that the call references. These
+//!   are written directly into the maps of the sbtc-registry contract,
+//!   and the locked sBTC of pending withdrawal requests is credited to
+//!   their senders.
+//!
+//! [`AsContractCall::validate`]: crate::stacks::contracts::AsContractCall::validate
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use blockstack_lib::chainstate::stacks::TransactionPayload;
+use blockstack_lib::clarity::vm::types::PrincipalData;
+use blockstack_lib::clarity::vm::types::QualifiedContractIdentifier;
+use blockstack_lib::clarity::vm::types::ResponseData;
+use blockstack_lib::clarity::vm::types::StandardPrincipalData;
+use blockstack_lib::clarity::vm::types::TupleData;
+use blockstack_lib::clarity::vm::ClarityName;
+use blockstack_lib::clarity::vm::ContractName;
+use blockstack_lib::clarity::vm::Value as ClarityValue;
+use blockstack_lib::core::CHAIN_ID_MAINNET;
+use blockstack_lib::core::CHAIN_ID_TESTNET;
+use blockstack_lib::types::chainstate::StacksAddress;
+use clarity::types::chainstate::BlockHeaderHash;
+use clarity::types::chainstate::BurnchainHeaderHash;
+use clarity::types::chainstate::ConsensusHash;
+use clarity::types::chainstate::SortitionId;
+use clarity::types::chainstate::StacksBlockId;
+use clarity::types::chainstate::VRFSeed;
+use clarity::types::StacksEpoch;
+use clarity::types::StacksEpochId;
+use clarity::vm::ast::ASTRules;
+use clarity::vm::contexts::OwnedEnvironment;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::database::BurnStateDB;
+use clarity::vm::database::ClarityDatabase;
+use clarity::vm::database::HeadersDB;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::database::NULL_BURN_STATE_DB;
+use clarity::vm::database::NULL_HEADER_DB;
+use clarity::vm::ClarityVersion;
+use clarity::vm::SymbolicExpression;
+
+use crate::config::NetworkKind;
+use crate::context::Context;
+use crate::error::Error;
+use crate::stacks::contracts::AcceptWithdrawalV2;
+use crate::stacks::contracts::ActiveContracts;
+use crate::stacks::contracts::AsContractCall as _;
+use crate::stacks::contracts::AsTxPayload as _;
+use crate::stacks::contracts::CompleteDepositV2;
+use crate::stacks::contracts::ContractCall;
+use crate::stacks::contracts::ContractVersion;
+use crate::stacks::contracts::ProtocolRole;
+use crate::stacks::contracts::RejectWithdrawalV2;
+use crate::stacks::contracts::ReqContext;
+use crate::stacks::contracts::RotateKeysV1;
+use crate::stacks::contracts::SmartContract;
+use crate::stacks::contracts::UpdateProtocolContractV1;
+use crate::stacks::contracts::SMART_CONTRACTS;
+use crate::stacks::wallet::SignerWallet;
+use crate::storage::model;
+use crate::storage::model::BitcoinBlockHash;
+use crate::storage::model::RotateKeysTransaction;
+use crate::storage::model::ToLittleEndianOrder as _;
+use crate::storage::DbRead;
+
+/// The epoch that the simulated contracts are deployed and executed in.
+const SIMULATED_EPOCH: StacksEpochId = StacksEpochId::Epoch30;
+
+/// The consensus hash of every simulated stacks block.
+const SIMULATED_CONSENSUS_HASH: ConsensusHash = ConsensusHash([1; 20]);
+
+/// The sortition that [`SIMULATED_CONSENSUS_HASH`] belongs to.
+const SIMULATED_SORTITION_ID: SortitionId = SortitionId([1; 32]);
+
+/// The later versions of the protocol contracts. These are deployed after
+/// [`SMART_CONTRACTS`] so that calls can be simulated against whichever
+/// version is active.
+const UPGRADE_CONTRACTS: [SmartContract; 2] = [
+    SmartContract::SbtcDepositV2,
+    SmartContract::SbtcWithdrawalV2,
+];
+
+/// The fungible token in the sbtc-token contract that holds the sBTC
+/// locked by pending withdrawal requests.
+const LOCKED_TOKEN_NAME: &str = "sbtc-token-locked";
+
+/// Answers the stacks block header lookups made by the clarity VM.
+///
+/// Every simulated stacks block is associated with the same consensus
+/// hash so that burnchain lookups resolve to [`SimulatedBurnState`]. All
+/// other lookups behave as they do without a headers database.
+struct SimulatedHeaders;
+
+impl HeadersDB for SimulatedHeaders {
+    fn get_stacks_block_header_hash_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<BlockHeaderHash> {
+        NULL_HEADER_DB.get_stacks_block_header_hash_for_block(id_bhh, epoch)
+    }
+    fn get_burn_header_hash_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+    ) -> Option<BurnchainHeaderHash> {
+        NULL_HEADER_DB.get_burn_header_hash_for_block(id_bhh)
+    }
+    fn get_consensus_hash_for_block(
+        &self,
+        _id_bhh: &StacksBlockId,
+        _epoch: &StacksEpochId,
+    ) -> Option<ConsensusHash> {
+        Some(SIMULATED_CONSENSUS_HASH)
+    }
+    fn get_vrf_seed_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<VRFSeed> {
+        NULL_HEADER_DB.get_vrf_seed_for_block(id_bhh, epoch)
+    }
+    fn get_stacks_block_time_for_block(&self, id_bhh: &StacksBlockId) -> Option<u64> {
+        NULL_HEADER_DB.get_stacks_block_time_for_block(id_bhh)
+    }
+    fn get_burn_block_time_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: Option<&StacksEpochId>,
+    ) -> Option<u64> {
+        NULL_HEADER_DB.get_burn_block_time_for_block(id_bhh, epoch)
+    }
+    fn get_burn_block_height_for_block(&self, id_bhh: &StacksBlockId) -> Option<u32> {
+        NULL_HEADER_DB.get_burn_block_height_for_block(id_bhh)
+    }
+    fn get_miner_address(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<StacksAddress> {
+        NULL_HEADER_DB.get_miner_address(id_bhh, epoch)
+    }
+    fn get_burnchain_tokens_spent_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<u128> {
+        NULL_HEADER_DB.get_burnchain_tokens_spent_for_block(id_bhh, epoch)
+    }
+    fn get_burnchain_tokens_spent_for_winning_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<u128> {
+        NULL_HEADER_DB.get_burnchain_tokens_spent_for_winning_block(id_bhh, epoch)
+    }
+    fn get_tokens_earned_for_block(
+        &self,
+        id_bhh: &StacksBlockId,
+        epoch: &StacksEpochId,
+    ) -> Option<u128> {
+        NULL_HEADER_DB.get_tokens_earned_for_block(id_bhh, epoch)
+    }
+}
+
+/// Answers the burnchain lookups made by the clarity VM from the bitcoin
+/// block headers seeded into the simulator.
+///
+/// All other lookups behave as they do without a burnchain state
+/// database.
+struct SimulatedBurnState<'a> {
+    /// The bitcoin block headers, keyed by height.
+    burn_headers: &'a BTreeMap<u64, BitcoinBlockHash>,
+}
+
+impl BurnStateDB for SimulatedBurnState<'_> {
+    fn get_tip_burn_block_height(&self) -> Option<u32> {
+        NULL_BURN_STATE_DB.get_tip_burn_block_height()
+    }
+    fn get_tip_sortition_id(&self) -> Option<SortitionId> {
+        Some(SIMULATED_SORTITION_ID)
+    }
+    fn get_v1_unlock_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_v1_unlock_height()
+    }
+    fn get_v2_unlock_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_v2_unlock_height()
+    }
+    fn get_v3_unlock_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_v3_unlock_height()
+    }
+    fn get_pox_3_activation_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_pox_3_activation_height()
+    }
+    fn get_pox_4_activation_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_pox_4_activation_height()
+    }
+    fn get_burn_block_height(&self, sortition_id: &SortitionId) -> Option<u32> {
+        NULL_BURN_STATE_DB.get_burn_block_height(sortition_id)
+    }
+    fn get_burn_header_hash(
+        &self,
+        height: u32,
+        sortition_id: &SortitionId,
+    ) -> Option<BurnchainHeaderHash> {
+        if *sortition_id != SIMULATED_SORTITION_ID {
+            return None;
+        }
+        self.burn_headers
+            .get(&u64::from(height))
+            .map(|block_hash| BurnchainHeaderHash(block_hash.to_le_bytes()))
+    }
+    fn get_sortition_id_from_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Option<SortitionId> {
+        (*consensus_hash == SIMULATED_CONSENSUS_HASH).then_some(SIMULATED_SORTITION_ID)
+    }
+    fn get_stacks_epoch(&self, height: u32) -> Option<StacksEpoch<ExecutionCost>> {
+        NULL_BURN_STATE_DB.get_stacks_epoch(height)
+    }
+    fn get_stacks_epoch_by_epoch_id(
+        &self,
+        epoch_id: &StacksEpochId,
+    ) -> Option<StacksEpoch<ExecutionCost>> {
+        NULL_BURN_STATE_DB.get_stacks_epoch_by_epoch_id(epoch_id)
+    }
+    fn get_burn_start_height(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_burn_start_height()
+    }
+    fn get_pox_prepare_length(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_pox_prepare_length()
+    }
+    fn get_pox_reward_cycle_length(&self) -> u32 {
+        NULL_BURN_STATE_DB.get_pox_reward_cycle_length()
+    }
+    fn get_pox_rejection_fraction(&self) -> u64 {
+        NULL_BURN_STATE_DB.get_pox_rejection_fraction()
+    }
+    fn get_pox_payout_addrs(
+        &self,
+        height: u32,
+        sortition_id: &SortitionId,
+    ) -> Option<(Vec<TupleData>, u128)> {
+        NULL_BURN_STATE_DB.get_pox_payout_addrs(height, sortition_id)
+    }
+    fn get_ast_rules(&self, height: u32) -> ASTRules {
+        NULL_BURN_STATE_DB.get_ast_rules(height)
+    }
+}
+
+/// Convert a bitcoin `scriptPubKey` into the `version` and `hashbytes`
+/// of the recipient tuple that the sbtc-withdrawal contract stores.
+///
+/// Nested segwit scripts cannot be told apart from other P2SH scripts, so
+/// they all map to the P2SH version. The contracts do not read the
+/// recipient when a withdrawal request is accepted or rejected, so this
+/// has no effect on the outcome of a simulated call.
+fn recipient_version_hashbytes(script_pubkey: &ScriptBuf) -> Option<(u8, &[u8])> {
+    let bytes = script_pubkey.as_bytes();
+    if script_pubkey.is_p2pkh() {
+        Some((0x00, &bytes[3..23]))
+    } else if script_pubkey.is_p2sh() {
+        Some((0x01, &bytes[2..22]))
+    } else if script_pubkey.is_p2wpkh() {
+        Some((0x04, &bytes[2..]))
+    } else if script_pubkey.is_p2wsh() {
+        Some((0x05, &bytes[2..]))
+    } else if script_pubkey.is_p2tr() {
+        Some((0x06, &bytes[2..]))
+    } else {
+        None
+    }
+}
+
+/// The error codes returned by the sBTC smart contracts.
+///
+/// The codes are unique across all of the sBTC contracts, so they map
+/// one-to-one onto the `ERR_*` constants in the contract sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum SbtcContractErrorCode {
+    /// `ERR_NOT_OWNER` in the sbtc-token contract.
+    #[error("sbtc-token: the sender does not own the tokens (u4)")]
+    TokenNotOwner,
+    /// `ERR_KEY_SIZE` in the sbtc-bootstrap-signers contract.
+    #[error("sbtc-bootstrap-signers: invalid number of keys or aggregate key length (u200)")]
+    BootstrapKeySize,
+    /// `ERR_INVALID_CALLER` in the sbtc-bootstrap-signers contract.
+    #[error("sbtc-bootstrap-signers: the caller is not the current signer principal (u201)")]
+    BootstrapInvalidCaller,
+    /// `ERR_SIGNATURE_THRESHOLD` in the sbtc-bootstrap-signers contract.
+    #[error("sbtc-bootstrap-signers: invalid signature threshold (u202)")]
+    BootstrapSignatureThreshold,
+    /// One of the new signer keys has the wrong length. The index of the
+    /// offending key is included.
+    #[error("sbtc-bootstrap-signers: the key at index {0} has the wrong length")]
+    BootstrapInvalidKey(u128),
+    /// `ERR_TXID_LEN` in the sbtc-deposit contract.
+    #[error("sbtc-deposit: a txid has the wrong length (u300)")]
+    DepositTxidLength,
+    /// `ERR_DEPOSIT_REPLAY` in the sbtc-deposit contract.
+    #[error("sbtc-deposit: the deposit has already been completed (u301)")]
+    DepositReplay,
+    /// `ERR_LOWER_THAN_DUST` in the sbtc-deposit contract.
+    #[error("sbtc-deposit: the amount is below the dust limit (u302)")]
+    DepositLowerThanDust,
+    /// `ERR_DEPOSIT` in the sbtc-deposit contract.
+    #[error("sbtc-deposit: the deposit failed (u303)")]
+    Deposit,
+    /// `ERR_INVALID_CALLER` in the sbtc-deposit contract.
+    #[error("sbtc-deposit: the caller is not the current signer principal (u304)")]
+    DepositInvalidCaller,
+    /// `ERR_INVALID_BURN_HASH` in the sbtc-deposit contract.
+    #[error(
+        "sbtc-deposit: the burn hash does not match the bitcoin block at the given height (u305)"
+    )]
+    DepositInvalidBurnHash,
+    /// `ERR_UNAUTHORIZED` in the sbtc-registry contract.
+    #[error("sbtc-registry: the caller is not a protocol contract (u400)")]
+    RegistryUnauthorized,
+    /// `ERR_INVALID_REQUEST_ID` in the sbtc-registry contract.
+    #[error("sbtc-registry: invalid withdrawal request id (u401)")]
+    RegistryInvalidRequestId,
+    /// `ERR_AGG_PUBKEY_REPLAY` in the sbtc-registry contract.
+    #[error("sbtc-registry: the aggregate key has already been used (u402)")]
+    RegistryAggregateKeyReplay,
+    /// `ERR_INVALID_ADDR_VERSION` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: invalid recipient address version (u500)")]
+    WithdrawalInvalidAddressVersion,
+    /// `ERR_INVALID_ADDR_HASHBYTES` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: invalid recipient address hashbytes (u501)")]
+    WithdrawalInvalidAddressHashbytes,
+    /// `ERR_DUST_LIMIT` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the amount is below the dust limit (u502)")]
+    WithdrawalDustLimit,
+    /// `ERR_INVALID_REQUEST` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: unknown withdrawal request (u503)")]
+    WithdrawalInvalidRequest,
+    /// `ERR_INVALID_CALLER` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the caller is not the current signer principal (u504)")]
+    WithdrawalInvalidCaller,
+    /// `ERR_ALREADY_PROCESSED` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the withdrawal request was already processed (u505)")]
+    WithdrawalAlreadyProcessed,
+    /// `ERR_FEE_TOO_HIGH` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the fee is higher than the requested max fee (u506)")]
+    WithdrawalFeeTooHigh,
+    /// `ERR_WITHDRAWAL_INDEX` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the withdrawal failed (u507)")]
+    Withdrawal,
+    /// `ERR_INVALID_BURN_HASH` in the sbtc-withdrawal contract.
+    #[error("sbtc-withdrawal: the burn hash does not match the bitcoin block at the given height (u508)")]
+    WithdrawalInvalidBurnHash,
+    /// An error code that is not defined in any of the sBTC contracts.
+    #[error("unknown error code u{0}")]
+    Unknown(u128),
+}
+
+impl SbtcContractErrorCode {
+    /// Map an error code returned by one of the sBTC contracts to its
+    /// meaning.
+    pub fn from_code(code: u128) -> Self {
+        match code {
+            4 => Self::TokenNotOwner,
+            200 => Self::BootstrapKeySize,
+            201 => Self::BootstrapInvalidCaller,
+            202 => Self::BootstrapSignatureThreshold,
+            210..=337 => Self::BootstrapInvalidKey(code - 210),
+            300 => Self::DepositTxidLength,
+            301 => Self::DepositReplay,
+            302 => Self::DepositLowerThanDust,
+            303 => Self::Deposit,
+            304 => Self::DepositInvalidCaller,
+            305 => Self::DepositInvalidBurnHash,
+            400 => Self::RegistryUnauthorized,
+            401 => Self::RegistryInvalidRequestId,
+            402 => Self::RegistryAggregateKeyReplay,
+            500 => Self::WithdrawalInvalidAddressVersion,
+            501 => Self::WithdrawalInvalidAddressHashbytes,
+            502 => Self::WithdrawalDustLimit,
+            503 => Self::WithdrawalInvalidRequest,
+            504 => Self::WithdrawalInvalidCaller,
+            505 => Self::WithdrawalAlreadyProcessed,
+            506 => Self::WithdrawalFeeTooHigh,
+            507 => Self::Withdrawal,
+            508 => Self::WithdrawalInvalidBurnHash,
+            _ => Self::Unknown(code),
+        }
+    }
+}
+
+/// The reasons why a simulated contract call can fail.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SimulationError {
+    /// The contract call returned an error response with a known error
+    /// code. This is the error that the transaction would abort with on
+    /// chain.
+    #[error("the contract call would fail: {0}")]
+    Contract(SbtcContractErrorCode),
+    /// The contract call returned something other than a response with a
+    /// uint error code.
+    #[error("the contract call returned an unexpected value: {0}")]
+    UnexpectedResult(String),
+    /// The clarity VM failed while executing the contract call.
+    #[error("the clarity VM failed to execute the contract call: {0}")]
+    Runtime(String),
+    /// The simulated sBTC contracts could not be deployed or seeded.
+    #[error("could not set up the simulated sBTC contracts: {0}")]
+    Setup(String),
+}
+
+/// Executes sBTC contract calls against an in-memory deployment of the
+/// sBTC smart contracts.
+#[derive(Debug, Clone)]
+pub struct ContractCallSimulator {
+    /// The address that deploys the simulated contracts.
+    deployer: StacksAddress,
+    /// The network that the contracts are deployed on. This affects the
+    /// principal of the signers' multi-sig wallet.
+    network: NetworkKind,
+    /// The versions of the protocol contracts that are active in the
+    /// sbtc-registry.
+    active_contracts: ActiveContracts,
+    /// The key rotation that the sbtc-registry is seeded with.
+    key_rotation: Option<RotateKeysTransaction>,
+    /// The bitcoin block headers, keyed by height, that the contracts can
+    /// look up.
+    burn_headers: BTreeMap<u64, BitcoinBlockHash>,
+    /// The deposits that have already been completed.
+    completed_deposits: BTreeSet<OutPoint>,
+    /// The withdrawal requests, keyed by request ID, that the
+    /// sbtc-registry is seeded with.
+    withdrawal_requests: BTreeMap<u64, model::WithdrawalRequest>,
+    /// Whether a withdrawal request, keyed by request ID, was accepted or
+    /// rejected.
+    withdrawal_outcomes: BTreeMap<u64, bool>,
+}
+
+impl ContractCallSimulator {
+    /// Create a new simulator for contracts deployed by the given
+    /// address on the given network.
+    pub fn new(deployer: StacksAddress, network: NetworkKind) -> Self {
+        Self {
+            deployer,
+            network,
+            active_contracts: ActiveContracts::default(),
+            key_rotation: None,
+            burn_headers: BTreeMap::new(),
+            completed_deposits: BTreeSet::new(),
+            withdrawal_requests: BTreeMap::new(),
+            withdrawal_outcomes: BTreeMap::new(),
+        }
+    }
+
+    /// Make the given versions of the protocol contracts the active ones
+    /// in the sbtc-registry.
+    pub fn with_active_contracts(mut self, active_contracts: ActiveContracts) -> Self {
+        self.active_contracts = active_contracts;
+        self
+    }
+
+    /// Seed the sbtc-registry contract with the given key rotation.
+    pub fn with_key_rotation(mut self, key_rotation: RotateKeysTransaction) -> Self {
+        self.key_rotation = Some(key_rotation);
+        self
+    }
+
+    /// Make the header hash of the bitcoin block at the given height
+    /// available to the contracts.
+    pub fn with_burn_header(mut self, height: u64, block_hash: BitcoinBlockHash) -> Self {
+        self.burn_headers.insert(height, block_hash);
+        self
+    }
+
+    /// Mark the deposit with the given outpoint as completed in the
+    /// sbtc-registry contract.
+    pub fn with_completed_deposit(mut self, outpoint: OutPoint) -> Self {
+        self.completed_deposits.insert(outpoint);
+        self
+    }
+
+    /// Seed the sbtc-registry contract with the given withdrawal request.
+    /// Unless an outcome is also seeded, the request is pending and the
+    /// sender holds the locked sBTC for it.
+    pub fn with_withdrawal_request(mut self, request: model::WithdrawalRequest) -> Self {
+        self.withdrawal_requests.insert(request.request_id, request);
+        self
+    }
+
+    /// Record that the withdrawal request with the given ID was accepted
+    /// or rejected.
+    pub fn with_withdrawal_outcome(mut self, request_id: u64, accepted: bool) -> Self {
+        self.withdrawal_outcomes.insert(request_id, accepted);
+        self
+    }
+
+    /// Execute the contract call as the given sender.
+    ///
+    /// This deploys and seeds a fresh set of contracts for each call, so
+    /// simulated calls never affect each other.
+    pub fn simulate(
+        &self,
+        sender: &StacksAddress,
+        contract_call: &ContractCall,
+    ) -> Result<(), SimulationError> {
+        let TransactionPayload::ContractCall(call) = contract_call.tx_payload() else {
+            return Err(SimulationError::Setup(
+                "the contract call does not have a contract call payload".to_string(),
+            ));
+        };
+
+        let headers_db = SimulatedHeaders;
+        let burn_state_db = SimulatedBurnState {
+            burn_headers: &self.burn_headers,
+        };
+        let mut store = MemoryBackingStore::new();
+
+        let mut env = self.environment(ClarityDatabase::new(
+            &mut store,
+            &headers_db,
+            &burn_state_db,
+        ));
+
+        // The contracts are deployed by the deployer, which makes the
+        // deployer the current signer principal until the first key
+        // rotation.
+        for contract in SMART_CONTRACTS.into_iter().chain(UPGRADE_CONTRACTS) {
+            env.initialize_versioned_contract(
+                self.contract_id(contract.contract_name()),
+                ClarityVersion::Clarity3,
+                contract.contract_body(),
+                None,
+                ASTRules::PrecheckSize,
+            )
+            .map_err(|err| SimulationError::Setup(err.to_string()))?;
+        }
+
+        let deployer = PrincipalData::from(StandardPrincipalData::from(self.deployer));
+
+        for role in [ProtocolRole::Deposit, ProtocolRole::Withdrawal] {
+            let version = self.active_contracts.version(role);
+            if version == ContractVersion::V1 {
+                continue;
+            }
+            let update = UpdateProtocolContractV1 {
+                role,
+                version,
+                deployer: self.deployer,
+            };
+            let contract_id = self.contract_id(UpdateProtocolContractV1::CONTRACT_NAME);
+            let args = update.as_contract_args();
+            let fn_name = UpdateProtocolContractV1::FUNCTION_NAME;
+            let result = Self::execute(&mut env, &deployer, contract_id, fn_name, &args);
+            result.map_err(|err| SimulationError::Setup(err.to_string()))?;
+        }
+
+        if let Some(key_rotation) = self.key_rotation.as_ref() {
+            let rotate_keys = RotateKeysV1 {
+                new_keys: key_rotation.signer_set.iter().copied().collect(),
+                aggregate_key: key_rotation.aggregate_key,
+                deployer: self.deployer,
+                signatures_required: key_rotation.signatures_required,
+            };
+            let contract_id = self.contract_id(RotateKeysV1::CONTRACT_NAME);
+            let args = rotate_keys.as_contract_args();
+            let fn_name = RotateKeysV1::FUNCTION_NAME;
+            let result = Self::execute(&mut env, &deployer, contract_id, fn_name, &args);
+            result.map_err(|err| SimulationError::Setup(err.to_string()))?;
+        }
+        drop(env);
+
+        // The contracts do not expose functions for setting the state of
+        // deposits and withdrawal requests directly, so we write it into
+        // the contracts' storage.
+        let mut db = ClarityDatabase::new(&mut store, &headers_db, &burn_state_db);
+        db.begin();
+        self.seed_requests(&mut db)?;
+        db.commit()
+            .map_err(|err| SimulationError::Setup(err.to_string()))?;
+
+        let mut env = self.environment(db);
+        let sender = PrincipalData::from(StandardPrincipalData::from(*sender));
+        let contract_id = QualifiedContractIdentifier::new(
+            StandardPrincipalData::from(call.address),
+            call.contract_name,
+        );
+        Self::execute(
+            &mut env,
+            &sender,
+            contract_id,
+            call.function_name.as_str(),
+            &call.function_args,
+        )
+    }
+
+    /// Create an environment for executing transactions against the given
+    /// database.
+    fn environment<'a>(&self, db: ClarityDatabase<'a>) -> OwnedEnvironment<'a, 'a> {
+        let chain_id = match self.network {
+            NetworkKind::Mainnet => CHAIN_ID_MAINNET,
+            _ => CHAIN_ID_TESTNET,
+        };
+        let mainnet = self.network == NetworkKind::Mainnet;
+        OwnedEnvironment::new_free(mainnet, chain_id, db, SIMULATED_EPOCH)
+    }
+
+    /// Write the completed deposits, withdrawal requests and withdrawal
+    /// outcomes into the storage of the deployed contracts.
+    fn seed_requests(&self, db: &mut ClarityDatabase<'_>) -> Result<(), SimulationError> {
+        let setup_error = |err: clarity::vm::errors::Error| SimulationError::Setup(err.to_string());
+        let registry_id = self.contract_id(SmartContract::SbtcRegistry.contract_name());
+        let token_id = self.contract_id(SmartContract::SbtcToken.contract_name());
+
+        for outpoint in self.completed_deposits.iter() {
+            let key = TupleData::from_data(vec![
+                (
+                    ClarityName::from("txid"),
+                    ClarityValue::buff_from(outpoint.txid.to_le_bytes().to_vec())
+                        .map_err(setup_error)?,
+                ),
+                (
+                    ClarityName::from("vout-index"),
+                    ClarityValue::UInt(outpoint.vout as u128),
+                ),
+            ])
+            .map_err(setup_error)?;
+            let value = ClarityValue::Bool(true);
+            db.set_entry_unknown_descriptor(
+                &registry_id,
+                "deposit-status",
+                ClarityValue::Tuple(key),
+                value,
+                &SIMULATED_EPOCH,
+            )
+            .map_err(setup_error)?;
+        }
+
+        let locked_token = db
+            .load_ft(&token_id, LOCKED_TOKEN_NAME)
+            .map_err(setup_error)?;
+
+        for (request_id, request) in self.withdrawal_requests.iter() {
+            let Some((version, hashbytes)) = recipient_version_hashbytes(&request.recipient) else {
+                return Err(SimulationError::Setup(format!(
+                    "unsupported recipient for withdrawal request {request_id}"
+                )));
+            };
+            let recipient = TupleData::from_data(vec![
+                (
+                    ClarityName::from("version"),
+                    ClarityValue::buff_from_byte(version),
+                ),
+                (
+                    ClarityName::from("hashbytes"),
+                    ClarityValue::buff_from(hashbytes.to_vec()).map_err(setup_error)?,
+                ),
+            ])
+            .map_err(setup_error)?;
+            let sender = PrincipalData::clone(&request.sender_address);
+            // The block height is not read when a withdrawal request is
+            // accepted or rejected, and we do not track it.
+            let value = TupleData::from_data(vec![
+                (
+                    ClarityName::from("amount"),
+                    ClarityValue::UInt(request.amount as u128),
+                ),
+                (
+                    ClarityName::from("max-fee"),
+                    ClarityValue::UInt(request.max_fee as u128),
+                ),
+                (
+                    ClarityName::from("sender"),
+                    ClarityValue::Principal(sender.clone()),
+                ),
+                (
+                    ClarityName::from("recipient"),
+                    ClarityValue::Tuple(recipient),
+                ),
+                (ClarityName::from("block-height"), ClarityValue::UInt(0)),
+            ])
+            .map_err(setup_error)?;
+            db.set_entry_unknown_descriptor(
+                &registry_id,
+                "withdrawal-requests",
+                ClarityValue::UInt(*request_id as u128),
+                ClarityValue::Tuple(value),
+                &SIMULATED_EPOCH,
+            )
+            .map_err(setup_error)?;
+
+            // The sBTC of a processed request has already been burned or
+            // unlocked.
+            if self.withdrawal_outcomes.contains_key(request_id) {
+                continue;
+            }
+            let locked = request.amount as u128 + request.max_fee as u128;
+            db.checked_increase_token_supply(&token_id, LOCKED_TOKEN_NAME, locked, &locked_token)
+                .map_err(setup_error)?;
+            let balance = db
+                .get_ft_balance(&token_id, LOCKED_TOKEN_NAME, &sender, Some(&locked_token))
+                .map_err(setup_error)?;
+            db.set_ft_balance(&token_id, LOCKED_TOKEN_NAME, &sender, balance + locked)
+                .map_err(setup_error)?;
+        }
+
+        for (request_id, accepted) in self.withdrawal_outcomes.iter() {
+            db.set_entry_unknown_descriptor(
+                &registry_id,
+                "withdrawal-status",
+                ClarityValue::UInt(*request_id as u128),
+                ClarityValue::Bool(*accepted),
+                &SIMULATED_EPOCH,
+            )
+            .map_err(setup_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a public function and map any error response to a
+    /// [`SimulationError`].
+    fn execute(
+        env: &mut OwnedEnvironment<'_, '_>,
+        sender: &PrincipalData,
+        contract_id: QualifiedContractIdentifier,
+        function_name: &str,
+        args: &[ClarityValue],
+    ) -> Result<(), SimulationError> {
+        let args: Vec<SymbolicExpression> = args
+            .iter()
+            .cloned()
+            .map(SymbolicExpression::atom_value)
+            .collect();
+
+        let (value, _, _) = env
+            .execute_transaction(sender.clone(), None, contract_id, function_name, &args)
+            .map_err(|err| SimulationError::Runtime(err.to_string()))?;
+
+        match value {
+            ClarityValue::Response(ResponseData { committed: true, .. }) => Ok(()),
+            ClarityValue::Response(ResponseData { committed: false, data }) => match *data {
+                ClarityValue::UInt(code) => Err(SimulationError::Contract(
+                    SbtcContractErrorCode::from_code(code),
+                )),
+                value => Err(SimulationError::UnexpectedResult(value.to_string())),
+            },
+            value => Err(SimulationError::UnexpectedResult(value.to_string())),
+        }
+    }
+
+    /// The identifier of the simulated contract with the given name.
+    fn contract_id(&self, contract_name: &str) -> QualifiedContractIdentifier {
+        QualifiedContractIdentifier::new(
+            StandardPrincipalData::from(self.deployer),
+            ContractName::from(contract_name),
+        )
+    }
+}
+
+/// Execute the given contract call locally before signing it.
+///
+/// The sender of the simulated call is the multi-sig wallet of the signer
+/// set associated with the aggregate key in the request context. The
+/// contracts are seeded with the last key rotation confirmed on the
+/// canonical chain, with the header of the bitcoin block containing the
+/// sweep transaction, and with what the signer knows about the deposit or
+/// withdrawal request that the call fulfills.
+pub async fn simulate_contract_call<C>(
+    ctx: &C,
+    req_ctx: &ReqContext,
+    contract_call: &ContractCall,
+) -> Result<(), Error>
+where
+    C: Context + Send + Sync,
+{
+    let db = ctx.get_storage();
+    let network = ctx.config().signer.network;
+    let chain_tip = &req_ctx.chain_tip.block_hash;
+
+    let Some(shares) = db.get_encrypted_dkg_shares(&req_ctx.aggregate_key).await? else {
+        return Err(Error::MissingDkgShares(req_ctx.aggregate_key.into()));
+    };
+    let wallet = SignerWallet::new(
+        &shares.signer_set_public_keys,
+        req_ctx.signatures_required,
+        network,
+        0,
+    )?;
+
+    let mut simulator = ContractCallSimulator::new(req_ctx.deployer, network)
+        .with_active_contracts(ctx.state().active_contracts());
+
+    let key_rotation = db.get_last_key_rotation(chain_tip).await?;
+    if let Some(key_rotation) = key_rotation {
+        simulator = simulator.with_key_rotation(key_rotation);
+    }
+
+    // We only seed the header if our record of the sweep block agrees
+    // with the height in the contract call, mirroring the check that the
+    // contract does against the actual bitcoin blockchain.
+    let sweep_block = match contract_call {
+        ContractCall::CompleteDepositV1(call)
+        | ContractCall::CompleteDepositV2(CompleteDepositV2(call)) => {
+            Some((call.sweep_block_height, call.sweep_block_hash))
+        }
+        ContractCall::AcceptWithdrawalV1(call)
+        | ContractCall::AcceptWithdrawalV2(AcceptWithdrawalV2(call)) => {
+            Some((call.sweep_block_height, call.sweep_block_hash))
+        }
+        _ => None,
+    };
+    if let Some((height, block_hash)) = sweep_block {
+        let block = db.get_bitcoin_block(&block_hash).await?;
+        if block.is_some_and(|block| block.block_height == height) {
+            simulator = simulator.with_burn_header(height, block_hash);
+        }
+    }
+
+    match contract_call {
+        ContractCall::CompleteDepositV1(call)
+        | ContractCall::CompleteDepositV2(CompleteDepositV2(call)) => {
+            let txid = model::BitcoinTxId::from(call.outpoint.txid);
+            if db
+                .is_deposit_completed(chain_tip, &txid, call.outpoint.vout)
+                .await?
+            {
+                simulator = simulator.with_completed_deposit(call.outpoint);
+            }
+        }
+        ContractCall::AcceptWithdrawalV1(call)
+        | ContractCall::AcceptWithdrawalV2(AcceptWithdrawalV2(call)) => {
+            simulator = seed_withdrawal_request(&db, chain_tip, call.request_id, simulator).await?;
+        }
+        ContractCall::RejectWithdrawalV1(call)
+        | ContractCall::RejectWithdrawalV2(RejectWithdrawalV2(call)) => {
+            simulator = seed_withdrawal_request(&db, chain_tip, call.request_id, simulator).await?;
+        }
+        _ => {}
+    }
+
+    simulator
+        .simulate(wallet.address(), contract_call)
+        .map_err(Error::ContractCallSimulation)
+}
+
+/// Seed the simulator with the withdrawal request with the given ID, and
+/// its outcome, as recorded on the canonical stacks blockchain.
+async fn seed_withdrawal_request<D>(
+    db: &D,
+    chain_tip: &BitcoinBlockHash,
+    request_id: u64,
+    mut simulator: ContractCallSimulator,
+) -> Result<ContractCallSimulator, Error>
+where
+    D: DbRead,
+{
+    if let Some(request) = db.get_withdrawal_request(chain_tip, request_id).await? {
+        simulator = simulator.with_withdrawal_request(request);
+    }
+    if let Some(accepted) = db
+        .get_withdrawal_request_outcome(chain_tip, request_id)
+        .await?
+    {
+        simulator = simulator.with_withdrawal_outcome(request_id, accepted);
+    }
+    Ok(simulator)
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::OutPoint;
+    use fake::Fake as _;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng as _;
+    use secp256k1::SecretKey;
+    use secp256k1::SECP256K1;
+
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::PubkeyHash;
+    use bitcoin::WPubkeyHash;
+    use bitvec::array::BitArray;
+
+    use crate::keys::PublicKey;
+    use crate::stacks::contracts::AcceptWithdrawalV1;
+    use crate::stacks::contracts::CompleteDepositV1;
+    use crate::stacks::contracts::RejectWithdrawalV1;
+    use crate::storage::model::BitcoinTxId;
+
+    use super::*;
+
+    fn complete_deposit(deployer: StacksAddress) -> ContractCall {
+        ContractCall::CompleteDepositV1(CompleteDepositV1 {
+            outpoint: OutPoint::null(),
+            amount: 15000,
+            recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+            deployer,
+            sweep_txid: BitcoinTxId::from([1; 32]),
+            sweep_block_hash: BitcoinBlockHash::from([2; 32]),
+            sweep_block_height: 7,
+        })
+    }
+
+    /// A simulator seeded with a key rotation to the signers of the
+    /// returned wallet and with the header of the sweep block used in
+    /// [`complete_deposit`] and [`accept_withdrawal`].
+    fn seeded_simulator(rng: &mut StdRng) -> (SignerWallet, ContractCallSimulator) {
+        let public_keys: Vec<PublicKey> = (0..3)
+            .map(|_| SecretKey::new(rng).public_key(SECP256K1).into())
+            .collect();
+        let wallet = SignerWallet::new(&public_keys, 2, NetworkKind::Testnet, 0).unwrap();
+        let deployer = StacksAddress::burn_address(false);
+
+        let key_rotation = RotateKeysTransaction {
+            signer_set: public_keys,
+            signatures_required: 2,
+            ..fake::Faker.fake_with_rng(rng)
+        };
+        let simulator = ContractCallSimulator::new(deployer, NetworkKind::Testnet)
+            .with_key_rotation(key_rotation)
+            .with_burn_header(7, BitcoinBlockHash::from([2; 32]));
+
+        (wallet, simulator)
+    }
+
+    fn withdrawal_request(request_id: u64) -> model::WithdrawalRequest {
+        let sender = PrincipalData::from(StacksAddress::burn_address(false));
+        model::WithdrawalRequest {
+            request_id,
+            amount: 100_000,
+            max_fee: 500,
+            recipient: ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20])).into(),
+            sender_address: sender.into(),
+            ..fake::Faker.fake_with_rng(&mut StdRng::seed_from_u64(request_id))
+        }
+    }
+
+    fn accept_withdrawal(request_id: u64, tx_fee: u64) -> ContractCall {
+        ContractCall::AcceptWithdrawalV1(AcceptWithdrawalV1 {
+            request_id,
+            outpoint: OutPoint::null(),
+            tx_fee,
+            signer_bitmap: BitArray::ZERO,
+            deployer: StacksAddress::burn_address(false),
+            sweep_block_hash: BitcoinBlockHash::from([2; 32]),
+            sweep_block_height: 7,
+        })
+    }
+
+    fn reject_withdrawal(request_id: u64) -> ContractCall {
+        ContractCall::RejectWithdrawalV1(RejectWithdrawalV1 {
+            request_id,
+            signer_bitmap: BitArray::ZERO,
+            deployer: StacksAddress::burn_address(false),
+        })
+    }
+
+    #[test]
+    fn simulated_calls_return_contract_error_codes() {
+        let mut rng = StdRng::seed_from_u64(28);
+        let secret_keys = [
+            SecretKey::new(&mut rng),
+            SecretKey::new(&mut rng),
+            SecretKey::new(&mut rng),
+        ];
+        let public_keys = secret_keys.map(|sk| sk.public_key(SECP256K1).into());
+        let wallet = SignerWallet::new(&public_keys, 2, NetworkKind::Testnet, 0).unwrap();
+        let deployer = StacksAddress::burn_address(false);
+        let aggregate_key: PublicKey = fake::Faker.fake_with_rng(&mut rng);
+
+        let key_rotation = RotateKeysTransaction {
+            aggregate_key,
+            signer_set: public_keys.to_vec(),
+            signatures_required: 2,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        };
+        let simulator = ContractCallSimulator::new(deployer, NetworkKind::Testnet)
+            .with_key_rotation(key_rotation);
+
+        // Without the header of the sweep block, the contract cannot
+        // verify the burn hash.
+        let call = complete_deposit(deployer);
+        let error = simulator.simulate(wallet.address(), &call).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::DepositInvalidBurnHash)
+        );
+
+        let simulator = simulator.with_burn_header(7, BitcoinBlockHash::from([2; 32]));
+        simulator.simulate(wallet.address(), &call).unwrap();
+
+        // Only the current signers may complete deposits.
+        let error = simulator.simulate(&deployer, &call).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::DepositInvalidCaller)
+        );
+
+        // The registry rejects rotating to an aggregate key that has been
+        // used before.
+        let call = ContractCall::RotateKeysV1(RotateKeysV1::new(&wallet, deployer, &aggregate_key));
+        let error = simulator.simulate(wallet.address(), &call).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::RegistryAggregateKeyReplay)
+        );
+
+        let new_aggregate_key: PublicKey = fake::Faker.fake_with_rng(&mut rng);
+        let call =
+            ContractCall::RotateKeysV1(RotateKeysV1::new(&wallet, deployer, &new_aggregate_key));
+        simulator.simulate(wallet.address(), &call).unwrap();
+    }
+
+    #[test]
+    fn error_codes_map_to_contract_errors() {
+        assert_eq!(
+            SbtcContractErrorCode::from_code(305),
+            SbtcContractErrorCode::DepositInvalidBurnHash
+        );
+        assert_eq!(
+            SbtcContractErrorCode::from_code(402),
+            SbtcContractErrorCode::RegistryAggregateKeyReplay
+        );
+        assert_eq!(
+            SbtcContractErrorCode::from_code(213),
+            SbtcContractErrorCode::BootstrapInvalidKey(3)
+        );
+        assert_eq!(
+            SbtcContractErrorCode::from_code(9999),
+            SbtcContractErrorCode::Unknown(9999)
+        );
+    }
+
+    #[test]
+    fn completed_deposits_are_replays() {
+        let mut rng = StdRng::seed_from_u64(29);
+        let (wallet, simulator) = seeded_simulator(&mut rng);
+        let call = complete_deposit(StacksAddress::burn_address(false));
+
+        simulator.simulate(wallet.address(), &call).unwrap();
+
+        let simulator = simulator.with_completed_deposit(OutPoint::null());
+        let error = simulator.simulate(wallet.address(), &call).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::DepositReplay)
+        );
+
+        // Other outputs of the same transaction are unaffected.
+        let simulator = ContractCallSimulator {
+            completed_deposits: BTreeSet::from([OutPoint::new(OutPoint::null().txid, 1)]),
+            ..simulator
+        };
+        simulator.simulate(wallet.address(), &call).unwrap();
+    }
+
+    #[test]
+    fn withdrawal_requests_can_be_accepted_once() {
+        let mut rng = StdRng::seed_from_u64(30);
+        let (wallet, simulator) = seeded_simulator(&mut rng);
+
+        // The contract does not know about requests that were not seeded.
+        let error = simulator
+            .simulate(wallet.address(), &accept_withdrawal(3, 200))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalInvalidRequest)
+        );
+
+        let simulator = simulator.with_withdrawal_request(withdrawal_request(3));
+        simulator
+            .simulate(wallet.address(), &accept_withdrawal(3, 200))
+            .unwrap();
+
+        let error = simulator
+            .simulate(wallet.address(), &accept_withdrawal(3, 501))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalFeeTooHigh)
+        );
+
+        let error = simulator
+            .clone()
+            .with_burn_header(7, BitcoinBlockHash::from([4; 32]))
+            .simulate(wallet.address(), &accept_withdrawal(3, 200))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalInvalidBurnHash)
+        );
+
+        for accepted in [true, false] {
+            let error = simulator
+                .clone()
+                .with_withdrawal_outcome(3, accepted)
+                .simulate(wallet.address(), &accept_withdrawal(3, 200))
+                .unwrap_err();
+            assert_eq!(
+                error,
+                SimulationError::Contract(SbtcContractErrorCode::WithdrawalAlreadyProcessed)
+            );
+        }
+    }
+
+    #[test]
+    fn withdrawal_requests_can_be_rejected_once() {
+        let mut rng = StdRng::seed_from_u64(31);
+        let (wallet, simulator) = seeded_simulator(&mut rng);
+
+        let error = simulator
+            .simulate(wallet.address(), &reject_withdrawal(5))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalInvalidRequest)
+        );
+
+        let simulator = simulator.with_withdrawal_request(withdrawal_request(5));
+        simulator
+            .simulate(wallet.address(), &reject_withdrawal(5))
+            .unwrap();
+
+        let error = simulator
+            .simulate(&StacksAddress::burn_address(false), &reject_withdrawal(5))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalInvalidCaller)
+        );
+
+        let error = simulator
+            .with_withdrawal_outcome(5, false)
+            .simulate(wallet.address(), &reject_withdrawal(5))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::WithdrawalAlreadyProcessed)
+        );
+    }
+
+    #[test]
+    fn protocol_contracts_are_upgraded_by_the_signers() {
+        let mut rng = StdRng::seed_from_u64(32);
+        let (wallet, simulator) = seeded_simulator(&mut rng);
+        let deployer = StacksAddress::burn_address(false);
+
+        let ContractCall::CompleteDepositV1(call) = complete_deposit(deployer) else {
+            unreachable!("complete_deposit returns a CompleteDepositV1 call");
+        };
+        let deposit_v1 = ContractCall::complete_deposit(call.clone(), ContractVersion::V1);
+        let deposit_v2 = ContractCall::complete_deposit(call, ContractVersion::V2);
+
+        // The second version is deployed, but the sbtc-token only lets the
+        // active version mint.
+        simulator.simulate(wallet.address(), &deposit_v1).unwrap();
+        let error = simulator
+            .simulate(wallet.address(), &deposit_v2)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::RegistryUnauthorized)
+        );
+
+        // Only the current signers may activate another version.
+        let update = ContractCall::UpdateProtocolContractV1(UpdateProtocolContractV1 {
+            role: ProtocolRole::Deposit,
+            version: ContractVersion::V2,
+            deployer,
+        });
+        simulator.simulate(wallet.address(), &update).unwrap();
+        let error = simulator.simulate(&deployer, &update).unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::BootstrapInvalidCaller)
+        );
+
+        // Once the update has been made, the roles are swapped.
+        let simulator = simulator.with_active_contracts(ActiveContracts {
+            deposit: ContractVersion::V2,
+            ..ActiveContracts::default()
+        });
+        simulator.simulate(wallet.address(), &deposit_v2).unwrap();
+        let error = simulator
+            .simulate(wallet.address(), &deposit_v1)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::RegistryUnauthorized)
+        );
+
+        // The withdrawal contract is upgraded independently.
+        let simulator = simulator.with_withdrawal_request(withdrawal_request(9));
+        simulator
+            .simulate(wallet.address(), &reject_withdrawal(9))
+            .unwrap();
+        let ContractCall::RejectWithdrawalV1(call) = reject_withdrawal(9) else {
+            unreachable!("reject_withdrawal returns a RejectWithdrawalV1 call");
+        };
+        let reject_v2 = ContractCall::reject_withdrawal(call, ContractVersion::V2);
+        let error = simulator
+            .simulate(wallet.address(), &reject_v2)
+            .unwrap_err();
+        assert_eq!(
+            error,
+            SimulationError::Contract(SbtcContractErrorCode::RegistryUnauthorized)
+        );
+    }
+
+    #[test]
+    fn recipient_scripts_map_to_contract_versions() {
+        let p2pkh = ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([5; 20]));
+        assert_eq!(
+            recipient_version_hashbytes(&p2pkh),
+            Some((0x00, [5; 20].as_slice()))
+        );
+
+        let p2wpkh = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([6; 20]));
+        assert_eq!(
+            recipient_version_hashbytes(&p2wpkh),
+            Some((0x04, [6; 20].as_slice()))
+        );
+
+        let op_return = ScriptBuf::from_bytes(vec![0x6a, 0x01, 0x07]);
+        assert_eq!(recipient_version_hashbytes(&op_return), None);
+    }
+}
@@ -90,6 +90,22 @@ pub struct Store {
     /// Rotate keys transactions
     pub rotate_keys_transactions: HashMap<model::StacksTxId, model::RotateKeysTransaction>,
 
+    /// Stacks transactions signed for submission to a stacks node, along
+    /// with the time that they were recorded.
+    pub submitted_stacks_txs:
+        HashMap<model::StacksTxId, (OffsetDateTime, model::SubmittedStacksTx)>,
+
+    /// Stacks blocks whose events have been scanned
+    pub stacks_block_event_scans: HashSet<model::StacksBlockHash>,
+
+    /// The event observer journal, in the order that the payloads were
+    /// received
+    pub event_observer_journal: Vec<model::EventObserverPayload>,
+
+    /// The ID of the last payload written to the event observer journal,
+    /// which keeps counting up when old payloads are removed
+    pub event_observer_journal_last_id: i64,
+
     /// A mapping between request_ids and withdrawal-create events. Note
     /// that in prod we can have a single request_id be associated with
     /// more than one withdrawal-create event because of reorgs.
@@ -122,6 +138,17 @@ pub struct Store {
     /// Bitcoin withdrawal outputs
     pub bitcoin_withdrawal_outputs:
         HashMap<(u64, model::StacksBlockHash), model::BitcoinWithdrawalOutput>,
+
+    /// The stages reached by deposit requests, and when they were reached
+    pub deposit_request_stages:
+        HashMap<DepositRequestPk, BTreeMap<model::RequestStage, OffsetDateTime>>,
+
+    /// The stages reached by withdrawal requests, and when they were
+    /// reached
+    pub withdrawal_request_stages: HashMap<u64, BTreeMap<model::RequestStage, OffsetDateTime>>,
+
+    /// The signing audit log, in order of position
+    pub signing_audit_log: Vec<model::SigningAuditEntry>,
 }
 
 impl Store {
@@ -601,10 +628,105 @@ impl super::DbRead for SharedStore {
             .map(|(_, shares)| shares.clone()))
     }
 
+    async fn get_latest_verified_dkg_shares(
+        &self,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .encrypted_dkg_shares
+            .values()
+            .filter(|(_, shares)| shares.dkg_shares_status == model::DkgSharesStatus::Verified)
+            .max_by_key(|(time, _)| time)
+            .map(|(_, shares)| shares.clone()))
+    }
+
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         Ok(self.lock().await.encrypted_dkg_shares.len() as u32)
     }
 
+    async fn get_verified_dkg_shares_count(&self) -> Result<u32, Error> {
+        let count = self
+            .lock()
+            .await
+            .encrypted_dkg_shares
+            .values()
+            .filter(|(_, shares)| shares.dkg_shares_status == model::DkgSharesStatus::Verified)
+            .count();
+
+        Ok(count as u32)
+    }
+
+    async fn get_pending_stacks_txs(&self) -> Result<Vec<model::SubmittedStacksTx>, Error> {
+        self.get_stale_stacks_txs(std::time::Duration::ZERO).await
+    }
+
+    async fn get_stale_stacks_txs(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<model::SubmittedStacksTx>, Error> {
+        let now = OffsetDateTime::now_utc();
+        let mut txs: Vec<_> = self
+            .lock()
+            .await
+            .submitted_stacks_txs
+            .values()
+            .filter(|(_, tx)| tx.status == model::StacksTxStatus::Pending)
+            .filter(|(submitted_at, _)| now - *submitted_at >= max_age)
+            .cloned()
+            .collect();
+
+        txs.sort_by_key(|(submitted_at, tx)| (tx.nonce, *submitted_at));
+        Ok(txs.into_iter().map(|(_, tx)| tx).collect())
+    }
+
+    async fn stacks_tx_exists(&self, txid: &model::StacksTxId) -> Result<bool, Error> {
+        Ok(self
+            .lock()
+            .await
+            .stacks_transactions_to_blocks
+            .get(txid)
+            .is_some_and(|blocks| !blocks.is_empty()))
+    }
+
+    async fn get_stacks_blocks_without_event_scan(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_depth: u16,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        let store = self.lock().await;
+        let Some(stacks_chain_tip) = store.get_stacks_chain_tip(chain_tip) else {
+            return Ok(Vec::new());
+        };
+
+        let mut blocks: Vec<_> = std::iter::successors(Some(stacks_chain_tip), |block| {
+            store.stacks_blocks.get(&block.parent_hash).cloned()
+        })
+        .take(max_depth as usize)
+        .filter(|block| &block.bitcoin_anchor != chain_tip)
+        .filter(|block| !store.stacks_block_event_scans.contains(&block.block_hash))
+        .collect();
+
+        blocks.sort_by_key(|block| (block.block_height, block.block_hash));
+        Ok(blocks)
+    }
+
+    async fn get_event_observer_payloads(
+        &self,
+        from_id: i64,
+        limit: u32,
+    ) -> Result<Vec<model::EventObserverPayload>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .event_observer_journal
+            .iter()
+            .filter(|payload| payload.id >= from_id)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
     async fn get_last_key_rotation(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -641,6 +763,87 @@ impl super::DbRead for SharedStore {
         unimplemented!()
     }
 
+    async fn is_deposit_completed(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<bool, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(false);
+        };
+
+        let store = self.lock().await;
+        let outpoint = OutPoint::new(**txid, output_index);
+        let Some(event) = store.completed_deposit_events.get(&outpoint) else {
+            return Ok(false);
+        };
+
+        Ok(
+            std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
+                store.stacks_blocks.get(&stacks_block.parent_hash)
+            })
+            .any(|block| block.block_hash == event.block_id),
+        )
+    }
+
+    async fn get_withdrawal_request(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> Result<Option<model::WithdrawalRequest>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(None);
+        };
+
+        let store = self.lock().await;
+
+        Ok(
+            std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
+                store.stacks_blocks.get(&stacks_block.parent_hash)
+            })
+            .find_map(|block| {
+                store
+                    .withdrawal_requests
+                    .get(&(request_id, block.block_hash))
+            })
+            .cloned(),
+        )
+    }
+
+    async fn get_withdrawal_request_outcome(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> Result<Option<bool>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(None);
+        };
+
+        let store = self.lock().await;
+        let accepted_in = store
+            .withdrawal_accept_events
+            .get(&request_id)
+            .map(|event| (true, event.block_id));
+        let rejected_in = store
+            .withdrawal_reject_events
+            .get(&request_id)
+            .map(|event| (false, event.block_id));
+
+        Ok(
+            std::iter::successors(Some(&stacks_chain_tip), |stacks_block| {
+                store.stacks_blocks.get(&stacks_block.parent_hash)
+            })
+            .find_map(|block| {
+                [accepted_in, rejected_in]
+                    .into_iter()
+                    .flatten()
+                    .find(|(_, block_hash)| *block_hash == block.block_hash)
+                    .map(|(accepted, _)| accepted)
+            }),
+        )
+    }
+
     async fn get_signers_script_pubkeys(&self) -> Result<Vec<model::Bytes>, Error> {
         Ok(self
             .lock()
@@ -908,6 +1111,170 @@ impl super::DbRead for SharedStore {
             .get(sighash)
             .map(|s| (s.will_sign, s.aggregate_key)))
     }
+
+    async fn get_withdrawal_request_ids_swept_by(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<u64>, Error> {
+        let request_ids: BTreeSet<u64> = self
+            .lock()
+            .await
+            .bitcoin_withdrawal_outputs
+            .values()
+            .filter(|output| &output.bitcoin_txid == sweep_txid)
+            .map(|output| output.request_id)
+            .collect();
+
+        Ok(request_ids.into_iter().collect())
+    }
+
+    async fn is_deposit_in_signed_sweep(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<bool, Error> {
+        Ok(self.lock().await.bitcoin_sighashes.values().any(|sighash| {
+            &sighash.prevout_txid == txid
+                && sighash.prevout_output_index == output_index
+                && sighash.prevout_type == model::TxPrevoutType::Deposit
+                && sighash.will_sign
+        }))
+    }
+
+    async fn is_withdrawal_in_signed_sweep(
+        &self,
+        id: &model::QualifiedRequestId,
+    ) -> Result<bool, Error> {
+        Ok(self
+            .lock()
+            .await
+            .bitcoin_withdrawal_outputs
+            .values()
+            .any(|output| {
+                output.request_id == id.request_id
+                    && output.stacks_txid == id.txid
+                    && output.stacks_block_hash == id.block_hash
+                    && output.is_valid_tx
+            }))
+    }
+
+    async fn get_deposit_request_stages(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Vec<model::RequestStageTimestamp>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .deposit_request_stages
+            .get(&(*txid, output_index))
+            .map(to_request_stage_timestamps)
+            .unwrap_or_default())
+    }
+
+    async fn get_withdrawal_request_stages(
+        &self,
+        request_id: u64,
+    ) -> Result<Vec<model::RequestStageTimestamp>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .withdrawal_request_stages
+            .get(&request_id)
+            .map(to_request_stage_timestamps)
+            .unwrap_or_default())
+    }
+
+    async fn get_request_stage_backlog(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<model::RequestStageBacklog>, Error> {
+        let store = self.lock().await;
+
+        let deposit_stages = store
+            .deposit_request_stages
+            .values()
+            .map(|stages| (model::RequestKind::Deposit, stages));
+        let withdrawal_stages = store
+            .withdrawal_request_stages
+            .values()
+            .map(|stages| (model::RequestKind::Withdrawal, stages));
+
+        let mut backlog: BTreeMap<(model::RequestKind, model::RequestStage), u64> = BTreeMap::new();
+        for (kind, stages) in deposit_stages.chain(withdrawal_stages) {
+            if stages.values().all(|reached_at| *reached_at < since) {
+                continue;
+            }
+            let Some((&stage, _)) = stages.last_key_value() else {
+                continue;
+            };
+            if !stage.is_terminal() {
+                *backlog.entry((kind, stage)).or_default() += 1;
+            }
+        }
+
+        Ok(backlog
+            .into_iter()
+            .map(|((kind, stage), count)| model::RequestStageBacklog { kind, stage, count })
+            .collect())
+    }
+
+    async fn get_bitcoin_tx_sighash(
+        &self,
+        sighash: &model::SigHash,
+    ) -> Result<Option<model::BitcoinTxSigHash>, Error> {
+        Ok(self.lock().await.bitcoin_sighashes.get(sighash).cloned())
+    }
+
+    async fn get_bitcoin_withdrawal_outputs(
+        &self,
+        txid: &model::BitcoinTxId,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Vec<model::BitcoinWithdrawalOutput>, Error> {
+        let mut outputs: Vec<_> = self
+            .lock()
+            .await
+            .bitcoin_withdrawal_outputs
+            .values()
+            .filter(|output| &output.bitcoin_txid == txid)
+            .filter(|output| &output.bitcoin_chain_tip == chain_tip)
+            .cloned()
+            .collect();
+
+        outputs.sort_by_key(|output| output.output_index);
+        Ok(outputs)
+    }
+
+    async fn get_last_signing_audit_entry(
+        &self,
+    ) -> Result<Option<model::SigningAuditEntry>, Error> {
+        Ok(self.lock().await.signing_audit_log.last().cloned())
+    }
+
+    async fn get_signing_audit_entries(
+        &self,
+        from: u64,
+        limit: u32,
+    ) -> Result<Vec<model::SigningAuditEntry>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .signing_audit_log
+            .iter()
+            .filter(|entry| entry.position >= from)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+}
+
+fn to_request_stage_timestamps(
+    stages: &BTreeMap<model::RequestStage, OffsetDateTime>,
+) -> Vec<model::RequestStageTimestamp> {
+    stages
+        .iter()
+        .map(|(&stage, &reached_at)| model::RequestStageTimestamp { stage, reached_at })
+        .collect()
 }
 
 impl super::DbWrite for SharedStore {
@@ -1123,6 +1490,109 @@ impl super::DbWrite for SharedStore {
         Ok(())
     }
 
+    async fn update_dkg_shares_status(
+        &self,
+        aggregate_key: &PublicKeyXOnly,
+        status: model::DkgSharesStatus,
+    ) -> Result<(), Error> {
+        if let Some((_, shares)) = self
+            .lock()
+            .await
+            .encrypted_dkg_shares
+            .get_mut(aggregate_key)
+        {
+            shares.dkg_shares_status = status;
+        }
+
+        Ok(())
+    }
+
+    async fn write_submitted_stacks_tx(&self, tx: &model::SubmittedStacksTx) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        if store.submitted_stacks_txs.contains_key(&tx.txid) {
+            return Ok(());
+        }
+
+        store
+            .submitted_stacks_txs
+            .values_mut()
+            .filter(|(_, other)| other.nonce == tx.nonce)
+            .filter(|(_, other)| other.status == model::StacksTxStatus::Pending)
+            .for_each(|(_, other)| other.status = model::StacksTxStatus::Replaced);
+
+        store
+            .submitted_stacks_txs
+            .insert(tx.txid, (OffsetDateTime::now_utc(), tx.clone()));
+
+        Ok(())
+    }
+
+    async fn update_stacks_tx_status(
+        &self,
+        txid: &model::StacksTxId,
+        status: model::StacksTxStatus,
+    ) -> Result<(), Error> {
+        if let Some((_, tx)) = self.lock().await.submitted_stacks_txs.get_mut(txid) {
+            tx.status = status;
+        }
+
+        Ok(())
+    }
+
+    async fn confirm_stacks_txs(&self, txids: &[model::StacksTxId]) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        for txid in txids {
+            if let Some((_, tx)) = store.submitted_stacks_txs.get_mut(txid) {
+                tx.status = model::StacksTxStatus::Confirmed;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_event_scan(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        self.lock()
+            .await
+            .stacks_block_event_scans
+            .insert(*block_hash);
+
+        Ok(())
+    }
+
+    async fn write_event_observer_payload(&self, body: &str) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        store.event_observer_journal_last_id += 1;
+        let id = store.event_observer_journal_last_id;
+        store
+            .event_observer_journal
+            .push(model::EventObserverPayload {
+                id,
+                body: body.to_string(),
+                created_at: OffsetDateTime::now_utc().unix_timestamp() as u64,
+            });
+
+        Ok(())
+    }
+
+    async fn delete_event_observer_payloads_older_than(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<u64, Error> {
+        let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+        let cutoff = now.saturating_sub(max_age.as_secs());
+
+        let mut store = self.lock().await;
+        let count = store.event_observer_journal.len();
+        store
+            .event_observer_journal
+            .retain(|payload| payload.created_at >= cutoff);
+
+        Ok((count - store.event_observer_journal.len()) as u64)
+    }
+
     async fn write_rotate_keys_transaction(
         &self,
         key_rotation: &model::RotateKeysTransaction,
@@ -1227,4 +1697,63 @@ impl super::DbWrite for SharedStore {
         });
         Ok(())
     }
+
+    async fn write_deposit_request_stage(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+        stage: model::RequestStage,
+        reached_at: OffsetDateTime,
+    ) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        let stages = store
+            .deposit_request_stages
+            .entry((*txid, output_index))
+            .or_default();
+
+        if stages.contains_key(&stage) {
+            return Ok(false);
+        }
+        stages.insert(stage, reached_at);
+        Ok(true)
+    }
+
+    async fn write_withdrawal_request_stage(
+        &self,
+        request_id: u64,
+        stage: model::RequestStage,
+        reached_at: OffsetDateTime,
+    ) -> Result<bool, Error> {
+        let mut store = self.lock().await;
+        let stages = store
+            .withdrawal_request_stages
+            .entry(request_id)
+            .or_default();
+
+        if stages.contains_key(&stage) {
+            return Ok(false);
+        }
+        stages.insert(stage, reached_at);
+        Ok(true)
+    }
+
+    async fn write_signing_audit_entry(
+        &self,
+        entry: &model::SigningAuditEntry,
+    ) -> Result<(), Error> {
+        let mut store = self.lock().await;
+        // Like the primary key in postgres, this only stops two entries
+        // from taking the same position.
+        let is_taken = store
+            .signing_audit_log
+            .iter()
+            .any(|existing| existing.position == entry.position);
+        if is_taken {
+            return Err(Error::AuditLogPositionTaken(entry.position));
+        }
+
+        store.signing_audit_log.push(entry.clone());
+        store.signing_audit_log.sort_by_key(|entry| entry.position);
+        Ok(())
+    }
 }
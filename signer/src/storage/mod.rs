@@ -218,9 +218,60 @@ pub trait DbRead {
         &self,
     ) -> impl Future<Output = Result<Option<model::EncryptedDkgShares>, Error>> + Send;
 
+    /// Return the most recent DKG shares that have passed verification,
+    /// and return None if there are no such shares.
+    fn get_latest_verified_dkg_shares(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::EncryptedDkgShares>, Error>> + Send;
+
     /// Returns the number of DKG shares entries in the database.
     fn get_encrypted_dkg_shares_count(&self) -> impl Future<Output = Result<u32, Error>> + Send;
 
+    /// Returns the number of DKG shares entries in the database that have
+    /// passed verification.
+    fn get_verified_dkg_shares_count(&self) -> impl Future<Output = Result<u32, Error>> + Send;
+
+    /// Return all submitted Stacks transactions that are still pending,
+    /// ordered by nonce.
+    fn get_pending_stacks_txs(
+        &self,
+    ) -> impl Future<Output = Result<Vec<model::SubmittedStacksTx>, Error>> + Send;
+
+    /// Return the submitted Stacks transactions that have been pending
+    /// for at least `max_age`, ordered by nonce.
+    fn get_stale_stacks_txs(
+        &self,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<Vec<model::SubmittedStacksTx>, Error>> + Send;
+
+    /// Returns whether the given Stacks transaction has been included in
+    /// a Stacks block that we know about.
+    fn stacks_tx_exists(
+        &self,
+        txid: &model::StacksTxId,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Return the stacks blocks on the canonical stacks blockchain,
+    /// confirmed by the given bitcoin chain tip, that have not had their
+    /// events scanned. Blocks anchored to the chain tip itself are left out,
+    /// since their events may still be on their way from the stacks node.
+    /// Only blocks within `max_depth` of the stacks chain tip are looked at,
+    /// and they are returned in ascending order of height.
+    fn get_stacks_blocks_without_event_scan(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_depth: u16,
+    ) -> impl Future<Output = Result<Vec<model::StacksBlock>, Error>> + Send;
+
+    /// Return up to `limit` payloads from the event observer journal, in
+    /// the order that they were received, starting with the payload whose
+    /// ID is `from_id`, or the first one after it.
+    fn get_event_observer_payloads(
+        &self,
+        from_id: i64,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<model::EventObserverPayload>, Error>> + Send;
+
     /// Return the latest rotate-keys transaction confirmed by the given `chain-tip`.
     fn get_last_key_rotation(
         &self,
@@ -236,6 +287,34 @@ pub trait DbRead {
         signatures_required: u16,
     ) -> impl Future<Output = Result<bool, Error>> + Send;
 
+    /// Checks whether the deposit with the given outpoint has been
+    /// completed by a complete-deposit contract call on the canonical
+    /// stacks blockchain.
+    fn is_deposit_completed(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Get the withdrawal request with the given request ID that was
+    /// created on the canonical stacks blockchain.
+    fn get_withdrawal_request(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> impl Future<Output = Result<Option<model::WithdrawalRequest>, Error>> + Send;
+
+    /// Get the outcome of the withdrawal request with the given request
+    /// ID on the canonical stacks blockchain. This is `Some(true)` if the
+    /// request was accepted, `Some(false)` if it was rejected and `None`
+    /// if it has not been processed yet.
+    fn get_withdrawal_request_outcome(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> impl Future<Output = Result<Option<bool>, Error>> + Send;
+
     /// Get the last 365 days worth of the signers' `scriptPubkey`s. If no
     /// keys are available within the last 365, then return the most recent
     /// key.
@@ -342,6 +421,84 @@ pub trait DbRead {
         &self,
         sighash: &model::SigHash,
     ) -> impl Future<Output = Result<Option<(bool, PublicKeyXOnly)>, Error>> + Send;
+
+    /// Get the request IDs of the withdrawal requests that are fulfilled
+    /// by outputs of the given sweep transaction, as recorded when the
+    /// signer validated the transaction.
+    fn get_withdrawal_request_ids_swept_by(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> impl Future<Output = Result<Vec<u64>, Error>> + Send;
+
+    /// Check whether the signer has agreed to sign a sweep transaction
+    /// that spends the given deposit request, as recorded when it
+    /// validated the transaction.
+    fn is_deposit_in_signed_sweep(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Check whether the signer has found a sweep transaction that
+    /// services the given withdrawal request to be valid, as recorded when
+    /// it validated the transaction.
+    fn is_withdrawal_in_signed_sweep(
+        &self,
+        id: &model::QualifiedRequestId,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Get the stages that the given deposit request has reached, ordered
+    /// by stage.
+    fn get_deposit_request_stages(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> impl Future<Output = Result<Vec<model::RequestStageTimestamp>, Error>> + Send;
+
+    /// Get the stages that the withdrawal request with the given request
+    /// ID has reached, ordered by stage.
+    fn get_withdrawal_request_stages(
+        &self,
+        request_id: u64,
+    ) -> impl Future<Output = Result<Vec<model::RequestStageTimestamp>, Error>> + Send;
+
+    /// Count the requests that have not reached a terminal stage by the
+    /// latest stage that they have reached. Requests that did not reach
+    /// any stage at or after `since` are left out, and so are stages
+    /// without any requests.
+    fn get_request_stage_backlog(
+        &self,
+        since: time::OffsetDateTime,
+    ) -> impl Future<Output = Result<Vec<model::RequestStageBacklog>, Error>> + Send;
+
+    /// Get the row for the given sighash that was written when the signer
+    /// validated the bitcoin transaction that it belongs to.
+    fn get_bitcoin_tx_sighash(
+        &self,
+        sighash: &model::SigHash,
+    ) -> impl Future<Output = Result<Option<model::BitcoinTxSigHash>, Error>> + Send;
+
+    /// Get the withdrawal outputs of the given bitcoin transaction, as
+    /// validated by the signer when the chain tip was the given block.
+    fn get_bitcoin_withdrawal_outputs(
+        &self,
+        txid: &model::BitcoinTxId,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> impl Future<Output = Result<Vec<model::BitcoinWithdrawalOutput>, Error>> + Send;
+
+    /// Get the entry at the end of the signing audit log, if the log has
+    /// any entries.
+    fn get_last_signing_audit_entry(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::SigningAuditEntry>, Error>> + Send;
+
+    /// Get at most `limit` entries of the signing audit log, ordered by
+    /// position, starting at the given position.
+    fn get_signing_audit_entries(
+        &self,
+        from: u64,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<model::SigningAuditEntry>, Error>> + Send;
 }
 
 /// Represents the ability to write data to the signer storage.
@@ -430,6 +587,58 @@ pub trait DbWrite {
         shares: &model::EncryptedDkgShares,
     ) -> impl Future<Output = Result<(), Error>> + Send;
 
+    /// Set the verification status of the DKG shares associated with the
+    /// given aggregate key.
+    fn update_dkg_shares_status(
+        &self,
+        aggregate_key: &PublicKeyXOnly,
+        status: model::DkgSharesStatus,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write a Stacks transaction that was signed for submission to a
+    /// stacks node. If the transaction is new then any other pending
+    /// transactions with the same nonce are marked as replaced.
+    fn write_submitted_stacks_tx(
+        &self,
+        tx: &model::SubmittedStacksTx,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Set the status of the submitted Stacks transaction with the given
+    /// txid.
+    fn update_stacks_tx_status(
+        &self,
+        txid: &model::StacksTxId,
+        status: model::StacksTxStatus,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Mark any submitted Stacks transactions with the given txids as
+    /// confirmed.
+    fn confirm_stacks_txs(
+        &self,
+        txids: &[model::StacksTxId],
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that the events of the stacks block with the given hash
+    /// have been scanned.
+    fn write_stacks_block_event_scan(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Append the body of a webhook posted to the event observer to the
+    /// event observer journal.
+    fn write_event_observer_payload(
+        &self,
+        body: &str,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Remove the payloads in the event observer journal that were
+    /// received more than `max_age` ago, returning how many were removed.
+    fn delete_event_observer_payloads_older_than(
+        &self,
+        max_age: std::time::Duration,
+    ) -> impl Future<Output = Result<u64, Error>> + Send;
+
     /// Write rotate-keys transaction
     fn write_rotate_keys_transaction(
         &self,
@@ -483,4 +692,33 @@ pub trait DbWrite {
         &self,
         withdrawals_outputs: &[model::BitcoinWithdrawalOutput],
     ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Record that the given deposit request reached the given stage at
+    /// `reached_at`. Returns `false`, and leaves the recorded time alone,
+    /// if the request had already reached the stage.
+    fn write_deposit_request_stage(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+        stage: model::RequestStage,
+        reached_at: time::OffsetDateTime,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Record that the withdrawal request with the given request ID
+    /// reached the given stage at `reached_at`. Returns `false`, and
+    /// leaves the recorded time alone, if the request had already reached
+    /// the stage.
+    fn write_withdrawal_request_stage(
+        &self,
+        request_id: u64,
+        stage: model::RequestStage,
+        reached_at: time::OffsetDateTime,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Append the given entry to the signing audit log. This fails if the
+    /// log already has an entry at the position of the given entry.
+    fn write_signing_audit_entry(
+        &self,
+        entry: &model::SigningAuditEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
 }
@@ -462,6 +462,47 @@ pub struct EncryptedDkgShares {
     /// (shares) that are needed in order to construct a signature.
     #[sqlx(try_from = "i32")]
     pub signature_share_threshold: u16,
+    /// Whether the signers have verified that these shares can be used
+    /// to produce a valid signature.
+    #[cfg_attr(feature = "testing", dummy(expr = "DkgSharesStatus::Verified"))]
+    pub dkg_shares_status: DkgSharesStatus,
+}
+
+/// A Stacks transaction that was signed by the signers for submission to
+/// a stacks node by the coordinator.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, sqlx::FromRow)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub struct SubmittedStacksTx {
+    /// The ID of the submitted transaction.
+    pub txid: StacksTxId,
+    /// The nonce of the signers' multi-sig wallet used in the transaction.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
+    pub nonce: u64,
+    /// The fee paid for the transaction, in microSTX.
+    #[sqlx(try_from = "i64")]
+    #[cfg_attr(feature = "testing", dummy(faker = "100..10_000_000"))]
+    pub tx_fee: u64,
+    /// The protobuf encoded `StacksTransactionSignRequest` that the
+    /// signers signed. It is used for rebuilding the transaction with a
+    /// higher fee.
+    pub sign_request: Bytes,
+    /// The current status of the transaction.
+    pub status: StacksTxStatus,
+}
+
+/// A webhook body that the stacks node posted to the `/new_block`
+/// endpoint of our event observer.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct EventObserverPayload {
+    /// The position of the payload in the journal. Payloads that were
+    /// received later have larger IDs.
+    pub id: i64,
+    /// The body of the request, exactly as it was received.
+    pub body: String,
+    /// When the payload was received, in seconds since the unix epoch.
+    #[sqlx(try_from = "i64")]
+    pub created_at: u64,
 }
 
 /// Persisted public DKG shares from other signers
@@ -601,6 +642,153 @@ pub enum TxPrevoutType {
     Deposit,
 }
 
+/// The verification status of a set of DKG shares.
+///
+/// Newly generated DKG shares start off as unverified. They become
+/// verified once the full signer set has produced a Schnorr signature,
+/// using the new aggregate key, over the DKG verification message.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
+#[sqlx(type_name = "dkg_shares_status", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum DkgSharesStatus {
+    /// DKG has completed successfully but the shares have not been used
+    /// to produce a valid signature yet.
+    Unverified,
+    /// The shares were used to produce a valid signature over the DKG
+    /// verification message.
+    Verified,
+    /// The signature produced over the DKG verification message did not
+    /// verify against the aggregate key, so these shares must never be
+    /// used.
+    Failed,
+}
+
+/// The status of a Stacks transaction signed by the signers.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, strum::Display)]
+#[sqlx(type_name = "stacks_tx_status", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum StacksTxStatus {
+    /// The transaction was signed for submission to a stacks node but we
+    /// have not seen it in a block yet.
+    Pending,
+    /// The transaction was included in a Stacks block.
+    Confirmed,
+    /// The transaction was replaced by another transaction with the same
+    /// nonce and a higher fee.
+    Replaced,
+    /// The nonce of the transaction was used by some other transaction,
+    /// so this transaction will never be included in a block.
+    Dropped,
+}
+
+/// The kinds of requests that the signers fulfill.
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, strum::Display, strum::IntoStaticStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum RequestKind {
+    /// A deposit request.
+    Deposit,
+    /// A withdrawal request.
+    Withdrawal,
+}
+
+/// The stages that a deposit or withdrawal request goes through, in the
+/// order that they are normally reached.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    strum::Display,
+    strum::IntoStaticStr,
+    strum::EnumIter,
+)]
+#[sqlx(type_name = "request_stage", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+pub enum RequestStage {
+    /// The signer learned about the request, from Emily for deposits and
+    /// from the Stacks blockchain for withdrawals.
+    FirstSeen,
+    /// The signer decided whether it accepts the request.
+    Decided,
+    /// The signer accepted the coordinator's request to sign a sweep
+    /// transaction that fulfills the request. The coordinator broadcasts
+    /// the transaction once the signers have signed it, and every signer,
+    /// the coordinator included, sees this stage.
+    SweepSigned,
+    /// A sweep transaction that fulfills the request was confirmed on the
+    /// bitcoin blockchain.
+    SweepConfirmed,
+    /// The request was completed on the Stacks blockchain, by a
+    /// `complete-deposit` or an `accept-withdrawal-request` contract call.
+    Completed,
+    /// The withdrawal request was rejected on the Stacks blockchain by a
+    /// `reject-withdrawal-request` contract call.
+    Rejected,
+}
+
+impl RequestStage {
+    /// Whether a request that reached this stage is done with, so that it
+    /// is no longer part of the backlog.
+    pub fn is_terminal(self) -> bool {
+        matches!(self, RequestStage::Completed | RequestStage::Rejected)
+    }
+}
+
+/// The time at which a request reached a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestStageTimestamp {
+    /// The stage that was reached.
+    pub stage: RequestStage,
+    /// When the signer saw the request reach the stage.
+    pub reached_at: time::OffsetDateTime,
+}
+
+/// The number of requests of one kind whose latest stage is a given
+/// stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestStageBacklog {
+    /// The kind of the requests.
+    pub kind: RequestKind,
+    /// The latest stage of the requests.
+    pub stage: RequestStage,
+    /// The number of requests.
+    pub count: u64,
+}
+
+/// An entry in the signing audit log.
+///
+/// The log is a hash chain: the `entry_hash` of each entry commits to its
+/// position, to the `entry_hash` of the entry before it and to the
+/// record, and the signer signs the `entry_hash` with its private key. See
+/// the [`audit_log`](crate::audit_log) module for how entries are built
+/// and verified.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct SigningAuditEntry {
+    /// The position of the entry in the log, starting at zero.
+    #[sqlx(try_from = "i64")]
+    pub position: u64,
+    /// The entry hash of the previous entry, or 32 zero bytes for the
+    /// first entry in the log.
+    pub prev_hash: Bytes,
+    /// The hash of this entry.
+    pub entry_hash: Bytes,
+    /// The JSON encoded record of what was signed and why.
+    pub record: Bytes,
+    /// A compact ECDSA signature over the entry hash, made with the
+    /// signer's private key.
+    pub signature: Bytes,
+}
+
 /// An identifier for a withdrawal request, comprised of the Stacks
 /// transaction ID, the Stacks block ID that included the transaction, and
 /// the request-id generated by the clarity contract for the withdrawal
@@ -1014,7 +1202,7 @@ impl std::fmt::Display for SigHash {
 }
 
 /// The sighash and enough metadata to piece together what happened.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub struct BitcoinTxSigHash {
     /// The transaction ID of the bitcoin transaction that sweeps funds
@@ -1031,6 +1219,7 @@ pub struct BitcoinTxSigHash {
     pub aggregate_key: PublicKeyXOnly,
     /// The index of the vout from the transaction that created this
     /// output.
+    #[sqlx(try_from = "i32")]
     #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
     pub prevout_output_index: u32,
     /// The sighash associated with the prevout.
@@ -1051,7 +1240,7 @@ pub struct BitcoinTxSigHash {
 }
 
 /// An output that was created due to a withdrawal request.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 #[cfg_attr(feature = "testing", derive(fake::Dummy))]
 pub struct BitcoinWithdrawalOutput {
     /// The ID of the transaction that includes this withdrawal output.
@@ -1061,12 +1250,14 @@ pub struct BitcoinWithdrawalOutput {
     /// containing inputs
     pub bitcoin_chain_tip: BitcoinBlockHash,
     /// The index of the referenced output in the transaction's outputs.
+    #[sqlx(try_from = "i32")]
     #[cfg_attr(feature = "testing", dummy(faker = "0..i32::MAX as u32"))]
     pub output_index: u32,
     /// The request ID of the withdrawal request. These increment for each
     /// withdrawal, but there can be duplicates if there is a reorg that
     /// affects a transaction that calls the `initiate-withdrawal-request`
     /// public function.
+    #[sqlx(try_from = "i64")]
     #[cfg_attr(feature = "testing", dummy(faker = "0..i64::MAX as u64"))]
     pub request_id: u64,
     /// The stacks transaction ID that lead to the creation of the
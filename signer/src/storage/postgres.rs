@@ -1474,6 +1474,7 @@ impl super::DbRead for PgStore {
               , public_shares
               , signer_set_public_keys
               , signature_share_threshold
+              , dkg_shares_status
             FROM sbtc_signer.dkg_shares
             WHERE substring(aggregate_key FROM 2) = $1;
             "#,
@@ -1497,6 +1498,7 @@ impl super::DbRead for PgStore {
               , public_shares
               , signer_set_public_keys
               , signature_share_threshold
+              , dkg_shares_status
             FROM sbtc_signer.dkg_shares
             ORDER BY created_at DESC
             LIMIT 1;
@@ -1507,6 +1509,31 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_latest_verified_dkg_shares(
+        &self,
+    ) -> Result<Option<model::EncryptedDkgShares>, Error> {
+        sqlx::query_as::<_, model::EncryptedDkgShares>(
+            r#"
+            SELECT
+                aggregate_key
+              , tweaked_aggregate_key
+              , script_pubkey
+              , encrypted_private_shares
+              , public_shares
+              , signer_set_public_keys
+              , signature_share_threshold
+              , dkg_shares_status
+            FROM sbtc_signer.dkg_shares
+            WHERE dkg_shares_status = 'verified'
+            ORDER BY created_at DESC
+            LIMIT 1;
+            "#,
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     /// Returns the number of rows in the `dkg_shares` table.
     async fn get_encrypted_dkg_shares_count(&self) -> Result<u32, Error> {
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sbtc_signer.dkg_shares;")
@@ -1517,6 +1544,161 @@ impl super::DbRead for PgStore {
         u32::try_from(count).map_err(Error::ConversionDatabaseInt)
     }
 
+    /// Returns the number of rows in the `dkg_shares` table for shares
+    /// that have passed verification.
+    async fn get_verified_dkg_shares_count(&self) -> Result<u32, Error> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM sbtc_signer.dkg_shares
+            WHERE dkg_shares_status = 'verified';
+            "#,
+        )
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        u32::try_from(count).map_err(Error::ConversionDatabaseInt)
+    }
+
+    async fn get_pending_stacks_txs(&self) -> Result<Vec<model::SubmittedStacksTx>, Error> {
+        sqlx::query_as::<_, model::SubmittedStacksTx>(
+            r#"
+            SELECT
+                txid
+              , nonce
+              , tx_fee
+              , sign_request
+              , status
+            FROM sbtc_signer.submitted_stacks_transactions
+            WHERE status = 'pending'
+            ORDER BY nonce ASC, created_at ASC;
+            "#,
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stale_stacks_txs(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<Vec<model::SubmittedStacksTx>, Error> {
+        sqlx::query_as::<_, model::SubmittedStacksTx>(
+            r#"
+            SELECT
+                txid
+              , nonce
+              , tx_fee
+              , sign_request
+              , status
+            FROM sbtc_signer.submitted_stacks_transactions
+            WHERE status = 'pending'
+              AND created_at <= CURRENT_TIMESTAMP - make_interval(secs => $1)
+            ORDER BY nonce ASC, created_at ASC;
+            "#,
+        )
+        .bind(max_age.as_secs_f64())
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn stacks_tx_exists(&self, txid: &model::StacksTxId) -> Result<bool, Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.stacks_transactions AS st
+                JOIN sbtc_signer.stacks_blocks AS sb
+                  ON sb.block_hash = st.block_hash
+                WHERE st.txid = $1
+            );"#,
+        )
+        .bind(txid)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_stacks_blocks_without_event_scan(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        max_depth: u16,
+    ) -> Result<Vec<model::StacksBlock>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_as::<_, model::StacksBlock>(
+            r#"
+            WITH RECURSIVE stacks_blocks AS (
+                SELECT
+                    block_hash
+                  , block_height
+                  , parent_hash
+                  , bitcoin_anchor
+                  , 1 AS depth
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.block_height
+                  , parent.parent_hash
+                  , parent.bitcoin_anchor
+                  , last.depth + 1
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_blocks last ON parent.block_hash = last.parent_hash
+                WHERE last.depth < $3
+            )
+            SELECT
+                sb.block_hash
+              , sb.block_height
+              , sb.parent_hash
+              , sb.bitcoin_anchor
+            FROM stacks_blocks sb
+            LEFT JOIN sbtc_signer.stacks_block_event_scans scans
+              ON scans.block_hash = sb.block_hash
+            WHERE scans.block_hash IS NULL
+              AND sb.bitcoin_anchor <> $2
+            ORDER BY sb.block_height ASC, sb.block_hash ASC;
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(chain_tip)
+        .bind(i32::from(max_depth))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_event_observer_payloads(
+        &self,
+        from_id: i64,
+        limit: u32,
+    ) -> Result<Vec<model::EventObserverPayload>, Error> {
+        sqlx::query_as::<_, model::EventObserverPayload>(
+            r#"
+            SELECT
+                id
+              , body
+              , EXTRACT(EPOCH FROM created_at)::BIGINT AS created_at
+            FROM sbtc_signer.event_observer_journal
+            WHERE id >= $1
+            ORDER BY id ASC
+            LIMIT $2;
+            "#,
+        )
+        .bind(from_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     /// Find the last key rotation by iterating backwards from the stacks
     /// chain tip scanning all transactions until we encounter a key
     /// rotation transactions.
@@ -1624,6 +1806,161 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn is_deposit_completed(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<bool, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(false);
+        };
+
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            WITH RECURSIVE stacks_blocks AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , block_height
+                  , 1 AS depth
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , parent.block_height
+                  , last.depth + 1
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_blocks last ON parent.block_hash = last.parent_hash
+            )
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.completed_deposit_events cde
+                JOIN stacks_blocks sb ON cde.block_hash = sb.block_hash
+                WHERE cde.bitcoin_txid = $2
+                  AND cde.output_index = $3
+            )
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(txid)
+        .bind(i64::from(output_index))
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_withdrawal_request(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> Result<Option<model::WithdrawalRequest>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(None);
+        };
+
+        sqlx::query_as::<_, model::WithdrawalRequest>(
+            r#"
+            WITH RECURSIVE stacks_blocks AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , block_height
+                  , 1 AS depth
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , parent.block_height
+                  , last.depth + 1
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_blocks last ON parent.block_hash = last.parent_hash
+            )
+            SELECT
+                wr.request_id
+              , wr.txid
+              , wr.block_hash
+              , wr.recipient
+              , wr.amount
+              , wr.max_fee
+              , wr.sender_address
+            FROM sbtc_signer.withdrawal_requests wr
+            JOIN stacks_blocks sb ON wr.block_hash = sb.block_hash
+            WHERE wr.request_id = $2
+            ORDER BY sb.depth
+            LIMIT 1
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_withdrawal_request_outcome(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        request_id: u64,
+    ) -> Result<Option<bool>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(None);
+        };
+
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            WITH RECURSIVE stacks_blocks AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , block_height
+                  , 1 AS depth
+                FROM sbtc_signer.stacks_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , parent.block_height
+                  , last.depth + 1
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_blocks last ON parent.block_hash = last.parent_hash
+            )
+            , outcomes AS (
+                SELECT TRUE AS accepted, block_hash
+                FROM sbtc_signer.withdrawal_accept_events
+                WHERE request_id = $2
+
+                UNION ALL
+
+                SELECT FALSE AS accepted, block_hash
+                FROM sbtc_signer.withdrawal_reject_events
+                WHERE request_id = $2
+            )
+            SELECT outcomes.accepted
+            FROM outcomes
+            JOIN stacks_blocks sb ON outcomes.block_hash = sb.block_hash
+            ORDER BY sb.depth
+            LIMIT 1
+            "#,
+        )
+        .bind(stacks_chain_tip.block_hash)
+        .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn get_signers_script_pubkeys(&self) -> Result<Vec<model::Bytes>, Error> {
         sqlx::query_scalar::<_, model::Bytes>(
             r#"
@@ -1863,55 +2200,265 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
-    async fn get_swept_withdrawal_requests(
-        &self,
-        _chain_tip: &model::BitcoinBlockHash,
-        _context_window: u16,
-    ) -> Result<Vec<model::SweptWithdrawalRequest>, Error> {
-        // TODO: This can use a similar query to
-        // `get_swept_deposit_requests()`, but using withdrawal tables instead
-        // of deposit.
-        unimplemented!()
-    }
-
-    async fn get_deposit_request(
+    async fn get_swept_withdrawal_requests(
+        &self,
+        _chain_tip: &model::BitcoinBlockHash,
+        _context_window: u16,
+    ) -> Result<Vec<model::SweptWithdrawalRequest>, Error> {
+        // TODO: This can use a similar query to
+        // `get_swept_deposit_requests()`, but using withdrawal tables instead
+        // of deposit.
+        unimplemented!()
+    }
+
+    async fn get_deposit_request(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<model::DepositRequest>, Error> {
+        sqlx::query_as::<_, model::DepositRequest>(
+            r#"
+            SELECT txid
+                 , output_index
+                 , spend_script
+                 , reclaim_script
+                 , recipient
+                 , amount
+                 , max_fee
+                 , lock_time
+                 , signers_public_key
+                 , sender_script_pub_keys
+            FROM sbtc_signer.deposit_requests
+            WHERE txid = $1
+              AND output_index = $2
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn will_sign_bitcoin_tx_sighash(
+        &self,
+        sighash: &model::SigHash,
+    ) -> Result<Option<(bool, PublicKeyXOnly)>, Error> {
+        sqlx::query_as::<_, (bool, PublicKeyXOnly)>(
+            r#"
+            SELECT
+                will_sign
+              , x_only_public_key
+            FROM sbtc_signer.bitcoin_tx_sighashes
+            WHERE sighash = $1
+            "#,
+        )
+        .bind(sighash)
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_withdrawal_request_ids_swept_by(
+        &self,
+        sweep_txid: &model::BitcoinTxId,
+    ) -> Result<Vec<u64>, Error> {
+        let request_ids = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT DISTINCT request_id
+            FROM sbtc_signer.bitcoin_withdrawals_outputs
+            WHERE bitcoin_txid = $1
+            ORDER BY request_id
+            "#,
+        )
+        .bind(sweep_txid)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        request_ids
+            .into_iter()
+            .map(|request_id| u64::try_from(request_id).map_err(Error::ConversionDatabaseInt))
+            .collect()
+    }
+
+    async fn is_deposit_in_signed_sweep(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<bool, Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.bitcoin_tx_sighashes
+                WHERE prevout_txid = $1
+                  AND prevout_output_index = $2
+                  AND prevout_type = 'deposit'
+                  AND will_sign
+            )
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn is_withdrawal_in_signed_sweep(
+        &self,
+        id: &model::QualifiedRequestId,
+    ) -> Result<bool, Error> {
+        sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS (
+                SELECT TRUE
+                FROM sbtc_signer.bitcoin_withdrawals_outputs
+                WHERE request_id = $1
+                  AND stacks_txid = $2
+                  AND stacks_block_hash = $3
+                  AND is_valid_tx
+            )
+            "#,
+        )
+        .bind(i64::try_from(id.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(id.txid)
+        .bind(id.block_hash)
+        .fetch_one(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_deposit_request_stages(
         &self,
         txid: &model::BitcoinTxId,
         output_index: u32,
-    ) -> Result<Option<model::DepositRequest>, Error> {
-        sqlx::query_as::<_, model::DepositRequest>(
+    ) -> Result<Vec<model::RequestStageTimestamp>, Error> {
+        sqlx::query_as::<_, (model::RequestStage, f64)>(
             r#"
-            SELECT txid
-                 , output_index
-                 , spend_script
-                 , reclaim_script
-                 , recipient
-                 , amount
-                 , max_fee
-                 , lock_time
-                 , signers_public_key
-                 , sender_script_pub_keys
-            FROM sbtc_signer.deposit_requests
+            SELECT
+                stage
+              , EXTRACT(EPOCH FROM reached_at)::DOUBLE PRECISION
+            FROM sbtc_signer.deposit_request_stages
             WHERE txid = $1
               AND output_index = $2
+            ORDER BY stage
             "#,
         )
         .bind(txid)
         .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
-        .fetch_optional(&self.0)
+        .fetch_all(&self.0)
         .await
-        .map_err(Error::SqlxQuery)
+        .map_err(Error::SqlxQuery)?
+        .into_iter()
+        .map(to_request_stage_timestamp)
+        .collect()
     }
 
-    async fn will_sign_bitcoin_tx_sighash(
+    async fn get_withdrawal_request_stages(
+        &self,
+        request_id: u64,
+    ) -> Result<Vec<model::RequestStageTimestamp>, Error> {
+        sqlx::query_as::<_, (model::RequestStage, f64)>(
+            r#"
+            SELECT
+                stage
+              , EXTRACT(EPOCH FROM reached_at)::DOUBLE PRECISION
+            FROM sbtc_signer.withdrawal_request_stages
+            WHERE request_id = $1
+            ORDER BY stage
+            "#,
+        )
+        .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?
+        .into_iter()
+        .map(to_request_stage_timestamp)
+        .collect()
+    }
+
+    async fn get_request_stage_backlog(
+        &self,
+        since: time::OffsetDateTime,
+    ) -> Result<Vec<model::RequestStageBacklog>, Error> {
+        // Enums in postgres are ordered by the order of their labels, so
+        // the maximum stage of a request is the latest one it reached.
+        // Only the requests with a recent stage are looked at, using the
+        // index on reached_at, but all of their stages are taken into
+        // account since stages can be recorded out of order.
+        let rows = sqlx::query_as::<_, (bool, model::RequestStage, i64)>(
+            r#"
+            WITH deposit_stages AS (
+                SELECT MAX(stage) AS stage
+                FROM sbtc_signer.deposit_request_stages
+                WHERE (txid, output_index) IN (
+                    SELECT txid, output_index
+                    FROM sbtc_signer.deposit_request_stages
+                    WHERE reached_at >= $1
+                )
+                GROUP BY txid, output_index
+            )
+            , withdrawal_stages AS (
+                SELECT MAX(stage) AS stage
+                FROM sbtc_signer.withdrawal_request_stages
+                WHERE request_id IN (
+                    SELECT request_id
+                    FROM sbtc_signer.withdrawal_request_stages
+                    WHERE reached_at >= $1
+                )
+                GROUP BY request_id
+            )
+            SELECT TRUE AS is_deposit, stage, COUNT(*)
+            FROM deposit_stages
+            WHERE stage NOT IN ('completed', 'rejected')
+            GROUP BY stage
+            UNION ALL
+            SELECT FALSE AS is_deposit, stage, COUNT(*)
+            FROM withdrawal_stages
+            WHERE stage NOT IN ('completed', 'rejected')
+            GROUP BY stage
+            ORDER BY is_deposit DESC, stage
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|(is_deposit, stage, count)| {
+                Ok(model::RequestStageBacklog {
+                    kind: if is_deposit {
+                        model::RequestKind::Deposit
+                    } else {
+                        model::RequestKind::Withdrawal
+                    },
+                    stage,
+                    count: u64::try_from(count).map_err(Error::ConversionDatabaseInt)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_bitcoin_tx_sighash(
         &self,
         sighash: &model::SigHash,
-    ) -> Result<Option<(bool, PublicKeyXOnly)>, Error> {
-        sqlx::query_as::<_, (bool, PublicKeyXOnly)>(
+    ) -> Result<Option<model::BitcoinTxSigHash>, Error> {
+        sqlx::query_as::<_, model::BitcoinTxSigHash>(
             r#"
             SELECT
-                will_sign
-              , x_only_public_key
+                txid
+              , chain_tip
+              , prevout_txid
+              , x_only_public_key AS aggregate_key
+              , prevout_output_index
+              , sighash
+              , prevout_type
+              , validation_result
+              , is_valid_tx
+              , will_sign
             FROM sbtc_signer.bitcoin_tx_sighashes
             WHERE sighash = $1
             "#,
@@ -1921,6 +2468,99 @@ impl super::DbRead for PgStore {
         .await
         .map_err(Error::SqlxQuery)
     }
+
+    async fn get_bitcoin_withdrawal_outputs(
+        &self,
+        txid: &model::BitcoinTxId,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<Vec<model::BitcoinWithdrawalOutput>, Error> {
+        sqlx::query_as::<_, model::BitcoinWithdrawalOutput>(
+            r#"
+            SELECT
+                bitcoin_txid
+              , bitcoin_chain_tip
+              , output_index
+              , request_id
+              , stacks_txid
+              , stacks_block_hash
+              , validation_result
+              , is_valid_tx
+            FROM sbtc_signer.bitcoin_withdrawals_outputs
+            WHERE bitcoin_txid = $1
+              AND bitcoin_chain_tip = $2
+            ORDER BY output_index
+            "#,
+        )
+        .bind(txid)
+        .bind(chain_tip)
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_last_signing_audit_entry(
+        &self,
+    ) -> Result<Option<model::SigningAuditEntry>, Error> {
+        sqlx::query_as::<_, model::SigningAuditEntry>(
+            r#"
+            SELECT
+                position
+              , prev_hash
+              , entry_hash
+              , record
+              , signature
+            FROM sbtc_signer.signing_audit_log
+            ORDER BY position DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_signing_audit_entries(
+        &self,
+        from: u64,
+        limit: u32,
+    ) -> Result<Vec<model::SigningAuditEntry>, Error> {
+        sqlx::query_as::<_, model::SigningAuditEntry>(
+            r#"
+            SELECT
+                position
+              , prev_hash
+              , entry_hash
+              , record
+              , signature
+            FROM sbtc_signer.signing_audit_log
+            WHERE position >= $1
+            ORDER BY position
+            LIMIT $2
+            "#,
+        )
+        .bind(i64::try_from(from).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::from(limit))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+}
+
+/// Convert a stage and the number of seconds since the unix epoch at
+/// which it was reached, as returned by postgres, into a timestamp.
+fn to_request_stage_timestamp(
+    (stage, seconds): (model::RequestStage, f64),
+) -> Result<model::RequestStageTimestamp, Error> {
+    let nanos = (seconds * 1e9) as i128;
+    let reached_at =
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(Error::InvalidTimestamp)?;
+    Ok(model::RequestStageTimestamp { stage, reached_at })
+}
+
+/// The number of seconds since the unix epoch of the given timestamp, in
+/// the form that the postgres `to_timestamp` function takes.
+fn to_epoch_seconds(timestamp: time::OffsetDateTime) -> f64 {
+    timestamp.unix_timestamp_nanos() as f64 / 1e9
 }
 
 impl super::DbWrite for PgStore {
@@ -2390,8 +3030,9 @@ impl super::DbWrite for PgStore {
               , script_pubkey
               , signer_set_public_keys
               , signature_share_threshold
+              , dkg_shares_status
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             ON CONFLICT DO NOTHING"#,
         )
         .bind(shares.aggregate_key)
@@ -2401,6 +3042,140 @@ impl super::DbWrite for PgStore {
         .bind(&shares.script_pubkey)
         .bind(&shares.signer_set_public_keys)
         .bind(i32::from(shares.signature_share_threshold))
+        .bind(shares.dkg_shares_status)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn update_dkg_shares_status(
+        &self,
+        aggregate_key: &PublicKeyXOnly,
+        status: model::DkgSharesStatus,
+    ) -> Result<(), Error> {
+        // The aggregate_key column stores compressed public keys, so we
+        // lop off the parity byte when comparing with the x-only key.
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.dkg_shares
+            SET dkg_shares_status = $1
+            WHERE substring(aggregate_key FROM 2) = $2;
+            "#,
+        )
+        .bind(status)
+        .bind(aggregate_key)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_submitted_stacks_tx(&self, tx: &model::SubmittedStacksTx) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            WITH inserted AS (
+                INSERT INTO sbtc_signer.submitted_stacks_transactions (
+                    txid
+                  , nonce
+                  , tx_fee
+                  , sign_request
+                  , status
+                )
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT DO NOTHING
+                RETURNING txid, nonce
+            )
+            UPDATE sbtc_signer.submitted_stacks_transactions AS sst
+            SET status = 'replaced'
+              , updated_at = CURRENT_TIMESTAMP
+            FROM inserted
+            WHERE sst.nonce = inserted.nonce
+              AND sst.txid <> inserted.txid
+              AND sst.status = 'pending';"#,
+        )
+        .bind(tx.txid)
+        .bind(i64::try_from(tx.nonce).map_err(Error::ConversionDatabaseInt)?)
+        .bind(i64::try_from(tx.tx_fee).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&tx.sign_request)
+        .bind(tx.status)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn update_stacks_tx_status(
+        &self,
+        txid: &model::StacksTxId,
+        status: model::StacksTxStatus,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.submitted_stacks_transactions
+            SET status = $2
+              , updated_at = CURRENT_TIMESTAMP
+            WHERE txid = $1;
+            "#,
+        )
+        .bind(txid)
+        .bind(status)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn confirm_stacks_txs(&self, txids: &[model::StacksTxId]) -> Result<(), Error> {
+        if txids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE sbtc_signer.submitted_stacks_transactions
+            SET status = 'confirmed'
+              , updated_at = CURRENT_TIMESTAMP
+            WHERE txid = ANY($1);
+            "#,
+        )
+        .bind(txids)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_stacks_block_event_scan(
+        &self,
+        block_hash: &model::StacksBlockHash,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_block_event_scans (block_hash)
+            VALUES ($1)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(block_hash)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_event_observer_payload(&self, body: &str) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.event_observer_journal (body)
+            VALUES ($1)"#,
+        )
+        .bind(body)
         .execute(&self.0)
         .await
         .map_err(Error::SqlxQuery)?;
@@ -2408,6 +3183,23 @@ impl super::DbWrite for PgStore {
         Ok(())
     }
 
+    async fn delete_event_observer_payloads_older_than(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<u64, Error> {
+        sqlx::query(
+            r#"
+            DELETE FROM sbtc_signer.event_observer_journal
+            WHERE created_at < CURRENT_TIMESTAMP - make_interval(secs => $1);
+            "#,
+        )
+        .bind(max_age.as_secs_f64())
+        .execute(&self.0)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn write_rotate_keys_transaction(
         &self,
         key_rotation: &model::RotateKeysTransaction,
@@ -2803,6 +3595,98 @@ impl super::DbWrite for PgStore {
 
         Ok(())
     }
+
+    async fn write_deposit_request_stage(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+        stage: model::RequestStage,
+        reached_at: time::OffsetDateTime,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.deposit_request_stages (
+                txid
+              , output_index
+              , stage
+              , reached_at
+            )
+            VALUES ($1, $2, $3, to_timestamp($4))
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(txid)
+        .bind(i32::try_from(output_index).map_err(Error::ConversionDatabaseInt)?)
+        .bind(stage)
+        .bind(to_epoch_seconds(reached_at))
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn write_withdrawal_request_stage(
+        &self,
+        request_id: u64,
+        stage: model::RequestStage,
+        reached_at: time::OffsetDateTime,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.withdrawal_request_stages (
+                request_id
+              , stage
+              , reached_at
+            )
+            VALUES ($1, $2, to_timestamp($3))
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(stage)
+        .bind(to_epoch_seconds(reached_at))
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn write_signing_audit_entry(
+        &self,
+        entry: &model::SigningAuditEntry,
+    ) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.signing_audit_log (
+                position
+              , prev_hash
+              , entry_hash
+              , record
+              , signature
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(i64::try_from(entry.position).map_err(Error::ConversionDatabaseInt)?)
+        .bind(&entry.prev_hash)
+        .bind(&entry.entry_hash)
+        .bind(&entry.record)
+        .bind(&entry.signature)
+        .execute(&self.0)
+        .await
+        .map_err(|error| match error.as_database_error() {
+            // The position is the primary key, so another writer got to
+            // it first.
+            Some(db_error) if db_error.is_unique_violation() => {
+                Error::AuditLogPositionTaken(entry.position)
+            }
+            _ => Error::SqlxQuery(error),
+        })?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
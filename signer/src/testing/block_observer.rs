@@ -12,6 +12,7 @@ use blockstack_lib::chainstate::burn::ConsensusHash;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlockHeader;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
+use blockstack_lib::clarity::vm::types::PrincipalData;
 use blockstack_lib::net::api::getcontractsrc::ContractSrcResponse;
 use blockstack_lib::net::api::getinfo::RPCPeerInfoData;
 use blockstack_lib::net::api::getpoxinfo::RPCPoxEpoch;
@@ -45,6 +46,7 @@ use crate::stacks::api::FeePriority;
 use crate::stacks::api::StacksInteract;
 use crate::stacks::api::SubmitTxResponse;
 use crate::stacks::api::TenureBlocks;
+use crate::stacks::contracts::ProtocolRole;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::model;
 use crate::testing::dummy;
@@ -459,6 +461,14 @@ impl StacksInteract for TestHarness {
     async fn get_sbtc_total_supply(&self, _: &StacksAddress) -> Result<Amount, Error> {
         Ok(Amount::from_sat(u64::MAX))
     }
+
+    async fn get_active_protocol_contract(
+        &self,
+        _: &StacksAddress,
+        _: ProtocolRole,
+    ) -> Result<Option<PrincipalData>, Error> {
+        Ok(None)
+    }
 }
 
 impl EmilyInteract for TestHarness {
@@ -518,7 +528,8 @@ impl EmilyInteract for TestHarness {
     }
 }
 
-fn get_pox_info_data() -> RPCPoxInfoData {
+/// A canned `/v2/pox` response from a regtest stacks node.
+pub(crate) fn get_pox_info_data() -> RPCPoxInfoData {
     let raw_json_response = r#"
     {
         "contract_id": "ST000000000000000000002AMW42H.pox-4",
@@ -560,7 +571,8 @@ fn get_pox_info_data() -> RPCPoxInfoData {
     serde_json::from_str::<RPCPoxInfoData>(raw_json_response).unwrap()
 }
 
-fn get_node_info_data() -> RPCPeerInfoData {
+/// A canned `/v2/info` response from a regtest stacks node.
+pub(crate) fn get_node_info_data() -> RPCPeerInfoData {
     let raw_json_response = r#"
     {
         "peer_version": 4207599114,
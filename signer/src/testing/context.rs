@@ -14,6 +14,7 @@ use blockstack_lib::{
     },
 };
 use clarity::types::chainstate::{StacksAddress, StacksBlockId};
+use clarity::vm::types::PrincipalData;
 use tokio::sync::{broadcast, Mutex};
 use tokio::time::error::Elapsed;
 
@@ -34,7 +35,7 @@ use crate::{
     keys::PublicKey,
     stacks::{
         api::{AccountInfo, FeePriority, MockStacksInteract, StacksInteract, SubmitTxResponse},
-        contracts::AsTxPayload,
+        contracts::{AsTxPayload, ProtocolRole},
     },
     storage::{
         in_memory::{SharedStore, Store},
@@ -217,6 +218,10 @@ where
         self.inner.config()
     }
 
+    fn subscribe_config(&self) -> tokio::sync::watch::Receiver<Arc<Settings>> {
+        self.inner.subscribe_config()
+    }
+
     fn state(&self) -> &SignerState {
         self.inner.state()
     }
@@ -462,6 +467,18 @@ impl StacksInteract for WrappedMock<MockStacksInteract> {
     async fn get_sbtc_total_supply(&self, sender: &StacksAddress) -> Result<Amount, Error> {
         self.inner.lock().await.get_sbtc_total_supply(sender).await
     }
+
+    async fn get_active_protocol_contract(
+        &self,
+        deployer: &StacksAddress,
+        role: ProtocolRole,
+    ) -> Result<Option<PrincipalData>, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_active_protocol_contract(deployer, role)
+            .await
+    }
 }
 
 impl EmilyInteract for WrappedMock<MockEmilyInteract> {
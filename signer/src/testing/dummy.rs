@@ -66,6 +66,7 @@ use crate::stacks::contracts::AcceptWithdrawalV1;
 use crate::stacks::contracts::CompleteDepositV1;
 use crate::stacks::contracts::RejectWithdrawalV1;
 use crate::stacks::contracts::RotateKeysV1;
+use crate::stacks::contracts::UpdateProtocolContractV1;
 use crate::storage::model;
 use crate::storage::model::BitcoinBlockHash;
 use crate::storage::model::BitcoinTx;
@@ -272,6 +273,7 @@ pub fn encrypted_dkg_shares<R: rand::RngCore + rand::CryptoRng>(
         script_pubkey: group_key.signers_script_pubkey().into(),
         signer_set_public_keys: vec![fake::Faker.fake_with_rng(rng)],
         signature_share_threshold: 1,
+        dkg_shares_status: model::DkgSharesStatus::Verified,
     }
 }
 
@@ -441,6 +443,7 @@ impl fake::Dummy<SignerSetConfig> for EncryptedDkgShares {
             public_shares: Vec::new(),
             signer_set_public_keys,
             signature_share_threshold: config.signatures_required,
+            dkg_shares_status: model::DkgSharesStatus::Verified,
         }
     }
 }
@@ -734,6 +737,20 @@ impl fake::Dummy<fake::Faker> for RotateKeysV1 {
     }
 }
 
+impl fake::Dummy<fake::Faker> for UpdateProtocolContractV1 {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
+        let public_key: PublicKey = config.fake_with_rng(rng);
+        let pubkey = stacks_common::util::secp256k1::Secp256k1PublicKey::from(&public_key);
+        let address = StacksAddress::p2pkh(false, &pubkey);
+
+        UpdateProtocolContractV1 {
+            role: config.fake_with_rng(rng),
+            version: config.fake_with_rng(rng),
+            deployer: address,
+        }
+    }
+}
+
 impl fake::Dummy<fake::Faker> for QualifiedRequestId {
     fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
         QualifiedRequestId {
@@ -62,7 +62,23 @@ impl fake::Dummy<fake::Faker> for message::SignerMessage {
     fn dummy_with_rng<R: rand::RngCore + ?Sized>(config: &fake::Faker, rng: &mut R) -> Self {
         let payload: message::Payload = config.fake_with_rng(rng);
 
-        Self::random_with_payload(rng, payload)
+        let mut message = Self::random_with_payload(rng, payload);
+        message.trace_context = config.fake_with_rng(rng);
+        message
+    }
+}
+
+impl fake::Dummy<fake::Faker> for message::TraceContext {
+    fn dummy_with_rng<R: rand::RngCore + ?Sized>(_: &fake::Faker, rng: &mut R) -> Self {
+        let mut trace_id = [0u8; 16];
+        let mut span_id = [0u8; 8];
+        rng.fill_bytes(&mut trace_id);
+        rng.fill_bytes(&mut span_id);
+
+        Self {
+            traceparent: format!("00-{}-{}-01", hex::encode(trace_id), hex::encode(span_id)),
+            tracestate: String::new(),
+        }
     }
 }
 
@@ -0,0 +1,609 @@
+//! A bitcoin client backed by the in-process [`MockChain`].
+//!
+//! [`MockChainClient`] implements [`BitcoinInteract`] on top of a shared
+//! [`MockChain`], and hands out block hash streams that behave like the
+//! ZMQ `hashblock` stream of bitcoin-core, so the block observer, the
+//! transaction coordinator and anything else that talks to bitcoin-core
+//! can run without a bitcoin-core node.
+//!
+//! The RPC result types are built from the same JSON that bitcoin-core
+//! returns for the corresponding RPC, so they are decoded by the same
+//! serde implementations as in production.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use bitcoin::hex::DisplayHex as _;
+use bitcoin::Amount;
+use bitcoin::Block;
+use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::Script;
+use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::Txid;
+use bitcoincore_rpc::jsonrpc::error::Error as JsonRpcError;
+use bitcoincore_rpc::jsonrpc::error::RpcError;
+use bitcoincore_rpc::Error as BtcRpcError;
+use bitcoincore_rpc_json::GetMempoolEntryResult;
+use bitcoincore_rpc_json::GetTxOutResult;
+use futures::Stream;
+use sbtc::testing::mock_chain::MockChain;
+use sbtc::testing::mock_chain::MockChainError;
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt as _;
+
+use crate::bitcoin::rpc::BitcoinBlockHeader;
+use crate::bitcoin::rpc::BitcoinTxInfo;
+use crate::bitcoin::rpc::GetTxResponse;
+use crate::bitcoin::BitcoinInteract;
+use crate::bitcoin::GetTransactionFeeResult;
+use crate::bitcoin::TransactionLookupHint;
+use crate::error::Error;
+
+/// The number of block hashes that a block hash stream buffers before it
+/// starts dropping them. The block observer fetches the headers of any
+/// blocks that it missed, so dropped hashes only delay it.
+const BLOCK_STREAM_CAPACITY: usize = 1024;
+
+/// The fee rate, in sats per vbyte, returned by
+/// [`BitcoinInteract::estimate_fee_rate`] unless it is changed with
+/// [`MockChainClient::set_fee_rate`].
+const DEFAULT_FEE_RATE: f64 = 1.0;
+
+/// The error code that bitcoin-core returns when a transaction is
+/// rejected by mempool policy.
+const RPC_VERIFY_REJECTED: i32 = -26;
+
+/// The error code that bitcoin-core returns when a transaction spends
+/// missing or spent outputs.
+const RPC_VERIFY_ERROR: i32 = -25;
+
+/// The error code that bitcoin-core returns when a transaction is already
+/// confirmed.
+const RPC_VERIFY_ALREADY_IN_CHAIN: i32 = -27;
+
+#[derive(Debug)]
+struct Inner {
+    chain: MockChain,
+    fee_rate: f64,
+    broadcasts: Vec<Broadcast>,
+}
+
+/// A transaction passed to [`BitcoinInteract::broadcast_transaction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Broadcast {
+    /// The transaction.
+    pub tx: Transaction,
+    /// The chain tip when the transaction was broadcast.
+    pub chain_tip: BlockHash,
+}
+
+/// A [`BitcoinInteract`] implementation backed by a [`MockChain`].
+///
+/// Clones share the same chain, so a test can keep a clone to mine
+/// blocks and broadcast transactions while the code under test uses
+/// another.
+#[derive(Debug, Clone)]
+pub struct MockChainClient {
+    inner: Arc<Mutex<Inner>>,
+    block_hashes: broadcast::Sender<BlockHash>,
+}
+
+impl Default for MockChainClient {
+    fn default() -> Self {
+        Self::new(MockChain::new())
+    }
+}
+
+impl MockChainClient {
+    /// Create a new client for the given chain.
+    pub fn new(chain: MockChain) -> Self {
+        let (block_hashes, _) = broadcast::channel(BLOCK_STREAM_CAPACITY);
+        let inner = Inner {
+            chain,
+            fee_rate: DEFAULT_FEE_RATE,
+            broadcasts: Vec::new(),
+        };
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            block_hashes,
+        }
+    }
+
+    /// Run the given function with the chain, then publish the hashes of
+    /// any blocks that it connected to the active chain on the block hash
+    /// streams.
+    pub fn with_chain<T>(&self, f: impl FnOnce(&mut MockChain) -> T) -> T {
+        let mut inner = self.inner.lock().unwrap();
+        let result = f(&mut inner.chain);
+        for block_hash in inner.chain.drain_connected_blocks() {
+            // There is nothing to do if nobody is listening.
+            let _ = self.block_hashes.send(block_hash);
+        }
+        result
+    }
+
+    /// Mine the given number of blocks on the chain tip.
+    pub fn generate_blocks(&self, num_blocks: u64) -> Vec<BlockHash> {
+        self.with_chain(|chain| chain.generate_blocks(num_blocks))
+    }
+
+    /// Set the fee rate returned by [`BitcoinInteract::estimate_fee_rate`].
+    pub fn set_fee_rate(&self, fee_rate: f64) {
+        self.inner.lock().unwrap().fee_rate = fee_rate;
+    }
+
+    /// A stream of the hashes of the blocks connected to the active chain
+    /// after this call, in the same form as
+    /// [`BitcoinCoreMessageStream::to_block_hash_stream`] returns them.
+    ///
+    /// [`BitcoinCoreMessageStream::to_block_hash_stream`]:
+    /// crate::bitcoin::zmq::BitcoinCoreMessageStream::to_block_hash_stream
+    pub fn block_hash_stream(
+        &self,
+    ) -> impl Stream<Item = Result<BlockHash, Error>> + Unpin + Send + 'static {
+        BroadcastStream::new(self.block_hashes.subscribe())
+            .filter_map(|block_hash| block_hash.ok().map(Ok))
+    }
+
+    /// Every transaction passed to
+    /// [`BitcoinInteract::broadcast_transaction`] so far, in order,
+    /// including the ones that the chain rejected.
+    pub fn broadcasts(&self) -> Vec<Broadcast> {
+        self.inner.lock().unwrap().broadcasts.clone()
+    }
+
+    fn chain<T>(&self, f: impl FnOnce(&MockChain) -> T) -> T {
+        f(&self.inner.lock().unwrap().chain)
+    }
+}
+
+/// The error that bitcoin-core returns when it rejects a transaction for
+/// the given reason.
+fn rejection_error(error: MockChainError) -> Error {
+    let code = match error {
+        MockChainError::MissingInputs => RPC_VERIFY_ERROR,
+        MockChainError::AlreadyKnown => RPC_VERIFY_ALREADY_IN_CHAIN,
+        _ => RPC_VERIFY_REJECTED,
+    };
+    let rpc_error = RpcError {
+        code,
+        message: error.to_string(),
+        data: None,
+    };
+    Error::BitcoinCoreRpc(BtcRpcError::JsonRpc(JsonRpcError::Rpc(rpc_error)))
+}
+
+fn script_json(script: &Script) -> serde_json::Value {
+    json!({
+        "asm": script.to_asm_string(),
+        "hex": script.to_hex_string(),
+    })
+}
+
+fn vin_json(tx_in: &TxIn) -> serde_json::Value {
+    let mut vin = if tx_in.previous_output.is_null() {
+        json!({ "coinbase": tx_in.script_sig.to_hex_string() })
+    } else {
+        json!({
+            "txid": tx_in.previous_output.txid,
+            "vout": tx_in.previous_output.vout,
+            "scriptSig": script_json(&tx_in.script_sig),
+        })
+    };
+    if !tx_in.witness.is_empty() {
+        let witness: Vec<String> = tx_in
+            .witness
+            .iter()
+            .map(|item| item.to_lower_hex_string())
+            .collect();
+        vin["txinwitness"] = json!(witness);
+    }
+    vin["sequence"] = json!(tx_in.sequence.0);
+    vin
+}
+
+fn vout_json(tx: &Transaction) -> serde_json::Value {
+    tx.output
+        .iter()
+        .enumerate()
+        .map(|(n, tx_out)| {
+            json!({
+                "value": tx_out.value.to_btc(),
+                "n": n,
+                "scriptPubKey": script_json(&tx_out.script_pubkey),
+            })
+        })
+        .collect()
+}
+
+/// Whether the mempool transaction, or any of its mempool ancestors,
+/// signals replaceability.
+fn signals_rbf(chain: &MockChain, txid: &Txid) -> bool {
+    std::iter::once(*txid)
+        .chain(chain.mempool_ancestors(txid))
+        .filter_map(|txid| chain.mempool_entry(&txid))
+        .any(|entry| entry.tx.input.iter().any(|tx_in| tx_in.sequence.is_rbf()))
+}
+
+fn mempool_entry_json(chain: &MockChain, txid: &Txid) -> Option<serde_json::Value> {
+    let entry = chain.mempool_entry(txid)?;
+
+    let sum = |txids: &[Txid]| {
+        txids
+            .iter()
+            .filter_map(|txid| chain.mempool_entry(txid))
+            .fold((entry.vsize, entry.fee), |(vsize, fee), entry| {
+                (vsize + entry.vsize, fee + entry.fee)
+            })
+    };
+    let ancestors = chain.mempool_ancestors(txid);
+    let descendants = chain.mempool_descendants(txid);
+    let (ancestor_size, ancestor_fees) = sum(&ancestors);
+    let (descendant_size, descendant_fees) = sum(&descendants);
+
+    let mut depends: Vec<Txid> = entry
+        .tx
+        .input
+        .iter()
+        .map(|tx_in| tx_in.previous_output.txid)
+        .filter(|parent| chain.mempool_entry(parent).is_some())
+        .collect();
+    depends.dedup();
+    let spent_by: Vec<Txid> = chain
+        .mempool()
+        .iter()
+        .filter(|child| {
+            child
+                .tx
+                .input
+                .iter()
+                .any(|tx_in| &tx_in.previous_output.txid == txid)
+        })
+        .map(|child| child.tx.compute_txid())
+        .collect();
+
+    Some(json!({
+        "vsize": entry.vsize,
+        "weight": entry.tx.weight().to_wu(),
+        "time": entry.time,
+        "height": entry.height,
+        "descendantcount": descendants.len() + 1,
+        "descendantsize": descendant_size,
+        "ancestorcount": ancestors.len() + 1,
+        "ancestorsize": ancestor_size,
+        "wtxid": entry.tx.compute_wtxid(),
+        "fees": {
+            "base": entry.fee.to_btc(),
+            "modified": entry.fee.to_btc(),
+            "ancestor": ancestor_fees.to_btc(),
+            "descendant": descendant_fees.to_btc(),
+        },
+        "depends": depends,
+        "spentby": spent_by,
+        "bip125-replaceable": signals_rbf(chain, txid),
+        "unbroadcast": false,
+    }))
+}
+
+fn tx_info_json(chain: &MockChain, txid: &Txid, block: &Block) -> Option<serde_json::Value> {
+    let block_hash = block.block_hash();
+    let tx = block.txdata.iter().find(|tx| &tx.compute_txid() == txid)?;
+
+    let vin: Vec<serde_json::Value> = tx
+        .input
+        .iter()
+        .map(|tx_in| {
+            let mut vin = vin_json(tx_in);
+            let outpoint = tx_in.previous_output;
+            // Like bitcoin-core, coinbase inputs do not have a prevout.
+            if let Some(prevout) = chain.output(&outpoint) {
+                let prev_tx = chain.transaction(&outpoint.txid)?;
+                let height = chain
+                    .confirming_block(&outpoint.txid)
+                    .and_then(|block_hash| chain.block_height(&block_hash))
+                    .unwrap_or_default();
+                vin["prevout"] = json!({
+                    "generated": prev_tx.is_coinbase(),
+                    "height": height,
+                    "value": prevout.value.to_btc(),
+                    "scriptPubKey": script_json(&prevout.script_pubkey),
+                });
+            }
+            Some(vin)
+        })
+        .collect::<Option<_>>()?;
+
+    Some(json!({
+        "in_active_chain": chain.is_in_active_chain(&block_hash),
+        "fee": chain.transaction_fee(txid).unwrap_or(Amount::ZERO).to_btc(),
+        "hex": bitcoin::consensus::encode::serialize_hex(tx),
+        "txid": txid,
+        "hash": tx.compute_wtxid(),
+        "size": tx.total_size(),
+        "vsize": tx.vsize(),
+        "vin": vin,
+        "vout": vout_json(tx),
+        "blockhash": block_hash,
+        "confirmations": chain.confirmations(&block_hash).unwrap_or_default(),
+        "blocktime": block.header.time,
+    }))
+}
+
+impl BitcoinInteract for MockChainClient {
+    async fn get_block(&self, block_hash: &BlockHash) -> Result<Option<Block>, Error> {
+        Ok(self.chain(|chain| chain.block(block_hash).cloned()))
+    }
+
+    async fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BitcoinBlockHeader>, Error> {
+        Ok(self.chain(|chain| {
+            let block = chain.block(block_hash)?;
+            Some(BitcoinBlockHeader {
+                hash: *block_hash,
+                height: chain.block_height(block_hash)?,
+                time: block.header.time as u64,
+                previous_block_hash: block.header.prev_blockhash,
+            })
+        }))
+    }
+
+    async fn get_tx(&self, txid: &Txid) -> Result<Option<GetTxResponse>, Error> {
+        Ok(self.chain(|chain| {
+            let tx = chain.transaction(txid)?.clone();
+            match chain.confirming_block(txid) {
+                Some(block_hash) => Some(GetTxResponse {
+                    tx,
+                    block_hash: Some(block_hash),
+                    confirmations: chain.confirmations(&block_hash),
+                    block_time: chain
+                        .block(&block_hash)
+                        .map(|block| block.header.time as u64),
+                }),
+                None if chain.mempool_entry(txid).is_some() => Some(GetTxResponse {
+                    tx,
+                    block_hash: None,
+                    confirmations: None,
+                    block_time: None,
+                }),
+                None => None,
+            }
+        }))
+    }
+
+    async fn get_tx_info(
+        &self,
+        txid: &Txid,
+        block_hash: &BlockHash,
+    ) -> Result<Option<BitcoinTxInfo>, Error> {
+        let tx_info = self.chain(|chain| tx_info_json(chain, txid, chain.block(block_hash)?));
+        tx_info
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(Error::JsonSerialize)
+    }
+
+    async fn estimate_fee_rate(&self) -> Result<f64, Error> {
+        Ok(self.inner.lock().unwrap().fee_rate)
+    }
+
+    async fn broadcast_transaction(&self, tx: &Transaction) -> Result<(), Error> {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            let chain_tip = inner.chain.tip();
+            inner
+                .broadcasts
+                .push(Broadcast { tx: tx.clone(), chain_tip });
+        }
+        self.with_chain(|chain| chain.broadcast(tx.clone()))
+            .map(|_| ())
+            .map_err(rejection_error)
+    }
+
+    async fn find_mempool_transactions_spending_output(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<Vec<Txid>, Error> {
+        Ok(self.chain(|chain| chain.mempool_spending(outpoint)))
+    }
+
+    async fn find_mempool_descendants(&self, txid: &Txid) -> Result<Vec<Txid>, Error> {
+        Ok(self.chain(|chain| chain.mempool_descendants(txid)))
+    }
+
+    async fn get_transaction_output(
+        &self,
+        outpoint: &OutPoint,
+        include_mempool: bool,
+    ) -> Result<Option<GetTxOutResult>, Error> {
+        let tx_out = self.chain(|chain| {
+            if include_mempool && !chain.mempool_spending(outpoint).is_empty() {
+                return None;
+            }
+            let (tx_out, confirmations, coinbase) = match chain.utxo(outpoint) {
+                Some(utxo) => {
+                    let confirmations = chain.tip_height() - utxo.height + 1;
+                    (&utxo.output, confirmations, utxo.is_coinbase)
+                }
+                None if include_mempool && chain.mempool_entry(&outpoint.txid).is_some() => {
+                    (chain.output(outpoint)?, 0, false)
+                }
+                None => return None,
+            };
+            Some(json!({
+                "bestblock": chain.tip(),
+                "confirmations": confirmations,
+                "value": tx_out.value.to_btc(),
+                "scriptPubKey": script_json(&tx_out.script_pubkey),
+                "coinbase": coinbase,
+            }))
+        });
+        tx_out
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(Error::JsonSerialize)
+    }
+
+    async fn get_transaction_fee(
+        &self,
+        txid: &Txid,
+        lookup_hint: Option<TransactionLookupHint>,
+    ) -> Result<GetTransactionFeeResult, Error> {
+        let fee_and_vsize = self.chain(|chain| {
+            let mempool = || {
+                let entry = chain.mempool_entry(txid)?;
+                Some((entry.fee, entry.vsize))
+            };
+            let confirmed = || {
+                chain.confirming_block(txid)?;
+                let tx = chain.transaction(txid)?;
+                Some((chain.transaction_fee(txid)?, tx.vsize() as u64))
+            };
+            match lookup_hint {
+                None => confirmed().or_else(mempool),
+                Some(TransactionLookupHint::Confirmed) => confirmed(),
+                Some(TransactionLookupHint::Mempool) => mempool(),
+            }
+        });
+
+        let (fee, vsize) = fee_and_vsize.ok_or(Error::BitcoinTxMissing(*txid, None))?;
+        if vsize == 0 {
+            return Err(Error::DivideByZero);
+        }
+
+        Ok(GetTransactionFeeResult {
+            fee: fee.to_sat(),
+            fee_rate: fee.to_sat() as f64 / vsize as f64,
+            vsize,
+        })
+    }
+
+    async fn get_mempool_entry(&self, txid: &Txid) -> Result<Option<GetMempoolEntryResult>, Error> {
+        self.chain(|chain| mempool_entry_json(chain, txid))
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(Error::JsonSerialize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::ScriptBuf;
+    use bitcoin::WPubkeyHash;
+    use tokio_stream::StreamExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rpc_results_match_the_chain() {
+        let client = MockChainClient::default();
+        client.generate_blocks(110);
+        let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20]));
+        let outpoint = client
+            .with_chain(|chain| chain.send_to(script_pubkey.clone(), Amount::from_sat(50_000)))
+            .unwrap();
+
+        let entry = client
+            .get_mempool_entry(&outpoint.txid)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.fees.base, Amount::from_sat(1_000));
+        assert_eq!(entry.height, 110);
+        let fee = client
+            .get_transaction_fee(&outpoint.txid, Some(TransactionLookupHint::Mempool))
+            .await
+            .unwrap();
+        assert_eq!(fee.fee, 1_000);
+        let tx_out = client
+            .get_transaction_output(&outpoint, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx_out.confirmations, 0);
+        assert!(client
+            .get_transaction_output(&outpoint, false)
+            .await
+            .unwrap()
+            .is_none());
+
+        let block_hash = client.generate_blocks(1)[0];
+        assert!(client
+            .get_mempool_entry(&outpoint.txid)
+            .await
+            .unwrap()
+            .is_none());
+
+        let tx_info = client
+            .get_tx_info(&outpoint.txid, &block_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(tx_info.in_active_chain);
+        assert_eq!(tx_info.fee, Amount::from_sat(1_000));
+        assert_eq!(tx_info.confirmations, 1);
+        assert_eq!(tx_info.vout[0].value, Amount::from_sat(50_000));
+        assert!(tx_info.vin[0].prevout.generated);
+
+        let tx_out = client
+            .get_transaction_output(&outpoint, false)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx_out.value, Amount::from_sat(50_000));
+        assert_eq!(tx_out.confirmations, 1);
+
+        let header = client.get_block_header(&block_hash).await.unwrap().unwrap();
+        assert_eq!(header.height, 111);
+    }
+
+    #[tokio::test]
+    async fn rejected_transactions_return_bitcoin_core_errors() {
+        let client = MockChainClient::default();
+        client.generate_blocks(110);
+        let outpoint = client
+            .with_chain(|chain| chain.send_to(ScriptBuf::new_op_return([0; 8]), Amount::ZERO))
+            .unwrap();
+        let tx = client
+            .with_chain(|chain| chain.transaction(&outpoint.txid).cloned())
+            .unwrap();
+
+        let error = client.broadcast_transaction(&tx).await.unwrap_err();
+        match error {
+            Error::BitcoinCoreRpc(BtcRpcError::JsonRpc(JsonRpcError::Rpc(RpcError {
+                code,
+                message,
+                ..
+            }))) => {
+                assert_eq!(code, RPC_VERIFY_REJECTED);
+                assert_eq!(message, "txn-already-in-mempool");
+            }
+            error => panic!("unexpected error: {error}"),
+        }
+
+        // Rejected transactions are recorded all the same.
+        let chain_tip = client.with_chain(|chain| chain.tip());
+        assert_eq!(client.broadcasts(), vec![Broadcast { tx, chain_tip }]);
+    }
+
+    #[tokio::test]
+    async fn block_hash_stream_follows_reorgs() {
+        let client = MockChainClient::default();
+        client.generate_blocks(5);
+        let mut stream = client.block_hash_stream();
+
+        let mined = client.generate_blocks(2);
+        let reorged = client.with_chain(|chain| chain.reorg(2));
+
+        let expected: Vec<BlockHash> = mined.into_iter().chain(reorged).collect();
+        for block_hash in expected {
+            assert_eq!(stream.next().await.unwrap().unwrap(), block_hash);
+        }
+    }
+}
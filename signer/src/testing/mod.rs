@@ -8,8 +8,10 @@ pub mod btc;
 pub mod context;
 pub mod dummy;
 pub mod message;
+pub mod mock_chain;
 pub mod network;
 pub mod request_decider;
+pub mod simulation;
 pub mod stacks;
 pub mod storage;
 pub mod transaction_coordinator;
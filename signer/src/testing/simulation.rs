@@ -0,0 +1,719 @@
+//! A simulation of a whole signer set in one process.
+//!
+//! A [`Simulation`] runs every signer of the set with all of its event
+//! loops, the block observer, request decider, transaction signer and
+//! transaction coordinator, each signer with its own [`SharedStore`]. The
+//! signers share one [`MockChainClient`] for bitcoin, one
+//! [`SimulatedStacks`] and one [`SimulatedEmily`], and talk over a
+//! [`WanNetwork`] whose link delays, message loss and partitions the test
+//! controls. Tests can also crash and restart signers and mine or
+//! reorganize bitcoin blocks at any point.
+//!
+//! Simulations are meant to run on tokio's paused clock, with
+//! `#[tokio::test(start_paused = true)]`. Time then only moves forward
+//! when every task is waiting on a timer, so timeouts in the signers take
+//! no wall-clock time.
+//!
+//! Everything random in a simulation is drawn from its seed: the keys of
+//! the signers, the random number generators of their transaction
+//! signers, the network, and the block scheduler. The scheduler mines a
+//! bitcoin block at seeded intervals while virtual time passes, the way
+//! the real chain keeps producing blocks, so tests don't have to pick
+//! the blocks on which something should happen. A block whose coordinator
+//! is crashed or cut off just passes, and the next one gets another
+//! chance.
+//!
+//! Once the scenario of a test has played out, [`Simulation::violations`]
+//! checks the safety properties in [`properties`] against the bitcoin
+//! chain and the Stacks transactions, and [`Simulation::run_until`] is
+//! how tests assert liveness within a virtual time bound.
+
+pub mod clients;
+pub mod properties;
+
+use std::collections::HashSet;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use bitcoin::Amount;
+use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use clarity::types::chainstate::StacksAddress;
+use clarity::vm::types::PrincipalData;
+use lru::LruCache;
+use rand::rngs::StdRng;
+use rand::Rng as _;
+use rand::SeedableRng as _;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositScriptInputs;
+use sbtc::deposits::ReclaimScriptInputs;
+use sbtc::testing::mock_chain::MockChain;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::block_observer::BlockObserver;
+use crate::config::Settings;
+use crate::context::Context as _;
+use crate::error::Error;
+use crate::keys::PrivateKey;
+use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
+use crate::network::in_memory2::WanNetwork;
+use crate::request_decider::RequestDeciderEventLoop;
+use crate::storage::in_memory::SharedStore;
+use crate::storage::in_memory::Store;
+use crate::storage::model;
+use crate::storage::DbRead as _;
+use crate::storage::DbWrite as _;
+use crate::testing::context::*;
+use crate::testing::mock_chain::MockChainClient;
+use crate::transaction_coordinator::TxCoordinatorEventLoop;
+use crate::transaction_signer::TxSignerEventLoop;
+use crate::MAX_SIGNER_STATE_MACHINES;
+
+use clients::SbtcContractCall;
+use clients::SimulatedEmily;
+use clients::SimulatedStacks;
+use properties::Violation;
+
+/// The context of a signer in a simulation.
+pub type SimulatedContext =
+    TestContext<SharedStore, MockChainClient, SimulatedStacks, SimulatedEmily>;
+
+/// How often the simulation checks the block schedule and the condition of
+/// [`Simulation::run_until`] while virtual time passes.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The parameters of a [`Simulation`].
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// The number of signers in the signer set.
+    pub num_signers: usize,
+    /// The number of signatures required by the signer set.
+    pub signatures_required: u16,
+    /// The seed for the keys of the signers, the random number generators
+    /// of their transaction signers, the network, and the block scheduler.
+    pub seed: u64,
+    /// The number of bitcoin blocks mined before the signers start. The
+    /// signers only observe the blocks mined after these. It has to be
+    /// more than the coinbase maturity for the chain to be able to fund
+    /// deposits and donations.
+    pub initial_blocks: u64,
+    /// The average time between the bitcoin blocks that the simulation
+    /// mines while virtual time passes, or `None` to only mine the blocks
+    /// that the test asks for. The time to each block is drawn uniformly
+    /// from between half and one and a half times this.
+    pub block_interval: Option<Duration>,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            num_signers: 3,
+            signatures_required: 2,
+            seed: 0,
+            initial_blocks: 101,
+            block_interval: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
+/// Decides when the simulation mines a bitcoin block on its own.
+#[derive(Debug)]
+struct BlockScheduler {
+    rng: StdRng,
+    interval: Option<Duration>,
+    next_block_at: Option<Instant>,
+}
+
+impl BlockScheduler {
+    /// Whether a block is due, scheduling the next one if it is. The first
+    /// block is scheduled on the first call.
+    fn is_block_due(&mut self) -> bool {
+        let Some(interval) = self.interval else {
+            return false;
+        };
+        let now = Instant::now();
+        match self.next_block_at {
+            Some(next_block_at) if now >= next_block_at => {}
+            Some(_) => return false,
+            None => {
+                self.next_block_at = Some(now + self.time_to_block(interval));
+                return false;
+            }
+        }
+        self.next_block_at = Some(now + self.time_to_block(interval));
+        true
+    }
+
+    fn time_to_block(&mut self, interval: Duration) -> Duration {
+        self.rng.gen_range(interval / 2..=interval * 3 / 2)
+    }
+}
+
+/// A signer of a [`Simulation`].
+pub struct SimulatedSigner {
+    /// The private key of the signer.
+    pub private_key: PrivateKey,
+    /// The database of the signer, which survives crashes.
+    pub storage: SharedStore,
+    /// The context of the signer while it is running.
+    context: Option<SimulatedContext>,
+    /// The event loops of the signer while it is running.
+    tasks: Vec<JoinHandle<Result<(), Error>>>,
+    /// The number of times the signer was started.
+    starts: u64,
+}
+
+impl SimulatedSigner {
+    /// The public key of the signer.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_private_key(&self.private_key)
+    }
+
+    /// The context of the signer, if it is running.
+    pub fn context(&self) -> Option<&SimulatedContext> {
+        self.context.as_ref()
+    }
+
+    /// Whether the signer is running.
+    pub fn is_running(&self) -> bool {
+        self.context.is_some()
+    }
+}
+
+/// A whole signer set running against simulated chains and a simulated
+/// network. See the [module documentation](self) for an overview.
+pub struct Simulation {
+    /// The bitcoin chain.
+    pub bitcoin: MockChainClient,
+    /// The Stacks chain.
+    pub stacks: SimulatedStacks,
+    /// The Emily API.
+    pub emily: SimulatedEmily,
+    /// The network between the signers. Each signer is connected under
+    /// its index in the signer set.
+    pub network: WanNetwork,
+    signers: Vec<SimulatedSigner>,
+    settings: Settings,
+    seed: u64,
+    blocks: BlockScheduler,
+    stacks_tip: Option<model::StacksBlock>,
+    confirmed_calls: Vec<SbtcContractCall>,
+}
+
+impl Simulation {
+    /// Set up a simulation with the given parameters. The signers are
+    /// not running until they are started.
+    pub fn new(config: SimulationConfig) -> Self {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let private_keys: Vec<PrivateKey> = (0..config.num_signers)
+            .map(|_| PrivateKey::new(&mut rng))
+            .collect();
+
+        let mut chain = MockChain::new();
+        chain.generate_blocks(config.initial_blocks);
+        let start_height = chain.tip_height() + 1;
+        let bitcoin = MockChainClient::new(chain);
+
+        let mut settings = Settings::new_from_default_config().unwrap();
+        settings.signer.bootstrap_signing_set = private_keys
+            .iter()
+            .map(PublicKey::from_private_key)
+            .collect();
+        settings.signer.bootstrap_signatures_required = config.signatures_required;
+        settings.signer.sbtc_bitcoin_start_height = Some(start_height);
+        settings.signer.bitcoin_processing_delay = Duration::ZERO;
+
+        let signers = private_keys
+            .into_iter()
+            .map(|private_key| SimulatedSigner {
+                private_key,
+                storage: Store::new_shared(),
+                context: None,
+                tasks: Vec::new(),
+                starts: 0,
+            })
+            .collect();
+
+        let network = WanNetwork::new();
+        network.set_seed(config.seed);
+
+        Self {
+            stacks: SimulatedStacks::new(bitcoin.clone()),
+            emily: SimulatedEmily::default(),
+            network,
+            bitcoin,
+            signers,
+            settings,
+            seed: config.seed,
+            blocks: BlockScheduler {
+                rng,
+                interval: config.block_interval,
+                next_block_at: None,
+            },
+            stacks_tip: None,
+            confirmed_calls: Vec::new(),
+        }
+    }
+
+    /// The signers of the simulation.
+    pub fn signers(&self) -> &[SimulatedSigner] {
+        &self.signers
+    }
+
+    /// Start every signer that is not running.
+    pub async fn start_all(&mut self) {
+        for index in 0..self.signers.len() {
+            if !self.signers[index].is_running() {
+                self.start(index).await;
+            }
+        }
+    }
+
+    /// Start the signer with the given index, or restart it after a
+    /// crash. The signer keeps its database, but everything else starts
+    /// from scratch, and it only hears about the bitcoin blocks that are
+    /// mined after this.
+    pub async fn start(&mut self, index: usize) {
+        assert!(
+            !self.signers[index].is_running(),
+            "signer {index} is running"
+        );
+
+        let signer = &mut self.signers[index];
+        let private_key = signer.private_key;
+        let ctx = TestContext::builder()
+            .with_storage(signer.storage.clone())
+            .with_bitcoin_client(self.bitcoin.clone())
+            .with_stacks_client(self.stacks.clone())
+            .with_emily_client(self.emily.clone())
+            .with_settings(self.settings.clone())
+            .modify_settings(|settings| settings.signer.private_key = private_key)
+            .build();
+        // The simulated Stacks chain has no contracts to deploy.
+        ctx.state().set_sbtc_contracts_deployed();
+
+        let network = self.network.connect_as(index as u8, &ctx);
+        let config = ctx.config().signer.clone();
+        let max_state_machines = NonZeroUsize::new(MAX_SIGNER_STATE_MACHINES as usize).unwrap();
+        let rng_seed = self.seed ^ ((index as u64) << 32) ^ signer.starts;
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: self.bitcoin.block_hash_stream(),
+        };
+        let request_decider = RequestDeciderEventLoop {
+            network: network.spawn(),
+            context: ctx.clone(),
+            context_window: config.context_window,
+            blocklist_checker: Some(()),
+            signer_private_key: private_key,
+        };
+        let tx_signer = TxSignerEventLoop {
+            network: network.spawn(),
+            context: ctx.clone(),
+            context_window: config.context_window,
+            threshold: config.bootstrap_signatures_required.into(),
+            rng: StdRng::seed_from_u64(rng_seed),
+            signer_private_key: private_key,
+            wsts_state_machines: LruCache::new(max_state_machines),
+            dkg_verification_state_machines: LruCache::new(max_state_machines),
+            dkg_begin_pause: config.dkg_begin_pause.map(Duration::from_secs),
+        };
+        let tx_coordinator = TxCoordinatorEventLoop {
+            network: network.spawn(),
+            context: ctx.clone(),
+            context_window: config.context_window,
+            private_key,
+            signing_round_max_duration: config.signer_round_max_duration,
+            bitcoin_presign_request_max_duration: config.bitcoin_presign_request_max_duration,
+            threshold: config.bootstrap_signatures_required,
+            dkg_max_duration: config.dkg_max_duration,
+            is_epoch3: false,
+        };
+
+        signer.tasks = vec![
+            tokio::spawn(block_observer.run()),
+            tokio::spawn(request_decider.run()),
+            tokio::spawn(tx_signer.run()),
+            tokio::spawn(tx_coordinator.run()),
+        ];
+        signer.context = Some(ctx);
+        signer.starts += 1;
+
+        // Let the event loops subscribe to the signal channel before
+        // anything happens.
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    /// Crash the signer with the given index. Its event loops stop where
+    /// they are, and the messages for it are lost until it is started
+    /// again.
+    pub fn crash(&mut self, index: usize) {
+        let signer = &mut self.signers[index];
+        for task in signer.tasks.drain(..) {
+            task.abort();
+        }
+        signer.context = None;
+        self.network.disconnect(index as u8);
+    }
+
+    /// Mine the given number of bitcoin blocks, along with a Stacks block
+    /// for each of them. This does not move the block schedule.
+    pub async fn mine_blocks(&mut self, num_blocks: u64) -> Vec<BlockHash> {
+        let block_hashes = self.bitcoin.generate_blocks(num_blocks);
+        self.mine_stacks_blocks(&block_hashes).await;
+        block_hashes
+    }
+
+    /// Replace the top `depth` bitcoin blocks with `depth + 1` empty
+    /// blocks, along with a Stacks block for each of the new blocks.
+    pub async fn reorg(&mut self, depth: u64) -> Vec<BlockHash> {
+        let block_hashes = self.bitcoin.with_chain(|chain| chain.reorg(depth));
+        self.mine_stacks_blocks(&block_hashes).await;
+        block_hashes
+    }
+
+    /// Add a Stacks block anchored to each of the given bitcoin blocks to
+    /// the database of every signer, with the Stacks transactions that
+    /// were submitted since the last one in the first block.
+    ///
+    /// This stands in for the Stacks node and its event observer, which
+    /// retries until the signer receives each block. That is why crashed
+    /// signers get the blocks too.
+    async fn mine_stacks_blocks(&mut self, bitcoin_blocks: &[BlockHash]) {
+        for (i, bitcoin_block) in bitcoin_blocks.iter().enumerate() {
+            let txs = if i == 0 {
+                self.stacks.confirm_mempool()
+            } else {
+                Vec::new()
+            };
+
+            let block_height = self
+                .stacks_tip
+                .as_ref()
+                .map_or(0, |tip| tip.block_height + 1);
+            let mut block_hash = [0; 32];
+            block_hash[..8].copy_from_slice(&block_height.to_be_bytes());
+            let block = model::StacksBlock {
+                block_hash: block_hash.into(),
+                block_height,
+                parent_hash: self
+                    .stacks_tip
+                    .as_ref()
+                    .map_or([0; 32].into(), |tip| tip.block_hash),
+                bitcoin_anchor: (*bitcoin_block).into(),
+            };
+
+            let calls: Vec<(model::StacksTxId, SbtcContractCall)> = txs
+                .iter()
+                .filter_map(|tx| Some((tx.txid().into(), SbtcContractCall::from_tx(tx)?)))
+                .collect();
+            for signer in &self.signers {
+                let db = &signer.storage;
+                db.write_stacks_block(&block).await.unwrap();
+                for tx in &txs {
+                    let tx = model::StacksTransaction {
+                        txid: tx.txid().into(),
+                        block_hash: block.block_hash,
+                    };
+                    db.write_stacks_transaction(&tx).await.unwrap();
+                }
+                for (txid, call) in &calls {
+                    self.write_event(db, *txid, &block, call).await;
+                }
+            }
+
+            self.confirmed_calls
+                .extend(calls.into_iter().map(|(_, call)| call));
+            self.stacks_tip = Some(block);
+        }
+    }
+
+    /// Write the event that the sBTC contracts emit for the contract call
+    /// to the database.
+    async fn write_event(
+        &self,
+        db: &SharedStore,
+        txid: model::StacksTxId,
+        block: &model::StacksBlock,
+        call: &SbtcContractCall,
+    ) {
+        match call {
+            SbtcContractCall::RotateKeys {
+                new_keys,
+                aggregate_key,
+                signatures_required,
+            } => {
+                let event = model::RotateKeysTransaction {
+                    txid,
+                    address: PrincipalData::from(self.settings.signer.deployer).into(),
+                    aggregate_key: *aggregate_key,
+                    signer_set: new_keys.clone(),
+                    signatures_required: *signatures_required,
+                };
+                db.write_rotate_keys_transaction(&event).await.unwrap();
+            }
+            SbtcContractCall::CompleteDeposit {
+                outpoint,
+                amount,
+                sweep_block_hash,
+                sweep_block_height,
+                sweep_txid,
+            } => {
+                let event = model::CompletedDepositEvent {
+                    txid,
+                    block_id: block.block_hash,
+                    amount: *amount,
+                    outpoint: *outpoint,
+                    sweep_block_hash: *sweep_block_hash,
+                    sweep_block_height: *sweep_block_height,
+                    sweep_txid: *sweep_txid,
+                };
+                db.write_completed_deposit_event(&event).await.unwrap();
+            }
+        }
+    }
+
+    /// Mine a block if one is due, then let virtual time pass until the
+    /// next check or the deadline, whichever comes first.
+    async fn tick(&mut self, deadline: Instant) {
+        if self.blocks.is_block_due() {
+            self.mine_blocks(1).await;
+        }
+        let next_check = Instant::now() + CHECK_INTERVAL;
+        tokio::time::sleep_until(next_check.min(deadline)).await;
+    }
+
+    /// Let the given amount of virtual time pass, mining blocks as they
+    /// are due.
+    pub async fn run_for(&mut self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            self.tick(deadline).await;
+        }
+    }
+
+    /// Let virtual time pass, mining blocks as they are due, until the
+    /// given condition holds, returning whether it did within the
+    /// timeout.
+    pub async fn run_until<F>(&mut self, timeout: Duration, mut condition: F) -> bool
+    where
+        F: FnMut(&Simulation) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if condition(self) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            self.tick(deadline).await;
+        }
+    }
+
+    /// The aggregate key that every signer has verified DKG shares for,
+    /// if they all agree on one.
+    pub async fn agreed_aggregate_key(&self) -> Option<PublicKey> {
+        let mut keys = HashSet::new();
+        for signer in &self.signers {
+            let shares = signer
+                .storage
+                .get_latest_verified_dkg_shares()
+                .await
+                .unwrap()?;
+            keys.insert(shares.aggregate_key);
+        }
+        if keys.len() == 1 {
+            keys.into_iter().next()
+        } else {
+            None
+        }
+    }
+
+    /// Whether a `complete-deposit` contract call for the given deposit
+    /// has been confirmed on the Stacks chain.
+    pub fn is_minted(&self, deposit: &OutPoint) -> bool {
+        self.confirmed_calls.iter().any(|call| {
+            matches!(call, SbtcContractCall::CompleteDeposit { outpoint, .. } if outpoint == deposit)
+        })
+    }
+
+    /// Send the given amount to the signers' `scriptPubKey` for the given
+    /// aggregate key. The transaction goes into the mempool.
+    pub fn donate(&self, aggregate_key: &PublicKey, amount: Amount) -> OutPoint {
+        let script_pubkey = aggregate_key.signers_script_pubkey();
+        self.bitcoin
+            .with_chain(|chain| chain.send_to(script_pubkey, amount))
+            .unwrap()
+    }
+
+    /// Create a deposit of the given amount to the signers with the given
+    /// aggregate key and register it with Emily. The deposit transaction
+    /// goes into the mempool.
+    pub fn deposit(&self, aggregate_key: &PublicKey, amount: u64, max_fee: u64) -> OutPoint {
+        let deposit_inputs = DepositScriptInputs {
+            signers_public_key: (*aggregate_key).into(),
+            recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+            max_fee,
+        };
+        let reclaim_inputs = ReclaimScriptInputs::try_new(100, ScriptBuf::new()).unwrap();
+        let deposit_script = deposit_inputs.deposit_script();
+        let reclaim_script = reclaim_inputs.reclaim_script();
+
+        let script_pubkey =
+            sbtc::deposits::to_script_pubkey(deposit_script.clone(), reclaim_script.clone());
+        let outpoint = self
+            .bitcoin
+            .with_chain(|chain| chain.send_to(script_pubkey, Amount::from_sat(amount + max_fee)))
+            .unwrap();
+
+        self.emily.add_deposit(CreateDepositRequest {
+            outpoint,
+            reclaim_script,
+            deposit_script,
+        });
+        outpoint
+    }
+
+    /// Check the safety properties of the signers, returning every
+    /// violation.
+    pub async fn violations(&self) -> Vec<Violation> {
+        let mut signer_scripts: HashSet<ScriptBuf> = HashSet::new();
+        for signer in &self.signers {
+            let shares = signer
+                .storage
+                .get_latest_encrypted_dkg_shares()
+                .await
+                .unwrap();
+            signer_scripts
+                .extend(shares.map(|shares| shares.aggregate_key.signers_script_pubkey()));
+        }
+
+        let broadcasts = self.bitcoin.broadcasts();
+        let mut violations = self.stacks.violations();
+        violations.extend(self.bitcoin.with_chain(|chain| {
+            let mut violations = properties::check_signer_utxo(chain, &signer_scripts);
+            violations.extend(properties::check_conflicting_broadcasts(
+                chain,
+                &broadcasts,
+                &signer_scripts,
+            ));
+            violations
+        }));
+        violations.extend(properties::check_duplicate_mints(&self.confirmed_calls));
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::in_memory2::Latency;
+    use crate::network::in_memory2::LinkConditions;
+
+    use super::*;
+
+    /// The virtual time that the signers get to reach a goal, which spans
+    /// dozens of bitcoin blocks.
+    const TIMEOUT: Duration = Duration::from_secs(6 * 3600);
+
+    /// Let the signers run until they agree on an aggregate key and the
+    /// key rotation is confirmed on Stacks.
+    async fn run_dkg(sim: &mut Simulation) -> PublicKey {
+        let rotated = sim
+            .run_until(TIMEOUT, |sim| sim.stacks.aggregate_key().is_some())
+            .await;
+        assert!(rotated, "the key rotation was not confirmed");
+
+        let aggregate_key = sim.agreed_aggregate_key().await.expect("signers disagree");
+        assert_eq!(sim.stacks.aggregate_key(), Some(aggregate_key));
+        aggregate_key
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dkg_completes_with_delayed_and_reordered_messages() {
+        let mut sim = Simulation::new(SimulationConfig { seed: 1, ..Default::default() });
+        sim.network.set_default_link(LinkConditions {
+            latency: Latency::Uniform {
+                min: Duration::from_millis(10),
+                max: Duration::from_millis(500),
+            },
+            reordering: true,
+            ..Default::default()
+        });
+        sim.start_all().await;
+
+        run_dkg(&mut sim).await;
+
+        assert_eq!(sim.violations().await, Vec::new());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dkg_completes_after_a_signer_restarts() {
+        let mut sim = Simulation::new(SimulationConfig { seed: 2, ..Default::default() });
+        sim.start_all().await;
+
+        // DKG needs every signer, so nothing happens while one is down.
+        sim.crash(2);
+        sim.run_for(Duration::from_secs(3600)).await;
+        assert!(sim.agreed_aggregate_key().await.is_none());
+
+        sim.start(2).await;
+        run_dkg(&mut sim).await;
+
+        assert_eq!(sim.violations().await, Vec::new());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn deposit_is_minted_once_after_a_reorg_of_the_sweep() {
+        let mut sim = Simulation::new(SimulationConfig { seed: 3, ..Default::default() });
+        sim.start_all().await;
+        let aggregate_key = run_dkg(&mut sim).await;
+
+        sim.donate(&aggregate_key, Amount::from_sat(1_000_000));
+        let deposit = sim.deposit(&aggregate_key, 100_000, 20_000);
+
+        // Wait for the sweep to show up in the mempool, then confirm it
+        // and immediately reorganize it out of the chain.
+        let swept = sim
+            .run_until(TIMEOUT, |sim| {
+                sim.bitcoin
+                    .with_chain(|chain| !chain.mempool_spending(&deposit).is_empty())
+            })
+            .await;
+        assert!(swept, "the deposit was not swept");
+        sim.mine_blocks(1).await;
+        sim.reorg(1).await;
+
+        let minted = sim.run_until(TIMEOUT, |sim| sim.is_minted(&deposit)).await;
+        assert!(minted, "the deposit was not minted");
+
+        // A few more blocks to give the signers a chance to mint it twice.
+        sim.run_for(Duration::from_secs(3600)).await;
+
+        assert_eq!(sim.violations().await, Vec::new());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partitioned_minority_does_not_stop_the_majority_from_signing() {
+        let mut sim = Simulation::new(SimulationConfig { seed: 4, ..Default::default() });
+        sim.start_all().await;
+        let aggregate_key = run_dkg(&mut sim).await;
+
+        sim.donate(&aggregate_key, Amount::from_sat(1_000_000));
+        let deposit = sim.deposit(&aggregate_key, 100_000, 20_000);
+
+        // Two of the three signers are enough to sweep and mint. Blocks
+        // whose coordinator is the cut off signer go by without a sweep.
+        sim.network.add_partition("minority", [2]);
+        sim.network.enable_partition("minority");
+        let minted = sim.run_until(TIMEOUT, |sim| sim.is_minted(&deposit)).await;
+        sim.network.disable_partition("minority");
+
+        assert!(minted, "the deposit was not minted");
+        assert_eq!(sim.violations().await, Vec::new());
+    }
+}
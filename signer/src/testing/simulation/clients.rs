@@ -0,0 +1,424 @@
+//! Stacks and Emily clients for the signers of a [`Simulation`].
+//!
+//! All signers of a simulation share one [`SimulatedStacks`] and one
+//! [`SimulatedEmily`], the same way that the signers of a real network
+//! talk to the same Stacks chain and the same Emily deployment.
+//!
+//! [`Simulation`]: super::Simulation
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use bitcoin::hashes::Hash as _;
+use bitcoin::Amount;
+use bitcoin::OutPoint;
+use blockstack_lib::chainstate::burn::ConsensusHash;
+use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
+use blockstack_lib::chainstate::stacks::StacksTransaction;
+use blockstack_lib::chainstate::stacks::TransactionPayload;
+use blockstack_lib::clarity::vm::types::BuffData;
+use blockstack_lib::clarity::vm::types::ListData;
+use blockstack_lib::clarity::vm::types::PrincipalData;
+use blockstack_lib::clarity::vm::types::SequenceData;
+use blockstack_lib::clarity::vm::Value as ClarityValue;
+use blockstack_lib::net::api::getcontractsrc::ContractSrcResponse;
+use blockstack_lib::net::api::getinfo::RPCPeerInfoData;
+use blockstack_lib::net::api::getpoxinfo::RPCPoxEpoch;
+use blockstack_lib::net::api::getpoxinfo::RPCPoxInfoData;
+use blockstack_lib::net::api::getsortition::SortitionInfo;
+use blockstack_lib::net::api::gettenureinfo::RPCGetTenureInfo;
+use blockstack_lib::types::chainstate::StacksAddress;
+use blockstack_lib::types::chainstate::StacksBlockId;
+use clarity::vm::costs::ExecutionCost;
+use emily_client::models::Chainstate;
+use emily_client::models::CreateWithdrawalRequestBody;
+use emily_client::models::UpdateDepositsResponse;
+use emily_client::models::UpdateWithdrawalsResponse;
+use emily_client::models::Withdrawal;
+use sbtc::deposits::CreateDepositRequest;
+
+use crate::bitcoin::utxo::UnsignedTransaction;
+use crate::context::SbtcLimits;
+use crate::emily_client::EmilyInteract;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::stacks::api::AccountInfo;
+use crate::stacks::api::FeePriority;
+use crate::stacks::api::StacksInteract;
+use crate::stacks::api::SubmitTxResponse;
+use crate::stacks::api::TenureBlocks;
+use crate::stacks::contracts::AsContractCall as _;
+use crate::stacks::contracts::AsTxPayload;
+use crate::stacks::contracts::CompleteDepositV1;
+use crate::stacks::contracts::ProtocolRole;
+use crate::stacks::contracts::RotateKeysV1;
+use crate::stacks::wallet::SignerWallet;
+use crate::storage::model;
+use crate::testing::block_observer::get_node_info_data;
+use crate::testing::block_observer::get_pox_info_data;
+use crate::testing::mock_chain::MockChainClient;
+
+use super::properties;
+use super::properties::Violation;
+
+/// The fee that [`SimulatedStacks`] estimates for every transaction.
+const SIMULATED_STACKS_FEE: u64 = 100_000;
+
+/// A contract call of the sBTC protocol, decoded from the arguments of a
+/// Stacks transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SbtcContractCall {
+    /// A call to `rotate-keys-wrapper`.
+    RotateKeys {
+        /// The public keys of the new signer set.
+        new_keys: Vec<PublicKey>,
+        /// The new aggregate key.
+        aggregate_key: PublicKey,
+        /// The number of signatures that the new signer set requires.
+        signatures_required: u16,
+    },
+    /// A call to `complete-deposit-wrapper`.
+    CompleteDeposit {
+        /// The outpoint of the deposit.
+        outpoint: OutPoint,
+        /// The amount of sBTC to mint.
+        amount: u64,
+        /// The bitcoin block that confirmed the sweep transaction.
+        sweep_block_hash: model::BitcoinBlockHash,
+        /// The height of that block.
+        sweep_block_height: u64,
+        /// The sweep transaction that fulfilled the deposit.
+        sweep_txid: model::BitcoinTxId,
+    },
+}
+
+impl SbtcContractCall {
+    /// Decode the sBTC contract call in the given transaction, if it is
+    /// one that the simulation knows about.
+    pub fn from_tx(tx: &StacksTransaction) -> Option<Self> {
+        let TransactionPayload::ContractCall(call) = &tx.payload else {
+            return None;
+        };
+        let args = call.function_args.as_slice();
+        let function_name = call.function_name.as_str();
+
+        if function_name == RotateKeysV1::FUNCTION_NAME {
+            let [ClarityValue::Sequence(SequenceData::List(ListData { data: keys, .. })), key, ClarityValue::UInt(signatures_required)] =
+                args
+            else {
+                return None;
+            };
+            let new_keys = keys
+                .iter()
+                .map(|key| PublicKey::from_slice(buffer(key)?).ok())
+                .collect::<Option<_>>()?;
+            Some(Self::RotateKeys {
+                new_keys,
+                aggregate_key: PublicKey::from_slice(buffer(key)?).ok()?,
+                signatures_required: u16::try_from(*signatures_required).ok()?,
+            })
+        } else if function_name == CompleteDepositV1::FUNCTION_NAME {
+            let [txid, ClarityValue::UInt(vout), ClarityValue::UInt(amount), _, sweep_block_hash, ClarityValue::UInt(sweep_block_height), sweep_txid] =
+                args
+            else {
+                return None;
+            };
+            let txid = bitcoin::Txid::from_byte_array(from_le_bytes(buffer(txid)?)?);
+            let sweep_txid = bitcoin::Txid::from_byte_array(from_le_bytes(buffer(sweep_txid)?)?);
+            let sweep_block_hash =
+                bitcoin::BlockHash::from_byte_array(from_le_bytes(buffer(sweep_block_hash)?)?);
+            Some(Self::CompleteDeposit {
+                outpoint: OutPoint::new(txid, u32::try_from(*vout).ok()?),
+                amount: u64::try_from(*amount).ok()?,
+                sweep_block_hash: sweep_block_hash.into(),
+                sweep_block_height: u64::try_from(*sweep_block_height).ok()?,
+                sweep_txid: sweep_txid.into(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn buffer(value: &ClarityValue) -> Option<&[u8]> {
+    match value {
+        ClarityValue::Sequence(SequenceData::Buffer(BuffData { data })) => Some(data),
+        _ => None,
+    }
+}
+
+/// Hashes are passed to the contracts in little-endian order, the reverse
+/// of their byte order in the bitcoin library.
+fn from_le_bytes(bytes: &[u8]) -> Option<[u8; 32]> {
+    let mut bytes: [u8; 32] = bytes.try_into().ok()?;
+    bytes.reverse();
+    Some(bytes)
+}
+
+#[derive(Debug, Default)]
+struct StacksState {
+    /// Every transaction that was submitted, in order.
+    submitted: Vec<StacksTransaction>,
+    /// Submitted transactions that have not been included in a block.
+    mempool: Vec<StacksTransaction>,
+    /// The number of transactions that were included in a block.
+    confirmed: u64,
+    /// The aggregate key of the last confirmed key rotation.
+    aggregate_key: Option<PublicKey>,
+    /// The signer set of the last confirmed key rotation.
+    signer_set: Vec<PublicKey>,
+    /// Safety violations detected when transactions were submitted.
+    violations: Vec<Violation>,
+}
+
+/// A [`StacksInteract`] implementation for simulations.
+///
+/// The Stacks chain is kept to what the signers need from it: submitted
+/// transactions wait in a mempool until the [`Simulation`] includes them
+/// in a block, and confirmed key rotations become the current signer set
+/// and aggregate key of the sBTC contracts. The chain never forks.
+///
+/// [`Simulation`]: super::Simulation
+#[derive(Debug, Clone)]
+pub struct SimulatedStacks {
+    state: Arc<Mutex<StacksState>>,
+    bitcoin: MockChainClient,
+}
+
+impl SimulatedStacks {
+    /// Create a new Stacks client that checks the transactions submitted
+    /// to it against the given bitcoin chain.
+    pub fn new(bitcoin: MockChainClient) -> Self {
+        Self {
+            state: Default::default(),
+            bitcoin,
+        }
+    }
+
+    /// Every transaction that was submitted so far, in order.
+    pub fn submitted(&self) -> Vec<StacksTransaction> {
+        self.state.lock().unwrap().submitted.clone()
+    }
+
+    /// The aggregate key of the last confirmed key rotation.
+    pub fn aggregate_key(&self) -> Option<PublicKey> {
+        self.state.lock().unwrap().aggregate_key
+    }
+
+    /// The safety violations that were detected when transactions were
+    /// submitted.
+    pub fn violations(&self) -> Vec<Violation> {
+        self.state.lock().unwrap().violations.clone()
+    }
+
+    /// Remove the transactions waiting in the mempool and treat them as
+    /// confirmed, returning them in submission order.
+    pub fn confirm_mempool(&self) -> Vec<StacksTransaction> {
+        let mut state = self.state.lock().unwrap();
+        let txs = std::mem::take(&mut state.mempool);
+        state.confirmed += txs.len() as u64;
+
+        for call in txs.iter().filter_map(SbtcContractCall::from_tx) {
+            if let SbtcContractCall::RotateKeys { new_keys, aggregate_key, .. } = call {
+                state.aggregate_key = Some(aggregate_key);
+                state.signer_set = new_keys;
+            }
+        }
+        txs
+    }
+}
+
+impl StacksInteract for SimulatedStacks {
+    async fn get_current_signer_set(
+        &self,
+        _contract_principal: &StacksAddress,
+    ) -> Result<Vec<PublicKey>, Error> {
+        Ok(self.state.lock().unwrap().signer_set.clone())
+    }
+
+    async fn get_current_signers_aggregate_key(
+        &self,
+        _contract_principal: &StacksAddress,
+    ) -> Result<Option<PublicKey>, Error> {
+        Ok(self.aggregate_key())
+    }
+
+    async fn get_account(&self, _address: &StacksAddress) -> Result<AccountInfo, Error> {
+        Ok(AccountInfo {
+            balance: 1_000_000_000,
+            locked: 0,
+            unlock_height: 0,
+            nonce: self.state.lock().unwrap().confirmed,
+        })
+    }
+
+    async fn submit_tx(&self, tx: &StacksTransaction) -> Result<SubmitTxResponse, Error> {
+        let violation = match SbtcContractCall::from_tx(tx) {
+            Some(call @ SbtcContractCall::CompleteDeposit { .. }) => self
+                .bitcoin
+                .with_chain(|chain| properties::check_mint_has_sweep(chain, &call)),
+            _ => None,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.violations.extend(violation);
+        state.submitted.push(tx.clone());
+        state.mempool.push(tx.clone());
+        Ok(SubmitTxResponse::Acceptance(tx.txid()))
+    }
+
+    async fn get_block(&self, _block_id: StacksBlockId) -> Result<NakamotoBlock, Error> {
+        Err(Error::MissingBlock)
+    }
+
+    async fn get_tenure(&self, _block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
+        Err(Error::MissingBlock)
+    }
+
+    async fn get_tenure_info(&self) -> Result<RPCGetTenureInfo, Error> {
+        // The simulation writes Stacks blocks straight into the database
+        // of each signer, so there is nothing for the block observer to
+        // fetch.
+        Err(Error::MissingBlock)
+    }
+
+    async fn get_sortition_info(
+        &self,
+        _consensus_hash: &ConsensusHash,
+    ) -> Result<SortitionInfo, Error> {
+        Err(Error::MissingBlock)
+    }
+
+    async fn estimate_fees<T>(&self, _: &SignerWallet, _: &T, _: FeePriority) -> Result<u64, Error>
+    where
+        T: AsTxPayload,
+    {
+        Ok(SIMULATED_STACKS_FEE)
+    }
+
+    async fn get_pox_info(&self) -> Result<RPCPoxInfoData, Error> {
+        let data = get_pox_info_data();
+        Ok(RPCPoxInfoData {
+            current_burnchain_block_height: self.bitcoin.with_chain(|chain| chain.tip_height()),
+            epochs: vec![RPCPoxEpoch {
+                epoch_id: clarity::types::StacksEpochId::Epoch30,
+                start_height: 0,
+                end_height: 9223372036854776000,
+                network_epoch: 11,
+                block_limit: ExecutionCost {
+                    write_length: 15_000_000,
+                    write_count: 15_000,
+                    read_length: 100_000_000,
+                    read_count: 15_000,
+                    runtime: 5_000_000_000,
+                },
+            }],
+            ..data
+        })
+    }
+
+    async fn get_node_info(&self) -> Result<RPCPeerInfoData, Error> {
+        let data = get_node_info_data();
+        Ok(RPCPeerInfoData {
+            burn_block_height: self.bitcoin.with_chain(|chain| chain.tip_height()),
+            ..data
+        })
+    }
+
+    async fn get_contract_source(
+        &self,
+        _address: &StacksAddress,
+        _contract_name: &str,
+    ) -> Result<ContractSrcResponse, Error> {
+        Ok(ContractSrcResponse {
+            source: "contract source".to_string(),
+            publish_height: 1,
+            marf_proof: None,
+        })
+    }
+
+    async fn get_sbtc_total_supply(&self, _: &StacksAddress) -> Result<Amount, Error> {
+        Ok(Amount::ZERO)
+    }
+
+    async fn get_active_protocol_contract(
+        &self,
+        _: &StacksAddress,
+        _: ProtocolRole,
+    ) -> Result<Option<PrincipalData>, Error> {
+        Ok(None)
+    }
+}
+
+/// An [`EmilyInteract`] implementation for simulations.
+///
+/// It serves the deposit requests that the test registers with
+/// [`SimulatedEmily::add_deposit`] and accepts every update.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedEmily {
+    deposits: Arc<Mutex<Vec<CreateDepositRequest>>>,
+}
+
+impl SimulatedEmily {
+    /// Register a deposit request, as the sBTC bridge would after the
+    /// depositor broadcasts their transaction.
+    pub fn add_deposit(&self, request: CreateDepositRequest) {
+        self.deposits.lock().unwrap().push(request);
+    }
+}
+
+impl EmilyInteract for SimulatedEmily {
+    async fn get_deposit(
+        &self,
+        txid: &model::BitcoinTxId,
+        output_index: u32,
+    ) -> Result<Option<CreateDepositRequest>, Error> {
+        let outpoint = OutPoint::new(**txid, output_index);
+        let deposits = self.deposits.lock().unwrap();
+        Ok(deposits
+            .iter()
+            .find(|request| request.outpoint == outpoint)
+            .cloned())
+    }
+
+    async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
+        Ok(self.deposits.lock().unwrap().clone())
+    }
+
+    async fn update_deposits(
+        &self,
+        _update_deposits: Vec<emily_client::models::DepositUpdate>,
+    ) -> Result<UpdateDepositsResponse, Error> {
+        Ok(UpdateDepositsResponse::new(Vec::new()))
+    }
+
+    async fn accept_deposits<'a>(
+        &'a self,
+        _transaction: &'a UnsignedTransaction<'a>,
+        _stacks_chain_tip: &'a model::StacksBlock,
+    ) -> Result<UpdateDepositsResponse, Error> {
+        Ok(UpdateDepositsResponse::new(Vec::new()))
+    }
+
+    async fn create_withdrawals(
+        &self,
+        _create_withdrawals: Vec<CreateWithdrawalRequestBody>,
+    ) -> Vec<Result<Withdrawal, Error>> {
+        Vec::new()
+    }
+
+    async fn update_withdrawals(
+        &self,
+        _update_withdrawals: Vec<emily_client::models::WithdrawalUpdate>,
+    ) -> Result<UpdateWithdrawalsResponse, Error> {
+        Ok(UpdateWithdrawalsResponse::new(Vec::new()))
+    }
+
+    async fn set_chainstate(&self, chainstate: Chainstate) -> Result<Chainstate, Error> {
+        Ok(chainstate)
+    }
+
+    async fn get_limits(&self) -> Result<SbtcLimits, Error> {
+        Ok(SbtcLimits::new(None, None, None, None, None))
+    }
+}
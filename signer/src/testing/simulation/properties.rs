@@ -0,0 +1,347 @@
+//! Safety properties that a [`Simulation`] checks.
+//!
+//! The checks look at the bitcoin chain, the bitcoin transactions that the
+//! signers broadcast and the Stacks transactions of a simulation, not at
+//! the internal state of the signers, so they hold the signers to what an
+//! outside observer of both chains can see.
+//!
+//! [`Simulation`]: super::Simulation
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use sbtc::testing::mock_chain::MockChain;
+
+use super::clients::SbtcContractCall;
+use crate::testing::mock_chain::Broadcast;
+
+/// A violation of one of the safety properties of the signers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The signers broadcast more than one transaction that spends the
+    /// same output of theirs on top of the same chain tip. The chain only
+    /// accepts one of them, so this is caught here rather than on chain.
+    DoubleSpend {
+        /// The output that was spent more than once.
+        outpoint: OutPoint,
+        /// The chain tip that the transactions were broadcast on.
+        chain_tip: BlockHash,
+        /// The transactions that spent it, in the order that they were
+        /// broadcast.
+        spenders: Vec<Txid>,
+    },
+    /// A transaction of the signers did not spend the signers' UTXO as
+    /// its first input, so the signers lost track of their own UTXO.
+    ForkedSignerUtxo {
+        /// The transaction of the signers.
+        txid: Txid,
+        /// The signers' UTXO at the time, which the transaction should
+        /// have spent.
+        expected: OutPoint,
+        /// The output that the transaction spent as its first input.
+        spent: OutPoint,
+    },
+    /// A `complete-deposit` contract call was submitted for a deposit
+    /// that was not swept on the active bitcoin chain at the time.
+    MintWithoutSweep {
+        /// The deposit that was minted.
+        outpoint: OutPoint,
+        /// The sweep transaction named in the contract call.
+        sweep_txid: Txid,
+        /// What was wrong with the sweep.
+        reason: &'static str,
+    },
+    /// More than one `complete-deposit` contract call was confirmed for
+    /// the same deposit.
+    DuplicateMint {
+        /// The deposit that was minted more than once.
+        outpoint: OutPoint,
+        /// The number of confirmed contract calls for it.
+        count: usize,
+    },
+}
+
+/// Check that the sweep named by a `complete-deposit` contract call is
+/// confirmed on the active chain, in the block that the call names, and
+/// that it spends the deposit. Returns `None` for other contract calls.
+pub fn check_mint_has_sweep(chain: &MockChain, call: &SbtcContractCall) -> Option<Violation> {
+    let SbtcContractCall::CompleteDeposit {
+        outpoint,
+        sweep_block_hash,
+        sweep_txid,
+        ..
+    } = call
+    else {
+        return None;
+    };
+
+    let violation = |reason| {
+        Some(Violation::MintWithoutSweep {
+            outpoint: *outpoint,
+            sweep_txid: **sweep_txid,
+            reason,
+        })
+    };
+
+    let block_hash = bitcoin::BlockHash::from(*sweep_block_hash);
+    if !chain.is_in_active_chain(&block_hash) {
+        return violation("the sweep block is not on the active chain");
+    }
+    let block = chain.block(&block_hash)?;
+    let Some(sweep) = block
+        .txdata
+        .iter()
+        .find(|tx| tx.compute_txid() == **sweep_txid)
+    else {
+        return violation("the sweep transaction is not in the sweep block");
+    };
+    if !sweep
+        .input
+        .iter()
+        .any(|tx_in| tx_in.previous_output == *outpoint)
+    {
+        return violation("the sweep transaction does not spend the deposit");
+    }
+    None
+}
+
+/// Check the transactions on the active chain that spend outputs locked
+/// by any of the given `scriptPubKey`s of the signers.
+///
+/// Every transaction of the signers must spend the signers' UTXO, the
+/// first output of the previous transaction of the signers, as its first
+/// input. Until the signers create their first transaction, the first
+/// donation to them is their UTXO.
+pub fn check_signer_utxo(chain: &MockChain, signer_scripts: &HashSet<ScriptBuf>) -> Vec<Violation> {
+    let is_signer_output = |outpoint: &OutPoint| {
+        chain
+            .output(outpoint)
+            .is_some_and(|output| signer_scripts.contains(&output.script_pubkey))
+    };
+
+    let mut violations = Vec::new();
+    let mut signer_utxo: Option<OutPoint> = None;
+
+    let active_chain = (0..=chain.tip_height()).filter_map(|height| chain.block_hash_at(height));
+    for block_hash in active_chain {
+        let Some(block) = chain.block(&block_hash) else {
+            continue;
+        };
+        for tx in &block.txdata {
+            let txid = tx.compute_txid();
+            let spends_signer_output = tx
+                .input
+                .iter()
+                .any(|tx_in| is_signer_output(&tx_in.previous_output));
+            let pays_signers = tx
+                .output
+                .first()
+                .is_some_and(|output| signer_scripts.contains(&output.script_pubkey));
+
+            if !spends_signer_output {
+                if pays_signers && signer_utxo.is_none() {
+                    signer_utxo = Some(OutPoint::new(txid, 0));
+                }
+                continue;
+            }
+
+            let spent = tx.input[0].previous_output;
+            if let Some(expected) = signer_utxo.filter(|expected| *expected != spent) {
+                violations.push(Violation::ForkedSignerUtxo { txid, expected, spent });
+            }
+            signer_utxo = pays_signers.then_some(OutPoint::new(txid, 0));
+        }
+    }
+    violations
+}
+
+/// Check that the signers never broadcast two different transactions that
+/// spend the same output locked by any of the given `scriptPubKey`s on top
+/// of the same chain tip.
+///
+/// The coordinator builds one transaction package for each chain tip, so
+/// conflicting transactions for one tip mean that the signers signed two
+/// spends of the same output. Replacing a transaction after a new block is
+/// fine, which is why broadcasts on different chain tips are not compared.
+/// Broadcasts that the chain rejected count too.
+pub fn check_conflicting_broadcasts(
+    chain: &MockChain,
+    broadcasts: &[Broadcast],
+    signer_scripts: &HashSet<ScriptBuf>,
+) -> Vec<Violation> {
+    let mut spenders: BTreeMap<(OutPoint, BlockHash), Vec<Txid>> = BTreeMap::new();
+    for Broadcast { tx, chain_tip } in broadcasts {
+        let txid = tx.compute_txid();
+        let signer_inputs = tx
+            .input
+            .iter()
+            .map(|tx_in| tx_in.previous_output)
+            .filter(|outpoint| {
+                chain
+                    .output(outpoint)
+                    .is_some_and(|output| signer_scripts.contains(&output.script_pubkey))
+            });
+        for outpoint in signer_inputs {
+            let txids = spenders.entry((outpoint, *chain_tip)).or_default();
+            if !txids.contains(&txid) {
+                txids.push(txid);
+            }
+        }
+    }
+
+    spenders
+        .into_iter()
+        .filter(|(_, spenders)| spenders.len() > 1)
+        .map(|((outpoint, chain_tip), spenders)| Violation::DoubleSpend {
+            outpoint,
+            chain_tip,
+            spenders,
+        })
+        .collect()
+}
+
+/// Check that no deposit was minted more than once by the given confirmed
+/// contract calls.
+pub fn check_duplicate_mints<'a, I>(calls: I) -> Vec<Violation>
+where
+    I: IntoIterator<Item = &'a SbtcContractCall>,
+{
+    let mut mints: BTreeMap<OutPoint, usize> = BTreeMap::new();
+    for call in calls {
+        if let SbtcContractCall::CompleteDeposit { outpoint, .. } = call {
+            *mints.entry(*outpoint).or_default() += 1;
+        }
+    }
+
+    mints
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(outpoint, count)| Violation::DuplicateMint { outpoint, count })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::Amount;
+    use bitcoin::WPubkeyHash;
+
+    use super::*;
+
+    fn signers_script() -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([1; 20]))
+    }
+
+    /// A transaction of the signers that spends the given output and pays
+    /// the signers.
+    fn signers_tx(outpoint: OutPoint, value: Amount) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output: outpoint,
+                ..Default::default()
+            }],
+            output: vec![bitcoin::TxOut {
+                value,
+                script_pubkey: signers_script(),
+            }],
+        }
+    }
+
+    #[test]
+    fn chained_signer_transactions_pass() {
+        let mut chain = MockChain::new();
+        chain.generate_blocks(101);
+        let scripts = HashSet::from([signers_script()]);
+
+        let donation = chain
+            .send_to(signers_script(), Amount::from_sat(100_000))
+            .unwrap();
+        chain.generate_blocks(1);
+        let txid = chain
+            .broadcast(signers_tx(donation, Amount::from_sat(90_000)))
+            .unwrap();
+        chain.generate_blocks(1);
+        chain
+            .broadcast(signers_tx(OutPoint::new(txid, 0), Amount::from_sat(80_000)))
+            .unwrap();
+        chain.generate_blocks(1);
+
+        assert!(check_signer_utxo(&chain, &scripts).is_empty());
+    }
+
+    #[test]
+    fn spending_another_signer_output_is_a_fork() {
+        let mut chain = MockChain::new();
+        chain.generate_blocks(101);
+        let scripts = HashSet::from([signers_script()]);
+
+        let first = chain
+            .send_to(signers_script(), Amount::from_sat(100_000))
+            .unwrap();
+        let second = chain
+            .send_to(signers_script(), Amount::from_sat(100_000))
+            .unwrap();
+        chain.generate_blocks(1);
+
+        // The first donation is the signers' UTXO, so a transaction of the
+        // signers that starts with the second one forks their UTXO.
+        let txid = chain
+            .broadcast(signers_tx(second, Amount::from_sat(90_000)))
+            .unwrap();
+        chain.generate_blocks(1);
+
+        let violations = check_signer_utxo(&chain, &scripts);
+        let expected = Violation::ForkedSignerUtxo {
+            txid,
+            expected: first,
+            spent: second,
+        };
+        assert_eq!(violations, vec![expected]);
+    }
+
+    #[test]
+    fn conflicting_broadcasts_on_one_chain_tip_are_a_double_spend() {
+        let mut chain = MockChain::new();
+        chain.generate_blocks(101);
+        let scripts = HashSet::from([signers_script()]);
+
+        let donation = chain
+            .send_to(signers_script(), Amount::from_sat(100_000))
+            .unwrap();
+        let first_tip = chain.generate_blocks(1)[0];
+        let broadcast = |value, chain_tip| Broadcast {
+            tx: signers_tx(donation, Amount::from_sat(value)),
+            chain_tip,
+        };
+
+        // A replacement on the next block is fine, and so is broadcasting
+        // the same transaction again.
+        let second_tip = chain.generate_blocks(1)[0];
+        let broadcasts = vec![
+            broadcast(90_000, first_tip),
+            broadcast(90_000, first_tip),
+            broadcast(80_000, second_tip),
+        ];
+        assert!(check_conflicting_broadcasts(&chain, &broadcasts, &scripts).is_empty());
+
+        let conflicting = broadcast(85_000, second_tip);
+        let spenders = vec![
+            broadcasts[2].tx.compute_txid(),
+            conflicting.tx.compute_txid(),
+        ];
+        let broadcasts = [broadcasts, vec![conflicting]].concat();
+        let violations = check_conflicting_broadcasts(&chain, &broadcasts, &scripts);
+        let expected = Violation::DoubleSpend {
+            outpoint: donation,
+            chain_tip: second_tip,
+            spenders,
+        };
+        assert_eq!(violations, vec![expected]);
+    }
+}
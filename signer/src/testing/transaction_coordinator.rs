@@ -10,6 +10,8 @@ use crate::bitcoin::utxo::SignerUtxo;
 use crate::bitcoin::MockBitcoinInteract;
 use crate::context::Context;
 use crate::context::RequestDeciderEvent;
+use crate::context::RollingCap;
+use crate::context::SbtcLimits;
 use crate::emily_client::MockEmilyInteract;
 use crate::error;
 use crate::keys;
@@ -244,6 +246,138 @@ where
         assert!(!withdrawals_in_storage.is_empty());
     }
 
+    /// Asserts that TxCoordinatorEventLoop::get_pending_requests doesn't
+    /// count the deposits of a sweep that is being replaced against the
+    /// rolling deposit cap twice, once in the volume reported by Emily and
+    /// once in the new package.
+    pub async fn assert_rbf_at_rolling_cap_keeps_swept_deposits(mut self) {
+        // Setup network and signer info
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let network = network::InMemoryNetwork::new();
+        let signer_info = testing::wsts::generate_signer_info(&mut rng, self.num_signers as usize);
+        let mut testing_signer_set =
+            testing::wsts::SignerSet::new(&signer_info, self.signing_threshold as u32, || {
+                network.connect()
+            });
+        let (aggregate_key, bitcoin_chain_tip, mut test_data) = self
+            .prepare_database_and_run_dkg(&mut rng, &mut testing_signer_set)
+            .await;
+
+        // Add signer utxo to storage
+        let tx_1 = bitcoin::Transaction {
+            output: vec![bitcoin::TxOut {
+                value: bitcoin::Amount::from_sat(1_337_000_000_000),
+                script_pubkey: aggregate_key.signers_script_pubkey(),
+            }],
+            ..EMPTY_BITCOIN_TX
+        };
+        test_data.push_bitcoin_txs(
+            &bitcoin_chain_tip,
+            vec![(model::TransactionType::SbtcTransaction, tx_1.clone())],
+        );
+        self.write_test_data(&test_data).await;
+
+        self.context
+            .with_bitcoin_client(|client| {
+                client
+                    .expect_estimate_fee_rate()
+                    .times(2)
+                    .returning(|| Box::pin(async { Ok(1.3) }));
+            })
+            .await;
+
+        // The pending deposits were all swept by a sweep that this signer
+        // agreed to sign, and Emily counted them once it was broadcast,
+        // which took the window up to the cap.
+        let pending_deposits = self
+            .context
+            .get_storage()
+            .get_pending_accepted_deposit_requests(
+                &bitcoin_chain_tip.block_hash,
+                self.context_window,
+                self.signing_threshold,
+            )
+            .await
+            .expect("Error extracting deposits from db");
+        assert!(!pending_deposits.is_empty());
+        let swept_amount: u64 = pending_deposits.iter().map(|req| req.amount).sum();
+        let rolling_cap = RollingCap {
+            cap: bitcoin::Amount::from_sat(swept_amount),
+            volume: bitcoin::Amount::from_sat(swept_amount),
+        };
+        self.context.state().update_current_limits(
+            SbtcLimits::default().with_rolling_caps(Some(rolling_cap), None),
+        );
+
+        // Create the coordinator
+        self.context.state().set_sbtc_contracts_deployed();
+        let signer_network = SignerNetwork::single(&self.context);
+        let coordinator = TxCoordinatorEventLoop {
+            context: self.context,
+            network: signer_network.spawn(),
+            private_key: select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info),
+            threshold: self.signing_threshold,
+            context_window: self.context_window,
+            signing_round_max_duration: Duration::from_millis(500),
+            bitcoin_presign_request_max_duration: Duration::from_millis(500),
+            dkg_max_duration: Duration::from_millis(500),
+            is_epoch3: true,
+        };
+        let signer_public_keys = &signer_info
+            .last()
+            .expect("Empty signer set!")
+            .signer_public_keys;
+
+        // Before the sweep is signed, the deposits would take the window
+        // over the cap.
+        let pending_requests = coordinator
+            .get_pending_requests(
+                &bitcoin_chain_tip.block_hash,
+                &aggregate_key,
+                signer_public_keys,
+            )
+            .await
+            .expect("Error getting pending requests")
+            .expect("Empty pending requests");
+        assert_eq!(
+            pending_requests.sbtc_limits.rolling_deposit_capacity(),
+            bitcoin::Amount::ZERO
+        );
+
+        let sighashes: Vec<model::BitcoinTxSigHash> = pending_deposits
+            .iter()
+            .map(|req| model::BitcoinTxSigHash {
+                prevout_txid: req.txid,
+                prevout_output_index: req.output_index,
+                prevout_type: model::TxPrevoutType::Deposit,
+                will_sign: true,
+                ..Faker.fake_with_rng(&mut rng)
+            })
+            .collect();
+        coordinator
+            .context
+            .get_storage_mut()
+            .write_bitcoin_txs_sighashes(&sighashes)
+            .await
+            .expect("Error writing sighashes");
+
+        // Replacing the sweep leaves room for all of its deposits again.
+        let pending_requests = coordinator
+            .get_pending_requests(
+                &bitcoin_chain_tip.block_hash,
+                &aggregate_key,
+                signer_public_keys,
+            )
+            .await
+            .expect("Error getting pending requests")
+            .expect("Empty pending requests");
+        assert_eq!(
+            pending_requests.sbtc_limits.rolling_deposit_capacity(),
+            bitcoin::Amount::from_sat(swept_amount)
+        );
+        assert_eq!(pending_requests.deposits.len(), pending_deposits.len());
+    }
+
     /// Assert that a coordinator should be able to coordiante a signing round
     pub async fn assert_should_be_able_to_coordinate_signing_rounds(
         mut self,
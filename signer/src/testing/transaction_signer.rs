@@ -56,6 +56,7 @@ where
                 signer_private_key,
                 context_window,
                 wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+                dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
                 threshold,
                 rng,
                 dkg_begin_pause: None,
@@ -424,10 +424,14 @@ impl SignerSet {
             self.signers
                 .iter()
                 .map(|signer| {
-                    signer
+                    let mut shares = signer
                         .wsts_signer
                         .get_encrypted_dkg_shares(rng)
-                        .expect("failed to get encrypted shares")
+                        .expect("failed to get encrypted shares");
+                    // Callers use these shares as if they have already
+                    // gone through DKG verification.
+                    shares.dkg_shares_status = model::DkgSharesStatus::Verified;
+                    shares
                 })
                 .collect(),
         )
@@ -14,11 +14,16 @@ use futures::future::try_join_all;
 use futures::Stream;
 use futures::StreamExt as _;
 use sha2::Digest;
+use tracing::Instrument as _;
 
+use crate::bitcoin::dry_run::SweepDryRunReport;
 use crate::bitcoin::utxo;
 use crate::bitcoin::utxo::Fees;
+use crate::bitcoin::validation::BitcoinTxContext;
 use crate::bitcoin::BitcoinInteract;
 use crate::bitcoin::TransactionLookupHint;
+use crate::codec::Decode as _;
+use crate::codec::Encode as _;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::RequestDeciderEvent;
@@ -33,6 +38,7 @@ use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::keys::PrivateKey;
 use crate::keys::PublicKey;
+use crate::keys::PublicKeyXOnly;
 use crate::message;
 use crate::message::BitcoinPreSignRequest;
 use crate::message::Payload;
@@ -47,20 +53,26 @@ use crate::stacks::api::FeePriority;
 use crate::stacks::api::GetNakamotoStartHeight;
 use crate::stacks::api::StacksInteract;
 use crate::stacks::api::SubmitTxResponse;
+use crate::stacks::api::MAX_TX_FEE;
 use crate::stacks::contracts::AsTxPayload;
 use crate::stacks::contracts::CompleteDepositV1;
 use crate::stacks::contracts::ContractCall;
+use crate::stacks::contracts::ProtocolRole;
 use crate::stacks::contracts::RotateKeysV1;
 use crate::stacks::contracts::SmartContract;
+use crate::stacks::contracts::StacksTx;
+use crate::stacks::contracts::UpdateProtocolContractV1;
 use crate::stacks::contracts::SMART_CONTRACTS;
 use crate::stacks::wallet::MultisigTx;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::model;
 use crate::storage::model::StacksTxId;
 use crate::storage::DbRead as _;
+use crate::wsts_state_machine;
 use crate::wsts_state_machine::CoordinatorStateMachine;
 
 use bitcoin::hashes::Hash as _;
+use bitcoin::Amount;
 use wsts::net::SignatureType;
 use wsts::state_machine::coordinator::Coordinator as _;
 use wsts::state_machine::coordinator::State as WstsCoordinatorState;
@@ -207,7 +219,15 @@ where
                         event
                     {
                         tracing::debug!("received signal; processing requests");
-                        if let Err(error) = self.process_new_blocks().await {
+                        // Every tenure gets its own trace, rather than
+                        // adding to the trace of the event loop, which
+                        // only ends when the signer stops.
+                        let span = tracing::info_span!(
+                            parent: None,
+                            "tx-coordinator-iteration",
+                            public_key = %self.pub_key(),
+                        );
+                        if let Err(error) = self.process_new_blocks().instrument(span).await {
                             tracing::error!(
                                 %error,
                                 "error processing requests; skipping this round"
@@ -311,14 +331,22 @@ where
             should_coordinate_dkg(&self.context, &bitcoin_chain_tip).await?;
         let aggregate_key = if should_coordinate_dkg {
             let dkg_result = self.coordinate_dkg(&bitcoin_chain_tip).await?;
-            // TODO: in `run_dkg_from_scratch` test, `dkg_result` differs from
-            // value fetched from the db. Adding a temporary fix for the (probably)
-            // race condition, but we should address this properly.
+
+            // New DKG shares are not used until every signer in the
+            // signing set has shown that they can produce a valid
+            // signature with them. If verification fails then we carry on
+            // with the last verified aggregate key, if there is one.
+            let verification = self
+                .coordinate_dkg_verification(&bitcoin_chain_tip, &dkg_result)
+                .await;
+            if let Err(error) = verification {
+                tracing::error!(%error, "failed to verify the new DKG shares");
+            }
+
             self.get_signer_set_and_aggregate_key(&bitcoin_chain_tip)
-                .await
-                .ok()
-                .and_then(|res| res.0)
-                .unwrap_or(dkg_result)
+                .await?
+                .0
+                .ok_or(Error::MissingAggregateKey(*bitcoin_chain_tip))?
         } else {
             maybe_aggregate_key.ok_or(Error::MissingAggregateKey(*bitcoin_chain_tip))?
         };
@@ -329,6 +357,13 @@ where
         self.check_and_submit_rotate_key_transaction(&bitcoin_chain_tip, &aggregate_key)
             .await?;
 
+        if let Err(error) = self
+            .check_and_submit_protocol_contract_updates(&bitcoin_chain_tip, &aggregate_key)
+            .await
+        {
+            tracing::error!(%error, "failed to activate protocol contracts");
+        }
+
         let bitcoin_processing_fut = self.construct_and_sign_bitcoin_sbtc_transactions(
             &bitcoin_chain_tip,
             &aggregate_key,
@@ -339,6 +374,13 @@ where
             tracing::error!(%error, "failed to construct and sign bitcoin transactions");
         }
 
+        if let Err(error) = self
+            .resubmit_stale_stacks_transactions(&bitcoin_chain_tip)
+            .await
+        {
+            tracing::error!(%error, "failed to resubmit stale stacks transactions");
+        }
+
         self.construct_and_sign_stacks_sbtc_response_transactions(
             &bitcoin_chain_tip,
             &aggregate_key,
@@ -348,7 +390,117 @@ where
         Ok(())
     }
 
-    /// Submit the rotate key tx for the latest DKG shares, if the aggregate key
+    /// Deploy and activate the protocol contract versions that this
+    /// signer has been configured to run, if they are not yet the active
+    /// ones in the sbtc-registry.
+    ///
+    /// A contract that has not been deployed is deployed through the
+    /// signers' multi-sig wallet, and it is activated with an
+    /// update-protocol-contract-wrapper call in a later tenure, once the
+    /// deployment has been confirmed.
+    ///
+    /// Only the deposit and withdrawal contracts are considered; the
+    /// signers always make governance calls against the first version.
+    #[tracing::instrument(skip_all)]
+    async fn check_and_submit_protocol_contract_updates(
+        &mut self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> Result<(), Error> {
+        let configured = self.context.config().signer.contract_versions;
+        let active = self.context.state().active_contracts();
+        let deployer = self.context.config().signer.deployer;
+
+        let mut updates: Vec<UpdateProtocolContractV1> =
+            [ProtocolRole::Deposit, ProtocolRole::Withdrawal]
+                .into_iter()
+                .filter(|role| configured.version(*role) != active.version(*role))
+                .map(|role| UpdateProtocolContractV1 {
+                    role,
+                    version: configured.version(role),
+                    deployer,
+                })
+                .collect();
+
+        if updates.is_empty() || !self.all_smart_contracts_deployed().await? {
+            return Ok(());
+        }
+
+        // Deployments and updates that are waiting to be confirmed are
+        // left alone.
+        let pending_requests = self.pending_stacks_sign_requests().await?;
+        let mut pending_updates = Vec::new();
+        let mut pending_deployments = Vec::new();
+        for req in pending_requests {
+            match req.contract_tx {
+                StacksTx::ContractCall(ContractCall::UpdateProtocolContractV1(call)) => {
+                    pending_updates.push(call)
+                }
+                StacksTx::SmartContract(contract) => pending_deployments.push(contract),
+                _ => {}
+            }
+        }
+        updates.retain(|update| !pending_updates.contains(update));
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let stacks = self.context.get_stacks_client();
+        let wallet = self.get_signer_wallet(bitcoin_chain_tip).await?;
+        for update in updates {
+            if !update.is_deployed(&stacks).await? {
+                let Some(contract) = SmartContract::protocol_contract(update.role, update.version)
+                else {
+                    tracing::warn!(
+                        role = %update.role,
+                        contract_name = update.contract_name(),
+                        "configured protocol contract has not been deployed"
+                    );
+                    continue;
+                };
+                if pending_deployments.contains(&contract) {
+                    continue;
+                }
+
+                tracing::info!(
+                    role = %update.role,
+                    version = %update.version,
+                    "deploying protocol contract"
+                );
+                self.deploy_smart_contract(contract, bitcoin_chain_tip, aggregate_key, &wallet)
+                    .await?;
+                continue;
+            }
+
+            let contract_call = ContractCall::UpdateProtocolContractV1(update);
+            let tx_fee = stacks
+                .estimate_fees(&wallet, &contract_call, FeePriority::High)
+                .await?;
+
+            let multi_tx = MultisigTx::new_tx(&contract_call, &wallet, tx_fee);
+            let tx = multi_tx.tx();
+
+            let sign_request = StacksTransactionSignRequest {
+                aggregate_key: *aggregate_key,
+                contract_tx: contract_call.into(),
+                nonce: tx.get_origin_nonce(),
+                tx_fee: tx.get_tx_fee(),
+                txid: tx.txid(),
+            };
+
+            tracing::info!(
+                role = %update.role,
+                version = %update.version,
+                "activating protocol contract"
+            );
+            self.process_sign_request(sign_request, bitcoin_chain_tip, multi_tx, &wallet)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Submit the rotate key tx for the latest verified DKG shares, if the aggregate key
     /// differs from the one in the smart contract registry
     #[tracing::instrument(skip_all)]
     async fn check_and_submit_rotate_key_transaction(
@@ -363,10 +515,10 @@ where
         let last_dkg = self
             .context
             .get_storage()
-            .get_latest_encrypted_dkg_shares()
+            .get_latest_verified_dkg_shares()
             .await?;
 
-        // If we don't have DKG shares nothing to do here
+        // If we don't have verified DKG shares nothing to do here
         let Some(last_dkg) = last_dkg else {
             return Ok(());
         };
@@ -383,6 +535,24 @@ where
             return Ok(());
         }
 
+        // If we have already submitted a rotate-keys transaction for this
+        // aggregate key then we wait for it to be confirmed, or for it to
+        // be resubmitted with a higher fee.
+        let rotation_pending = self
+            .pending_stacks_sign_requests()
+            .await?
+            .into_iter()
+            .any(|req| match req.contract_tx {
+                StacksTx::ContractCall(ContractCall::RotateKeysV1(call)) => {
+                    call.aggregate_key == last_dkg.aggregate_key
+                }
+                _ => false,
+            });
+        if rotation_pending {
+            tracing::debug!("rotate-keys transaction already pending for the latest aggregate key");
+            return Ok(());
+        }
+
         let wallet = self.get_signer_wallet(bitcoin_chain_tip).await?;
         // current_aggregate_key define which wallet can sign stacks tx interacting
         // with the registry smart contract; fallbacks to `aggregate_key` if it's
@@ -608,7 +778,6 @@ where
         bitcoin_aggregate_key: &PublicKey,
     ) -> Result<(), Error> {
         let wallet = SignerWallet::load(&self.context, chain_tip).await?;
-        let stacks = self.context.get_stacks_client();
 
         // Fetch deposit and withdrawal requests from the database where
         // there has been a confirmed bitcoin transaction associated with
@@ -621,12 +790,31 @@ where
         // For withdrawals, we need to have a record of the `request_id`
         // associated with the bitcoin transaction's outputs.
 
-        let deposit_requests = self
+        let mut deposit_requests = self
             .context
             .get_storage()
             .get_swept_deposit_requests(chain_tip, self.context_window)
             .await?;
 
+        // Deposits that already have a complete-deposit transaction
+        // waiting to be confirmed are left alone; if that transaction
+        // gets stuck then it is resubmitted with a higher fee instead.
+        let pending_outpoints: HashSet<bitcoin::OutPoint> = self
+            .pending_stacks_sign_requests()
+            .await?
+            .into_iter()
+            .filter_map(|req| match req.contract_tx {
+                StacksTx::ContractCall(ContractCall::CompleteDepositV1(call)) => {
+                    Some(call.outpoint)
+                }
+                StacksTx::ContractCall(ContractCall::CompleteDepositV2(call)) => {
+                    Some(call.outpoint)
+                }
+                _ => None,
+            })
+            .collect();
+        deposit_requests.retain(|req| !pending_outpoints.contains(&req.deposit_outpoint()));
+
         if deposit_requests.is_empty() {
             tracing::debug!("no stacks transactions to create, exiting");
             return Ok(());
@@ -641,11 +829,11 @@ where
         //
         // Note that the wallet object will automatically increment the
         // nonce for each transaction that it creates.
-        let account = stacks.get_account(wallet.address()).await?;
-        wallet.set_nonce(account.nonce);
+        let in_flight_nonces = self.sync_wallet_nonce(&wallet).await?;
 
         for req in deposit_requests {
             let outpoint = req.deposit_outpoint();
+            skip_in_flight_nonces(&wallet, &in_flight_nonces);
             let sign_request_fut =
                 self.construct_deposit_stacks_sign_request(req, bitcoin_aggregate_key, &wallet);
 
@@ -743,6 +931,9 @@ where
         wallet: &SignerWallet,
     ) -> Result<StacksTxId, Error> {
         let kind = sign_request.tx_kind();
+        let nonce = sign_request.nonce;
+        let tx_fee = sign_request.tx_fee;
+        let encoded_request = sign_request.clone().encode_to_vec();
 
         let instant = std::time::Instant::now();
         let tx = self
@@ -766,11 +957,208 @@ where
         )
         .increment(1);
 
-        match self.context.get_stacks_client().submit_tx(&tx?).await {
-            Ok(SubmitTxResponse::Acceptance(txid)) => Ok(txid.into()),
-            Ok(SubmitTxResponse::Rejection(err)) => Err(err.into()),
-            Err(err) => Err(err),
+        let txid: StacksTxId = match self.context.get_stacks_client().submit_tx(&tx?).await {
+            Ok(SubmitTxResponse::Acceptance(txid)) => txid.into(),
+            Ok(SubmitTxResponse::Rejection(err)) => return Err(err.into()),
+            Err(err) => return Err(err),
+        };
+
+        // We keep track of the transaction so that we can tell when it
+        // has been confirmed, and resubmit it with a higher fee if it
+        // takes too long.
+        let submitted_tx = model::SubmittedStacksTx {
+            txid,
+            nonce,
+            tx_fee,
+            sign_request: encoded_request,
+            status: model::StacksTxStatus::Pending,
+        };
+        self.context
+            .get_storage_mut()
+            .write_submitted_stacks_tx(&submitted_tx)
+            .await?;
+
+        Ok(txid)
+    }
+
+    /// Rebuild and resubmit any of our Stacks transactions that have been
+    /// pending for longer than the configured resubmission timeout.
+    ///
+    /// The new transaction uses the same nonce as the stale one but pays
+    /// a higher fee, so that stacks nodes will replace the original
+    /// transaction in their mempool. The original transaction is marked
+    /// as replaced when the new one is recorded.
+    #[tracing::instrument(skip_all)]
+    async fn resubmit_stale_stacks_transactions(
+        &mut self,
+        chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        let timeout = self.context.config().signer.stacks_tx_resubmit_timeout;
+        let stale_txs = self
+            .context
+            .get_storage()
+            .get_stale_stacks_txs(timeout)
+            .await?;
+
+        if stale_txs.is_empty() {
+            return Ok(());
+        }
+
+        let wallet = SignerWallet::load(&self.context, chain_tip).await?;
+        // Transactions with a nonce below the account nonce can no longer
+        // be replaced, so there is nothing to resubmit for them.
+        self.sync_wallet_nonce(&wallet).await?;
+        let account_nonce = wallet.get_nonce();
+
+        for stale_tx in stale_txs {
+            if stale_tx.nonce < account_nonce {
+                continue;
+            }
+
+            let resubmit_fut = self.resubmit_stacks_transaction(&stale_tx, chain_tip, &wallet);
+
+            let status = match resubmit_fut.await {
+                Ok(Some(txid)) => {
+                    tracing::info!(
+                        %txid,
+                        replaced_txid = %stale_tx.txid,
+                        nonce = %stale_tx.nonce,
+                        "resubmitted stale stacks transaction with a higher fee"
+                    );
+                    "success"
+                }
+                Ok(None) => continue,
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        txid = %stale_tx.txid,
+                        nonce = %stale_tx.nonce,
+                        "could not resubmit stale stacks transaction"
+                    );
+                    "failure"
+                }
+            };
+
+            metrics::counter!(
+                Metrics::TransactionsSubmittedTotal,
+                "blockchain" => STACKS_BLOCKCHAIN,
+                "status" => status,
+            )
+            .increment(1);
         }
+
+        Ok(())
+    }
+
+    /// Rebuild the given stale transaction with a higher fee and the same
+    /// nonce, then sign and broadcast it.
+    ///
+    /// Returns `None` if the fee could not be raised because the stale
+    /// transaction already pays the maximum fee.
+    async fn resubmit_stacks_transaction(
+        &mut self,
+        stale_tx: &model::SubmittedStacksTx,
+        chain_tip: &model::BitcoinBlockHash,
+        wallet: &SignerWallet,
+    ) -> Result<Option<StacksTxId>, Error> {
+        let request = StacksTransactionSignRequest::decode(stale_tx.sign_request.as_slice())?;
+
+        // Stacks nodes only accept a replacement transaction if it pays
+        // a meaningfully higher fee than the original, so we bump the fee
+        // by at least 25%.
+        let tx_fee = self
+            .context
+            .get_stacks_client()
+            .estimate_fees(wallet, &request.contract_tx, FeePriority::High)
+            .await?
+            .max(stale_tx.tx_fee.saturating_mul(5) / 4)
+            .min(MAX_TX_FEE);
+
+        if tx_fee <= stale_tx.tx_fee {
+            tracing::warn!(
+                txid = %stale_tx.txid,
+                tx_fee = %stale_tx.tx_fee,
+                "stale stacks transaction already pays the maximum fee"
+            );
+            return Ok(None);
+        }
+
+        wallet.set_nonce(stale_tx.nonce);
+        let multi_tx = MultisigTx::new_tx(&request.contract_tx, wallet, tx_fee);
+        let tx = multi_tx.tx();
+
+        let sign_request = StacksTransactionSignRequest {
+            aggregate_key: request.aggregate_key,
+            contract_tx: request.contract_tx,
+            nonce: tx.get_origin_nonce(),
+            tx_fee: tx.get_tx_fee(),
+            txid: tx.txid(),
+        };
+
+        self.process_sign_request(sign_request, chain_tip, multi_tx, wallet)
+            .await
+            .map(Some)
+    }
+
+    /// Set the nonce of the given wallet to the nonce of its account on
+    /// the stacks node and return the nonces of our transactions that are
+    /// still in flight.
+    ///
+    /// Pending transactions with a nonce below the account nonce can no
+    /// longer be mined. They are marked as confirmed if we have seen them
+    /// in a Stacks block, since we may have missed the event for that
+    /// block, and as dropped otherwise. If one of our
+    /// submissions failed after earlier nonces were handed out then the
+    /// account nonce will be below some of the in-flight nonces. We log
+    /// this nonce gap here, and it gets filled by the next transaction we
+    /// create, since [`skip_in_flight_nonces`] only skips nonces that are
+    /// actually in use.
+    async fn sync_wallet_nonce(&self, wallet: &SignerWallet) -> Result<BTreeSet<u64>, Error> {
+        let stacks = self.context.get_stacks_client();
+        let account = stacks.get_account(wallet.address()).await?;
+        wallet.set_nonce(account.nonce);
+
+        let storage = self.context.get_storage_mut();
+        let mut in_flight_nonces = BTreeSet::new();
+
+        for tx in storage.get_pending_stacks_txs().await? {
+            if tx.nonce < account.nonce {
+                let status = if storage.stacks_tx_exists(&tx.txid).await? {
+                    model::StacksTxStatus::Confirmed
+                } else {
+                    model::StacksTxStatus::Dropped
+                };
+                storage.update_stacks_tx_status(&tx.txid, status).await?;
+            } else {
+                in_flight_nonces.insert(tx.nonce);
+            }
+        }
+
+        if in_flight_nonces
+            .first()
+            .is_some_and(|&nonce| nonce > account.nonce)
+        {
+            tracing::warn!(
+                account_nonce = %account.nonce,
+                ?in_flight_nonces,
+                "detected a gap in the nonces of our pending stacks transactions"
+            );
+        }
+
+        Ok(in_flight_nonces)
+    }
+
+    /// Return the sign requests of our Stacks transactions that are
+    /// still pending.
+    async fn pending_stacks_sign_requests(
+        &self,
+    ) -> Result<Vec<StacksTransactionSignRequest>, Error> {
+        let pending_txs = self.context.get_storage().get_pending_stacks_txs().await?;
+
+        pending_txs
+            .iter()
+            .map(|tx| StacksTransactionSignRequest::decode(tx.sign_request.as_slice()))
+            .collect()
     }
 
     /// Transform the swept deposit request into a Stacks sign request
@@ -805,7 +1193,7 @@ where
 
         // TODO: we should validate the contract call before asking others
         // to sign it.
-        let contract_call = ContractCall::CompleteDepositV1(CompleteDepositV1 {
+        let call = CompleteDepositV1 {
             amount: req.amount - assessed_bitcoin_fee.to_sat(),
             outpoint,
             recipient: req.recipient.into(),
@@ -813,7 +1201,9 @@ where
             sweep_txid: req.sweep_txid,
             sweep_block_hash: req.sweep_block_hash,
             sweep_block_height: req.sweep_block_height,
-        });
+        };
+        let version = self.context.state().active_contracts().deposit;
+        let contract_call = ContractCall::complete_deposit(call, version);
 
         // Complete deposit requests should be done as soon as possible, so
         // we set the fee rate to the high priority fee.
@@ -1143,6 +1533,70 @@ where
         }
     }
 
+    /// Run a signing round over the DKG verification message using the
+    /// newly generated DKG shares for the given aggregate key.
+    ///
+    /// The signing round requires every signer in the signing set to
+    /// participate. If the resulting signature is valid for the aggregate
+    /// key then the shares are marked as verified, and if it is not then
+    /// they are marked as failed. The shares are left unverified if the
+    /// round does not complete.
+    #[tracing::instrument(skip_all, fields(%aggregate_key))]
+    async fn coordinate_dkg_verification(
+        &mut self,
+        chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+    ) -> Result<(), Error> {
+        tracing::info!("Coordinating DKG verification");
+        let x_only_aggregate_key = PublicKeyXOnly::from(aggregate_key);
+        let mut db = self.context.get_storage_mut();
+
+        let shares = db
+            .get_encrypted_dkg_shares(x_only_aggregate_key)
+            .await?
+            .ok_or(Error::MissingDkgShares(x_only_aggregate_key))?;
+
+        // All signers must take part in the signing round, so the
+        // threshold is the size of the signing set.
+        let signer_set = shares.signer_set_public_keys;
+        let threshold: u16 = signer_set
+            .len()
+            .try_into()
+            .map_err(|_| Error::TypeConversion)?;
+
+        let mut coordinator_state_machine = CoordinatorStateMachine::load(
+            &mut db,
+            x_only_aggregate_key,
+            signer_set,
+            threshold,
+            self.private_key,
+        )
+        .await?;
+
+        let msg = wsts_state_machine::dkg_verification_message(&x_only_aggregate_key);
+        let txid = bitcoin::Txid::from_byte_array(msg);
+
+        let signature = self
+            .coordinate_signing_round(
+                chain_tip,
+                &mut coordinator_state_machine,
+                txid,
+                &msg,
+                SignatureType::Schnorr,
+            )
+            .await?;
+
+        if !wsts_state_machine::verify_dkg_signature(&x_only_aggregate_key, &signature.signature) {
+            db.update_dkg_shares_status(&x_only_aggregate_key, model::DkgSharesStatus::Failed)
+                .await?;
+            return Err(Error::DkgVerificationFailed(x_only_aggregate_key));
+        }
+
+        tracing::info!("DKG shares have been verified");
+        db.update_dkg_shares_status(&x_only_aggregate_key, model::DkgSharesStatus::Verified)
+            .await
+    }
+
     #[tracing::instrument(skip_all)]
     async fn drive_wsts_state_machine<S>(
         &mut self,
@@ -1363,13 +1817,22 @@ where
             .await?;
 
         let mut deposits: Vec<utxo::DepositRequest> = Vec::new();
+        // Emily already counts the deposits of a sweep that is being
+        // replaced towards the rolling window, see
+        // `SbtcLimits::excluding_rolling_volume`.
+        let mut swept_deposits = 0u64;
 
         for req in pending_deposit_requests {
-            let votes = self
-                .context
-                .get_storage()
+            let storage = self.context.get_storage();
+            let votes = storage
                 .get_deposit_request_signer_votes(&req.txid, req.output_index, aggregate_key)
                 .await?;
+            if storage
+                .is_deposit_in_signed_sweep(&req.txid, req.output_index)
+                .await?
+            {
+                swept_deposits = swept_deposits.saturating_add(req.amount);
+            }
 
             let deposit = utxo::DepositRequest::from_model(req, votes);
             deposits.push(deposit);
@@ -1385,18 +1848,103 @@ where
         if deposits.is_empty() && withdrawals.is_empty() {
             return Ok(None);
         }
-        let signer_config = &self.context.config().signer;
+        // This setting can be changed while the signer is running.
+        let max_deposits_per_bitcoin_tx = self
+            .context
+            .latest_config()
+            .signer
+            .max_deposits_per_bitcoin_tx
+            .get();
         Ok(Some(utxo::SbtcRequests {
             deposits,
             withdrawals,
             signer_state: self.get_btc_state(bitcoin_chain_tip, aggregate_key).await?,
             accept_threshold: threshold,
             num_signers,
-            sbtc_limits: self.context.state().get_current_limits(),
-            max_deposits_per_bitcoin_tx: signer_config.max_deposits_per_bitcoin_tx.get(),
+            sbtc_limits: self
+                .context
+                .state()
+                .get_current_limits()
+                .excluding_rolling_volume(Amount::from_sat(swept_deposits), Amount::ZERO),
+            max_deposits_per_bitcoin_tx,
         }))
     }
 
+    /// Construct the sweep transaction package for the given chain tip
+    /// and validate it the way the other signers would, without signing
+    /// or broadcasting anything.
+    ///
+    /// The returned report says, for each pending request, whether it was
+    /// included in the package and if not, why it was left out.
+    #[tracing::instrument(skip_all)]
+    pub async fn sweep_dry_run(
+        &self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<SweepDryRunReport, Error> {
+        let db = self.context.get_storage();
+        let chain_tip_height = db
+            .get_bitcoin_block(bitcoin_chain_tip)
+            .await?
+            .ok_or(Error::NoChainTip)?
+            .block_height;
+
+        let (maybe_aggregate_key, signer_public_keys) = self
+            .get_signer_set_and_aggregate_key(bitcoin_chain_tip)
+            .await?;
+        let aggregate_key = maybe_aggregate_key.ok_or(Error::NoDkgShares)?;
+
+        let mut report = SweepDryRunReport::new(*bitcoin_chain_tip, chain_tip_height);
+
+        let pending_requests = self
+            .get_pending_requests(bitcoin_chain_tip, &aggregate_key, &signer_public_keys)
+            .await?;
+
+        if let Some(pending_requests) = pending_requests {
+            let transaction_package = pending_requests.construct_transactions()?;
+
+            let validation = if transaction_package.is_empty() {
+                Ok(Vec::new())
+            } else {
+                let sbtc_requests = BitcoinPreSignRequest {
+                    request_package: transaction_package
+                        .iter()
+                        .map(|tx| (&tx.requests).into())
+                        .collect(),
+                    fee_rate: pending_requests.signer_state.fee_rate,
+                    last_fees: pending_requests.signer_state.last_fees.map(Into::into),
+                };
+                let btc_ctx = BitcoinTxContext {
+                    chain_tip: *bitcoin_chain_tip,
+                    chain_tip_height,
+                    context_window: self.context_window,
+                    signer_public_key: self.pub_key(),
+                    aggregate_key,
+                };
+                sbtc_requests
+                    .construct_package_sighashes(&self.context, &btc_ctx)
+                    .await
+            };
+
+            report.add_requests(&pending_requests, &transaction_package, validation);
+        }
+
+        let unvoted_deposits = db
+            .get_pending_deposit_requests(bitcoin_chain_tip, self.context_window, &self.pub_key())
+            .await?;
+        report.add_unvoted_deposits(&unvoted_deposits);
+
+        let accepted_withdrawals = db
+            .get_pending_accepted_withdrawal_requests(
+                bitcoin_chain_tip,
+                self.context_window,
+                self.threshold,
+            )
+            .await?;
+        report.add_unsupported_withdrawals(&accepted_withdrawals);
+
+        Ok(report)
+    }
+
     /// Return the signing set that can make sBTC related contract calls
     /// along with the current aggregate key to use for locking UTXOs on
     /// bitcoin.
@@ -1405,10 +1953,10 @@ where
     /// canonical Stacks blockchain as part of a `rotate-keys` contract
     /// call. It will be the public key that is the result of a DKG run. If
     /// there are no rotate-keys transactions on the canonical stacks
-    /// blockchain, then we fall back on the last known DKG shares row in
-    /// our database, and return None as the aggregate key if no DKG shares
-    /// can be found, implying that this signer has not participated in
-    /// DKG.
+    /// blockchain, then we fall back on the last verified DKG shares row
+    /// in our database, and return None as the aggregate key if no such
+    /// DKG shares can be found, implying that this signer has not
+    /// participated in a successful DKG round.
     #[tracing::instrument(skip_all)]
     pub async fn get_signer_set_and_aggregate_key(
         &self,
@@ -1431,7 +1979,7 @@ where
                 let signer_set = last_key.signer_set.into_iter().collect();
                 Ok((Some(aggregate_key), signer_set))
             }
-            None => match db.get_latest_encrypted_dkg_shares().await? {
+            None => match db.get_latest_verified_dkg_shares().await? {
                 Some(shares) => {
                     let signer_set = shares.signer_set_public_keys.into_iter().collect();
                     Ok((Some(shares.aggregate_key), signer_set))
@@ -1464,6 +2012,7 @@ where
         let msg = msg
             .into()
             .to_message(*bitcoin_chain_tip)
+            .with_current_trace_context()
             .sign_ecdsa(&self.private_key);
 
         self.network.broadcast(msg.clone()).await?;
@@ -1600,9 +2149,8 @@ where
         //
         // Note that the wallet object will automatically increment the
         // nonce for each transaction that it creates.
-        let stacks = self.context.get_stacks_client();
-        let account = stacks.get_account(wallet.address()).await?;
-        wallet.set_nonce(account.nonce);
+        let in_flight_nonces = self.sync_wallet_nonce(&wallet).await?;
+        skip_in_flight_nonces(&wallet, &in_flight_nonces);
 
         Ok(wallet)
     }
@@ -1715,6 +2263,43 @@ where
     }
 }
 
+/// Advance the nonce of the given wallet past any nonces that are used by
+/// transactions that are still in flight.
+fn skip_in_flight_nonces(wallet: &SignerWallet, in_flight_nonces: &BTreeSet<u64>) {
+    let mut nonce = wallet.get_nonce();
+    while in_flight_nonces.contains(&nonce) {
+        nonce += 1;
+    }
+    wallet.set_nonce(nonce);
+}
+
+/// Run a sweep dry run against the current canonical bitcoin chain tip,
+/// using the signer's configuration for the coordinator parameters.
+///
+/// See [`TxCoordinatorEventLoop::sweep_dry_run`] for details.
+pub async fn sweep_dry_run<C: Context>(ctx: &C) -> Result<SweepDryRunReport, Error> {
+    let config = ctx.config();
+    let coordinator = TxCoordinatorEventLoop {
+        network: network::P2PNetwork::new(ctx),
+        context: ctx.clone(),
+        context_window: config.signer.context_window,
+        private_key: config.signer.private_key,
+        signing_round_max_duration: config.signer.signer_round_max_duration,
+        bitcoin_presign_request_max_duration: config.signer.bitcoin_presign_request_max_duration,
+        threshold: config.signer.bootstrap_signatures_required,
+        dkg_max_duration: config.signer.dkg_max_duration,
+        is_epoch3: false,
+    };
+
+    let chain_tip = ctx
+        .get_storage()
+        .get_bitcoin_canonical_chain_tip()
+        .await?
+        .ok_or(Error::NoChainTip)?;
+
+    coordinator.sweep_dry_run(&chain_tip).await
+}
+
 /// Check if the provided public key is the coordinator for the provided chain
 /// tip
 pub fn given_key_is_coordinator(
@@ -1769,8 +2354,10 @@ pub async fn should_coordinate_dkg(
         .await?
         .ok_or(Error::NoChainTip)?;
 
-    // Get the number of DKG shares that have been stored
-    let dkg_shares_entry_count = storage.get_encrypted_dkg_shares_count().await?;
+    // Get the number of DKG rounds that produced verified shares. Shares
+    // that failed verification, or whose verification round never
+    // finished, do not count, so that DKG is coordinated again for them.
+    let dkg_shares_entry_count = storage.get_verified_dkg_shares_count().await?;
 
     // Get DKG configuration parameters
     let dkg_min_bitcoin_block_height = config.signer.dkg_min_bitcoin_block_height;
@@ -1808,14 +2395,23 @@ pub async fn should_coordinate_dkg(
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
     use std::num::{NonZeroU32, NonZeroU64};
+    use std::time::Duration;
 
     use crate::bitcoin::MockBitcoinInteract;
-    use crate::context::Context;
+    use crate::codec::Encode as _;
+    use crate::context::{Context, P2PEvent};
+    use crate::ecdsa::SignEcdsa as _;
     use crate::emily_client::MockEmilyInteract;
-    use crate::stacks::api::MockStacksInteract;
+    use crate::keys::{PrivateKey, PublicKey};
+    use crate::message::{Payload, StacksTransactionSignRequest, StacksTransactionSignature};
+    use crate::network::in_memory::MpmcBroadcaster;
+    use crate::network::{InMemoryNetwork, MessageTransfer as _};
+    use crate::stacks::api::{AccountInfo, MockStacksInteract, SubmitTxResponse};
+    use crate::stacks::wallet::{MultisigTx, SignerWallet};
     use crate::storage::in_memory::SharedStore;
-    use crate::storage::{model, DbWrite};
+    use crate::storage::{model, DbRead as _, DbWrite};
     use crate::testing;
     use crate::testing::context::*;
     use crate::testing::transaction_coordinator::TestEnvironment;
@@ -1824,7 +2420,7 @@ mod tests {
     use test_case::test_case;
     use test_log::test;
 
-    use super::should_coordinate_dkg;
+    use super::{should_coordinate_dkg, skip_in_flight_nonces, TxCoordinatorEventLoop};
 
     fn test_environment() -> TestEnvironment<
         TestContext<
@@ -1926,6 +2522,13 @@ mod tests {
         test_environment().assert_ignore_withdrawals().await;
     }
 
+    #[tokio::test]
+    async fn should_keep_swept_deposits_when_replacing_a_sweep_at_the_rolling_cap() {
+        test_environment()
+            .assert_rbf_at_rolling_cap_keeps_swept_deposits()
+            .await;
+    }
+
     #[test_case(0, None, 1, 100, true; "first DKG allowed without min height")]
     #[test_case(0, Some(100), 1, 5, true; "first DKG allowed regardless of min height")]
     #[test_case(1, None, 2, 100, false; "subsequent DKG not allowed without min height")]
@@ -1982,4 +2585,244 @@ mod tests {
         // Assert the result
         assert_eq!(result, should_allow);
     }
+
+    #[test_case(model::DkgSharesStatus::Unverified; "unverified shares")]
+    #[test_case(model::DkgSharesStatus::Failed; "failed shares")]
+    #[test_log::test(tokio::test)]
+    async fn test_should_coordinate_dkg_ignores_unverified_shares(
+        dkg_shares_status: model::DkgSharesStatus,
+    ) {
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|s| {
+                s.signer.dkg_min_bitcoin_block_height = None;
+                s.signer.dkg_target_rounds = NonZeroU32::new(1).unwrap();
+            })
+            .build();
+
+        let storage = context.get_storage_mut();
+
+        // Write a `dkg_shares` entry from a round whose verification did
+        // not succeed; DKG should be coordinated again.
+        let mut shares: model::EncryptedDkgShares = Faker.fake();
+        shares.dkg_shares_status = dkg_shares_status;
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        let bitcoin_chain_tip: model::BitcoinBlockHash = Faker.fake();
+        storage
+            .write_bitcoin_block(&model::BitcoinBlock {
+                block_height: 1,
+                parent_hash: Faker.fake(),
+                block_hash: bitcoin_chain_tip,
+            })
+            .await
+            .unwrap();
+
+        let result = should_coordinate_dkg(&context, &bitcoin_chain_tip)
+            .await
+            .expect("failed to check if DKG should be coordinated");
+        assert!(result);
+    }
+
+    fn coordinator_event_loop<C: Context>(
+        context: C,
+        network: MpmcBroadcaster,
+    ) -> TxCoordinatorEventLoop<C, MpmcBroadcaster> {
+        TxCoordinatorEventLoop {
+            private_key: context.config().signer.private_key,
+            context,
+            network,
+            threshold: 1,
+            context_window: 5,
+            signing_round_max_duration: Duration::from_secs(10),
+            bitcoin_presign_request_max_duration: Duration::from_secs(10),
+            dkg_max_duration: Duration::from_secs(10),
+            is_epoch3: true,
+        }
+    }
+
+    fn pending_stacks_tx(nonce: u64, tx_fee: u64) -> model::SubmittedStacksTx {
+        let request = StacksTransactionSignRequest { nonce, tx_fee, ..Faker.fake() };
+        model::SubmittedStacksTx {
+            txid: request.txid.into(),
+            nonce,
+            tx_fee,
+            sign_request: request.encode_to_vec(),
+            status: model::StacksTxStatus::Pending,
+        }
+    }
+
+    fn expect_account_nonce(client: &mut MockStacksInteract, nonce: u64) {
+        client.expect_get_account().returning(move |_| {
+            Box::pin(async move {
+                Ok(AccountInfo {
+                    balance: 1_000_000,
+                    locked: 0,
+                    unlock_height: 0,
+                    nonce,
+                })
+            })
+        });
+    }
+
+    async fn stacks_tx_status(
+        store: &SharedStore,
+        txid: &model::StacksTxId,
+    ) -> Option<model::StacksTxStatus> {
+        let store = store.lock().await;
+        store
+            .submitted_stacks_txs
+            .get(txid)
+            .map(|(_, tx)| tx.status)
+    }
+
+    #[tokio::test]
+    async fn sync_wallet_nonce_handles_nonce_gaps_and_mined_nonces() {
+        let mut context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        context
+            .with_stacks_client(|client| expect_account_nonce(client, 5))
+            .await;
+
+        // Two transactions whose nonces have been used, one of which we
+        // have seen in a stacks block, and two transactions in flight
+        // that leave a gap at the account nonce, as if the submission
+        // with that nonce had failed.
+        let included = pending_stacks_tx(3, 1_000);
+        let dropped = pending_stacks_tx(4, 1_000);
+        let in_flight = [pending_stacks_tx(6, 1_000), pending_stacks_tx(7, 1_000)];
+
+        let storage = context.get_storage_mut();
+        for tx in [&included, &dropped].into_iter().chain(&in_flight) {
+            storage.write_submitted_stacks_tx(tx).await.unwrap();
+        }
+        let stacks_tx = model::StacksTransaction {
+            txid: included.txid,
+            block_hash: Faker.fake(),
+        };
+        storage.write_stacks_transaction(&stacks_tx).await.unwrap();
+
+        let network = InMemoryNetwork::new();
+        let coordinator = coordinator_event_loop(context.clone(), network.connect());
+        let wallet = SignerWallet::load_boostrap_wallet(&context.config().signer).unwrap();
+
+        let in_flight_nonces = coordinator.sync_wallet_nonce(&wallet).await.unwrap();
+        assert_eq!(in_flight_nonces, BTreeSet::from([6, 7]));
+
+        // The next transaction fills the gap, and the one after that
+        // skips over the nonces that are in flight.
+        skip_in_flight_nonces(&wallet, &in_flight_nonces);
+        assert_eq!(wallet.get_nonce(), 5);
+        wallet.set_nonce(6);
+        skip_in_flight_nonces(&wallet, &in_flight_nonces);
+        assert_eq!(wallet.get_nonce(), 8);
+
+        // A transaction whose nonce has been used is only dropped if we
+        // have not seen it in a block.
+        let status = stacks_tx_status(&context.storage, &included.txid).await;
+        assert_eq!(status, Some(model::StacksTxStatus::Confirmed));
+        let status = stacks_tx_status(&context.storage, &dropped.txid).await;
+        assert_eq!(status, Some(model::StacksTxStatus::Dropped));
+        for tx in in_flight.iter() {
+            let status = stacks_tx_status(&context.storage, &tx.txid).await;
+            assert_eq!(status, Some(model::StacksTxStatus::Pending));
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_stacks_txs_are_resubmitted_with_a_higher_fee() {
+        let private_key = PrivateKey::new(&mut rand::rngs::OsRng);
+        let mut context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|s| {
+                s.signer.private_key = private_key;
+                s.signer.bootstrap_signing_set = vec![PublicKey::from_private_key(&private_key)];
+                s.signer.bootstrap_signatures_required = 1;
+                s.signer.stacks_tx_resubmit_timeout = Duration::from_millis(1);
+            })
+            .build();
+
+        context
+            .with_stacks_client(|client| {
+                expect_account_nonce(client, 5);
+                client
+                    .expect_estimate_fees()
+                    .returning(|_, _, _| Box::pin(async { Ok(800) }));
+                client.expect_submit_tx().times(1).returning(|tx| {
+                    let txid = tx.txid();
+                    Box::pin(async move { Ok(SubmitTxResponse::Acceptance(txid)) })
+                });
+            })
+            .await;
+
+        // The transaction with nonce 4 can no longer be replaced, but the
+        // one with nonce 5 is still waiting for a block.
+        let mined = pending_stacks_tx(4, 1_000);
+        let stale = pending_stacks_tx(5, 1_000);
+        let storage = context.get_storage_mut();
+        storage.write_submitted_stacks_tx(&mined).await.unwrap();
+        storage.write_submitted_stacks_tx(&stale).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let network = InMemoryNetwork::new();
+        let mut signer_network = network.connect();
+        let mut coordinator = coordinator_event_loop(context.clone(), network.connect());
+
+        // Stand in for the signer set: sign the transaction that the
+        // coordinator asks for, and keep sending the signature until the
+        // coordinator picks it up.
+        let signer_context = context.clone();
+        let signer = tokio::spawn(async move {
+            let msg = signer_network.receive().await.unwrap();
+            let Payload::StacksTransactionSignRequest(request) = &msg.inner.payload else {
+                panic!("expected a stacks transaction sign request");
+            };
+
+            let wallet =
+                SignerWallet::load_boostrap_wallet(&signer_context.config().signer).unwrap();
+            wallet.set_nonce(request.nonce);
+            let multi_tx = MultisigTx::new_tx(&request.contract_tx, &wallet, request.tx_fee);
+            let signature = crate::signature::sign_stacks_tx(multi_tx.tx(), &private_key);
+
+            let response = StacksTransactionSignature { txid: request.txid, signature };
+            let response = Payload::from(response)
+                .to_message(msg.bitcoin_chain_tip)
+                .sign_ecdsa(&private_key);
+
+            loop {
+                let event = P2PEvent::MessageReceived(response.clone());
+                let _ = signer_context.signal(event.into());
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        });
+
+        let chain_tip: model::BitcoinBlockHash = Faker.fake();
+        coordinator
+            .resubmit_stale_stacks_transactions(&chain_tip)
+            .await
+            .unwrap();
+        signer.abort();
+
+        // The stale transaction was replaced by one with the same nonce
+        // and a fee that is 25% higher, since the fee estimate was lower
+        // than that.
+        let pending_txs = context
+            .get_storage()
+            .get_pending_stacks_txs()
+            .await
+            .unwrap();
+        assert_eq!(pending_txs.len(), 1);
+        assert_eq!(pending_txs[0].nonce, stale.nonce);
+        assert_eq!(pending_txs[0].tx_fee, 1_250);
+
+        let status = stacks_tx_status(&context.storage, &stale.txid).await;
+        assert_eq!(status, Some(model::StacksTxStatus::Replaced));
+        let status = stacks_tx_status(&context.storage, &mined.txid).await;
+        assert_eq!(status, Some(model::StacksTxStatus::Dropped));
+    }
 }
@@ -8,7 +8,13 @@
 use std::collections::BTreeSet;
 use std::time::Duration;
 
+use crate::audit_log;
+use crate::audit_log::SignatureKind;
+use crate::audit_log::SigningRecord;
+use crate::audit_log::SigningValidation;
+use crate::audit_log::StacksTxValidation;
 use crate::bitcoin::validation::BitcoinTxContext;
+use crate::codec::Encode as _;
 use crate::context::Context;
 use crate::context::P2PEvent;
 use crate::context::SignerCommand;
@@ -28,16 +34,21 @@ use crate::metrics::Metrics;
 use crate::metrics::BITCOIN_BLOCKCHAIN;
 use crate::metrics::STACKS_BLOCKCHAIN;
 use crate::network;
+use crate::request_lifecycle;
+use crate::signature::SighashDigest as _;
 use crate::stacks::contracts::AsContractCall as _;
 use crate::stacks::contracts::ContractCall;
 use crate::stacks::contracts::ReqContext;
 use crate::stacks::contracts::StacksTx;
+use crate::stacks::simulator;
 use crate::stacks::wallet::MultisigTx;
 use crate::stacks::wallet::SignerWallet;
 use crate::storage::model;
 use crate::storage::model::SigHash;
 use crate::storage::DbRead;
 use crate::storage::DbWrite as _;
+use crate::wsts_state_machine;
+use crate::wsts_state_machine::CoordinatorStateMachine;
 use crate::wsts_state_machine::SignerStateMachine;
 use crate::wsts_state_machine::StateMachineId;
 
@@ -45,9 +56,12 @@ use bitcoin::hashes::Hash as _;
 use bitcoin::TapSighash;
 use futures::StreamExt;
 use lru::LruCache;
+use tracing::Instrument as _;
 use wsts::net::DkgEnd;
 use wsts::net::DkgStatus;
 use wsts::net::Message as WstsNetMessage;
+use wsts::state_machine::coordinator::Coordinator as _;
+use wsts::state_machine::OperationResult as WstsOperationResult;
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// # Transaction signer event loop
@@ -130,6 +144,12 @@ pub struct TxSignerEventLoop<Context, Network, Rng> {
     /// - For DKG rounds, TxID should be the ID of the transaction that
     ///   defined the signer set.
     pub wsts_state_machines: LruCache<StateMachineId, SignerStateMachine>,
+    /// WSTS coordinator state machines used for observing DKG
+    /// verification signing rounds, along with the aggregate key of the
+    /// DKG shares being verified. They let the signer check the final
+    /// signature for itself.
+    pub dkg_verification_state_machines:
+        LruCache<StateMachineId, (PublicKeyXOnly, CoordinatorStateMachine)>,
     /// The threshold for the signer
     pub threshold: u32,
     /// How many bitcoin blocks back from the chain tip the signer will look for requests.
@@ -198,7 +218,16 @@ where
                 SignerSignal::Event(event) => match event {
                     SignerEvent::TxCoordinator(TxCoordinatorEvent::MessageGenerated(msg))
                     | SignerEvent::P2P(P2PEvent::MessageReceived(msg)) => {
-                        if let Err(error) = self.handle_signer_message(&msg).await {
+                        // Every message starts a new trace, rather than
+                        // adding to the trace of the event loop, which
+                        // only ends when the signer stops.
+                        let span = tracing::info_span!(
+                            parent: None,
+                            "tx-signer-iteration",
+                            public_key = %self.signer_public_key(),
+                        );
+                        let handled = self.handle_signer_message(&msg).instrument(span).await;
+                        if let Err(error) = handled {
                             tracing::error!(%error, "error handling signer message");
                         }
                     }
@@ -238,12 +267,8 @@ where
                 true,
                 ChainTipStatus::Canonical,
             ) => {
-                self.handle_stacks_transaction_sign_request(
-                    request,
-                    &msg.bitcoin_chain_tip,
-                    &msg.signer_public_key,
-                )
-                .await?;
+                self.handle_stacks_transaction_sign_request(request, msg)
+                    .await?;
             }
 
             (message::Payload::WstsMessage(wsts_msg), _, _) => {
@@ -252,6 +277,7 @@ where
                     &msg.bitcoin_chain_tip,
                     msg.signer_public_key,
                     &chain_tip_report,
+                    msg,
                 )
                 .await?;
             }
@@ -388,6 +414,23 @@ where
         db.write_bitcoin_withdrawals_outputs(&withdrawals_outputs)
             .await?;
 
+        // Only the coordinator broadcasts the sweep, so this is the last
+        // point where every signer knows that the requests are on their
+        // way to being swept.
+        let stage = model::RequestStage::SweepSigned;
+        let now = time::OffsetDateTime::now_utc();
+        for requests in request.request_package.iter() {
+            for outpoint in requests.deposits.iter() {
+                let txid = model::BitcoinTxId::from(outpoint.txid);
+                let vout = outpoint.vout;
+                request_lifecycle::record_deposit_stage(&db, &txid, vout, stage, now).await;
+            }
+            for withdrawal in requests.withdrawals.iter() {
+                let request_id = withdrawal.request_id;
+                request_lifecycle::record_withdrawal_stage(&db, request_id, stage, now).await;
+            }
+        }
+
         self.send_message(BitcoinPreSignAck, bitcoin_chain_tip)
             .await?;
         Ok(())
@@ -397,9 +440,10 @@ where
     async fn handle_stacks_transaction_sign_request(
         &mut self,
         request: &StacksTransactionSignRequest,
-        bitcoin_chain_tip: &model::BitcoinBlockHash,
-        origin_public_key: &PublicKey,
+        signed_msg: &network::Msg,
     ) -> Result<(), Error> {
+        let bitcoin_chain_tip = &signed_msg.bitcoin_chain_tip;
+        let origin_public_key = &signed_msg.signer_public_key;
         let instant = std::time::Instant::now();
         let validation_status = self
             .assert_valid_stacks_tx_sign_request(request, bitcoin_chain_tip, origin_public_key)
@@ -418,7 +462,7 @@ where
             "status" => if validation_status.is_ok() { "success" } else { "failed" },
         )
         .increment(1);
-        validation_status?;
+        let req_ctx = validation_status?;
 
         // We need to set the nonce in order to get the exact transaction
         // that we need to sign.
@@ -434,6 +478,30 @@ where
 
         let signature = crate::signature::sign_stacks_tx(multi_sig.tx(), &self.signer_private_key);
 
+        let record = SigningRecord::new(
+            SignatureKind::StacksTransaction,
+            self.signer_public_key(),
+            signed_msg,
+            &multi_sig.tx().digest(),
+            SigningValidation::StacksTx(StacksTxValidation::new(request, &req_ctx)),
+        );
+        self.write_audit_entry(&record).await?;
+
+        // Every signer keeps track of the transactions that it signs, so
+        // that whichever signer is coordinator later on can tell that the
+        // transaction is in flight and resubmit it if it gets stuck.
+        let submitted_tx = model::SubmittedStacksTx {
+            txid: txid.into(),
+            nonce: request.nonce,
+            tx_fee: request.tx_fee,
+            sign_request: request.clone().encode_to_vec(),
+            status: model::StacksTxStatus::Pending,
+        };
+        self.context
+            .get_storage_mut()
+            .write_submitted_stacks_tx(&submitted_tx)
+            .await?;
+
         let msg = message::StacksTransactionSignature { txid, signature };
 
         self.send_message(msg, bitcoin_chain_tip).await?;
@@ -443,13 +511,16 @@ where
 
     /// Check that the transaction is indeed valid. We specific checks that
     /// are run depend on the transaction being signed.
+    ///
+    /// On success, the context that the request was validated in is
+    /// returned.
     #[tracing::instrument(skip_all, fields(sender = %origin_public_key, txid = %request.txid), err)]
     pub async fn assert_valid_stacks_tx_sign_request(
         &self,
         request: &StacksTransactionSignRequest,
         chain_tip: &model::BitcoinBlockHash,
         origin_public_key: &PublicKey,
-    ) -> Result<(), Error> {
+    ) -> Result<ReqContext, Error> {
         let db = self.context.get_storage();
         let public_key = self.signer_public_key();
 
@@ -476,6 +547,17 @@ where
             deployer: self.context.config().signer.deployer,
         };
         let ctx = &self.context;
+
+        // The sbtc-registry rejects calls that come through a protocol
+        // contract that is not the active one for its role.
+        if let StacksTx::ContractCall(call) = &request.contract_tx {
+            let role = call.protocol_role();
+            let version = call.contract_version();
+            if ctx.state().active_contracts().version(role) != version {
+                return Err(Error::InactiveContractVersion(role, version));
+            }
+        }
+
         tracing::info!("running validation on stacks transaction");
         match &request.contract_tx {
             StacksTx::ContractCall(ContractCall::AcceptWithdrawalV1(contract)) => {
@@ -490,16 +572,44 @@ where
             StacksTx::ContractCall(ContractCall::RotateKeysV1(contract)) => {
                 contract.validate(ctx, &req_ctx).await?
             }
+            StacksTx::ContractCall(ContractCall::CompleteDepositV2(contract)) => {
+                contract.validate(ctx, &req_ctx).await?
+            }
+            StacksTx::ContractCall(ContractCall::AcceptWithdrawalV2(contract)) => {
+                contract.validate(ctx, &req_ctx).await?
+            }
+            StacksTx::ContractCall(ContractCall::RejectWithdrawalV2(contract)) => {
+                contract.validate(ctx, &req_ctx).await?
+            }
+            StacksTx::ContractCall(ContractCall::UpdateProtocolContractV1(contract)) => {
+                contract.validate(ctx, &req_ctx).await?
+            }
             StacksTx::SmartContract(smart_contract) => {
                 smart_contract.validate(ctx, &req_ctx).await?
             }
         };
 
+        // Contract deployments are not simulated, they only need the
+        // contract to not exist yet.
+        let simulate = self.context.config().signer.simulate_contract_calls;
+        if let StacksTx::ContractCall(call) = &request.contract_tx {
+            if simulate {
+                tracing::info!("simulating stacks contract call");
+                simulator::simulate_contract_call(ctx, &req_ctx, call).await?
+            }
+        }
+
         tracing::info!("stacks validation finished successfully");
-        Ok(())
+        Ok(req_ctx)
     }
 
     /// Process WSTS messages
+    ///
+    /// The `signed_msg` is the signed message that carried the WSTS
+    /// message. Signature share requests are written to the signing audit
+    /// log along with it. When it carries the trace context of the span
+    /// that sent it, the span of this function links to it, so that a
+    /// signing round can be followed from the coordinator to every signer.
     #[tracing::instrument(skip_all, fields(txid = %msg.txid))]
     pub async fn handle_wsts_message(
         &mut self,
@@ -507,7 +617,12 @@ where
         bitcoin_chain_tip: &model::BitcoinBlockHash,
         msg_public_key: PublicKey,
         chain_tip_report: &MsgChainTipReport,
+        signed_msg: &network::Msg,
     ) -> Result<(), Error> {
+        if let Some(trace_context) = signed_msg.trace_context.as_ref() {
+            trace_context.link_current_span();
+        }
+
         match &msg.inner {
             WstsNetMessage::DkgBegin(_) => {
                 tracing::info!("handling DkgBegin");
@@ -591,6 +706,18 @@ where
                     return Ok(());
                 }
 
+                if let Some(aggregate_key) = self
+                    .dkg_verification_aggregate_key(&request.message)
+                    .await?
+                {
+                    let id = StateMachineId::dkg_verification(&aggregate_key);
+                    self.start_dkg_verification(id, aggregate_key).await?;
+                    self.observe_dkg_verification(&id, &msg.inner).await?;
+                    self.relay_message(id, msg.txid, &msg.inner, bitcoin_chain_tip)
+                        .await?;
+                    return Ok(());
+                }
+
                 let db = self.context.get_storage();
                 let accepted_sighash =
                     Self::validate_bitcoin_sign_request(&db, &request.message).await;
@@ -633,13 +760,43 @@ where
                     return Ok(());
                 }
 
-                let db = self.context.get_storage();
-                let accepted_sighash =
-                    Self::validate_bitcoin_sign_request(&db, &request.message).await?;
+                let (id, validation) = match self
+                    .dkg_verification_aggregate_key(&request.message)
+                    .await?
+                {
+                    Some(aggregate_key) => (
+                        StateMachineId::dkg_verification(&aggregate_key),
+                        SigningValidation::DkgVerification { aggregate_key },
+                    ),
+                    None => {
+                        let db = self.context.get_storage();
+                        let accepted_sighash =
+                            Self::validate_bitcoin_sign_request(&db, &request.message).await?;
+                        let validation =
+                            Self::bitcoin_tx_validation(&db, &accepted_sighash.sighash).await?;
+                        (accepted_sighash.sighash.into(), validation)
+                    }
+                };
 
-                let id = accepted_sighash.sighash.into();
+                // The audit log keeps the coordinator's signed request, so
+                // that the entry shows who asked for the share.
+                let record = SigningRecord::new(
+                    SignatureKind::SignatureShare,
+                    self.signer_public_key(),
+                    signed_msg,
+                    &request.message,
+                    validation,
+                );
+
+                self.observe_dkg_verification(&id, &msg.inner).await?;
                 let response = self
-                    .relay_message(id, msg.txid, &msg.inner, bitcoin_chain_tip)
+                    .relay_message_with_audit(
+                        id,
+                        msg.txid,
+                        &msg.inner,
+                        bitcoin_chain_tip,
+                        Some(&record),
+                    )
                     .await;
 
                 self.wsts_state_machines.pop(&id);
@@ -666,7 +823,32 @@ where
                 }
             }
             WstsNetMessage::NonceResponse(_) | WstsNetMessage::SignatureShareResponse(_) => {
-                tracing::trace!("ignoring message");
+                // These messages only matter to us if they are part of a
+                // DKG verification round, since then we need to check the
+                // resulting signature ourselves. The coordinator uses the
+                // verification message as the round's identifier, and we
+                // only observe rounds that we started when handling the
+                // NonceRequest, so there is no need to hit the database
+                // for every response.
+                let id = StateMachineId::from(&msg.txid);
+                let Some((_, state_machine)) = self.dkg_verification_state_machines.get(&id) else {
+                    tracing::trace!("ignoring message");
+                    return Ok(());
+                };
+
+                let public_keys = &state_machine.get_config().signer_public_keys;
+                let signer_id = match &msg.inner {
+                    WstsNetMessage::NonceResponse(response) => response.signer_id,
+                    WstsNetMessage::SignatureShareResponse(response) => response.signer_id,
+                    _ => unreachable!(),
+                };
+                let sender_key = p256k1::point::Point::from(msg_public_key);
+                if public_keys.get(&signer_id) != Some(&sender_key) {
+                    tracing::warn!(%signer_id, "message was signed by the wrong signer");
+                    return Ok(());
+                }
+
+                self.observe_dkg_verification(&id, &msg.inner).await?;
             }
         }
 
@@ -718,6 +900,171 @@ where
         }
     }
 
+    /// Fetch what the signer found when it validated the sweep transaction
+    /// with the given sighash during pre-sign.
+    async fn bitcoin_tx_validation<D>(db: &D, sighash: &SigHash) -> Result<SigningValidation, Error>
+    where
+        D: DbRead,
+    {
+        let input = db
+            .get_bitcoin_tx_sighash(sighash)
+            .await?
+            .ok_or(Error::UnknownSigHash(*sighash))?;
+
+        let withdrawal_outputs = db
+            .get_bitcoin_withdrawal_outputs(&input.txid, &input.chain_tip)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(SigningValidation::BitcoinTx {
+            input: input.into(),
+            withdrawal_outputs,
+        })
+    }
+
+    /// Append an entry for the given record to the signing audit log.
+    ///
+    /// Every [`audit_log::CHECKPOINT_INTERVAL`] entries, a checkpoint of
+    /// the head of the log is logged, so that the logs anchor the signing
+    /// audit log from outside of the database.
+    async fn write_audit_entry(&self, record: &SigningRecord) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+        let entry = audit_log::append(&db, &self.signer_private_key, record).await?;
+        tracing::debug!(position = %entry.position, "wrote signing audit log entry");
+
+        if (entry.position + 1) % audit_log::CHECKPOINT_INTERVAL == 0 {
+            let checkpoint = audit_log::Checkpoint::of_entry(&entry, &self.signer_private_key);
+            let checkpoint = serde_json::to_string(&checkpoint).map_err(Error::JsonSerialize)?;
+            tracing::info!(%checkpoint, "signing audit log checkpoint");
+        }
+        Ok(())
+    }
+
+    /// Return the aggregate key of the latest DKG shares if they have not
+    /// been verified yet and the given message is the DKG verification
+    /// message for them.
+    async fn dkg_verification_aggregate_key(
+        &self,
+        msg: &[u8],
+    ) -> Result<Option<PublicKeyXOnly>, Error> {
+        let db = self.context.get_storage();
+        let Some(shares) = db.get_latest_encrypted_dkg_shares().await? else {
+            return Ok(None);
+        };
+
+        if shares.dkg_shares_status != model::DkgSharesStatus::Unverified {
+            return Ok(None);
+        }
+
+        let aggregate_key = PublicKeyXOnly::from(shares.aggregate_key);
+        let expected_msg = wsts_state_machine::dkg_verification_message(&aggregate_key);
+
+        Ok((msg == expected_msg.as_slice()).then_some(aggregate_key))
+    }
+
+    /// Load the signer state machine for the DKG shares being verified,
+    /// along with a coordinator state machine for observing the round.
+    ///
+    /// The observer requires every signer in the signing set to take part
+    /// in the signing round, just like the coordinator running it.
+    #[tracing::instrument(skip(self))]
+    async fn start_dkg_verification(
+        &mut self,
+        id: StateMachineId,
+        aggregate_key: PublicKeyXOnly,
+    ) -> Result<(), Error> {
+        let mut db = self.context.get_storage_mut();
+        let shares = db
+            .get_encrypted_dkg_shares(aggregate_key)
+            .await?
+            .ok_or(Error::MissingDkgShares(aggregate_key))?;
+
+        let signer_set = shares.signer_set_public_keys;
+        let threshold: u16 = signer_set
+            .len()
+            .try_into()
+            .map_err(|_| Error::TypeConversion)?;
+
+        let state_machine =
+            SignerStateMachine::load(&db, aggregate_key, self.threshold, self.signer_private_key)
+                .await?;
+        let observer = CoordinatorStateMachine::load(
+            &mut db,
+            aggregate_key,
+            signer_set,
+            threshold,
+            self.signer_private_key,
+        )
+        .await?;
+
+        self.wsts_state_machines.put(id, state_machine);
+        self.dkg_verification_state_machines
+            .put(id, (aggregate_key, observer));
+
+        Ok(())
+    }
+
+    /// Hand the given message to the state machine observing the DKG
+    /// verification round with the given identifier, if there is one.
+    ///
+    /// Once the round completes we check the signature against the
+    /// aggregate key and record whether the DKG shares were verified.
+    #[tracing::instrument(skip_all)]
+    async fn observe_dkg_verification(
+        &mut self,
+        id: &StateMachineId,
+        msg: &WstsNetMessage,
+    ) -> Result<(), Error> {
+        let Some((aggregate_key, state_machine)) = self.dkg_verification_state_machines.get_mut(id)
+        else {
+            return Ok(());
+        };
+        let aggregate_key = *aggregate_key;
+
+        let packet = wsts::net::Packet {
+            msg: msg.clone(),
+            sig: Vec::new(),
+        };
+        let operation_result = match state_machine.process_message(&packet) {
+            Ok((_, Some(result))) => result,
+            Ok((_, None)) => return Ok(()),
+            Err(error) => {
+                tracing::warn!(%error, "DKG verification observer ignored message");
+                return Ok(());
+            }
+        };
+
+        self.dkg_verification_state_machines.pop(id);
+
+        let WstsOperationResult::SignSchnorr(proof) = operation_result else {
+            return Err(Error::UnexpectedOperationResult(Box::new(operation_result)));
+        };
+
+        let signature = crate::signature::TaprootSignature::from(proof);
+        let is_valid =
+            wsts_state_machine::verify_dkg_signature(&aggregate_key, &signature.signature);
+
+        let status = if is_valid {
+            model::DkgSharesStatus::Verified
+        } else {
+            model::DkgSharesStatus::Failed
+        };
+
+        tracing::info!(%aggregate_key, %status, "DKG verification round completed");
+        self.context
+            .get_storage_mut()
+            .update_dkg_shares_status(&aggregate_key, status)
+            .await?;
+
+        if !is_valid {
+            return Err(Error::DkgVerificationFailed(aggregate_key));
+        }
+
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self))]
     async fn store_dkg_shares(&mut self, id: &StateMachineId) -> Result<(), Error> {
         let state_machine = self
@@ -736,13 +1083,30 @@ where
         Ok(())
     }
 
-    #[tracing::instrument(skip_all)]
     async fn relay_message(
         &mut self,
         id: StateMachineId,
         txid: bitcoin::Txid,
         msg: &WstsNetMessage,
         bitcoin_chain_tip: &model::BitcoinBlockHash,
+    ) -> Result<(), Error> {
+        self.relay_message_with_audit(id, txid, msg, bitcoin_chain_tip, None)
+            .await
+    }
+
+    /// Hand the message to the state machine with the given ID and send
+    /// out whatever messages it produces.
+    ///
+    /// Signature shares are only sent after an entry for the given record
+    /// has been written to the signing audit log.
+    #[tracing::instrument(skip_all)]
+    async fn relay_message_with_audit(
+        &mut self,
+        id: StateMachineId,
+        txid: bitcoin::Txid,
+        msg: &WstsNetMessage,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        audit_record: Option<&SigningRecord>,
     ) -> Result<(), Error> {
         let Some(state_machine) = self.wsts_state_machines.get_mut(&id) else {
             tracing::warn!("missing signing round");
@@ -767,6 +1131,13 @@ where
                 self.store_dkg_shares(&id).await?;
                 self.wsts_state_machines.pop(&id);
             }
+            if let WstsNetMessage::SignatureShareResponse(_) = outbound {
+                let record = audit_record.ok_or(Error::MissingSigningAuditRecord)?;
+                self.write_audit_entry(record).await?;
+            }
+            // Our own responses never come back to us over the network,
+            // so we need to hand them to the observer directly.
+            self.observe_dkg_verification(&id, &outbound).await?;
             let msg = message::WstsMessage { txid, inner: outbound };
 
             self.send_message(msg, bitcoin_chain_tip).await?;
@@ -786,6 +1157,7 @@ where
 
         let msg = payload
             .to_message(*bitcoin_chain_tip)
+            .with_current_trace_context()
             .sign_ecdsa(&self.signer_private_key);
 
         self.network.broadcast(msg.clone()).await?;
@@ -803,10 +1175,10 @@ where
     /// canonical Stacks blockchain as part of a `rotate-keys` contract
     /// call. It will be the public key that is the result of a DKG run. If
     /// there are no rotate-keys transactions on the canonical stacks
-    /// blockchain, then we fall back on the last known DKG shares row in
-    /// our database, and return None as the aggregate key if no DKG shares
-    /// can be found, implying that this signer has not participated in
-    /// DKG.
+    /// blockchain, then we fall back on the last verified DKG shares row
+    /// in our database, and return None as the aggregate key if no such
+    /// DKG shares can be found, implying that this signer has not
+    /// participated in a successful DKG round.
     #[tracing::instrument(skip_all)]
     pub async fn get_signer_set_and_aggregate_key(
         &self,
@@ -829,7 +1201,7 @@ where
                 let signer_set = last_key.signer_set.into_iter().collect();
                 Ok((Some(aggregate_key), signer_set))
             }
-            None => match db.get_latest_encrypted_dkg_shares().await? {
+            None => match db.get_latest_verified_dkg_shares().await? {
                 Some(shares) => {
                     let signer_set = shares.signer_set_public_keys.into_iter().collect();
                     Ok((Some(shares.aggregate_key), signer_set))
@@ -882,8 +1254,10 @@ async fn assert_allow_dkg_begin(
         .await?
         .ok_or(Error::NoChainTip)?;
 
-    // Get the number of DKG shares that have been stored
-    let dkg_shares_entry_count = storage.get_encrypted_dkg_shares_count().await?;
+    // Get the number of DKG rounds that produced verified shares. Shares
+    // that failed verification, or whose verification round never
+    // finished, do not count, so that DKG can be run again for them.
+    let dkg_shares_entry_count = storage.get_verified_dkg_shares_count().await?;
 
     // Get DKG configuration parameters
     let dkg_min_bitcoin_block_height = config.signer.dkg_min_bitcoin_block_height;
@@ -1084,6 +1458,43 @@ mod tests {
         }
     }
 
+    #[test_case(model::DkgSharesStatus::Unverified; "unverified shares")]
+    #[test_case(model::DkgSharesStatus::Failed; "failed shares")]
+    #[tokio::test]
+    async fn test_assert_allow_dkg_begin_ignores_unverified_shares(
+        dkg_shares_status: model::DkgSharesStatus,
+    ) {
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|s| {
+                s.signer.dkg_min_bitcoin_block_height = None;
+                s.signer.dkg_target_rounds = NonZeroU32::new(1).unwrap();
+            })
+            .build();
+
+        let storage = context.get_storage_mut();
+
+        // Write a `dkg_shares` entry from a round whose verification did
+        // not succeed; it must not count as a completed DKG round.
+        let mut shares: model::EncryptedDkgShares = Faker.fake();
+        shares.dkg_shares_status = dkg_shares_status;
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        let bitcoin_chain_tip: model::BitcoinBlockHash = Faker.fake();
+        storage
+            .write_bitcoin_block(&model::BitcoinBlock {
+                block_height: 1,
+                parent_hash: Faker.fake(),
+                block_hash: bitcoin_chain_tip,
+            })
+            .await
+            .unwrap();
+
+        let result = assert_allow_dkg_begin(&context, &bitcoin_chain_tip).await;
+        assert!(result.is_ok());
+    }
+
     #[tokio::test]
     async fn test_handle_wsts_message_asserts_dkg_begin() {
         let context = TestContext::builder()
@@ -1121,6 +1532,7 @@ mod tests {
             signer_private_key: PrivateKey::new(&mut rand::rngs::OsRng),
             context_window: 1,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             threshold: 1,
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
@@ -1142,7 +1554,13 @@ mod tests {
         // Attempt to handle the DkgBegin message. This should fail using the
         // default settings, as the default settings allow only one DKG round.
         let result = signer
-            .handle_wsts_message(&msg, &bitcoin_chain_tip, Faker.fake(), &chain_tip_report)
+            .handle_wsts_message(
+                &msg,
+                &bitcoin_chain_tip,
+                Faker.fake(),
+                &chain_tip_report,
+                &Faker.fake::<network::Msg>(),
+            )
             .await;
 
         // Assert that the DkgBegin message was not allowed to proceed and
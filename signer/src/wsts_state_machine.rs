@@ -15,6 +15,8 @@ use crate::storage::model;
 use crate::storage::model::SigHash;
 
 use bitcoin::hashes::Hash as _;
+use secp256k1::SECP256K1;
+use sha2::Digest as _;
 use wsts::common::PolyCommitment;
 use wsts::state_machine::coordinator::Coordinator as _;
 use wsts::state_machine::coordinator::State as WstsState;
@@ -42,6 +44,23 @@ impl From<SigHash> for StateMachineId {
     }
 }
 
+/// The coordinator uses the message being signed in a DKG verification
+/// round as the txid of the round's WSTS messages, so the txid identifies
+/// the round.
+impl From<&bitcoin::Txid> for StateMachineId {
+    fn from(value: &bitcoin::Txid) -> Self {
+        StateMachineId(value.to_byte_array())
+    }
+}
+
+impl StateMachineId {
+    /// Return the identifier of the state machine used for verifying the
+    /// DKG shares associated with the given aggregate key.
+    pub fn dkg_verification(aggregate_key: &PublicKeyXOnly) -> Self {
+        StateMachineId(dkg_verification_message(aggregate_key))
+    }
+}
+
 #[cfg(any(test, feature = "testing"))]
 impl StateMachineId {
     /// Create a new identifier
@@ -50,6 +69,36 @@ impl StateMachineId {
     }
 }
 
+/// The domain separation tag that is prepended to the aggregate key when
+/// constructing the message signed during DKG verification.
+const DKG_VERIFICATION_TAG: &[u8] = b"SBTC_DKG_VERIFICATION";
+
+/// Return the message that the signers sign after DKG to check that the
+/// new shares can be used to produce a valid signature.
+///
+/// The message is the SHA-256 hash of a domain separation tag followed by
+/// the x-only aggregate key, so it can never be a valid bitcoin sighash
+/// that the signers would otherwise agree to sign.
+pub fn dkg_verification_message(aggregate_key: &PublicKeyXOnly) -> [u8; 32] {
+    sha2::Sha256::new()
+        .chain_update(DKG_VERIFICATION_TAG)
+        .chain_update(aggregate_key.serialize())
+        .finalize()
+        .into()
+}
+
+/// Check whether the given signature is a valid BIP-340 signature of the
+/// DKG verification message under the given aggregate key.
+pub fn verify_dkg_signature(
+    aggregate_key: &PublicKeyXOnly,
+    signature: &secp256k1::schnorr::Signature,
+) -> bool {
+    let msg = secp256k1::Message::from_digest(dkg_verification_message(aggregate_key));
+    SECP256K1
+        .verify_schnorr(signature, &msg, &aggregate_key.into())
+        .is_ok()
+}
+
 /// Wrapper around a WSTS signer state machine
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignerStateMachine(wsts::state_machine::signer::Signer<wsts::v2::Party>);
@@ -189,6 +238,7 @@ impl SignerStateMachine {
             public_shares,
             signer_set_public_keys,
             signature_share_threshold,
+            dkg_shares_status: model::DkgSharesStatus::Unverified,
         })
     }
 }
@@ -318,3 +368,33 @@ impl std::ops::DerefMut for CoordinatorStateMachine {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dkg_verification_signature_checks_message_and_key() {
+        let keypair = secp256k1::Keypair::new_global(&mut rand::rngs::OsRng);
+        let aggregate_key = PublicKeyXOnly::from(keypair.x_only_public_key().0);
+
+        let msg = secp256k1::Message::from_digest(dkg_verification_message(&aggregate_key));
+        let signature = SECP256K1.sign_schnorr(&msg, &keypair);
+        assert!(verify_dkg_signature(&aggregate_key, &signature));
+
+        // A signature over any other message does not verify.
+        let other_msg = secp256k1::Message::from_digest([1; 32]);
+        let signature = SECP256K1.sign_schnorr(&other_msg, &keypair);
+        assert!(!verify_dkg_signature(&aggregate_key, &signature));
+
+        // Nor does a valid signature under some other aggregate key.
+        let other_keypair = secp256k1::Keypair::new_global(&mut rand::rngs::OsRng);
+        let other_key = PublicKeyXOnly::from(other_keypair.x_only_public_key().0);
+        let signature = SECP256K1.sign_schnorr(&msg, &other_keypair);
+        assert!(!verify_dkg_signature(&aggregate_key, &signature));
+        assert_ne!(
+            dkg_verification_message(&aggregate_key),
+            dkg_verification_message(&other_key)
+        );
+    }
+}
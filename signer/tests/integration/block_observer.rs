@@ -727,6 +727,9 @@ async fn block_observer_handles_update_limits(deployed: bool, sbtc_limits: SbtcL
             client
                 .expect_get_sbtc_total_supply()
                 .returning(|_| Box::pin(std::future::ready(Ok(Amount::from_sat(1)))));
+            client
+                .expect_get_active_protocol_contract()
+                .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
         })
         .await;
         ctx.state().set_sbtc_contracts_deployed();
@@ -385,6 +385,10 @@ async fn deposit_flow() {
             client
                 .expect_get_current_signers_aggregate_key()
                 .returning(move |_| Box::pin(std::future::ready(Ok(Some(aggregate_key)))));
+
+            client
+                .expect_get_active_protocol_contract()
+                .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
         })
         .await;
 
@@ -15,13 +15,19 @@ use futures::StreamExt;
 use rand::seq::IteratorRandom;
 use rand::seq::SliceRandom;
 
+use signer::audit_log;
+use signer::audit_log::SignatureKind;
+use signer::audit_log::SigningRecord;
+use signer::audit_log::SigningValidation;
 use signer::bitcoin::validation::DepositConfirmationStatus;
 use signer::bitcoin::MockBitcoinInteract;
 use signer::config::Settings;
 use signer::context::Context;
 use signer::emily_client::MockEmilyInteract;
 use signer::error::Error;
+use signer::keys::PrivateKey;
 use signer::keys::PublicKey;
+use signer::keys::PublicKeyXOnly;
 use signer::keys::SignerScriptPubKey as _;
 use signer::network;
 use signer::stacks::api::MockStacksInteract;
@@ -41,6 +47,9 @@ use signer::storage::model::BitcoinWithdrawalOutput;
 use signer::storage::model::CompletedDepositEvent;
 use signer::storage::model::EncryptedDkgShares;
 use signer::storage::model::QualifiedRequestId;
+use signer::storage::model::RequestKind;
+use signer::storage::model::RequestStage;
+use signer::storage::model::RequestStageBacklog;
 use signer::storage::model::ScriptPubKey;
 use signer::storage::model::StacksBlock;
 use signer::storage::model::StacksBlockHash;
@@ -1664,6 +1673,7 @@ async fn is_signer_script_pub_key_checks_dkg_shares_for_script_pubkeys() {
         aggregate_key,
         signer_set_public_keys: vec![fake::Faker.fake_with_rng(&mut rng)],
         signature_share_threshold: 1,
+        dkg_shares_status: model::DkgSharesStatus::Verified,
     };
     db.write_encrypted_dkg_shares(&shares).await.unwrap();
     mem.write_encrypted_dkg_shares(&shares).await.unwrap();
@@ -1778,6 +1788,283 @@ async fn get_last_encrypted_dkg_shares_gets_most_recent_shares() {
     signer::testing::storage::drop_db(db).await;
 }
 
+/// The [`DbRead::get_latest_verified_dkg_shares`] function should skip
+/// over DKG shares that have not passed verification, and
+/// [`DbRead::get_verified_dkg_shares_count`] should only count verified
+/// shares.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_latest_verified_dkg_shares_skips_unverified_shares() {
+    let db = testing::storage::new_test_database().await;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let verified_shares: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    db.write_encrypted_dkg_shares(&verified_shares)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    // These are the most recent shares, but they haven't been verified.
+    let mut new_shares: model::EncryptedDkgShares = fake::Faker.fake_with_rng(&mut rng);
+    new_shares.dkg_shares_status = model::DkgSharesStatus::Unverified;
+    db.write_encrypted_dkg_shares(&new_shares).await.unwrap();
+
+    let latest_shares = db.get_latest_encrypted_dkg_shares().await.unwrap();
+    assert_eq!(latest_shares.as_ref(), Some(&new_shares));
+
+    let latest_verified = db.get_latest_verified_dkg_shares().await.unwrap();
+    assert_eq!(latest_verified.as_ref(), Some(&verified_shares));
+    assert_eq!(db.get_verified_dkg_shares_count().await.unwrap(), 1);
+    assert_eq!(db.get_encrypted_dkg_shares_count().await.unwrap(), 2);
+
+    // Once the new shares are verified they become the latest verified
+    // shares.
+    let aggregate_key = new_shares.aggregate_key.into();
+    db.update_dkg_shares_status(&aggregate_key, model::DkgSharesStatus::Verified)
+        .await
+        .unwrap();
+    new_shares.dkg_shares_status = model::DkgSharesStatus::Verified;
+
+    let latest_verified = db.get_latest_verified_dkg_shares().await.unwrap();
+    assert_eq!(latest_verified.as_ref(), Some(&new_shares));
+    assert_eq!(db.get_verified_dkg_shares_count().await.unwrap(), 2);
+
+    // Failed shares are never returned and are not counted.
+    db.update_dkg_shares_status(&aggregate_key, model::DkgSharesStatus::Failed)
+        .await
+        .unwrap();
+
+    let latest_verified = db.get_latest_verified_dkg_shares().await.unwrap();
+    assert_eq!(latest_verified.as_ref(), Some(&verified_shares));
+    assert_eq!(db.get_verified_dkg_shares_count().await.unwrap(), 1);
+    assert_eq!(db.get_encrypted_dkg_shares_count().await.unwrap(), 2);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that submitted stacks transactions are tracked until they are
+/// confirmed or replaced, that only transactions that have been pending
+/// for long enough are considered stale, and that recording a new
+/// transaction with the nonce of a pending one replaces it.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn submitted_stacks_txs_are_tracked_until_confirmed() {
+    let db = testing::storage::new_test_database().await;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let txs: Vec<model::SubmittedStacksTx> = (0..3)
+        .map(|nonce| model::SubmittedStacksTx {
+            nonce,
+            status: model::StacksTxStatus::Pending,
+            ..fake::Faker.fake_with_rng(&mut rng)
+        })
+        .collect();
+
+    for tx in txs.iter() {
+        db.write_submitted_stacks_tx(tx).await.unwrap();
+    }
+
+    // Writing the same transaction twice does not change anything.
+    db.write_submitted_stacks_tx(&txs[0]).await.unwrap();
+
+    let pending_txs = db.get_pending_stacks_txs().await.unwrap();
+    assert_eq!(pending_txs, txs);
+
+    // None of the transactions have been around for an hour.
+    let stale_txs = db
+        .get_stale_stacks_txs(Duration::from_secs(3600))
+        .await
+        .unwrap();
+    assert!(stale_txs.is_empty());
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let stale_txs = db
+        .get_stale_stacks_txs(Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(stale_txs, txs);
+
+    // Confirmed and replaced transactions are no longer pending. Unknown
+    // txids are ignored.
+    let unknown_txid: StacksTxId = fake::Faker.fake_with_rng(&mut rng);
+    db.confirm_stacks_txs(&[txs[0].txid, unknown_txid])
+        .await
+        .unwrap();
+    db.update_stacks_tx_status(&txs[1].txid, model::StacksTxStatus::Replaced)
+        .await
+        .unwrap();
+
+    let pending_txs = db.get_pending_stacks_txs().await.unwrap();
+    assert_eq!(pending_txs, txs[2..]);
+
+    // A new transaction with the same nonce as a pending one, say from a
+    // resubmission with a higher fee, replaces the pending one.
+    let replacement = model::SubmittedStacksTx {
+        nonce: txs[2].nonce,
+        status: model::StacksTxStatus::Pending,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_submitted_stacks_tx(&replacement).await.unwrap();
+
+    let pending_txs = db.get_pending_stacks_txs().await.unwrap();
+    assert_eq!(pending_txs, vec![replacement.clone()]);
+
+    // Writing the replaced transaction again does not bring it back, nor
+    // does it replace the new one.
+    db.write_submitted_stacks_tx(&txs[2]).await.unwrap();
+    let pending_txs = db.get_pending_stacks_txs().await.unwrap();
+    assert_eq!(pending_txs, vec![replacement.clone()]);
+
+    // We only know that a transaction was included once we have stored
+    // the stacks block that includes it.
+    assert!(!db.stacks_tx_exists(&replacement.txid).await.unwrap());
+
+    let stacks_block: model::StacksBlock = fake::Faker.fake_with_rng(&mut rng);
+    let tx = model::Transaction {
+        txid: replacement.txid.into_bytes(),
+        tx: Vec::new(),
+        tx_type: model::TransactionType::DepositAccept,
+        block_hash: stacks_block.block_hash.into_bytes(),
+    };
+    let stacks_tx = model::StacksTransaction {
+        txid: replacement.txid,
+        block_hash: stacks_block.block_hash,
+    };
+    db.write_stacks_block(&stacks_block).await.unwrap();
+    db.write_transaction(&tx).await.unwrap();
+    db.write_stacks_transaction(&stacks_tx).await.unwrap();
+
+    assert!(db.stacks_tx_exists(&replacement.txid).await.unwrap());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that postgres and the in-memory store agree on which stacks
+/// blocks still need their events scanned, and that recording a scan
+/// removes the block from the list.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn stacks_blocks_without_event_scan_match_in_memory_store() {
+    let mut pg_store = testing::storage::new_test_database().await;
+    let mut in_memory_store = storage::in_memory::Store::new_shared();
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 20,
+        num_stacks_blocks_per_bitcoin_block: 3,
+        num_deposit_requests_per_block: 0,
+        num_withdraw_requests_per_block: 0,
+        num_signers_per_request: 0,
+    };
+    let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, 7);
+    let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+
+    test_data.write_to(&mut in_memory_store).await;
+    test_data.write_to(&mut pg_store).await;
+
+    let chain_tip = in_memory_store
+        .get_bitcoin_canonical_chain_tip()
+        .await
+        .unwrap()
+        .unwrap();
+
+    let max_depth = 30;
+    let in_memory_blocks = in_memory_store
+        .get_stacks_blocks_without_event_scan(&chain_tip, max_depth)
+        .await
+        .unwrap();
+    let pg_blocks = pg_store
+        .get_stacks_blocks_without_event_scan(&chain_tip, max_depth)
+        .await
+        .unwrap();
+
+    assert!(!pg_blocks.is_empty());
+    assert!(pg_blocks.len() <= max_depth as usize);
+    assert_eq!(pg_blocks, in_memory_blocks);
+    assert!(pg_blocks
+        .windows(2)
+        .all(|pair| pair[0].block_height < pair[1].block_height));
+    assert!(pg_blocks
+        .iter()
+        .all(|block| block.bitcoin_anchor != chain_tip));
+
+    // Recording a scan twice is fine.
+    let scanned = &pg_blocks[0];
+    for _ in 0..2 {
+        pg_store
+            .write_stacks_block_event_scan(&scanned.block_hash)
+            .await
+            .unwrap();
+        in_memory_store
+            .write_stacks_block_event_scan(&scanned.block_hash)
+            .await
+            .unwrap();
+    }
+
+    let in_memory_blocks = in_memory_store
+        .get_stacks_blocks_without_event_scan(&chain_tip, max_depth)
+        .await
+        .unwrap();
+    let pg_blocks_after_scan = pg_store
+        .get_stacks_blocks_without_event_scan(&chain_tip, max_depth)
+        .await
+        .unwrap();
+
+    assert_eq!(pg_blocks_after_scan, in_memory_blocks);
+    assert_eq!(pg_blocks_after_scan, pg_blocks[1..]);
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
+/// Check that payloads in the event observer journal are read back in the
+/// order that they were written and are removed once they are too old.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn event_observer_journal_is_read_in_order_and_pruned() {
+    let db = testing::storage::new_test_database().await;
+
+    for body in ["first", "second", "third"] {
+        db.write_event_observer_payload(body).await.unwrap();
+    }
+
+    let payloads = db.get_event_observer_payloads(0, 10).await.unwrap();
+    let bodies: Vec<_> = payloads.iter().map(|p| p.body.as_str()).collect();
+    assert_eq!(bodies, ["first", "second", "third"]);
+    assert!(payloads.windows(2).all(|pair| pair[0].id < pair[1].id));
+
+    // Reading from an ID includes that payload and respects the limit.
+    let page = db
+        .get_event_observer_payloads(payloads[1].id, 1)
+        .await
+        .unwrap();
+    assert_eq!(page, payloads[1..2]);
+
+    // Nothing is an hour old yet.
+    let count = db
+        .delete_event_observer_payloads_older_than(Duration::from_secs(3600))
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let count = db
+        .delete_event_observer_payloads_older_than(Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(count, 3);
+    assert!(db
+        .get_event_observer_payloads(0, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// The [`DbRead::deposit_request_exists`] function is return true we have
 /// a record of the deposit request and false otherwise.
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
@@ -2310,6 +2597,22 @@ async fn should_ignore_withdrawals() {
     testing::storage::drop_db(store).await;
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test(tokio::test)]
+/// Tests that TxCoordinatorEventLoop::get_pending_requests doesn't count
+/// the deposits of a sweep that is being replaced against the rolling cap
+/// twice
+async fn should_keep_swept_deposits_when_replacing_a_sweep_at_the_rolling_cap() {
+    let store = testing::storage::new_test_database().await;
+
+    transaction_coordinator_test_environment(store.clone())
+        .await
+        .assert_rbf_at_rolling_cap_keeps_swept_deposits()
+        .await;
+
+    testing::storage::drop_db(store).await;
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn should_get_signer_utxo_simple() {
@@ -2943,6 +3246,120 @@ async fn get_deposit_request_returns_returns_inserted_deposit_request() {
     signer::testing::storage::drop_db(db).await;
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn request_stages_are_written_once_and_counted_in_the_backlog() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let txid: model::BitcoinTxId = fake::Faker.fake_with_rng(&mut rng);
+    let first_seen_at = time::OffsetDateTime::now_utc() - time::Duration::minutes(10);
+    let decided_at = first_seen_at + time::Duration::seconds(30);
+
+    let is_new = db
+        .write_deposit_request_stage(&txid, 3, RequestStage::FirstSeen, first_seen_at)
+        .await
+        .unwrap();
+    assert!(is_new);
+    let is_new = db
+        .write_deposit_request_stage(&txid, 3, RequestStage::Decided, decided_at)
+        .await
+        .unwrap();
+    assert!(is_new);
+
+    // Writing a stage again does not overwrite the original timestamp.
+    let is_new = db
+        .write_deposit_request_stage(&txid, 3, RequestStage::FirstSeen, decided_at)
+        .await
+        .unwrap();
+    assert!(!is_new);
+
+    let stages = db.get_deposit_request_stages(&txid, 3).await.unwrap();
+    let stage_names: Vec<_> = stages.iter().map(|s| s.stage).collect();
+    assert_eq!(
+        stage_names,
+        [RequestStage::FirstSeen, RequestStage::Decided]
+    );
+
+    // Postgres stores timestamps with microsecond precision.
+    let difference = (stages[0].reached_at - first_seen_at).abs();
+    assert!(difference < time::Duration::milliseconds(1));
+
+    // One withdrawal that is still pending and one that is completed.
+    let now = time::OffsetDateTime::now_utc();
+    for stage in [RequestStage::FirstSeen, RequestStage::SweepConfirmed] {
+        db.write_withdrawal_request_stage(1, stage, now)
+            .await
+            .unwrap();
+    }
+    for stage in [RequestStage::FirstSeen, RequestStage::Completed] {
+        db.write_withdrawal_request_stage(2, stage, now)
+            .await
+            .unwrap();
+    }
+    // A rejected withdrawal is done too.
+    for stage in [RequestStage::FirstSeen, RequestStage::Rejected] {
+        db.write_withdrawal_request_stage(3, stage, now)
+            .await
+            .unwrap();
+    }
+    // A deposit that was last seen long ago, say because it was reclaimed,
+    // falls out of the backlog.
+    let old_txid: model::BitcoinTxId = fake::Faker.fake_with_rng(&mut rng);
+    let long_ago = now - time::Duration::days(30);
+    db.write_deposit_request_stage(&old_txid, 0, RequestStage::FirstSeen, long_ago)
+        .await
+        .unwrap();
+
+    let stages = db.get_withdrawal_request_stages(2).await.unwrap();
+    assert_eq!(stages.len(), 2);
+    assert_eq!(stages[1].stage, RequestStage::Completed);
+
+    let since = now - time::Duration::days(7);
+    let backlog = db.get_request_stage_backlog(since).await.unwrap();
+    let expected = vec![
+        RequestStageBacklog {
+            kind: RequestKind::Deposit,
+            stage: RequestStage::Decided,
+            count: 1,
+        },
+        RequestStageBacklog {
+            kind: RequestKind::Withdrawal,
+            stage: RequestStage::SweepConfirmed,
+            count: 1,
+        },
+    ];
+    assert_eq!(backlog, expected);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_withdrawal_request_ids_swept_by_returns_swept_requests() {
+    let db = testing::storage::new_test_database().await;
+
+    let mut outputs: Vec<BitcoinWithdrawalOutput> = (0..3).map(|_| fake::Faker.fake()).collect();
+    let sweep_txid = outputs[0].bitcoin_txid;
+    outputs[1].bitcoin_txid = sweep_txid;
+
+    db.write_bitcoin_withdrawals_outputs(&outputs)
+        .await
+        .unwrap();
+
+    let mut expected = vec![outputs[0].request_id, outputs[1].request_id];
+    expected.sort();
+    expected.dedup();
+
+    let request_ids = db
+        .get_withdrawal_request_ids_swept_by(&sweep_txid)
+        .await
+        .unwrap();
+    assert_eq!(request_ids, expected);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
 /// This struct is for testing different conditions when attempting to
 /// retrieve the signers' UTXO.
 struct ReorgDescription<const N: usize> {
@@ -3161,3 +3578,165 @@ async fn signer_utxo_reorg_suite<const N: usize>(desc: ReorgDescription<N>) {
 
     signer::testing::storage::drop_db(db).await;
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn signing_audit_log_is_append_only() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(52);
+    let private_key = PrivateKey::new(&mut rng);
+    let public_key = PublicKey::from_private_key(&private_key);
+
+    for _ in 0..3 {
+        let request: network::Msg = fake::Faker.fake_with_rng(&mut rng);
+        let aggregate_key: PublicKeyXOnly = fake::Faker.fake_with_rng(&mut rng);
+        let record = SigningRecord::new(
+            SignatureKind::SignatureShare,
+            public_key,
+            &request,
+            &signer::wsts_state_machine::dkg_verification_message(&aggregate_key),
+            SigningValidation::DkgVerification { aggregate_key },
+        );
+        audit_log::append(&db, &private_key, &record).await.unwrap();
+    }
+
+    let checkpoint = audit_log::checkpoint(&db, &private_key).await.unwrap();
+    assert_eq!(checkpoint.length, 3);
+    assert_eq!(audit_log::verify(&db, &public_key, None).await.unwrap(), 3);
+
+    let last = db.get_last_signing_audit_entry().await.unwrap().unwrap();
+    assert_eq!(last.position, 2);
+    let entries = db.get_signing_audit_entries(1, 10).await.unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1], last);
+
+    // Another entry at a position that is already taken is rejected.
+    let result = db.write_signing_audit_entry(&last).await;
+    assert!(matches!(result, Err(Error::AuditLogPositionTaken(2))));
+
+    // The table rejects updates, deletes and truncation.
+    for sql in [
+        "UPDATE sbtc_signer.signing_audit_log SET record = '\\x00' WHERE position = 1",
+        "DELETE FROM sbtc_signer.signing_audit_log WHERE position = 1",
+        "TRUNCATE sbtc_signer.signing_audit_log",
+    ] {
+        let result = sqlx::query(sql).execute(db.pool()).await;
+        assert!(result.is_err(), "{sql} should have failed");
+    }
+
+    let verified = audit_log::verify(&db, &public_key, Some(&checkpoint)).await;
+    assert_eq!(verified.unwrap(), 3);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+/// Check that the completed deposits, withdrawal requests and withdrawal
+/// outcomes that are used to seed the contract call simulator are only
+/// taken from the canonical stacks blockchain.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn simulator_seed_data_comes_from_the_canonical_stacks_chain() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(28);
+
+    let bitcoin_block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+    let chain_tip = bitcoin_block.block_hash;
+
+    let txid: BitcoinTxId = fake::Faker.fake_with_rng(&mut rng);
+    let outpoint = bitcoin::OutPoint::new(txid.into(), 2);
+    let request_id = 7;
+
+    // Without any stacks blocks there is nothing to look up.
+    assert!(!db.is_deposit_completed(&chain_tip, &txid, 2).await.unwrap());
+    let request = db.get_withdrawal_request(&chain_tip, request_id).await;
+    assert!(request.unwrap().is_none());
+
+    let genesis = StacksBlock {
+        block_hash: fake::Faker.fake_with_rng(&mut rng),
+        block_height: 0,
+        parent_hash: fake::Faker.fake_with_rng(&mut rng),
+        bitcoin_anchor: chain_tip,
+    };
+    let canonical = StacksBlock {
+        block_hash: fake::Faker.fake_with_rng(&mut rng),
+        block_height: 1,
+        parent_hash: genesis.block_hash,
+        bitcoin_anchor: chain_tip,
+    };
+    // This block is anchored to a bitcoin block that we do not know
+    // about, so it is not on the canonical stacks blockchain.
+    let fork = StacksBlock {
+        block_hash: fake::Faker.fake_with_rng(&mut rng),
+        block_height: 1,
+        parent_hash: genesis.block_hash,
+        bitcoin_anchor: fake::Faker.fake_with_rng(&mut rng),
+    };
+    db.write_stacks_block_headers(vec![genesis, canonical.clone(), fork.clone()])
+        .await
+        .unwrap();
+
+    // Everything first happens on the fork.
+    let event = CompletedDepositEvent {
+        block_id: fork.block_hash,
+        outpoint,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_completed_deposit_event(&event).await.unwrap();
+
+    let request = model::WithdrawalRequest {
+        request_id,
+        block_hash: fork.block_hash,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_request(&request).await.unwrap();
+
+    let event = WithdrawalRejectEvent {
+        block_id: fork.block_hash,
+        request_id,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_reject_event(&event).await.unwrap();
+
+    assert!(!db.is_deposit_completed(&chain_tip, &txid, 2).await.unwrap());
+    let request = db.get_withdrawal_request(&chain_tip, request_id).await;
+    assert!(request.unwrap().is_none());
+    let outcome = db
+        .get_withdrawal_request_outcome(&chain_tip, request_id)
+        .await;
+    assert_eq!(outcome.unwrap(), None);
+
+    // Now the same things happen on the canonical chain, except that the
+    // withdrawal request is accepted.
+    let event = CompletedDepositEvent {
+        block_id: canonical.block_hash,
+        outpoint,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_completed_deposit_event(&event).await.unwrap();
+
+    let request = model::WithdrawalRequest {
+        request_id,
+        block_hash: canonical.block_hash,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_request(&request).await.unwrap();
+
+    let event = WithdrawalAcceptEvent {
+        block_id: canonical.block_hash,
+        request_id,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_accept_event(&event).await.unwrap();
+
+    assert!(db.is_deposit_completed(&chain_tip, &txid, 2).await.unwrap());
+    assert!(!db.is_deposit_completed(&chain_tip, &txid, 3).await.unwrap());
+    let stored = db.get_withdrawal_request(&chain_tip, request_id).await;
+    assert_eq!(stored.unwrap(), Some(request));
+    let outcome = db
+        .get_withdrawal_request_outcome(&chain_tip, request_id)
+        .await;
+    assert_eq!(outcome.unwrap(), Some(true));
+
+    signer::testing::storage::drop_db(db).await;
+}
@@ -12,6 +12,7 @@ use signer::stacks::contracts::RotateKeysErrorMsg;
 use signer::stacks::contracts::RotateKeysV1;
 use signer::stacks::wallet::SignerWallet;
 use signer::storage::model::BitcoinBlock;
+use signer::storage::model::DkgSharesStatus;
 use signer::storage::model::EncryptedDkgShares;
 use signer::storage::model::RotateKeysTransaction;
 use signer::storage::model::StacksPrincipal;
@@ -115,6 +116,7 @@ impl TestRotateKeySetup {
             aggregate_key,
             signer_set_public_keys: self.signer_keys.clone(),
             signature_share_threshold: self.signatures_required,
+            dkg_shares_status: DkgSharesStatus::Verified,
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
     }
@@ -344,6 +346,51 @@ async fn rotate_key_validation_wrong_signing_set() {
     testing::storage::drop_db(db).await;
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn rotate_key_validation_unverified_dkg_shares() {
+    // Normal: preamble
+    let mut db = testing::storage::new_test_database().await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 20,
+        num_stacks_blocks_per_bitcoin_block: 3,
+        num_deposit_requests_per_block: 0,
+        num_withdraw_requests_per_block: 0,
+        num_signers_per_request: 0,
+    };
+    let test_data = TestData::generate(&mut rng, &[], &test_model_params);
+    test_data.write_to(&mut db).await;
+
+    let ctx = TestContext::builder()
+        .with_storage(db.clone())
+        .with_mocked_clients()
+        .build();
+
+    let setup = TestRotateKeySetup::new(&db, 2, 3, &mut rng).await;
+
+    // Normal: we store setup dkg shares
+    setup.store_dkg_shares(&db).await;
+
+    // Different: the shares have not passed verification
+    db.update_dkg_shares_status(&setup.aggregate_key().into(), DkgSharesStatus::Unverified)
+        .await
+        .unwrap();
+
+    let (rotate_key_tx, req_ctx) = make_rotate_key(&setup);
+
+    let validate_future = rotate_key_tx.validate(&ctx, &req_ctx);
+    match validate_future.await.unwrap_err() {
+        Error::RotateKeysValidation(ref err) => {
+            assert_eq!(err.error, RotateKeysErrorMsg::DkgSharesNotVerified)
+        }
+        err => panic!("unexpected error during validation {err}"),
+    }
+
+    testing::storage::drop_db(db).await;
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn rotate_key_validation_wrong_aggregate_key() {
@@ -380,6 +380,7 @@ impl TestSweepSetup {
             aggregate_key,
             signer_set_public_keys: self.signer_keys.clone(),
             signature_share_threshold: self.signatures_required,
+            dkg_shares_status: model::DkgSharesStatus::Verified,
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
     }
@@ -978,6 +979,7 @@ impl TestSweepSetup2 {
             aggregate_key,
             signer_set_public_keys: self.signers.keys.clone(),
             signature_share_threshold: self.signatures_required,
+            dkg_shares_status: model::DkgSharesStatus::Verified,
         };
         db.write_encrypted_dkg_shares(&shares).await.unwrap();
     }
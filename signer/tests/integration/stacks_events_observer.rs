@@ -217,6 +217,7 @@ async fn test_new_blocks_sends_withdrawal_accept_update() {
         amount: 100,
         parameters: Box::new(WithdrawalParameters { max_fee: 10 }),
         recipient: ScriptBuf::default().to_hex_string(),
+        sender: None,
         request_id: withdrawal_accept_event.request_id,
         stacks_block_hash: withdrawal_accept_event.block_id.to_hex(),
         stacks_block_height: new_block_event.block_height,
@@ -262,6 +263,7 @@ async fn test_new_blocks_sends_withdrawal_reject_update() {
         amount: 100,
         parameters: Box::new(WithdrawalParameters { max_fee: 10 }),
         recipient: ScriptBuf::default().to_hex_string(),
+        sender: None,
         request_id: withdrawal_reject_event.request_id,
         stacks_block_hash: withdrawal_reject_event.block_id.to_hex(),
         stacks_block_height: new_block_event.block_height,
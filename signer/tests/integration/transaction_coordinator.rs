@@ -2,6 +2,7 @@ use std::collections::BTreeSet;
 use std::num::NonZeroU32;
 use std::num::NonZeroU64;
 use std::num::NonZeroUsize;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -54,6 +55,7 @@ use signer::stacks::api::TenureBlocks;
 use signer::stacks::contracts::AsContractCall;
 use signer::stacks::contracts::RotateKeysV1;
 use signer::stacks::contracts::SmartContract;
+use signer::stacks::contracts::UpdateProtocolContractV1;
 use signer::storage::model::BitcoinBlockHash;
 use signer::storage::model::BitcoinTx;
 use signer::storage::postgres::PgStore;
@@ -82,6 +84,7 @@ use signer::stacks::api::AccountInfo;
 use signer::stacks::api::MockStacksInteract;
 use signer::stacks::api::SubmitTxResponse;
 use signer::stacks::contracts::CompleteDepositV1;
+use signer::stacks::contracts::ContractVersion;
 use signer::stacks::contracts::SMART_CONTRACTS;
 use signer::storage::model;
 use signer::storage::model::EncryptedDkgShares;
@@ -768,6 +771,213 @@ async fn deploy_smart_contracts_coordinator<F>(
     testing::storage::drop_db(db).await;
 }
 
+/// Return the contract source for a contract while `deployed` is set, and
+/// a 404 response otherwise.
+fn mock_contract_source(client: &mut MockStacksInteract, deployed: Arc<AtomicBool>) {
+    client.expect_get_contract_source().returning(move |_, _| {
+        let deployed = deployed.load(Ordering::SeqCst);
+        Box::pin(async move {
+            if deployed {
+                Ok(ContractSrcResponse {
+                    source: String::new(),
+                    publish_height: 1,
+                    marf_proof: None,
+                })
+            } else {
+                Err(Error::StacksNodeResponse(
+                    mock_reqwests_status_code_error(404).await,
+                ))
+            }
+        })
+    });
+}
+
+/// A protocol contract version that the signers have been configured to
+/// run, but that has not been deployed, is first deployed through the
+/// signers' multi-sig wallet. Once the deployment has been confirmed, the
+/// coordinator activates it with an update-protocol-contract-wrapper call
+/// in a later tenure.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn protocol_contract_upgrades_are_deployed_then_activated() {
+    let db = testing::storage::new_test_database().await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let bitcoin_block: model::BitcoinBlock = Faker.fake_with_rng(&mut rng);
+    db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+
+    let mut stacks_block: model::StacksBlock = Faker.fake_with_rng(&mut rng);
+    stacks_block.bitcoin_anchor = bitcoin_block.block_hash;
+    db.write_stacks_block(&stacks_block).await.unwrap();
+
+    let mut context = TestContext::builder()
+        .with_storage(db.clone())
+        .with_mocked_clients()
+        .modify_settings(|settings| {
+            settings.signer.contract_versions.deposit = ContractVersion::V2;
+        })
+        .build();
+
+    let nonce = 12;
+    let num_signers = 7;
+    let signing_threshold = 5;
+    let context_window = 10;
+
+    let network = network::in_memory::InMemoryNetwork::new();
+    let signer_info: Vec<testing::wsts::SignerInfo> =
+        testing::wsts::generate_signer_info(&mut rng, num_signers);
+
+    let mut testing_signer_set =
+        testing::wsts::SignerSet::new(&signer_info, signing_threshold, || network.connect());
+
+    let (aggregate_key, bitcoin_chain_tip) =
+        run_dkg(&context, &mut rng, &mut testing_signer_set).await;
+
+    // The sBTC contracts are all deployed, and the deposit contract is
+    // deployed once the coordinator has broadcast its deployment.
+    context.state().set_sbtc_contracts_deployed();
+    let deployed = Arc::new(AtomicBool::new(false));
+
+    let signers_deployed = deployed.clone();
+    context
+        .with_stacks_client(|client| mock_contract_source(client, signers_deployed))
+        .await;
+
+    let (broadcasted_transaction_tx, _broadcasted_transaction_rx) =
+        tokio::sync::broadcast::channel(2);
+    let mut broadcasted_transaction_rx = broadcasted_transaction_tx.subscribe();
+
+    let mut tx_coordinator_context = TestContext::new(
+        context.config().clone(),
+        context.storage.clone(),
+        context.bitcoin_client.clone(),
+        WrappedMock::default(),
+        context.emily_client.clone(),
+    );
+    tx_coordinator_context.state().set_sbtc_contracts_deployed();
+
+    let coordinator_deployed = deployed.clone();
+    tx_coordinator_context
+        .with_stacks_client(|client| {
+            mock_contract_source(client, coordinator_deployed.clone());
+            client
+                .expect_estimate_fees()
+                .returning(|_, _, _| Box::pin(async { Ok(100) }));
+            client.expect_get_account().returning(move |_| {
+                Box::pin(async move {
+                    Ok(AccountInfo {
+                        balance: 1_000_000,
+                        locked: 0,
+                        unlock_height: 0,
+                        nonce,
+                    })
+                })
+            });
+            client
+                .expect_get_current_signers_aggregate_key()
+                .returning(move |_| Box::pin(std::future::ready(Ok(Some(aggregate_key)))));
+            client.expect_submit_tx().returning(move |tx| {
+                let tx = tx.clone();
+                let txid = tx.txid();
+                if matches!(tx.payload, TransactionPayload::SmartContract(..)) {
+                    coordinator_deployed.store(true, Ordering::SeqCst);
+                }
+                let broadcasted_transaction_tx = broadcasted_transaction_tx.clone();
+                Box::pin(async move {
+                    broadcasted_transaction_tx
+                        .send(tx)
+                        .expect("Failed to send result");
+                    Ok(SubmitTxResponse::Acceptance(txid))
+                })
+            });
+        })
+        .await;
+
+    let private_key = select_coordinator(&bitcoin_chain_tip.block_hash, &signer_info);
+
+    let tx_coordinator = transaction_coordinator::TxCoordinatorEventLoop {
+        context: tx_coordinator_context.clone(),
+        network: network.connect(),
+        private_key,
+        context_window,
+        threshold: signing_threshold as u16,
+        signing_round_max_duration: Duration::from_secs(10),
+        bitcoin_presign_request_max_duration: Duration::from_secs(10),
+        dkg_max_duration: Duration::from_secs(10),
+        is_epoch3: true,
+    };
+    let tx_coordinator_handle = tokio::spawn(async move { tx_coordinator.run().await });
+
+    let _event_loop_handles: Vec<_> = signer_info
+        .clone()
+        .into_iter()
+        .map(|signer_info| {
+            let event_loop_harness = TxSignerEventLoopHarness::create(
+                context.clone(),
+                network.connect(),
+                context_window,
+                signer_info.signer_private_key,
+                signing_threshold,
+                rng.clone(),
+            );
+
+            event_loop_harness.start()
+        })
+        .collect();
+
+    // Yield to get signers ready
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // In the first tenure the contract is not deployed, so the
+    // coordinator deploys it.
+    tx_coordinator_context
+        .signal(RequestDeciderEvent::NewRequestsHandled.into())
+        .expect("failed to signal");
+
+    let broadcasted_tx =
+        tokio::time::timeout(Duration::from_secs(10), broadcasted_transaction_rx.recv())
+            .await
+            .unwrap()
+            .expect("failed to receive message");
+
+    broadcasted_tx.verify().unwrap();
+    let TransactionPayload::SmartContract(contract, _) = broadcasted_tx.payload else {
+        panic!("unexpected tx payload")
+    };
+    let deposit_v2 = SmartContract::SbtcDepositV2;
+    assert_eq!(contract.name.as_str(), deposit_v2.contract_name());
+    assert_eq!(&contract.code_body.to_string(), deposit_v2.contract_body());
+
+    // In the next tenure the contract has been deployed, so the
+    // coordinator activates it.
+    tx_coordinator_context
+        .signal(RequestDeciderEvent::NewRequestsHandled.into())
+        .expect("failed to signal");
+
+    let broadcasted_tx =
+        tokio::time::timeout(Duration::from_secs(10), broadcasted_transaction_rx.recv())
+            .await
+            .unwrap()
+            .expect("failed to receive message");
+
+    broadcasted_tx.verify().unwrap();
+    let TransactionPayload::ContractCall(contract_call) = broadcasted_tx.payload else {
+        panic!("unexpected tx payload")
+    };
+    assert_eq!(
+        contract_call.contract_name.to_string(),
+        UpdateProtocolContractV1::CONTRACT_NAME
+    );
+    assert_eq!(
+        contract_call.function_name.to_string(),
+        UpdateProtocolContractV1::FUNCTION_NAME
+    );
+
+    tx_coordinator_handle.abort();
+
+    testing::storage::drop_db(db).await;
+}
+
 /// The [`TxCoordinatorEventLoop::get_signer_set_and_aggregate_key`]
 /// function is supposed to fetch the "current" signing set and the
 /// aggregate key to use for bitcoin transactions. It attempts to get the
@@ -1023,6 +1233,7 @@ async fn run_dkg_from_scratch() {
             context: context.clone(),
             context_window: 10000,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             signer_private_key: kp.secret_key().into(),
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
@@ -1255,6 +1466,7 @@ async fn run_subsequent_dkg() {
             context: context.clone(),
             context_window: 10000,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             signer_private_key: kp.secret_key().into(),
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
@@ -1532,6 +1744,12 @@ async fn sign_bitcoin_transaction() {
             client
                 .expect_get_sbtc_total_supply()
                 .returning(move |_| Box::pin(async move { Ok(Amount::ZERO) }));
+
+            // The block observer looks up the active protocol contracts
+            // in the sbtc-registry once the contracts are deployed.
+            client
+                .expect_get_active_protocol_contract()
+                .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
         })
         .await;
     }
@@ -1570,6 +1788,7 @@ async fn sign_bitcoin_transaction() {
             context: ctx.clone(),
             context_window: 10000,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             signer_private_key: kp.secret_key().into(),
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
@@ -1974,6 +2193,12 @@ async fn sign_bitcoin_transaction_multiple_locking_keys() {
             client
                 .expect_get_sbtc_total_supply()
                 .returning(move |_| Box::pin(async move { Ok(Amount::ZERO) }));
+
+            // The block observer looks up the active protocol contracts
+            // in the sbtc-registry once the contracts are deployed.
+            client
+                .expect_get_active_protocol_contract()
+                .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
         })
         .await;
     }
@@ -2012,6 +2237,7 @@ async fn sign_bitcoin_transaction_multiple_locking_keys() {
             context: ctx.clone(),
             context_window: 10000,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             signer_private_key: kp.secret_key().into(),
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
@@ -2562,6 +2788,10 @@ async fn skip_smart_contract_deployment_and_key_rotation_if_up_to_date() {
                     })
                 });
 
+            client
+                .expect_get_active_protocol_contract()
+                .returning(|_, _| Box::pin(std::future::ready(Ok(None))));
+
             // No transactions should be submitted.
             client.expect_submit_tx().never();
         })
@@ -2602,6 +2832,7 @@ async fn skip_smart_contract_deployment_and_key_rotation_if_up_to_date() {
             context: ctx.clone(),
             context_window: 10000,
             wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+            dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
             signer_private_key: kp.secret_key().into(),
             rng: rand::rngs::OsRng,
             dkg_begin_pause: None,
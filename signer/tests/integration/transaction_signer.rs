@@ -70,6 +70,7 @@ async fn get_signer_public_keys_and_aggregate_key_falls_back() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+        dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
         signer_private_key: ctx.config().signer.private_key,
         threshold: 2,
         rng: rand::rngs::StdRng::seed_from_u64(51),
@@ -180,6 +181,7 @@ async fn signing_set_validation_check_for_stacks_transactions() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+        dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
         signer_private_key: setup.aggregated_signer.keypair.secret_key().into(),
         threshold: 2,
         rng: rand::rngs::StdRng::seed_from_u64(51),
@@ -266,6 +268,7 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+        dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
         signer_private_key: setup.aggregated_signer.keypair.secret_key().into(),
         threshold: 2,
         rng: rand::rngs::StdRng::seed_from_u64(51),
@@ -380,6 +383,7 @@ async fn new_state_machine_per_valid_sighash() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+        dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
         // We use this private key because it needs to be associated with
         // one of the public keys that we stored in the DKG shares table.
         signer_private_key: setup.signers.signer.keypair.secret_key().into(),
@@ -436,6 +440,7 @@ async fn new_state_machine_per_valid_sighash() {
             &report.chain_tip,
             msg_public_key,
             &report,
+            &Faker.fake_with_rng(&mut rng),
         )
         .await
         .unwrap();
@@ -464,6 +469,7 @@ async fn new_state_machine_per_valid_sighash() {
             &report.chain_tip,
             msg_public_key,
             &report,
+            &Faker.fake_with_rng(&mut rng),
         )
         .await;
 
@@ -503,6 +509,7 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
         context: ctx.clone(),
         context_window: 10000,
         wsts_state_machines: LruCache::new(NonZeroUsize::new(100).unwrap()),
+        dkg_verification_state_machines: LruCache::new(NonZeroUsize::new(5).unwrap()),
         signer_private_key: ctx.config().signer.private_key,
         threshold: 2,
         rng: rand::rngs::StdRng::seed_from_u64(51),
@@ -526,12 +533,19 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
         inner: wsts::net::Message::DkgBegin(DkgBegin { dkg_id }),
     };
     let msg_public_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
+    let signed_msg: signer::network::Msg = Faker.fake_with_rng(&mut rng);
 
     // Sanity check, the state machines cache should be empty.
     assert!(tx_signer.wsts_state_machines.is_empty());
 
     tx_signer
-        .handle_wsts_message(&dkg_begin_msg, &chain_tip, msg_public_key, &report)
+        .handle_wsts_message(
+            &dkg_begin_msg,
+            &chain_tip,
+            msg_public_key,
+            &report,
+            &signed_msg,
+        )
         .await
         .unwrap();
 
@@ -552,7 +566,13 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
     };
 
     tx_signer
-        .handle_wsts_message(&dkg_begin_msg, &chain_tip, msg_public_key, &report)
+        .handle_wsts_message(
+            &dkg_begin_msg,
+            &chain_tip,
+            msg_public_key,
+            &report,
+            &signed_msg,
+        )
         .await
         .unwrap();
 
@@ -567,7 +587,13 @@ async fn max_one_state_machine_per_bitcoin_block_hash_for_dkg() {
     db.write_bitcoin_block(&random_block).await.unwrap();
 
     tx_signer
-        .handle_wsts_message(&dkg_begin_msg, &chain_tip, msg_public_key, &report)
+        .handle_wsts_message(
+            &dkg_begin_msg,
+            &chain_tip,
+            msg_public_key,
+            &report,
+            &signed_msg,
+        )
         .await
         .unwrap();
 